@@ -0,0 +1,194 @@
+/* C API for Ply's layout engine, built with the "ffi" feature.
+ * See src/ffi.rs for how a frame is declared. */
+
+#ifndef PLY_H
+#define PLY_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct PlyContext PlyContext;
+
+/* UTF-8 text that isn't null-terminated. */
+typedef struct {
+    size_t length;
+    const char *chars;
+} PlyString;
+
+#define PLY_STRING(literal) ((PlyString){ sizeof(literal) - 1, (literal) })
+
+typedef struct { float x, y; } PlyVector2;
+typedef struct { float width, height; } PlyDimensions;
+typedef struct { float x, y, width, height; } PlyBoundingBox;
+/* 0-255 per channel. */
+typedef struct { float r, g, b, a; } PlyColor;
+
+typedef uint8_t PlySizingType;
+enum { PLY_SIZING_FIT, PLY_SIZING_GROW, PLY_SIZING_PERCENT, PLY_SIZING_FIXED, PLY_SIZING_VIEWPORT_WIDTH, PLY_SIZING_VIEWPORT_HEIGHT, PLY_SIZING_MIN_CONTENT, PLY_SIZING_MAX_CONTENT };
+
+typedef uint8_t PlyAlignX;
+enum { PLY_ALIGN_X_LEFT, PLY_ALIGN_X_CENTER, PLY_ALIGN_X_RIGHT };
+
+typedef uint8_t PlyAlignY;
+enum { PLY_ALIGN_Y_TOP, PLY_ALIGN_Y_CENTER, PLY_ALIGN_Y_BOTTOM, PLY_ALIGN_Y_BASELINE };
+
+typedef uint8_t PlyLayoutDirection;
+enum { PLY_LEFT_TO_RIGHT, PLY_TOP_TO_BOTTOM };
+
+typedef uint8_t PlyPointerCaptureMode;
+enum { PLY_POINTER_CAPTURE, PLY_POINTER_PASSTHROUGH };
+
+typedef uint8_t PlyFloatingAttachTo;
+enum { PLY_ATTACH_TO_NONE, PLY_ATTACH_TO_PARENT, PLY_ATTACH_TO_ELEMENT_WITH_ID, PLY_ATTACH_TO_ROOT, PLY_ATTACH_TO_POINTER };
+
+typedef uint8_t PlyFloatingClipTo;
+enum { PLY_CLIP_TO_NONE, PLY_CLIP_TO_ATTACHED_PARENT };
+
+typedef uint8_t PlyWrapMode;
+enum { PLY_WRAP_WORDS, PLY_WRAP_NEWLINES, PLY_WRAP_NONE, PLY_WRAP_ANYWHERE };
+
+typedef uint8_t PlyRenderCommandType;
+enum {
+    PLY_RENDER_COMMAND_NONE,
+    PLY_RENDER_COMMAND_RECTANGLE,
+    PLY_RENDER_COMMAND_BORDER,
+    PLY_RENDER_COMMAND_TEXT,
+    PLY_RENDER_COMMAND_IMAGE,
+    PLY_RENDER_COMMAND_SCISSOR_START,
+    PLY_RENDER_COMMAND_SCISSOR_END,
+    PLY_RENDER_COMMAND_CUSTOM,
+    PLY_RENDER_COMMAND_CANVAS,
+    PLY_RENDER_COMMAND_DRAW,
+    PLY_RENDER_COMMAND_GROUP_BEGIN,
+    PLY_RENDER_COMMAND_GROUP_END,
+};
+
+/* For PLY_SIZING_FIXED, min is the size; for PLY_SIZING_PERCENT, percent is
+ * the fraction of the parent from 0 to 1. A max of zero or less is unbounded. */
+typedef struct {
+    PlySizingType type;
+    float min;
+    float max;
+    float percent;
+} PlySizingAxis;
+
+typedef struct { uint16_t left, right, top, bottom; } PlyPadding;
+
+typedef struct {
+    PlySizingAxis width;
+    PlySizingAxis height;
+    PlyPadding padding;
+    uint16_t child_gap;
+    PlyAlignX child_align_x;
+    PlyAlignY child_align_y;
+    PlyLayoutDirection direction;
+} PlyLayoutConfig;
+
+typedef struct { float top_left, top_right, bottom_left, bottom_right; } PlyCornerRadius;
+
+/* Drawn outside the bounding box. */
+typedef struct {
+    PlyColor color;
+    uint16_t left, right, top, bottom, between_children;
+} PlyBorder;
+
+typedef struct {
+    bool horizontal;
+    bool vertical;
+    PlyVector2 child_offset;
+} PlyClip;
+
+typedef struct {
+    PlyVector2 offset;
+    uint32_t parent_id;
+    int16_t z_index;
+    PlyAlignX element_x;
+    PlyAlignY element_y;
+    PlyAlignX parent_x;
+    PlyAlignY parent_y;
+    PlyPointerCaptureMode pointer_capture_mode;
+    PlyFloatingAttachTo attach_to;
+    PlyFloatingClipTo clip_to;
+} PlyFloating;
+
+/* Zero-initialize and set what you need. */
+typedef struct {
+    PlyLayoutConfig layout;
+    PlyColor background_color;
+    PlyCornerRadius corner_radius;
+    float aspect_ratio;
+    PlyFloating floating;
+    PlyClip clip;
+    PlyBorder border;
+    uintptr_t user_data;
+} PlyElementDeclaration;
+
+typedef struct {
+    /* Handed to the measure function and back on render commands, e.g. a font ID. */
+    uintptr_t user_data;
+    PlyColor color;
+    uint16_t font_size;
+    uint16_t letter_spacing;
+    uint16_t line_height;
+    PlyWrapMode wrap_mode;
+    PlyAlignX alignment;
+    /* Stretches wrapped lines but the last to the full width. */
+    bool justify;
+} PlyTextConfig;
+
+/* color is the background, border or text color; text and the font fields
+ * are for text commands, border_width for borders. */
+typedef struct {
+    PlyBoundingBox bounding_box;
+    PlyRenderCommandType command_type;
+    int16_t z_index;
+    uint32_t id;
+    uintptr_t user_data;
+    PlyColor color;
+    PlyCornerRadius corner_radius;
+    PlyPadding border_width;
+    PlyString text;
+    uint16_t font_size;
+    uint16_t letter_spacing;
+    uint16_t line_height;
+} PlyRenderCommand;
+
+/* Valid until the next ply_begin_layout. */
+typedef struct {
+    size_t length;
+    const PlyRenderCommand *commands;
+} PlyRenderCommandArray;
+
+typedef PlyDimensions (*PlyMeasureTextFunction)(PlyString text, const PlyTextConfig *config, void *user_data);
+
+PlyContext *ply_create(PlyDimensions dimensions);
+void ply_destroy(PlyContext *ply);
+void ply_set_layout_dimensions(PlyContext *ply, PlyDimensions dimensions);
+void ply_set_measure_text_function(PlyContext *ply, PlyMeasureTextFunction measure, void *user_data);
+
+void ply_set_pointer_state(PlyContext *ply, PlyVector2 position, bool is_down);
+void ply_update_scroll_containers(PlyContext *ply, bool enable_drag_scrolling, PlyVector2 scroll_delta, float delta_time);
+
+void ply_begin_layout(PlyContext *ply);
+void ply_open_element(PlyContext *ply);
+void ply_open_element_with_id(PlyContext *ply, PlyString label);
+void ply_configure_open_element(PlyContext *ply, const PlyElementDeclaration *declaration);
+void ply_close_element(PlyContext *ply);
+void ply_open_text_element(PlyContext *ply, PlyString text, const PlyTextConfig *config);
+PlyRenderCommandArray ply_end_layout(PlyContext *ply);
+
+uint32_t ply_get_element_id(PlyString label);
+bool ply_pointer_over(const PlyContext *ply, uint32_t id);
+bool ply_get_element_bounding_box(const PlyContext *ply, uint32_t id, PlyBoundingBox *out);
+PlyVector2 ply_get_scroll_offset(const PlyContext *ply);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PLY_H */
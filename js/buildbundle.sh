@@ -18,8 +18,12 @@ echo "" >> .build/bundle.js
 cat .build/sapp_jsutils.js >> .build/bundle.js
 wrap_js net.js
 wrap_js storage.js
+wrap_js dialogs.js
 wrap_js ply_fixes.js
 wrap_js ply_accessibility.js
+wrap_js ply_file_drop.js
+wrap_js ply_window.js
+wrap_js ply_system.js
 
 npx minify@9.2.0 .build/bundle.js > ply_bundle.js
 
@@ -0,0 +1,93 @@
+// Ply dialogs JS bridge
+// Opens files through a hidden <input type=file> and saves by downloading.
+
+var ply_dialog_op_uid = 1;
+var ply_dialog_pending = {};
+
+function ply_dialog_finish(op_id, result) {
+    ply_dialog_pending[op_id] = result;
+}
+
+function ply_dialog_open_file(accept_obj) {
+    var op_id = ply_dialog_op_uid;
+    ply_dialog_op_uid += 1;
+
+    var input = document.createElement('input');
+    input.type = 'file';
+    input.accept = consume_js_object(accept_obj);
+    input.style.display = 'none';
+    document.body.appendChild(input);
+
+    var done = false;
+    function finish(result) {
+        if (done) return;
+        done = true;
+        input.remove();
+        ply_dialog_finish(op_id, result);
+    }
+
+    input.addEventListener('change', function () {
+        var file = input.files && input.files[0];
+        if (!file) {
+            finish({ status: 2 });
+            return;
+        }
+        file.arrayBuffer().then(function (buffer) {
+            finish({ status: 1, name: file.name, data: new Uint8Array(buffer) });
+        }, function (error) {
+            finish({ status: 0, error: String(error && error.message || error) });
+        });
+    });
+    input.addEventListener('cancel', function () {
+        finish({ status: 2 });
+    });
+    input.click();
+
+    return op_id;
+}
+
+function ply_dialog_save_file(file_name_obj, data_obj) {
+    var op_id = ply_dialog_op_uid;
+    ply_dialog_op_uid += 1;
+
+    var file_name = consume_js_object(file_name_obj);
+    var data = consume_js_object(data_obj);
+    try {
+        var download_url = URL.createObjectURL(new Blob([data]));
+        var anchor = document.createElement('a');
+        anchor.href = download_url;
+        anchor.download = file_name;
+        anchor.style.display = 'none';
+        document.body.appendChild(anchor);
+        anchor.click();
+        anchor.remove();
+        setTimeout(function () {
+            URL.revokeObjectURL(download_url);
+        }, 0);
+        ply_dialog_finish(op_id, { status: 1, name: file_name });
+    } catch (error) {
+        ply_dialog_finish(op_id, { status: 0, error: String(error && error.message || error) });
+    }
+
+    return op_id;
+}
+
+function ply_dialog_try_recv(op_id) {
+    if (Object.prototype.hasOwnProperty.call(ply_dialog_pending, op_id)) {
+        var result = ply_dialog_pending[op_id];
+        delete ply_dialog_pending[op_id];
+        return js_object(result);
+    }
+    return -1;
+}
+
+miniquad_add_plugin({
+    register_plugin: function (importObject) {
+        importObject.env.ply_dialog_open_file = ply_dialog_open_file;
+        importObject.env.ply_dialog_save_file = ply_dialog_save_file;
+        importObject.env.ply_dialog_try_recv = ply_dialog_try_recv;
+    },
+    on_init: function () {},
+    version: 1,
+    name: "ply_dialogs"
+});
@@ -121,6 +121,29 @@ miniquad_add_plugin({
                 el.setAttribute("aria-checked", checked ? "true" : "false");
         };
 
+        // Set position among related items (aria-posinset / aria-setsize)
+        imp.env.ply_a11y_set_position_in_set = function (id, position, size) {
+            var el = nodes[id];
+            if (el) {
+                el.setAttribute("aria-posinset", position);
+                el.setAttribute("aria-setsize", size);
+            }
+        };
+
+        // Set whether a controlled popup is open (combo boxes)
+        imp.env.ply_a11y_set_expanded = function (id, expanded) {
+            var el = nodes[id];
+            if (el)
+                el.setAttribute("aria-expanded", expanded ? "true" : "false");
+        };
+
+        // Set selected state (list box options)
+        imp.env.ply_a11y_set_selected = function (id, selected) {
+            var el = nodes[id];
+            if (el)
+                el.setAttribute("aria-selected", selected ? "true" : "false");
+        };
+
         // Set value + optional min/max (for sliders, progress bars)
         imp.env.ply_a11y_set_value = function (
             id,
@@ -180,6 +203,28 @@ miniquad_add_plugin({
             if (el) el.textContent = UTF8ToString(text_ptr, text_len);
         };
 
+        // Speak a message through a shared live region (1 = polite, 2 = assertive).
+        // The regions live outside the a11y root so clearing the tree keeps them.
+        var live_regions = {};
+        imp.env.ply_a11y_announce_live = function (text_ptr, text_len, mode) {
+            var politeness = mode === 2 ? "assertive" : "polite";
+            var region = live_regions[politeness];
+            if (!region) {
+                region = document.createElement("div");
+                applyA11yNodeStyle(region);
+                region.setAttribute("aria-live", politeness);
+                region.setAttribute("aria-atomic", "true");
+                document.body.appendChild(region);
+                live_regions[politeness] = region;
+            }
+            var text = UTF8ToString(text_ptr, text_len);
+            // Clear first so repeating the same message is announced again
+            region.textContent = "";
+            setTimeout(function () {
+                region.textContent = text;
+            }, 50);
+        };
+
         // Set aria-description
         imp.env.ply_a11y_set_description = function (
             id,
@@ -194,6 +239,19 @@ miniquad_add_plugin({
             }
         };
 
+        imp.env.ply_a11y_set_key_shortcuts = function (
+            id,
+            keys_ptr,
+            keys_len,
+        ) {
+            var el = nodes[id];
+            if (el) {
+                var keys = UTF8ToString(keys_ptr, keys_len);
+                if (keys) el.setAttribute("aria-keyshortcuts", keys);
+                else el.removeAttribute("aria-keyshortcuts");
+            }
+        };
+
         // Reorder DOM children to match layout order.
         // ids_ptr points to a u32 array of element IDs in desired order.
         // We use Int32Array because WASM passes u32 as i32 to JS, and
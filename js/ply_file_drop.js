@@ -0,0 +1,63 @@
+// Ply file drop JS bridge
+// Tracks files dragged over the canvas so elements can show drop feedback
+// before the drop. The drop itself is delivered by miniquad.
+
+miniquad_add_plugin({
+    register_plugin: function (imp) {
+        var drag_active = false;
+        var drag_depth = 0;
+        var drag_x = 0;
+        var drag_y = 0;
+
+        function hasFiles(e) {
+            var types = e.dataTransfer && e.dataTransfer.types;
+            if (!types) return false;
+            for (var i = 0; i < types.length; i++) {
+                if (types[i] === "Files") return true;
+            }
+            return false;
+        }
+
+        // Convert to canvas pixels, matching miniquad's mouse coordinates.
+        function updatePosition(e) {
+            var rect = canvas.getBoundingClientRect();
+            var scale_x = rect.width > 0 ? canvas.width / rect.width : 1;
+            var scale_y = rect.height > 0 ? canvas.height / rect.height : 1;
+            drag_x = (e.clientX - rect.left) * scale_x;
+            drag_y = (e.clientY - rect.top) * scale_y;
+        }
+
+        canvas.addEventListener("dragenter", function (e) {
+            if (!hasFiles(e)) return;
+            drag_depth += 1;
+            drag_active = true;
+            updatePosition(e);
+        });
+        canvas.addEventListener("dragover", function (e) {
+            if (!hasFiles(e)) return;
+            drag_active = true;
+            updatePosition(e);
+        });
+        canvas.addEventListener("dragleave", function () {
+            drag_depth = Math.max(0, drag_depth - 1);
+            if (drag_depth === 0) drag_active = false;
+        });
+        canvas.addEventListener("drop", function () {
+            drag_depth = 0;
+            drag_active = false;
+        });
+
+        imp.env.ply_file_drag_active = function () {
+            return drag_active;
+        };
+        imp.env.ply_file_drag_x = function () {
+            return drag_x;
+        };
+        imp.env.ply_file_drag_y = function () {
+            return drag_y;
+        };
+    },
+    on_init: function () {},
+    version: 1,
+    name: "ply_file_drop",
+});
@@ -0,0 +1,25 @@
+// Ply system appearance JS bridge
+// Reads dark mode and reduced motion from media queries, and the text size
+// from the root font size relative to the 16px browser default.
+
+function ply_media_matches(query) {
+    return window.matchMedia && window.matchMedia(query).matches ? 1 : 0;
+}
+
+miniquad_add_plugin({
+    register_plugin: function (imp) {
+        imp.env.ply_system_prefers_dark = function () {
+            return ply_media_matches("(prefers-color-scheme: dark)");
+        };
+        imp.env.ply_system_prefers_reduced_motion = function () {
+            return ply_media_matches("(prefers-reduced-motion: reduce)");
+        };
+        imp.env.ply_system_text_scale = function () {
+            var size = parseFloat(getComputedStyle(document.documentElement).fontSize);
+            return size > 0 ? size / 16 : 1;
+        };
+    },
+    on_init: function () {},
+    version: 1,
+    name: "ply_system",
+});
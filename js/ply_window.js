@@ -0,0 +1,14 @@
+// Ply window JS bridge
+// Lets the app set the page title as its window title.
+
+miniquad_add_plugin({
+    register_plugin: function (imp) {
+        imp.env.ply_set_window_title = function (ptr, len) {
+            var bytes = new Uint8Array(wasm_memory.buffer, ptr, len);
+            document.title = new TextDecoder("utf-8").decode(bytes);
+        };
+    },
+    on_init: function () {},
+    version: 1,
+    name: "ply_window",
+});
@@ -1,8 +1,11 @@
 use crate::color::Color;
 use crate::id::Id;
+use crate::math::BoundingBox;
+use macroquad::input::KeyCode;
 
 /// Defines the semantic role of a UI element for screen readers and assistive technologies.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AccessibilityRole {
     #[default]
     None,
@@ -19,12 +22,17 @@ pub enum AccessibilityRole {
     TextInput,
     TextArea,
     Checkbox,
+    Switch,
     RadioButton,
+    RadioGroup,
+    ComboBox,
     Slider,
     // Containers
     Group,
     List,
     ListItem,
+    ListBox,
+    ListBoxOption,
     Menu,
     MenuItem,
     MenuBar,
@@ -40,6 +48,7 @@ pub enum AccessibilityRole {
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LiveRegionMode {
     /// No live announcements.
     #[default]
@@ -51,6 +60,7 @@ pub enum LiveRegionMode {
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccessibilityConfig {
     pub focusable: bool,
     pub role: AccessibilityRole,
@@ -60,6 +70,12 @@ pub struct AccessibilityConfig {
     pub value_min: Option<f32>,
     pub value_max: Option<f32>,
     pub checked: Option<bool>,
+    /// 1-based position among related items and their count, e.g. radios in a group.
+    pub position_in_set: Option<(u32, u32)>,
+    /// Whether a popup controlled by this element is open, e.g. a combo box list.
+    pub expanded: Option<bool>,
+    /// Whether this option is the selected one in a list box.
+    pub selected: Option<bool>,
     pub tab_index: Option<i32>,
     pub focus_right: Option<u32>,
     pub focus_left: Option<u32>,
@@ -69,6 +85,14 @@ pub struct AccessibilityConfig {
     pub ring_color: Option<Color>,
     pub ring_width: Option<u16>,
     pub live_region: LiveRegionMode,
+    /// Hidden from screen readers, for purely visual images and ornaments.
+    pub decorative: bool,
+    /// Keys that activate the element while focused, `None` for Enter and Space.
+    /// Not serialized, macroquad's key codes don't support serde.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub activation_keys: Option<Vec<KeyCode>>,
+    /// How to activate the element from the keyboard, for screen readers, e.g. `"Enter"` or `"Control+S"`.
+    pub key_hint: String,
 }
 
 impl AccessibilityConfig {
@@ -80,6 +104,207 @@ impl AccessibilityConfig {
     }
 }
 
+/// Colors that replace the UI's own in high-contrast mode, see
+/// [`Ply::set_high_contrast`](crate::Ply::set_high_contrast).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HighContrastPalette {
+    /// Replaces every opaque or translucent background.
+    pub background: Color,
+    /// Replaces every text color.
+    pub text: Color,
+    /// Replaces every border color.
+    pub border: Color,
+    /// Color of the focus ring, which is always shown in high-contrast mode.
+    pub focus_ring: Color,
+}
+
+impl Default for HighContrastPalette {
+    /// White on black with a yellow focus ring.
+    fn default() -> Self {
+        Self {
+            background: Color::rgb(0.0, 0.0, 0.0),
+            text: Color::rgb(255.0, 255.0, 255.0),
+            border: Color::rgb(255.0, 255.0, 255.0),
+            focus_ring: Color::rgb(255.0, 255.0, 0.0),
+        }
+    }
+}
+
+impl HighContrastPalette {
+    /// Black on white with a blue focus ring.
+    pub fn light() -> Self {
+        Self {
+            background: Color::rgb(255.0, 255.0, 255.0),
+            text: Color::rgb(0.0, 0.0, 0.0),
+            border: Color::rgb(0.0, 0.0, 0.0),
+            focus_ring: Color::rgb(0.0, 0.0, 255.0),
+        }
+    }
+}
+
+/// Which arrow keys move focus between the members of a focus group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FocusGroupAxis {
+    /// Left/Up move to the previous member, Right/Down to the next.
+    #[default]
+    Both,
+    /// Only Left/Right, like a toolbar.
+    Horizontal,
+    /// Only Up/Down, like a menu.
+    Vertical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FocusGroupConfig {
+    /// Whether the group is a single Tab stop, see [`FocusGroupBuilder::roving`].
+    pub roving: bool,
+    pub axis: FocusGroupAxis,
+    /// Members per row when laid out as a grid, `0` when not a grid.
+    pub columns: u32,
+    /// Whether moving past the last member goes to the first and vice versa.
+    pub wrap: bool,
+}
+
+impl Default for FocusGroupConfig {
+    fn default() -> Self {
+        Self {
+            roving: false,
+            axis: FocusGroupAxis::Both,
+            columns: 0,
+            wrap: true,
+        }
+    }
+}
+
+impl FocusGroupConfig {
+    /// Returns the member to move to from `index` of `count` members, if any.
+    pub(crate) fn step(&self, index: usize, count: usize, direction: crate::engine::ArrowDirection) -> Option<usize> {
+        use crate::engine::ArrowDirection;
+        let horizontal = matches!(direction, ArrowDirection::Left | ArrowDirection::Right);
+        let delta: isize = match (self.axis, direction) {
+            (FocusGroupAxis::Horizontal, ArrowDirection::Up | ArrowDirection::Down) => return None,
+            (FocusGroupAxis::Vertical, ArrowDirection::Left | ArrowDirection::Right) => return None,
+            (_, ArrowDirection::Left) => -1,
+            (_, ArrowDirection::Right) => 1,
+            (_, ArrowDirection::Up) if self.columns > 0 => -(self.columns as isize),
+            (_, ArrowDirection::Down) if self.columns > 0 => self.columns as isize,
+            (_, ArrowDirection::Up) => -1,
+            (_, ArrowDirection::Down) => 1,
+        };
+        let target = index as isize + delta;
+        if (0..count as isize).contains(&target) {
+            Some(target as usize)
+        } else if self.wrap && (horizontal || self.columns == 0) {
+            Some(target.rem_euclid(count as isize) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// Builder for configuring a focus group using a closure.
+pub struct FocusGroupBuilder {
+    pub(crate) config: FocusGroupConfig,
+}
+
+impl FocusGroupBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            config: FocusGroupConfig::default(),
+        }
+    }
+
+    /// Makes the group a single Tab stop (roving tabindex): Tab enters at
+    /// the member focused last, or the first one, and leaves the group.
+    pub fn roving(&mut self) -> &mut Self {
+        self.config.roving = true;
+        self
+    }
+
+    /// Only Left/Right move within the group.
+    pub fn horizontal(&mut self) -> &mut Self {
+        self.config.axis = FocusGroupAxis::Horizontal;
+        self
+    }
+
+    /// Only Up/Down move within the group.
+    pub fn vertical(&mut self) -> &mut Self {
+        self.config.axis = FocusGroupAxis::Vertical;
+        self
+    }
+
+    /// Treats members as rows of `columns`: Left/Right move by one, Up/Down by a row.
+    pub fn grid(&mut self, columns: u32) -> &mut Self {
+        self.config.columns = columns;
+        self
+    }
+
+    /// Sets whether arrow keys wrap around at the ends. Default is `true`.
+    pub fn wrap(&mut self, wrap: bool) -> &mut Self {
+        self.config.wrap = wrap;
+        self
+    }
+}
+
+/// An element as assistive technology sees it, from [`Ply::accessibility_tree`](crate::Ply::accessibility_tree).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibilityNode {
+    pub id: u32,
+    pub role: AccessibilityRole,
+    pub label: String,
+    pub description: String,
+    pub value: String,
+    pub value_min: Option<f32>,
+    pub value_max: Option<f32>,
+    pub checked: Option<bool>,
+    pub position_in_set: Option<(u32, u32)>,
+    pub expanded: Option<bool>,
+    pub selected: Option<bool>,
+    pub focusable: bool,
+    pub focused: bool,
+    pub live_region: LiveRegionMode,
+    pub key_hint: String,
+    /// Where the element was laid out, as reported to the platform.
+    pub bounds: BoundingBox,
+}
+
+impl AccessibilityNode {
+    pub(crate) fn new(id: u32, config: &AccessibilityConfig, bounds: BoundingBox, focused: bool) -> Self {
+        Self {
+            id,
+            role: config.role.clone(),
+            label: config.label.clone(),
+            description: config.description.clone(),
+            value: config.value.clone(),
+            value_min: config.value_min,
+            value_max: config.value_max,
+            checked: config.checked,
+            position_in_set: config.position_in_set,
+            expanded: config.expanded,
+            selected: config.selected,
+            focusable: config.focusable,
+            focused,
+            live_region: config.live_region,
+            key_hint: config.key_hint.clone(),
+            bounds,
+        }
+    }
+
+    /// Outline color in the accessibility overlay: orange when focused,
+    /// blue when focusable and green otherwise.
+    pub(crate) fn overlay_color(&self) -> Color {
+        if self.focused {
+            Color::rgba(255.0, 150.0, 0.0, 255.0)
+        } else if self.focusable {
+            Color::rgba(60.0, 140.0, 255.0, 220.0)
+        } else {
+            Color::rgba(60.0, 200.0, 120.0, 180.0)
+        }
+    }
+}
+
 pub struct AccessibilityBuilder {
     pub(crate) config: AccessibilityConfig,
 }
@@ -150,6 +375,14 @@ impl AccessibilityBuilder {
         self
     }
 
+    /// Hides the element from screen readers, e.g. an image that is purely
+    /// visual. It can't be focused.
+    pub fn decorative(&mut self) -> &mut Self {
+        self.config.decorative = true;
+        self.config.focusable = false;
+        self
+    }
+
     /// Sets the role explicitly.
     pub fn role(&mut self, role: AccessibilityRole) -> &mut Self {
         self.config.role = role;
@@ -162,6 +395,20 @@ impl AccessibilityBuilder {
         self
     }
 
+    /// Sets the keys that activate the element while focused, instead of
+    /// Enter and Space. Pass `&[]` to make it keyboard-focusable only.
+    pub fn activation_keys(&mut self, keys: &[KeyCode]) -> &mut Self {
+        self.config.activation_keys = Some(keys.to_vec());
+        self
+    }
+
+    /// Tells screen readers which keys activate the element, in
+    /// `aria-keyshortcuts` form like `"Enter"`, `"E"` or `"Control+S"`.
+    pub fn key_hint(&mut self, keys: &str) -> &mut Self {
+        self.config.key_hint = keys.to_string();
+        self
+    }
+
     /// Sets the accessible description.
     pub fn description(&mut self, desc: &str) -> &mut Self {
         self.config.description = desc.to_string();
@@ -192,6 +439,25 @@ impl AccessibilityBuilder {
         self
     }
 
+    /// Sets the 1-based position of this item among `size` related items,
+    /// such as radio buttons in a group or options in a list.
+    pub fn position_in_set(&mut self, position: u32, size: u32) -> &mut Self {
+        self.config.position_in_set = Some((position, size));
+        self
+    }
+
+    /// Sets whether the popup this element controls is open (for combo boxes).
+    pub fn expanded(&mut self, expanded: bool) -> &mut Self {
+        self.config.expanded = Some(expanded);
+        self
+    }
+
+    /// Sets the selected state (for list box options).
+    pub fn selected(&mut self, selected: bool) -> &mut Self {
+        self.config.selected = Some(selected);
+        self
+    }
+
     /// Sets the explicit tab index. Elements without a tab_index
     /// follow insertion order.
     pub fn tab_index(&mut self, index: i32) -> &mut Self {
@@ -245,6 +511,12 @@ impl AccessibilityBuilder {
         self
     }
 
+    /// Sets how changes to this element's label and value are announced.
+    pub fn live_region(&mut self, mode: LiveRegionMode) -> &mut Self {
+        self.config.live_region = mode;
+        self
+    }
+
     /// Sets the live region to polite — screen reader announces changes on next idle.
     pub fn live_region_polite(&mut self) -> &mut Self {
         self.config.live_region = LiveRegionMode::Polite;
@@ -345,6 +617,57 @@ mod tests {
         assert_eq!(builder.config.ring_color, Some(Color::rgb(0.0, 120.0, 255.0)));
     }
 
+    #[test]
+    fn builder_live_region() {
+        let mut builder = AccessibilityBuilder::new();
+        builder.static_text("3 results").live_region(LiveRegionMode::Assertive);
+        assert_eq!(builder.config.live_region, LiveRegionMode::Assertive);
+        builder.live_region(LiveRegionMode::Off);
+        assert_eq!(builder.config.live_region, LiveRegionMode::Off);
+    }
+
+    #[test]
+    fn builder_image_alt_text_and_decorative() {
+        let mut builder = AccessibilityBuilder::new();
+        builder.image("Avatar of Kim").description("Profile photo");
+        assert_eq!(builder.config.role, AccessibilityRole::Image);
+        assert_eq!(builder.config.label, "Avatar of Kim");
+        assert_eq!(builder.config.description, "Profile photo");
+        assert!(!builder.config.decorative);
+
+        let mut builder = AccessibilityBuilder::new();
+        builder.button("Divider").decorative();
+        assert!(builder.config.decorative);
+        assert!(!builder.config.focusable);
+    }
+
+    #[test]
+    fn builder_activation_keys() {
+        let mut builder = AccessibilityBuilder::new();
+        builder.button("Interact").activation_keys(&[KeyCode::E]).key_hint("E");
+        assert_eq!(builder.config.activation_keys, Some(vec![KeyCode::E]));
+        assert_eq!(builder.config.key_hint, "E");
+    }
+
+    #[test]
+    fn focus_group_steps_along_its_axis() {
+        use crate::engine::ArrowDirection::*;
+        let mut builder = FocusGroupBuilder::new();
+        builder.roving().horizontal();
+        let toolbar = builder.config;
+        assert!(toolbar.roving);
+        assert_eq!(toolbar.step(0, 3, Right), Some(1));
+        assert_eq!(toolbar.step(0, 3, Left), Some(2));
+        assert_eq!(toolbar.step(0, 3, Down), None);
+
+        let mut builder = FocusGroupBuilder::new();
+        builder.grid(3).wrap(false);
+        let grid = builder.config;
+        assert_eq!(grid.step(1, 6, Down), Some(4));
+        assert_eq!(grid.step(4, 6, Down), None);
+        assert_eq!(grid.step(5, 6, Right), None);
+    }
+
     #[test]
     fn ring_defaults_are_none() {
         let config = AccessibilityConfig::new();
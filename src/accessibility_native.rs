@@ -31,6 +31,11 @@ const ROOT_NODE_ID: NodeId = NodeId(u64::MAX);
 /// This enables structural navigation in screen readers (e.g. Orca's Insert+Z).
 const DOCUMENT_NODE_ID: NodeId = NodeId(u64::MAX - 1);
 
+/// Sentinel NodeIds for the live regions that speak `Ply::announce` messages,
+/// one polite and one assertive. They join the document once first used.
+const POLITE_ANNOUNCEMENT_NODE_ID: NodeId = NodeId(u64::MAX - 2);
+const ASSERTIVE_ANNOUNCEMENT_NODE_ID: NodeId = NodeId(u64::MAX - 3);
+
 fn map_role(role: &AccessibilityRole) -> Role {
     match role {
         AccessibilityRole::None => Role::Unknown,
@@ -42,11 +47,16 @@ fn map_role(role: &AccessibilityRole) -> Role {
         AccessibilityRole::TextInput => Role::TextInput,
         AccessibilityRole::TextArea => Role::MultilineTextInput,
         AccessibilityRole::Checkbox => Role::CheckBox,
+        AccessibilityRole::Switch => Role::Switch,
         AccessibilityRole::RadioButton => Role::RadioButton,
+        AccessibilityRole::RadioGroup => Role::RadioGroup,
+        AccessibilityRole::ComboBox => Role::ComboBox,
         AccessibilityRole::Slider => Role::Slider,
         AccessibilityRole::Group => Role::Group,
         AccessibilityRole::List => Role::List,
         AccessibilityRole::ListItem => Role::ListItem,
+        AccessibilityRole::ListBox => Role::ListBox,
+        AccessibilityRole::ListBoxOption => Role::ListBoxOption,
         AccessibilityRole::Menu => Role::Menu,
         AccessibilityRole::MenuItem => Role::MenuItem,
         AccessibilityRole::MenuBar => Role::MenuBar,
@@ -85,6 +95,11 @@ fn build_node(config: &AccessibilityConfig, bounds: BoundingBox) -> Node {
         node.set_description(config.description.as_str());
     }
 
+    // Keys that activate it
+    if !config.key_hint.is_empty() {
+        node.set_keyboard_shortcut(config.key_hint.as_str());
+    }
+
     // Value (text value for sliders, progress bars, etc.)
     if !config.value.is_empty() {
         node.set_value(config.value.as_str());
@@ -119,6 +134,20 @@ fn build_node(config: &AccessibilityConfig, bounds: BoundingBox) -> Node {
         });
     }
 
+    // Position among related items (radio buttons in a group)
+    if let Some((position, size)) = config.position_in_set {
+        node.set_position_in_set(position as usize);
+        node.set_size_of_set(size as usize);
+    }
+
+    // Popup and selection state (combo boxes and their options)
+    if let Some(expanded) = config.expanded {
+        node.set_expanded(expanded);
+    }
+    if let Some(selected) = config.selected {
+        node.set_selected(selected);
+    }
+
     // Live region
     match config.live_region {
         LiveRegionMode::Off => {}
@@ -138,7 +167,11 @@ fn build_node(config: &AccessibilityConfig, bounds: BoundingBox) -> Node {
         AccessibilityRole::Button | AccessibilityRole::Link | AccessibilityRole::MenuItem => {
             node.add_action(Action::Click);
         }
-        AccessibilityRole::Checkbox | AccessibilityRole::RadioButton => {
+        AccessibilityRole::Checkbox
+        | AccessibilityRole::Switch
+        | AccessibilityRole::RadioButton
+        | AccessibilityRole::ComboBox
+        | AccessibilityRole::ListBoxOption => {
             node.add_action(Action::Click);
         }
         AccessibilityRole::Slider => {
@@ -158,16 +191,26 @@ fn build_tree_update(
     element_order: &[u32],
     focused_id: u32,
     viewport: Dimensions,
+    announcements: &Announcements,
     include_tree: bool,
 ) -> TreeUpdate {
     let mut nodes: Vec<(NodeId, Node)> = Vec::with_capacity(element_order.len() + 2);
 
     // Collect child NodeIds for the document container
-    let child_ids: Vec<NodeId> = element_order
+    let mut child_ids: Vec<NodeId> = element_order
         .iter()
         .filter(|id| configs.contains_key(id) && bounds_by_id.contains_key(id))
         .map(|&id| NodeId(id as u64))
         .collect();
+    let announcement_nodes = [
+        (POLITE_ANNOUNCEMENT_NODE_ID, &announcements.polite, Live::Polite),
+        (ASSERTIVE_ANNOUNCEMENT_NODE_ID, &announcements.assertive, Live::Assertive),
+    ];
+    for (node_id, text, _) in &announcement_nodes {
+        if !text.is_empty() {
+            child_ids.push(*node_id);
+        }
+    }
 
     let viewport_bounds = BoundingBox::new(0.0, 0.0, viewport.width.max(0.0), viewport.height.max(0.0));
 
@@ -192,6 +235,16 @@ fn build_tree_update(
         }
     }
 
+    // Live regions whose label changes are spoken
+    for (node_id, text, live) in announcement_nodes {
+        if !text.is_empty() {
+            let mut node = Node::new(Role::Status);
+            node.set_label(text.as_str());
+            node.set_live(live);
+            nodes.push((node_id, node));
+        }
+    }
+
     // Determine focus: if focused_id is 0 (no focus), point to root
     let focus = if focused_id != 0 && configs.contains_key(&focused_id) && bounds_by_id.contains_key(&focused_id) {
         NodeId(focused_id as u64)
@@ -443,6 +496,32 @@ pub struct NativeAccessibilityState {
     adapter: PlatformAdapter,
     action_queue: Arc<Mutex<Vec<ActionRequest>>>,
     initialized: bool,
+    announcements: Announcements,
+}
+
+/// Latest message of each live region made for `Ply::announce`.
+#[derive(Default)]
+struct Announcements {
+    polite: String,
+    assertive: String,
+}
+
+impl Announcements {
+    /// Replaces the message of the region for `mode`. Screen readers only
+    /// speak changes, so a repeated message gets a trailing no-break space
+    /// toggled to still differ.
+    fn push(&mut self, text: &str, mode: LiveRegionMode) {
+        let current = match mode {
+            LiveRegionMode::Off => return,
+            LiveRegionMode::Polite => &mut self.polite,
+            LiveRegionMode::Assertive => &mut self.assertive,
+        };
+        if *current == text {
+            *current = format!("{text}\u{a0}");
+        } else {
+            *current = text.to_string();
+        }
+    }
 }
 
 impl Default for NativeAccessibilityState {
@@ -451,6 +530,7 @@ impl Default for NativeAccessibilityState {
             adapter: PlatformAdapter::None,
             action_queue: Arc::new(Mutex::new(Vec::new())),
             initialized: false,
+            announcements: Announcements::default(),
         }
     }
 }
@@ -471,6 +551,7 @@ impl NativeAccessibilityState {
             element_order,
             focused_id,
             viewport,
+            &self.announcements,
             true,
         );
 
@@ -666,6 +747,7 @@ pub fn sync_accessibility_tree(
     accessibility_element_order: &[u32],
     focused_element_id: u32,
     viewport: Dimensions,
+    announcements: &[(String, LiveRegionMode)],
 ) -> Vec<PendingA11yAction> {
     // Lazy-initialize the platform adapter on first call
     if !state.initialized {
@@ -707,6 +789,10 @@ pub fn sync_accessibility_tree(
         }
     }
 
+    for (text, mode) in announcements {
+        state.announcements.push(text, *mode);
+    }
+
     // Build and push the tree update to the platform adapter
     let update = build_tree_update(
         accessibility_configs,
@@ -714,6 +800,7 @@ pub fn sync_accessibility_tree(
         accessibility_element_order,
         focused_element_id,
         viewport,
+        &state.announcements,
         false,
     );
 
@@ -783,11 +870,16 @@ mod tests {
             AccessibilityRole::TextInput,
             AccessibilityRole::TextArea,
             AccessibilityRole::Checkbox,
+            AccessibilityRole::Switch,
             AccessibilityRole::RadioButton,
+            AccessibilityRole::RadioGroup,
+            AccessibilityRole::ComboBox,
             AccessibilityRole::Slider,
             AccessibilityRole::Group,
             AccessibilityRole::List,
             AccessibilityRole::ListItem,
+            AccessibilityRole::ListBox,
+            AccessibilityRole::ListBoxOption,
             AccessibilityRole::Menu,
             AccessibilityRole::MenuItem,
             AccessibilityRole::MenuBar,
@@ -831,6 +923,25 @@ mod tests {
         assert_eq!(node.toggled(), Some(Toggled::True));
     }
 
+    #[test]
+    fn build_node_radio_position_in_set() {
+        let mut config = make_config(AccessibilityRole::RadioButton, "Medium");
+        config.position_in_set = Some((2, 3));
+        let node = build_node(&config, BoundingBox::new(0.0, 0.0, 10.0, 10.0));
+        assert_eq!(node.role(), Role::RadioButton);
+        assert_eq!(node.position_in_set(), Some(2));
+        assert_eq!(node.size_of_set(), Some(3));
+    }
+
+    #[test]
+    fn build_node_combo_box_expanded() {
+        let mut config = make_config(AccessibilityRole::ComboBox, "Country");
+        config.expanded = Some(true);
+        let node = build_node(&config, BoundingBox::new(0.0, 0.0, 10.0, 10.0));
+        assert_eq!(node.role(), Role::ComboBox);
+        assert_eq!(node.is_expanded(), Some(true));
+    }
+
     #[test]
     fn build_node_slider_values() {
         let mut config = make_config(AccessibilityRole::Slider, "Volume");
@@ -860,6 +971,14 @@ mod tests {
         assert_eq!(node.description(), Some("Submit the form"));
     }
 
+    #[test]
+    fn build_node_key_hint() {
+        let mut config = make_config(AccessibilityRole::Button, "Jump");
+        config.key_hint = "J".to_string();
+        let node = build_node(&config, BoundingBox::new(0.0, 0.0, 80.0, 24.0));
+        assert_eq!(node.keyboard_shortcut(), Some("J"));
+    }
+
     #[test]
     fn build_tree_update_structure() {
         let mut configs = FxHashMap::default();
@@ -870,7 +989,7 @@ mod tests {
         bounds.insert(102, BoundingBox::new(100.0, 10.0, 80.0, 32.0));
 
         let order = vec![101, 102];
-        let update = build_tree_update(&configs, &bounds, &order, 101, Dimensions::new(320.0, 240.0), true);
+        let update = build_tree_update(&configs, &bounds, &order, 101, Dimensions::new(320.0, 240.0), &Announcements::default(), true);
 
         // Should have root + document + 2 children = 4 nodes
         assert_eq!(update.nodes.len(), 4);
@@ -897,7 +1016,7 @@ mod tests {
         let configs = FxHashMap::default();
         let bounds = FxHashMap::default();
         let order = vec![];
-        let update = build_tree_update(&configs, &bounds, &order, 0, Dimensions::new(320.0, 240.0), true);
+        let update = build_tree_update(&configs, &bounds, &order, 0, Dimensions::new(320.0, 240.0), &Announcements::default(), true);
 
         // Only root + document nodes
         assert_eq!(update.nodes.len(), 2);
@@ -905,6 +1024,29 @@ mod tests {
         assert_eq!(update.focus, ROOT_NODE_ID);
     }
 
+    #[test]
+    fn build_tree_update_announcements() {
+        let configs = FxHashMap::default();
+        let bounds = FxHashMap::default();
+        let mut announcements = Announcements::default();
+        announcements.push("3 results found", LiveRegionMode::Polite);
+        let update = build_tree_update(&configs, &bounds, &[], 0, Dimensions::new(320.0, 240.0), &announcements, true);
+
+        // Only the polite region is in use
+        assert_eq!(update.nodes.len(), 3);
+        assert_eq!(update.nodes[1].1.children(), &[POLITE_ANNOUNCEMENT_NODE_ID]);
+        let (id, node) = &update.nodes[2];
+        assert_eq!(*id, POLITE_ANNOUNCEMENT_NODE_ID);
+        assert_eq!(node.label(), Some("3 results found"));
+        assert_eq!(node.live(), Some(Live::Polite));
+
+        // Repeating a message still changes the label, so it is spoken again
+        announcements.push("3 results found", LiveRegionMode::Polite);
+        assert_eq!(announcements.polite, "3 results found\u{a0}");
+        announcements.push("3 results found", LiveRegionMode::Polite);
+        assert_eq!(announcements.polite, "3 results found");
+    }
+
     #[test]
     fn default_state_is_uninitialized() {
         let state = NativeAccessibilityState::default();
@@ -26,6 +26,9 @@ extern "C" {
     );
     fn ply_a11y_set_heading_level(id: u32, level: u32);
     fn ply_a11y_set_checked(id: u32, checked: u32);
+    fn ply_a11y_set_position_in_set(id: u32, position: u32, size: u32);
+    fn ply_a11y_set_expanded(id: u32, expanded: u32);
+    fn ply_a11y_set_selected(id: u32, selected: u32);
     fn ply_a11y_set_value(
         id: u32,
         value_ptr: *const u8,
@@ -38,7 +41,9 @@ extern "C" {
     fn ply_a11y_set_focus(id: u32);
     fn ply_a11y_clear();
     fn ply_a11y_announce(id: u32, text_ptr: *const u8, text_len: u32);
+    fn ply_a11y_announce_live(text_ptr: *const u8, text_len: u32, mode: u32);
     fn ply_a11y_set_description(id: u32, desc_ptr: *const u8, desc_len: u32);
+    fn ply_a11y_set_key_shortcuts(id: u32, keys_ptr: *const u8, keys_len: u32);
     fn ply_a11y_reorder(ids_ptr: *const u32, count: u32);
     fn ply_a11y_set_bounds(id: u32, x: f32, y: f32, width: f32, height: f32);
     fn ply_a11y_set_viewport(width: f32, height: f32);
@@ -56,11 +61,16 @@ fn role_to_aria_string(role: &AccessibilityRole) -> &'static str {
         AccessibilityRole::TextInput => "textbox",
         AccessibilityRole::TextArea => "textbox",
         AccessibilityRole::Checkbox => "checkbox",
+        AccessibilityRole::Switch => "switch",
         AccessibilityRole::RadioButton => "radio",
+        AccessibilityRole::RadioGroup => "radiogroup",
+        AccessibilityRole::ComboBox => "combobox",
         AccessibilityRole::Slider => "slider",
         AccessibilityRole::Group => "group",
         AccessibilityRole::List => "list",
         AccessibilityRole::ListItem => "listitem",
+        AccessibilityRole::ListBox => "listbox",
+        AccessibilityRole::ListBoxOption => "option",
         AccessibilityRole::Menu => "menu",
         AccessibilityRole::MenuItem => "menuitem",
         AccessibilityRole::MenuBar => "menubar",
@@ -103,6 +113,7 @@ pub fn sync_accessibility_tree(
     accessibility_element_order: &[u32],
     focused_element_id: u32,
     viewport: crate::math::Dimensions,
+    announcements: &[(String, LiveRegionMode)],
 ) {
     // Initialize the hidden DOM root on first call
     if !state.initialized {
@@ -155,6 +166,19 @@ pub fn sync_accessibility_tree(
             unsafe { ply_a11y_set_checked(elem_id, if checked { 1 } else { 0 }); }
         }
 
+        // Position in set (radio buttons in a group)
+        if let Some((position, size)) = config.position_in_set {
+            unsafe { ply_a11y_set_position_in_set(elem_id, position, size); }
+        }
+
+        // Popup and selection state (combo boxes and their options)
+        if let Some(expanded) = config.expanded {
+            unsafe { ply_a11y_set_expanded(elem_id, if expanded { 1 } else { 0 }); }
+        }
+        if let Some(selected) = config.selected {
+            unsafe { ply_a11y_set_selected(elem_id, if selected { 1 } else { 0 }); }
+        }
+
         // Value (for sliders, progress bars)
         if !config.value.is_empty() {
             unsafe {
@@ -179,6 +203,17 @@ pub fn sync_accessibility_tree(
             }
         }
 
+        // Key hint
+        if !config.key_hint.is_empty() {
+            unsafe {
+                ply_a11y_set_key_shortcuts(
+                    elem_id,
+                    config.key_hint.as_ptr(),
+                    config.key_hint.len() as u32,
+                );
+            }
+        }
+
         // Live region
         let live_mode = match config.live_region {
             LiveRegionMode::Off => 0u32,
@@ -215,4 +250,14 @@ pub fn sync_accessibility_tree(
     }
 
     state.previous_ids = current_ids;
+
+    // Messages from `Ply::announce`, spoken through shared live regions
+    for (text, mode) in announcements {
+        let mode = match mode {
+            LiveRegionMode::Off => continue,
+            LiveRegionMode::Polite => 1,
+            LiveRegionMode::Assertive => 2,
+        };
+        unsafe { ply_a11y_announce_live(text.as_ptr(), text.len() as u32, mode); }
+    }
 }
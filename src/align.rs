@@ -1,5 +1,6 @@
 /// Horizontal alignment.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum AlignX {
     #[default]
@@ -10,10 +11,17 @@ pub enum AlignX {
 
 /// Vertical alignment.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum AlignY {
     #[default]
     Top,
     CenterY,
     Bottom,
+    /// Aligns the first text baselines of a `LeftToRight` row's children.
+    ///
+    /// Children without text align their bottom edge to the baseline.
+    /// Behaves like [`AlignY::Top`] in `TopToBottom` layouts. As a floating
+    /// attach point it refers to the element's first baseline.
+    Baseline,
 }
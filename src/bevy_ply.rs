@@ -0,0 +1,470 @@
+//! Running Ply inside a Bevy app: layout happens in a Bevy system and the
+//! render commands become Bevy UI nodes.
+//!
+//! ```ignore
+//! App::new()
+//!     .add_plugins((DefaultPlugins, PlyPlugin::<()>::default()))
+//!     .add_systems(Startup, |mut ply: NonSendMut<PlyUi>| {
+//!         ply.ply_mut().set_measure_text_function(measure_with_bevy_font_metrics);
+//!     })
+//!     .add_systems(Update, |mut ply: NonSendMut<PlyUi>| {
+//!         ply.frame(|ui| build_ui(ui));
+//!     })
+//!     .run();
+//! ```
+//!
+//! Mouse, wheel, touch, keyboard and IME input from the primary window is
+//! bridged in `PreUpdate`, and nodes are synced in `PostUpdate` under one
+//! full-window root node, see [`PlyUi::root`]. Rectangles, borders, text and
+//! clipping map onto UI nodes. Images, canvases, `on_draw` hooks, shaders and
+//! rotation don't and are skipped; custom elements get their background, and
+//! their commands are in [`PlyUi::commands`] for drawing the rest yourself.
+//! Copied text is handed out by [`PlyUi::take_copied_text`] and pasted with
+//! [`PlyUi::paste`].
+
+use std::marker::PhantomData;
+
+use ::bevy::app::{App, Plugin, PostUpdate, PreUpdate};
+use ::bevy::color::Color as BevyColor;
+use ::bevy::ecs::prelude::*;
+use ::bevy::input::keyboard::{KeyCode as BevyKeyCode, KeyboardInput};
+use ::bevy::input::mouse::{MouseButton, MouseScrollUnit, MouseWheel};
+use ::bevy::input::touch::{TouchInput, TouchPhase};
+use ::bevy::input::{ButtonInput, ButtonState};
+use ::bevy::text::{TextColor, TextFont, TextLayout};
+use ::bevy::time::Time;
+use ::bevy::ui::prelude::*;
+use ::bevy::ui::{BorderRadius, Overflow, PositionType, UiRect, Val, ZIndex};
+use ::bevy::window::{Ime, PrimaryWindow, Window};
+use rustc_hash::FxHashMap;
+
+use crate::color::Color;
+use crate::elements::BorderPosition;
+use crate::event_input::{map_key_code, EventInput, LINE_HEIGHT};
+use crate::math::{BoundingBox, Dimensions, Vector2};
+use crate::render_commands::{CornerRadii, RenderCommand, RenderCommandConfig};
+use crate::{Ply, Ui};
+
+/// Adds a [`PlyUi`] non-send resource and the systems feeding it input and
+/// syncing its nodes.
+pub struct PlyPlugin<CustomElementData = ()>(PhantomData<fn() -> CustomElementData>);
+
+impl<CustomElementData> Default for PlyPlugin<CustomElementData> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug + 'static> Plugin for PlyPlugin<CustomElementData> {
+    fn build(&self, app: &mut App) {
+        app.insert_non_send_resource(PlyUi::<CustomElementData>::new())
+            .add_systems(PreUpdate, bridge_input::<CustomElementData>)
+            .add_systems(PostUpdate, sync_nodes::<CustomElementData>);
+    }
+}
+
+/// Marks the UI nodes Ply spawned, with the ID of the element each draws.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PlyNode {
+    pub id: u32,
+}
+
+/// What a node draws; an element can have one of each, and a line of text each.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum NodeKind {
+    Rectangle,
+    Border,
+    Text,
+    Custom,
+    Clip,
+}
+
+struct NodeSlot {
+    entity: Entity,
+    parent: Entity,
+    seen: u64,
+}
+
+/// A headless [`Ply`] driven by Bevy's input and drawn as Bevy UI.
+pub struct PlyUi<CustomElementData: Clone + Default + std::fmt::Debug = ()> {
+    ply: Ply<CustomElementData>,
+    input: EventInput,
+    commands: Vec<RenderCommand<CustomElementData>>,
+    nodes: FxHashMap<(u32, NodeKind, u16), NodeSlot>,
+    root: Option<Entity>,
+    generation: u64,
+    /// Whether the last frame's commands still have to become nodes
+    pending: bool,
+    time: f64,
+    delta: f32,
+    dimensions: Dimensions,
+    warned: bool,
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyUi<CustomElementData> {
+    fn new() -> Self {
+        let mut ply = Ply::new_headless(Dimensions::new(0.0, 0.0));
+        // Unchanged frames leave the nodes alone
+        ply.set_change_detection(true);
+        Self {
+            ply,
+            input: EventInput::new(),
+            commands: Vec::new(),
+            nodes: FxHashMap::default(),
+            root: None,
+            generation: 0,
+            pending: false,
+            time: 0.0,
+            delta: 0.0,
+            dimensions: Dimensions::new(0.0, 0.0),
+            warned: false,
+        }
+    }
+
+    pub fn ply(&self) -> &Ply<CustomElementData> {
+        &self.ply
+    }
+
+    /// Set a measure function here before the first frame, so text is laid
+    /// out with the metrics of the font it is drawn with.
+    pub fn ply_mut(&mut self) -> &mut Ply<CustomElementData> {
+        &mut self.ply
+    }
+
+    /// Runs a frame: applies the input since the last one and declares the
+    /// UI in `build`. Its nodes are synced in `PostUpdate`.
+    pub fn frame(&mut self, build: impl FnOnce(&mut Ui<'_, CustomElementData>)) {
+        let ply = &mut self.ply;
+        ply.context.current_time = self.time;
+        ply.context.frame_delta_time = self.delta;
+        ply.set_layout_dimensions(self.dimensions);
+        self.input.apply(ply);
+
+        let mut ui = ply.begin();
+        build(&mut ui);
+        self.commands = ui.eval();
+        self.pending = self.ply.frame_changed();
+    }
+
+    /// The last frame's render commands.
+    pub fn commands(&self) -> &[RenderCommand<CustomElementData>] {
+        &self.commands
+    }
+
+    /// The node all of Ply's nodes are under, once the first frame is synced.
+    pub fn root(&self) -> Option<Entity> {
+        self.root
+    }
+
+    /// Text the IME is composing, not yet committed to the focused input.
+    pub fn ime_preedit(&self) -> &str {
+        &self.input.preedit
+    }
+
+    /// Takes the text copied or cut with Ctrl+C / Ctrl+X, to put on the
+    /// system clipboard.
+    pub fn take_copied_text(&mut self) -> Option<String> {
+        self.input.copied.take()
+    }
+
+    /// Pastes text from the system clipboard into the focused text input.
+    pub fn paste(&mut self, text: &str) {
+        EventInput::paste(&mut self.ply, text);
+    }
+}
+
+/// Gathers the primary window's input for the next [`PlyUi::frame`].
+#[allow(clippy::too_many_arguments)]
+fn bridge_input<CustomElementData: Clone + Default + std::fmt::Debug + 'static>(
+    mut ply_ui: NonSendMut<PlyUi<CustomElementData>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    time: Res<Time>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<BevyKeyCode>>,
+    mut wheel: EventReader<MouseWheel>,
+    mut touches: EventReader<TouchInput>,
+    mut keyboard: EventReader<KeyboardInput>,
+    mut ime: EventReader<Ime>,
+) {
+    let Ok(mut window) = windows.single_mut() else {
+        return;
+    };
+    let PlyUi { ply, input, time: now, delta, dimensions, .. } = &mut *ply_ui;
+    *now = time.elapsed_secs_f64();
+    *delta = time.delta_secs();
+    *dimensions = Dimensions::new(window.width(), window.height());
+    let scale_factor = window.scale_factor();
+
+    input.shift = keys.any_pressed([BevyKeyCode::ShiftLeft, BevyKeyCode::ShiftRight]);
+    input.ctrl = keys.any_pressed([BevyKeyCode::ControlLeft, BevyKeyCode::ControlRight]);
+    input.alt = keys.any_pressed([BevyKeyCode::AltLeft, BevyKeyCode::AltRight]);
+
+    for touch in touches.read() {
+        if input.touch.is_some_and(|id| id != touch.id) {
+            continue;
+        }
+        input.pointer = Vector2::new(touch.position.x, touch.position.y);
+        match touch.phase {
+            TouchPhase::Started => {
+                input.touch = Some(touch.id);
+                input.set_pointer_down(true);
+            }
+            TouchPhase::Moved => {}
+            TouchPhase::Ended | TouchPhase::Canceled => {
+                input.touch = None;
+                input.set_pointer_down(false);
+            }
+        }
+    }
+    if input.touch.is_none() {
+        input.pointer = window.cursor_position().map_or(Vector2::new(-1.0, -1.0), |p| Vector2::new(p.x, p.y));
+        if mouse.just_pressed(MouseButton::Left) {
+            input.set_pointer_down(true);
+        }
+        if mouse.just_released(MouseButton::Left) {
+            input.set_pointer_down(false);
+        }
+    }
+    for event in wheel.read() {
+        input.scroll_by(match event.unit {
+            MouseScrollUnit::Line => Vector2::new(event.x * LINE_HEIGHT, event.y * LINE_HEIGHT),
+            MouseScrollUnit::Pixel => Vector2::new(event.x / scale_factor, event.y / scale_factor),
+        });
+    }
+
+    for event in keyboard.read() {
+        let key = map_key_code!(event.key_code, BevyKeyCode);
+        if event.state == ButtonState::Pressed {
+            if let Some(key) = key {
+                input.press_key(ply, key, event.repeat);
+            }
+            if let Some(text) = &event.text {
+                input.type_key_text(ply, text);
+            }
+        } else if let Some(key) = key {
+            input.release_key(ply, key);
+        }
+    }
+    for event in ime.read() {
+        match event {
+            Ime::Enabled { .. } => input.ime_enabled = true,
+            Ime::Disabled { .. } => {
+                input.ime_enabled = false;
+                input.preedit.clear();
+            }
+            Ime::Preedit { value, .. } => input.preedit = value.clone(),
+            Ime::Commit { value, .. } => {
+                input.preedit.clear();
+                input.type_text(ply, value);
+            }
+        }
+    }
+
+    let ime_allowed = ply.context.is_text_input_focused();
+    if window.ime_enabled != ime_allowed {
+        window.ime_enabled = ime_allowed;
+    }
+}
+
+/// Spawns, updates and despawns UI nodes to match the last frame.
+fn sync_nodes<CustomElementData: Clone + Default + std::fmt::Debug + 'static>(
+    mut commands: Commands,
+    mut ply_ui: NonSendMut<PlyUi<CustomElementData>>,
+) {
+    if !ply_ui.pending {
+        return;
+    }
+    let ply_ui = &mut *ply_ui;
+    ply_ui.pending = false;
+    ply_ui.generation += 1;
+    let generation = ply_ui.generation;
+    let root = *ply_ui.root.get_or_insert_with(|| {
+        commands
+            .spawn(Node { width: Val::Percent(100.0), height: Val::Percent(100.0), ..Default::default() })
+            .id()
+    });
+
+    let mut occurrences: FxHashMap<(u32, NodeKind), u16> = FxHashMap::default();
+    // Open clip nodes, with their offset from the root
+    let mut clips: Vec<(Entity, Vector2)> = Vec::new();
+    for (index, command) in ply_ui.commands.iter().enumerate() {
+        let bb = command.bounding_box;
+        let (kind, bundle_bb) = match &command.config {
+            RenderCommandConfig::Rectangle(_) => (NodeKind::Rectangle, bb),
+            RenderCommandConfig::Border(border) => (NodeKind::Border, outer_border_box(&bb, border)),
+            RenderCommandConfig::Text(_) => (NodeKind::Text, bb),
+            RenderCommandConfig::Custom(_) => (NodeKind::Custom, bb),
+            RenderCommandConfig::ScissorStart() => (NodeKind::Clip, bb),
+            RenderCommandConfig::ScissorEnd() => {
+                clips.pop();
+                continue;
+            }
+            RenderCommandConfig::None() | RenderCommandConfig::GroupEnd => continue,
+            RenderCommandConfig::Image(_)
+            | RenderCommandConfig::Canvas(_)
+            | RenderCommandConfig::Draw(_)
+            | RenderCommandConfig::GroupBegin { .. } => {
+                if !ply_ui.warned {
+                    ply_ui.warned = true;
+                    crate::telemetry::warning!(
+                        "Bevy UI can't draw images, canvases, on_draw hooks or groups, skipping them"
+                    );
+                }
+                continue;
+            }
+        };
+        if (command.shape_rotation.is_some() || !command.effects.is_empty()) && !ply_ui.warned {
+            ply_ui.warned = true;
+            crate::telemetry::warning!("Bevy UI can't rotate elements or run shaders, drawing without them");
+        }
+
+        let occurrence = occurrences.entry((command.id, kind)).or_default();
+        let key = (command.id, kind, *occurrence);
+        *occurrence += 1;
+        let (parent, offset) = clips.last().copied().unwrap_or((root, Vector2::new(0.0, 0.0)));
+        let slot = ply_ui.nodes.entry(key).or_insert_with(|| NodeSlot {
+            entity: commands.spawn(PlyNode { id: command.id }).id(),
+            parent: Entity::PLACEHOLDER,
+            seen: 0,
+        });
+        slot.seen = generation;
+        if slot.parent != parent {
+            slot.parent = parent;
+            commands.entity(parent).add_child(slot.entity);
+        }
+
+        let mut entity = commands.entity(slot.entity);
+        let mut node = Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(bundle_bb.x - offset.x),
+            top: Val::Px(bundle_bb.y - offset.y),
+            width: Val::Px(bundle_bb.width),
+            height: Val::Px(bundle_bb.height),
+            ..Default::default()
+        };
+        entity.insert(ZIndex(index as i32));
+        match &command.config {
+            RenderCommandConfig::Rectangle(rectangle) => {
+                entity.insert((node, BackgroundColor(bevy_color(rectangle.color)), border_radius(&rectangle.corner_radii)));
+            }
+            RenderCommandConfig::Border(border) => {
+                let s = border_spread(border.position);
+                let w = &border.width;
+                node.border = UiRect {
+                    left: Val::Px(w.left as f32),
+                    right: Val::Px(w.right as f32),
+                    top: Val::Px(w.top as f32),
+                    bottom: Val::Px(w.bottom as f32),
+                };
+                let cr = &border.corner_radii;
+                let radii = CornerRadii {
+                    top_left: cr.top_left + (w.left.max(w.top) as f32) * s,
+                    top_right: cr.top_right + (w.right.max(w.top) as f32) * s,
+                    bottom_left: cr.bottom_left + (w.left.max(w.bottom) as f32) * s,
+                    bottom_right: cr.bottom_right + (w.right.max(w.bottom) as f32) * s,
+                };
+                entity.insert((node, BorderColor(bevy_color(border.color)), border_radius(&radii)));
+            }
+            RenderCommandConfig::Text(text) => {
+                // Lines come already wrapped by Ply
+                entity.insert((
+                    node,
+                    Text::new(text.text.clone()),
+                    TextFont { font_size: text.font_size as f32, ..Default::default() },
+                    TextColor(bevy_color(text.color)),
+                    TextLayout::new_with_no_wrap(),
+                ));
+            }
+            RenderCommandConfig::Custom(custom) => {
+                entity.insert((node, BackgroundColor(bevy_color(custom.background_color)), border_radius(&custom.corner_radii)));
+            }
+            RenderCommandConfig::ScissorStart() => {
+                node.overflow = Overflow::clip();
+                entity.insert(node);
+                clips.push((slot.entity, Vector2::new(bb.x, bb.y)));
+            }
+            _ => {}
+        }
+    }
+
+    ply_ui.nodes.retain(|_, slot| {
+        if slot.seen != generation {
+            commands.entity(slot.entity).try_despawn();
+        }
+        slot.seen == generation
+    });
+}
+
+fn bevy_color(color: Color) -> BevyColor {
+    BevyColor::srgba(color.r / 255.0, color.g / 255.0, color.b / 255.0, color.a / 255.0)
+}
+
+fn border_radius(radii: &CornerRadii) -> BorderRadius {
+    BorderRadius {
+        top_left: Val::Px(radii.top_left),
+        top_right: Val::Px(radii.top_right),
+        bottom_left: Val::Px(radii.bottom_left),
+        bottom_right: Val::Px(radii.bottom_right),
+    }
+}
+
+/// How much of the border lies outside the bounding box.
+fn border_spread(position: BorderPosition) -> f32 {
+    match position {
+        BorderPosition::Outside => 1.0,
+        BorderPosition::Middle => 0.5,
+        BorderPosition::Inside => 0.0,
+    }
+}
+
+/// Bevy draws borders inside the node, so the node covers the border's outer edge.
+fn outer_border_box(bb: &BoundingBox, border: &crate::render_commands::Border) -> BoundingBox {
+    let s = border_spread(border.position);
+    let w = &border.width;
+    let (left, right, top, bottom) = (w.left as f32, w.right as f32, w.top as f32, w.bottom as f32);
+    BoundingBox::new(
+        bb.x - left * s,
+        bb.y - top * s,
+        bb.width + (left + right) * s,
+        bb.height + (top + bottom) * s,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixed, grow};
+    use ::bevy::input::InputPlugin;
+    use ::bevy::window::WindowPlugin;
+    use ::bevy::MinimalPlugins;
+
+    #[test]
+    fn test_bevy_plugin_syncs_nodes_with_layout() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin, WindowPlugin::default(), PlyPlugin::<()>::default()));
+        app.add_systems(::bevy::app::Update, |mut ply: NonSendMut<PlyUi>, mut frame: Local<u32>| {
+            *frame += 1;
+            let with_button = *frame == 1;
+            ply.frame(|ui| {
+                ui.element().id("root").width(grow!()).height(grow!()).background_color(0x202020).children(|ui| {
+                    if with_button {
+                        ui.element().id("button").width(fixed!(50.0)).height(fixed!(30.0)).background_color(0x336699).empty();
+                    }
+                });
+            });
+        });
+
+        app.update();
+        let window = app.world_mut().query_filtered::<&Window, With<PrimaryWindow>>().single(app.world()).unwrap();
+        let size = (window.width(), window.height());
+        let root = app.world().non_send_resource::<PlyUi>().ply().bounding_box("root").unwrap();
+        assert_eq!((root.width, root.height), size);
+        let mut nodes = app.world_mut().query::<(&PlyNode, &Node, &BackgroundColor)>();
+        assert_eq!(nodes.iter(app.world()).count(), 2);
+        assert!(nodes.iter(app.world()).any(|(_, node, _)| node.width == Val::Px(50.0) && node.height == Val::Px(30.0)));
+
+        // The button's node goes away with the button
+        app.update();
+        assert_eq!(app.world_mut().query::<&PlyNode>().iter(app.world()).count(), 1);
+    }
+}
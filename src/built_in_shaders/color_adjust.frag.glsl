@@ -0,0 +1,40 @@
+#version 100
+precision mediump float;
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+uniform vec2 u_resolution;
+uniform vec2 u_position;
+
+// User uniforms, all unchanged at 0.0
+uniform float u_hue;        // hue rotation in radians
+uniform float u_saturation; // -1.0 = gray, 1.0 = double
+uniform float u_brightness; // added to each channel, -1.0-1.0
+uniform float u_contrast;   // -1.0 = flat gray, 1.0 = double
+uniform float u_grayscale;  // mix toward grayscale, 0.0-1.0
+
+const vec3 LUMA = vec3(0.2126, 0.7152, 0.0722);
+
+// Rotates the color around the gray axis
+vec3 rotate_hue(vec3 rgb, float angle) {
+    vec3 k = vec3(0.57735);
+    float c = cos(angle);
+    return rgb * c + cross(k, rgb) * sin(angle) + k * dot(k, rgb) * (1.0 - c);
+}
+
+void main() {
+    vec4 base = texture2D(Texture, uv) * color;
+    vec3 rgb = base.rgb;
+
+    rgb = rotate_hue(rgb, u_hue);
+
+    float luma = dot(rgb, LUMA);
+    rgb = mix(vec3(luma), rgb, 1.0 + u_saturation);
+
+    rgb += u_brightness;
+    rgb = (rgb - 0.5) * (1.0 + u_contrast) + 0.5;
+
+    rgb = mix(rgb, vec3(dot(rgb, LUMA)), clamp(u_grayscale, 0.0, 1.0));
+
+    gl_FragColor = vec4(clamp(rgb, 0.0, 1.0), base.a);
+}
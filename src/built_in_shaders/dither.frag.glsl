@@ -0,0 +1,38 @@
+#version 100
+precision mediump float;
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+uniform vec2 u_resolution;
+uniform vec2 u_position;
+
+// User uniforms
+uniform float u_levels;    // color levels per channel (default: 4.0)
+uniform float u_cell_size; // dither pattern pixel size (default: 1.0)
+
+// 2x2 and 4x4 Bayer thresholds in 0..1
+float bayer2(vec2 a) {
+    a = floor(a);
+    return fract(dot(a, vec2(0.5, a.y * 0.75)));
+}
+
+float bayer4(vec2 a) {
+    return bayer2(0.5 * a) * 0.25 + bayer2(a);
+}
+
+void main() {
+    float levels = u_levels >= 2.0 ? u_levels : 4.0;
+    float cell = u_cell_size > 0.0 ? u_cell_size : 1.0;
+
+    vec4 base = texture2D(Texture, uv) * color;
+
+    // Offset by the element position so the pattern stays put on screen
+    vec2 pixel = (uv * u_resolution + u_position) / cell;
+    float threshold = bayer4(pixel);
+
+    // Round each channel up or down to a level, by the pattern's threshold
+    float steps = levels - 1.0;
+    vec3 result = floor(base.rgb * steps + threshold) / steps;
+
+    gl_FragColor = vec4(clamp(result, 0.0, 1.0), base.a);
+}
@@ -0,0 +1,32 @@
+#version 100
+precision mediump float;
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+uniform vec2 u_resolution;
+uniform vec2 u_position;
+uniform float ply_time;
+
+// User uniforms
+uniform float u_intensity; // grain strength, 0.0-1.0 (default: 0.1)
+uniform float u_fps;       // new grain patterns per second (default: 24.0)
+uniform float u_size;      // grain size in pixels (default: 1.0)
+
+float hash(vec2 p) {
+    return fract(sin(dot(p, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+void main() {
+    float intensity = u_intensity > 0.0 ? u_intensity : 0.1;
+    float fps = u_fps > 0.0 ? u_fps : 24.0;
+    float size = u_size > 0.0 ? u_size : 1.0;
+
+    vec4 base = texture2D(Texture, uv) * color;
+
+    // Reseed a fixed number of times per second, like film frames
+    float frame = floor(ply_time * fps);
+    vec2 grain_pixel = floor((uv * u_resolution + u_position) / size);
+    float noise = hash(grain_pixel + frame * vec2(17.0, 31.0)) - 0.5;
+
+    gl_FragColor = vec4(clamp(base.rgb + noise * intensity, 0.0, 1.0), base.a);
+}
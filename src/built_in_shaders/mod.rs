@@ -72,6 +72,107 @@ pub const CRT: ShaderAsset = ShaderAsset::Source {
     fragment: include_str!("crt.frag.glsl"),
 };
 
+/// **Pixelate** — Blocky mosaic.
+///
+/// Fills each square cell with the color at its center, for retro styling or
+/// censoring a region.
+///
+/// | Uniform       | Type    | Default | Description         |
+/// |---------------|---------|---------|---------------------|
+/// | `u_cell_size` | `float` | `8.0`   | Cell size in pixels |
+pub const PIXELATE: ShaderAsset = ShaderAsset::Source {
+    file_name: "ply_builtin_pixelate",
+    fragment: include_str!("pixelate.frag.glsl"),
+};
+
+/// **Dither** — Ordered (Bayer) dithering.
+///
+/// Reduces each color channel to a few levels and hides the banding with a
+/// 4×4 Bayer pattern.
+///
+/// | Uniform       | Type    | Default | Description                           |
+/// |---------------|---------|---------|---------------------------------------|
+/// | `u_levels`    | `float` | `4.0`   | Color levels per channel (`≥ 2`)      |
+/// | `u_cell_size` | `float` | `1.0`   | Pattern pixel size, for chunkier dots |
+pub const DITHER: ShaderAsset = ShaderAsset::Source {
+    file_name: "ply_builtin_dither",
+    fragment: include_str!("dither.frag.glsl"),
+};
+
+/// **Vignette** — Darkened edges.
+///
+/// Fades the corners toward a color, drawing the eye to the center.
+///
+/// | Uniform       | Type    | Default | Description                              |
+/// |---------------|---------|---------|------------------------------------------|
+/// | `u_intensity` | `float` | `0.5`   | Edge darkness (`0.0`–`1.0`)              |
+/// | `u_radius`    | `float` | `0.75`  | Where darkening starts, `1.0` = corners  |
+/// | `u_softness`  | `float` | `0.45`  | Width of the falloff                     |
+/// | `u_color`     | `vec4`  | black   | Edge color                               |
+pub const VIGNETTE: ShaderAsset = ShaderAsset::Source {
+    file_name: "ply_builtin_vignette",
+    fragment: include_str!("vignette.frag.glsl"),
+};
+
+/// **Grain** — Animated film grain.
+///
+/// Adds flickering noise, reseeded `u_fps` times per second from the
+/// automatic `ply_time` uniform, so it needs no time plumbing.
+///
+/// | Uniform       | Type    | Default | Description                   |
+/// |---------------|---------|---------|-------------------------------|
+/// | `u_intensity` | `float` | `0.1`   | Grain strength (`0.0`–`1.0`)  |
+/// | `u_fps`       | `float` | `24.0`  | New grain patterns per second |
+/// | `u_size`      | `float` | `1.0`   | Grain size in pixels          |
+pub const GRAIN: ShaderAsset = ShaderAsset::Source {
+    file_name: "ply_builtin_grain",
+    fragment: include_str!("grain.frag.glsl"),
+};
+
+/// **Color Adjust** — Hue, saturation, brightness and contrast.
+///
+/// Every uniform leaves the colors unchanged at `0.0`, so only set what you
+/// need, e.g. `u_grayscale = 1.0` and `u_brightness = -0.2` for a disabled
+/// look on a whole subtree.
+///
+/// | Uniform        | Type    | Default | Description                                   |
+/// |----------------|---------|---------|-----------------------------------------------|
+/// | `u_hue`        | `float` | `0.0`   | Hue rotation in radians                       |
+/// | `u_saturation` | `float` | `0.0`   | `-1.0` = gray, `1.0` = double saturation      |
+/// | `u_brightness` | `float` | `0.0`   | Added to each channel (`-1.0`–`1.0`)          |
+/// | `u_contrast`   | `float` | `0.0`   | `-1.0` = flat gray, `1.0` = double contrast   |
+/// | `u_grayscale`  | `float` | `0.0`   | Mix toward grayscale (`0.0`–`1.0`)            |
+pub const COLOR_ADJUST: ShaderAsset = ShaderAsset::Source {
+    file_name: "ply_builtin_color_adjust",
+    fragment: include_str!("color_adjust.frag.glsl"),
+};
+
+/// **Wipe transition** — A soft edge sweeps the new state in.
+///
+/// For `.transition()`, see [`TransitionBuilder::shader`](crate::transition::TransitionBuilder::shader).
+///
+/// | Uniform      | Type    | Default | Description                                   |
+/// |--------------|---------|---------|-----------------------------------------------|
+/// | `u_angle`    | `float` | `0.0`   | Direction in radians (`0.0` = left→right)     |
+/// | `u_softness` | `float` | `0.05`  | Edge width (normalized)                       |
+pub const TRANSITION_WIPE: ShaderAsset = ShaderAsset::Source {
+    file_name: "ply_builtin_transition_wipe",
+    fragment: include_str!("transition_wipe.frag.glsl"),
+};
+
+/// **Dissolve transition** — The new state appears in random cells.
+///
+/// For `.transition()`, see [`TransitionBuilder::shader`](crate::transition::TransitionBuilder::shader).
+///
+/// | Uniform       | Type    | Default | Description                 |
+/// |---------------|---------|---------|-----------------------------|
+/// | `u_cell_size` | `float` | `4.0`   | Noise cell size in pixels   |
+/// | `u_seed`      | `float` | `0.0`   | Varies the pattern          |
+pub const TRANSITION_DISSOLVE: ShaderAsset = ShaderAsset::Source {
+    file_name: "ply_builtin_transition_dissolve",
+    fragment: include_str!("transition_dissolve.frag.glsl"),
+};
+
 /// **Gradient (Linear)** — Angle-based linear gradient.
 ///
 /// Blends between two colors along a direction defined by an angle.
@@ -0,0 +1,20 @@
+#version 100
+precision mediump float;
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+uniform vec2 u_resolution;
+uniform vec2 u_position;
+
+// User uniforms
+uniform float u_cell_size; // cell size in pixels (default: 8.0)
+
+void main() {
+    float cell = u_cell_size > 0.0 ? u_cell_size : 8.0;
+
+    // Sample every cell at its center so the whole cell takes one color
+    vec2 cells = max(u_resolution / cell, vec2(1.0));
+    vec2 snapped = (floor(uv * cells) + 0.5) / cells;
+
+    gl_FragColor = texture2D(Texture, snapped) * color;
+}
@@ -0,0 +1,28 @@
+#version 100
+precision mediump float;
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+uniform sampler2D ply_previous;
+uniform vec2 u_resolution;
+uniform float ply_progress;
+
+// User uniforms
+uniform float u_cell_size; // noise cell size in pixels (default: 4.0)
+uniform float u_seed;      // varies the pattern (default: 0.0)
+
+float hash(vec2 p) {
+    return fract(sin(dot(p + u_seed, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+void main() {
+    float cell = u_cell_size > 0.0 ? u_cell_size : 4.0;
+
+    // Each cell switches to the new frame once progress passes its noise value
+    float noise = hash(floor(uv * u_resolution / cell));
+    float reveal = step(noise, ply_progress);
+
+    vec4 next = texture2D(Texture, uv);
+    vec4 previous = texture2D(ply_previous, uv);
+    gl_FragColor = mix(previous, next, reveal) * color;
+}
@@ -0,0 +1,28 @@
+#version 100
+precision mediump float;
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+uniform sampler2D ply_previous;
+uniform float ply_progress;
+
+// User uniforms
+uniform float u_angle;    // wipe direction in radians (0.0 = left→right)
+uniform float u_softness; // edge width, normalized (default: 0.05)
+
+void main() {
+    float softness = u_softness > 0.0 ? u_softness : 0.05;
+
+    // Position along the wipe direction, 0 at the start corner and 1 at the end
+    vec2 dir = vec2(cos(u_angle), sin(u_angle));
+    float extent = abs(dir.x) + abs(dir.y);
+    float along = (dot(uv - 0.5, dir) / extent) + 0.5;
+
+    // The edge travels from before the start to past the end
+    float edge = ply_progress * (1.0 + softness);
+    float reveal = 1.0 - smoothstep(edge - softness, edge, along);
+
+    vec4 next = texture2D(Texture, uv);
+    vec4 previous = texture2D(ply_previous, uv);
+    gl_FragColor = mix(previous, next, reveal) * color;
+}
@@ -0,0 +1,27 @@
+#version 100
+precision mediump float;
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+uniform vec2 u_resolution;
+uniform vec2 u_position;
+
+// User uniforms
+uniform float u_intensity; // edge darkness, 0.0-1.0 (default: 0.5)
+uniform float u_radius;    // where darkening starts, 1.0 = the corners (default: 0.75)
+uniform float u_softness;  // width of the falloff (default: 0.45)
+uniform vec4  u_color;     // edge color (default: black)
+
+void main() {
+    float intensity = u_intensity > 0.0 ? u_intensity : 0.5;
+    float radius = u_radius > 0.0 ? u_radius : 0.75;
+    float softness = u_softness > 0.0 ? u_softness : 0.45;
+
+    vec4 base = texture2D(Texture, uv) * color;
+
+    // Distance from the center, scaled so the corners are at 1.0
+    float dist = length(uv - 0.5) / 0.7071;
+    float shade = smoothstep(radius - softness, radius, dist) * intensity;
+
+    gl_FragColor = vec4(mix(base.rgb, u_color.rgb, shade), base.a);
+}
@@ -0,0 +1,434 @@
+//! Canvas elements: vector drawing recorded into the element's render command.
+//!
+//! ```ignore
+//! ui.element()
+//!     .width(fixed!(120.0))
+//!     .height(fixed!(120.0))
+//!     .canvas(|p| p
+//!         .circle((60.0, 60.0), 50.0, |s| s.stroke(4.0, DARKGRAY))
+//!         .path(|path| path
+//!             .arc((60.0, 60.0), 50.0, PI * 0.75, PI * 0.75 + sweep)
+//!             .stroke(4.0, BLUE)
+//!         )
+//!         .line((60.0, 60.0), needle, 2.0, WHITE)
+//!     )
+//!     .empty();
+//! ```
+//!
+//! Coordinates are pixels from the element's top-left corner. Drawing is
+//! clipped to the element and follows its shape rotation, and the recorded
+//! commands reach the renderer as a [`RenderCommandConfig::Canvas`], so
+//! custom backends can draw them too.
+//!
+//! [`RenderCommandConfig::Canvas`]: crate::render_commands::RenderCommandConfig::Canvas
+//!
+//! For drawing the painter can't express, `.on_draw()` runs a closure at
+//! the element's place in the draw order instead:
+//!
+//! ```ignore
+//! ui.element()
+//!     .width(grow!())
+//!     .height(fixed!(80.0))
+//!     .on_draw(|bbox, _ctx| {
+//!         draw_texture(&noise, bbox.x, bbox.y, WHITE);
+//!     })
+//!     .empty();
+//! ```
+
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::engine::ShapeRotationConfig;
+use crate::math::{BoundingBox, Vector2};
+
+/// Pixels per straight segment when flattening curves and arcs.
+const FLATTEN_STEP: f32 = 4.0;
+/// Most segments a single curve or arc is flattened into.
+const MAX_SEGMENTS: usize = 128;
+
+/// An outline drawn along a shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stroke {
+    pub thickness: f32,
+    pub color: Color,
+}
+
+/// A recorded drawing command, in pixels from the element's top-left corner.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PaintCommand {
+    Line {
+        from: Vector2,
+        to: Vector2,
+        thickness: f32,
+        color: Color,
+    },
+    Circle {
+        center: Vector2,
+        radius: f32,
+        fill: Option<Color>,
+        stroke: Option<Stroke>,
+    },
+    /// A polyline; curves and arcs are already flattened into points.
+    Path {
+        points: Vec<Vector2>,
+        closed: bool,
+        fill: Option<Color>,
+        stroke: Option<Stroke>,
+    },
+}
+
+/// Records drawing commands for a canvas element.
+#[derive(Debug, Clone, Default)]
+pub struct Painter {
+    pub(crate) commands: Vec<PaintCommand>,
+}
+
+impl Painter {
+    /// Draws a straight line.
+    #[inline]
+    pub fn line(
+        &mut self,
+        from: impl Into<Vector2>,
+        to: impl Into<Vector2>,
+        thickness: f32,
+        color: impl Into<Color>,
+    ) -> &mut Self {
+        self.commands.push(PaintCommand::Line {
+            from: from.into(),
+            to: to.into(),
+            thickness,
+            color: color.into(),
+        });
+        self
+    }
+
+    /// Draws a circle, filled and/or outlined as set in `style_fn`.
+    #[inline]
+    pub fn circle(
+        &mut self,
+        center: impl Into<Vector2>,
+        radius: f32,
+        style_fn: impl for<'a> FnOnce(&'a mut ShapeStyle) -> &'a mut ShapeStyle,
+    ) -> &mut Self {
+        let mut style = ShapeStyle::default();
+        style_fn(&mut style);
+        self.commands.push(PaintCommand::Circle {
+            center: center.into(),
+            radius,
+            fill: style.fill,
+            stroke: style.stroke,
+        });
+        self
+    }
+
+    /// Draws a path of lines, curves and arcs built in `path_fn`.
+    pub fn path(
+        &mut self,
+        path_fn: impl for<'a> FnOnce(&'a mut PathBuilder) -> &'a mut PathBuilder,
+    ) -> &mut Self {
+        let mut path = PathBuilder::default();
+        path_fn(&mut path);
+        let PathBuilder { subpaths, style, .. } = path;
+        for (points, closed) in subpaths.into_iter().filter(|(points, _)| points.len() >= 2) {
+            self.commands.push(PaintCommand::Path {
+                points,
+                closed,
+                fill: style.fill,
+                stroke: style.stroke,
+            });
+        }
+        self
+    }
+}
+
+/// Fill and outline of a canvas shape. Shapes with neither aren't drawn.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShapeStyle {
+    pub(crate) fill: Option<Color>,
+    pub(crate) stroke: Option<Stroke>,
+}
+
+impl ShapeStyle {
+    /// Fills the shape.
+    #[inline]
+    pub fn fill(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.fill = Some(color.into());
+        self
+    }
+
+    /// Outlines the shape.
+    #[inline]
+    pub fn stroke(&mut self, thickness: f32, color: impl Into<Color>) -> &mut Self {
+        self.stroke = Some(Stroke { thickness, color: color.into() });
+        self
+    }
+}
+
+/// Builder for a canvas path via closure.
+///
+/// Fills are drawn as a fan from each subpath's first point, so they suit
+/// convex shapes and pie slices.
+#[derive(Debug, Clone, Default)]
+pub struct PathBuilder {
+    subpaths: Vec<(Vec<Vector2>, bool)>,
+    style: ShapeStyle,
+}
+
+impl PathBuilder {
+    fn current(&self) -> Option<Vector2> {
+        self.subpaths.last().filter(|(_, closed)| !closed).and_then(|(points, _)| points.last().copied())
+    }
+
+    fn push(&mut self, point: Vector2) {
+        match self.subpaths.last_mut() {
+            Some((points, false)) => points.push(point),
+            _ => self.subpaths.push((vec![point], false)),
+        }
+    }
+
+    /// Starts a new subpath at `point`.
+    #[inline]
+    pub fn move_to(&mut self, point: impl Into<Vector2>) -> &mut Self {
+        self.subpaths.push((vec![point.into()], false));
+        self
+    }
+
+    /// Adds a straight line to `point`.
+    #[inline]
+    pub fn line_to(&mut self, point: impl Into<Vector2>) -> &mut Self {
+        self.push(point.into());
+        self
+    }
+
+    /// Adds a quadratic Bézier curve to `point`.
+    pub fn quad_to(&mut self, control: impl Into<Vector2>, point: impl Into<Vector2>) -> &mut Self {
+        let (control, end) = (control.into(), point.into());
+        let start = self.current().unwrap_or(control);
+        let segments = segment_count(distance(start, control) + distance(control, end));
+        for i in 1..=segments {
+            let t = i as f32 / segments as f32;
+            let u = 1.0 - t;
+            self.push(Vector2::new(
+                u * u * start.x + 2.0 * u * t * control.x + t * t * end.x,
+                u * u * start.y + 2.0 * u * t * control.y + t * t * end.y,
+            ));
+        }
+        self
+    }
+
+    /// Adds a cubic Bézier curve to `point`, as used for node-graph edges.
+    pub fn cubic_to(
+        &mut self,
+        control_a: impl Into<Vector2>,
+        control_b: impl Into<Vector2>,
+        point: impl Into<Vector2>,
+    ) -> &mut Self {
+        let (a, b, end) = (control_a.into(), control_b.into(), point.into());
+        let start = self.current().unwrap_or(a);
+        let segments = segment_count(distance(start, a) + distance(a, b) + distance(b, end));
+        for i in 1..=segments {
+            let t = i as f32 / segments as f32;
+            let u = 1.0 - t;
+            let (w0, w1, w2, w3) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            self.push(Vector2::new(
+                w0 * start.x + w1 * a.x + w2 * b.x + w3 * end.x,
+                w0 * start.y + w1 * a.y + w2 * b.y + w3 * end.y,
+            ));
+        }
+        self
+    }
+
+    /// Adds a circular arc, joined to the path by a line.
+    ///
+    /// Angles are in radians, clockwise from the positive x axis.
+    pub fn arc(&mut self, center: impl Into<Vector2>, radius: f32, start_angle: f32, end_angle: f32) -> &mut Self {
+        let center = center.into();
+        let sweep = end_angle - start_angle;
+        let segments = segment_count(radius.abs() * sweep.abs());
+        for i in 0..=segments {
+            let angle = start_angle + sweep * i as f32 / segments as f32;
+            self.push(Vector2::new(center.x + radius * angle.cos(), center.y + radius * angle.sin()));
+        }
+        self
+    }
+
+    /// Closes the current subpath back to its first point.
+    #[inline]
+    pub fn close(&mut self) -> &mut Self {
+        if let Some((_, closed)) = self.subpaths.last_mut() {
+            *closed = true;
+        }
+        self
+    }
+
+    /// Fills the path.
+    #[inline]
+    pub fn fill(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.style.fill(color);
+        self
+    }
+
+    /// Outlines the path.
+    #[inline]
+    pub fn stroke(&mut self, thickness: f32, color: impl Into<Color>) -> &mut Self {
+        self.style.stroke(thickness, color);
+        self
+    }
+}
+
+/// Renderer state an `.on_draw()` closure runs under.
+#[derive(Debug, Clone, Copy)]
+pub struct DrawContext {
+    /// The active scissor rectangle, in physical pixels.
+    pub scissor: Option<(i32, i32, i32, i32)>,
+    /// The element's shape rotation, already applied as the model matrix.
+    pub rotation: Option<ShapeRotationConfig>,
+    /// Physical pixels per logical pixel.
+    pub dpi_scale: f32,
+}
+
+type DrawFn = dyn Fn(BoundingBox, &DrawContext) + Send + Sync;
+
+/// A closure set with `.on_draw()`, carried by a [`RenderCommandConfig::Draw`].
+///
+/// [`RenderCommandConfig::Draw`]: crate::render_commands::RenderCommandConfig::Draw
+#[derive(Clone)]
+pub struct DrawHook(Arc<DrawFn>);
+
+impl DrawHook {
+    pub(crate) fn new(f: impl Fn(BoundingBox, &DrawContext) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Runs the closure. Custom renderers call this with their own state applied.
+    pub fn call(&self, bounding_box: BoundingBox, ctx: &DrawContext) {
+        (self.0)(bounding_box, ctx)
+    }
+}
+
+impl std::fmt::Debug for DrawHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DrawHook")
+    }
+}
+
+fn distance(a: Vector2, b: Vector2) -> f32 {
+    (b.x - a.x).hypot(b.y - a.y)
+}
+
+fn segment_count(length: f32) -> usize {
+    ((length / FLATTEN_STEP).ceil() as usize).clamp(2, MAX_SEGMENTS)
+}
+
+/// Maps canvas points into screen space for an element at `bbox`, applying
+/// its shape rotation about the center like rectangles do.
+pub(crate) fn transform(
+    bbox: &BoundingBox,
+    rotation: Option<&ShapeRotationConfig>,
+) -> impl Fn(Vector2) -> Vector2 {
+    let center = Vector2::new(bbox.x + bbox.width / 2.0, bbox.y + bbox.height / 2.0);
+    let (half_w, half_h) = (bbox.width / 2.0, bbox.height / 2.0);
+    let (flip_x, flip_y, sin, cos) = match rotation {
+        Some(r) => (r.flip_x, r.flip_y, r.rotation_radians.sin(), r.rotation_radians.cos()),
+        None => (false, false, 0.0, 1.0),
+    };
+    move |point: Vector2| {
+        let mut x = point.x - half_w;
+        let mut y = point.y - half_h;
+        if flip_x {
+            x = -x;
+        }
+        if flip_y {
+            y = -y;
+        }
+        Vector2::new(center.x + x * cos - y * sin, center.y + x * sin + y * cos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Dimensions;
+    use crate::render_commands::RenderCommandConfig;
+    use crate::Ply;
+
+    #[test]
+    fn test_canvas_records_commands_after_background() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(200.0, 200.0));
+        let mut ui = ply.begin();
+        ui.element()
+            .width(crate::layout::Sizing::Fixed(100.0))
+            .height(crate::layout::Sizing::Fixed(50.0))
+            .background_color(0x202020)
+            .canvas(|p| p
+                .line((0.0, 0.0), (100.0, 50.0), 2.0, 0xFFFFFF)
+                .circle((50.0, 25.0), 10.0, |s| s.fill(0xFF0000))
+                .path(|path| path
+                    .move_to((0.0, 50.0))
+                    .cubic_to((30.0, 0.0), (70.0, 0.0), (100.0, 50.0))
+                    .stroke(2.0, 0x00FF00)
+                )
+            )
+            .empty();
+        let items = ui.eval();
+
+        assert_eq!(items.len(), 2);
+        assert!(matches!(items[0].config, RenderCommandConfig::Rectangle(_)));
+        let RenderCommandConfig::Canvas(canvas) = &items[1].config else {
+            panic!("Expected Canvas, got {:?}", items[1].config);
+        };
+        assert_eq!(canvas.commands.len(), 3);
+        let PaintCommand::Path { points, closed, fill, stroke } = &canvas.commands[2] else {
+            panic!("Expected Path, got {:?}", canvas.commands[2]);
+        };
+        assert!(!closed && fill.is_none() && stroke.is_some());
+        assert_eq!(points.first(), Some(&Vector2::new(0.0, 50.0)));
+        assert_eq!(points.last(), Some(&Vector2::new(100.0, 50.0)));
+        assert!(points.len() > 10);
+
+        // A half turn maps the top-left corner onto the bottom-right one
+        let rotation = ShapeRotationConfig { rotation_radians: std::f32::consts::PI, ..Default::default() };
+        let to_screen = transform(&items[1].bounding_box, Some(&rotation));
+        let corner = to_screen(Vector2::new(0.0, 0.0));
+        assert!((corner.x - 100.0).abs() < 0.001 && (corner.y - 50.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_on_draw_runs_with_resolved_bounds() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(200.0, 200.0));
+        let drawn = Arc::new(std::sync::Mutex::new(None));
+        let mut ui = ply.begin();
+        ui.element()
+            .layout(|l| l.padding((10, 10, 10, 10)))
+            .children(|ui| {
+                let drawn = drawn.clone();
+                ui.element()
+                    .width(crate::layout::Sizing::Fixed(60.0))
+                    .height(crate::layout::Sizing::Fixed(40.0))
+                    .background_color(0x202020)
+                    .on_draw(move |bbox, _| *drawn.lock().unwrap() = Some(bbox))
+                    .children(|ui| {
+                        ui.element()
+                            .width(crate::layout::Sizing::Fixed(10.0))
+                            .height(crate::layout::Sizing::Fixed(10.0))
+                            .background_color(0xFFFFFF)
+                            .empty();
+                    });
+            });
+        let items = ui.eval();
+
+        // Background, then the hook, then the child on top
+        assert_eq!(items.len(), 3);
+        assert!(matches!(items[0].config, RenderCommandConfig::Rectangle(_)));
+        let RenderCommandConfig::Draw(hook) = &items[1].config else {
+            panic!("Expected Draw, got {:?}", items[1].config);
+        };
+        assert!(matches!(items[2].config, RenderCommandConfig::Rectangle(_)));
+
+        let ctx = DrawContext { scissor: None, rotation: None, dpi_scale: 1.0 };
+        hook.call(items[1].bounding_box, &ctx);
+        assert_eq!(*drawn.lock().unwrap(), Some(BoundingBox::new(10.0, 10.0, 60.0, 40.0)));
+    }
+}
@@ -0,0 +1,100 @@
+//! Reading rendered pixels back, for screenshots, bug reports and thumbnails.
+//!
+//! ```ignore
+//! ui.show(|_| {}).await;
+//! if share_clicked {
+//!     let thumbnail = ui.capture_element("card").unwrap();
+//!     thumbnail.to_image().export_png("card.png");
+//! }
+//! next_frame().await;
+//! ```
+//!
+//! Captures read what has been drawn so far this frame, so take them after
+//! `show()` and before `next_frame()`. They need a window and aren't
+//! available to headless instances.
+
+use macroquad::prelude::{Image, Texture2D};
+
+/// Captured pixels, as tightly packed RGBA8 in physical pixels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageBuffer {
+    pub width: u16,
+    pub height: u16,
+    /// `width * height * 4` bytes, row by row from the top.
+    pub rgba: Vec<u8>,
+}
+
+impl ImageBuffer {
+    /// Reads back the window's framebuffer.
+    pub(crate) fn from_screen() -> Self {
+        let image = macroquad::prelude::get_screen_data();
+        // The framebuffer is stored bottom row first
+        Self::from_bottom_up(image.width, image.height, image.bytes)
+    }
+
+    fn from_bottom_up(width: u16, height: u16, rgba: Vec<u8>) -> Self {
+        let row = width as usize * 4;
+        let rgba = rgba.chunks_exact(row).rev().flatten().copied().collect();
+        Self { width, height, rgba }
+    }
+
+    /// Returns the pixel at `(x, y)` as `[r, g, b, a]`, if it is inside the image.
+    pub fn pixel(&self, x: u16, y: u16) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let start = (y as usize * self.width as usize + x as usize) * 4;
+        self.rgba[start..start + 4].try_into().ok()
+    }
+
+    /// Copies the pixels inside a rectangle, clamped to the image.
+    pub fn crop(&self, x: u16, y: u16, width: u16, height: u16) -> ImageBuffer {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+        let row = self.width as usize * 4;
+        let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+        for line in y..y + height {
+            let start = line as usize * row + x as usize * 4;
+            rgba.extend_from_slice(&self.rgba[start..start + width as usize * 4]);
+        }
+        ImageBuffer { width, height, rgba }
+    }
+
+    /// Uploads the pixels into a new texture.
+    pub fn to_texture(&self) -> Texture2D {
+        Texture2D::from_rgba8(self.width, self.height, &self.rgba)
+    }
+
+    /// Converts into a macroquad image, for `export_png` and friends.
+    pub fn to_image(&self) -> Image {
+        Image {
+            bytes: self.rgba.clone(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_buffer_flips_rows_and_crops() {
+        // 3×2 pixels, bottom row first, each pixel's red channel numbered
+        let bottom_up: Vec<u8> = [3, 4, 5, 0, 1, 2].iter().flat_map(|&n| [n, 0, 0, 255]).collect();
+        let image = ImageBuffer::from_bottom_up(3, 2, bottom_up);
+
+        assert_eq!(image.pixel(0, 0), Some([0, 0, 0, 255]));
+        assert_eq!(image.pixel(2, 1), Some([5, 0, 0, 255]));
+        assert_eq!(image.pixel(3, 0), None);
+
+        let cropped = image.crop(1, 0, 10, 2);
+        assert_eq!((cropped.width, cropped.height), (2, 2));
+        let reds: Vec<u8> = cropped.rgba.chunks_exact(4).map(|pixel| pixel[0]).collect();
+        assert_eq!(reds, vec![1, 2, 4, 5]);
+        assert_eq!(image.crop(5, 5, 1, 1).rgba.len(), 0);
+    }
+}
@@ -0,0 +1,396 @@
+//! Charts: sparklines, line charts and bar charts drawn straight from a slice.
+//!
+//! ```ignore
+//! ui.sparkline("fps", &fps_history, |s| s.color(GREEN));
+//! ui.bar_chart("sales", &monthly_sales, |c| c.label("Monthly sales").height(120.0));
+//! ui.line_chart("latency", &latency, |c| c.range(0.0, 200.0).point_size(4.0));
+//! ```
+//!
+//! There are no axes or labels; the charts are meant for dashboards and
+//! inline trends. Line charts fit the data's range unless one is set, and bar
+//! charts also include zero so bars grow from it. Line segments are rotated
+//! rectangles, so no custom render command is needed.
+
+use crate::accessibility::AccessibilityRole;
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::id::Id;
+use crate::layout::{LayoutDirection, Sizing};
+use crate::Ui;
+
+/// Builder for configuring a chart via closure.
+#[derive(Debug, Clone)]
+pub struct ChartBuilder {
+    pub(crate) label: String,
+    pub(crate) width: Sizing,
+    pub(crate) height: f32,
+    pub(crate) range: Option<(f32, f32)>,
+    pub(crate) color: Color,
+    pub(crate) background_color: Color,
+    pub(crate) padding: u16,
+    pub(crate) thickness: f32,
+    pub(crate) point_size: f32,
+    pub(crate) gap: u16,
+    pub(crate) corner_radius: f32,
+}
+
+impl ChartBuilder {
+    fn sparkline() -> Self {
+        Self {
+            label: String::new(),
+            width: Sizing::Fixed(120.0),
+            height: 24.0,
+            range: None,
+            color: Color::rgba(58.0, 123.0, 213.0, 255.0),
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+            padding: 0,
+            thickness: 1.5,
+            point_size: 0.0,
+            gap: 1,
+            corner_radius: 0.0,
+        }
+    }
+
+    fn chart() -> Self {
+        Self {
+            width: Sizing::Grow(0.0, f32::MAX, 1.0),
+            height: 160.0,
+            background_color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+            padding: 8,
+            thickness: 2.0,
+            gap: 4,
+            corner_radius: 6.0,
+            ..Self::sparkline()
+        }
+    }
+
+    /// Sets the accessible name of the chart. Screen readers also hear the
+    /// number of values, their range and the last one.
+    #[inline]
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// Sets the width of the chart. Sparklines default to 120, charts grow.
+    #[inline]
+    pub fn width(&mut self, width: Sizing) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the height of the chart. Sparklines default to 24, charts to 160.
+    #[inline]
+    pub fn height(&mut self, height: f32) -> &mut Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the values at the bottom and top of the chart. Values outside are clamped.
+    #[inline]
+    pub fn range(&mut self, min: f32, max: f32) -> &mut Self {
+        self.range = Some((min.min(max), max.max(min)));
+        self
+    }
+
+    /// Sets the color of the line or bars.
+    #[inline]
+    pub fn color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Sets the background color. Sparklines default to none.
+    #[inline]
+    pub fn background_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.background_color = color.into();
+        self
+    }
+
+    /// Sets the padding between the background's edge and the data.
+    #[inline]
+    pub fn padding(&mut self, padding: u16) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the thickness of the line. Defaults to 1.5 for sparklines, 2 for charts.
+    #[inline]
+    pub fn thickness(&mut self, thickness: f32) -> &mut Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Draws a dot of this size at each value of a line. Defaults to none.
+    #[inline]
+    pub fn point_size(&mut self, size: f32) -> &mut Self {
+        self.point_size = size;
+        self
+    }
+
+    /// Sets the gap between bars. Defaults to 1 for sparklines, 4 for charts.
+    #[inline]
+    pub fn gap(&mut self, gap: u16) -> &mut Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Sets the corner radius of the background and bars.
+    #[inline]
+    pub fn corner_radius(&mut self, radius: f32) -> &mut Self {
+        self.corner_radius = radius;
+        self
+    }
+}
+
+/// The chart's range: the set one, else the data's, widened to include zero for bars.
+fn data_range(values: &[f32], range: Option<(f32, f32)>, include_zero: bool) -> (f32, f32) {
+    if let Some(range) = range {
+        return range;
+    }
+    let finite = values.iter().copied().filter(|value| value.is_finite());
+    let (min, max) = finite.fold((f32::MAX, f32::MIN), |(min, max), value| (min.min(value), max.max(value)));
+    if min > max {
+        return (0.0, 1.0);
+    }
+    if include_zero {
+        (min.min(0.0), max.max(0.0))
+    } else {
+        (min, max)
+    }
+}
+
+fn describe(values: &[f32], (min, max): (f32, f32)) -> String {
+    match values.last() {
+        Some(last) => format!("{} values from {min} to {max}, last {last}", values.len()),
+        None => "No values".to_string(),
+    }
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Adds a small line chart of `values`, for trends inline with text or in table cells.
+    ///
+    /// ```ignore
+    /// ui.sparkline("cpu", &cpu_history, |s| s.width(Sizing::Fixed(80.0)));
+    /// ```
+    pub fn sparkline(
+        &mut self,
+        id: impl Into<Id>,
+        values: &[f32],
+        config_fn: impl for<'a> FnOnce(&'a mut ChartBuilder) -> &'a mut ChartBuilder,
+    ) {
+        let mut config = ChartBuilder::sparkline();
+        config_fn(&mut config);
+        self.draw_line_chart(id.into(), values, config);
+    }
+
+    /// Adds a line chart of `values`, spread evenly across its width.
+    ///
+    /// ```ignore
+    /// ui.line_chart("memory", &memory, |c| c.label("Memory").range(0.0, 16.0));
+    /// ```
+    pub fn line_chart(
+        &mut self,
+        id: impl Into<Id>,
+        values: &[f32],
+        config_fn: impl for<'a> FnOnce(&'a mut ChartBuilder) -> &'a mut ChartBuilder,
+    ) {
+        let mut config = ChartBuilder::chart();
+        config_fn(&mut config);
+        self.draw_line_chart(id.into(), values, config);
+    }
+
+    /// Adds a bar chart with a bar per value. Negative values hang below zero.
+    ///
+    /// ```ignore
+    /// ui.bar_chart("visits", &visits_per_day, |c| c.label("Visits").gap(2));
+    /// ```
+    pub fn bar_chart(
+        &mut self,
+        id: impl Into<Id>,
+        values: &[f32],
+        config_fn: impl for<'a> FnOnce(&'a mut ChartBuilder) -> &'a mut ChartBuilder,
+    ) {
+        let mut config = ChartBuilder::chart();
+        config_fn(&mut config);
+        let id = id.into();
+        let (min, max) = data_range(values, config.range, true);
+        let span = max - min;
+        let plot_height = (config.height - config.padding as f32 * 2.0).max(0.0);
+        let baseline = 0.0f32.clamp(min, max);
+        let to_y = |value: f32| if span > 0.0 { (max - value.clamp(min, max)) / span * plot_height } else { plot_height };
+
+        self.element()
+            .id(id.clone())
+            .width(config.width)
+            .height(Sizing::Fixed(config.height))
+            .background_color(config.background_color)
+            .corner_radius(config.corner_radius)
+            .accessibility(|a| a
+                .role(AccessibilityRole::Image)
+                .label(&config.label)
+                .description(&describe(values, (min, max)))
+            )
+            .layout(|l| l.padding(config.padding).gap(config.gap))
+            .children(|ui| {
+                for (index, &value) in values.iter().enumerate() {
+                    let value = if value.is_finite() { value } else { baseline };
+                    let (top, bottom) = (to_y(value.max(baseline)), to_y(value.min(baseline)));
+                    ui.element()
+                        .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+                        .height(Sizing::Grow(0.0, f32::MAX, 1.0))
+                        .layout(|l| l.direction(LayoutDirection::TopToBottom).padding((top.round() as u16, 0, 0, 0)))
+                        .children(|ui| {
+                            ui.element()
+                                .id(id.child_index("bar", index as u32))
+                                .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+                                .height(Sizing::Fixed(bottom - top))
+                                .background_color(config.color)
+                                .corner_radius(config.corner_radius.min((bottom - top) / 2.0))
+                                .empty();
+                        });
+                }
+            });
+    }
+
+    /// Draws a line between two points, in pixels from the open element's
+    /// top-left corner. The line is a rectangle as long as the segment,
+    /// rotated about its center onto it. Without an id it lets the pointer
+    /// through to what's underneath.
+    pub(crate) fn line_segment(
+        &mut self,
+        id: Option<Id>,
+        (x0, y0): (f32, f32),
+        (x1, y1): (f32, f32),
+        thickness: f32,
+        color: Color,
+        clip: bool,
+    ) {
+        let length = ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt();
+        let passthrough = id.is_none();
+        let mut segment = self.element();
+        if let Some(id) = id {
+            segment = segment.id(id);
+        }
+        segment
+            .width(Sizing::Fixed(length))
+            .height(Sizing::Fixed(thickness))
+            .background_color(color)
+            .corner_radius(thickness / 2.0)
+            .rotate_shape(|r| r.radians((y1 - y0).atan2(x1 - x0)))
+            .floating(|f| {
+                f.attach_parent()
+                    .anchor((AlignX::CenterX, AlignY::CenterY), (AlignX::Left, AlignY::Top))
+                    .offset(((x0 + x1) / 2.0, (y0 + y1) / 2.0));
+                if clip {
+                    f.clip_by_parent();
+                }
+                if passthrough {
+                    f.passthrough();
+                }
+                f
+            })
+            .empty();
+    }
+
+    fn draw_line_chart(&mut self, id: Id, values: &[f32], config: ChartBuilder) {
+        let (min, max) = data_range(values, config.range, false);
+        let span = max - min;
+        let padding = config.padding as f32;
+        let plot_height = (config.height - padding * 2.0).max(0.0);
+        // Points are placed in pixels, so grow widths use last frame's size
+        let width = match config.width {
+            Sizing::Fixed(width) => Some(width),
+            _ => self.ply.context.get_element_data(id.clone()).map(|bounds| bounds.width),
+        };
+        let plot_width = width.map_or(0.0, |width| (width - padding * 2.0).max(0.0));
+        let count = values.len();
+        let points: Vec<(f32, f32)> = values
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| {
+                let x = if count > 1 { index as f32 / (count - 1) as f32 * plot_width } else { plot_width / 2.0 };
+                let y = if span > 0.0 && value.is_finite() {
+                    (max - value.clamp(min, max)) / span * plot_height
+                } else {
+                    plot_height / 2.0
+                };
+                (padding + x, padding + y)
+            })
+            .collect();
+
+        self.element()
+            .id(id.clone())
+            .width(config.width)
+            .height(Sizing::Fixed(config.height))
+            .background_color(config.background_color)
+            .corner_radius(config.corner_radius)
+            .accessibility(|a| a
+                .role(AccessibilityRole::Image)
+                .label(&config.label)
+                .description(&describe(values, (min, max)))
+            )
+            .children(|ui| {
+                if width.is_none() {
+                    return;
+                }
+                for (index, pair) in points.windows(2).enumerate() {
+                    ui.line_segment(
+                        Some(id.child_index("segment", index as u32)),
+                        pair[0],
+                        pair[1],
+                        config.thickness,
+                        config.color,
+                        false,
+                    );
+                }
+                if config.point_size > 0.0 {
+                    for &(x, y) in &points {
+                        ui.element()
+                            .width(Sizing::Fixed(config.point_size))
+                            .height(Sizing::Fixed(config.point_size))
+                            .background_color(config.color)
+                            .corner_radius(config.point_size / 2.0)
+                            .floating(|f| f
+                                .attach_parent()
+                                .anchor((AlignX::CenterX, AlignY::CenterY), (AlignX::Left, AlignY::Top))
+                                .offset((x, y))
+                            )
+                            .empty();
+                    }
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Dimensions;
+    use crate::Ply;
+
+    #[test]
+    fn test_charts_place_bars_and_segments_from_data() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 400.0));
+        let mut ui = ply.begin();
+        ui.element().layout(|l| l.direction(LayoutDirection::TopToBottom)).children(|ui| {
+            ui.bar_chart("bars", &[1.0, 2.0, 4.0], |c| c.width(Sizing::Fixed(100.0)).height(100.0).padding(0).corner_radius(0.0));
+            ui.sparkline("line", &[0.0, 10.0, 10.0], |s| s.width(Sizing::Fixed(100.0)).height(50.0).thickness(2.0));
+        });
+        ui.eval();
+
+        let bounds = |id: Id| ply.context.get_element_data(id).unwrap();
+        let bar = |index: u32| bounds(Id::new("bars").child_index("bar", index));
+        assert_eq!(bar(0).height, 25.0);
+        assert_eq!(bar(1).height, 50.0);
+        assert_eq!(bar(2).height, 100.0);
+        assert_eq!(bar(0).y + bar(0).height, bar(2).y + bar(2).height);
+
+        // The flat second segment runs along the top, from x = 50 to 100
+        let line = bounds(Id::new("line"));
+        let segment = bounds(Id::new("line").child_index("segment", 1));
+        assert!((segment.x - (line.x + 50.0)).abs() < 0.01);
+        assert!((segment.width - 50.0).abs() < 0.01);
+        assert!((segment.y - (line.y - 1.0)).abs() < 0.01);
+    }
+}
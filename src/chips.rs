@@ -0,0 +1,434 @@
+//! Chips inputs: a text field that turns entries into removable pills.
+//!
+//! ```ignore
+//! ui.chips_input("tags", &mut post.tags, |c| c
+//!     .label("Tags")
+//!     .placeholder("Add a tag")
+//!     .suggestions(&["rust", "gamedev", "ui", "wasm"])
+//! );
+//! ```
+//!
+//! Typing a comma or pressing Enter turns the text into a chip. Backspace in
+//! the empty field removes the last chip, and each chip has a "×" button.
+//! Blank and repeated entries are ignored. With suggestions, matches for the
+//! typed text show in a list under the field; Down moves into it and Enter
+//! or a click adds the suggestion.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::accessibility::AccessibilityRole;
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::elements::Layer;
+use crate::id::Id;
+use crate::layout::{LayoutDirection, Sizing};
+use crate::renderer::FontAsset;
+use crate::style::StyleStates;
+use crate::text::TextConfig;
+use crate::Ui;
+
+/// Suggestions shown at once.
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Set when Enter is pressed in a chips input, to commit its text next frame.
+#[derive(Default)]
+struct ChipsSubmit(Rc<Cell<bool>>);
+
+/// Adds `text` as a chip unless it is blank or already there.
+fn commit(chips: &mut Vec<String>, text: &str) -> bool {
+    let text = text.trim();
+    if text.is_empty() || chips.iter().any(|chip| chip == text) {
+        return false;
+    }
+    chips.push(text.to_string());
+    true
+}
+
+/// Builder for configuring a chips input via closure.
+#[derive(Debug, Clone)]
+pub struct ChipsBuilder {
+    pub(crate) label: String,
+    pub(crate) placeholder: String,
+    pub(crate) suggestions: Vec<String>,
+    pub(crate) font_asset: Option<&'static FontAsset>,
+    pub(crate) font_size: u16,
+    pub(crate) padding: u16,
+    pub(crate) width: Sizing,
+    pub(crate) corner_radius: f32,
+    pub(crate) background_color: Color,
+    pub(crate) chip_color: Color,
+    pub(crate) list_color: Color,
+    pub(crate) highlight_color: Color,
+    pub(crate) text_color: Color,
+    pub(crate) placeholder_color: Color,
+}
+
+impl Default for ChipsBuilder {
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            placeholder: String::new(),
+            suggestions: Vec::new(),
+            font_asset: None,
+            font_size: 16,
+            padding: 6,
+            width: Sizing::Grow(0.0, f32::MAX, 1.0),
+            corner_radius: 6.0,
+            background_color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+            chip_color: Color::rgba(70.0, 70.0, 70.0, 255.0),
+            list_color: Color::rgba(30.0, 30.0, 30.0, 255.0),
+            highlight_color: Color::rgba(58.0, 123.0, 213.0, 255.0),
+            text_color: Color::rgba(255.0, 255.0, 255.0, 255.0),
+            placeholder_color: Color::rgba(128.0, 128.0, 128.0, 255.0),
+        }
+    }
+}
+
+impl ChipsBuilder {
+    /// Sets the accessible name of the text field.
+    #[inline]
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// Sets the placeholder, shown while there are no chips.
+    #[inline]
+    pub fn placeholder(&mut self, text: &str) -> &mut Self {
+        self.placeholder = text.to_string();
+        self
+    }
+
+    /// Sets values suggested while typing. Matching ignores case.
+    #[inline]
+    pub fn suggestions(&mut self, suggestions: &[&str]) -> &mut Self {
+        self.suggestions = suggestions.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Sets the font asset.
+    #[inline]
+    pub fn font(&mut self, asset: &'static FontAsset) -> &mut Self {
+        self.font_asset = Some(asset);
+        self
+    }
+
+    /// Sets the font size. Defaults to 16.
+    #[inline]
+    pub fn font_size(&mut self, size: u16) -> &mut Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Sets the padding of the field and the gap between chips. Defaults to 6.
+    #[inline]
+    pub fn padding(&mut self, padding: u16) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the width of the field. Defaults to growing.
+    #[inline]
+    pub fn width(&mut self, width: Sizing) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the corner radius of the field, chips and suggestion list. Defaults to 6.
+    #[inline]
+    pub fn corner_radius(&mut self, radius: f32) -> &mut Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Sets the background color of the field.
+    #[inline]
+    pub fn background_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.background_color = color.into();
+        self
+    }
+
+    /// Sets the background color of the chips.
+    #[inline]
+    pub fn chip_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.chip_color = color.into();
+        self
+    }
+
+    /// Sets the background color of the suggestion list.
+    #[inline]
+    pub fn list_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.list_color = color.into();
+        self
+    }
+
+    /// Sets the background color of the hovered or focused suggestion.
+    #[inline]
+    pub fn highlight_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.highlight_color = color.into();
+        self
+    }
+
+    /// Sets the color of the typed text, chips and suggestions.
+    #[inline]
+    pub fn text_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.text_color = color.into();
+        self
+    }
+
+    /// Sets the color of the placeholder text.
+    #[inline]
+    pub fn placeholder_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.placeholder_color = color.into();
+        self
+    }
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Adds a chips input editing `chips`.
+    ///
+    /// Returns `true` on the frame a chip was added or removed.
+    ///
+    /// ```ignore
+    /// ui.chips_input("recipients", &mut recipients, |c| c.placeholder("Add people"));
+    /// ```
+    pub fn chips_input(
+        &mut self,
+        id: impl Into<Id>,
+        chips: &mut Vec<String>,
+        config_fn: impl for<'a> FnOnce(&'a mut ChipsBuilder) -> &'a mut ChipsBuilder,
+    ) -> bool {
+        let mut config = ChipsBuilder::default();
+        config_fn(&mut config);
+        let id = id.into();
+        let input_id = id.child("input");
+        let list_id = id.child("suggestions");
+        let suggestion_id = |index: usize| id.child_index("suggestion", index as u32);
+        let mut changed = false;
+
+        // Commas split off chips as they are typed, or pasted
+        let mut text = self.ply.context.get_text_value(input_id.id).to_string();
+        if let Some(last_comma) = text.rfind(',') {
+            for part in text[..last_comma].split(',') {
+                changed |= commit(chips, part);
+            }
+            text = text[last_comma + 1..].to_string();
+            self.set_text_value(input_id.clone(), &text);
+        }
+        let submit = self.ply.component_states.widget_state(id.id, ChipsSubmit::default).0.clone();
+        if submit.take() {
+            changed |= commit(chips, &text);
+            text.clear();
+            self.set_text_value(input_id.clone(), "");
+        }
+        if self.ply.context.is_backspace_on_empty(input_id.id) {
+            changed |= chips.pop().is_some();
+        }
+
+        let query = text.trim().to_lowercase();
+        let matches: Vec<usize> = if query.is_empty() {
+            Vec::new()
+        } else {
+            (0..config.suggestions.len())
+                .filter(|&index| {
+                    let suggestion = &config.suggestions[index];
+                    suggestion.to_lowercase().contains(&query) && !chips.contains(suggestion)
+                })
+                .take(MAX_SUGGESTIONS)
+                .collect()
+        };
+        let focused = self.ply.context.focused_element_id;
+        let focused_suggestion = matches.iter().copied().find(|&index| suggestion_id(index).id == focused);
+        let show_list = !matches.is_empty() && (focused == input_id.id || focused_suggestion.is_some());
+        let mut refocus_input = focused_suggestion.is_some() && self.ply.escape_pressed;
+
+        let text_config = |t: &mut TextConfig, color: Color| {
+            if let Some(font) = config.font_asset {
+                t.font(font);
+            }
+            t.font_size(config.font_size).color(color);
+        };
+        let line_height = (config.font_size as f32 * 1.5).round();
+        let mut removed = None;
+        let mut picked = None;
+
+        self.element()
+            .id(id.clone())
+            .width(config.width)
+            .background_color(config.background_color)
+            .corner_radius(config.corner_radius)
+            .layout(|l| l
+                .direction(LayoutDirection::LeftToRight)
+                .wrap()
+                .padding(config.padding)
+                .gap(config.padding)
+                .align(AlignX::Left, AlignY::CenterY)
+            )
+            .children(|ui| {
+                for (index, chip) in chips.iter().enumerate() {
+                    ui.element()
+                        .id(id.child_index("chip", index as u32))
+                        .height(Sizing::Fixed(line_height))
+                        .background_color(config.chip_color)
+                        .corner_radius(config.corner_radius)
+                        .layout(|l| l
+                            .padding((0, config.padding / 2, 0, config.padding))
+                            .gap(config.padding / 2)
+                            .align(AlignX::Left, AlignY::CenterY)
+                        )
+                        .children(|ui| {
+                            ui.text(chip, |t| { text_config(t, config.text_color); t });
+                            ui.element()
+                                .id(id.child_index("remove", index as u32))
+                                .preserve_focus()
+                                .accessibility(|a| a.button(&format!("Remove {chip}")))
+                                .layout(|l| l.padding((0, config.padding / 2, 0, config.padding / 2)))
+                                .children(|ui| {
+                                    if ui.just_pressed() {
+                                        removed = Some(index);
+                                    }
+                                    ui.text("×", |t| { text_config(t, config.placeholder_color); t });
+                                });
+                        });
+                }
+
+                ui.element()
+                    .id(input_id.clone())
+                    .width(Sizing::Grow(60.0, f32::MAX, 1.0))
+                    .height(Sizing::Fixed(line_height))
+                    .accessibility(|a| {
+                        a.role(AccessibilityRole::TextInput).label(&config.label);
+                        if let Some(&first) = matches.first().filter(|_| show_list) {
+                            a.focus_down(suggestion_id(first));
+                        }
+                        a
+                    })
+                    .text_input(|t| {
+                        if let Some(font) = config.font_asset {
+                            t.font(font);
+                        }
+                        let submit = submit.clone();
+                        t.placeholder(if chips.is_empty() { config.placeholder.as_str() } else { "" })
+                            .font_size(config.font_size)
+                            .text_color(config.text_color)
+                            .placeholder_color(config.placeholder_color)
+                            .on_submit(move |_| submit.set(true))
+                    })
+                    .empty();
+
+                if !show_list {
+                    return;
+                }
+                ui.element()
+                    .id(list_id.clone())
+                    .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+                    .background_color(config.list_color)
+                    .corner_radius(config.corner_radius)
+                    .floating(|f| f.attach_parent().anchor((AlignX::Left, AlignY::Top), (AlignX::Left, AlignY::Bottom)).offset((0.0, 4.0)).layer(Layer::Overlay))
+                    .preserve_focus()
+                    .accessibility(|a| a.role(AccessibilityRole::ListBox).label(&config.label))
+                    .layout(|l| l.direction(LayoutDirection::TopToBottom).padding(config.padding / 2))
+                    .children(|ui| {
+                        let count = matches.len();
+                        for (position, &index) in matches.iter().enumerate() {
+                            let above = match position {
+                                0 => input_id.clone(),
+                                _ => suggestion_id(matches[position - 1]),
+                            };
+                            let below = suggestion_id(matches[(position + 1).min(count - 1)]);
+                            let mut row = ui
+                                .element()
+                                .id(suggestion_id(index))
+                                .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+                                .corner_radius(config.corner_radius / 2.0)
+                                .accessibility(|a| a
+                                    .role(AccessibilityRole::ListBoxOption)
+                                    .label(&config.suggestions[index])
+                                    .position_in_set(position as u32 + 1, count as u32)
+                                    .focus_up(above)
+                                    .focus_down(below)
+                                )
+                                .layout(|l| l.padding(config.padding));
+                            let mut states = StyleStates::default();
+                            states
+                                .hover(|s| s.background_color(config.highlight_color))
+                                .focused(|s| s.background_color(config.highlight_color));
+                            row.style_states = Some(states);
+                            row.children(|ui| {
+                                if ui.just_pressed() {
+                                    picked = Some(index);
+                                }
+                                ui.text(&config.suggestions[index], |t| { text_config(t, config.text_color); t });
+                            });
+                        }
+                    });
+            });
+
+        if let Some(index) = removed {
+            chips.remove(index);
+            changed = true;
+        }
+        if let Some(index) = picked {
+            changed |= commit(chips, &config.suggestions[index]);
+            self.set_text_value(input_id.clone(), "");
+            refocus_input = true;
+        }
+        if refocus_input {
+            self.set_focus(input_id);
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::TextInputAction;
+    use crate::math::{Dimensions, Vector2};
+    use crate::Ply;
+
+    #[test]
+    fn test_chips_commit_on_comma_enter_and_suggestion() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let mut chips = vec!["rust".to_string()];
+        let mut frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            let changed = ui.chips_input("tags", &mut chips, |c| c.suggestions(&["rust", "ruby", "go"]));
+            ui.eval();
+            (changed, chips.clone())
+        };
+        let input = Id::new("tags").child("input");
+
+        frame(&mut ply);
+        ply.set_focus(input.clone());
+
+        // A comma commits what is before it; repeats are ignored
+        for ch in "wasm, rust,ga".chars() {
+            ply.context.process_text_input_char(ch);
+        }
+        assert_eq!(frame(&mut ply), (true, vec!["rust".to_string(), "wasm".to_string()]));
+        assert_eq!(ply.get_text_value(input.clone()), "ga");
+
+        // Enter commits the rest
+        ply.context.process_text_input_action(TextInputAction::Submit);
+        assert_eq!(frame(&mut ply).1.last().map(String::as_str), Some("ga"));
+        assert_eq!(ply.get_text_value(input.clone()), "");
+
+        // Backspace in the empty field removes the last chip
+        ply.context.process_text_input_action(TextInputAction::Backspace);
+        assert_eq!(frame(&mut ply), (true, vec!["rust".to_string(), "wasm".to_string()]));
+
+        // Suggestions skip existing chips; clicking one adds it
+        ply.set_text_value(input.clone(), "ru");
+        frame(&mut ply);
+        let ruby = Id::new("tags").child_index("suggestion", 1);
+        assert!(ply.context.get_element_data(Id::new("tags").child_index("suggestion", 0)).is_none());
+        let bounds = ply.context.get_element_data(ruby).unwrap();
+        ply.context.set_pointer_state(Vector2::new(bounds.x + 1.0, bounds.y + 1.0), true);
+        let (changed, chips) = frame(&mut ply);
+        assert!(changed);
+        assert_eq!(chips.last().map(String::as_str), Some("ruby"));
+        assert_eq!(ply.context.focused_element_id, input.id);
+    }
+}
@@ -0,0 +1,200 @@
+//! Clipboard access beyond plain text: reading images and copying rich text.
+//!
+//! Images and HTML go through the system clipboard on desktop platforms.
+//! Elsewhere [`get_image`] returns `None` and [`set_html`] copies only the
+//! plain text fallback.
+//!
+//! ```ignore
+//! ui.element()
+//!     .id("canvas")
+//!     .on_paste_image(|image| pasted = Some(image.to_texture()))
+//!     .empty();
+//! ```
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use std::cell::RefCell;
+
+use macroquad::prelude::Texture2D;
+
+/// An image read from the clipboard, as tightly packed RGBA8 pixels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardImage {
+    pub width: u16,
+    pub height: u16,
+    /// `width * height * 4` bytes, row by row from the top.
+    pub rgba: Vec<u8>,
+}
+
+impl ClipboardImage {
+    /// Uploads the pixels into a new texture.
+    pub fn to_texture(&self) -> Texture2D {
+        Texture2D::from_rgba8(self.width, self.height, &self.rgba)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+thread_local! {
+    // Kept for the whole run: on Linux the clipboard content is lost
+    // as soon as the handle that set it is dropped.
+    static CLIPBOARD: RefCell<Option<arboard::Clipboard>> = RefCell::new(arboard::Clipboard::new().ok());
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+fn with_clipboard<R>(f: impl FnOnce(&mut arboard::Clipboard) -> Option<R>) -> Option<R> {
+    CLIPBOARD.with(|clipboard| clipboard.borrow_mut().as_mut().and_then(f))
+}
+
+/// Returns the image on the clipboard, if there is one.
+pub fn get_image() -> Option<ClipboardImage> {
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    {
+        let image = with_clipboard(|clipboard| clipboard.get_image().ok())?;
+        Some(ClipboardImage {
+            width: u16::try_from(image.width).ok()?,
+            height: u16::try_from(image.height).ok()?,
+            rgba: image.bytes.into_owned(),
+        })
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+/// Puts an image on the clipboard. Returns false where images aren't supported.
+pub fn set_image(image: &ClipboardImage) -> bool {
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    {
+        with_clipboard(|clipboard| {
+            clipboard
+                .set_image(arboard::ImageData {
+                    width: image.width as usize,
+                    height: image.height as usize,
+                    bytes: std::borrow::Cow::Borrowed(&image.rgba),
+                })
+                .ok()
+        })
+        .is_some()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        let _ = image;
+        false
+    }
+}
+
+/// Copies `html` with `plain` as the text fallback. Where HTML isn't
+/// supported only `plain` is copied.
+pub fn set_html(html: &str, plain: &str) {
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    if with_clipboard(|clipboard| clipboard.set_html(html, Some(plain)).ok()).is_some() {
+        return;
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    let _ = html;
+    macroquad::miniquad::window::clipboard_set(plain);
+}
+
+/// Converts styled markup into HTML.
+///
+/// `bold`, `italic`, `color` and `link` map to their HTML equivalents.
+/// Other styles are animations or effects with no HTML form, so their text
+/// is kept unstyled.
+#[cfg(feature = "text-styling")]
+pub fn styled_to_html(markup: &str) -> String {
+    use crate::text_input::styling::{styled_chars, write_styled_chars};
+
+    let chars = styled_chars(markup);
+    let mut html = String::new();
+    write_styled_chars(
+        &chars,
+        &mut html,
+        |out, tag| {
+            let (cmd, value) = style_command(tag);
+            match cmd {
+                "bold" => out.push_str("<b>"),
+                "italic" => out.push_str("<i>"),
+                "color" => {
+                    out.push_str("<span style=\"color:");
+                    out.push_str(&escape_html(&css_color(value)));
+                    out.push_str("\">");
+                }
+                "link" => {
+                    out.push_str("<a href=\"");
+                    out.push_str(&escape_html(value));
+                    out.push_str("\">");
+                }
+                _ => out.push_str("<span>"),
+            }
+        },
+        |out, tag| match style_command(tag).0 {
+            "bold" => out.push_str("</b>"),
+            "italic" => out.push_str("</i>"),
+            "link" => out.push_str("</a>"),
+            _ => out.push_str("</span>"),
+        },
+        |out, ch| match ch {
+            '\n' => out.push_str("<br>"),
+            _ => push_escaped_html(out, ch),
+        },
+    );
+    html
+}
+
+/// Splits a style like `color=red` into its command and value.
+#[cfg(feature = "text-styling")]
+fn style_command(tag: &str) -> (&str, &str) {
+    tag.split_once('=').unwrap_or((tag, ""))
+}
+
+/// Converts a markup color (`red`, `#ff0000`, `(255,0,0)`) into a CSS color.
+#[cfg(feature = "text-styling")]
+fn css_color(value: &str) -> String {
+    let value = value.trim();
+    if value.starts_with('(') && value.ends_with(')') {
+        format!("rgb{value}")
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(feature = "text-styling")]
+fn push_escaped_html(out: &mut String, ch: char) {
+    match ch {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        '"' => out.push_str("&quot;"),
+        _ => out.push(ch),
+    }
+}
+
+#[cfg(feature = "text-styling")]
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        push_escaped_html(&mut out, ch);
+    }
+    out
+}
+
+#[cfg(all(test, feature = "text-styling"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_styled_to_html() {
+        assert_eq!(styled_to_html("plain <text>"), "plain &lt;text&gt;");
+        assert_eq!(
+            styled_to_html("{bold|hi {italic|there}}"),
+            "<b>hi <i>there</i></b>"
+        );
+        assert_eq!(
+            styled_to_html("{color=(255,0,0)|red}\n{link=https://a.b/?x=1&y=2|go}"),
+            "<span style=\"color:rgb(255,0,0)\">red</span><br><a href=\"https://a.b/?x=1&amp;y=2\">go</a>"
+        );
+        assert_eq!(styled_to_html("{wave|a\\{b}"), "<span>a{b</span>");
+    }
+}
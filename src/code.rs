@@ -0,0 +1,342 @@
+//! Syntax-highlighted code blocks (feature: `syntax-highlighting`).
+//!
+//! Source is tokenized with syntect and turned into text-styling markup,
+//! so each line renders through the regular text path.
+//!
+//! ```ignore
+//! ui.code(source, "rust", |c| c.theme("base16-ocean.dark").line_numbers());
+//! ```
+
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+use rustc_hash::FxHasher;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+use crate::color::Color;
+use crate::id::Id;
+use crate::layout::{LayoutDirection, Sizing};
+use crate::renderer::FontAsset;
+use crate::text::WrapMode;
+use crate::text_input::styling::escape_str;
+use crate::Ui;
+
+/// Theme used when none is set.
+pub const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+/// Builder for configuring a code block via closure.
+#[derive(Debug, Clone)]
+pub struct CodeBuilder {
+    pub(crate) id: Option<Id>,
+    pub(crate) theme: String,
+    pub(crate) line_numbers: bool,
+    pub(crate) font_asset: Option<&'static FontAsset>,
+    pub(crate) font_size: u16,
+    pub(crate) padding: u16,
+    pub(crate) width: Sizing,
+    pub(crate) height: Sizing,
+    pub(crate) background_color: Option<Color>,
+    pub(crate) line_number_color: Color,
+}
+
+impl Default for CodeBuilder {
+    fn default() -> Self {
+        Self {
+            id: None,
+            theme: DEFAULT_THEME.to_string(),
+            line_numbers: false,
+            font_asset: None,
+            font_size: 16,
+            padding: 8,
+            width: Sizing::Grow(0.0, f32::MAX, 1.0),
+            height: Sizing::Fit(0.0, f32::MAX),
+            background_color: None,
+            line_number_color: Color::rgba(128.0, 128.0, 128.0, 255.0),
+        }
+    }
+}
+
+impl CodeBuilder {
+    /// Sets the element ID, needed to keep horizontal scroll state stable.
+    #[inline]
+    pub fn id(&mut self, id: impl Into<Id>) -> &mut Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the syntect theme by name, e.g. `"InspiredGitHub"` or `"Solarized (dark)"`.
+    ///
+    /// Unknown names fall back to [`DEFAULT_THEME`].
+    #[inline]
+    pub fn theme(&mut self, name: &str) -> &mut Self {
+        self.theme = name.to_string();
+        self
+    }
+
+    /// Shows a line number gutter.
+    #[inline]
+    pub fn line_numbers(&mut self) -> &mut Self {
+        self.line_numbers = true;
+        self
+    }
+
+    /// Sets the font. Monospace fonts are strongly recommended.
+    #[inline]
+    pub fn font(&mut self, asset: &'static FontAsset) -> &mut Self {
+        self.font_asset = Some(asset);
+        self
+    }
+
+    /// Sets the font size.
+    #[inline]
+    pub fn font_size(&mut self, size: u16) -> &mut Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Sets the padding around the code.
+    #[inline]
+    pub fn padding(&mut self, padding: u16) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the width of the code block. Defaults to `grow!()`.
+    #[inline]
+    pub fn width(&mut self, width: Sizing) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the height of the code block. Defaults to `fit!()`.
+    #[inline]
+    pub fn height(&mut self, height: Sizing) -> &mut Self {
+        self.height = height;
+        self
+    }
+
+    /// Overrides the theme's background color.
+    #[inline]
+    pub fn background_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.background_color = Some(color.into());
+        self
+    }
+
+    /// Sets the color of the line number gutter.
+    #[inline]
+    pub fn line_number_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.line_number_color = color.into();
+        self
+    }
+}
+
+/// Highlighted source, one text-styling markup string per line.
+#[derive(Debug)]
+pub(crate) struct HighlightedCode {
+    pub(crate) lines: Vec<String>,
+    pub(crate) foreground: Color,
+    pub(crate) background: Color,
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+    THEME_SET.get_or_init(ThemeSet::load_defaults)
+}
+
+fn to_ply_color(color: syntect::highlighting::Color) -> Color {
+    Color::u_rgba(color.r, color.g, color.b, color.a)
+}
+
+/// A code block's highlighted source, kept between frames while the source,
+/// language and theme hash to the same `key`.
+struct CachedHighlight {
+    key: u64,
+    code: Rc<HighlightedCode>,
+}
+
+pub(crate) fn highlight(source: &str, language: &str, theme: &str) -> HighlightedCode {
+    let syntaxes = syntax_set();
+    let themes = theme_set();
+    let syntax = syntaxes
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let theme = themes
+        .themes
+        .get(theme)
+        .unwrap_or_else(|| &themes.themes[DEFAULT_THEME]);
+
+    let foreground = theme
+        .settings
+        .foreground
+        .map(to_ply_color)
+        .unwrap_or(Color::rgba(255.0, 255.0, 255.0, 255.0));
+    let background = theme
+        .settings
+        .background
+        .map(to_ply_color)
+        .unwrap_or(Color::rgba(0.0, 0.0, 0.0, 255.0));
+
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut lines = Vec::new();
+    for line in LinesWithEndings::from(source) {
+        let mut markup = String::new();
+        match highlighter.highlight_line(line, syntaxes) {
+            Ok(ranges) => {
+                for (style, token) in ranges {
+                    let token = token.trim_end_matches(['\n', '\r']);
+                    if token.is_empty() {
+                        continue;
+                    }
+                    let fg = style.foreground;
+                    markup.push_str(&format!(
+                        "{{color=#{:02X}{:02X}{:02X}|{}}}",
+                        fg.r,
+                        fg.g,
+                        fg.b,
+                        escape_str(token)
+                    ));
+                }
+            }
+            Err(_) => markup.push_str(&escape_str(line.trim_end_matches(['\n', '\r']))),
+        }
+        lines.push(markup);
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+
+    HighlightedCode {
+        lines,
+        foreground,
+        background,
+    }
+}
+
+fn code_line<CustomElementData: Clone + Default + std::fmt::Debug>(
+    ui: &mut Ui<'_, CustomElementData>,
+    text: &str,
+    color: Color,
+    font_asset: Option<&'static FontAsset>,
+    font_size: u16,
+) {
+    // Empty lines still need a line of height
+    let text = if text.is_empty() { " " } else { text };
+    ui.text(text, |t| {
+        if let Some(font) = font_asset {
+            t.font(font);
+        }
+        t.font_size(font_size).color(color).wrap_mode(WrapMode::None)
+    });
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Adds a syntax-highlighted, horizontally scrollable code block.
+    ///
+    /// `language` is a syntect token such as `"rust"`, `"rs"`, `"py"` or `"json"`.
+    /// Unknown languages render as plain text.
+    ///
+    /// ```ignore
+    /// ui.code(SOURCE, "rust", |c| c.id("snippet").line_numbers().font(&MONO));
+    /// ```
+    pub fn code(
+        &mut self,
+        source: &str,
+        language: &str,
+        config_fn: impl for<'a> FnOnce(&'a mut CodeBuilder) -> &'a mut CodeBuilder,
+    ) -> Id {
+        let mut config = CodeBuilder::default();
+        config_fn(&mut config);
+        let mut hasher = FxHasher::default();
+        (source, language, &config.theme).hash(&mut hasher);
+        let key = hasher.finish();
+        // Blocks without an id are cached under their source's hash instead
+        let slot = config.id.as_ref().map_or(key as u32, |id| id.id);
+        let cached = self.ply.component_states.widget_state::<Option<CachedHighlight>>(slot, || None);
+        let highlighted = match cached {
+            Some(cached) if cached.key == key => cached.code.clone(),
+            _ => {
+                let code = Rc::new(highlight(source, language, &config.theme));
+                *cached = Some(CachedHighlight { key, code: code.clone() });
+                code
+            }
+        };
+
+        let CodeBuilder {
+            id,
+            line_numbers,
+            font_asset,
+            font_size,
+            padding,
+            width,
+            height,
+            background_color,
+            line_number_color,
+            ..
+        } = config;
+        let foreground = highlighted.foreground;
+
+        let mut element = self.element();
+        if let Some(id) = id {
+            element = element.id(id);
+        }
+        element
+            .width(width)
+            .height(height)
+            .background_color(background_color.unwrap_or(highlighted.background))
+            .overflow(|o| o.scroll_x())
+            .layout(|l| l
+                .direction(LayoutDirection::LeftToRight)
+                .padding(padding)
+                .gap(font_size)
+            )
+            .children(|ui| {
+                if line_numbers {
+                    ui.element()
+                        .layout(|l| l.direction(LayoutDirection::TopToBottom))
+                        .children(|ui| {
+                            for number in 1..=highlighted.lines.len() {
+                                code_line(ui, &number.to_string(), line_number_color, font_asset, font_size);
+                            }
+                        });
+                }
+
+                ui.element()
+                    .layout(|l| l.direction(LayoutDirection::TopToBottom))
+                    .children(|ui| {
+                        for line in &highlighted.lines {
+                            code_line(ui, line, foreground, font_asset, font_size);
+                        }
+                    });
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_emits_color_markup_per_line() {
+        let code = highlight("fn main() {}\nlet x = 1;", "rust", DEFAULT_THEME);
+        assert_eq!(code.lines.len(), 2);
+        assert!(code.lines[0].starts_with("{color=#"));
+        // Braces in the source must be escaped so they aren't read as markup
+        assert!(code.lines[0].contains("\\{"));
+    }
+
+    #[test]
+    fn test_highlight_unknown_language_falls_back_to_plain_text() {
+        let code = highlight("hello", "not-a-language", "not-a-theme");
+        assert_eq!(code.lines.len(), 1);
+        assert!(code.lines[0].contains("hello"));
+    }
+}
@@ -1,5 +1,6 @@
 /// An RGBA color with floating-point components (0.0–255.0 range).
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Color {
     pub r: f32,
@@ -0,0 +1,149 @@
+//! Components: reusable widgets with props, per-instance state and scoped ids.
+//!
+//! ```ignore
+//! struct Counter<'a> {
+//!     label: &'a str,
+//! }
+//!
+//! impl Component for Counter<'_> {
+//!     type State = u32;
+//!
+//!     fn view(&mut self, ui: &mut Ui, id: &Id, count: &mut u32) {
+//!         ui.element().id(id.clone()).children(|ui| {
+//!             if ui.just_pressed() {
+//!                 *count += 1;
+//!             }
+//!             ui.text(&format!("{}: {}", self.label, count), |t| t.font_size(16));
+//!         });
+//!     }
+//! }
+//!
+//! ui.component("apples", Counter { label: "Apples" });
+//! ui.component("pears", Counter { label: "Pears" });
+//! ```
+//!
+//! Props are the component's fields. State is created with `Default` the
+//! first time an id is drawn, and dropped when a frame goes by without
+//! drawing that id. Inner elements should use [`Id::child`] so two instances
+//! never share an id.
+
+use std::any::{Any, TypeId};
+
+use rustc_hash::FxHashMap;
+
+use crate::id::Id;
+use crate::Ui;
+
+/// A reusable widget, drawn with [`Ui::component`].
+pub trait Component<CustomElementData: Clone + Default + std::fmt::Debug = ()> {
+    /// State kept between frames for each instance.
+    type State: Default + 'static;
+
+    /// Declares the component's elements. `id` is the instance's id; derive
+    /// the ids of inner elements from it with [`Id::child`].
+    fn view(&mut self, ui: &mut Ui<'_, CustomElementData>, id: &Id, state: &mut Self::State);
+}
+
+struct ComponentState {
+    state: Box<dyn Any>,
+    /// Whether the component was drawn since the last sweep.
+    drawn: bool,
+}
+
+/// States of the components drawn recently, kept by [`Ply`](crate::Ply).
+#[derive(Default)]
+pub(crate) struct ComponentStates {
+    states: FxHashMap<(u32, TypeId), ComponentState>,
+}
+
+impl ComponentStates {
+    /// Drops the states of components not drawn last frame. Called from `begin()`.
+    pub(crate) fn sweep(&mut self) {
+        self.states.retain(|_, entry| std::mem::take(&mut entry.drawn));
+    }
+
+    /// State of a built-in widget drawn with `id`, made with `init` the first
+    /// time. Like a component's, it is dropped once a frame goes by without it.
+    pub(crate) fn widget_state<T: 'static>(&mut self, id: u32, init: impl FnOnce() -> T) -> &mut T {
+        let entry = self
+            .states
+            .entry((id, TypeId::of::<T>()))
+            .or_insert_with(|| ComponentState { state: Box::new(init()), drawn: true });
+        entry.drawn = true;
+        entry.state.downcast_mut::<T>().expect("widget states are keyed by their type")
+    }
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Draws a component with its state for `id`.
+    ///
+    /// Accepts an `Id` or a `&'static str` label. Components of different
+    /// types may share an id without sharing state.
+    pub fn component<T: Component<CustomElementData>>(&mut self, id: impl Into<Id>, mut component: T) {
+        let id = id.into();
+        let key = (id.id, TypeId::of::<T::State>());
+        // Taken out while drawing, so nested components can reach the map
+        let mut state: Box<dyn Any> = match self.ply.component_states.states.remove(&key) {
+            Some(entry) => entry.state,
+            None => Box::new(T::State::default()),
+        };
+        if let Some(state) = state.downcast_mut::<T::State>() {
+            component.view(self, &id, state);
+        }
+        self.ply.component_states.states.insert(key, ComponentState { state, drawn: true });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::Sizing;
+    use crate::math::{Dimensions, Vector2};
+    use crate::Ply;
+
+    struct Counter<'a> {
+        count: &'a mut u32,
+    }
+
+    impl Component for Counter<'_> {
+        type State = u32;
+
+        fn view(&mut self, ui: &mut Ui<'_>, id: &Id, count: &mut u32) {
+            ui.element()
+                .id(id.child("button"))
+                .width(Sizing::Fixed(50.0))
+                .height(Sizing::Fixed(50.0))
+                .children(|ui| {
+                    if ui.just_pressed() {
+                        *count += 1;
+                    }
+                });
+            *self.count = *count;
+        }
+    }
+
+    #[test]
+    fn test_component_state_is_per_id_and_dropped_when_not_drawn() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(100.0, 100.0));
+        let frame = |ply: &mut Ply<()>, draw_first: bool| {
+            let (mut first, mut second) = (0, 0);
+            let mut ui = ply.begin();
+            if draw_first {
+                ui.component("first", Counter { count: &mut first });
+            }
+            ui.component("second", Counter { count: &mut second });
+            ui.eval();
+            (first, second)
+        };
+
+        frame(&mut ply, true);
+        ply.context.set_pointer_state(Vector2::new(25.0, 25.0), true);
+        assert_eq!(frame(&mut ply, true), (1, 0));
+        ply.context.set_pointer_state(Vector2::new(25.0, 25.0), false);
+        assert_eq!(frame(&mut ply, true), (1, 0));
+
+        // Skipped for a frame: the state is dropped
+        frame(&mut ply, false);
+        assert_eq!(frame(&mut ply, true), (0, 0));
+    }
+}
@@ -0,0 +1,168 @@
+//! Problems found while building a frame, reported instead of silently
+//! papered over.
+//!
+//! ```ignore
+//! ply.set_diagnostics_handler(|diagnostic| eprintln!("ply: {diagnostic}"));
+//! // or, after eval()/show():
+//! for diagnostic in ply.diagnostics() {
+//!     eprintln!("ply: {diagnostic}");
+//! }
+//! ```
+//!
+//! The list describes the last evaluated frame and is rebuilt every frame, so
+//! a problem that persists is reported once per frame.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::panic::Location;
+
+thread_local! {
+    /// Problems found outside a frame's layout, like shader compiles during
+    /// rendering, waiting for the next `eval()` on this thread to pick them up.
+    static PENDING: RefCell<Vec<Diagnostic>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Something that went wrong while declaring or laying out a frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// Two elements were declared with the same id. The later one doesn't get
+    /// its own bounding box or handlers. The string ids only differ when two
+    /// labels hash the same. Locations are known for ids set with `.id()`.
+    DuplicateId {
+        id: String,
+        location: Option<&'static Location<'static>>,
+        first_id: String,
+        first_location: Option<&'static Location<'static>>,
+    },
+    /// More elements were declared than `max_element_count` allows. Nothing is
+    /// laid out or drawn for the frame.
+    MaxElementsExceeded { max: u32 },
+    /// Text was declared with no measure function set, so it has no size.
+    MissingMeasureFunction,
+    /// Elements were still open when the frame ended.
+    UnbalancedElements { unclosed: usize },
+    /// A `ShaderAsset::Path` file couldn't be read. The last version that
+    /// loaded is used, or a pass-through shader if none did.
+    ShaderLoadFailed { path: String, error: String },
+    /// A shader failed to compile. The last version that compiled is used,
+    /// or a pass-through shader if none did. Reported once per source, in the
+    /// frame after it was drawn.
+    ShaderCompileFailed { name: String, error: String },
+    /// A shader uniform was set with a type or array length that doesn't
+    /// match its GLSL declaration, so it was left unset.
+    ShaderUniformMismatch { shader: String, uniform: String, declared: String, given: String },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::DuplicateId { id, location, first_id, first_location } => {
+                write!(f, "duplicate element id \"{id}\"")?;
+                if let Some(location) = location {
+                    write!(f, " at {location}")?;
+                }
+                write!(f, ", first declared as \"{first_id}\"")?;
+                if let Some(location) = first_location {
+                    write!(f, " at {location}")?;
+                }
+                Ok(())
+            }
+            Diagnostic::MaxElementsExceeded { max } => {
+                write!(f, "more than {max} elements declared, raise max_element_count")
+            }
+            Diagnostic::MissingMeasureFunction => write!(f, "text declared without a measure function"),
+            Diagnostic::UnbalancedElements { unclosed } => {
+                write!(f, "{unclosed} element(s) left open at the end of the frame")
+            }
+            Diagnostic::ShaderLoadFailed { path, error } => write!(f, "failed to read shader \"{path}\": {error}"),
+            Diagnostic::ShaderCompileFailed { name, error } => write!(f, "failed to compile shader \"{name}\": {error}"),
+            Diagnostic::ShaderUniformMismatch { shader, uniform, declared, given } => {
+                write!(f, "uniform \"{uniform}\" of shader \"{shader}\" is declared {declared} but was given {given}")
+            }
+        }
+    }
+}
+
+pub(crate) type DiagnosticsHandler = Box<dyn FnMut(&Diagnostic)>;
+
+/// Queues a diagnostic for the next evaluated frame.
+pub(crate) fn report(diagnostic: Diagnostic) {
+    PENDING.with(|pending| pending.borrow_mut().push(diagnostic));
+}
+
+/// Takes the diagnostics queued since the last frame.
+pub(crate) fn take_pending() -> Vec<Diagnostic> {
+    PENDING.with(|pending| std::mem::take(&mut *pending.borrow_mut()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::fixed;
+    use crate::math::Dimensions;
+    use crate::render_commands::RenderCommandConfig;
+    use crate::Ply;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_diagnostics_report_duplicates_limits_and_unbalanced_frames() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let reported = Rc::new(RefCell::new(Vec::new()));
+        let sink = reported.clone();
+        ply.set_diagnostics_handler(move |diagnostic| sink.borrow_mut().push(diagnostic.clone()));
+
+        // Headless instances have no measure function until one is set
+        let mut ui = ply.begin();
+        ui.element().id("row").width(fixed!(50.0)).height(fixed!(20.0)).empty();
+        ui.element().id("row").width(fixed!(50.0)).height(fixed!(20.0)).empty();
+        ui.text("Hi", |t| t.font_size(16));
+        ui.eval();
+        let [Diagnostic::DuplicateId { id, location, first_id, first_location }, Diagnostic::MissingMeasureFunction] =
+            ply.diagnostics()
+        else {
+            panic!("unexpected diagnostics: {:?}", ply.diagnostics());
+        };
+        assert_eq!((id.as_str(), first_id.as_str()), ("row", "row"));
+        let (location, first_location) = (location.unwrap(), first_location.unwrap());
+        assert_eq!(location.file(), file!());
+        assert_eq!(location.line(), first_location.line() + 1);
+        assert_eq!(*reported.borrow(), ply.diagnostics());
+
+        // A clean frame clears the list
+        ply.set_measure_text_function(|text, _| Dimensions::new(text.len() as f32 * 8.0, 16.0));
+        let mut ui = ply.begin();
+        ui.text("Hi", |t| t.font_size(16));
+        ui.eval();
+        assert!(ply.diagnostics().is_empty());
+
+        let mut ui = ply.begin();
+        ui.ply.context.open_element();
+        ui.eval();
+        assert_eq!(ply.diagnostics(), &[Diagnostic::UnbalancedElements { unclosed: 1 }]);
+
+        ply.max_element_count(4);
+        let mut ui = ply.begin();
+        for _ in 0..8 {
+            ui.element().width(fixed!(10.0)).height(fixed!(10.0)).background_color(0x808080).empty();
+        }
+        let items = ui.eval();
+        assert_eq!(ply.diagnostics(), &[Diagnostic::MaxElementsExceeded { max: 4 }]);
+        assert!(items.is_empty());
+        assert_eq!(reported.borrow().len(), 4);
+
+        // Debug mode outlines both elements sharing an id
+        ply.max_element_count(8192);
+        ply.set_debug_mode(true);
+        let mut ui = ply.begin();
+        ui.element().id("row").width(fixed!(50.0)).height(fixed!(20.0)).empty();
+        ui.element().id("row").width(fixed!(50.0)).height(fixed!(20.0)).empty();
+        let items = ui.eval();
+        let outlines = items
+            .iter()
+            .filter(|item| matches!(&item.config, RenderCommandConfig::Border(border) if border.color == Color::rgba(255.0, 40.0, 40.0, 255.0)))
+            .count();
+        assert_eq!(outlines, 2);
+    }
+}
@@ -0,0 +1,357 @@
+//! Non-blocking file open/save dialogs.
+//!
+//! Same fire-and-poll pattern as [`net`](crate::net): firing a dialog is
+//! idempotent by ID, and the result is polled every frame through a handle.
+//!
+//! ```ignore
+//! if ui.just_pressed() {
+//!     dialogs::open_file("import", &[("Images", &["png", "jpg"])]);
+//! }
+//! if let Some(dialog) = dialogs::dialog("import") {
+//!     if let Some(result) = dialog.result() {
+//!         if let Ok(Some(file)) = result {
+//!             import(&file.name, &file.bytes);
+//!         }
+//!         dialog.close();
+//!     }
+//! }
+//! ```
+//!
+//! Native builds use the system dialog. On the web, opening goes through a
+//! hidden `<input type=file>` and saving downloads the file.
+
+use rustc_hash::FxHashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+
+#[cfg(target_arch = "wasm32")]
+use sapp_jsutils::JsObject;
+
+/// Global manager for all open dialogs.
+#[allow(private_interfaces)]
+pub static DIALOG_MANAGER: LazyLock<Mutex<DialogManager>> =
+    LazyLock::new(|| Mutex::new(DialogManager::new()));
+
+/// A file picked in an open dialog, or written through a save dialog.
+#[derive(Debug, Clone)]
+pub struct DialogFile {
+    /// File name without directories.
+    pub name: String,
+    /// Full path. `None` on the web, where files have no paths.
+    pub path: Option<PathBuf>,
+    /// Contents of an opened file. Empty for saved files.
+    pub bytes: Vec<u8>,
+}
+
+type DialogResult = Result<Option<DialogFile>, String>;
+
+/// Internal state for a tracked dialog.
+pub(crate) enum DialogState {
+    /// Still open.
+    Pending(PendingDialog),
+    /// Closed with a file, or `None` when canceled.
+    Done(Option<Arc<DialogFile>>),
+    /// The dialog couldn't be shown or the file couldn't be read or written.
+    Error(String),
+}
+
+pub(crate) struct TrackedDialog {
+    frames_not_accessed: usize,
+    state: DialogState,
+}
+
+pub(crate) struct DialogManager {
+    pub(crate) dialogs: FxHashMap<u64, TrackedDialog>,
+    /// Number of frames after which an unused closed dialog is evicted.
+    pub max_frames_not_used: usize,
+}
+
+impl DialogManager {
+    fn new() -> Self {
+        Self {
+            dialogs: FxHashMap::default(),
+            max_frames_not_used: 60,
+        }
+    }
+
+    /// Frame-based eviction. Called once per frame from `eval()`.
+    pub fn clean(&mut self) {
+        self.dialogs.retain(|_, entry| {
+            entry.poll();
+            match entry.state {
+                DialogState::Pending(_) => true, // never evict
+                _ => {
+                    entry.frames_not_accessed += 1;
+                    entry.frames_not_accessed <= self.max_frames_not_used
+                }
+            }
+        });
+    }
+}
+
+impl TrackedDialog {
+    /// Try to transition Pending → Done/Error.
+    fn poll(&mut self) {
+        if let DialogState::Pending(pending) = &mut self.state {
+            if let Some(result) = pending.try_recv() {
+                self.state = match result {
+                    Ok(file) => DialogState::Done(file.map(Arc::new)),
+                    Err(e) => DialogState::Error(e),
+                };
+            }
+        }
+    }
+}
+
+/// Abstraction over the pending receive mechanism.
+pub(crate) struct PendingDialog {
+    #[cfg(not(target_arch = "wasm32"))]
+    rx: std::sync::mpsc::Receiver<DialogResult>,
+    #[cfg(target_arch = "wasm32")]
+    op_id: i32,
+}
+
+impl PendingDialog {
+    /// Try to receive the dialog result. Returns None while the dialog is open.
+    fn try_recv(&mut self) -> Option<DialogResult> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            match self.rx.try_recv() {
+                Ok(result) => Some(result),
+                Err(std::sync::mpsc::TryRecvError::Empty) => None,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    Some(Err("Dialog thread exited without a result".to_owned()))
+                }
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let result = unsafe { ply_dialog_try_recv(self.op_id) };
+            if result.is_nil() {
+                return None;
+            }
+            match result.field_u32("status") {
+                0 => {
+                    let mut error = String::new();
+                    result.field("error").to_string(&mut error);
+                    Some(Err(error))
+                }
+                2 => Some(Ok(None)),
+                _ => {
+                    let mut name = String::new();
+                    result.field("name").to_string(&mut name);
+                    let mut bytes = Vec::new();
+                    if result.have_field("data") {
+                        result.field("data").to_byte_buffer(&mut bytes);
+                    }
+                    Some(Ok(Some(DialogFile { name, path: None, bytes })))
+                }
+            }
+        }
+    }
+}
+
+/// A thin handle to a dialog tracked by the global manager.
+///
+/// Does not own the data — just a key for lookups.
+pub struct Dialog {
+    pub(crate) id: u64,
+}
+
+impl Dialog {
+    /// Check whether the dialog has closed.
+    ///
+    /// - `None`: still open
+    /// - `Some(Ok(Some(file)))`: a file was opened or saved
+    /// - `Some(Ok(None))`: canceled
+    /// - `Some(Err(e))`: the dialog or file access failed
+    pub fn result(&self) -> Option<Result<Option<Arc<DialogFile>>, String>> {
+        let mut mgr = DIALOG_MANAGER.lock().unwrap();
+        let entry = mgr.dialogs.get_mut(&self.id)?;
+        entry.frames_not_accessed = 0;
+        entry.poll();
+
+        match &entry.state {
+            DialogState::Pending(_) => None,
+            DialogState::Done(file) => Some(Ok(file.clone())),
+            DialogState::Error(e) => Some(Err(e.clone())),
+        }
+    }
+
+    /// Forget the dialog so its ID can be fired again. Consumes the handle.
+    ///
+    /// A native dialog that is still open stays on screen, but its result is dropped.
+    pub fn close(self) {
+        let mut mgr = DIALOG_MANAGER.lock().unwrap();
+        mgr.dialogs.remove(&self.id);
+    }
+}
+
+fn hash_id(id: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = rustc_hash::FxHasher::default();
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn owned_filters(filters: &[(&str, &[&str])]) -> Vec<(String, Vec<String>)> {
+    filters
+        .iter()
+        .map(|(name, extensions)| {
+            let extensions = extensions
+                .iter()
+                .map(|ext| ext.trim_start_matches('.').to_owned())
+                .collect();
+            (name.to_string(), extensions)
+        })
+        .collect()
+}
+
+/// Register a dialog under `id` unless one exists, starting it with `start`.
+fn fire(id: &str, start: impl FnOnce() -> PendingDialog) {
+    let key = hash_id(id);
+    let mut mgr = DIALOG_MANAGER.lock().unwrap();
+
+    // Idempotent: don't reopen while a dialog with this ID exists
+    if mgr.dialogs.contains_key(&key) {
+        return;
+    }
+
+    mgr.dialogs.insert(
+        key,
+        TrackedDialog {
+            frames_not_accessed: 0,
+            state: DialogState::Pending(start()),
+        },
+    );
+}
+
+/// Run a dialog on a background thread so the render loop keeps going.
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_dialog(job: impl FnOnce() -> DialogResult + Send + 'static) -> PendingDialog {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(job());
+    });
+    PendingDialog { rx }
+}
+
+/// Show a file open dialog. Idempotent: won't reopen while a dialog with this ID exists.
+///
+/// `filters` are `(name, extensions)` pairs, like `("Images", &["png", "jpg"])`.
+/// The picked file is read in the background and returned with its contents.
+pub fn open_file(id: &str, filters: &[(&str, &[&str])]) {
+    let filters = owned_filters(filters);
+    fire(id, move || {
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        {
+            spawn_dialog(move || {
+                let mut dialog = rfd::AsyncFileDialog::new();
+                for (name, extensions) in &filters {
+                    dialog = dialog.add_filter(name, extensions.as_slice());
+                }
+                let Some(handle) = crate::jobs::block_on_native(dialog.pick_file()) else {
+                    return Ok(None);
+                };
+                let path = handle.path().to_path_buf();
+                let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
+                Ok(Some(DialogFile {
+                    name: handle.file_name(),
+                    path: Some(path),
+                    bytes,
+                }))
+            })
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let accept = filters
+                .iter()
+                .flat_map(|(_, extensions)| extensions.iter().map(|ext| format!(".{ext}")))
+                .collect::<Vec<_>>()
+                .join(",");
+            let op_id = unsafe { ply_dialog_open_file(JsObject::string(&accept)) };
+            PendingDialog { op_id }
+        }
+
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "windows",
+            target_arch = "wasm32"
+        )))]
+        {
+            let _ = filters;
+            spawn_dialog(|| Err("File dialogs are not supported on this platform".to_owned()))
+        }
+    });
+}
+
+/// Show a file save dialog and write `data` to the chosen file. Idempotent:
+/// won't reopen while a dialog with this ID exists.
+///
+/// `file_name` is the suggested name. On the web the file is downloaded
+/// under that name instead.
+pub fn save_file(id: &str, file_name: &str, data: Vec<u8>, filters: &[(&str, &[&str])]) {
+    let filters = owned_filters(filters);
+    let file_name = file_name.to_owned();
+    fire(id, move || {
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        {
+            spawn_dialog(move || {
+                let mut dialog = rfd::AsyncFileDialog::new().set_file_name(&file_name);
+                for (name, extensions) in &filters {
+                    dialog = dialog.add_filter(name, extensions.as_slice());
+                }
+                let Some(handle) = crate::jobs::block_on_native(dialog.save_file()) else {
+                    return Ok(None);
+                };
+                let path = handle.path().to_path_buf();
+                std::fs::write(&path, &data).map_err(|e| e.to_string())?;
+                Ok(Some(DialogFile {
+                    name: handle.file_name(),
+                    path: Some(path),
+                    bytes: Vec::new(),
+                }))
+            })
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = filters;
+            let op_id = unsafe {
+                ply_dialog_save_file(JsObject::string(&file_name), JsObject::buffer(&data))
+            };
+            PendingDialog { op_id }
+        }
+
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "windows",
+            target_arch = "wasm32"
+        )))]
+        {
+            let _ = (filters, file_name, data);
+            spawn_dialog(|| Err("File dialogs are not supported on this platform".to_owned()))
+        }
+    });
+}
+
+/// Get a handle to an existing dialog. Returns `None` if no such ID.
+pub fn dialog(id: &str) -> Option<Dialog> {
+    let key = hash_id(id);
+    let mut mgr = DIALOG_MANAGER.lock().unwrap();
+    let entry = mgr.dialogs.get_mut(&key)?;
+    entry.frames_not_accessed = 0;
+    Some(Dialog { id: key })
+}
+
+// WASM FFI
+#[cfg(target_arch = "wasm32")]
+extern "C" {
+    fn ply_dialog_open_file(accept: JsObject) -> i32;
+    fn ply_dialog_save_file(file_name: JsObject, data: JsObject) -> i32;
+    fn ply_dialog_try_recv(op_id: i32) -> JsObject;
+}
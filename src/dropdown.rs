@@ -0,0 +1,518 @@
+//! Dropdowns: a button that opens a floating, scrollable list of options.
+//!
+//! ```ignore
+//! let countries = ["Austria", "Belgium", "Croatia", "Denmark"];
+//! if ui.dropdown("country", &mut country, &countries, |d| d
+//!     .label("Country")
+//!     .placeholder("Pick a country")
+//!     .searchable()
+//! ) {
+//!     println!("Picked {}", countries[country]);
+//! }
+//! ```
+//!
+//! The list opens on click, Enter or Space and closes when an option is
+//! picked, on Escape, on a click outside it, or when Tab moves focus away.
+//! While open, the arrow keys move between options and Enter picks one.
+//! A searchable dropdown filters the options as you type; Enter in the
+//! search field picks the first match.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::accessibility::AccessibilityRole;
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::elements::Layer;
+use crate::id::Id;
+use crate::layout::{LayoutDirection, Sizing};
+use crate::math::Vector2;
+use crate::renderer::FontAsset;
+use crate::style::StyleStates;
+use crate::text::TextConfig;
+use crate::Ui;
+
+/// Per-dropdown state kept between frames.
+#[derive(Debug, Default, Clone)]
+struct DropdownState {
+    open: bool,
+    /// Option that had focus last frame, to scroll the list when it changes.
+    highlighted: Option<usize>,
+    /// Whether the button, search field or an option had focus last frame.
+    focus_inside: bool,
+    /// Set by Enter in the search field to pick the highlighted option.
+    submit: Rc<Cell<bool>>,
+}
+
+/// Builder for configuring a dropdown via closure.
+#[derive(Debug, Clone)]
+pub struct DropdownBuilder {
+    pub(crate) label: String,
+    pub(crate) placeholder: String,
+    pub(crate) searchable: bool,
+    pub(crate) search_placeholder: String,
+    pub(crate) disabled: bool,
+    pub(crate) font_asset: Option<&'static FontAsset>,
+    pub(crate) font_size: u16,
+    pub(crate) padding: u16,
+    pub(crate) width: Sizing,
+    pub(crate) max_height: f32,
+    pub(crate) corner_radius: f32,
+    pub(crate) background_color: Color,
+    pub(crate) list_color: Color,
+    pub(crate) highlight_color: Color,
+    pub(crate) text_color: Color,
+    pub(crate) placeholder_color: Color,
+}
+
+impl Default for DropdownBuilder {
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            placeholder: "Select".to_string(),
+            searchable: false,
+            search_placeholder: "Search".to_string(),
+            disabled: false,
+            font_asset: None,
+            font_size: 16,
+            padding: 8,
+            width: Sizing::Fixed(200.0),
+            max_height: 240.0,
+            corner_radius: 6.0,
+            background_color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+            list_color: Color::rgba(30.0, 30.0, 30.0, 255.0),
+            highlight_color: Color::rgba(58.0, 123.0, 213.0, 255.0),
+            text_color: Color::rgba(255.0, 255.0, 255.0, 255.0),
+            placeholder_color: Color::rgba(128.0, 128.0, 128.0, 255.0),
+        }
+    }
+}
+
+impl DropdownBuilder {
+    /// Sets the accessible name of the dropdown.
+    #[inline]
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// Sets the text shown while no valid option is selected. Defaults to `"Select"`.
+    #[inline]
+    pub fn placeholder(&mut self, text: &str) -> &mut Self {
+        self.placeholder = text.to_string();
+        self
+    }
+
+    /// Adds a search field at the top of the list that filters the options.
+    #[inline]
+    pub fn searchable(&mut self) -> &mut Self {
+        self.searchable = true;
+        self
+    }
+
+    /// Sets the placeholder of the search field. Defaults to `"Search"`.
+    #[inline]
+    pub fn search_placeholder(&mut self, text: &str) -> &mut Self {
+        self.search_placeholder = text.to_string();
+        self
+    }
+
+    /// Keeps the list closed and removes the dropdown from the Tab order.
+    #[inline]
+    pub fn disabled(&mut self, disabled: bool) -> &mut Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Sets the font asset.
+    #[inline]
+    pub fn font(&mut self, asset: &'static FontAsset) -> &mut Self {
+        self.font_asset = Some(asset);
+        self
+    }
+
+    /// Sets the font size. Defaults to 16.
+    #[inline]
+    pub fn font_size(&mut self, size: u16) -> &mut Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Sets the padding of the button and of each option. Defaults to 8.
+    #[inline]
+    pub fn padding(&mut self, padding: u16) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the width of the button. The list matches it. Defaults to 200.
+    #[inline]
+    pub fn width(&mut self, width: Sizing) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the height past which the list scrolls. Defaults to 240.
+    #[inline]
+    pub fn max_height(&mut self, height: f32) -> &mut Self {
+        self.max_height = height;
+        self
+    }
+
+    /// Sets the corner radius of the button and the list. Defaults to 6.
+    #[inline]
+    pub fn corner_radius(&mut self, radius: f32) -> &mut Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Sets the background color of the button.
+    #[inline]
+    pub fn background_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.background_color = color.into();
+        self
+    }
+
+    /// Sets the background color of the list.
+    #[inline]
+    pub fn list_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.list_color = color.into();
+        self
+    }
+
+    /// Sets the background color of the hovered or focused option.
+    #[inline]
+    pub fn highlight_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.highlight_color = color.into();
+        self
+    }
+
+    /// Sets the color of the selected option's text and of the options.
+    #[inline]
+    pub fn text_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.text_color = color.into();
+        self
+    }
+
+    /// Sets the color of the placeholder text.
+    #[inline]
+    pub fn placeholder_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.placeholder_color = color.into();
+        self
+    }
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Adds a dropdown for picking one of `items`. `selected` is the index of
+    /// the selected item; out of range shows the placeholder.
+    ///
+    /// Returns `true` on the frame `selected` changed.
+    ///
+    /// ```ignore
+    /// ui.dropdown("size", &mut size, &["Small", "Medium", "Large"], |d| d.label("Size"));
+    /// ```
+    pub fn dropdown(
+        &mut self,
+        id: impl Into<Id>,
+        selected: &mut usize,
+        items: &[&str],
+        config_fn: impl for<'a> FnOnce(&'a mut DropdownBuilder) -> &'a mut DropdownBuilder,
+    ) -> bool {
+        let mut config = DropdownBuilder::default();
+        config_fn(&mut config);
+        let id = id.into();
+        let previous = *selected;
+        let list_id = id.child("list");
+        let search_id = id.child("search");
+        let option_id = |index: usize| id.child_index("option", index as u32);
+
+        let query = if config.searchable {
+            self.ply.context.get_text_value(search_id.id).to_lowercase()
+        } else {
+            String::new()
+        };
+        let visible: Vec<usize> = (0..items.len())
+            .filter(|&index| items[index].to_lowercase().contains(&query))
+            .collect();
+
+        let focused = self.ply.context.focused_element_id;
+        let focused_option = visible.iter().copied().find(|&index| option_id(index).id == focused);
+        let focus_inside = focused == id.id
+            || focused_option.is_some()
+            || (config.searchable && focused == search_id.id);
+
+        let mut state = std::mem::take(self.ply.component_states.widget_state(id.id, DropdownState::default));
+        let mut refocus_button = false;
+        if state.open {
+            let clicked_outside = self.ply.context.pointer_just_pressed()
+                && !self.ply.context.pointer_over(id.clone())
+                && !self.ply.context.pointer_over(list_id.clone());
+            let tabbed_away = state.focus_inside && !focus_inside && focused != 0;
+            let escaped = self.ply.escape_pressed && (state.focus_inside || focus_inside);
+            if clicked_outside || tabbed_away || escaped || config.disabled {
+                state.open = false;
+                refocus_button = escaped;
+            }
+        }
+
+        // Focus stays on the highlighted option; while searching, the top match is highlighted
+        let highlighted = focused_option
+            .or_else(|| visible.first().copied().filter(|_| !query.is_empty()))
+            .or_else(|| visible.iter().copied().find(|&index| index == *selected))
+            .or_else(|| visible.first().copied());
+
+        let mut picked = None;
+        if state.submit.take() && state.open {
+            picked = highlighted;
+        }
+
+        // Keep the focused option in view when the arrow keys move past the edge
+        if let Some(index) = focused_option.filter(|_| state.open && focused_option != state.highlighted) {
+            let list = self.ply.context.get_element_data(list_id.clone());
+            let option = self.ply.context.get_element_data(option_id(index));
+            if let (Some(list), Some(option)) = (list, option) {
+                let scroll = self.ply.context.get_scroll_container_data(list_id.clone());
+                let offset = -scroll.scroll_position.y;
+                let delta = if option.y < list.y {
+                    option.y - list.y
+                } else if option.y + option.height > list.y + list.height {
+                    option.y + option.height - (list.y + list.height)
+                } else {
+                    0.0
+                };
+                if delta != 0.0 {
+                    self.ply.context.set_scroll_position(list_id.clone(), Vector2::new(0.0, offset + delta));
+                }
+            }
+        }
+        state.highlighted = focused_option;
+
+        let text_config = |t: &mut TextConfig, color: Color| {
+            if let Some(font) = config.font_asset {
+                t.font(font);
+            }
+            t.font_size(config.font_size).color(color);
+        };
+        let current = items.get(*selected).copied();
+        let mut open = state.open;
+        let mut opened = false;
+
+        self.element()
+            .id(id.clone())
+            .width(config.width)
+            .background_color(config.background_color)
+            .corner_radius(config.corner_radius)
+            .accessibility(|a| {
+                a.role(AccessibilityRole::ComboBox)
+                    .label(&config.label)
+                    .value(current.unwrap_or(""))
+                    .expanded(open);
+                if !config.disabled {
+                    a.focusable();
+                }
+                if let Some(index) = highlighted.filter(|_| open) {
+                    a.focus_down(option_id(index));
+                }
+                a
+            })
+            .layout(|l| l
+                .direction(LayoutDirection::LeftToRight)
+                .padding(config.padding)
+                .gap(config.padding)
+                .align(AlignX::Left, AlignY::CenterY)
+            )
+            .children(|ui| {
+                if ui.just_pressed() && !config.disabled {
+                    open = !open;
+                    opened = open;
+                }
+
+                match current {
+                    Some(item) => ui.text(item, |t| { text_config(t, config.text_color); t }),
+                    None => ui.text(&config.placeholder, |t| { text_config(t, config.placeholder_color); t }),
+                }
+                ui.element().width(Sizing::Grow(0.0, f32::MAX, 1.0)).empty();
+                ui.text(if open { "▴" } else { "▾" }, |t| { text_config(t, config.text_color); t });
+
+                if !open {
+                    return;
+                }
+                if opened {
+                    if config.searchable {
+                        ui.set_text_value(search_id.clone(), "");
+                        ui.set_focus(search_id.clone());
+                    } else {
+                        let index = visible.iter().copied().find(|&index| index == *selected);
+                        ui.set_focus(option_id(index.or(visible.first().copied()).unwrap_or(0)));
+                    }
+                }
+                // Options show unfiltered right after opening, since the search was just cleared
+                let visible: Vec<usize> = if opened { (0..items.len()).collect() } else { visible.clone() };
+                let highlighted = if opened {
+                    visible.iter().copied().find(|&index| index == *selected).or(visible.first().copied())
+                } else {
+                    highlighted
+                };
+
+                ui.element()
+                    .id(list_id.clone())
+                    .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+                    .height(Sizing::Fit(0.0, config.max_height))
+                    .background_color(config.list_color)
+                    .corner_radius(config.corner_radius)
+                    .floating(|f| f.attach_parent().anchor((AlignX::Left, AlignY::Top), (AlignX::Left, AlignY::Bottom)).offset((0.0, 4.0)).layer(Layer::Overlay))
+                    .overflow(|o| o.scroll_y())
+                    .preserve_focus()
+                    .accessibility(|a| a.role(AccessibilityRole::ListBox).label(&config.label))
+                    .layout(|l| l.direction(LayoutDirection::TopToBottom).padding(config.padding / 2))
+                    .children(|ui| {
+                        if config.searchable {
+                            let submit = state.submit.clone();
+                            ui.element()
+                                .id(search_id.clone())
+                                .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+                                .height(Sizing::Fixed((config.font_size as f32 * 1.5).round()))
+                                .accessibility(|a| {
+                                    a.role(AccessibilityRole::TextInput).label(&config.search_placeholder);
+                                    if let Some(index) = highlighted {
+                                        a.focus_down(option_id(index));
+                                    }
+                                    a
+                                })
+                                .text_input(|t| {
+                                    if let Some(font) = config.font_asset {
+                                        t.font(font);
+                                    }
+                                    t.placeholder(&config.search_placeholder)
+                                        .font_size(config.font_size)
+                                        .text_color(config.text_color)
+                                        .placeholder_color(config.placeholder_color)
+                                        .on_submit(move |_| submit.set(true))
+                                })
+                                .empty();
+                        }
+
+                        let count = visible.len();
+                        for (position, &index) in visible.iter().enumerate() {
+                            let above = match position {
+                                0 if config.searchable => search_id.clone(),
+                                0 => option_id(index),
+                                _ => option_id(visible[position - 1]),
+                            };
+                            let below = option_id(visible[(position + 1).min(count - 1)]);
+                            let mut row = ui
+                                .element()
+                                .id(option_id(index))
+                                .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+                                .corner_radius(config.corner_radius / 2.0)
+                                .accessibility(|a| {
+                                    a.role(AccessibilityRole::ListBoxOption)
+                                        .label(items[index])
+                                        .selected(index == *selected)
+                                        .position_in_set(position as u32 + 1, count as u32)
+                                        .focus_up(above)
+                                        .focus_down(below);
+                                    if highlighted == Some(index) {
+                                        a.focusable();
+                                    }
+                                    a
+                                })
+                                .layout(|l| l.padding(config.padding));
+                            let mut states = StyleStates::default();
+                            states
+                                .hover(|s| s.background_color(config.highlight_color))
+                                .focused(|s| s.background_color(config.highlight_color));
+                            row.style_states = Some(states);
+                            row.children(|ui| {
+                                if ui.just_pressed() {
+                                    picked = Some(index);
+                                }
+                                ui.text(items[index], |t| { text_config(t, config.text_color); t });
+                            });
+                        }
+                    });
+            });
+
+        state.open = open;
+        if let Some(index) = picked {
+            state.open = false;
+            refocus_button = true;
+            *selected = index;
+        }
+        if refocus_button {
+            self.set_focus(id.clone());
+        }
+        state.focus_inside = focus_inside || opened;
+        *self.ply.component_states.widget_state(id.id, DropdownState::default) = state;
+
+        *selected != previous
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ArrowDirection;
+    use crate::math::Dimensions;
+    use crate::Ply;
+
+    #[test]
+    fn test_dropdown_opens_picks_and_closes() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 400.0));
+        let mut selected = 0;
+        let items = ["Apple", "Banana", "Cherry"];
+        let mut frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            let changed = ui.dropdown("fruit", &mut selected, &items, |d| d.label("Fruit"));
+            ui.eval();
+            (changed, selected)
+        };
+        let button = Id::new("fruit");
+        let option = |index: u32| button.child_index("option", index);
+        let center = |ply: &Ply<()>, id: Id| {
+            let bounds = ply.context.get_element_data(id).unwrap();
+            Vector2::new(bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0)
+        };
+        let expanded = |ply: &Ply<()>| ply.context.accessibility_configs[&button.id].expanded;
+
+        // Clicking the button opens the list and focuses the selected option
+        frame(&mut ply);
+        ply.context.set_pointer_state(center(&ply, button.clone()), true);
+        frame(&mut ply);
+        ply.context.set_pointer_state(center(&ply, button.clone()), false);
+        frame(&mut ply);
+        assert_eq!(expanded(&ply), Some(true));
+        assert_eq!(ply.context.focused_element_id, option(0).id);
+
+        // Clicking an option picks it, closes the list and refocuses the button
+        ply.context.set_pointer_state(center(&ply, option(2)), true);
+        assert_eq!(frame(&mut ply), (true, 2));
+        ply.context.set_pointer_state(center(&ply, option(2)), false);
+        frame(&mut ply);
+        assert_eq!(expanded(&ply), Some(false));
+        assert_eq!(ply.context.focused_element_id, button.id);
+
+        // Enter opens it, the arrow keys move and Enter picks
+        ply.context.handle_keyboard_activation(true, false);
+        frame(&mut ply);
+        ply.context.handle_keyboard_activation(false, true);
+        frame(&mut ply);
+        assert_eq!(ply.context.focused_element_id, option(2).id);
+        ply.context.arrow_focus(ArrowDirection::Up);
+        ply.context.handle_keyboard_activation(true, false);
+        assert_eq!(frame(&mut ply), (true, 1));
+        ply.context.handle_keyboard_activation(false, true);
+
+        // A click outside closes it without picking
+        ply.context.handle_keyboard_activation(true, false);
+        frame(&mut ply);
+        ply.context.handle_keyboard_activation(false, true);
+        frame(&mut ply);
+        assert_eq!(expanded(&ply), Some(true));
+        ply.context.set_pointer_state(Vector2::new(390.0, 390.0), true);
+        assert_eq!(frame(&mut ply), (false, 1));
+        ply.context.set_pointer_state(Vector2::new(390.0, 390.0), false);
+        frame(&mut ply);
+        assert_eq!(expanded(&ply), Some(false));
+    }
+}
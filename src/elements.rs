@@ -4,6 +4,7 @@ use crate::{color::Color, Vector2, engine};
 
 /// Specifies how pointer capture should behave for floating elements.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum PointerCaptureMode {
     /// Captures all pointer input.
@@ -15,6 +16,7 @@ pub enum PointerCaptureMode {
 
 /// Defines how a floating element is attached to other elements.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum FloatingAttachToElement {
     /// The floating element is not attached to any other element.
@@ -26,10 +28,40 @@ pub enum FloatingAttachToElement {
     ElementWithId,
     /// The floating element is attached to the root of the layout.
     Root,
+    /// The floating element follows the pointer, anchored to its position.
+    Pointer,
+}
+
+/// Named z-index bands for floating elements, so overlays from different
+/// places stack predictably. Add to a layer's base for order within it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Layer {
+    /// Behind the regular layout, e.g. decorative backdrops.
+    Background,
+    /// The regular layout. Elements that aren't floating are drawn here.
+    Content,
+    /// Popups, dropdowns, tooltips and modals.
+    Overlay,
+    /// Focus rings and the debug view, above everything else.
+    Debug,
+}
+
+impl Layer {
+    /// The lowest z-index in this layer.
+    pub const fn z_index(self) -> i16 {
+        match self {
+            Layer::Background => -16384,
+            Layer::Content => 0,
+            Layer::Overlay => 16384,
+            Layer::Debug => 32764,
+        }
+    }
 }
 
 /// Defines how a floating element is clipped.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum FloatingClipToElement {
     /// The floating element is not clipped.
@@ -59,6 +91,16 @@ impl FloatingBuilder {
         self
     }
 
+    /// Sets the floating element's Z-index to the base of a named layer.
+    /// ```ignore
+    /// .floating(|f| f.attach_root().layer(Layer::Overlay))
+    /// ```
+    #[inline]
+    pub fn layer(&mut self, layer: Layer) -> &mut Self {
+        self.config.z_index = layer.z_index();
+        self
+    }
+
     /// Sets the attachment points of the floating element and its parent.
     ///
     /// Each tuple is `(AlignX, AlignY)` — the first for the element, the second for the parent.
@@ -92,6 +134,17 @@ impl FloatingBuilder {
         self
     }
 
+    /// Attaches this floating element to the pointer, e.g. for tooltips and
+    /// drag previews. The parent anchor is the pointer's position, so
+    /// `offset` and the element's own anchor place it relative to the cursor.
+    /// Passes the pointer through, so it doesn't cover what's under the cursor.
+    #[inline]
+    pub fn attach_pointer(&mut self) -> &mut Self {
+        self.config.attach_to = FloatingAttachToElement::Pointer;
+        self.config.pointer_capture_mode = PointerCaptureMode::Passthrough;
+        self
+    }
+
     /// Attaches this floating element to a specific element by ID.
     #[inline]
     pub fn attach_id(&mut self, id: impl Into<Id>) -> &mut Self {
@@ -249,6 +302,7 @@ pub struct BorderBuilder {
 
 /// Defines the position of the border relative to the bounding box.
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BorderPosition {
     /// Fully outside the bounding box.
     #[default]
@@ -364,6 +418,92 @@ impl VisualRotationBuilder {
         self.config.flip_y = true;
         self
     }
+
+    /// Tilts the element in 3D around the horizontal axis through the pivot,
+    /// in degrees. Positive tips the top edge away.
+    #[inline]
+    pub fn rotate_x(&mut self, degrees: f32) -> &mut Self {
+        self.config.rotation_x_radians = degrees.to_radians();
+        self
+    }
+
+    /// Tilts the element in 3D around the vertical axis through the pivot,
+    /// in degrees. Positive turns the right edge away.
+    #[inline]
+    pub fn rotate_y(&mut self, degrees: f32) -> &mut Self {
+        self.config.rotation_y_radians = degrees.to_radians();
+        self
+    }
+
+    /// Sets the viewer's distance for tilts in logical pixels. Smaller is a
+    /// stronger perspective. Default is 800.
+    #[inline]
+    pub fn perspective(&mut self, distance: f32) -> &mut Self {
+        self.config.perspective = distance;
+        self
+    }
+}
+
+/// Builder for a visual transform (render-target based), see
+/// `ElementBuilder::transform`.
+pub struct TransformBuilder {
+    pub(crate) config: engine::VisualRotationConfig,
+}
+
+impl TransformBuilder {
+    /// Scales the element about the pivot by the same factor on both axes.
+    #[inline]
+    pub fn scale(&mut self, factor: f32) -> &mut Self {
+        self.config.scale_x = factor;
+        self.config.scale_y = factor;
+        self
+    }
+
+    /// Scales the element about the pivot horizontally.
+    #[inline]
+    pub fn scale_x(&mut self, factor: f32) -> &mut Self {
+        self.config.scale_x = factor;
+        self
+    }
+
+    /// Scales the element about the pivot vertically.
+    #[inline]
+    pub fn scale_y(&mut self, factor: f32) -> &mut Self {
+        self.config.scale_y = factor;
+        self
+    }
+
+    /// Moves the element by logical pixels.
+    #[inline]
+    pub fn translate(&mut self, x: f32, y: f32) -> &mut Self {
+        self.config.translate_x = x;
+        self.config.translate_y = y;
+        self
+    }
+
+    /// Skews the element horizontally about the pivot, in degrees.
+    #[inline]
+    pub fn skew_x(&mut self, degrees: f32) -> &mut Self {
+        self.config.skew_x_radians = degrees.to_radians();
+        self
+    }
+
+    /// Skews the element vertically about the pivot, in degrees.
+    #[inline]
+    pub fn skew_y(&mut self, degrees: f32) -> &mut Self {
+        self.config.skew_y_radians = degrees.to_radians();
+        self
+    }
+
+    /// Sets the pivot as normalized coordinates (0.0–1.0), shared with
+    /// `rotate_visual`. Default is (0.5, 0.5) = center of the element.
+    #[inline]
+    pub fn pivot(&mut self, pivot: impl Into<Vector2>) -> &mut Self {
+        let pivot = pivot.into();
+        self.config.pivot_x = pivot.x;
+        self.config.pivot_y = pivot.y;
+        self
+    }
 }
 
 /// Builder for configuring shape rotation (vertex-level).
@@ -1,33 +1,69 @@
 //! Pure Rust implementation of the Ply layout engine.
 //! A UI layout engine inspired by Clay.
 
-use rustc_hash::FxHashMap;
+use std::any::{Any, TypeId};
+use std::hash::{Hash, Hasher};
+use std::panic::Location;
+
+use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
+use unicode_segmentation::UnicodeSegmentation;
+use macroquad::DroppedFile;
 
 use crate::align::{AlignX, AlignY};
 use crate::color::Color;
+use crate::diagnostics::Diagnostic;
 use crate::elements::BorderPosition;
 use crate::renderer::ImageSource;
 use crate::shaders::ShaderConfig;
 use crate::elements::{
-    FloatingAttachToElement, FloatingClipToElement, PointerCaptureMode,
+    FloatingAttachToElement, FloatingClipToElement, Layer, PointerCaptureMode,
 };
 use crate::layout::{LayoutDirection, CornerRadius};
 use crate::math::{BoundingBox, Dimensions, Vector2};
-use crate::text::{TextConfig, WrapMode};
+use crate::text::{InlineAlign, InlineAtomContent, TextConfig, WrapMode, INLINE_ATOM};
 
 const DEFAULT_MAX_ELEMENT_COUNT: i32 = 8192;
 const DEFAULT_MAX_MEASURE_TEXT_WORD_CACHE_COUNT: i32 = 16384;
 const MAXFLOAT: f32 = 3.40282346638528859812e+38;
 const EPSILON: f32 = 0.01;
+const ELLIPSIS: &str = "\u{2026}";
+/// Added to the line index to id a static text selection highlight.
+const STATIC_TEXT_SELECTION_ID_OFFSET: u32 = 3000;
+/// Added to the atom index to id an inline image.
+const INLINE_ATOM_ID_OFFSET: u32 = 4000;
+/// Radius of the touch selection handles drawn under text input carets.
+const TEXT_INPUT_HANDLE_RADIUS: f32 = 8.0;
+/// How far from a handle's center a touch still grabs it.
+const TEXT_INPUT_HANDLE_HIT_RADIUS: f32 = 24.0;
+/// Zoom factor of the text input magnifier.
+const TEXT_INPUT_MAGNIFIER_SCALE: f32 = 1.5;
+const TEXT_INPUT_MAGNIFIER_WIDTH: f32 = 120.0;
+/// Drag-selection autoscroll speed, in pixels per second for each pixel the pointer is past the edge.
+const TEXT_INPUT_AUTOSCROLL_SPEED: f32 = 10.0;
+/// Parents or text elements one thread sizes or wraps at a time.
+const LAYOUT_CHUNK_LEN: usize = 8;
+/// Words a text needs before they are measured on several threads.
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+const PARALLEL_MIN_WORDS: usize = 256;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum SizingType {
     #[default]
     Fit,
     Grow,
     Percent,
     Fixed,
+    /// A fraction of the layout width, resolved to `Fixed` when declared.
+    ViewportWidth,
+    /// A fraction of the layout height, resolved to `Fixed` when declared.
+    ViewportHeight,
+    /// The children's minimum size, resolved to `Fixed` when closed.
+    MinContent,
+    /// The children's unwrapped size, resolved to `Fixed` when closed.
+    MaxContent,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -42,6 +78,8 @@ pub enum RenderCommandType {
     ScissorStart,
     ScissorEnd,
     Custom,
+    Canvas,
+    Draw,
     GroupBegin,
     GroupEnd,
 }
@@ -86,6 +124,15 @@ pub enum TextInputAction {
     Submit,
     Undo,
     Redo,
+    /// Steps a numeric input up by its step size.
+    Increment,
+    /// Steps a numeric input down by its step size.
+    Decrement,
+    /// Removes all text. Used by `clear_on_escape`.
+    Clear,
+    /// Toggles a style tag such as `bold` on the selection of a rich text input.
+    #[cfg(feature = "text-styling")]
+    ToggleStyle { tag: String },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -100,15 +147,19 @@ pub enum ElementConfigType {
     Border,
     Aspect,
     TextInput,
+    Canvas,
+    Draw,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SizingMinMax {
     pub min: f32,
     pub max: f32,
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SizingAxis {
     pub type_: SizingType,
     pub min_max: SizingMinMax,
@@ -128,12 +179,14 @@ impl Default for SizingAxis {
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SizingConfig {
     pub width: SizingAxis,
     pub height: SizingAxis,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PaddingConfig {
     pub left: u16,
     pub right: u16,
@@ -141,13 +194,37 @@ pub struct PaddingConfig {
     pub bottom: u16,
 }
 
+/// Space outside an element, between it and its siblings or its parent's
+/// padding. Negative margins pull neighbours over the element.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarginConfig {
+    pub left: i16,
+    pub right: i16,
+    pub top: i16,
+    pub bottom: i16,
+}
+
+impl MarginConfig {
+    /// Both margins along an axis.
+    fn along(&self, x_axis: bool) -> f32 {
+        if x_axis {
+            (self.left as i32 + self.right as i32) as f32
+        } else {
+            (self.top as i32 + self.bottom as i32) as f32
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChildAlignmentConfig {
     pub x: AlignX,
     pub y: AlignY,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LayoutConfig {
     pub sizing: SizingConfig,
     pub padding: PaddingConfig,
@@ -156,10 +233,13 @@ pub struct LayoutConfig {
     pub wrap_gap: u16,
     pub child_alignment: ChildAlignmentConfig,
     pub layout_direction: LayoutDirection,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub margin: MarginConfig,
 }
 
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VisualRotationConfig {
     /// Rotation angle in radians.
     pub rotation_radians: f32,
@@ -171,6 +251,46 @@ pub struct VisualRotationConfig {
     pub flip_x: bool,
     /// Mirror vertically.
     pub flip_y: bool,
+    /// Tilt around the horizontal axis through the pivot, in radians.
+    /// Positive tips the top edge away.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rotation_x_radians: f32,
+    /// Tilt around the vertical axis through the pivot, in radians.
+    /// Positive turns the right edge away.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rotation_y_radians: f32,
+    /// Distance of the viewer from the element for tilts, in logical pixels.
+    /// Smaller is a stronger perspective. Default 800.
+    #[cfg_attr(feature = "serde", serde(default = "default_perspective"))]
+    pub perspective: f32,
+    /// Horizontal scale about the pivot. Default 1.0.
+    #[cfg_attr(feature = "serde", serde(default = "default_scale"))]
+    pub scale_x: f32,
+    /// Vertical scale about the pivot. Default 1.0.
+    #[cfg_attr(feature = "serde", serde(default = "default_scale"))]
+    pub scale_y: f32,
+    /// Horizontal skew about the pivot, in radians.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub skew_x_radians: f32,
+    /// Vertical skew about the pivot, in radians.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub skew_y_radians: f32,
+    /// Horizontal offset applied last, in logical pixels.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub translate_x: f32,
+    /// Vertical offset applied last, in logical pixels.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub translate_y: f32,
+}
+
+#[cfg(feature = "serde")]
+fn default_perspective() -> f32 {
+    VisualRotationConfig::default().perspective
+}
+
+#[cfg(feature = "serde")]
+fn default_scale() -> f32 {
+    1.0
 }
 
 impl Default for VisualRotationConfig {
@@ -181,6 +301,15 @@ impl Default for VisualRotationConfig {
             pivot_y: 0.5,
             flip_x: false,
             flip_y: false,
+            rotation_x_radians: 0.0,
+            rotation_y_radians: 0.0,
+            perspective: 800.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            skew_x_radians: 0.0,
+            skew_y_radians: 0.0,
+            translate_x: 0.0,
+            translate_y: 0.0,
         }
     }
 }
@@ -188,11 +317,71 @@ impl Default for VisualRotationConfig {
 impl VisualRotationConfig {
     /// Returns `true` when the config is effectively a no-op.
     pub fn is_noop(&self) -> bool {
-        self.rotation_radians == 0.0 && !self.flip_x && !self.flip_y
+        self.rotation_radians == 0.0 && !self.flip_x && !self.flip_y && !self.is_tilted() && !self.is_transformed()
+    }
+
+    /// Returns `true` when the element is tilted in 3D.
+    pub fn is_tilted(&self) -> bool {
+        self.rotation_x_radians != 0.0 || self.rotation_y_radians != 0.0
+    }
+
+    /// Returns `true` when the element is scaled, skewed or translated.
+    pub fn is_transformed(&self) -> bool {
+        self.scale_x != 1.0
+            || self.scale_y != 1.0
+            || self.skew_x_radians != 0.0
+            || self.skew_y_radians != 0.0
+            || self.translate_x != 0.0
+            || self.translate_y != 0.0
+    }
+
+    /// Where `point`, in the untransformed group `bb`, ends up on screen:
+    /// flipped about the center, scaled, skewed and turned about the pivot,
+    /// tilted and projected, then translated.
+    pub(crate) fn project(&self, bb: &BoundingBox, point: (f32, f32)) -> (f32, f32) {
+        let (center_x, center_y) = (bb.x + bb.width / 2.0, bb.y + bb.height / 2.0);
+        let (pivot_x, pivot_y) = (bb.x + self.pivot_x * bb.width, bb.y + self.pivot_y * bb.height);
+        let x = if self.flip_x { 2.0 * center_x - point.0 } else { point.0 };
+        let y = if self.flip_y { 2.0 * center_y - point.1 } else { point.1 };
+        let (dx, dy) = ((x - pivot_x) * self.scale_x, (y - pivot_y) * self.scale_y);
+        let (dx, dy) = (dx + dy * self.skew_x_radians.tan(), dy + dx * self.skew_y_radians.tan());
+        let (sin, cos) = self.rotation_radians.sin_cos();
+        let (dx, dy) = (dx * cos - dy * sin, dx * sin + dy * cos);
+
+        // z points at the viewer
+        let (sin_x, cos_x) = self.rotation_x_radians.sin_cos();
+        let (dy, z) = (dy * cos_x, dy * sin_x);
+        let (sin_y, cos_y) = self.rotation_y_radians.sin_cos();
+        let (dx, z) = (dx * cos_y + z * sin_y, z * cos_y - dx * sin_y);
+        // Points behind the viewer would flip over, so they're held just in front
+        let scale = self.perspective / (self.perspective - z).max(1.0);
+        (pivot_x + dx * scale + self.translate_x, pivot_y + dy * scale + self.translate_y)
+    }
+
+    /// The point in the untransformed group `bb` that [`project`](Self::project)
+    /// puts at `point`, for hit testing. Exact unless tilted, where the
+    /// affine transform through three corners stands in. `None` when the
+    /// group is squashed flat.
+    pub(crate) fn unproject(&self, bb: &BoundingBox, point: (f32, f32)) -> Option<(f32, f32)> {
+        let origin = self.project(bb, (bb.x, bb.y));
+        let right = self.project(bb, (bb.x + bb.width, bb.y));
+        let down = self.project(bb, (bb.x, bb.y + bb.height));
+        let (ax, ay) = ((right.0 - origin.0) / bb.width, (right.1 - origin.1) / bb.width);
+        let (bx, by) = ((down.0 - origin.0) / bb.height, (down.1 - origin.1) / bb.height);
+        let determinant = ax * by - bx * ay;
+        if !determinant.is_normal() {
+            return None;
+        }
+        let (px, py) = (point.0 - origin.0, point.1 - origin.1);
+        Some((
+            bb.x + (px * by - py * bx) / determinant,
+            bb.y + (py * ax - px * ay) / determinant,
+        ))
     }
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShapeRotationConfig {
     /// Rotation angle in radians.
     pub rotation_radians: f32,
@@ -220,6 +409,7 @@ impl ShapeRotationConfig {
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FloatingAttachPoints {
     pub element_x: AlignX,
     pub element_y: AlignY,
@@ -228,6 +418,7 @@ pub struct FloatingAttachPoints {
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FloatingConfig {
     pub offset: Vector2,
     pub parent_id: u32,
@@ -239,6 +430,7 @@ pub struct FloatingConfig {
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClipConfig {
     pub horizontal: bool,
     pub vertical: bool,
@@ -250,6 +442,7 @@ pub struct ClipConfig {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScrollbarConfig {
     pub width: f32,
     pub corner_radius: f32,
@@ -273,6 +466,7 @@ impl Default for ScrollbarConfig {
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BorderWidth {
     pub left: u16,
     pub right: u16,
@@ -292,6 +486,7 @@ impl BorderWidth {
 }
 
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BorderConfig {
     pub color: Color,
     pub width: BorderWidth,
@@ -300,6 +495,8 @@ pub struct BorderConfig {
 
 /// The top-level element declaration.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct ElementDeclaration<CustomElementData: Clone + Default + std::fmt::Debug = ()> {
     pub layout: LayoutConfig,
     pub background_color: Color,
@@ -309,6 +506,9 @@ pub struct ElementDeclaration<CustomElementData: Clone + Default + std::fmt::Deb
     pub image_data: Option<ImageSource>,
     pub floating: FloatingConfig,
     pub custom_data: Option<CustomElementData>,
+    pub canvas: Option<Vec<crate::canvas::PaintCommand>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub on_draw: Option<crate::canvas::DrawHook>,
     pub clip: ClipConfig,
     pub border: BorderConfig,
     pub user_data: usize,
@@ -319,6 +519,7 @@ pub struct ElementDeclaration<CustomElementData: Clone + Default + std::fmt::Deb
     pub accessibility: Option<crate::accessibility::AccessibilityConfig>,
     pub text_input: Option<crate::text_input::TextInputConfig>,
     pub preserve_focus: bool,
+    pub focus_group: Option<crate::accessibility::FocusGroupConfig>,
 }
 
 impl<CustomElementData: Clone + Default + std::fmt::Debug> Default for ElementDeclaration<CustomElementData> {
@@ -332,6 +533,8 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Default for ElementDe
             image_data: None,
             floating: FloatingConfig::default(),
             custom_data: None,
+            canvas: None,
+            on_draw: None,
             clip: ClipConfig::default(),
             border: BorderConfig::default(),
             user_data: 0,
@@ -342,6 +545,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Default for ElementDe
             accessibility: None,
             text_input: None,
             preserve_focus: false,
+            focus_group: None,
         }
     }
 }
@@ -367,11 +571,93 @@ struct ElementConfigSlice {
     length: i32,
 }
 
+/// A run of shader configs in `PlyContext::frame_shaders`, see
+/// `PlyContext::shaders`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShaderSlice {
+    start: usize,
+    length: usize,
+}
+
+impl ShaderSlice {
+    fn range(self) -> std::ops::Range<usize> {
+        self.start..self.start + self.length
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.length == 0
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 struct WrappedTextLine {
     dimensions: Dimensions,
     start: usize,
     length: usize,
+    /// Set on the final line of clamped text; an ellipsis is appended when rendering.
+    ellipsis: bool,
+}
+
+/// The sizes and text wrapping a frame was laid out with, reused by the next
+/// frame when it declares the same layout.
+#[derive(Debug, Clone, Default)]
+struct LayoutSnapshot {
+    fingerprint: u64,
+    dimensions: Vec<Dimensions>,
+    /// Aspect ratio elements get their sizing rewritten during layout.
+    layout_configs: Vec<LayoutConfig>,
+    wrapped_text_lines: Vec<WrappedTextLine>,
+    /// `(wrapped_lines_start, wrapped_lines_length)` of each text element.
+    text_lines: Vec<(usize, i32)>,
+    /// Ids of the elements whose text was cut off at `max_lines`.
+    clamped: Vec<u32>,
+}
+
+/// Rendered line layout of a selectable text element, kept between frames
+/// so the pointer can be mapped back to byte offsets before the next layout.
+#[derive(Debug, Clone, Default)]
+struct SelectableTextLayout {
+    text: String,
+    font_asset: Option<&'static crate::renderer::FontAsset>,
+    font_size: u16,
+    /// (line bounding box, byte start, byte length)
+    lines: Vec<(BoundingBox, usize, usize)>,
+}
+
+/// Selection inside a `.selectable()` text element, as byte offsets into its text.
+#[derive(Debug, Clone, Copy, Default)]
+struct StaticTextSelection {
+    element_id: u32,
+    anchor: usize,
+    cursor: usize,
+    dragging: bool,
+}
+
+impl StaticTextSelection {
+    fn range(&self) -> (usize, usize) {
+        (self.anchor.min(self.cursor), self.anchor.max(self.cursor))
+    }
+}
+
+/// Caret geometry of a focused text input, for touch selection handles and the magnifier.
+#[derive(Debug, Clone)]
+struct TextInputTouchOverlay {
+    /// Top of the caret at the selection start, the selection end, and the cursor.
+    carets: [Vector2; 3],
+    caret_height: f32,
+    /// Text of the cursor's line and the cursor's x offset within it.
+    line: String,
+    line_caret_x: f32,
+}
+
+/// Clickable area of one line of a `{link=...|...}` span, rebuilt every layout.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "text-styling"), allow(dead_code))]
+struct LinkRegion {
+    bounding_box: BoundingBox,
+    /// Element whose `on_link_clicked` handles this link (the text's parent).
+    owner_id: u32,
+    url: String,
 }
 
 #[derive(Debug, Clone)]
@@ -379,6 +665,12 @@ struct TextElementData {
     text: String,
     preferred_dimensions: Dimensions,
     element_index: i32,
+    parent_id: u32,
+    /// Distance from the top of the natural line box to the baseline.
+    baseline: f32,
+    /// The element's text config and its words, measured when declared.
+    config_index: usize,
+    measured: MeasureTextCacheItem,
     wrapped_lines_start: usize,
     wrapped_lines_length: i32,
 }
@@ -398,23 +690,107 @@ struct LayoutElement {
     floating_children_count: u16,
 }
 
+/// A callback whose box can be refilled with another closure of the same type.
+pub(crate) trait Recyclable: Any {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn callback_type(&self) -> TypeId;
+}
+
+impl<F: Any> Recyclable for F {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn callback_type(&self) -> TypeId {
+        TypeId::of::<F>()
+    }
+}
+
+/// A hover, press or release callback. Implemented by every matching closure.
+pub(crate) trait PointerCallback: FnMut(Id, PointerData) + Recyclable {}
+impl<F: FnMut(Id, PointerData) + 'static> PointerCallback for F {}
+
+/// A focus or unfocus callback. Implemented by every matching closure.
+pub(crate) trait FocusCallback: FnMut(Id) + Recyclable {}
+impl<F: FnMut(Id) + 'static> FocusCallback for F {}
+
+/// A callback taking a string: a text input's text, or a clicked link's URL.
+pub(crate) type StrCallback = Box<dyn FnMut(&str)>;
+/// Checks a text input's text, returning the message to show when it's invalid.
+pub(crate) type ValidateFn = Box<dyn Fn(&str) -> Result<(), String>>;
+#[cfg(feature = "clipboard")]
+pub(crate) type PasteImageCallback = Box<dyn FnMut(&crate::clipboard::ClipboardImage)>;
+pub(crate) type FileDropCallback = Box<dyn FnMut(&[DroppedFile])>;
+pub(crate) type MeasureTextFn = Box<dyn Fn(&str, &TextConfig) -> Dimensions>;
+/// A `MeasureTextFn` that can be shared across threads.
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+pub(crate) type SharedMeasureTextFn = std::sync::Arc<dyn Fn(&str, &TextConfig) -> Dimensions + Send + Sync>;
+pub(crate) type TextBaselineFn = Box<dyn Fn(&TextConfig) -> f32>;
+
+/// Last frame's callback boxes, by closure type. Elements redeclare the same
+/// closures every frame, so their boxes are refilled instead of reallocated.
+pub(crate) struct CallbackPool<T: ?Sized> {
+    free: FxHashMap<TypeId, Vec<Box<T>>>,
+}
+
+impl<T: ?Sized> Default for CallbackPool<T> {
+    fn default() -> Self {
+        Self { free: FxHashMap::default() }
+    }
+}
+
+impl<T: ?Sized + Recyclable> CallbackPool<T> {
+    /// Boxes `callback`, reusing a recycled box of the same closure type if any.
+    pub(crate) fn boxed<F: 'static>(&mut self, callback: F, new: fn(F) -> Box<T>) -> Box<T> {
+        if let Some(mut boxed) = self.free.get_mut(&TypeId::of::<F>()).and_then(Vec::pop) {
+            if let Some(slot) = (*boxed).as_any_mut().downcast_mut::<F>() {
+                *slot = callback;
+                return boxed;
+            }
+        }
+        new(callback)
+    }
+
+    pub(crate) fn recycle(&mut self, callback: Option<Box<T>>) {
+        if let Some(callback) = callback {
+            self.free.entry((*callback).callback_type()).or_default().push(callback);
+        }
+    }
+
+    /// Drops boxes nobody reused, so captured state lives at most a frame longer.
+    fn release_unused(&mut self) {
+        for boxes in self.free.values_mut() {
+            boxes.clear();
+        }
+    }
+}
+
 #[derive(Default)]
 struct LayoutElementHashMapItem {
     bounding_box: BoundingBox,
     element_id: Id,
     layout_element_index: i32,
-    on_hover_fn: Option<Box<dyn FnMut(Id, PointerData)>>,
-    on_press_fn: Option<Box<dyn FnMut(Id, PointerData)>>,
-    on_release_fn: Option<Box<dyn FnMut(Id, PointerData)>>,
-    on_focus_fn: Option<Box<dyn FnMut(Id)>>,
-    on_unfocus_fn: Option<Box<dyn FnMut(Id)>>,
-    on_text_changed_fn: Option<Box<dyn FnMut(&str)>>,
-    on_text_submit_fn: Option<Box<dyn FnMut(&str)>>,
+    on_hover_fn: Option<Box<dyn PointerCallback>>,
+    on_press_fn: Option<Box<dyn PointerCallback>>,
+    on_release_fn: Option<Box<dyn PointerCallback>>,
+    on_focus_fn: Option<Box<dyn FocusCallback>>,
+    on_unfocus_fn: Option<Box<dyn FocusCallback>>,
+    on_text_changed_fn: Option<StrCallback>,
+    on_text_submit_fn: Option<StrCallback>,
+    on_link_clicked_fn: Option<StrCallback>,
+    #[cfg(feature = "clipboard")]
+    on_paste_image_fn: Option<PasteImageCallback>,
+    on_file_drop_fn: Option<FileDropCallback>,
     is_text_input: bool,
     preserve_focus: bool,
     generation: u32,
     collision: bool,
     collapsed: bool,
+    text_clamped: bool,
+    /// The z-index it was drawn at, that of its floating root.
+    z_index: i16,
+    /// Where the element's id was set, if through the element builder.
+    location: Option<&'static Location<'static>>,
 }
 
 impl Clone for LayoutElementHashMapItem {
@@ -430,11 +806,18 @@ impl Clone for LayoutElementHashMapItem {
             on_unfocus_fn: None,
             on_text_changed_fn: None,
             on_text_submit_fn: None,
+            on_link_clicked_fn: None,
+            #[cfg(feature = "clipboard")]
+            on_paste_image_fn: None,
+            on_file_drop_fn: None,
             is_text_input: self.is_text_input,
             preserve_focus: self.preserve_focus,
             generation: self.generation,
             collision: self.collision,
             collapsed: self.collapsed,
+            text_clamped: self.text_clamped,
+            z_index: self.z_index,
+            location: self.location,
         }
     }
 }
@@ -447,6 +830,15 @@ struct MeasuredWord {
     next: i32,
 }
 
+/// A run of text up to a space, a newline or the end, measured on its own.
+#[derive(Debug, Clone, Copy)]
+struct WordSpan {
+    start: usize,
+    end: usize,
+    /// The space or newline ending the run, or 0 at the end of the text.
+    separator: u8,
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 #[allow(dead_code)]
 struct MeasureTextCacheItem {
@@ -454,8 +846,10 @@ struct MeasureTextCacheItem {
     measured_words_start_index: i32,
     min_width: f32,
     contains_newlines: bool,
-    id: u32,
+    id: u64,
+    /// The generation it was last used in, for evicting the least recently used.
     generation: u32,
+    word_count: u32,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -467,6 +861,8 @@ struct ScrollContainerDataInternal {
     pointer_origin: Vector2,
     scroll_momentum: Vector2,
     scroll_position: Vector2,
+    /// The scroll position its children were last laid out with.
+    laid_out_offset: Vector2,
     previous_delta: Vector2,
     scrollbar: Option<ScrollbarConfig>,
     scroll_x_enabled: bool,
@@ -489,6 +885,8 @@ struct LayoutElementTreeNode {
     layout_element_index: i32,
     position: Vector2,
     next_child_offset: Vector2,
+    /// Inside a subtree clipped out of view, so nothing is drawn for it.
+    culled: bool,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -505,6 +903,16 @@ struct FocusableEntry {
     element_id: u32,
     tab_index: Option<i32>,
     insertion_order: u32,
+    /// The focus group it was declared in, if any.
+    group: Option<u32>,
+}
+
+/// A focus group declared this frame with `.focus_group()`.
+#[derive(Debug, Clone)]
+struct FocusGroup {
+    config: crate::accessibility::FocusGroupConfig,
+    /// Focusable members in declaration order.
+    members: Vec<u32>,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -513,15 +921,6 @@ pub struct PointerData {
     pub state: PointerDataInteractionState,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
-#[allow(dead_code)]
-struct BooleanWarnings {
-    max_elements_exceeded: bool,
-    text_measurement_fn_not_set: bool,
-    max_text_measure_cache_exceeded: bool,
-    max_render_commands_exceeded: bool,
-}
-
 #[derive(Debug, Clone)]
 pub struct InternalRenderCommand<CustomElementData: Clone + Default + std::fmt::Debug = ()> {
     pub bounding_box: BoundingBox,
@@ -530,7 +929,8 @@ pub struct InternalRenderCommand<CustomElementData: Clone + Default + std::fmt::
     pub user_data: usize,
     pub id: u32,
     pub z_index: i16,
-    pub effects: Vec<ShaderConfig>,
+    /// Effects chained on the command, in the frame's shader configs.
+    pub effects: ShaderSlice,
     pub visual_rotation: Option<VisualRotationConfig>,
     pub shape_rotation: Option<ShapeRotationConfig>,
 }
@@ -549,6 +949,8 @@ pub enum InternalRenderData<CustomElementData: Clone + Default + std::fmt::Debug
         letter_spacing: u16,
         line_height: u16,
         font_asset: Option<&'static crate::renderer::FontAsset>,
+        shadow: Option<crate::text::TextShadow>,
+        outline: Option<crate::text::TextOutline>,
     },
     Image {
         background_color: Color,
@@ -560,6 +962,12 @@ pub enum InternalRenderData<CustomElementData: Clone + Default + std::fmt::Debug
         corner_radius: CornerRadius,
         custom_data: CustomElementData,
     },
+    Canvas {
+        commands: Vec<crate::canvas::PaintCommand>,
+    },
+    Draw {
+        hook: crate::canvas::DrawHook,
+    },
     Border {
         color: Color,
         corner_radius: CornerRadius,
@@ -587,7 +995,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Default for InternalR
             user_data: 0,
             id: 0,
             z_index: 0,
-            effects: Vec::new(),
+            effects: ShaderSlice::default(),
             visual_rotation: None,
             shape_rotation: None,
         }
@@ -623,13 +1031,30 @@ pub struct PlyContext<CustomElementData: Clone + Default + std::fmt::Debug = ()>
     pub max_measure_text_cache_word_count: i32,
     pub debug_mode_enabled: bool,
     debug_view_width: f32,
+    /// Index of the first layout element of the debug view this frame.
+    debug_view_start: usize,
     pub culling_disabled: bool,
+    /// Set while generating a culled subtree, whose render commands are dropped.
+    skip_render_commands: bool,
     pub external_scroll_handling_enabled: bool,
     pub debug_selected_element_id: u32,
+    /// Whether clicking the UI selects an element in the debug view instead of pressing it.
+    debug_picking: bool,
+    /// The element under the pointer while picking.
+    debug_hovered_element_id: u32,
+    /// Where the debug view's own elements start in `layout_elements`.
+    debug_first_element_index: usize,
     pub generation: u32,
 
-    // Warnings
-    boolean_warnings: BooleanWarnings,
+    // Diagnostics
+    /// Problems found this frame, reset by `begin_layout`.
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    /// Set once `max_element_count` is hit; the rest of the frame is ignored.
+    max_elements_exceeded: bool,
+
+    // Callback boxes kept for the next frame's elements
+    pub(crate) pointer_callbacks: CallbackPool<dyn PointerCallback>,
+    pub(crate) focus_callbacks: CallbackPool<dyn FocusCallback>,
 
     // Pointer info
     pointer_info: PointerData,
@@ -639,7 +1064,12 @@ pub struct PlyContext<CustomElementData: Clone + Default + std::fmt::Debug = ()>
     dynamic_element_index: u32,
 
     // Measure text callback
-    measure_text_fn: Option<Box<dyn Fn(&str, &TextConfig) -> Dimensions>>,
+    measure_text_fn: Option<MeasureTextFn>,
+    // The same callback when it can be shared across threads, to measure and wrap text in parallel
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    parallel_measure_text_fn: Option<SharedMeasureTextFn>,
+    // Distance from the top of a text line to its baseline
+    text_baseline_fn: Option<TextBaselineFn>,
 
     // Layout elements
     layout_elements: Vec<LayoutElement>,
@@ -656,19 +1086,30 @@ pub struct PlyContext<CustomElementData: Clone + Default + std::fmt::Debug = ()>
     layout_configs: Vec<LayoutConfig>,
     element_configs: Vec<ElementConfig>,
     text_element_configs: Vec<TextConfig>,
+    // Effects of each text config, moved into `frame_shaders`
+    text_element_effects: Vec<ShaderSlice>,
     aspect_ratio_configs: Vec<f32>,
     aspect_ratio_cover_configs: Vec<bool>,
     image_element_configs: Vec<ImageSource>,
     floating_element_configs: Vec<FloatingConfig>,
+    /// Z-indexes set with `set_z_index`, replacing the declared ones.
+    z_index_overrides: FxHashMap<u32, i16>,
+    /// Added to the z-index of floating elements declared inside a portal.
+    pub(crate) portal_z_index: i16,
     clip_element_configs: Vec<ClipConfig>,
     custom_element_configs: Vec<CustomElementData>,
+    canvas_element_configs: Vec<Vec<crate::canvas::PaintCommand>>,
+    draw_hook_configs: Vec<crate::canvas::DrawHook>,
     border_element_configs: Vec<BorderConfig>,
     shared_element_configs: Vec<SharedElementConfig>,
 
+    // Shader configs declared this frame, sliced per element below. Cleared
+    // each frame but keeps its capacity, so steady frames don't allocate.
+    frame_shaders: Vec<ShaderConfig>,
     // Per-element shader effects (indexed by layout element index)
-    element_effects: Vec<Vec<ShaderConfig>>,
+    element_effects: Vec<ShaderSlice>,
     // Per-element group shaders (indexed by layout element index)
-    element_shaders: Vec<Vec<ShaderConfig>>,
+    element_shaders: Vec<ShaderSlice>,
 
     // Per-element visual rotation (indexed by layout element index)
     element_visual_rotations: Vec<Option<VisualRotationConfig>>,
@@ -690,11 +1131,26 @@ pub struct PlyContext<CustomElementData: Clone + Default + std::fmt::Debug = ()>
 
     // Layout element map: element id -> element data (bounding box, hover callback, etc.)
     layout_element_map: FxHashMap<u32, LayoutElementHashMapItem>,
+    /// Last frame's bounding boxes, for queries made while declaring this one.
+    previous_element_bounds: FxHashMap<u32, BoundingBox>,
+    /// Set once render commands are generated, so bounding boxes are this frame's.
+    elements_positioned: bool,
 
-    // Text measurement cache: content hash -> measured dimensions and words
-    measure_text_cache: FxHashMap<u32, MeasureTextCacheItem>,
+    // Text measurement cache: text and config hash -> measured dimensions and words
+    measure_text_cache: FxHashMap<u64, MeasureTextCacheItem>,
     measured_words: Vec<MeasuredWord>,
     measured_words_free_list: Vec<i32>,
+    // Words of the text being measured and their sizes, kept between misses
+    word_spans: Vec<WordSpan>,
+    word_dimensions: Vec<Dimensions>,
+    // Lines of the text wrap, a chunk of elements each, kept between frames
+    wrap_scratch: Vec<WrapScratch>,
+    // Parents being sized, a level of the tree at a time, and their children's new sizes
+    sizing_level: Vec<i32>,
+    sizing_next_level: Vec<i32>,
+    sizing_scratch: Vec<SizingScratch>,
+    // Entries, words held and lifetime totals of the cache
+    pub(crate) text_cache_stats: crate::profiler::TextCacheStats,
 
     // Clip/scroll
     open_clip_element_stack: Vec<i32>,
@@ -705,18 +1161,54 @@ pub struct PlyContext<CustomElementData: Clone + Default + std::fmt::Debug = ()>
     released_this_frame_ids: Vec<Id>,
     released_this_frame_generation: u32,
     keyboard_press_this_frame_generation: u32,
+    /// Text input that got Backspace while empty, and the frame it happened.
+    empty_backspace: (u32, u32),
+    /// Arrow key pressed this frame, the element focused at the time, and the frame.
+    arrow_key: (u32, Option<ArrowDirection>, u32),
+    /// Scroll wheel movement this frame.
+    wheel_delta: Vector2,
     scroll_container_datas: Vec<ScrollContainerDataInternal>,
 
+    /// Set by `Ply::new_headless`: there is no macroquad input queue to flush.
+    pub(crate) headless: bool,
+
     // Accessibility / focus
     pub focused_element_id: u32, // 0 = no focus
+    /// Where files dragged from the OS are over the window, while a drag is in progress.
+    pub(crate) file_drag_position: Option<Vector2>,
     /// True when focus was set via keyboard (Tab/arrow keys), false when via mouse click.
     pub(crate) focus_from_keyboard: bool,
     focusable_elements: Vec<FocusableEntry>,
+    /// Focus groups declared this frame, and the open ones with their element index.
+    focus_groups: FxHashMap<u32, FocusGroup>,
+    open_focus_groups: Vec<(usize, u32)>,
+    /// Member of each roving focus group that Tab enters at, the one focused last.
+    focus_group_stops: FxHashMap<u32, u32>,
+    /// Focus scopes, innermost last, with the element focused when each was pushed.
+    focus_scopes: Vec<(u32, u32)>,
+    /// Timings and counters for the frame being built.
+    pub(crate) frame_stats: crate::profiler::FrameStats,
+    frame_start_ms: f64,
+    /// Whether an unchanged layout is taken from the last frame instead of
+    /// being sized and wrapped again.
+    pub(crate) layout_cache_enabled: bool,
+    /// Everything declared this frame that sizing and wrapping depend on.
+    layout_fingerprint: FxHasher,
+    layout_snapshot: Option<LayoutSnapshot>,
+    /// Colors forced on every element when high-contrast mode is on.
+    pub(crate) high_contrast: Option<crate::accessibility::HighContrastPalette>,
+    /// Whether arrow keys without an override or focus group move to the
+    /// nearest focusable element in that direction.
+    pub(crate) spatial_navigation: bool,
     pub(crate) accessibility_configs: FxHashMap<u32, crate::accessibility::AccessibilityConfig>,
     pub(crate) accessibility_element_order: Vec<u32>,
 
     // Text input
     pub(crate) text_edit_states: FxHashMap<u32, crate::text_input::TextEditState>,
+    /// Validation errors of text inputs, from their last declared validator.
+    text_input_errors: FxHashMap<u32, String>,
+    /// Text inputs whose validator ran this frame; other errors are dropped.
+    validated_text_inputs: FxHashSet<u32>,
     text_input_configs: Vec<crate::text_input::TextInputConfig>,
     /// Set of element IDs that are text inputs this frame.
     pub(crate) text_input_element_ids: Vec<u32>,
@@ -734,6 +1226,22 @@ pub struct PlyContext<CustomElementData: Clone + Default + std::fmt::Debug = ()>
     pub(crate) text_input_scrollbar_drag_vertical: bool,
     pub(crate) text_input_scrollbar_drag_origin: f32,
     pub(crate) text_input_scrollbar_drag_scroll_origin: f32,
+    /// True while the focused text input was last pressed by touch, which shows selection handles.
+    pub(crate) text_input_touch_handles: bool,
+    /// Centers of the selection handles drawn last frame, as (element id, [start, end]).
+    pub(crate) text_input_handle_centers: Option<(u32, [Vector2; 2])>,
+    /// The selection handle being dragged: 0 = start, 1 = end.
+    pub(crate) text_input_handle_drag: Option<usize>,
+    /// Line layouts of selectable static text from the last layout, by element id.
+    selectable_text_layouts: FxHashMap<u32, SelectableTextLayout>,
+    /// Active selection in selectable static text, if any.
+    static_text_selection: Option<StaticTextSelection>,
+    /// Link hit regions from the last layout.
+    link_regions: Vec<LinkRegion>,
+    /// Index into `link_regions` of the link under the pointer.
+    hovered_link: Option<usize>,
+    /// (owner id, url) of the link the pointer was pressed on.
+    pressed_link: Option<(u32, String)>,
     /// Current absolute time in seconds (set by lib.rs each frame).
     pub(crate) current_time: f64,
     /// Delta time for the current frame in seconds (set by lib.rs each frame).
@@ -755,16 +1263,6 @@ pub struct PlyContext<CustomElementData: Clone + Default + std::fmt::Debug = ()>
     // Debug view: heap-allocated strings that survive the frame
 }
 
-fn hash_data_scalar(data: &[u8]) -> u64 {
-    let mut hash: u64 = 0;
-    for &b in data {
-        hash = hash.wrapping_add(b as u64);
-        hash = hash.wrapping_add(hash << 10);
-        hash ^= hash >> 6;
-    }
-    hash
-}
-
 pub fn hash_string(key: &str, seed: u32) -> Id {
     let mut hash: u32 = seed;
     for b in key.bytes() {
@@ -825,2520 +1323,3108 @@ fn hash_number(offset: u32, seed: u32) -> Id {
     }
 }
 
-fn hash_string_contents_with_config(
-    text: &str,
-    config: &TextConfig,
-) -> u32 {
-    let mut hash: u32 = (hash_data_scalar(text.as_bytes()) % u32::MAX as u64) as u32;
-    // Fold in font key bytes
-    for &b in config.font_asset.map(|a| a.key()).unwrap_or("").as_bytes() {
-        hash = hash.wrapping_add(b as u32);
-        hash = hash.wrapping_add(hash << 10);
-        hash ^= hash >> 6;
-    }
-    hash = hash.wrapping_add(config.font_size as u32);
-    hash = hash.wrapping_add(hash << 10);
-    hash ^= hash >> 6;
-    hash = hash.wrapping_add(config.letter_spacing as u32);
-    hash = hash.wrapping_add(hash << 10);
-    hash ^= hash >> 6;
-    hash = hash.wrapping_add(hash << 3);
-    hash ^= hash >> 11;
-    hash = hash.wrapping_add(hash << 15);
-    hash.wrapping_add(1)
+/// What wrapping text elements reads from the frame. Holds no callbacks,
+/// so it can be shared across threads.
+#[derive(Clone, Copy)]
+struct TextWrapJob<'a> {
+    text_element_data: &'a [TextElementData],
+    text_element_configs: &'a [TextConfig],
+    layout_elements: &'a [LayoutElement],
+    measured_words: &'a [MeasuredWord],
 }
 
-fn float_equal(left: f32, right: f32) -> bool {
-    let diff = left - right;
-    diff < EPSILON && diff > -EPSILON
+/// How one text element wrapped. Its lines were pushed right before.
+#[derive(Clone, Copy)]
+struct WrappedText {
+    line_count: usize,
+    /// Whether lines past `max_lines` were dropped.
+    clamped: bool,
+    /// The element's new height, if it wrapped at all.
+    height: Option<f32>,
 }
 
-fn point_is_inside_rect(point: Vector2, rect: BoundingBox) -> bool {
-    point.x >= rect.x
-        && point.x <= rect.x + rect.width
-        && point.y >= rect.y
-        && point.y <= rect.y + rect.height
+/// Lines and results of a run of text elements wrapped on one thread, kept
+/// between frames so wrapping doesn't allocate.
+#[derive(Default)]
+struct WrapScratch {
+    lines: Vec<WrappedTextLine>,
+    texts: Vec<WrappedText>,
 }
 
-#[derive(Debug, Clone, Copy)]
-struct ScrollbarAxisGeometry {
-    track_bbox: BoundingBox,
-    thumb_bbox: BoundingBox,
-    max_scroll: f32,
-    thumb_travel: f32,
-}
+/// Breaks a text element into lines that fit its container and pushes them
+/// to `lines`. Touches nothing else, so elements can be wrapped on several threads.
+fn wrap_text_element(
+    job: TextWrapJob,
+    text_idx: usize,
+    measure_fn: &dyn Fn(&str, &TextConfig) -> Dimensions,
+    lines: &mut Vec<WrappedTextLine>,
+) -> WrappedText {
+    let text_data = &job.text_element_data[text_idx];
+    let text = text_data.text.as_str();
+    let text_config = &job.text_element_configs[text_data.config_index];
+    let dimensions = job.layout_elements[text_data.element_index as usize].dimensions;
+    let container_width = dimensions.width;
+    let preferred_dims = text_data.preferred_dimensions;
+    let measured = text_data.measured;
+    let first_line = lines.len();
+
+    let line_height = if text_config.line_height > 0 {
+        text_config.line_height as f32
+    } else {
+        preferred_dims.height
+    };
 
-#[derive(Debug, Clone, Copy, Default)]
-struct WrappedLayoutLine {
-    start_child_offset: usize,
-    end_child_offset: usize,
-    main_size: f32,
-    cross_size: f32,
-}
+    if !measured.contains_newlines && preferred_dims.width <= container_width {
+        // Single line
+        lines.push(WrappedTextLine {
+            dimensions,
+            start: 0,
+            length: text.len(),
+            ellipsis: false,
+        });
+        return WrappedText { line_count: 1, clamped: false, height: None };
+    }
 
-fn scrollbar_visibility_alpha(config: ScrollbarConfig, idle_frames: u32) -> f32 {
-    match config.hide_after_frames {
-        None => 1.0,
-        Some(hide) => {
-            if hide == 0 {
-                return 0.0;
+    // Multi-line wrapping
+    let space_width = measure_fn(" ", text_config).width;
+
+    let mut word_index = measured.measured_words_start_index;
+    let mut line_width: f32 = 0.0;
+    let mut line_length_chars: i32 = 0;
+    let mut line_start_offset: i32 = 0;
+
+    while word_index != -1 {
+        let measured_word = job.measured_words[word_index as usize];
+
+        // Word doesn't fit on a line by itself: break it at grapheme boundaries
+        if text_config.wrap_mode == WrapMode::Anywhere
+            && line_length_chars == 0
+            && measured_word.length > 0
+            && measured_word.width > container_width
+        {
+            let word_start = measured_word.start_offset as usize;
+            let word_end = word_start + measured_word.length as usize;
+            let mut chunk_start = word_start;
+            let mut chunk_end = word_start;
+            let mut chunk_width: f32 = 0.0;
+            for (offset, grapheme) in text[word_start..word_end].grapheme_indices(true) {
+                let end = word_start + offset + grapheme.len();
+                let width = measure_span(&text[chunk_start..end], chunk_start, text_config, measure_fn).width;
+                if width > container_width && chunk_end > chunk_start && grapheme != " " {
+                    lines.push(WrappedTextLine {
+                        dimensions: Dimensions::new(chunk_width, line_height),
+                        start: chunk_start,
+                        length: chunk_end - chunk_start,
+                        ellipsis: false,
+                    });
+                    chunk_start = chunk_end;
+                    chunk_width = measure_span(&text[chunk_start..end], chunk_start, text_config, measure_fn).width;
+                } else {
+                    chunk_width = width;
+                }
+                chunk_end = end;
             }
-            if idle_frames <= hide {
-                return 1.0;
+            // The remainder starts the next line so following words can join it
+            line_start_offset = chunk_start as i32;
+            line_length_chars = (chunk_end - chunk_start) as i32;
+            line_width = chunk_width + text_config.letter_spacing as f32;
+            word_index = measured_word.next;
+        }
+        // Word doesn't fit but it's the only word on the line
+        else if line_length_chars == 0 && line_width + measured_word.width > container_width {
+            lines.push(WrappedTextLine {
+                dimensions: Dimensions::new(measured_word.width, line_height),
+                start: measured_word.start_offset as usize,
+                length: measured_word.length as usize,
+                ellipsis: false,
+            });
+            word_index = measured_word.next;
+            line_start_offset = measured_word.start_offset + measured_word.length;
+        }
+        // Newline or overflow
+        else if measured_word.length == 0 || line_width + measured_word.width > container_width {
+            let text_bytes = text.as_bytes();
+            let final_char_idx = (line_start_offset + line_length_chars - 1).max(0) as usize;
+            let final_char_is_space =
+                final_char_idx < text_bytes.len() && text_bytes[final_char_idx] == b' ';
+            let adj_width = line_width + if final_char_is_space { -space_width } else { 0.0 };
+            let adj_length = line_length_chars + if final_char_is_space { -1 } else { 0 };
+
+            lines.push(WrappedTextLine {
+                dimensions: Dimensions::new(adj_width, line_height),
+                start: line_start_offset as usize,
+                length: adj_length as usize,
+                ellipsis: false,
+            });
+
+            if line_length_chars == 0 || measured_word.length == 0 {
+                word_index = measured_word.next;
             }
-            let fade_frames = ((hide as f32) * 0.25).ceil().max(1.0) as u32;
-            let fade_progress = (idle_frames - hide) as f32 / fade_frames as f32;
-            (1.0 - fade_progress).clamp(0.0, 1.0)
+            line_width = 0.0;
+            line_length_chars = 0;
+            line_start_offset = measured_word.start_offset;
+        } else {
+            line_width += measured_word.width + text_config.letter_spacing as f32;
+            line_length_chars += measured_word.length;
+            word_index = measured_word.next;
+        }
+    }
+
+    if line_length_chars > 0 {
+        lines.push(WrappedTextLine {
+            dimensions: Dimensions::new(line_width - text_config.letter_spacing as f32, line_height),
+            start: line_start_offset as usize,
+            length: line_length_chars as usize,
+            ellipsis: false,
+        });
+    }
+
+    // Line clamp: drop lines past max_lines and ellipsize the last kept line
+    let max_lines = text_config.max_lines as usize;
+    let clamped = max_lines > 0 && lines.len() - first_line > max_lines;
+    if clamped {
+        lines.truncate(first_line + max_lines);
+        let last = first_line + max_lines - 1;
+        let line_start = lines[last].start;
+        let mut line_end = line_start + lines[last].length;
+        loop {
+            let kept = text[line_start..line_end].trim_end();
+            let width = measure_span(&format!("{kept}{ELLIPSIS}"), line_start, text_config, measure_fn).width;
+            if width <= container_width || kept.is_empty() {
+                lines[last] = WrappedTextLine {
+                    dimensions: Dimensions::new(width, line_height),
+                    start: line_start,
+                    length: kept.len(),
+                    ellipsis: true,
+                };
+                break;
+            }
+            // Drop the last character and try again
+            line_end = line_start + kept.char_indices().last().map_or(0, |(i, _)| i);
         }
     }
+
+    let line_count = lines.len() - first_line;
+    WrappedText { line_count, clamped, height: Some(line_height * line_count as f32) }
 }
 
-fn apply_alpha(color: Color, alpha_mul: f32) -> Color {
-    Color::rgba(
-        color.r,
-        color.g,
-        color.b,
-        (color.a * alpha_mul).clamp(0.0, 255.0),
-    )
+/// One axis of a size.
+fn size_along(dimensions: &mut Dimensions, x_axis: bool) -> &mut f32 {
+    if x_axis {
+        &mut dimensions.width
+    } else {
+        &mut dimensions.height
+    }
 }
 
-fn compute_vertical_scrollbar_geometry(
-    bbox: BoundingBox,
-    content_height: f32,
-    scroll_position_positive: f32,
-    config: ScrollbarConfig,
-) -> Option<ScrollbarAxisGeometry> {
-    let viewport = bbox.height;
-    let max_scroll = (content_height - viewport).max(0.0);
-    if viewport <= 0.0 || max_scroll <= 0.0 {
-        return None;
+/// Size a child breaks a wrapped line at: its minimum if it grows, else its size.
+fn wrap_break_size(sizing: SizingAxis, size: f32, margin: f32) -> f32 {
+    match sizing.type_ {
+        SizingType::Grow => sizing.min_max.min + margin,
+        SizingType::Percent
+        | SizingType::Fixed
+        | SizingType::Fit
+        | SizingType::ViewportWidth
+        | SizingType::ViewportHeight
+        | SizingType::MinContent
+        | SizingType::MaxContent => size + margin,
     }
+}
 
-    let thickness = config.width.max(1.0);
-    let track_len = viewport;
-    let thumb_len = (track_len * (viewport / content_height.max(viewport)))
-        .max(config.min_thumb_size.max(1.0))
-        .min(track_len);
-    let thumb_travel = (track_len - thumb_len).max(0.0);
-    let thumb_offset = if thumb_travel <= 0.0 {
-        0.0
-    } else {
-        (scroll_position_positive.clamp(0.0, max_scroll) / max_scroll) * thumb_travel
-    };
+/// Splits `count` children into lines no longer than `available`, given each
+/// child's break size, and pushes the child offset ranges to `lines`.
+fn break_wrapped_lines(
+    count: usize,
+    available: f32,
+    child_gap: f32,
+    break_size: impl Fn(usize) -> f32,
+    lines: &mut Vec<(usize, usize)>,
+) {
+    let mut line_start = 0usize;
+    let mut line_break_main = 0.0;
+    for child_offset in 0..count {
+        let size = break_size(child_offset);
+        let additional = if child_offset == line_start { size } else { child_gap + size };
+        if child_offset > line_start && line_break_main + additional > available + EPSILON {
+            lines.push((line_start, child_offset));
+            line_start = child_offset;
+            line_break_main = size;
+        } else {
+            line_break_main += additional;
+        }
+    }
+    lines.push((line_start, count));
+}
 
-    Some(ScrollbarAxisGeometry {
-        track_bbox: BoundingBox::new(
-            bbox.x + bbox.width - thickness,
-            bbox.y,
-            thickness,
-            track_len,
-        ),
-        thumb_bbox: BoundingBox::new(
-            bbox.x + bbox.width - thickness,
-            bbox.y + thumb_offset,
-            thickness,
-            thumb_len,
-        ),
-        max_scroll,
-        thumb_travel,
-    })
+/// What sizing reads from the frame's tree. Holds no callbacks, so parents
+/// on the same level can be sized on several threads.
+#[derive(Clone, Copy)]
+struct SizingTree<'a> {
+    layout_elements: &'a [LayoutElement],
+    layout_element_children: &'a [i32],
+    layout_configs: &'a [LayoutConfig],
+    element_configs: &'a [ElementConfig],
+    text_element_configs: &'a [TextConfig],
+    clip_element_configs: &'a [ClipConfig],
+    aspect_ratio_configs: &'a [f32],
+    aspect_ratio_cover_configs: &'a [bool],
 }
 
-fn compute_horizontal_scrollbar_geometry(
-    bbox: BoundingBox,
-    content_width: f32,
-    scroll_position_positive: f32,
-    config: ScrollbarConfig,
-) -> Option<ScrollbarAxisGeometry> {
-    let viewport = bbox.width;
-    let max_scroll = (content_width - viewport).max(0.0);
-    if viewport <= 0.0 || max_scroll <= 0.0 {
-        return None;
+/// Buffers one thread sizes children with, kept between frames.
+#[derive(Default)]
+struct SizingScratch {
+    /// New sizes of the children of every parent sized, in order.
+    dimensions: Vec<Dimensions>,
+    /// Offsets of the children that may still shrink or grow.
+    resizable: Vec<usize>,
+    lines: Vec<(usize, usize)>,
+}
+
+impl SizingTree<'_> {
+    fn config_index(&self, element_index: usize, config_type: ElementConfigType) -> Option<usize> {
+        let configs = self.layout_elements[element_index].element_configs;
+        self.element_configs[configs.start..configs.start + configs.length as usize]
+            .iter()
+            .find(|config| config.config_type == config_type)
+            .map(|config| config.config_index)
     }
 
-    let thickness = config.width.max(1.0);
-    let track_len = viewport;
-    let thumb_len = (track_len * (viewport / content_width.max(viewport)))
-        .max(config.min_thumb_size.max(1.0))
-        .min(track_len);
-    let thumb_travel = (track_len - thumb_len).max(0.0);
-    let thumb_offset = if thumb_travel <= 0.0 {
-        0.0
-    } else {
-        (scroll_position_positive.clamp(0.0, max_scroll) / max_scroll) * thumb_travel
-    };
+    fn sizing(&self, element_index: usize, x_axis: bool) -> SizingAxis {
+        let sizing = self.layout_configs[self.layout_elements[element_index].layout_config_index].sizing;
+        if x_axis { sizing.width } else { sizing.height }
+    }
 
-    Some(ScrollbarAxisGeometry {
-        track_bbox: BoundingBox::new(
-            bbox.x,
-            bbox.y + bbox.height - thickness,
-            track_len,
-            thickness,
-        ),
-        thumb_bbox: BoundingBox::new(
-            bbox.x + thumb_offset,
-            bbox.y + bbox.height - thickness,
-            thumb_len,
-            thickness,
-        ),
-        max_scroll,
-        thumb_travel,
-    })
-}
-
-impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElementData> {
-    pub fn new(dimensions: Dimensions) -> Self {
-        let max_element_count = DEFAULT_MAX_ELEMENT_COUNT;
-        let max_measure_text_cache_word_count = DEFAULT_MAX_MEASURE_TEXT_WORD_CACHE_COUNT;
-
-        let ctx = Self {
-            max_element_count,
-            max_measure_text_cache_word_count,
-            debug_mode_enabled: false,
-            debug_view_width: Self::DEBUG_VIEW_DEFAULT_WIDTH,
-            culling_disabled: false,
-            external_scroll_handling_enabled: false,
-            debug_selected_element_id: 0,
-            generation: 0,
-            boolean_warnings: BooleanWarnings::default(),
-            pointer_info: PointerData::default(),
-            layout_dimensions: dimensions,
-            dynamic_element_index: 0,
-            measure_text_fn: None,
-            layout_elements: Vec::new(),
-            render_commands: Vec::new(),
-            open_layout_element_stack: Vec::new(),
-            layout_element_children: Vec::new(),
-            layout_element_children_buffer: Vec::new(),
-            text_element_data: Vec::new(),
-            aspect_ratio_element_indexes: Vec::new(),
-            reusable_element_index_buffer: Vec::new(),
-            layout_element_clip_element_ids: Vec::new(),
-            layout_configs: Vec::new(),
-            element_configs: Vec::new(),
-            text_element_configs: Vec::new(),
-            aspect_ratio_configs: Vec::new(),
-            aspect_ratio_cover_configs: Vec::new(),
-            image_element_configs: Vec::new(),
-            floating_element_configs: Vec::new(),
-            clip_element_configs: Vec::new(),
-            custom_element_configs: Vec::new(),
-            border_element_configs: Vec::new(),
-            shared_element_configs: Vec::new(),
-            element_effects: Vec::new(),
-            element_shaders: Vec::new(),
-            element_visual_rotations: Vec::new(),
-            element_shape_rotations: Vec::new(),
-            element_pre_rotation_dimensions: Vec::new(),
-            layout_element_id_strings: Vec::new(),
-            wrapped_text_lines: Vec::new(),
-            tree_node_array: Vec::new(),
-            layout_element_tree_roots: Vec::new(),
-            layout_element_map: FxHashMap::default(),
-            measure_text_cache: FxHashMap::default(),
-            measured_words: Vec::new(),
-            measured_words_free_list: Vec::new(),
-            open_clip_element_stack: Vec::new(),
-            pointer_over_ids: Vec::new(),
-            pressed_element_ids: Vec::new(),
-            pressed_this_frame_ids: Vec::new(),
-            pressed_this_frame_generation: 0,
-            released_this_frame_ids: Vec::new(),
-            released_this_frame_generation: 0,
-            keyboard_press_this_frame_generation: 0,
-            scroll_container_datas: Vec::new(),
-            focused_element_id: 0,
-            focus_from_keyboard: false,
-            focusable_elements: Vec::new(),
-            accessibility_configs: FxHashMap::default(),
-            accessibility_element_order: Vec::new(),
-            text_edit_states: FxHashMap::default(),
-            text_input_configs: Vec::new(),
-            text_input_element_ids: Vec::new(),
-            pending_text_click: None,
-            text_input_scrollbar_idle_frames: FxHashMap::default(),
-            text_input_drag_active: false,
-            text_input_drag_origin: Vector2::default(),
-            text_input_drag_scroll_origin: Vector2::default(),
-            text_input_drag_element_id: 0,
-            text_input_drag_from_touch: false,
-            text_input_scrollbar_drag_active: false,
-            text_input_scrollbar_drag_vertical: false,
-            text_input_scrollbar_drag_origin: 0.0,
-            text_input_scrollbar_drag_scroll_origin: 0.0,
-            current_time: 0.0,
-            frame_delta_time: 0.0,
-            tree_node_visited: Vec::new(),
-            dynamic_string_data: Vec::new(),
-            font_height_cache: FxHashMap::default(),
-            default_font_key: "",
-        };
-        ctx
+    fn margin(&self, element_index: usize, x_axis: bool) -> f32 {
+        self.layout_configs[self.layout_elements[element_index].layout_config_index]
+            .margin
+            .along(x_axis)
     }
 
-    fn get_open_layout_element(&self) -> usize {
-        let idx = *self.open_layout_element_stack.last().unwrap();
-        idx as usize
+    fn clips(&self, element_index: usize, x_axis: bool) -> bool {
+        self.config_index(element_index, ElementConfigType::Clip).is_some_and(|clip_idx| {
+            let clip = &self.clip_element_configs[clip_idx];
+            (x_axis && clip.horizontal) || (!x_axis && clip.vertical)
+        })
     }
 
-    /// Returns the internal u32 id of the currently open element.
-    pub fn get_open_element_id(&self) -> u32 {
-        let open_idx = self.get_open_layout_element();
-        self.layout_elements[open_idx].id
+    /// Whether sizing may shrink or grow a child: neither fixed nor a
+    /// percentage, and not text that never wraps.
+    fn is_resizable(&self, child_index: usize, sizing: SizingAxis) -> bool {
+        let wraps = match self.config_index(child_index, ElementConfigType::Text) {
+            Some(text_cfg_idx) => matches!(
+                self.text_element_configs[text_cfg_idx].wrap_mode,
+                WrapMode::Words | WrapMode::Anywhere
+            ),
+            None => true,
+        };
+        sizing.type_ != SizingType::Percent && sizing.type_ != SizingType::Fixed && wraps
     }
 
-    pub fn get_parent_element_id(&self) -> u32 {
-        let stack_len = self.open_layout_element_stack.len();
-        let parent_idx = self.open_layout_element_stack[stack_len - 2] as usize;
-        self.layout_elements[parent_idx].id
+    /// Fills in the missing side of an aspect-ratio child.
+    fn fit_aspect_ratio(&self, child_index: usize, dimensions: &mut Dimensions) {
+        let Some(config_idx) = self.config_index(child_index, ElementConfigType::Aspect) else {
+            return;
+        };
+        let aspect_ratio = self.aspect_ratio_configs[config_idx];
+        if aspect_ratio == 0.0 {
+            return;
+        }
+        if dimensions.width == 0.0 && dimensions.height != 0.0 {
+            dimensions.width = dimensions.height * aspect_ratio;
+        } else if dimensions.width != 0.0 && dimensions.height == 0.0 {
+            dimensions.height = dimensions.width * (1.0 / aspect_ratio);
+        }
     }
 
-    fn add_hash_map_item(
-        &mut self,
-        element_id: &Id,
-        layout_element_index: i32,
-    ) {
-        let gen = self.generation;
-        match self.layout_element_map.entry(element_id.id) {
-            std::collections::hash_map::Entry::Occupied(mut entry) => {
-                let item = entry.get_mut();
-                if item.generation <= gen {
-                    item.element_id = element_id.clone();
-                    item.generation = gen + 1;
-                    item.layout_element_index = layout_element_index;
-                    item.collision = false;
-                    item.on_hover_fn = None;
-                    item.on_press_fn = None;
-                    item.on_release_fn = None;
-                    item.on_focus_fn = None;
-                    item.on_unfocus_fn = None;
-                    item.on_text_changed_fn = None;
-                    item.on_text_submit_fn = None;
-                    item.is_text_input = false;
-                    item.preserve_focus = false;
+    /// Sizes a parent's children along an axis and pushes their new sizes to
+    /// `scratch.dimensions`. Reads only the parent and its children, so
+    /// parents on the same level don't depend on each other.
+    fn size_children(&self, parent_index: usize, x_axis: bool, scratch: &mut SizingScratch) {
+        let parent = &self.layout_elements[parent_index];
+        let parent_config = self.layout_configs[parent.layout_config_index];
+        let parent_size = if x_axis { parent.dimensions.width } else { parent.dimensions.height };
+        let parent_padding = if x_axis {
+            (parent_config.padding.left + parent_config.padding.right) as f32
+        } else {
+            (parent_config.padding.top + parent_config.padding.bottom) as f32
+        };
+        let sizing_along_axis = (x_axis
+            && parent_config.layout_direction == LayoutDirection::LeftToRight)
+            || (!x_axis && parent_config.layout_direction == LayoutDirection::TopToBottom);
+        let parent_child_gap = parent_config.child_gap as f32;
+        let children = &self.layout_element_children
+            [parent.children_start..parent.children_start + parent.children_length as usize];
+
+        let SizingScratch { dimensions, resizable, lines } = scratch;
+        let first = dimensions.len();
+        dimensions.extend(children.iter().map(|&child| self.layout_elements[child as usize].dimensions));
+        let sizes = &mut dimensions[first..];
+
+        let mut inner_content_size: f32 = 0.0;
+        let mut total_padding_and_child_gaps = parent_padding;
+        // None = no grow seen, Some(Some(offset)) = exactly one grow, Some(None) = 2+ grows.
+        let mut single_along_axis_grow_candidate: Option<Option<usize>> = None;
+        resizable.clear();
+
+        for (child_offset, &child) in children.iter().enumerate() {
+            let child = child as usize;
+            let child_sizing = self.sizing(child, x_axis);
+            let child_size = *size_along(&mut sizes[child_offset], x_axis);
+            let child_margin = self.margin(child, x_axis);
+
+            if self.is_resizable(child, child_sizing) {
+                resizable.push(child_offset);
+                if sizing_along_axis
+                    && child_sizing.type_ == SizingType::Grow
+                    && child_sizing.grow_weight > 0.0
+                {
+                    single_along_axis_grow_candidate = match single_along_axis_grow_candidate {
+                        None => Some(Some(child_offset)),
+                        Some(Some(_)) | Some(None) => Some(None),
+                    };
+                }
+            }
+
+            if sizing_along_axis {
+                inner_content_size += if child_sizing.type_ == SizingType::Percent {
+                    0.0
                 } else {
-                    // Duplicate ID
-                    item.collision = true;
+                    child_size
+                };
+                inner_content_size += child_margin;
+                total_padding_and_child_gaps += child_margin;
+                if child_offset > 0 {
+                    inner_content_size += parent_child_gap;
+                    total_padding_and_child_gaps += parent_child_gap;
                 }
+            } else {
+                inner_content_size = f32::max(child_size + child_margin, inner_content_size);
             }
-            std::collections::hash_map::Entry::Vacant(entry) => {
-                entry.insert(LayoutElementHashMapItem {
-                    element_id: element_id.clone(),
-                    layout_element_index,
-                    generation: gen + 1,
-                    bounding_box: BoundingBox::default(),
-                    on_hover_fn: None,
-                    on_press_fn: None,
-                    on_release_fn: None,
-                    on_focus_fn: None,
-                    on_unfocus_fn: None,
-                    on_text_changed_fn: None,
-                    on_text_submit_fn: None,
-                    is_text_input: false,
-                    preserve_focus: false,
-                    collision: false,
-                    collapsed: false,
-                });
+        }
+
+        // Expand percentage containers
+        for (child_offset, &child) in children.iter().enumerate() {
+            let child_sizing = self.sizing(child as usize, x_axis);
+            if child_sizing.type_ == SizingType::Percent {
+                let new_size = (parent_size - total_padding_and_child_gaps) * child_sizing.percent;
+                *size_along(&mut sizes[child_offset], x_axis) = new_size;
+                if sizing_along_axis {
+                    inner_content_size += new_size;
+                }
+                self.fit_aspect_ratio(child as usize, &mut sizes[child_offset]);
             }
         }
-    }
 
-    fn generate_id_for_anonymous_element(&mut self, open_element_index: usize) -> Id {
-        let stack_len = self.open_layout_element_stack.len();
-        let parent_idx = self.open_layout_element_stack[stack_len - 2] as usize;
-        let parent = &self.layout_elements[parent_idx];
-        let offset =
-            parent.children_length as u32 + parent.floating_children_count as u32;
-        let parent_id = parent.id;
-        let element_id = hash_number(offset, parent_id);
-        self.layout_elements[open_element_index].id = element_id.id;
-        self.add_hash_map_item(&element_id, open_element_index as i32);
-        if self.debug_mode_enabled {
-            self.layout_element_id_strings.push(element_id.string_id.clone());
+        if sizing_along_axis && parent_config.wrap {
+            let parent_clips = self.clips(parent_index, x_axis);
+            lines.clear();
+            break_wrapped_lines(
+                children.len(),
+                (parent_size - parent_padding).max(0.0),
+                parent_child_gap,
+                |child_offset| {
+                    let child = children[child_offset] as usize;
+                    let size = if x_axis { sizes[child_offset].width } else { sizes[child_offset].height };
+                    wrap_break_size(self.sizing(child, x_axis), size, self.margin(child, x_axis))
+                },
+                lines,
+            );
+
+            for &(start, end) in lines.iter() {
+                if end <= start {
+                    continue;
+                }
+
+                let mut line_inner_content_size: f32 = 0.0;
+                let mut single_line_grow_candidate: Option<Option<usize>> = None;
+                resizable.clear();
+
+                for child_offset in start..end {
+                    let child = children[child_offset] as usize;
+                    let child_sizing = self.sizing(child, x_axis);
+                    if child_offset > start {
+                        line_inner_content_size += parent_child_gap;
+                    }
+                    line_inner_content_size +=
+                        *size_along(&mut sizes[child_offset], x_axis) + self.margin(child, x_axis);
+
+                    if self.is_resizable(child, child_sizing) {
+                        resizable.push(child_offset);
+                        if child_sizing.type_ == SizingType::Grow && child_sizing.grow_weight > 0.0 {
+                            single_line_grow_candidate = match single_line_grow_candidate {
+                                None => Some(Some(child_offset)),
+                                Some(Some(_)) | Some(None) => Some(None),
+                            };
+                        }
+                    }
+                }
+
+                let size_to_distribute = parent_size - parent_padding - line_inner_content_size;
+                if size_to_distribute < 0.0 {
+                    if !parent_clips {
+                        self.shrink_children(children, x_axis, sizes, resizable, size_to_distribute);
+                    }
+                } else if size_to_distribute > 0.0 {
+                    self.grow_children(
+                        children,
+                        x_axis,
+                        sizes,
+                        resizable,
+                        single_line_grow_candidate,
+                        size_to_distribute,
+                    );
+                }
+            }
+        } else if sizing_along_axis {
+            let size_to_distribute = parent_size - parent_padding - inner_content_size;
+            if size_to_distribute < 0.0 {
+                if !self.clips(parent_index, x_axis) {
+                    self.shrink_children(children, x_axis, sizes, resizable, size_to_distribute);
+                }
+            } else if size_to_distribute > 0.0 {
+                self.grow_children(
+                    children,
+                    x_axis,
+                    sizes,
+                    resizable,
+                    single_along_axis_grow_candidate,
+                    size_to_distribute,
+                );
+            }
+        } else {
+            // Off-axis sizing
+            let parent_clips = self.clips(parent_index, x_axis);
+            for &child_offset in resizable.iter() {
+                let child = children[child_offset] as usize;
+                let child_sizing = self.sizing(child, x_axis);
+                let min_dimensions = self.layout_elements[child].min_dimensions;
+                let min_size = if x_axis { min_dimensions.width } else { min_dimensions.height };
+
+                let child_margin = self.margin(child, x_axis);
+                let mut max_size = parent_size - parent_padding - child_margin;
+                if parent_clips {
+                    max_size = f32::max(max_size, inner_content_size - child_margin);
+                }
+
+                let is_cover_aspect = self
+                    .config_index(child, ElementConfigType::Aspect)
+                    .is_some_and(|cfg_idx| self.aspect_ratio_cover_configs[cfg_idx]);
+
+                let child_size = size_along(&mut sizes[child_offset], x_axis);
+                if child_sizing.type_ == SizingType::Grow && child_sizing.grow_weight > 0.0 {
+                    if is_cover_aspect {
+                        *child_size = f32::max(*child_size, max_size);
+                    } else {
+                        *child_size = f32::min(max_size, child_sizing.min_max.max);
+                    }
+                }
+
+                if is_cover_aspect {
+                    *child_size = f32::max(min_size, *child_size);
+                } else {
+                    *child_size = f32::max(min_size, f32::min(*child_size, max_size));
+                }
+            }
         }
-        element_id
     }
 
-    fn element_has_config(
+    /// Shrinks the largest resizable children first, down to their minimums,
+    /// until `distribute` (negative) is taken up.
+    fn shrink_children(
         &self,
-        element_index: usize,
-        config_type: ElementConfigType,
-    ) -> bool {
-        let element = &self.layout_elements[element_index];
-        let start = element.element_configs.start;
-        let length = element.element_configs.length;
-        for i in 0..length {
-            let config = &self.element_configs[start + i as usize];
-            if config.config_type == config_type {
-                return true;
+        children: &[i32],
+        x_axis: bool,
+        sizes: &mut [Dimensions],
+        resizable: &mut Vec<usize>,
+        mut distribute: f32,
+    ) {
+        while distribute < -EPSILON && !resizable.is_empty() {
+            let mut largest: f32 = 0.0;
+            let mut second_largest: f32 = 0.0;
+            let mut width_to_add = distribute;
+
+            for &child_offset in resizable.iter() {
+                let cs = *size_along(&mut sizes[child_offset], x_axis);
+                if float_equal(cs, largest) {
+                    continue;
+                }
+                if cs > largest {
+                    second_largest = largest;
+                    largest = cs;
+                }
+                if cs < largest {
+                    second_largest = f32::max(second_largest, cs);
+                    width_to_add = second_largest - largest;
+                }
+            }
+            width_to_add = f32::max(width_to_add, distribute / resizable.len() as f32);
+
+            let mut j = 0;
+            while j < resizable.len() {
+                let child_offset = resizable[j];
+                let min_dimensions = self.layout_elements[children[child_offset] as usize].min_dimensions;
+                let min_size = if x_axis { min_dimensions.width } else { min_dimensions.height };
+                let child_size = size_along(&mut sizes[child_offset], x_axis);
+                let current_size = *child_size;
+                if float_equal(current_size, largest) {
+                    let new_size = current_size + width_to_add;
+                    if new_size <= min_size {
+                        *child_size = min_size;
+                        distribute -= min_size - current_size;
+                        resizable.swap_remove(j);
+                        continue;
+                    }
+                    *child_size = new_size;
+                    distribute -= new_size - current_size;
+                }
+                j += 1;
             }
         }
-        false
     }
 
-    fn find_element_config_index(
+    /// Grows the growing children by weight, smallest first, up to their
+    /// maximums, until `distribute` is used up.
+    fn grow_children(
         &self,
-        element_index: usize,
-        config_type: ElementConfigType,
-    ) -> Option<usize> {
-        let element = &self.layout_elements[element_index];
-        let start = element.element_configs.start;
-        let length = element.element_configs.length;
-        for i in 0..length {
-            let config = &self.element_configs[start + i as usize];
-            if config.config_type == config_type {
-                return Some(config.config_index);
-            }
+        children: &[i32],
+        x_axis: bool,
+        sizes: &mut [Dimensions],
+        resizable: &mut Vec<usize>,
+        single_grow_candidate: Option<Option<usize>>,
+        mut distribute: f32,
+    ) {
+        if let Some(Some(child_offset)) = single_grow_candidate {
+            let child_max_size = self.sizing(children[child_offset] as usize, x_axis).min_max.max;
+            let child_size = size_along(&mut sizes[child_offset], x_axis);
+            *child_size = f32::min(*child_size + distribute, child_max_size);
+            return;
         }
-        None
-    }
 
-    fn update_aspect_ratio_box(&mut self, element_index: usize) {
-        if let Some(config_idx) =
-            self.find_element_config_index(element_index, ElementConfigType::Aspect)
-        {
-            let aspect_ratio = self.aspect_ratio_configs[config_idx];
-            if aspect_ratio == 0.0 {
-                return;
+        // Remove non-grow from resizable buffer
+        let mut j = 0;
+        while j < resizable.len() {
+            let child_sizing = self.sizing(children[resizable[j]] as usize, x_axis);
+            if child_sizing.type_ != SizingType::Grow || child_sizing.grow_weight <= 0.0 {
+                resizable.swap_remove(j);
+            } else {
+                j += 1;
             }
-            let elem = &mut self.layout_elements[element_index];
-            if elem.dimensions.width == 0.0 && elem.dimensions.height != 0.0 {
-                elem.dimensions.width = elem.dimensions.height * aspect_ratio;
-            } else if elem.dimensions.width != 0.0 && elem.dimensions.height == 0.0 {
-                elem.dimensions.height = elem.dimensions.width * (1.0 / aspect_ratio);
-            }
-        }
-    }
-
-    pub fn store_text_element_config(
-        &mut self,
-        config: TextConfig,
-    ) -> usize {
-        self.text_element_configs.push(config);
-        self.text_element_configs.len() - 1
-    }
-
-    fn store_layout_config(&mut self, config: LayoutConfig) -> usize {
-        self.layout_configs.push(config);
-        self.layout_configs.len() - 1
-    }
-
-    fn store_shared_config(&mut self, config: SharedElementConfig) -> usize {
-        self.shared_element_configs.push(config);
-        self.shared_element_configs.len() - 1
-    }
-
-    fn attach_element_config(&mut self, config_type: ElementConfigType, config_index: usize) {
-        if self.boolean_warnings.max_elements_exceeded {
-            return;
-        }
-        let open_idx = self.get_open_layout_element();
-        self.layout_elements[open_idx].element_configs.length += 1;
-        self.element_configs.push(ElementConfig {
-            config_type,
-            config_index,
-        });
-    }
-
-    pub fn open_element(&mut self) {
-        if self.boolean_warnings.max_elements_exceeded {
-            return;
         }
-        let elem = LayoutElement {
-            text_data_index: -1,
-            ..Default::default()
-        };
-        self.layout_elements.push(elem);
-        let idx = (self.layout_elements.len() - 1) as i32;
-        self.open_layout_element_stack.push(idx);
 
-        // Ensure clip IDs array is large enough
-        while self.layout_element_clip_element_ids.len() < self.layout_elements.len() {
-            self.layout_element_clip_element_ids.push(0);
-        }
+        while distribute > EPSILON && !resizable.is_empty() {
+            let mut total_weight = 0.0;
+            let mut smallest_ratio = MAXFLOAT;
+            let mut second_smallest_ratio = MAXFLOAT;
 
-        self.generate_id_for_anonymous_element(idx as usize);
+            for &child_offset in resizable.iter() {
+                let child_sizing = self.sizing(children[child_offset] as usize, x_axis);
+                total_weight += child_sizing.grow_weight;
 
-        if !self.open_clip_element_stack.is_empty() {
-            let clip_id = *self.open_clip_element_stack.last().unwrap();
-            self.layout_element_clip_element_ids[idx as usize] = clip_id;
-        } else {
-            self.layout_element_clip_element_ids[idx as usize] = 0;
-        }
-    }
+                let child_ratio = *size_along(&mut sizes[child_offset], x_axis) / child_sizing.grow_weight;
+                if float_equal(child_ratio, smallest_ratio) {
+                    continue;
+                }
+                if child_ratio < smallest_ratio {
+                    second_smallest_ratio = smallest_ratio;
+                    smallest_ratio = child_ratio;
+                } else if child_ratio > smallest_ratio {
+                    second_smallest_ratio = f32::min(second_smallest_ratio, child_ratio);
+                }
+            }
 
-    pub fn open_element_with_id(&mut self, element_id: &Id) {
-        if self.boolean_warnings.max_elements_exceeded {
-            return;
-        }
-        let mut elem = LayoutElement {
-            text_data_index: -1,
-            ..Default::default()
-        };
-        elem.id = element_id.id;
-        self.layout_elements.push(elem);
-        let idx = (self.layout_elements.len() - 1) as i32;
-        self.open_layout_element_stack.push(idx);
+            if total_weight <= 0.0 {
+                break;
+            }
 
-        while self.layout_element_clip_element_ids.len() < self.layout_elements.len() {
-            self.layout_element_clip_element_ids.push(0);
-        }
+            let per_weight_growth = distribute / total_weight;
+            let ratio_step_cap = if second_smallest_ratio == MAXFLOAT {
+                MAXFLOAT
+            } else {
+                second_smallest_ratio - smallest_ratio
+            };
 
-        self.add_hash_map_item(element_id, idx);
-        if self.debug_mode_enabled {
-            self.layout_element_id_strings.push(element_id.string_id.clone());
-        }
+            let mut resized_any = false;
+            let mut j = 0;
+            while j < resizable.len() {
+                let child_offset = resizable[j];
+                let child_sizing = self.sizing(children[child_offset] as usize, x_axis);
+                let child_size = size_along(&mut sizes[child_offset], x_axis);
 
-        if !self.open_clip_element_stack.is_empty() {
-            let clip_id = *self.open_clip_element_stack.last().unwrap();
-            self.layout_element_clip_element_ids[idx as usize] = clip_id;
-        } else {
-            self.layout_element_clip_element_ids[idx as usize] = 0;
-        }
-    }
+                let child_ratio = *child_size / child_sizing.grow_weight;
+                if !float_equal(child_ratio, smallest_ratio) {
+                    j += 1;
+                    continue;
+                }
 
-    pub fn configure_open_element(&mut self, declaration: &ElementDeclaration<CustomElementData>) {
-        if self.boolean_warnings.max_elements_exceeded {
-            return;
-        }
-        let open_idx = self.get_open_layout_element();
-        let layout_config_index = self.store_layout_config(declaration.layout);
-        self.layout_elements[open_idx].layout_config_index = layout_config_index;
+                let mut growth_share = per_weight_growth * child_sizing.grow_weight;
+                if ratio_step_cap != MAXFLOAT {
+                    growth_share = f32::min(growth_share, ratio_step_cap * child_sizing.grow_weight);
+                }
 
-        // Record the start of element configs for this element
-        self.layout_elements[open_idx].element_configs.start = self.element_configs.len();
+                let previous = *child_size;
+                let proposed = previous + growth_share;
+                if proposed >= child_sizing.min_max.max {
+                    *child_size = child_sizing.min_max.max;
+                    resized_any = true;
+                    resizable.swap_remove(j);
+                    continue;
+                }
 
-        // Shared config (background color, corner radius, user data)
-        let mut shared_config_index: Option<usize> = None;
-        if declaration.background_color.a > 0.0 {
-            let idx = self.store_shared_config(SharedElementConfig {
-                background_color: declaration.background_color,
-                corner_radius: CornerRadius::default(),
-                user_data: 0,
-            });
-            shared_config_index = Some(idx);
-            self.attach_element_config(ElementConfigType::Shared, idx);
-        }
-        if !declaration.corner_radius.is_zero() {
-            if let Some(idx) = shared_config_index {
-                self.shared_element_configs[idx].corner_radius = declaration.corner_radius;
-            } else {
-                let idx = self.store_shared_config(SharedElementConfig {
-                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.0),
-                    corner_radius: declaration.corner_radius,
-                    user_data: 0,
-                });
-                shared_config_index = Some(idx);
-                self.attach_element_config(ElementConfigType::Shared, idx);
-            }
-        }
-        if declaration.user_data != 0 {
-            if let Some(idx) = shared_config_index {
-                self.shared_element_configs[idx].user_data = declaration.user_data;
-            } else {
-                let idx = self.store_shared_config(SharedElementConfig {
-                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.0),
-                    corner_radius: CornerRadius::default(),
-                    user_data: declaration.user_data,
-                });
-                self.attach_element_config(ElementConfigType::Shared, idx);
+                *child_size = proposed;
+                distribute -= *child_size - previous;
+                resized_any = true;
+                j += 1;
             }
-        }
 
-        // Image config
-        if let Some(image_data) = declaration.image_data.clone() {
-            self.image_element_configs.push(image_data);
-            let idx = self.image_element_configs.len() - 1;
-            self.attach_element_config(ElementConfigType::Image, idx);
-        }
-
-        // Aspect ratio config
-        if declaration.aspect_ratio > 0.0 {
-            self.aspect_ratio_configs.push(declaration.aspect_ratio);
-            self.aspect_ratio_cover_configs
-                .push(declaration.cover_aspect_ratio);
-            let idx = self.aspect_ratio_configs.len() - 1;
-            self.attach_element_config(ElementConfigType::Aspect, idx);
-            self.aspect_ratio_element_indexes
-                .push((self.layout_elements.len() - 1) as i32);
+            if !resized_any {
+                break;
+            }
         }
+    }
+}
 
-        // Floating config
-        if declaration.floating.attach_to != FloatingAttachToElement::None {
-            let mut floating_config = declaration.floating;
-            let stack_len = self.open_layout_element_stack.len();
-
-            if stack_len >= 2 {
-                let hierarchical_parent_idx =
-                    self.open_layout_element_stack[stack_len - 2] as usize;
-                let hierarchical_parent_id = self.layout_elements[hierarchical_parent_idx].id;
+/// Feeds every field of a layout config to `hasher`.
+fn hash_layout_config(hasher: &mut FxHasher, config: &LayoutConfig) {
+    for axis in [&config.sizing.width, &config.sizing.height] {
+        std::mem::discriminant(&axis.type_).hash(hasher);
+        hasher.write_u32(axis.min_max.min.to_bits());
+        hasher.write_u32(axis.min_max.max.to_bits());
+        hasher.write_u32(axis.percent.to_bits());
+        hasher.write_u32(axis.grow_weight.to_bits());
+    }
+    let padding = config.padding;
+    for value in [padding.left, padding.right, padding.top, padding.bottom, config.child_gap, config.wrap_gap] {
+        hasher.write_u16(value);
+    }
+    let margin = config.margin;
+    for value in [margin.left, margin.right, margin.top, margin.bottom] {
+        hasher.write_i16(value);
+    }
+    hasher.write_u8(config.wrap as u8);
+    std::mem::discriminant(&config.child_alignment.x).hash(hasher);
+    std::mem::discriminant(&config.child_alignment.y).hash(hasher);
+    std::mem::discriminant(&config.layout_direction).hash(hasher);
+}
 
-                let mut clip_element_id: u32 = 0;
+/// Identifies a text measurement: the text and everything in its config that
+/// can change its size. Measurements taken while the font was still loading
+/// get their own key, so they're redone once it's in.
+fn text_cache_key(text: &str, config: &TextConfig) -> u64 {
+    let mut hasher = FxHasher::default();
+    text.hash(&mut hasher);
+    config.font_asset.map(|asset| asset.key()).hash(&mut hasher);
+    config.font_asset.is_none_or(crate::renderer::FontManager::is_loaded).hash(&mut hasher);
+    (config.font_size, config.letter_spacing, config.line_height).hash(&mut hasher);
+    // Custom measure functions may pick the font by it
+    config.user_data.hash(&mut hasher);
+    for atom in &config.inline_atoms {
+        (atom.width.to_bits(), atom.height.to_bits()).hash(&mut hasher);
+    }
+    hasher.finish()
+}
 
-                if declaration.floating.attach_to == FloatingAttachToElement::Parent {
-                    floating_config.parent_id = hierarchical_parent_id;
-                    if !self.open_clip_element_stack.is_empty() {
-                        clip_element_id =
-                            *self.open_clip_element_stack.last().unwrap() as u32;
+/// Byte offsets in a wrapped line where justification adds space: the start of
+/// each word after the first, or of each grapheme when the line has no spaces.
+/// Style markup never receives a point.
+fn justification_points(line: &str) -> Vec<usize> {
+    let line = line.trim_end_matches(' ');
+    // (byte offset, char) of every visible character
+    let mut visible = Vec::new();
+    #[cfg(feature = "text-styling")]
+    let (mut in_style_def, mut escape_start) = (false, None);
+    for (i, c) in line.char_indices() {
+        #[cfg(feature = "text-styling")]
+        {
+            if escape_start.is_none() {
+                match c {
+                    '\\' => {
+                        escape_start = Some(i);
+                        continue;
                     }
-                } else if declaration.floating.attach_to
-                    == FloatingAttachToElement::ElementWithId
-                {
-                    if let Some(parent_item) =
-                        self.layout_element_map.get(&floating_config.parent_id)
-                    {
-                        let parent_elem_idx = parent_item.layout_element_index as usize;
-                        clip_element_id =
-                            self.layout_element_clip_element_ids[parent_elem_idx] as u32;
+                    '{' => {
+                        in_style_def = true;
+                        continue;
                     }
-                } else if declaration.floating.attach_to
-                    == FloatingAttachToElement::Root
-                {
-                    floating_config.parent_id =
-                        hash_string("Ply__RootContainer", 0).id;
-                }
-
-                if declaration.floating.clip_to == FloatingClipToElement::None {
-                    clip_element_id = 0;
+                    '|' if in_style_def => {
+                        in_style_def = false;
+                        continue;
+                    }
+                    '}' => continue,
+                    _ if in_style_def => continue,
+                    _ => {}
                 }
-
-                let current_element_index =
-                    *self.open_layout_element_stack.last().unwrap();
-                self.layout_element_clip_element_ids[current_element_index as usize] =
-                    clip_element_id as i32;
-                self.open_clip_element_stack.push(clip_element_id as i32);
-
-                self.layout_element_tree_roots
-                    .push(LayoutElementTreeRoot {
-                        layout_element_index: current_element_index,
-                        parent_id: floating_config.parent_id,
-                        clip_element_id,
-                        z_index: floating_config.z_index,
-                        pointer_offset: Vector2::default(),
-                    });
-
-                self.floating_element_configs.push(floating_config);
-                let idx = self.floating_element_configs.len() - 1;
-                self.attach_element_config(ElementConfigType::Floating, idx);
             }
+            visible.push((escape_start.take().unwrap_or(i), c));
         }
+        #[cfg(not(feature = "text-styling"))]
+        visible.push((i, c));
+    }
+
+    let word_starts: Vec<usize> = visible
+        .windows(2)
+        .filter(|pair| pair[0].1 == ' ' && pair[1].1 != ' ')
+        .map(|pair| pair[1].0)
+        .collect();
+    if !word_starts.is_empty() {
+        return word_starts;
+    }
+    let grapheme_starts: Vec<usize> = line.grapheme_indices(true).map(|(i, _)| i).collect();
+    visible
+        .iter()
+        .skip(1)
+        .map(|&(i, _)| i)
+        .filter(|i| grapheme_starts.binary_search(i).is_ok())
+        .collect()
+}
 
-        // Custom config
-        if let Some(ref custom_data) = declaration.custom_data {
-            self.custom_element_configs.push(custom_data.clone());
-            let idx = self.custom_element_configs.len() - 1;
-            self.attach_element_config(ElementConfigType::Custom, idx);
-        }
+/// Inline atoms whose markers are in `text`, a span of a text element
+/// starting at byte `offset`, as (byte in the span, atom index).
+fn inline_atoms_in<'a>(text: &'a str, offset: usize, config: &'a TextConfig) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let offsets = &config.inline_atom_offsets;
+    let first = offsets.partition_point(|&atom_offset| atom_offset < offset);
+    offsets[first..]
+        .iter()
+        .enumerate()
+        .map(move |(i, &atom_offset)| (atom_offset - offset, first + i))
+        .take_while(move |&(position, _)| position < text.len())
+        .filter(move |&(position, _)| text.get(position..).is_some_and(|rest| rest.starts_with(INLINE_ATOM)))
+}
 
-        // Clip config
-        if declaration.clip.horizontal || declaration.clip.vertical {
-            let mut clip = declaration.clip;
+/// Measures `text`, a span of a text element starting at byte `offset`.
+/// Inline atoms count as their configured width and the text between them
+/// is measured with `measure_fn`.
+fn measure_span(
+    text: &str,
+    offset: usize,
+    config: &TextConfig,
+    measure_fn: &dyn Fn(&str, &TextConfig) -> Dimensions,
+) -> Dimensions {
+    if config.inline_atoms.is_empty() {
+        return measure_fn(text, config);
+    }
+    let mut dimensions = Dimensions::default();
+    let mut segment_start = 0;
+    let mut measured_text = false;
+    for (i, atom_index) in inline_atoms_in(text, offset, config) {
+        if i > segment_start {
+            let segment = measure_fn(&text[segment_start..i], config);
+            dimensions.width += segment.width;
+            dimensions.height = dimensions.height.max(segment.height);
+            measured_text = true;
+        }
+        dimensions.width += config.inline_atoms[atom_index].width;
+        segment_start = i + INLINE_ATOM.len_utf8();
+    }
+    if segment_start < text.len() {
+        let segment = measure_fn(&text[segment_start..], config);
+        dimensions.width += segment.width;
+        dimensions.height = dimensions.height.max(segment.height);
+        measured_text = true;
+    }
+    if !measured_text {
+        // Atom-only text still takes the height of a line of text
+        dimensions.height = measure_fn(" ", config).height;
+    }
+    dimensions
+}
 
-            let elem_id = self.layout_elements[open_idx].id;
+/// Fixes a min- or max-content sizing axis to the measured size.
+fn resolve_content_sizing(axis: &mut SizingAxis, size: f32, min_size: f32) {
+    let size = match axis.type_ {
+        SizingType::MinContent => min_size,
+        SizingType::MaxContent => size,
+        _ => return,
+    };
+    *axis = SizingAxis {
+        type_: SizingType::Fixed,
+        min_max: SizingMinMax { min: size, max: size },
+        percent: 0.0,
+        grow_weight: 1.0,
+    };
+}
 
-            // Auto-apply stored scroll position as child_offset
-            if clip.scroll_x || clip.scroll_y {
-                for scd in &self.scroll_container_datas {
-                    if scd.element_id == elem_id {
-                        clip.child_offset = scd.scroll_position;
-                        break;
-                    }
-                }
-            }
+fn float_equal(left: f32, right: f32) -> bool {
+    let diff = left - right;
+    diff < EPSILON && diff > -EPSILON
+}
 
-            self.clip_element_configs.push(clip);
-            let idx = self.clip_element_configs.len() - 1;
-            self.attach_element_config(ElementConfigType::Clip, idx);
+fn point_is_inside_rect(point: Vector2, rect: BoundingBox) -> bool {
+    point.x >= rect.x
+        && point.x <= rect.x + rect.width
+        && point.y >= rect.y
+        && point.y <= rect.y + rect.height
+}
 
-            self.open_clip_element_stack.push(elem_id as i32);
+#[derive(Debug, Clone, Copy)]
+struct ScrollbarAxisGeometry {
+    track_bbox: BoundingBox,
+    thumb_bbox: BoundingBox,
+    max_scroll: f32,
+    thumb_travel: f32,
+}
 
-            // Track scroll container
-            if clip.scroll_x || clip.scroll_y {
-                let mut found_existing = false;
-                for scd in &mut self.scroll_container_datas {
-                    if elem_id == scd.element_id {
-                        scd.layout_element_index = open_idx as i32;
-                        scd.open_this_frame = true;
-                        scd.scrollbar = clip.scrollbar;
-                        scd.scroll_x_enabled = clip.scroll_x;
-                        scd.scroll_y_enabled = clip.scroll_y;
-                        scd.no_drag_scroll = clip.no_drag_scroll;
-                        found_existing = true;
-                        break;
+#[derive(Debug, Clone, Copy, Default)]
+struct WrappedLayoutLine {
+    start_child_offset: usize,
+    end_child_offset: usize,
+    main_size: f32,
+    cross_size: f32,
+}
+
+fn scrollbar_visibility_alpha(config: ScrollbarConfig, idle_frames: u32) -> f32 {
+    match config.hide_after_frames {
+        None => 1.0,
+        Some(hide) => {
+            if hide == 0 {
+                return 0.0;
+            }
+            if idle_frames <= hide {
+                return 1.0;
+            }
+            let fade_frames = ((hide as f32) * 0.25).ceil().max(1.0) as u32;
+            let fade_progress = (idle_frames - hide) as f32 / fade_frames as f32;
+            (1.0 - fade_progress).clamp(0.0, 1.0)
+        }
+    }
+}
+
+fn apply_alpha(color: Color, alpha_mul: f32) -> Color {
+    Color::rgba(
+        color.r,
+        color.g,
+        color.b,
+        (color.a * alpha_mul).clamp(0.0, 255.0),
+    )
+}
+
+fn compute_vertical_scrollbar_geometry(
+    bbox: BoundingBox,
+    content_height: f32,
+    scroll_position_positive: f32,
+    config: ScrollbarConfig,
+) -> Option<ScrollbarAxisGeometry> {
+    let viewport = bbox.height;
+    let max_scroll = (content_height - viewport).max(0.0);
+    if viewport <= 0.0 || max_scroll <= 0.0 {
+        return None;
+    }
+
+    let thickness = config.width.max(1.0);
+    let track_len = viewport;
+    let thumb_len = (track_len * (viewport / content_height.max(viewport)))
+        .max(config.min_thumb_size.max(1.0))
+        .min(track_len);
+    let thumb_travel = (track_len - thumb_len).max(0.0);
+    let thumb_offset = if thumb_travel <= 0.0 {
+        0.0
+    } else {
+        (scroll_position_positive.clamp(0.0, max_scroll) / max_scroll) * thumb_travel
+    };
+
+    Some(ScrollbarAxisGeometry {
+        track_bbox: BoundingBox::new(
+            bbox.x + bbox.width - thickness,
+            bbox.y,
+            thickness,
+            track_len,
+        ),
+        thumb_bbox: BoundingBox::new(
+            bbox.x + bbox.width - thickness,
+            bbox.y + thumb_offset,
+            thickness,
+            thumb_len,
+        ),
+        max_scroll,
+        thumb_travel,
+    })
+}
+
+fn compute_horizontal_scrollbar_geometry(
+    bbox: BoundingBox,
+    content_width: f32,
+    scroll_position_positive: f32,
+    config: ScrollbarConfig,
+) -> Option<ScrollbarAxisGeometry> {
+    let viewport = bbox.width;
+    let max_scroll = (content_width - viewport).max(0.0);
+    if viewport <= 0.0 || max_scroll <= 0.0 {
+        return None;
+    }
+
+    let thickness = config.width.max(1.0);
+    let track_len = viewport;
+    let thumb_len = (track_len * (viewport / content_width.max(viewport)))
+        .max(config.min_thumb_size.max(1.0))
+        .min(track_len);
+    let thumb_travel = (track_len - thumb_len).max(0.0);
+    let thumb_offset = if thumb_travel <= 0.0 {
+        0.0
+    } else {
+        (scroll_position_positive.clamp(0.0, max_scroll) / max_scroll) * thumb_travel
+    };
+
+    Some(ScrollbarAxisGeometry {
+        track_bbox: BoundingBox::new(
+            bbox.x,
+            bbox.y + bbox.height - thickness,
+            track_len,
+            thickness,
+        ),
+        thumb_bbox: BoundingBox::new(
+            bbox.x + thumb_offset,
+            bbox.y + bbox.height - thickness,
+            thumb_len,
+            thickness,
+        ),
+        max_scroll,
+        thumb_travel,
+    })
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElementData> {
+    pub fn new(dimensions: Dimensions) -> Self {
+        let max_element_count = DEFAULT_MAX_ELEMENT_COUNT;
+        let max_measure_text_cache_word_count = DEFAULT_MAX_MEASURE_TEXT_WORD_CACHE_COUNT;
+
+        let ctx = Self {
+            max_element_count,
+            max_measure_text_cache_word_count,
+            debug_mode_enabled: false,
+            debug_view_width: Self::DEBUG_VIEW_DEFAULT_WIDTH,
+            debug_view_start: 0,
+            culling_disabled: false,
+            skip_render_commands: false,
+            external_scroll_handling_enabled: false,
+            debug_selected_element_id: 0,
+            debug_picking: false,
+            debug_hovered_element_id: 0,
+            debug_first_element_index: 0,
+            generation: 0,
+            diagnostics: Vec::new(),
+            max_elements_exceeded: false,
+            pointer_callbacks: CallbackPool::default(),
+            focus_callbacks: CallbackPool::default(),
+            pointer_info: PointerData::default(),
+            layout_dimensions: dimensions,
+            dynamic_element_index: 0,
+            measure_text_fn: None,
+            #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+            parallel_measure_text_fn: None,
+            text_baseline_fn: None,
+            layout_elements: Vec::new(),
+            render_commands: Vec::new(),
+            open_layout_element_stack: Vec::new(),
+            layout_element_children: Vec::new(),
+            layout_element_children_buffer: Vec::new(),
+            text_element_data: Vec::new(),
+            aspect_ratio_element_indexes: Vec::new(),
+            reusable_element_index_buffer: Vec::new(),
+            layout_element_clip_element_ids: Vec::new(),
+            layout_configs: Vec::new(),
+            element_configs: Vec::new(),
+            text_element_configs: Vec::new(),
+            text_element_effects: Vec::new(),
+            aspect_ratio_configs: Vec::new(),
+            aspect_ratio_cover_configs: Vec::new(),
+            image_element_configs: Vec::new(),
+            floating_element_configs: Vec::new(),
+            z_index_overrides: FxHashMap::default(),
+            portal_z_index: 0,
+            clip_element_configs: Vec::new(),
+            custom_element_configs: Vec::new(),
+            canvas_element_configs: Vec::new(),
+            draw_hook_configs: Vec::new(),
+            border_element_configs: Vec::new(),
+            shared_element_configs: Vec::new(),
+            frame_shaders: Vec::new(),
+            element_effects: Vec::new(),
+            element_shaders: Vec::new(),
+            element_visual_rotations: Vec::new(),
+            element_shape_rotations: Vec::new(),
+            element_pre_rotation_dimensions: Vec::new(),
+            layout_element_id_strings: Vec::new(),
+            wrapped_text_lines: Vec::new(),
+            tree_node_array: Vec::new(),
+            layout_element_tree_roots: Vec::new(),
+            layout_element_map: FxHashMap::default(),
+            previous_element_bounds: FxHashMap::default(),
+            elements_positioned: false,
+            measure_text_cache: FxHashMap::default(),
+            measured_words: Vec::new(),
+            measured_words_free_list: Vec::new(),
+            word_spans: Vec::new(),
+            word_dimensions: Vec::new(),
+            wrap_scratch: Vec::new(),
+            sizing_level: Vec::new(),
+            sizing_next_level: Vec::new(),
+            sizing_scratch: Vec::new(),
+            text_cache_stats: crate::profiler::TextCacheStats::default(),
+            open_clip_element_stack: Vec::new(),
+            pointer_over_ids: Vec::new(),
+            pressed_element_ids: Vec::new(),
+            pressed_this_frame_ids: Vec::new(),
+            pressed_this_frame_generation: 0,
+            released_this_frame_ids: Vec::new(),
+            released_this_frame_generation: 0,
+            keyboard_press_this_frame_generation: 0,
+            empty_backspace: (0, 0),
+            arrow_key: (0, None, 0),
+            wheel_delta: Vector2::new(0.0, 0.0),
+            scroll_container_datas: Vec::new(),
+            headless: false,
+            focused_element_id: 0,
+            file_drag_position: None,
+            focus_from_keyboard: false,
+            focusable_elements: Vec::new(),
+            focus_groups: FxHashMap::default(),
+            open_focus_groups: Vec::new(),
+            focus_group_stops: FxHashMap::default(),
+            focus_scopes: Vec::new(),
+            spatial_navigation: true,
+            high_contrast: None,
+            frame_stats: crate::profiler::FrameStats::default(),
+            frame_start_ms: 0.0,
+            layout_cache_enabled: true,
+            layout_fingerprint: FxHasher::default(),
+            layout_snapshot: None,
+            accessibility_configs: FxHashMap::default(),
+            accessibility_element_order: Vec::new(),
+            text_edit_states: FxHashMap::default(),
+            text_input_errors: FxHashMap::default(),
+            validated_text_inputs: FxHashSet::default(),
+            text_input_configs: Vec::new(),
+            text_input_element_ids: Vec::new(),
+            pending_text_click: None,
+            text_input_scrollbar_idle_frames: FxHashMap::default(),
+            text_input_drag_active: false,
+            text_input_drag_origin: Vector2::default(),
+            text_input_drag_scroll_origin: Vector2::default(),
+            text_input_drag_element_id: 0,
+            text_input_drag_from_touch: false,
+            text_input_scrollbar_drag_active: false,
+            text_input_scrollbar_drag_vertical: false,
+            text_input_scrollbar_drag_origin: 0.0,
+            text_input_scrollbar_drag_scroll_origin: 0.0,
+            text_input_touch_handles: false,
+            text_input_handle_centers: None,
+            text_input_handle_drag: None,
+            selectable_text_layouts: FxHashMap::default(),
+            static_text_selection: None,
+            link_regions: Vec::new(),
+            hovered_link: None,
+            pressed_link: None,
+            current_time: 0.0,
+            frame_delta_time: 0.0,
+            tree_node_visited: Vec::new(),
+            dynamic_string_data: Vec::new(),
+            font_height_cache: FxHashMap::default(),
+            default_font_key: "",
+        };
+        ctx
+    }
+
+    fn get_open_layout_element(&self) -> usize {
+        let idx = *self.open_layout_element_stack.last().unwrap();
+        idx as usize
+    }
+
+    /// Returns the internal u32 id of the currently open element.
+    pub fn get_open_element_id(&self) -> u32 {
+        let open_idx = self.get_open_layout_element();
+        self.layout_elements[open_idx].id
+    }
+
+    pub fn get_parent_element_id(&self) -> u32 {
+        let stack_len = self.open_layout_element_stack.len();
+        let parent_idx = self.open_layout_element_stack[stack_len - 2] as usize;
+        self.layout_elements[parent_idx].id
+    }
+
+    fn add_hash_map_item(
+        &mut self,
+        element_id: &Id,
+        layout_element_index: i32,
+        location: Option<&'static Location<'static>>,
+    ) {
+        let gen = self.generation;
+        match self.layout_element_map.entry(element_id.id) {
+            std::collections::hash_map::Entry::Occupied(mut entry) => {
+                let item = entry.get_mut();
+                if item.generation <= gen {
+                    item.element_id = element_id.clone();
+                    item.generation = gen + 1;
+                    item.layout_element_index = layout_element_index;
+                    item.collision = false;
+                    self.pointer_callbacks.recycle(item.on_hover_fn.take());
+                    self.pointer_callbacks.recycle(item.on_press_fn.take());
+                    self.pointer_callbacks.recycle(item.on_release_fn.take());
+                    self.focus_callbacks.recycle(item.on_focus_fn.take());
+                    self.focus_callbacks.recycle(item.on_unfocus_fn.take());
+                    item.on_text_changed_fn = None;
+                    item.on_text_submit_fn = None;
+                    item.on_link_clicked_fn = None;
+                    #[cfg(feature = "clipboard")]
+                    {
+                        item.on_paste_image_fn = None;
                     }
-                }
-                if !found_existing {
-                    self.scroll_container_datas.push(ScrollContainerDataInternal {
-                        layout_element_index: open_idx as i32,
-                        scroll_origin: Vector2::new(-1.0, -1.0),
-                        scrollbar: clip.scrollbar,
-                        scroll_x_enabled: clip.scroll_x,
-                        scroll_y_enabled: clip.scroll_y,
-                        no_drag_scroll: clip.no_drag_scroll,
-                        element_id: elem_id,
-                        open_this_frame: true,
-                        ..Default::default()
+                    item.on_file_drop_fn = None;
+                    item.is_text_input = false;
+                    item.preserve_focus = false;
+                    item.location = location;
+                } else if !item.collision {
+                    item.collision = true;
+                    self.diagnostics.push(Diagnostic::DuplicateId {
+                        id: element_id.string_id.as_str().to_string(),
+                        location,
+                        first_id: item.element_id.string_id.as_str().to_string(),
+                        first_location: item.location,
                     });
                 }
             }
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(LayoutElementHashMapItem {
+                    element_id: element_id.clone(),
+                    layout_element_index,
+                    generation: gen + 1,
+                    bounding_box: BoundingBox::default(),
+                    on_hover_fn: None,
+                    on_press_fn: None,
+                    on_release_fn: None,
+                    on_focus_fn: None,
+                    on_unfocus_fn: None,
+                    on_text_changed_fn: None,
+                    on_text_submit_fn: None,
+                    on_link_clicked_fn: None,
+                    #[cfg(feature = "clipboard")]
+                    on_paste_image_fn: None,
+                    on_file_drop_fn: None,
+                    is_text_input: false,
+                    preserve_focus: false,
+                    collision: false,
+                    collapsed: false,
+                    text_clamped: false,
+                    z_index: 0,
+                    location,
+                });
+            }
         }
+    }
 
-        // Border config
-        if !declaration.border.width.is_zero() {
-            self.border_element_configs.push(declaration.border);
-            let idx = self.border_element_configs.len() - 1;
-            self.attach_element_config(ElementConfigType::Border, idx);
+    fn generate_id_for_anonymous_element(&mut self, open_element_index: usize) -> Id {
+        let stack_len = self.open_layout_element_stack.len();
+        let parent_idx = self.open_layout_element_stack[stack_len - 2] as usize;
+        let parent = &self.layout_elements[parent_idx];
+        let offset =
+            parent.children_length as u32 + parent.floating_children_count as u32;
+        let parent_id = parent.id;
+        let element_id = hash_number(offset, parent_id);
+        self.layout_elements[open_element_index].id = element_id.id;
+        self.add_hash_map_item(&element_id, open_element_index as i32, None);
+        if self.debug_mode_enabled {
+            self.layout_element_id_strings.push(element_id.string_id.clone());
         }
+        element_id
+    }
 
-        // Store per-element shader effects
-        // Ensure element_effects is large enough for open_idx
-        while self.element_effects.len() <= open_idx {
-            self.element_effects.push(Vec::new());
+    fn element_has_config(
+        &self,
+        element_index: usize,
+        config_type: ElementConfigType,
+    ) -> bool {
+        let element = &self.layout_elements[element_index];
+        let start = element.element_configs.start;
+        let length = element.element_configs.length;
+        for i in 0..length {
+            let config = &self.element_configs[start + i as usize];
+            if config.config_type == config_type {
+                return true;
+            }
         }
-        self.element_effects[open_idx] = declaration.effects.clone();
+        false
+    }
 
-        // Store per-element group shaders
-        while self.element_shaders.len() <= open_idx {
-            self.element_shaders.push(Vec::new());
+    fn find_element_config_index(
+        &self,
+        element_index: usize,
+        config_type: ElementConfigType,
+    ) -> Option<usize> {
+        let element = &self.layout_elements[element_index];
+        let start = element.element_configs.start;
+        let length = element.element_configs.length;
+        for i in 0..length {
+            let config = &self.element_configs[start + i as usize];
+            if config.config_type == config_type {
+                return Some(config.config_index);
+            }
         }
-        self.element_shaders[open_idx] = declaration.shaders.clone();
+        None
+    }
 
-        // Store per-element visual rotation
-        while self.element_visual_rotations.len() <= open_idx {
-            self.element_visual_rotations.push(None);
+    fn update_aspect_ratio_box(&mut self, element_index: usize) {
+        if let Some(config_idx) =
+            self.find_element_config_index(element_index, ElementConfigType::Aspect)
+        {
+            let aspect_ratio = self.aspect_ratio_configs[config_idx];
+            if aspect_ratio == 0.0 {
+                return;
+            }
+            let elem = &mut self.layout_elements[element_index];
+            if elem.dimensions.width == 0.0 && elem.dimensions.height != 0.0 {
+                elem.dimensions.width = elem.dimensions.height * aspect_ratio;
+            } else if elem.dimensions.width != 0.0 && elem.dimensions.height == 0.0 {
+                elem.dimensions.height = elem.dimensions.width * (1.0 / aspect_ratio);
+            }
         }
-        self.element_visual_rotations[open_idx] = declaration.visual_rotation;
+    }
 
-        // Store per-element shape rotation
-        while self.element_shape_rotations.len() <= open_idx {
-            self.element_shape_rotations.push(None);
+    pub fn store_text_element_config(
+        &mut self,
+        mut config: TextConfig,
+    ) -> usize {
+        let effects = self.store_shaders(std::mem::take(&mut config.effects));
+        self.text_element_effects.push(effects);
+        self.text_element_configs.push(config);
+        self.text_element_configs.len() - 1
+    }
+
+    /// Turns viewport-relative sizing into a fixed size for this frame's
+    /// layout dimensions, so layout never sees it.
+    fn resolve_viewport_sizing(&self, axis: SizingAxis) -> SizingAxis {
+        let size = match axis.type_ {
+            SizingType::ViewportWidth => self.layout_dimensions.width * axis.percent,
+            SizingType::ViewportHeight => self.layout_dimensions.height * axis.percent,
+            _ => return axis,
+        };
+        SizingAxis {
+            type_: SizingType::Fixed,
+            min_max: SizingMinMax { min: size, max: size },
+            percent: 0.0,
+            grow_weight: 1.0,
         }
-        self.element_shape_rotations[open_idx] = declaration.shape_rotation;
+    }
 
-        // Accessibility config
-        if let Some(ref a11y) = declaration.accessibility {
-            let elem_id = self.layout_elements[open_idx].id;
-            if a11y.focusable {
-                self.focusable_elements.push(FocusableEntry {
-                    element_id: elem_id,
-                    tab_index: a11y.tab_index,
-                    insertion_order: self.focusable_elements.len() as u32,
-                });
-            }
-            self.accessibility_configs.insert(elem_id, a11y.clone());
-            self.accessibility_element_order.push(elem_id);
+    fn store_layout_config(&mut self, config: LayoutConfig) -> usize {
+        self.layout_configs.push(config);
+        self.layout_configs.len() - 1
+    }
+
+    /// Folds what an element declares for sizing into the layout fingerprint.
+    /// Colors, borders and the like don't change sizes and are left out.
+    fn fingerprint_declaration(&mut self, element_id: u32, declaration: &ElementDeclaration<CustomElementData>) {
+        let hasher = &mut self.layout_fingerprint;
+        hasher.write_u32(element_id);
+        hash_layout_config(hasher, &declaration.layout);
+        hasher.write_u32(declaration.aspect_ratio.to_bits());
+        hasher.write_u8(declaration.cover_aspect_ratio as u8);
+        std::mem::discriminant(&declaration.floating.attach_to).hash(hasher);
+        hasher.write_u32(declaration.floating.parent_id);
+        hasher.write_u8(declaration.clip.horizontal as u8);
+        hasher.write_u8(declaration.clip.vertical as u8);
+        if let Some(rotation) = declaration.shape_rotation {
+            hasher.write_u32(rotation.rotation_radians.to_bits());
+        }
+    }
+
+    /// Folds a text element's content and metrics into the layout fingerprint.
+    fn fingerprint_text(&mut self, text: &str, config: &TextConfig) {
+        let hasher = &mut self.layout_fingerprint;
+        hasher.write_usize(text.len());
+        hasher.write(text.as_bytes());
+        hasher.write(config.font_asset.map_or("", |asset| asset.key()).as_bytes());
+        // Text measured while its font loads is remeasured once it's in
+        hasher.write_u8(config.font_asset.is_none_or(crate::renderer::FontManager::is_loaded) as u8);
+        hasher.write_u16(config.font_size);
+        hasher.write_u16(config.letter_spacing);
+        hasher.write_u16(config.line_height);
+        hasher.write_u16(config.max_lines);
+        hasher.write_usize(config.user_data);
+        std::mem::discriminant(&config.wrap_mode).hash(hasher);
+        std::mem::discriminant(&config.alignment).hash(hasher);
+        std::mem::discriminant(&config.inline_align).hash(hasher);
+        for atom in &config.inline_atoms {
+            hasher.write_u32(atom.width.to_bits());
+            hasher.write_u32(atom.height.to_bits());
         }
+    }
 
-        // Text input config
-        if let Some(ref ti_config) = declaration.text_input {
-            let elem_id = self.layout_elements[open_idx].id;
-            self.text_input_configs.push(ti_config.clone());
-            let idx = self.text_input_configs.len() - 1;
-            self.attach_element_config(ElementConfigType::TextInput, idx);
-            self.text_input_element_ids.push(elem_id);
+    fn store_shared_config(&mut self, config: SharedElementConfig) -> usize {
+        self.shared_element_configs.push(config);
+        self.shared_element_configs.len() - 1
+    }
 
-            // Mark the element as a text input in the layout map
-            if let Some(item) = self.layout_element_map.get_mut(&elem_id) {
-                item.is_text_input = true;
-            }
+    /// Moves shader configs into this frame's arena.
+    fn store_shaders(&mut self, shaders: Vec<ShaderConfig>) -> ShaderSlice {
+        let start = self.frame_shaders.len();
+        self.frame_shaders.extend(shaders);
+        ShaderSlice { start, length: self.frame_shaders.len() - start }
+    }
+
+    /// The shader configs a slice of this frame points at, such as a render
+    /// command's effects. Valid until the next `begin_layout`.
+    pub fn shaders(&self, slice: ShaderSlice) -> &[ShaderConfig] {
+        &self.frame_shaders[slice.range()]
+    }
+
+    fn element_effects_at(&self, element_index: usize) -> ShaderSlice {
+        self.element_effects.get(element_index).copied().unwrap_or_default()
+    }
+
+    fn element_shaders_at(&self, element_index: usize) -> ShaderSlice {
+        self.element_shaders.get(element_index).copied().unwrap_or_default()
+    }
+
+    fn attach_element_config(&mut self, config_type: ElementConfigType, config_index: usize) {
+        if self.max_elements_exceeded {
+            return;
+        }
+        let open_idx = self.get_open_layout_element();
+        self.layout_elements[open_idx].element_configs.length += 1;
+        self.element_configs.push(ElementConfig {
+            config_type,
+            config_index,
+        });
+    }
 
-            // Ensure a TextEditState exists for this element
-            self.text_edit_states.entry(elem_id)
-                .or_insert_with(crate::text_input::TextEditState::default);
+    /// Checks `max_element_count` before another element is added, reporting
+    /// the first time it is hit.
+    fn element_limit_reached(&mut self) -> bool {
+        if !self.max_elements_exceeded && self.layout_elements.len() >= self.max_element_count.max(0) as usize {
+            self.max_elements_exceeded = true;
+            self.diagnostics.push(Diagnostic::MaxElementsExceeded { max: self.max_element_count.max(0) as u32 });
+        }
+        self.max_elements_exceeded
+    }
 
-            self.text_input_scrollbar_idle_frames
-                .entry(elem_id)
-                .or_insert(0);
+    pub fn open_element(&mut self) {
+        if self.element_limit_reached() {
+            return;
+        }
+        let elem = LayoutElement {
+            text_data_index: -1,
+            ..Default::default()
+        };
+        self.layout_elements.push(elem);
+        let idx = (self.layout_elements.len() - 1) as i32;
+        self.open_layout_element_stack.push(idx);
 
-            // Sync config flags to persistent state
-            if let Some(state) = self.text_edit_states.get_mut(&elem_id) {
-                state.no_styles_movement = ti_config.no_styles_movement;
-            }
+        // Ensure clip IDs array is large enough
+        while self.layout_element_clip_element_ids.len() < self.layout_elements.len() {
+            self.layout_element_clip_element_ids.push(0);
+        }
 
-            // Process any pending click on this text input
-            if let Some((click_elem, click_x, click_y, click_shift)) = self.pending_text_click.take() {
-                if click_elem == elem_id {
-                    if let Some(ref measure_fn) = self.measure_text_fn {
-                        let state = self.text_edit_states.get(&elem_id).cloned()
-                            .unwrap_or_default();
-                        let disp_text = crate::text_input::display_text(
-                            &state.text,
-                            &ti_config.placeholder,
-                            ti_config.is_password,
-                        );
-                        // Only position cursor in actual text, not placeholder
-                        if !state.text.is_empty() {
-                            // Double-click detection
-                            let is_double_click = state.last_click_element == elem_id
-                                && (self.current_time - state.last_click_time) < 0.4;
+        self.generate_id_for_anonymous_element(idx as usize);
 
-                            if ti_config.is_multiline {
-                                // Multiline: determine which visual line was clicked
-                                let elem_width = self.layout_element_map.get(&elem_id)
-                                    .map(|item| item.bounding_box.width)
-                                    .unwrap_or(200.0);
-                                let visual_lines = crate::text_input::wrap_lines(
-                                    &disp_text,
-                                    elem_width,
-                                    ti_config.font_asset,
-                                    ti_config.font_size,
-                                    measure_fn.as_ref(),
-                                );
-                                let font_height = if ti_config.line_height > 0 {
-                                    ti_config.line_height as f32
-                                } else {
-                                    let config = crate::text::TextConfig {
-                                        font_asset: ti_config.font_asset,
-                                        font_size: ti_config.font_size,
-                                        ..Default::default()
-                                    };
-                                    measure_fn(&"Mg", &config).height
-                                };
-                                let adjusted_y = click_y + state.scroll_offset_y;
-                                let clicked_line = (adjusted_y / font_height).floor().max(0.0) as usize;
-                                let clicked_line = clicked_line.min(visual_lines.len().saturating_sub(1));
+        if !self.open_clip_element_stack.is_empty() {
+            let clip_id = *self.open_clip_element_stack.last().unwrap();
+            self.layout_element_clip_element_ids[idx as usize] = clip_id;
+        } else {
+            self.layout_element_clip_element_ids[idx as usize] = 0;
+        }
+    }
 
-                                let vl = &visual_lines[clicked_line];
-                                let line_char_x_positions = crate::text_input::compute_char_x_positions(
-                                    &vl.text,
-                                    ti_config.font_asset,
-                                    ti_config.font_size,
-                                    measure_fn.as_ref(),
-                                );
-                                let col = crate::text_input::find_nearest_char_boundary(
-                                    click_x, &line_char_x_positions,
-                                );
-                                let global_pos = vl.global_char_start + col;
+    pub fn open_element_with_id(&mut self, element_id: &Id) {
+        self.open_element_with_id_at(element_id, None);
+    }
 
-                                if let Some(state) = self.text_edit_states.get_mut(&elem_id) {
-                                    #[cfg(feature = "text-styling")]
-                                    {
-                                        let visual_pos = crate::text_input::styling::raw_to_cursor(&state.text, global_pos);
-                                        if is_double_click {
-                                            state.select_word_at_styled(visual_pos);
-                                        } else {
-                                            state.click_to_cursor_styled(visual_pos, click_shift);
-                                        }
-                                    }
-                                    #[cfg(not(feature = "text-styling"))]
-                                    {
-                                        if is_double_click {
-                                            state.select_word_at(global_pos);
-                                        } else {
-                                            if click_shift {
-                                                if state.selection_anchor.is_none() {
-                                                    state.selection_anchor = Some(state.cursor_pos);
-                                                }
-                                            } else {
-                                                state.selection_anchor = None;
-                                            }
-                                            state.cursor_pos = global_pos;
-                                            state.reset_blink();
-                                        }
-                                    }
-                                    state.last_click_time = self.current_time;
-                                    state.last_click_element = elem_id;
-                                }
-                            } else {
-                                // Single-line: existing behavior
-                                let char_x_positions = crate::text_input::compute_char_x_positions(
-                                    &disp_text,
-                                    ti_config.font_asset,
-                                    ti_config.font_size,
-                                    measure_fn.as_ref(),
-                                );
-                                let adjusted_x = click_x + state.scroll_offset;
+    /// Like `open_element_with_id`, remembering where the id was set so a
+    /// duplicate can point at both declarations.
+    pub fn open_element_with_id_at(&mut self, element_id: &Id, location: Option<&'static Location<'static>>) {
+        if self.element_limit_reached() {
+            return;
+        }
+        let mut elem = LayoutElement {
+            text_data_index: -1,
+            ..Default::default()
+        };
+        elem.id = element_id.id;
+        self.layout_elements.push(elem);
+        let idx = (self.layout_elements.len() - 1) as i32;
+        self.open_layout_element_stack.push(idx);
 
-                                if let Some(state) = self.text_edit_states.get_mut(&elem_id) {
-                                    let raw_click_pos = crate::text_input::find_nearest_char_boundary(
-                                        adjusted_x, &char_x_positions,
-                                    );
-                                    #[cfg(feature = "text-styling")]
-                                    {
-                                        let visual_pos = crate::text_input::styling::raw_to_cursor(&state.text, raw_click_pos);
-                                        if is_double_click {
-                                            state.select_word_at_styled(visual_pos);
-                                        } else {
-                                            state.click_to_cursor_styled(visual_pos, click_shift);
-                                        }
-                                    }
-                                    #[cfg(not(feature = "text-styling"))]
-                                    {
-                                        if is_double_click {
-                                            state.select_word_at(raw_click_pos);
-                                        } else {
-                                            state.click_to_cursor(adjusted_x, &char_x_positions, click_shift);
-                                        }
-                                    }
-                                    state.last_click_time = self.current_time;
-                                    state.last_click_element = elem_id;
-                                }
-                            }
-                        } else if let Some(state) = self.text_edit_states.get_mut(&elem_id) {
-                            state.cursor_pos = 0;
-                            state.selection_anchor = None;
-                            state.last_click_time = self.current_time;
-                            state.last_click_element = elem_id;
-                            state.reset_blink();
-                        }
-                    }
-                } else {
-                    // Wasn't for this element, put it back
-                    self.pending_text_click = Some((click_elem, click_x, click_y, click_shift));
-                }
-            }
+        while self.layout_element_clip_element_ids.len() < self.layout_elements.len() {
+            self.layout_element_clip_element_ids.push(0);
+        }
 
-            // Auto-register as focusable if not already done via accessibility
-            if declaration.accessibility.is_none() || !declaration.accessibility.as_ref().unwrap().focusable {
-                // Check it's not already registered
-                let already = self.focusable_elements.iter().any(|e| e.element_id == elem_id);
-                if !already {
-                    self.focusable_elements.push(FocusableEntry {
-                        element_id: elem_id,
-                        tab_index: None,
-                        insertion_order: self.focusable_elements.len() as u32,
-                    });
-                }
-            }
+        self.add_hash_map_item(element_id, idx, location);
+        if self.debug_mode_enabled {
+            self.layout_element_id_strings.push(element_id.string_id.clone());
         }
 
-        // Preserve-focus flag
-        if declaration.preserve_focus {
-            let elem_id = self.layout_elements[open_idx].id;
-            if let Some(item) = self.layout_element_map.get_mut(&elem_id) {
-                item.preserve_focus = true;
-            }
+        if !self.open_clip_element_stack.is_empty() {
+            let clip_id = *self.open_clip_element_stack.last().unwrap();
+            self.layout_element_clip_element_ids[idx as usize] = clip_id;
+        } else {
+            self.layout_element_clip_element_ids[idx as usize] = 0;
         }
     }
 
-    pub fn close_element(&mut self) {
-        if self.boolean_warnings.max_elements_exceeded {
+    /// Configures the open element, moving the declaration's effects,
+    /// shaders and other owned data into the frame without copying them.
+    pub fn configure_open_element(&mut self, mut declaration: ElementDeclaration<CustomElementData>) {
+        if self.max_elements_exceeded {
             return;
         }
-
+        declaration.layout.sizing.width = self.resolve_viewport_sizing(declaration.layout.sizing.width);
+        declaration.layout.sizing.height = self.resolve_viewport_sizing(declaration.layout.sizing.height);
         let open_idx = self.get_open_layout_element();
-        let layout_config_index = self.layout_elements[open_idx].layout_config_index;
-        let layout_config = self.layout_configs[layout_config_index];
+        self.fingerprint_declaration(self.layout_elements[open_idx].id, &declaration);
+        let layout_config_index = self.store_layout_config(declaration.layout);
+        self.layout_elements[open_idx].layout_config_index = layout_config_index;
 
-        // Check for clip and floating configs
-        let mut element_has_clip_horizontal = false;
-        let mut element_has_clip_vertical = false;
-        let element_configs_start = self.layout_elements[open_idx].element_configs.start;
-        let element_configs_length = self.layout_elements[open_idx].element_configs.length;
+        // Record the start of element configs for this element
+        self.layout_elements[open_idx].element_configs.start = self.element_configs.len();
 
-        for i in 0..element_configs_length {
-            let config = &self.element_configs[element_configs_start + i as usize];
-            if config.config_type == ElementConfigType::Clip {
-                let clip = &self.clip_element_configs[config.config_index];
-                element_has_clip_horizontal = clip.horizontal;
-                element_has_clip_vertical = clip.vertical;
-                self.open_clip_element_stack.pop();
-                break;
-            } else if config.config_type == ElementConfigType::Floating {
-                self.open_clip_element_stack.pop();
+        // Shared config (background color, corner radius, user data)
+        let mut shared_config_index: Option<usize> = None;
+        if declaration.background_color.a > 0.0 {
+            let idx = self.store_shared_config(SharedElementConfig {
+                background_color: declaration.background_color,
+                corner_radius: CornerRadius::default(),
+                user_data: 0,
+            });
+            shared_config_index = Some(idx);
+            self.attach_element_config(ElementConfigType::Shared, idx);
+        }
+        if !declaration.corner_radius.is_zero() {
+            if let Some(idx) = shared_config_index {
+                self.shared_element_configs[idx].corner_radius = declaration.corner_radius;
+            } else {
+                let idx = self.store_shared_config(SharedElementConfig {
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+                    corner_radius: declaration.corner_radius,
+                    user_data: 0,
+                });
+                shared_config_index = Some(idx);
+                self.attach_element_config(ElementConfigType::Shared, idx);
+            }
+        }
+        if declaration.user_data != 0 {
+            if let Some(idx) = shared_config_index {
+                self.shared_element_configs[idx].user_data = declaration.user_data;
+            } else {
+                let idx = self.store_shared_config(SharedElementConfig {
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+                    corner_radius: CornerRadius::default(),
+                    user_data: declaration.user_data,
+                });
+                self.attach_element_config(ElementConfigType::Shared, idx);
             }
         }
 
-        let left_right_padding =
-            (layout_config.padding.left + layout_config.padding.right) as f32;
-        let top_bottom_padding =
-            (layout_config.padding.top + layout_config.padding.bottom) as f32;
-
-        let children_length = self.layout_elements[open_idx].children_length;
+        // Image config
+        if let Some(image_data) = declaration.image_data {
+            self.image_element_configs.push(image_data);
+            let idx = self.image_element_configs.len() - 1;
+            self.attach_element_config(ElementConfigType::Image, idx);
+        }
 
-        // Attach children to the current open element
-        let children_start = self.layout_element_children.len();
-        self.layout_elements[open_idx].children_start = children_start;
+        // Aspect ratio config
+        if declaration.aspect_ratio > 0.0 {
+            self.aspect_ratio_configs.push(declaration.aspect_ratio);
+            self.aspect_ratio_cover_configs
+                .push(declaration.cover_aspect_ratio);
+            let idx = self.aspect_ratio_configs.len() - 1;
+            self.attach_element_config(ElementConfigType::Aspect, idx);
+            self.aspect_ratio_element_indexes
+                .push((self.layout_elements.len() - 1) as i32);
+        }
 
-        if layout_config.layout_direction == LayoutDirection::LeftToRight {
-            self.layout_elements[open_idx].dimensions.width = left_right_padding;
-            self.layout_elements[open_idx].min_dimensions.width = left_right_padding;
+        // Floating config
+        if declaration.floating.attach_to != FloatingAttachToElement::None {
+            let mut floating_config = declaration.floating;
+            let stack_len = self.open_layout_element_stack.len();
 
-            for i in 0..children_length {
-                let buf_idx = self.layout_element_children_buffer.len()
-                    - children_length as usize
-                    + i as usize;
-                let child_index = self.layout_element_children_buffer[buf_idx];
-                let child = &self.layout_elements[child_index as usize];
-                let child_width = child.dimensions.width;
-                let child_height = child.dimensions.height;
-                let child_min_width = child.min_dimensions.width;
-                let child_min_height = child.min_dimensions.height;
+            if stack_len >= 2 {
+                let hierarchical_parent_idx =
+                    self.open_layout_element_stack[stack_len - 2] as usize;
+                let hierarchical_parent_id = self.layout_elements[hierarchical_parent_idx].id;
 
-                self.layout_elements[open_idx].dimensions.width += child_width;
-                let current_height = self.layout_elements[open_idx].dimensions.height;
-                self.layout_elements[open_idx].dimensions.height =
-                    f32::max(current_height, child_height + top_bottom_padding);
+                let mut clip_element_id: u32 = 0;
 
-                if !element_has_clip_horizontal {
-                    self.layout_elements[open_idx].min_dimensions.width += child_min_width;
+                if declaration.floating.attach_to == FloatingAttachToElement::Parent {
+                    floating_config.parent_id = hierarchical_parent_id;
+                    if !self.open_clip_element_stack.is_empty() {
+                        clip_element_id =
+                            *self.open_clip_element_stack.last().unwrap() as u32;
+                    }
+                } else if declaration.floating.attach_to
+                    == FloatingAttachToElement::ElementWithId
+                {
+                    if let Some(parent_item) =
+                        self.layout_element_map.get(&floating_config.parent_id)
+                    {
+                        let parent_elem_idx = parent_item.layout_element_index as usize;
+                        clip_element_id =
+                            self.layout_element_clip_element_ids[parent_elem_idx] as u32;
+                    }
+                } else if declaration.floating.attach_to == FloatingAttachToElement::Root
+                    || declaration.floating.attach_to == FloatingAttachToElement::Pointer
+                {
+                    floating_config.parent_id =
+                        hash_string("Ply__RootContainer", 0).id;
                 }
-                if !element_has_clip_vertical {
-                    let current_min_h = self.layout_elements[open_idx].min_dimensions.height;
-                    self.layout_elements[open_idx].min_dimensions.height =
-                        f32::max(current_min_h, child_min_height + top_bottom_padding);
+
+                if declaration.floating.clip_to == FloatingClipToElement::None {
+                    clip_element_id = 0;
                 }
-                self.layout_element_children.push(child_index);
-            }
-            let child_gap =
-                (children_length.saturating_sub(1) as u32 * layout_config.child_gap as u32) as f32;
-            self.layout_elements[open_idx].dimensions.width += child_gap;
-            if !element_has_clip_horizontal {
-                self.layout_elements[open_idx].min_dimensions.width += child_gap;
-            }
-        } else {
-            // TopToBottom
-            self.layout_elements[open_idx].dimensions.height = top_bottom_padding;
-            self.layout_elements[open_idx].min_dimensions.height = top_bottom_padding;
 
-            for i in 0..children_length {
-                let buf_idx = self.layout_element_children_buffer.len()
-                    - children_length as usize
-                    + i as usize;
-                let child_index = self.layout_element_children_buffer[buf_idx];
-                let child = &self.layout_elements[child_index as usize];
-                let child_width = child.dimensions.width;
-                let child_height = child.dimensions.height;
-                let child_min_width = child.min_dimensions.width;
-                let child_min_height = child.min_dimensions.height;
+                let current_element_index =
+                    *self.open_layout_element_stack.last().unwrap();
+                let current_id = self.layout_elements[current_element_index as usize].id;
+                floating_config.z_index = floating_config.z_index.saturating_add(self.portal_z_index);
+                if let Some(&z_index) = self.z_index_overrides.get(&current_id) {
+                    floating_config.z_index = z_index;
+                }
+                self.layout_element_clip_element_ids[current_element_index as usize] =
+                    clip_element_id as i32;
+                self.open_clip_element_stack.push(clip_element_id as i32);
 
-                self.layout_elements[open_idx].dimensions.height += child_height;
-                let current_width = self.layout_elements[open_idx].dimensions.width;
-                self.layout_elements[open_idx].dimensions.width =
-                    f32::max(current_width, child_width + left_right_padding);
+                self.layout_element_tree_roots
+                    .push(LayoutElementTreeRoot {
+                        layout_element_index: current_element_index,
+                        parent_id: floating_config.parent_id,
+                        clip_element_id,
+                        z_index: floating_config.z_index,
+                        pointer_offset: Vector2::default(),
+                    });
 
-                if !element_has_clip_vertical {
-                    self.layout_elements[open_idx].min_dimensions.height += child_min_height;
-                }
-                if !element_has_clip_horizontal {
-                    let current_min_w = self.layout_elements[open_idx].min_dimensions.width;
-                    self.layout_elements[open_idx].min_dimensions.width =
-                        f32::max(current_min_w, child_min_width + left_right_padding);
-                }
-                self.layout_element_children.push(child_index);
-            }
-            let child_gap =
-                (children_length.saturating_sub(1) as u32 * layout_config.child_gap as u32) as f32;
-            self.layout_elements[open_idx].dimensions.height += child_gap;
-            if !element_has_clip_vertical {
-                self.layout_elements[open_idx].min_dimensions.height += child_gap;
+                self.floating_element_configs.push(floating_config);
+                let idx = self.floating_element_configs.len() - 1;
+                self.attach_element_config(ElementConfigType::Floating, idx);
             }
         }
 
-        // Remove children from buffer
-        let remove_count = children_length as usize;
-        let new_len = self.layout_element_children_buffer.len().saturating_sub(remove_count);
-        self.layout_element_children_buffer.truncate(new_len);
+        // Custom config
+        if let Some(custom_data) = declaration.custom_data {
+            self.custom_element_configs.push(custom_data);
+            let idx = self.custom_element_configs.len() - 1;
+            self.attach_element_config(ElementConfigType::Custom, idx);
+        }
 
-        // Clamp width
-        {
-            let sizing_type = self.layout_configs[layout_config_index].sizing.width.type_;
-            if sizing_type != SizingType::Percent {
-                let mut max_w = self.layout_configs[layout_config_index].sizing.width.min_max.max;
-                if max_w <= 0.0 {
-                    max_w = MAXFLOAT;
-                    self.layout_configs[layout_config_index].sizing.width.min_max.max = max_w;
-                }
-                let min_w = self.layout_configs[layout_config_index].sizing.width.min_max.min;
-                self.layout_elements[open_idx].dimensions.width = f32::min(
-                    f32::max(self.layout_elements[open_idx].dimensions.width, min_w),
-                    max_w,
-                );
-                self.layout_elements[open_idx].min_dimensions.width = f32::min(
-                    f32::max(self.layout_elements[open_idx].min_dimensions.width, min_w),
-                    max_w,
-                );
-            } else {
-                self.layout_elements[open_idx].dimensions.width = 0.0;
-            }
+        // Canvas config
+        if let Some(commands) = declaration.canvas {
+            self.canvas_element_configs.push(commands);
+            let idx = self.canvas_element_configs.len() - 1;
+            self.attach_element_config(ElementConfigType::Canvas, idx);
         }
 
-        // Clamp height
-        {
-            let sizing_type = self.layout_configs[layout_config_index].sizing.height.type_;
-            if sizing_type != SizingType::Percent {
-                let mut max_h = self.layout_configs[layout_config_index].sizing.height.min_max.max;
-                if max_h <= 0.0 {
-                    max_h = MAXFLOAT;
-                    self.layout_configs[layout_config_index].sizing.height.min_max.max = max_h;
-                }
-                let min_h = self.layout_configs[layout_config_index].sizing.height.min_max.min;
-                self.layout_elements[open_idx].dimensions.height = f32::min(
-                    f32::max(self.layout_elements[open_idx].dimensions.height, min_h),
-                    max_h,
-                );
-                self.layout_elements[open_idx].min_dimensions.height = f32::min(
-                    f32::max(self.layout_elements[open_idx].min_dimensions.height, min_h),
-                    max_h,
-                );
-            } else {
-                self.layout_elements[open_idx].dimensions.height = 0.0;
-            }
+        // Draw hook config
+        if let Some(hook) = declaration.on_draw {
+            self.draw_hook_configs.push(hook);
+            let idx = self.draw_hook_configs.len() - 1;
+            self.attach_element_config(ElementConfigType::Draw, idx);
         }
 
-        self.update_aspect_ratio_box(open_idx);
+        // Clip config
+        if declaration.clip.horizontal || declaration.clip.vertical {
+            let mut clip = declaration.clip;
 
-        // Apply shape rotation AABB expansion
-        if let Some(shape_rot) = self.element_shape_rotations.get(open_idx).copied().flatten() {
-            if !shape_rot.is_noop() {
-                let orig_w = self.layout_elements[open_idx].dimensions.width;
-                let orig_h = self.layout_elements[open_idx].dimensions.height;
+            let elem_id = self.layout_elements[open_idx].id;
 
-                // Find corner radius for this element
-                let cr = self
-                    .find_element_config_index(open_idx, ElementConfigType::Shared)
-                    .map(|idx| self.shared_element_configs[idx].corner_radius)
-                    .unwrap_or_default();
+            // Auto-apply stored scroll position as child_offset
+            if clip.scroll_x || clip.scroll_y {
+                for scd in &self.scroll_container_datas {
+                    if scd.element_id == elem_id {
+                        clip.child_offset = scd.scroll_position;
+                        break;
+                    }
+                }
+            }
 
-                let (eff_w, eff_h) = crate::math::compute_rotated_aabb(
-                    orig_w,
-                    orig_h,
-                    &cr,
-                    shape_rot.rotation_radians,
-                );
+            self.clip_element_configs.push(clip);
+            let idx = self.clip_element_configs.len() - 1;
+            self.attach_element_config(ElementConfigType::Clip, idx);
 
-                // Store original dimensions for renderer
-                while self.element_pre_rotation_dimensions.len() <= open_idx {
-                    self.element_pre_rotation_dimensions.push(None);
-                }
-                self.element_pre_rotation_dimensions[open_idx] =
-                    Some(Dimensions::new(orig_w, orig_h));
+            self.open_clip_element_stack.push(elem_id as i32);
 
-                // Replace layout dimensions with AABB
-                self.layout_elements[open_idx].dimensions.width = eff_w;
-                self.layout_elements[open_idx].dimensions.height = eff_h;
-                self.layout_elements[open_idx].min_dimensions.width = eff_w;
-                self.layout_elements[open_idx].min_dimensions.height = eff_h;
+            // Track scroll container
+            if clip.scroll_x || clip.scroll_y {
+                let mut found_existing = false;
+                for scd in &mut self.scroll_container_datas {
+                    if elem_id == scd.element_id {
+                        scd.layout_element_index = open_idx as i32;
+                        scd.open_this_frame = true;
+                        scd.scrollbar = clip.scrollbar;
+                        scd.scroll_x_enabled = clip.scroll_x;
+                        scd.scroll_y_enabled = clip.scroll_y;
+                        scd.no_drag_scroll = clip.no_drag_scroll;
+                        found_existing = true;
+                        break;
+                    }
+                }
+                if !found_existing {
+                    self.scroll_container_datas.push(ScrollContainerDataInternal {
+                        layout_element_index: open_idx as i32,
+                        scroll_origin: Vector2::new(-1.0, -1.0),
+                        scrollbar: clip.scrollbar,
+                        scroll_x_enabled: clip.scroll_x,
+                        scroll_y_enabled: clip.scroll_y,
+                        no_drag_scroll: clip.no_drag_scroll,
+                        element_id: elem_id,
+                        open_this_frame: true,
+                        ..Default::default()
+                    });
+                }
             }
         }
 
-        let element_is_floating =
-            self.element_has_config(open_idx, ElementConfigType::Floating);
+        // Border config
+        if !declaration.border.width.is_zero() {
+            self.border_element_configs.push(declaration.border);
+            let idx = self.border_element_configs.len() - 1;
+            self.attach_element_config(ElementConfigType::Border, idx);
+        }
+
+        // Store per-element shader effects and group shaders
+        if self.element_effects.len() <= open_idx {
+            self.element_effects.resize(open_idx + 1, ShaderSlice::default());
+            self.element_shaders.resize(open_idx + 1, ShaderSlice::default());
+        }
+        self.element_effects[open_idx] = self.store_shaders(declaration.effects);
+        self.element_shaders[open_idx] = self.store_shaders(declaration.shaders);
 
-        // Pop from open stack
-        self.open_layout_element_stack.pop();
+        // Store per-element visual rotation
+        while self.element_visual_rotations.len() <= open_idx {
+            self.element_visual_rotations.push(None);
+        }
+        self.element_visual_rotations[open_idx] = declaration.visual_rotation;
 
-        // Add to parent's children
-        if self.open_layout_element_stack.len() > 1 {
-            if element_is_floating {
-                let parent_idx = self.get_open_layout_element();
-                self.layout_elements[parent_idx].floating_children_count += 1;
-                return;
-            }
-            let parent_idx = self.get_open_layout_element();
-            self.layout_elements[parent_idx].children_length += 1;
-            self.layout_element_children_buffer.push(open_idx as i32);
+        // Store per-element shape rotation
+        while self.element_shape_rotations.len() <= open_idx {
+            self.element_shape_rotations.push(None);
         }
-    }
+        self.element_shape_rotations[open_idx] = declaration.shape_rotation;
 
-    pub fn open_text_element(
-        &mut self,
-        text: &str,
-        text_config_index: usize,
-    ) {
-        if self.boolean_warnings.max_elements_exceeded {
-            return;
+        // Accessibility config
+        let declared_focusable = declaration.accessibility.as_ref().is_some_and(|a11y| a11y.focusable);
+        if let Some(a11y) = declaration.accessibility.filter(|a11y| !a11y.decorative) {
+            let elem_id = self.layout_elements[open_idx].id;
+            if a11y.focusable {
+                self.register_focusable(elem_id, a11y.tab_index);
+            }
+            self.accessibility_configs.insert(elem_id, a11y);
+            self.accessibility_element_order.push(elem_id);
         }
 
-        let parent_idx = self.get_open_layout_element();
-        let parent_id = self.layout_elements[parent_idx].id;
-        let parent_children_count = self.layout_elements[parent_idx].children_length;
+        // Text input config
+        if let Some(ref ti_config) = declaration.text_input {
+            let elem_id = self.layout_elements[open_idx].id;
+            self.text_input_configs.push(ti_config.clone());
+            let idx = self.text_input_configs.len() - 1;
+            self.attach_element_config(ElementConfigType::TextInput, idx);
+            self.text_input_element_ids.push(elem_id);
 
-        // Create text layout element
-        let text_element = LayoutElement {
-            text_data_index: -1,
-            ..Default::default()
-        };
-        self.layout_elements.push(text_element);
-        let text_elem_idx = (self.layout_elements.len() - 1) as i32;
+            // Mark the element as a text input in the layout map
+            if let Some(item) = self.layout_element_map.get_mut(&elem_id) {
+                item.is_text_input = true;
+            }
 
-        while self.layout_element_clip_element_ids.len() < self.layout_elements.len() {
-            self.layout_element_clip_element_ids.push(0);
-        }
-        if !self.open_clip_element_stack.is_empty() {
-            let clip_id = *self.open_clip_element_stack.last().unwrap();
-            self.layout_element_clip_element_ids[text_elem_idx as usize] = clip_id;
-        } else {
-            self.layout_element_clip_element_ids[text_elem_idx as usize] = 0;
-        }
+            // Ensure a TextEditState exists for this element
+            self.text_edit_states.entry(elem_id)
+                .or_default();
 
-        self.layout_element_children_buffer.push(text_elem_idx);
+            self.text_input_scrollbar_idle_frames
+                .entry(elem_id)
+                .or_insert(0);
 
-        // Measure text
-        let text_config = self.text_element_configs[text_config_index].clone();
-        let text_measured =
-            self.measure_text_cached(text, &text_config);
+            // Sync config flags to persistent state
+            if let Some(state) = self.text_edit_states.get_mut(&elem_id) {
+                state.no_styles_movement = ti_config.no_styles_movement;
+            }
 
-        let element_id = hash_number(parent_children_count as u32, parent_id);
-        self.layout_elements[text_elem_idx as usize].id = element_id.id;
-        self.add_hash_map_item(&element_id, text_elem_idx);
-        if self.debug_mode_enabled {
-            self.layout_element_id_strings.push(element_id.string_id);
-        }
+            // Process any pending click on this text input
+            if let Some((click_elem, click_x, click_y, click_shift)) = self.pending_text_click.take() {
+                if click_elem == elem_id {
+                    if let Some(ref measure_fn) = self.measure_text_fn {
+                        let state = self.text_edit_states.get(&elem_id).cloned()
+                            .unwrap_or_default();
+                        let disp_text = crate::text_input::display_text(
+                            &state.text,
+                            &ti_config.placeholder,
+                            ti_config.is_password,
+                        );
+                        // Only position cursor in actual text, not placeholder
+                        if !state.text.is_empty() {
+                            // Double-click detection
+                            let is_double_click = state.last_click_element == elem_id
+                                && (self.current_time - state.last_click_time) < 0.4;
 
-        // If the text element is marked accessible, register it in the
-        // accessibility tree with a StaticText role and the text content
-        // as the label.
-        if text_config.accessible {
-            let a11y = crate::accessibility::AccessibilityConfig {
-                role: crate::accessibility::AccessibilityRole::StaticText,
-                label: text.to_string(),
-                ..Default::default()
-            };
-            self.accessibility_configs.insert(element_id.id, a11y);
-            self.accessibility_element_order.push(element_id.id);
-        }
+                            if ti_config.is_multiline {
+                                // Multiline: determine which visual line was clicked
+                                let elem_width = self.layout_element_map.get(&elem_id)
+                                    .map(|item| item.bounding_box.width)
+                                    .unwrap_or(200.0);
+                                let visual_lines = crate::text_input::wrap_lines(
+                                    &disp_text,
+                                    elem_width,
+                                    ti_config.font_asset,
+                                    ti_config.font_size,
+                                    measure_fn.as_ref(),
+                                );
+                                let font_height = if ti_config.line_height > 0 {
+                                    ti_config.line_height as f32
+                                } else {
+                                    let config = crate::text::TextConfig {
+                                        font_asset: ti_config.font_asset,
+                                        font_size: ti_config.font_size,
+                                        ..Default::default()
+                                    };
+                                    measure_fn(&"Mg", &config).height
+                                };
+                                let adjusted_y = click_y + state.scroll_offset_y;
+                                let clicked_line = (adjusted_y / font_height).floor().max(0.0) as usize;
+                                let clicked_line = clicked_line.min(visual_lines.len().saturating_sub(1));
 
-        let text_width = text_measured.unwrapped_dimensions.width;
-        let text_height = if text_config.line_height > 0 {
-            text_config.line_height as f32
-        } else {
-            text_measured.unwrapped_dimensions.height
-        };
-        let min_width = text_measured.min_width;
+                                let vl = &visual_lines[clicked_line];
+                                let line_char_x_positions = crate::text_input::compute_char_x_positions(
+                                    &vl.text,
+                                    ti_config.font_asset,
+                                    ti_config.font_size,
+                                    measure_fn.as_ref(),
+                                );
+                                let col = crate::text_input::find_nearest_char_boundary(
+                                    click_x, &line_char_x_positions,
+                                );
+                                let global_pos = vl.global_char_start + col;
 
-        self.layout_elements[text_elem_idx as usize].dimensions =
-            Dimensions::new(text_width, text_height);
-        self.layout_elements[text_elem_idx as usize].min_dimensions =
-            Dimensions::new(min_width, text_height);
+                                if let Some(state) = self.text_edit_states.get_mut(&elem_id) {
+                                    #[cfg(feature = "text-styling")]
+                                    {
+                                        let visual_pos = crate::text_input::styling::raw_to_cursor(&state.text, global_pos);
+                                        if is_double_click {
+                                            state.select_word_at_styled(visual_pos);
+                                        } else {
+                                            state.click_to_cursor_styled(visual_pos, click_shift);
+                                        }
+                                    }
+                                    #[cfg(not(feature = "text-styling"))]
+                                    {
+                                        if is_double_click {
+                                            state.select_word_at(global_pos);
+                                        } else {
+                                            if click_shift {
+                                                if state.selection_anchor.is_none() {
+                                                    state.selection_anchor = Some(state.cursor_pos);
+                                                }
+                                            } else {
+                                                state.selection_anchor = None;
+                                            }
+                                            state.cursor_pos = global_pos;
+                                            state.reset_blink();
+                                        }
+                                    }
+                                    state.last_click_time = self.current_time;
+                                    state.last_click_element = elem_id;
+                                }
+                            } else {
+                                // Single-line: existing behavior
+                                let char_x_positions = crate::text_input::compute_char_x_positions(
+                                    &disp_text,
+                                    ti_config.font_asset,
+                                    ti_config.font_size,
+                                    measure_fn.as_ref(),
+                                );
+                                let adjusted_x = click_x + state.scroll_offset;
 
-        // Store text element data
-        let text_data = TextElementData {
-            text: text.to_string(),
-            preferred_dimensions: text_measured.unwrapped_dimensions,
-            element_index: text_elem_idx,
-            wrapped_lines_start: 0,
-            wrapped_lines_length: 0,
-        };
-        self.text_element_data.push(text_data);
-        let text_data_idx = (self.text_element_data.len() - 1) as i32;
-        self.layout_elements[text_elem_idx as usize].text_data_index = text_data_idx;
+                                if let Some(state) = self.text_edit_states.get_mut(&elem_id) {
+                                    let raw_click_pos = crate::text_input::find_nearest_char_boundary(
+                                        adjusted_x, &char_x_positions,
+                                    );
+                                    #[cfg(feature = "text-styling")]
+                                    {
+                                        let visual_pos = crate::text_input::styling::raw_to_cursor(&state.text, raw_click_pos);
+                                        if is_double_click {
+                                            state.select_word_at_styled(visual_pos);
+                                        } else {
+                                            state.click_to_cursor_styled(visual_pos, click_shift);
+                                        }
+                                    }
+                                    #[cfg(not(feature = "text-styling"))]
+                                    {
+                                        if is_double_click {
+                                            state.select_word_at(raw_click_pos);
+                                        } else {
+                                            state.click_to_cursor(adjusted_x, &char_x_positions, click_shift);
+                                        }
+                                    }
+                                    state.last_click_time = self.current_time;
+                                    state.last_click_element = elem_id;
+                                }
+                            }
+                        } else if let Some(state) = self.text_edit_states.get_mut(&elem_id) {
+                            state.cursor_pos = 0;
+                            state.selection_anchor = None;
+                            state.last_click_time = self.current_time;
+                            state.last_click_element = elem_id;
+                            state.reset_blink();
+                        }
+                    }
+                } else {
+                    // Wasn't for this element, put it back
+                    self.pending_text_click = Some((click_elem, click_x, click_y, click_shift));
+                }
+            }
 
-        // Attach text config
-        self.layout_elements[text_elem_idx as usize].element_configs.start =
-            self.element_configs.len();
-        self.element_configs.push(ElementConfig {
-            config_type: ElementConfigType::Text,
-            config_index: text_config_index,
-        });
-        self.layout_elements[text_elem_idx as usize].element_configs.length = 1;
+            // Auto-register as focusable if not already done via accessibility
+            if !declared_focusable {
+                // Check it's not already registered
+                let already = self.focusable_elements.iter().any(|e| e.element_id == elem_id);
+                if !already {
+                    self.register_focusable(elem_id, None);
+                }
+            }
+        }
 
-        // Set default layout config
-        let default_layout_idx = self.store_layout_config(LayoutConfig::default());
-        self.layout_elements[text_elem_idx as usize].layout_config_index = default_layout_idx;
+        // Preserve-focus flag
+        if declaration.preserve_focus {
+            let elem_id = self.layout_elements[open_idx].id;
+            if let Some(item) = self.layout_element_map.get_mut(&elem_id) {
+                item.preserve_focus = true;
+            }
+        }
 
-        // Add to parent's children count
-        self.layout_elements[parent_idx].children_length += 1;
+        // Focusable descendants join the group until this element closes
+        if let Some(config) = declaration.focus_group {
+            let elem_id = self.layout_elements[open_idx].id;
+            self.focus_groups.insert(elem_id, FocusGroup { config, members: Vec::new() });
+            self.open_focus_groups.push((open_idx, elem_id));
+        }
     }
 
-    /// Returns the cached font height for the given (font_asset, font_size) pair.
-    /// Measures `"Mg"` on the first call for each pair and caches the result.
-    fn font_height(&mut self, font_asset: Option<&'static crate::renderer::FontAsset>, font_size: u16) -> f32 {
-        let font_key = font_asset.map(|a| a.key()).unwrap_or("");
-        let key = (font_key, font_size);
-        if let Some(&h) = self.font_height_cache.get(&key) {
-            return h;
-        }
-        let h = if let Some(ref measure_fn) = self.measure_text_fn {
-            let config = TextConfig {
-                font_asset,
-                font_size,
-                ..Default::default()
-            };
-            measure_fn("Mg", &config).height
-        } else {
-            font_size as f32
-        };
-        let font_loaded = font_asset
-            .map_or(true, |a| crate::renderer::FontManager::is_loaded(a));
-        if font_loaded {
-            self.font_height_cache.insert(key, h);
+    /// Adds an element to the tab order, and to the innermost open focus group.
+    fn register_focusable(&mut self, element_id: u32, tab_index: Option<i32>) {
+        let group = self.open_focus_groups.last().map(|&(_, group_id)| group_id);
+        if let Some(group) = group.and_then(|group_id| self.focus_groups.get_mut(&group_id)) {
+            group.members.push(element_id);
         }
-        h
+        self.focusable_elements.push(FocusableEntry {
+            element_id,
+            tab_index,
+            insertion_order: self.focusable_elements.len() as u32,
+            group,
+        });
     }
 
-    fn measure_text_cached(
-        &mut self,
-        text: &str,
-        config: &TextConfig,
-    ) -> MeasureTextCacheItem {
-        match &self.measure_text_fn {
-            Some(_) => {},
-            None => {
-                if !self.boolean_warnings.text_measurement_fn_not_set {
-                    self.boolean_warnings.text_measurement_fn_not_set = true;
+    /// The elements inside the innermost focus scope, or `None` without a scope.
+    ///
+    /// Floating elements attached inside the scope count as inside it.
+    fn focus_scope_elements(&self) -> Option<FxHashSet<u32>> {
+        let &(root_id, _) = self.focus_scopes.last()?;
+        let mut inside = FxHashSet::default();
+        let mut stack: Vec<usize> = self
+            .layout_elements
+            .iter()
+            .position(|element| element.id == root_id)
+            .into_iter()
+            .collect();
+        loop {
+            while let Some(idx) = stack.pop() {
+                let element = &self.layout_elements[idx];
+                if !inside.insert(element.id) || element.text_data_index >= 0 {
+                    continue;
                 }
-                return MeasureTextCacheItem::default();
+                let children = element.children_start..element.children_start + element.children_length as usize;
+                stack.extend(children.map(|child| self.layout_element_children[child] as usize));
             }
-        };
+            stack.extend(
+                self.layout_element_tree_roots
+                    .iter()
+                    .filter(|root| inside.contains(&root.parent_id))
+                    .map(|root| root.layout_element_index as usize)
+                    .filter(|&idx| !inside.contains(&self.layout_elements[idx].id)),
+            );
+            if stack.is_empty() {
+                return Some(inside);
+            }
+        }
+    }
 
-        let id = hash_string_contents_with_config(text, config);
+    /// Restricts Tab and arrow key focus movement to descendants of `root_id`.
+    pub fn push_focus_scope(&mut self, root_id: u32) {
+        self.focus_scopes.push((root_id, self.focused_element_id));
+    }
 
-        // Check cache
-        if let Some(item) = self.measure_text_cache.get_mut(&id) {
-            item.generation = self.generation;
-            return *item;
+    /// Removes the innermost focus scope and refocuses the element that was
+    /// focused when it was pushed.
+    pub fn pop_focus_scope(&mut self) {
+        if let Some((_, restore_id)) = self.focus_scopes.pop() {
+            if restore_id == 0 || self.layout_element_map.contains_key(&restore_id) {
+                self.change_focus(restore_id);
+            }
         }
+    }
 
-        // Not cached - measure now
-        let text_data = text.as_bytes();
-        let text_length = text_data.len() as i32;
+    /// The focus group `element_id` is a member of, if any.
+    fn focus_group_of(&self, element_id: u32) -> Option<(u32, &FocusGroup)> {
+        let group_id = self
+            .focusable_elements
+            .iter()
+            .find(|entry| entry.element_id == element_id)?
+            .group?;
+        self.focus_groups.get(&group_id).map(|group| (group_id, group))
+    }
 
-        let space_str = " ";
-        let space_width = (self.measure_text_fn.as_ref().unwrap())(space_str, config).width;
+    pub fn close_element(&mut self) {
+        if self.max_elements_exceeded {
+            return;
+        }
+        // Marks where the element's children end
+        self.layout_fingerprint.write_u8(0xC1);
 
-        let mut start: i32 = 0;
-        let mut end: i32 = 0;
-        let mut line_width: f32 = 0.0;
-        let mut measured_width: f32 = 0.0;
-        let mut measured_height: f32 = 0.0;
-        let mut min_width: f32 = 0.0;
-        let mut contains_newlines = false;
+        let open_idx = self.get_open_layout_element();
+        let layout_config_index = self.layout_elements[open_idx].layout_config_index;
+        let layout_config = self.layout_configs[layout_config_index];
+        if self.open_focus_groups.last().is_some_and(|&(group_idx, _)| group_idx == open_idx) {
+            self.open_focus_groups.pop();
+        }
 
-        let mut temp_word_next: i32 = -1;
-        let mut previous_word_index: i32 = -1;
+        // Check for clip and floating configs
+        let mut element_has_clip_horizontal = false;
+        let mut element_has_clip_vertical = false;
+        let element_configs_start = self.layout_elements[open_idx].element_configs.start;
+        let element_configs_length = self.layout_elements[open_idx].element_configs.length;
 
-        while end < text_length {
-            let current = text_data[end as usize];
-            if current == b' ' || current == b'\n' {
-                let length = end - start;
-                let mut dimensions = Dimensions::default();
-                if length > 0 {
-                    let substr =
-                        core::str::from_utf8(&text_data[start as usize..end as usize]).unwrap();
-                    dimensions = (self.measure_text_fn.as_ref().unwrap())(substr, config);
-                }
-                min_width = f32::max(dimensions.width, min_width);
-                measured_height = f32::max(measured_height, dimensions.height);
-
-                if current == b' ' {
-                    dimensions.width += space_width;
-                    let word = MeasuredWord {
-                        start_offset: start,
-                        length: length + 1,
-                        width: dimensions.width,
-                        next: -1,
-                    };
-                    let word_idx = self.add_measured_word(word, previous_word_index);
-                    if previous_word_index == -1 {
-                        temp_word_next = word_idx;
-                    }
-                    previous_word_index = word_idx;
-                    line_width += dimensions.width;
-                }
-                if current == b'\n' {
-                    if length > 0 {
-                        let word = MeasuredWord {
-                            start_offset: start,
-                            length,
-                            width: dimensions.width,
-                            next: -1,
-                        };
-                        let word_idx = self.add_measured_word(word, previous_word_index);
-                        if previous_word_index == -1 {
-                            temp_word_next = word_idx;
-                        }
-                        previous_word_index = word_idx;
-                    }
-                    let newline_word = MeasuredWord {
-                        start_offset: end + 1,
-                        length: 0,
-                        width: 0.0,
-                        next: -1,
-                    };
-                    let word_idx = self.add_measured_word(newline_word, previous_word_index);
-                    if previous_word_index == -1 {
-                        temp_word_next = word_idx;
-                    }
-                    previous_word_index = word_idx;
-                    line_width += dimensions.width;
-                    measured_width = f32::max(line_width, measured_width);
-                    contains_newlines = true;
-                    line_width = 0.0;
-                }
-                start = end + 1;
+        for i in 0..element_configs_length {
+            let config = &self.element_configs[element_configs_start + i as usize];
+            if config.config_type == ElementConfigType::Clip {
+                let clip = &self.clip_element_configs[config.config_index];
+                element_has_clip_horizontal = clip.horizontal;
+                element_has_clip_vertical = clip.vertical;
+                self.open_clip_element_stack.pop();
+                break;
+            } else if config.config_type == ElementConfigType::Floating {
+                self.open_clip_element_stack.pop();
             }
-            end += 1;
         }
 
-        if end - start > 0 {
-            let substr =
-                core::str::from_utf8(&text_data[start as usize..end as usize]).unwrap();
-            let dimensions = (self.measure_text_fn.as_ref().unwrap())(substr, config);
-            let word = MeasuredWord {
-                start_offset: start,
-                length: end - start,
-                width: dimensions.width,
-                next: -1,
-            };
-            let word_idx = self.add_measured_word(word, previous_word_index);
-            if previous_word_index == -1 {
-                temp_word_next = word_idx;
-            }
-            line_width += dimensions.width;
-            measured_height = f32::max(measured_height, dimensions.height);
-            min_width = f32::max(dimensions.width, min_width);
-        }
+        let left_right_padding =
+            (layout_config.padding.left + layout_config.padding.right) as f32;
+        let top_bottom_padding =
+            (layout_config.padding.top + layout_config.padding.bottom) as f32;
 
-        measured_width =
-            f32::max(line_width, measured_width) - config.letter_spacing as f32;
+        let children_length = self.layout_elements[open_idx].children_length;
 
-        let result = MeasureTextCacheItem {
-            id,
-            generation: self.generation,
-            measured_words_start_index: temp_word_next,
-            unwrapped_dimensions: Dimensions::new(measured_width, measured_height),
-            min_width,
-            contains_newlines,
-        };
-        self.measure_text_cache.insert(id, result);
-        result
-    }
+        // Attach children to the current open element
+        let children_start = self.layout_element_children.len();
+        self.layout_elements[open_idx].children_start = children_start;
 
-    fn add_measured_word(&mut self, word: MeasuredWord, previous_word_index: i32) -> i32 {
-        let new_index: i32;
-        if let Some(&free_idx) = self.measured_words_free_list.last() {
-            self.measured_words_free_list.pop();
-            new_index = free_idx;
-            self.measured_words[free_idx as usize] = word;
+        if layout_config.layout_direction == LayoutDirection::LeftToRight {
+            self.layout_elements[open_idx].dimensions.width = left_right_padding;
+            self.layout_elements[open_idx].min_dimensions.width = left_right_padding;
+
+            for i in 0..children_length {
+                let buf_idx = self.layout_element_children_buffer.len()
+                    - children_length as usize
+                    + i as usize;
+                let child_index = self.layout_element_children_buffer[buf_idx];
+                let child = &self.layout_elements[child_index as usize];
+                let margin = self.layout_configs[child.layout_config_index].margin;
+                let child_width = child.dimensions.width + margin.along(true);
+                let child_height = child.dimensions.height + margin.along(false);
+                let child_min_width = child.min_dimensions.width + margin.along(true);
+                let child_min_height = child.min_dimensions.height + margin.along(false);
+
+                self.layout_elements[open_idx].dimensions.width += child_width;
+                let current_height = self.layout_elements[open_idx].dimensions.height;
+                self.layout_elements[open_idx].dimensions.height =
+                    f32::max(current_height, child_height + top_bottom_padding);
+
+                if !element_has_clip_horizontal {
+                    self.layout_elements[open_idx].min_dimensions.width += child_min_width;
+                }
+                if !element_has_clip_vertical {
+                    let current_min_h = self.layout_elements[open_idx].min_dimensions.height;
+                    self.layout_elements[open_idx].min_dimensions.height =
+                        f32::max(current_min_h, child_min_height + top_bottom_padding);
+                }
+                self.layout_element_children.push(child_index);
+            }
+            let child_gap =
+                (children_length.saturating_sub(1) as u32 * layout_config.child_gap as u32) as f32;
+            self.layout_elements[open_idx].dimensions.width += child_gap;
+            if !element_has_clip_horizontal {
+                self.layout_elements[open_idx].min_dimensions.width += child_gap;
+            }
+            if layout_config.child_alignment.y == AlignY::Baseline && children_length > 0 {
+                let (above, below) = self.baseline_extent(open_idx, 0, children_length as usize);
+                let current_height = self.layout_elements[open_idx].dimensions.height;
+                self.layout_elements[open_idx].dimensions.height =
+                    f32::max(current_height, above + below + top_bottom_padding);
+            }
         } else {
-            self.measured_words.push(word);
-            new_index = (self.measured_words.len() - 1) as i32;
-        }
-        if previous_word_index >= 0 {
-            self.measured_words[previous_word_index as usize].next = new_index;
-        }
-        new_index
-    }
+            // TopToBottom
+            self.layout_elements[open_idx].dimensions.height = top_bottom_padding;
+            self.layout_elements[open_idx].min_dimensions.height = top_bottom_padding;
 
-    pub fn begin_layout(&mut self) {
-        self.initialize_ephemeral_memory();
-        self.generation += 1;
-        if self.pressed_this_frame_generation != self.generation {
-            self.pressed_this_frame_ids.clear();
+            for i in 0..children_length {
+                let buf_idx = self.layout_element_children_buffer.len()
+                    - children_length as usize
+                    + i as usize;
+                let child_index = self.layout_element_children_buffer[buf_idx];
+                let child = &self.layout_elements[child_index as usize];
+                let margin = self.layout_configs[child.layout_config_index].margin;
+                let child_width = child.dimensions.width + margin.along(true);
+                let child_height = child.dimensions.height + margin.along(false);
+                let child_min_width = child.min_dimensions.width + margin.along(true);
+                let child_min_height = child.min_dimensions.height + margin.along(false);
+
+                self.layout_elements[open_idx].dimensions.height += child_height;
+                let current_width = self.layout_elements[open_idx].dimensions.width;
+                self.layout_elements[open_idx].dimensions.width =
+                    f32::max(current_width, child_width + left_right_padding);
+
+                if !element_has_clip_vertical {
+                    self.layout_elements[open_idx].min_dimensions.height += child_min_height;
+                }
+                if !element_has_clip_horizontal {
+                    let current_min_w = self.layout_elements[open_idx].min_dimensions.width;
+                    self.layout_elements[open_idx].min_dimensions.width =
+                        f32::max(current_min_w, child_min_width + left_right_padding);
+                }
+                self.layout_element_children.push(child_index);
+            }
+            let child_gap =
+                (children_length.saturating_sub(1) as u32 * layout_config.child_gap as u32) as f32;
+            self.layout_elements[open_idx].dimensions.height += child_gap;
+            if !element_has_clip_vertical {
+                self.layout_elements[open_idx].min_dimensions.height += child_gap;
+            }
         }
-        if self.released_this_frame_generation != self.generation {
-            self.released_this_frame_ids.clear();
+
+        // Remove children from buffer
+        let remove_count = children_length as usize;
+        let new_len = self.layout_element_children_buffer.len().saturating_sub(remove_count);
+        self.layout_element_children_buffer.truncate(new_len);
+
+        // The children are measured, so min- and max-content become fixed sizes
+        let element = &self.layout_elements[open_idx];
+        let sizing = &mut self.layout_configs[layout_config_index].sizing;
+        resolve_content_sizing(&mut sizing.width, element.dimensions.width, element.min_dimensions.width);
+        resolve_content_sizing(&mut sizing.height, element.dimensions.height, element.min_dimensions.height);
+
+        // Clamp width
+        {
+            let sizing_type = self.layout_configs[layout_config_index].sizing.width.type_;
+            if sizing_type != SizingType::Percent {
+                let mut max_w = self.layout_configs[layout_config_index].sizing.width.min_max.max;
+                if max_w <= 0.0 {
+                    max_w = MAXFLOAT;
+                    self.layout_configs[layout_config_index].sizing.width.min_max.max = max_w;
+                }
+                let min_w = self.layout_configs[layout_config_index].sizing.width.min_max.min;
+                self.layout_elements[open_idx].dimensions.width = f32::min(
+                    f32::max(self.layout_elements[open_idx].dimensions.width, min_w),
+                    max_w,
+                );
+                self.layout_elements[open_idx].min_dimensions.width = f32::min(
+                    f32::max(self.layout_elements[open_idx].min_dimensions.width, min_w),
+                    max_w,
+                );
+            } else {
+                self.layout_elements[open_idx].dimensions.width = 0.0;
+            }
         }
-        self.dynamic_element_index = 0;
 
-        // Evict stale text measurement cache entries
-        self.evict_stale_text_cache();
+        // Clamp height
+        {
+            let sizing_type = self.layout_configs[layout_config_index].sizing.height.type_;
+            if sizing_type != SizingType::Percent {
+                let mut max_h = self.layout_configs[layout_config_index].sizing.height.min_max.max;
+                if max_h <= 0.0 {
+                    max_h = MAXFLOAT;
+                    self.layout_configs[layout_config_index].sizing.height.min_max.max = max_h;
+                }
+                let min_h = self.layout_configs[layout_config_index].sizing.height.min_max.min;
+                self.layout_elements[open_idx].dimensions.height = f32::min(
+                    f32::max(self.layout_elements[open_idx].dimensions.height, min_h),
+                    max_h,
+                );
+                self.layout_elements[open_idx].min_dimensions.height = f32::min(
+                    f32::max(self.layout_elements[open_idx].min_dimensions.height, min_h),
+                    max_h,
+                );
+            } else {
+                self.layout_elements[open_idx].dimensions.height = 0.0;
+            }
+        }
 
-        let root_width = self.layout_dimensions.width;
-        let root_height = self.layout_dimensions.height;
+        self.update_aspect_ratio_box(open_idx);
 
-        self.boolean_warnings = BooleanWarnings::default();
+        // Apply shape rotation AABB expansion
+        if let Some(shape_rot) = self.element_shape_rotations.get(open_idx).copied().flatten() {
+            if !shape_rot.is_noop() {
+                let orig_w = self.layout_elements[open_idx].dimensions.width;
+                let orig_h = self.layout_elements[open_idx].dimensions.height;
 
-        let root_id = hash_string("Ply__RootContainer", 0);
-        self.open_element_with_id(&root_id);
+                // Find corner radius for this element
+                let cr = self
+                    .find_element_config_index(open_idx, ElementConfigType::Shared)
+                    .map(|idx| self.shared_element_configs[idx].corner_radius)
+                    .unwrap_or_default();
 
-        let root_decl = ElementDeclaration {
-            layout: LayoutConfig {
-                sizing: SizingConfig {
-                    width: SizingAxis {
-                        type_: SizingType::Fixed,
-                        min_max: SizingMinMax {
-                            min: root_width,
-                            max: root_width,
-                        },
-                        percent: 0.0,
-                        grow_weight: 1.0,
-                    },
-                    height: SizingAxis {
-                        type_: SizingType::Fixed,
-                        min_max: SizingMinMax {
-                            min: root_height,
-                            max: root_height,
-                        },
-                        percent: 0.0,
-                        grow_weight: 1.0,
-                    },
-                },
-                ..Default::default()
-            },
-            ..Default::default()
-        };
-        self.configure_open_element(&root_decl);
-        self.open_layout_element_stack.push(0);
-        self.layout_element_tree_roots.push(LayoutElementTreeRoot {
-            layout_element_index: 0,
-            ..Default::default()
-        });
-    }
+                let (eff_w, eff_h) = crate::math::compute_rotated_aabb(
+                    orig_w,
+                    orig_h,
+                    &cr,
+                    shape_rot.rotation_radians,
+                );
 
-    pub fn end_layout(&mut self) -> &[InternalRenderCommand<CustomElementData>] {
-        self.close_element();
+                // Store original dimensions for renderer
+                while self.element_pre_rotation_dimensions.len() <= open_idx {
+                    self.element_pre_rotation_dimensions.push(None);
+                }
+                self.element_pre_rotation_dimensions[open_idx] =
+                    Some(Dimensions::new(orig_w, orig_h));
 
-        if self.open_layout_element_stack.len() > 1 {
-            // Unbalanced open/close warning
+                // Replace layout dimensions with AABB
+                self.layout_elements[open_idx].dimensions.width = eff_w;
+                self.layout_elements[open_idx].dimensions.height = eff_h;
+                self.layout_elements[open_idx].min_dimensions.width = eff_w;
+                self.layout_elements[open_idx].min_dimensions.height = eff_h;
+            }
         }
 
-        if self.debug_mode_enabled {
-            self.render_debug_view();
-        }
+        let element_is_floating =
+            self.element_has_config(open_idx, ElementConfigType::Floating);
 
-        self.calculate_final_layout();
-        &self.render_commands
-    }
+        // Pop from open stack
+        self.open_layout_element_stack.pop();
 
-    /// Evicts stale entries from the text measurement cache.
-    /// Entries that haven't been used for more than 2 generations are removed.
-    fn evict_stale_text_cache(&mut self) {
-        let gen = self.generation;
-        let measured_words = &mut self.measured_words;
-        let free_list = &mut self.measured_words_free_list;
-        self.measure_text_cache.retain(|_, item| {
-            if gen.wrapping_sub(item.generation) <= 2 {
-                true
-            } else {
-                // Clean up measured words for this evicted entry
-                let mut idx = item.measured_words_start_index;
-                while idx != -1 {
-                    let word = measured_words[idx as usize];
-                    free_list.push(idx);
-                    idx = word.next;
-                }
-                false
+        // Add to parent's children
+        if self.open_layout_element_stack.len() > 1 {
+            if element_is_floating {
+                let parent_idx = self.get_open_layout_element();
+                self.layout_elements[parent_idx].floating_children_count += 1;
+                return;
             }
-        });
-    }
-
-    fn initialize_ephemeral_memory(&mut self) {
-        self.layout_element_children_buffer.clear();
-        self.layout_elements.clear();
-        self.layout_configs.clear();
-        self.element_configs.clear();
-        self.text_element_configs.clear();
-        self.aspect_ratio_configs.clear();
-        self.aspect_ratio_cover_configs.clear();
-        self.image_element_configs.clear();
-        self.floating_element_configs.clear();
-        self.clip_element_configs.clear();
-        self.custom_element_configs.clear();
-        self.border_element_configs.clear();
-        self.shared_element_configs.clear();
-        self.element_effects.clear();
-        self.element_shaders.clear();
-        self.element_visual_rotations.clear();
-        self.element_shape_rotations.clear();
-        self.element_pre_rotation_dimensions.clear();
-        self.layout_element_id_strings.clear();
-        self.wrapped_text_lines.clear();
-        self.tree_node_array.clear();
-        self.layout_element_tree_roots.clear();
-        self.layout_element_children.clear();
-        self.layout_element_map.clear();
-        self.open_layout_element_stack.clear();
-        self.text_element_data.clear();
-        self.aspect_ratio_element_indexes.clear();
-        self.render_commands.clear();
-        self.tree_node_visited.clear();
-        self.open_clip_element_stack.clear();
-        self.reusable_element_index_buffer.clear();
-        self.layout_element_clip_element_ids.clear();
-        self.dynamic_string_data.clear();
-        self.focusable_elements.clear();
-        self.accessibility_configs.clear();
-        self.accessibility_element_order.clear();
-        self.text_input_configs.clear();
-        self.text_input_element_ids.clear();
-    }
-
-    fn child_size_on_axis(&self, child_index: usize, x_axis: bool) -> f32 {
-        if x_axis {
-            self.layout_elements[child_index].dimensions.width
-        } else {
-            self.layout_elements[child_index].dimensions.height
+            let parent_idx = self.get_open_layout_element();
+            self.layout_elements[parent_idx].children_length += 1;
+            self.layout_element_children_buffer.push(open_idx as i32);
         }
     }
 
-    fn child_sizing_on_axis(&self, child_index: usize, x_axis: bool) -> SizingAxis {
-        let child_layout_idx = self.layout_elements[child_index].layout_config_index;
-        if x_axis {
-            self.layout_configs[child_layout_idx].sizing.width
-        } else {
-            self.layout_configs[child_layout_idx].sizing.height
+    pub fn open_text_element(
+        &mut self,
+        text: &str,
+        text_config_index: usize,
+    ) {
+        if self.element_limit_reached() {
+            return;
         }
-    }
 
-    fn child_wrap_break_main_size(&self, child_index: usize, main_axis_x: bool) -> f32 {
-        let child_sizing = self.child_sizing_on_axis(child_index, main_axis_x);
-        match child_sizing.type_ {
-            SizingType::Grow => child_sizing.min_max.min,
-            SizingType::Percent | SizingType::Fixed | SizingType::Fit => {
-                self.child_size_on_axis(child_index, main_axis_x)
-            }
+        let parent_idx = self.get_open_layout_element();
+        let parent_id = self.layout_elements[parent_idx].id;
+        let parent_children_count = self.layout_elements[parent_idx].children_length;
+
+        // Create text layout element
+        let text_element = LayoutElement {
+            text_data_index: -1,
+            ..Default::default()
+        };
+        self.layout_elements.push(text_element);
+        let text_elem_idx = (self.layout_elements.len() - 1) as i32;
+
+        while self.layout_element_clip_element_ids.len() < self.layout_elements.len() {
+            self.layout_element_clip_element_ids.push(0);
+        }
+        if !self.open_clip_element_stack.is_empty() {
+            let clip_id = *self.open_clip_element_stack.last().unwrap();
+            self.layout_element_clip_element_ids[text_elem_idx as usize] = clip_id;
+        } else {
+            self.layout_element_clip_element_ids[text_elem_idx as usize] = 0;
         }
-    }
 
-    fn build_wrapped_line(
-        &self,
-        parent_index: usize,
-        start_child_offset: usize,
-        end_child_offset: usize,
-        main_axis_x: bool,
-    ) -> WrappedLayoutLine {
-        let layout_idx = self.layout_elements[parent_index].layout_config_index;
-        let layout = self.layout_configs[layout_idx];
-        let children_start = self.layout_elements[parent_index].children_start;
+        self.layout_element_children_buffer.push(text_elem_idx);
 
-        let mut main_size = 0.0;
-        let mut cross_size = 0.0;
+        // Measure text
+        let config = &mut self.text_element_configs[text_config_index];
+        if !config.inline_atoms.is_empty() {
+            let atom_count = config.inline_atoms.len();
+            config.inline_atom_offsets.clear();
+            config.inline_atom_offsets.extend(text.match_indices(INLINE_ATOM).map(|(i, _)| i).take(atom_count));
+        }
+        // Taken out while measuring, put back below
+        let text_config = std::mem::take(config);
+        self.fingerprint_text(text, &text_config);
+        let text_measured =
+            self.measure_text_cached(text, &text_config);
 
-        for child_offset in start_child_offset..end_child_offset {
-            let child_index = self.layout_element_children[children_start + child_offset] as usize;
-            if child_offset > start_child_offset {
-                main_size += layout.child_gap as f32;
+        let element_id = hash_number(parent_children_count as u32, parent_id);
+        self.layout_elements[text_elem_idx as usize].id = element_id.id;
+        self.add_hash_map_item(&element_id, text_elem_idx, None);
+        // Inline element slots get their own IDs for floating elements to attach to
+        for atom in &text_config.inline_atoms {
+            if let InlineAtomContent::Element(atom_id) = &atom.content {
+                self.add_hash_map_item(atom_id, text_elem_idx, None);
             }
-
-            let child_main = self.child_size_on_axis(child_index, main_axis_x);
-            let child_cross = self.child_size_on_axis(child_index, !main_axis_x);
-            main_size += child_main;
-            cross_size = f32::max(cross_size, child_cross);
         }
-
-        WrappedLayoutLine {
-            start_child_offset,
-            end_child_offset,
-            main_size,
-            cross_size,
+        if self.debug_mode_enabled {
+            self.layout_element_id_strings.push(element_id.string_id);
         }
-    }
-
-    fn compute_wrapped_lines(
-        &self,
-        parent_index: usize,
-        main_axis_x: bool,
-    ) -> Vec<WrappedLayoutLine> {
-        let layout_idx = self.layout_elements[parent_index].layout_config_index;
-        let layout = self.layout_configs[layout_idx];
 
-        let children_length = self.layout_elements[parent_index].children_length as usize;
-        if children_length == 0 {
-            return Vec::new();
+        // If the text element is marked accessible, register it in the
+        // accessibility tree with a StaticText role and the text content
+        // as the label.
+        if text_config.accessible {
+            let a11y = crate::accessibility::AccessibilityConfig {
+                role: crate::accessibility::AccessibilityRole::StaticText,
+                label: text.to_string(),
+                ..Default::default()
+            };
+            self.accessibility_configs.insert(element_id.id, a11y);
+            self.accessibility_element_order.push(element_id.id);
         }
 
-        let parent_main_size = if main_axis_x {
-            self.layout_elements[parent_index].dimensions.width
+        let text_width = text_measured.unwrapped_dimensions.width;
+        let text_height = if text_config.line_height > 0 {
+            text_config.line_height as f32
         } else {
-            self.layout_elements[parent_index].dimensions.height
+            text_measured.unwrapped_dimensions.height
         };
-        let main_padding = if main_axis_x {
-            (layout.padding.left + layout.padding.right) as f32
+        // Anywhere-wrapped text can break inside words, so it has no word-based minimum
+        let min_width = if text_config.wrap_mode == WrapMode::Anywhere {
+            0.0
         } else {
-            (layout.padding.top + layout.padding.bottom) as f32
+            text_measured.min_width
         };
-        let available_main = (parent_main_size - main_padding).max(0.0);
-
-        if !layout.wrap {
-            return vec![self.build_wrapped_line(
-                parent_index,
-                0,
-                children_length,
-                main_axis_x,
-            )];
-        }
-
-        let children_start = self.layout_elements[parent_index].children_start;
-        let mut lines = Vec::new();
-        let mut line_start = 0usize;
-        let mut line_break_main = 0.0;
 
-        for child_offset in 0..children_length {
-            let child_index = self.layout_element_children[children_start + child_offset] as usize;
-            let break_size = self.child_wrap_break_main_size(child_index, main_axis_x);
+        self.layout_elements[text_elem_idx as usize].dimensions =
+            Dimensions::new(text_width, text_height);
+        self.layout_elements[text_elem_idx as usize].min_dimensions =
+            Dimensions::new(min_width, text_height);
 
-            let additional = if child_offset == line_start {
-                break_size
-            } else {
-                layout.child_gap as f32 + break_size
-            };
+        // Store text element data
+        let text_data = TextElementData {
+            text: text.to_string(),
+            preferred_dimensions: text_measured.unwrapped_dimensions,
+            element_index: text_elem_idx,
+            parent_id,
+            baseline: self.text_baseline(&text_config, text_measured.unwrapped_dimensions.height),
+            config_index: text_config_index,
+            measured: text_measured,
+            wrapped_lines_start: 0,
+            wrapped_lines_length: 0,
+        };
+        self.text_element_data.push(text_data);
+        let text_data_idx = (self.text_element_data.len() - 1) as i32;
+        self.layout_elements[text_elem_idx as usize].text_data_index = text_data_idx;
 
-            if child_offset > line_start && line_break_main + additional > available_main + EPSILON {
-                lines.push(self.build_wrapped_line(
-                    parent_index,
-                    line_start,
-                    child_offset,
-                    main_axis_x,
-                ));
-                line_start = child_offset;
-                line_break_main = break_size;
-            } else {
-                line_break_main += additional;
-            }
-        }
+        // Attach text config
+        self.layout_elements[text_elem_idx as usize].element_configs.start =
+            self.element_configs.len();
+        self.element_configs.push(ElementConfig {
+            config_type: ElementConfigType::Text,
+            config_index: text_config_index,
+        });
+        self.layout_elements[text_elem_idx as usize].element_configs.length = 1;
+        self.text_element_configs[text_config_index] = text_config;
 
-        lines.push(self.build_wrapped_line(
-            parent_index,
-            line_start,
-            children_length,
-            main_axis_x,
-        ));
+        // Set default layout config
+        let default_layout_idx = self.store_layout_config(LayoutConfig::default());
+        self.layout_elements[text_elem_idx as usize].layout_config_index = default_layout_idx;
 
-        lines
+        // Add to parent's children count
+        self.layout_elements[parent_idx].children_length += 1;
     }
 
-    fn wrapped_content_dimensions(
-        &self,
-        parent_index: usize,
-        main_axis_x: bool,
-        lines: &[WrappedLayoutLine],
-    ) -> Dimensions {
-        let layout_idx = self.layout_elements[parent_index].layout_config_index;
-        let layout = self.layout_configs[layout_idx];
-
-        let lr_padding = (layout.padding.left + layout.padding.right) as f32;
-        let tb_padding = (layout.padding.top + layout.padding.bottom) as f32;
-
-        if lines.is_empty() {
-            return Dimensions::new(lr_padding, tb_padding);
+    /// Returns the cached font height for the given (font_asset, font_size) pair.
+    /// Measures `"Mg"` on the first call for each pair and caches the result.
+    fn font_height(&mut self, font_asset: Option<&'static crate::renderer::FontAsset>, font_size: u16) -> f32 {
+        let font_key = font_asset.map(|a| a.key()).unwrap_or("");
+        let key = (font_key, font_size);
+        if let Some(&h) = self.font_height_cache.get(&key) {
+            return h;
         }
-
-        let wrap_gap_total = lines.len().saturating_sub(1) as f32 * layout.wrap_gap as f32;
-        let max_main = lines.iter().fold(0.0_f32, |acc, line| acc.max(line.main_size));
-        let total_cross = lines.iter().map(|line| line.cross_size).sum::<f32>() + wrap_gap_total;
-
-        if main_axis_x {
-            Dimensions::new(max_main + lr_padding, total_cross + tb_padding)
+        let h = if let Some(ref measure_fn) = self.measure_text_fn {
+            let config = TextConfig {
+                font_asset,
+                font_size,
+                ..Default::default()
+            };
+            measure_fn("Mg", &config).height
         } else {
-            Dimensions::new(total_cross + lr_padding, max_main + tb_padding)
+            font_size as f32
+        };
+        let font_loaded = font_asset.is_none_or(crate::renderer::FontManager::is_loaded);
+        if font_loaded {
+            self.font_height_cache.insert(key, h);
         }
+        h
     }
 
-    fn size_containers_along_axis(&mut self, x_axis: bool) {
-        let mut bfs_buffer: Vec<i32> = Vec::new();
-        let mut resizable_container_buffer: Vec<i32> = Vec::new();
+    fn measure_text_cached(
+        &mut self,
+        text: &str,
+        config: &TextConfig,
+    ) -> MeasureTextCacheItem {
+        match &self.measure_text_fn {
+            Some(_) => {},
+            None => {
+                if !self.diagnostics.contains(&Diagnostic::MissingMeasureFunction) {
+                    self.diagnostics.push(Diagnostic::MissingMeasureFunction);
+                }
+                return MeasureTextCacheItem::default();
+            }
+        };
 
-        for root_index in 0..self.layout_element_tree_roots.len() {
-            bfs_buffer.clear();
-            let root = self.layout_element_tree_roots[root_index];
-            let root_elem_idx = root.layout_element_index as usize;
-            bfs_buffer.push(root.layout_element_index);
+        let id = text_cache_key(text, config);
 
-            // Size floating containers to their parents
-            if self.element_has_config(root_elem_idx, ElementConfigType::Floating) {
-                if let Some(float_cfg_idx) =
-                    self.find_element_config_index(root_elem_idx, ElementConfigType::Floating)
-                {
-                    let parent_id = self.floating_element_configs[float_cfg_idx].parent_id;
-                    if let Some(parent_item) = self.layout_element_map.get(&parent_id) {
-                        let parent_elem_idx = parent_item.layout_element_index as usize;
-                        let parent_dims = self.layout_elements[parent_elem_idx].dimensions;
-                        let root_layout_idx =
-                            self.layout_elements[root_elem_idx].layout_config_index;
+        // Check cache
+        if let Some(item) = self.measure_text_cache.get_mut(&id) {
+            item.generation = self.generation;
+            self.frame_stats.text_cache_hits += 1;
+            self.text_cache_stats.hits += 1;
+            return *item;
+        }
 
-                        let w_type = self.layout_configs[root_layout_idx].sizing.width.type_;
-                        match w_type {
-                            SizingType::Grow => {
-                                self.layout_elements[root_elem_idx].dimensions.width =
-                                    parent_dims.width;
-                            }
-                            SizingType::Percent => {
-                                self.layout_elements[root_elem_idx].dimensions.width =
-                                    parent_dims.width
-                                        * self.layout_configs[root_layout_idx]
-                                            .sizing
-                                            .width
-                                            .percent;
-                            }
-                            _ => {}
-                        }
-                        let h_type = self.layout_configs[root_layout_idx].sizing.height.type_;
-                        match h_type {
-                            SizingType::Grow => {
-                                self.layout_elements[root_elem_idx].dimensions.height =
-                                    parent_dims.height;
-                            }
-                            SizingType::Percent => {
-                                self.layout_elements[root_elem_idx].dimensions.height =
-                                    parent_dims.height
-                                        * self.layout_configs[root_layout_idx]
-                                            .sizing
-                                            .height
-                                            .percent;
-                            }
-                            _ => {}
-                        }
-                    }
-                }
+        // Not cached - measure now
+        let measure_start = crate::profiler::now_ms();
+        self.frame_stats.text_cache_misses += 1;
+        self.text_cache_stats.misses += 1;
+        let words_before = self.live_measured_words();
+        // Split the text into words, then measure them, on several threads for long texts
+        let mut spans = std::mem::take(&mut self.word_spans);
+        let mut word_dimensions = std::mem::take(&mut self.word_dimensions);
+        spans.clear();
+        let mut start = 0;
+        for (end, separator) in text.bytes().enumerate() {
+            if separator == b' ' || separator == b'\n' {
+                spans.push(WordSpan { start, end, separator });
+                start = end + 1;
             }
-
-            // Clamp root element
-            let root_layout_idx = self.layout_elements[root_elem_idx].layout_config_index;
-            if self.layout_configs[root_layout_idx].sizing.width.type_ != SizingType::Percent {
-                let min = self.layout_configs[root_layout_idx].sizing.width.min_max.min;
-                let max = self.layout_configs[root_layout_idx].sizing.width.min_max.max;
-                self.layout_elements[root_elem_idx].dimensions.width = f32::min(
-                    f32::max(self.layout_elements[root_elem_idx].dimensions.width, min),
-                    max,
-                );
+        }
+        if start < text.len() {
+            spans.push(WordSpan { start, end: text.len(), separator: 0 });
+        }
+        let measure_fn = self.measure_text_fn.as_deref().unwrap();
+        let space_width = measure_fn(" ", config).width;
+        let measure_word = |span: &WordSpan, measure_fn: &dyn Fn(&str, &TextConfig) -> Dimensions| {
+            if span.end > span.start {
+                measure_span(&text[span.start..span.end], span.start, config, measure_fn)
+            } else {
+                Dimensions::default()
             }
-            if self.layout_configs[root_layout_idx].sizing.height.type_ != SizingType::Percent {
-                let min = self.layout_configs[root_layout_idx].sizing.height.min_max.min;
-                let max = self.layout_configs[root_layout_idx].sizing.height.min_max.max;
-                self.layout_elements[root_elem_idx].dimensions.height = f32::min(
-                    f32::max(self.layout_elements[root_elem_idx].dimensions.height, min),
-                    max,
+        };
+        word_dimensions.clear();
+        #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+        match self.parallel_measure_text_fn.as_deref() {
+            Some(measure_fn) if spans.len() >= PARALLEL_MIN_WORDS => {
+                use rayon::prelude::*;
+                word_dimensions.par_extend(
+                    spans.par_iter().with_min_len(PARALLEL_MIN_WORDS / 4).map(|span| measure_word(span, measure_fn)),
                 );
             }
+            _ => word_dimensions.extend(spans.iter().map(|span| measure_word(span, measure_fn))),
+        }
+        #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+        word_dimensions.extend(spans.iter().map(|span| measure_word(span, measure_fn)));
 
-            let mut i = 0;
-            while i < bfs_buffer.len() {
-                let parent_index = bfs_buffer[i] as usize;
-                i += 1;
-
-                let parent_layout_idx = self.layout_elements[parent_index].layout_config_index;
-                let parent_config = self.layout_configs[parent_layout_idx];
-                let parent_size = if x_axis {
-                    self.layout_elements[parent_index].dimensions.width
-                } else {
-                    self.layout_elements[parent_index].dimensions.height
-                };
-                let parent_padding = if x_axis {
-                    (parent_config.padding.left + parent_config.padding.right) as f32
-                } else {
-                    (parent_config.padding.top + parent_config.padding.bottom) as f32
-                };
-                let sizing_along_axis = (x_axis
-                    && parent_config.layout_direction == LayoutDirection::LeftToRight)
-                    || (!x_axis
-                        && parent_config.layout_direction == LayoutDirection::TopToBottom);
-
-                let mut inner_content_size: f32 = 0.0;
-                let mut total_padding_and_child_gaps = parent_padding;
-                let parent_child_gap = parent_config.child_gap as f32;
-                // None = no grow seen, Some(Some(idx)) = exactly one grow, Some(None) = 2+ grows.
-                let mut single_along_axis_grow_candidate: Option<Option<usize>> = None;
-
-                resizable_container_buffer.clear();
-
-                let children_start = self.layout_elements[parent_index].children_start;
-                let children_length = self.layout_elements[parent_index].children_length as usize;
-
-                for child_offset in 0..children_length {
-                    let child_element_index =
-                        self.layout_element_children[children_start + child_offset] as usize;
-                    let child_layout_idx =
-                        self.layout_elements[child_element_index].layout_config_index;
-                    let child_sizing = if x_axis {
-                        self.layout_configs[child_layout_idx].sizing.width
-                    } else {
-                        self.layout_configs[child_layout_idx].sizing.height
-                    };
-                    let child_size = if x_axis {
-                        self.layout_elements[child_element_index].dimensions.width
-                    } else {
-                        self.layout_elements[child_element_index].dimensions.height
-                    };
-
-                    let is_text_element =
-                        self.element_has_config(child_element_index, ElementConfigType::Text);
-                    let has_children = self.layout_elements[child_element_index].children_length > 0;
-
-                    if !is_text_element && has_children {
-                        bfs_buffer.push(child_element_index as i32);
-                    }
-
-                    let is_wrapping_text = if is_text_element {
-                        if let Some(text_cfg_idx) = self.find_element_config_index(
-                            child_element_index,
-                            ElementConfigType::Text,
-                        ) {
-                            self.text_element_configs[text_cfg_idx].wrap_mode
-                                == WrapMode::Words
-                        } else {
-                            false
-                        }
-                    } else {
-                        false
-                    };
+        // Link the words into the cache's list
+        let mut line_width: f32 = 0.0;
+        let mut measured_width: f32 = 0.0;
+        let mut measured_height: f32 = 0.0;
+        let mut min_width: f32 = 0.0;
+        let mut contains_newlines = false;
 
-                    if child_sizing.type_ != SizingType::Percent
-                        && child_sizing.type_ != SizingType::Fixed
-                        && (!is_text_element || is_wrapping_text)
-                    {
-                        resizable_container_buffer.push(child_element_index as i32);
-                        if sizing_along_axis
-                            && child_sizing.type_ == SizingType::Grow
-                            && child_sizing.grow_weight > 0.0
-                        {
-                            single_along_axis_grow_candidate = match single_along_axis_grow_candidate {
-                                None => Some(Some(child_element_index)),
-                                Some(Some(_)) | Some(None) => Some(None),
-                            };
-                        }
-                    }
+        let mut temp_word_next: i32 = -1;
+        let mut previous_word_index: i32 = -1;
 
-                    if sizing_along_axis {
-                        inner_content_size += if child_sizing.type_ == SizingType::Percent {
-                            0.0
-                        } else {
-                            child_size
-                        };
-                        if child_offset > 0 {
-                            inner_content_size += parent_child_gap;
-                            total_padding_and_child_gaps += parent_child_gap;
-                        }
-                    } else {
-                        inner_content_size = f32::max(child_size, inner_content_size);
-                    }
+        for (span, &dimensions) in spans.iter().zip(&word_dimensions) {
+            let length = (span.end - span.start) as i32;
+            min_width = f32::max(dimensions.width, min_width);
+            measured_height = f32::max(measured_height, dimensions.height);
+            let mut words = [None, None];
+            match span.separator {
+                b' ' => {
+                    words[0] = Some(MeasuredWord {
+                        start_offset: span.start as i32,
+                        length: length + 1,
+                        width: dimensions.width + space_width,
+                        next: -1,
+                    });
+                    line_width += dimensions.width + space_width;
                 }
-
-                // Expand percentage containers
-                for child_offset in 0..children_length {
-                    let child_element_index =
-                        self.layout_element_children[children_start + child_offset] as usize;
-                    let child_layout_idx =
-                        self.layout_elements[child_element_index].layout_config_index;
-                    let child_sizing = if x_axis {
-                        self.layout_configs[child_layout_idx].sizing.width
-                    } else {
-                        self.layout_configs[child_layout_idx].sizing.height
-                    };
-                    if child_sizing.type_ == SizingType::Percent {
-                        let new_size =
-                            (parent_size - total_padding_and_child_gaps) * child_sizing.percent;
-                        if x_axis {
-                            self.layout_elements[child_element_index].dimensions.width = new_size;
-                        } else {
-                            self.layout_elements[child_element_index].dimensions.height = new_size;
-                        }
-                        if sizing_along_axis {
-                            inner_content_size += new_size;
-                        }
-                        self.update_aspect_ratio_box(child_element_index);
+                b'\n' => {
+                    if length > 0 {
+                        words[0] = Some(MeasuredWord {
+                            start_offset: span.start as i32,
+                            length,
+                            width: dimensions.width,
+                            next: -1,
+                        });
                     }
+                    words[1] = Some(MeasuredWord {
+                        start_offset: span.end as i32 + 1,
+                        length: 0,
+                        width: 0.0,
+                        next: -1,
+                    });
+                    line_width += dimensions.width;
+                    measured_width = f32::max(line_width, measured_width);
+                    contains_newlines = true;
+                    line_width = 0.0;
+                }
+                _ => {
+                    words[0] = Some(MeasuredWord {
+                        start_offset: span.start as i32,
+                        length,
+                        width: dimensions.width,
+                        next: -1,
+                    });
+                    line_width += dimensions.width;
                 }
+            }
+            for word in words.into_iter().flatten() {
+                let word_idx = self.add_measured_word(word, previous_word_index);
+                if previous_word_index == -1 {
+                    temp_word_next = word_idx;
+                }
+                previous_word_index = word_idx;
+            }
+        }
+        self.word_spans = spans;
+        self.word_dimensions = word_dimensions;
 
-                if sizing_along_axis && parent_config.wrap {
-                    let parent_clips = if let Some(clip_idx) = self
-                        .find_element_config_index(parent_index, ElementConfigType::Clip)
-                    {
-                        let clip = &self.clip_element_configs[clip_idx];
-                        (x_axis && clip.horizontal) || (!x_axis && clip.vertical)
-                    } else {
-                        false
-                    };
-
-                    let wrapped_lines = self.compute_wrapped_lines(parent_index, x_axis);
-
-                    for line in wrapped_lines {
-                        if line.end_child_offset <= line.start_child_offset {
-                            continue;
-                        }
-
-                        let mut line_children: Vec<usize> = Vec::new();
-                        for child_offset in line.start_child_offset..line.end_child_offset {
-                            let child_element_index =
-                                self.layout_element_children[children_start + child_offset] as usize;
-                            line_children.push(child_element_index);
-                        }
-
-                        let mut line_inner_content_size: f32 = 0.0;
-                        let mut line_resizable_buffer: Vec<i32> = Vec::new();
-                        let mut single_line_grow_candidate: Option<Option<usize>> = None;
-
-                        for line_child_offset in 0..line_children.len() {
-                            let child_idx = line_children[line_child_offset];
-                            let child_layout_idx = self.layout_elements[child_idx].layout_config_index;
-                            let child_sizing = if x_axis {
-                                self.layout_configs[child_layout_idx].sizing.width
-                            } else {
-                                self.layout_configs[child_layout_idx].sizing.height
-                            };
-                            let child_size = if x_axis {
-                                self.layout_elements[child_idx].dimensions.width
-                            } else {
-                                self.layout_elements[child_idx].dimensions.height
-                            };
-
-                            if line_child_offset > 0 {
-                                line_inner_content_size += parent_child_gap;
-                            }
-                            line_inner_content_size += child_size;
-
-                            let is_text_element =
-                                self.element_has_config(child_idx, ElementConfigType::Text);
-                            let is_wrapping_text = if is_text_element {
-                                if let Some(text_cfg_idx) = self
-                                    .find_element_config_index(child_idx, ElementConfigType::Text)
-                                {
-                                    self.text_element_configs[text_cfg_idx].wrap_mode
-                                        == WrapMode::Words
-                                } else {
-                                    false
-                                }
-                            } else {
-                                false
-                            };
+        measured_width =
+            f32::max(line_width, measured_width) - config.letter_spacing as f32;
 
-                            if child_sizing.type_ != SizingType::Percent
-                                && child_sizing.type_ != SizingType::Fixed
-                                && (!is_text_element || is_wrapping_text)
-                            {
-                                line_resizable_buffer.push(child_idx as i32);
+        let result = MeasureTextCacheItem {
+            id,
+            generation: self.generation,
+            measured_words_start_index: temp_word_next,
+            unwrapped_dimensions: Dimensions::new(measured_width, measured_height),
+            min_width,
+            contains_newlines,
+            word_count: (self.live_measured_words() - words_before) as u32,
+        };
+        self.measure_text_cache.insert(id, result);
+        self.text_cache_stats.entries = self.measure_text_cache.len() as u32;
+        self.text_cache_stats.words += result.word_count.max(1);
+        self.frame_stats.text_ms += crate::profiler::now_ms() - measure_start;
+        result
+    }
 
-                                if child_sizing.type_ == SizingType::Grow
-                                    && child_sizing.grow_weight > 0.0
-                                {
-                                    single_line_grow_candidate = match single_line_grow_candidate {
-                                        None => Some(Some(child_idx)),
-                                        Some(Some(_)) | Some(None) => Some(None),
-                                    };
-                                }
-                            }
-                        }
+    fn add_measured_word(&mut self, word: MeasuredWord, previous_word_index: i32) -> i32 {
+        let new_index: i32;
+        if let Some(&free_idx) = self.measured_words_free_list.last() {
+            self.measured_words_free_list.pop();
+            new_index = free_idx;
+            self.measured_words[free_idx as usize] = word;
+        } else {
+            self.measured_words.push(word);
+            new_index = (self.measured_words.len() - 1) as i32;
+        }
+        if previous_word_index >= 0 {
+            self.measured_words[previous_word_index as usize].next = new_index;
+        }
+        new_index
+    }
 
-                        let size_to_distribute =
-                            parent_size - parent_padding - line_inner_content_size;
+    pub fn begin_layout(&mut self) {
+        self.frame_start_ms = crate::profiler::now_ms();
+        self.frame_stats = crate::profiler::FrameStats::default();
+        self.layout_fingerprint = FxHasher::default();
+        self.initialize_ephemeral_memory();
+        self.generation += 1;
+        if self.pressed_this_frame_generation != self.generation {
+            self.pressed_this_frame_ids.clear();
+        }
+        if self.released_this_frame_generation != self.generation {
+            self.released_this_frame_ids.clear();
+        }
+        self.dynamic_element_index = 0;
 
-                        if size_to_distribute < 0.0 {
-                            if parent_clips {
-                                continue;
-                            }
+        // Keep the text measurement cache under its capacity
+        self.evict_text_cache();
 
-                            let mut distribute = size_to_distribute;
-                            while distribute < -EPSILON && !line_resizable_buffer.is_empty() {
-                                let mut largest: f32 = 0.0;
-                                let mut second_largest: f32 = 0.0;
-                                let mut width_to_add = distribute;
+        let root_width = self.layout_dimensions.width;
+        let root_height = self.layout_dimensions.height;
 
-                                for &child_idx in &line_resizable_buffer {
-                                    let cs = if x_axis {
-                                        self.layout_elements[child_idx as usize].dimensions.width
-                                    } else {
-                                        self.layout_elements[child_idx as usize].dimensions.height
-                                    };
-                                    if float_equal(cs, largest) {
-                                        continue;
-                                    }
-                                    if cs > largest {
-                                        second_largest = largest;
-                                        largest = cs;
-                                    }
-                                    if cs < largest {
-                                        second_largest = f32::max(second_largest, cs);
-                                        width_to_add = second_largest - largest;
-                                    }
-                                }
+        self.diagnostics.clear();
+        self.max_elements_exceeded = false;
 
-                                width_to_add = f32::max(
-                                    width_to_add,
-                                    distribute / line_resizable_buffer.len() as f32,
-                                );
+        let root_id = hash_string("Ply__RootContainer", 0);
+        self.open_element_with_id(&root_id);
 
-                                let mut j = 0;
-                                while j < line_resizable_buffer.len() {
-                                    let child_idx = line_resizable_buffer[j] as usize;
-                                    let current_size = if x_axis {
-                                        self.layout_elements[child_idx].dimensions.width
-                                    } else {
-                                        self.layout_elements[child_idx].dimensions.height
-                                    };
-                                    let min_size = if x_axis {
-                                        self.layout_elements[child_idx].min_dimensions.width
-                                    } else {
-                                        self.layout_elements[child_idx].min_dimensions.height
-                                    };
+        let root_decl = ElementDeclaration {
+            layout: LayoutConfig {
+                sizing: SizingConfig {
+                    width: SizingAxis {
+                        type_: SizingType::Fixed,
+                        min_max: SizingMinMax {
+                            min: root_width,
+                            max: root_width,
+                        },
+                        percent: 0.0,
+                        grow_weight: 1.0,
+                    },
+                    height: SizingAxis {
+                        type_: SizingType::Fixed,
+                        min_max: SizingMinMax {
+                            min: root_height,
+                            max: root_height,
+                        },
+                        percent: 0.0,
+                        grow_weight: 1.0,
+                    },
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        self.configure_open_element(root_decl);
+        self.open_layout_element_stack.push(0);
+        self.layout_element_tree_roots.push(LayoutElementTreeRoot {
+            layout_element_index: 0,
+            ..Default::default()
+        });
+    }
 
-                                    if float_equal(current_size, largest) {
-                                        let new_size = current_size + width_to_add;
-                                        if new_size <= min_size {
-                                            if x_axis {
-                                                self.layout_elements[child_idx].dimensions.width =
-                                                    min_size;
-                                            } else {
-                                                self.layout_elements[child_idx].dimensions.height =
-                                                    min_size;
-                                            }
-                                            distribute -= min_size - current_size;
-                                            line_resizable_buffer.swap_remove(j);
-                                            continue;
-                                        }
+    /// The render commands of the last `end_layout`.
+    pub(crate) fn render_commands(&self) -> &[InternalRenderCommand<CustomElementData>] {
+        &self.render_commands
+    }
 
-                                        if x_axis {
-                                            self.layout_elements[child_idx].dimensions.width =
-                                                new_size;
-                                        } else {
-                                            self.layout_elements[child_idx].dimensions.height =
-                                                new_size;
-                                        }
-                                        distribute -= new_size - current_size;
-                                    }
+    pub fn end_layout(&mut self) -> &[InternalRenderCommand<CustomElementData>] {
+        self.frame_stats.declaration_ms = crate::profiler::now_ms() - self.frame_start_ms;
+        if self.max_elements_exceeded {
+            // The tree is incomplete, so nothing is laid out
+            self.frame_stats.element_count = self.layout_elements.len() as u32;
+            return &self.render_commands;
+        }
 
-                                    j += 1;
-                                }
-                            }
-                        } else if size_to_distribute > 0.0 {
-                            if let Some(Some(single_line_grow_child_idx)) =
-                                single_line_grow_candidate
-                            {
-                                let child_layout_idx =
-                                    self.layout_elements[single_line_grow_child_idx]
-                                        .layout_config_index;
-                                let child_max_size = if x_axis {
-                                    self.layout_configs[child_layout_idx].sizing.width.min_max.max
-                                } else {
-                                    self.layout_configs[child_layout_idx]
-                                        .sizing
-                                        .height
-                                        .min_max
-                                        .max
-                                };
+        // The root sits on the stack twice, see `begin_layout`
+        let unclosed = self.open_layout_element_stack.len().saturating_sub(2);
+        if unclosed > 0 {
+            self.diagnostics.push(Diagnostic::UnbalancedElements { unclosed });
+        }
+        // Close anything left open, then the root
+        while self.open_layout_element_stack.len() > 1 {
+            self.close_element();
+        }
 
-                                let child_size_ref = if x_axis {
-                                    &mut self.layout_elements[single_line_grow_child_idx]
-                                        .dimensions
-                                        .width
-                                } else {
-                                    &mut self.layout_elements[single_line_grow_child_idx]
-                                        .dimensions
-                                        .height
-                                };
+        // Overrides for elements this frame didn't declare are dropped
+        let declared = &self.layout_element_map;
+        self.z_index_overrides.retain(|id, _| declared.contains_key(id));
+        // As are errors of inputs gone or without a validator now
+        let validated = &self.validated_text_inputs;
+        self.text_input_errors.retain(|id, _| validated.contains(id));
 
-                                *child_size_ref =
-                                    f32::min(*child_size_ref + size_to_distribute, child_max_size);
-                            } else {
-                                let mut j = 0;
-                                while j < line_resizable_buffer.len() {
-                                    let child_idx = line_resizable_buffer[j] as usize;
-                                    let child_layout_idx =
-                                        self.layout_elements[child_idx].layout_config_index;
-                                    let child_sizing = if x_axis {
-                                        self.layout_configs[child_layout_idx].sizing.width
-                                    } else {
-                                        self.layout_configs[child_layout_idx].sizing.height
-                                    };
-                                    if child_sizing.type_ != SizingType::Grow
-                                        || child_sizing.grow_weight <= 0.0
-                                    {
-                                        line_resizable_buffer.swap_remove(j);
-                                    } else {
-                                        j += 1;
-                                    }
-                                }
+        self.debug_view_start = self.layout_elements.len();
+        if self.debug_mode_enabled {
+            self.render_debug_view();
+        }
 
-                                let mut distribute = size_to_distribute;
-                                while distribute > EPSILON && !line_resizable_buffer.is_empty() {
-                                    let mut total_weight = 0.0;
-                                    let mut smallest_ratio = MAXFLOAT;
-                                    let mut second_smallest_ratio = MAXFLOAT;
-
-                                    for &child_idx in &line_resizable_buffer {
-                                        let child_layout_idx =
-                                            self.layout_elements[child_idx as usize]
-                                                .layout_config_index;
-                                        let child_sizing = if x_axis {
-                                            self.layout_configs[child_layout_idx].sizing.width
-                                        } else {
-                                            self.layout_configs[child_layout_idx].sizing.height
-                                        };
+        self.calculate_final_layout();
+        self.frame_stats.element_count = self.layout_elements.len() as u32;
+        self.frame_stats.render_command_count = self.render_commands.len() as u32;
+        &self.render_commands
+    }
 
-                                        total_weight += child_sizing.grow_weight;
+    fn live_measured_words(&self) -> usize {
+        self.measured_words.len() - self.measured_words_free_list.len()
+    }
 
-                                        let child_size = if x_axis {
-                                            self.layout_elements[child_idx as usize].dimensions.width
-                                        } else {
-                                            self.layout_elements[child_idx as usize].dimensions.height
-                                        };
-                                        let child_ratio = child_size / child_sizing.grow_weight;
+    /// Evicts the least recently used text measurements once the cache holds
+    /// more than `max_measure_text_cache_word_count` words, down to three
+    /// quarters of it so eviction doesn't run every frame. Entries count as at
+    /// least one word. Entries used last frame are kept.
+    fn evict_text_cache(&mut self) {
+        let capacity = self.max_measure_text_cache_word_count.max(0) as u32;
+        if self.text_cache_stats.words <= capacity {
+            return;
+        }
+        let target = capacity - capacity / 4;
+        let gen = self.generation;
+        let mut by_age: Vec<(u32, u64)> = self
+            .measure_text_cache
+            .values()
+            .map(|item| (gen.wrapping_sub(item.generation), item.id))
+            .collect();
+        by_age.sort_unstable_by_key(|&(age, _)| std::cmp::Reverse(age));
+
+        for (age, id) in by_age {
+            if self.text_cache_stats.words <= target || age <= 1 {
+                break;
+            }
+            let Some(item) = self.measure_text_cache.remove(&id) else {
+                continue;
+            };
+            let mut idx = item.measured_words_start_index;
+            for _ in 0..item.word_count {
+                self.measured_words_free_list.push(idx);
+                idx = self.measured_words[idx as usize].next;
+            }
+            self.text_cache_stats.words -= item.word_count.max(1);
+            self.text_cache_stats.evictions += 1;
+        }
+        self.text_cache_stats.entries = self.measure_text_cache.len() as u32;
+    }
 
-                                        if float_equal(child_ratio, smallest_ratio) {
-                                            continue;
-                                        }
-                                        if child_ratio < smallest_ratio {
-                                            second_smallest_ratio = smallest_ratio;
-                                            smallest_ratio = child_ratio;
-                                        } else if child_ratio > smallest_ratio {
-                                            second_smallest_ratio =
-                                                f32::min(second_smallest_ratio, child_ratio);
-                                        }
-                                    }
+    /// Forgets every text measurement, for when the measure function changes.
+    fn clear_text_cache(&mut self) {
+        self.measure_text_cache.clear();
+        self.measured_words.clear();
+        self.measured_words_free_list.clear();
+        self.text_cache_stats = crate::profiler::TextCacheStats::default();
+    }
 
-                                    if total_weight <= 0.0 {
-                                        break;
-                                    }
+    fn initialize_ephemeral_memory(&mut self) {
+        self.layout_element_children_buffer.clear();
+        self.layout_elements.clear();
+        self.layout_configs.clear();
+        self.element_configs.clear();
+        self.text_element_configs.clear();
+        self.text_element_effects.clear();
+        self.aspect_ratio_configs.clear();
+        self.aspect_ratio_cover_configs.clear();
+        self.image_element_configs.clear();
+        self.floating_element_configs.clear();
+        self.clip_element_configs.clear();
+        self.custom_element_configs.clear();
+        self.canvas_element_configs.clear();
+        self.draw_hook_configs.clear();
+        self.border_element_configs.clear();
+        self.shared_element_configs.clear();
+        self.frame_shaders.clear();
+        self.element_effects.clear();
+        self.element_shaders.clear();
+        self.element_visual_rotations.clear();
+        self.element_shape_rotations.clear();
+        self.element_pre_rotation_dimensions.clear();
+        self.layout_element_id_strings.clear();
+        self.wrapped_text_lines.clear();
+        self.tree_node_array.clear();
+        self.layout_element_tree_roots.clear();
+        self.layout_element_children.clear();
+        self.pointer_callbacks.release_unused();
+        self.focus_callbacks.release_unused();
+        for item in self.layout_element_map.values_mut() {
+            self.pointer_callbacks.recycle(item.on_hover_fn.take());
+            self.pointer_callbacks.recycle(item.on_press_fn.take());
+            self.pointer_callbacks.recycle(item.on_release_fn.take());
+            self.focus_callbacks.recycle(item.on_focus_fn.take());
+            self.focus_callbacks.recycle(item.on_unfocus_fn.take());
+        }
+        self.previous_element_bounds.clear();
+        self.previous_element_bounds
+            .extend(self.layout_element_map.iter().map(|(&id, item)| (id, item.bounding_box)));
+        self.layout_element_map.clear();
+        self.elements_positioned = false;
+        self.open_layout_element_stack.clear();
+        self.text_element_data.clear();
+        self.aspect_ratio_element_indexes.clear();
+        self.render_commands.clear();
+        self.tree_node_visited.clear();
+        self.open_clip_element_stack.clear();
+        self.reusable_element_index_buffer.clear();
+        self.layout_element_clip_element_ids.clear();
+        self.dynamic_string_data.clear();
+        self.focusable_elements.clear();
+        self.focus_groups.clear();
+        self.open_focus_groups.clear();
+        self.accessibility_configs.clear();
+        self.accessibility_element_order.clear();
+        self.text_input_configs.clear();
+        self.text_input_element_ids.clear();
+        self.validated_text_inputs.clear();
+    }
 
-                                    let per_weight_growth = distribute / total_weight;
-                                    let ratio_step_cap = if second_smallest_ratio == MAXFLOAT {
-                                        MAXFLOAT
-                                    } else {
-                                        second_smallest_ratio - smallest_ratio
-                                    };
+    /// Size of a child along an axis, including its margins.
+    fn child_size_on_axis(&self, child_index: usize, x_axis: bool) -> f32 {
+        let margin = self.child_margin(child_index).along(x_axis);
+        if x_axis {
+            self.layout_elements[child_index].dimensions.width + margin
+        } else {
+            self.layout_elements[child_index].dimensions.height + margin
+        }
+    }
 
-                                    let mut resized_any = false;
+    fn child_margin(&self, child_index: usize) -> MarginConfig {
+        self.layout_configs[self.layout_elements[child_index].layout_config_index].margin
+    }
 
-                                    let mut j = 0;
-                                    while j < line_resizable_buffer.len() {
-                                        let child_idx = line_resizable_buffer[j] as usize;
-                                        let child_layout_idx =
-                                            self.layout_elements[child_idx].layout_config_index;
-                                        let child_sizing = if x_axis {
-                                            self.layout_configs[child_layout_idx].sizing.width
-                                        } else {
-                                            self.layout_configs[child_layout_idx].sizing.height
-                                        };
+    fn child_sizing_on_axis(&self, child_index: usize, x_axis: bool) -> SizingAxis {
+        let child_layout_idx = self.layout_elements[child_index].layout_config_index;
+        if x_axis {
+            self.layout_configs[child_layout_idx].sizing.width
+        } else {
+            self.layout_configs[child_layout_idx].sizing.height
+        }
+    }
 
-                                        let child_size_ref = if x_axis {
-                                            &mut self.layout_elements[child_idx].dimensions.width
-                                        } else {
-                                            &mut self.layout_elements[child_idx].dimensions.height
-                                        };
+    fn child_wrap_break_main_size(&self, child_index: usize, main_axis_x: bool) -> f32 {
+        let dimensions = self.layout_elements[child_index].dimensions;
+        wrap_break_size(
+            self.child_sizing_on_axis(child_index, main_axis_x),
+            if main_axis_x { dimensions.width } else { dimensions.height },
+            self.child_margin(child_index).along(main_axis_x),
+        )
+    }
 
-                                        let child_ratio =
-                                            *child_size_ref / child_sizing.grow_weight;
-                                        if !float_equal(child_ratio, smallest_ratio) {
-                                            j += 1;
-                                            continue;
-                                        }
+    fn build_wrapped_line(
+        &self,
+        parent_index: usize,
+        start_child_offset: usize,
+        end_child_offset: usize,
+        main_axis_x: bool,
+    ) -> WrappedLayoutLine {
+        let layout_idx = self.layout_elements[parent_index].layout_config_index;
+        let layout = self.layout_configs[layout_idx];
+        let children_start = self.layout_elements[parent_index].children_start;
 
-                                        let max_size = if x_axis {
-                                            self.layout_configs[child_layout_idx]
-                                                .sizing
-                                                .width
-                                                .min_max
-                                                .max
-                                        } else {
-                                            self.layout_configs[child_layout_idx]
-                                                .sizing
-                                                .height
-                                                .min_max
-                                                .max
-                                        };
+        let mut main_size = 0.0;
+        let mut cross_size = 0.0;
 
-                                        let mut growth_share =
-                                            per_weight_growth * child_sizing.grow_weight;
-                                        if ratio_step_cap != MAXFLOAT {
-                                            growth_share = f32::min(
-                                                growth_share,
-                                                ratio_step_cap * child_sizing.grow_weight,
-                                            );
-                                        }
+        for child_offset in start_child_offset..end_child_offset {
+            let child_index = self.layout_element_children[children_start + child_offset] as usize;
+            if child_offset > start_child_offset {
+                main_size += layout.child_gap as f32;
+            }
 
-                                        let previous = *child_size_ref;
-                                        let proposed = previous + growth_share;
-                                        if proposed >= max_size {
-                                            *child_size_ref = max_size;
-                                            resized_any = true;
-                                            line_resizable_buffer.swap_remove(j);
-                                            continue;
-                                        }
+            let child_main = self.child_size_on_axis(child_index, main_axis_x);
+            let child_cross = self.child_size_on_axis(child_index, !main_axis_x);
+            main_size += child_main;
+            cross_size = f32::max(cross_size, child_cross);
+        }
+        if main_axis_x && layout.child_alignment.y == AlignY::Baseline {
+            let (above, below) = self.baseline_extent(parent_index, start_child_offset, end_child_offset);
+            cross_size = f32::max(cross_size, above + below);
+        }
 
-                                        *child_size_ref = proposed;
-                                        distribute -= *child_size_ref - previous;
-                                        resized_any = true;
-                                        j += 1;
-                                    }
+        WrappedLayoutLine {
+            start_child_offset,
+            end_child_offset,
+            main_size,
+            cross_size,
+        }
+    }
 
-                                    if !resized_any {
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                } else if sizing_along_axis {
-                    let size_to_distribute = parent_size - parent_padding - inner_content_size;
+    /// Baseline of a text line with the given natural height, from the
+    /// baseline function if set, otherwise estimated from the height.
+    fn text_baseline(&self, config: &TextConfig, line_height: f32) -> f32 {
+        match &self.text_baseline_fn {
+            Some(baseline_fn) => baseline_fn(config).min(line_height),
+            None => line_height * crate::text::INLINE_BASELINE_RATIO,
+        }
+    }
 
-                    if size_to_distribute < 0.0 {
-                        // Check if parent clips
-                        let parent_clips = if let Some(clip_idx) = self
-                            .find_element_config_index(parent_index, ElementConfigType::Clip)
-                        {
-                            let clip = &self.clip_element_configs[clip_idx];
-                            (x_axis && clip.horizontal) || (!x_axis && clip.vertical)
-                        } else {
-                            false
-                        };
-                        if parent_clips {
-                            continue;
-                        }
+    /// Distance from the top of an element to its first baseline.
+    ///
+    /// Text uses its first line, baseline-aligned rows their shared baseline,
+    /// other containers their first child. Elements without text use their bottom edge.
+    fn element_baseline(&self, elem_idx: usize) -> f32 {
+        let element = &self.layout_elements[elem_idx];
+        if let Some(config_index) = self.find_element_config_index(elem_idx, ElementConfigType::Text) {
+            if element.text_data_index < 0 {
+                return element.dimensions.height;
+            }
+            let text_data = &self.text_element_data[element.text_data_index as usize];
+            let natural_line_height = text_data.preferred_dimensions.height;
+            let line_height = self.text_element_configs[config_index].line_height;
+            let line_height_offset = if line_height > 0 {
+                (line_height as f32 - natural_line_height) / 2.0
+            } else {
+                0.0
+            };
+            return line_height_offset + text_data.baseline;
+        }
+        if element.children_length == 0 {
+            return element.dimensions.height;
+        }
+        let layout = self.layout_configs[element.layout_config_index];
+        let first_child = self.layout_element_children[element.children_start] as usize;
+        if layout.layout_direction == LayoutDirection::LeftToRight
+            && layout.child_alignment.y == AlignY::Baseline
+            && !layout.wrap
+        {
+            let (above, _) = self.baseline_extent(elem_idx, 0, element.children_length as usize);
+            return layout.padding.top as f32 + above;
+        }
+        layout.padding.top as f32
+            + self.child_margin(first_child).top as f32
+            + self.element_baseline(first_child)
+    }
 
-                        // Compress children
-                        let mut distribute = size_to_distribute;
-                        while distribute < -EPSILON && !resizable_container_buffer.is_empty() {
-                            let mut largest: f32 = 0.0;
-                            let mut second_largest: f32 = 0.0;
-                            let mut width_to_add = distribute;
+    /// Largest extent above and below the baseline among a range of children.
+    fn baseline_extent(&self, parent_index: usize, start_child_offset: usize, end_child_offset: usize) -> (f32, f32) {
+        let children_start = self.layout_elements[parent_index].children_start;
+        let mut above: f32 = 0.0;
+        let mut below: f32 = 0.0;
+        for child_offset in start_child_offset..end_child_offset {
+            let child_index = self.layout_element_children[children_start + child_offset] as usize;
+            let baseline = self.element_baseline(child_index);
+            let margin = self.child_margin(child_index);
+            above = above.max(baseline + margin.top as f32);
+            let height = self.layout_elements[child_index].dimensions.height;
+            below = below.max(height - baseline + margin.bottom as f32);
+        }
+        (above, below)
+    }
 
-                            for &child_idx in &resizable_container_buffer {
-                                let cs = if x_axis {
-                                    self.layout_elements[child_idx as usize].dimensions.width
-                                } else {
-                                    self.layout_elements[child_idx as usize].dimensions.height
-                                };
-                                if float_equal(cs, largest) {
-                                    continue;
-                                }
-                                if cs > largest {
-                                    second_largest = largest;
-                                    largest = cs;
-                                }
-                                if cs < largest {
-                                    second_largest = f32::max(second_largest, cs);
-                                    width_to_add = second_largest - largest;
-                                }
-                            }
-                            width_to_add = f32::max(
-                                width_to_add,
-                                distribute / resizable_container_buffer.len() as f32,
-                            );
+    fn compute_wrapped_lines(
+        &self,
+        parent_index: usize,
+        main_axis_x: bool,
+    ) -> Vec<WrappedLayoutLine> {
+        let layout_idx = self.layout_elements[parent_index].layout_config_index;
+        let layout = self.layout_configs[layout_idx];
 
-                            let mut j = 0;
-                            while j < resizable_container_buffer.len() {
-                                let child_idx = resizable_container_buffer[j] as usize;
-                                let current_size = if x_axis {
-                                    self.layout_elements[child_idx].dimensions.width
-                                } else {
-                                    self.layout_elements[child_idx].dimensions.height
-                                };
-                                let min_size = if x_axis {
-                                    self.layout_elements[child_idx].min_dimensions.width
-                                } else {
-                                    self.layout_elements[child_idx].min_dimensions.height
-                                };
-                                if float_equal(current_size, largest) {
-                                    let new_size = current_size + width_to_add;
-                                    if new_size <= min_size {
-                                        if x_axis {
-                                            self.layout_elements[child_idx].dimensions.width = min_size;
-                                        } else {
-                                            self.layout_elements[child_idx].dimensions.height = min_size;
-                                        }
-                                        distribute -= min_size - current_size;
-                                        resizable_container_buffer.swap_remove(j);
-                                        continue;
-                                    }
-                                    if x_axis {
-                                        self.layout_elements[child_idx].dimensions.width = new_size;
-                                    } else {
-                                        self.layout_elements[child_idx].dimensions.height = new_size;
-                                    }
-                                    distribute -= new_size - current_size;
-                                }
-                                j += 1;
-                            }
-                        }
-                    } else if size_to_distribute > 0.0 {
-                        if let Some(Some(single_along_axis_grow_child_idx)) =
-                            single_along_axis_grow_candidate
-                        {
-                            let child_layout_idx = self
-                                .layout_elements[single_along_axis_grow_child_idx]
-                                .layout_config_index;
-                            let child_max_size = if x_axis {
-                                self.layout_configs[child_layout_idx].sizing.width.min_max.max
-                            } else {
-                                self.layout_configs[child_layout_idx].sizing.height.min_max.max
-                            };
-                            let child_size_ref = if x_axis {
-                                &mut self.layout_elements[single_along_axis_grow_child_idx]
-                                    .dimensions
-                                    .width
-                            } else {
-                                &mut self.layout_elements[single_along_axis_grow_child_idx]
-                                    .dimensions
-                                    .height
-                            };
-                            *child_size_ref = f32::min(*child_size_ref + size_to_distribute, child_max_size);
-                        } else {
-                            // Remove non-grow from resizable buffer
-                            let mut j = 0;
-                            while j < resizable_container_buffer.len() {
-                                let child_idx = resizable_container_buffer[j] as usize;
-                                let child_layout_idx =
-                                    self.layout_elements[child_idx].layout_config_index;
-                                let child_sizing = if x_axis {
-                                    self.layout_configs[child_layout_idx].sizing.width
-                                } else {
-                                    self.layout_configs[child_layout_idx].sizing.height
-                                };
-                                if child_sizing.type_ != SizingType::Grow
-                                    || child_sizing.grow_weight <= 0.0
-                                {
-                                    resizable_container_buffer.swap_remove(j);
-                                } else {
-                                    j += 1;
-                                }
-                            }
+        let children_length = self.layout_elements[parent_index].children_length as usize;
+        if children_length == 0 {
+            return Vec::new();
+        }
 
-                            let mut distribute = size_to_distribute;
-                            while distribute > EPSILON && !resizable_container_buffer.is_empty() {
-                                let mut total_weight = 0.0;
-                                let mut smallest_ratio = MAXFLOAT;
-                                let mut second_smallest_ratio = MAXFLOAT;
-
-                                for &child_idx in &resizable_container_buffer {
-                                    let child_layout_idx =
-                                        self.layout_elements[child_idx as usize].layout_config_index;
-                                    let child_sizing = if x_axis {
-                                        self.layout_configs[child_layout_idx].sizing.width
-                                    } else {
-                                        self.layout_configs[child_layout_idx].sizing.height
-                                    };
+        let parent_main_size = if main_axis_x {
+            self.layout_elements[parent_index].dimensions.width
+        } else {
+            self.layout_elements[parent_index].dimensions.height
+        };
+        let main_padding = if main_axis_x {
+            (layout.padding.left + layout.padding.right) as f32
+        } else {
+            (layout.padding.top + layout.padding.bottom) as f32
+        };
+        let available_main = (parent_main_size - main_padding).max(0.0);
 
-                                    total_weight += child_sizing.grow_weight;
+        if !layout.wrap {
+            return vec![self.build_wrapped_line(
+                parent_index,
+                0,
+                children_length,
+                main_axis_x,
+            )];
+        }
 
-                                    let child_size = if x_axis {
-                                        self.layout_elements[child_idx as usize].dimensions.width
-                                    } else {
-                                        self.layout_elements[child_idx as usize].dimensions.height
-                                    };
-                                    let child_ratio = child_size / child_sizing.grow_weight;
+        let children_start = self.layout_elements[parent_index].children_start;
+        let mut ranges = Vec::new();
+        break_wrapped_lines(
+            children_length,
+            available_main,
+            layout.child_gap as f32,
+            |child_offset| {
+                let child_index = self.layout_element_children[children_start + child_offset] as usize;
+                self.child_wrap_break_main_size(child_index, main_axis_x)
+            },
+            &mut ranges,
+        );
+        ranges
+            .into_iter()
+            .map(|(start, end)| self.build_wrapped_line(parent_index, start, end, main_axis_x))
+            .collect()
+    }
 
-                                    if float_equal(child_ratio, smallest_ratio) {
-                                        continue;
-                                    }
-                                    if child_ratio < smallest_ratio {
-                                        second_smallest_ratio = smallest_ratio;
-                                        smallest_ratio = child_ratio;
-                                    } else if child_ratio > smallest_ratio {
-                                        second_smallest_ratio = f32::min(second_smallest_ratio, child_ratio);
-                                    }
-                                }
+    fn wrapped_content_dimensions(
+        &self,
+        parent_index: usize,
+        main_axis_x: bool,
+        lines: &[WrappedLayoutLine],
+    ) -> Dimensions {
+        let layout_idx = self.layout_elements[parent_index].layout_config_index;
+        let layout = self.layout_configs[layout_idx];
 
-                                if total_weight <= 0.0 {
-                                    break;
-                                }
+        let lr_padding = (layout.padding.left + layout.padding.right) as f32;
+        let tb_padding = (layout.padding.top + layout.padding.bottom) as f32;
 
-                                let per_weight_growth = distribute / total_weight;
+        if lines.is_empty() {
+            return Dimensions::new(lr_padding, tb_padding);
+        }
 
-                                let ratio_step_cap = if second_smallest_ratio == MAXFLOAT {
-                                    MAXFLOAT
-                                } else {
-                                    second_smallest_ratio - smallest_ratio
-                                };
+        let wrap_gap_total = lines.len().saturating_sub(1) as f32 * layout.wrap_gap as f32;
+        let max_main = lines.iter().fold(0.0_f32, |acc, line| acc.max(line.main_size));
+        let total_cross = lines.iter().map(|line| line.cross_size).sum::<f32>() + wrap_gap_total;
 
-                                let mut resized_any = false;
+        if main_axis_x {
+            Dimensions::new(max_main + lr_padding, total_cross + tb_padding)
+        } else {
+            Dimensions::new(total_cross + lr_padding, max_main + tb_padding)
+        }
+    }
 
-                                let mut j = 0;
-                                while j < resizable_container_buffer.len() {
-                                    let child_idx = resizable_container_buffer[j] as usize;
-                                    let child_layout_idx =
-                                        self.layout_elements[child_idx].layout_config_index;
-                                    let child_sizing = if x_axis {
-                                        self.layout_configs[child_layout_idx].sizing.width
-                                    } else {
-                                        self.layout_configs[child_layout_idx].sizing.height
-                                    };
+    fn size_containers_along_axis(&mut self, x_axis: bool) {
+        let mut level = std::mem::take(&mut self.sizing_level);
+        let mut next_level = std::mem::take(&mut self.sizing_next_level);
+        let mut chunks = std::mem::take(&mut self.sizing_scratch);
 
-                                    let child_size_ref = if x_axis {
-                                        &mut self.layout_elements[child_idx].dimensions.width
-                                    } else {
-                                        &mut self.layout_elements[child_idx].dimensions.height
-                                    };
+        for root_index in 0..self.layout_element_tree_roots.len() {
+            let root = self.layout_element_tree_roots[root_index];
+            let root_elem_idx = root.layout_element_index as usize;
 
-                                    let child_ratio = *child_size_ref / child_sizing.grow_weight;
-                                    if !float_equal(child_ratio, smallest_ratio) {
-                                        j += 1;
-                                        continue;
-                                    }
+            // Size floating containers to their parents
+            if self.element_has_config(root_elem_idx, ElementConfigType::Floating) {
+                if let Some(float_cfg_idx) =
+                    self.find_element_config_index(root_elem_idx, ElementConfigType::Floating)
+                {
+                    let parent_id = self.floating_element_configs[float_cfg_idx].parent_id;
+                    if let Some(parent_item) = self.layout_element_map.get(&parent_id) {
+                        let parent_elem_idx = parent_item.layout_element_index as usize;
+                        let parent_dims = self.layout_elements[parent_elem_idx].dimensions;
+                        let root_layout_idx =
+                            self.layout_elements[root_elem_idx].layout_config_index;
 
-                                    let max_size = if x_axis {
-                                        self.layout_configs[child_layout_idx]
+                        let w_type = self.layout_configs[root_layout_idx].sizing.width.type_;
+                        match w_type {
+                            SizingType::Grow => {
+                                self.layout_elements[root_elem_idx].dimensions.width =
+                                    parent_dims.width;
+                            }
+                            SizingType::Percent => {
+                                self.layout_elements[root_elem_idx].dimensions.width =
+                                    parent_dims.width
+                                        * self.layout_configs[root_layout_idx]
                                             .sizing
                                             .width
-                                            .min_max
-                                            .max
-                                    } else {
-                                        self.layout_configs[child_layout_idx]
+                                            .percent;
+                            }
+                            _ => {}
+                        }
+                        let h_type = self.layout_configs[root_layout_idx].sizing.height.type_;
+                        match h_type {
+                            SizingType::Grow => {
+                                self.layout_elements[root_elem_idx].dimensions.height =
+                                    parent_dims.height;
+                            }
+                            SizingType::Percent => {
+                                self.layout_elements[root_elem_idx].dimensions.height =
+                                    parent_dims.height
+                                        * self.layout_configs[root_layout_idx]
                                             .sizing
                                             .height
-                                            .min_max
-                                            .max
-                                    };
-
-                                    let mut growth_share = per_weight_growth * child_sizing.grow_weight;
-                                    if ratio_step_cap != MAXFLOAT {
-                                        growth_share =
-                                            f32::min(growth_share, ratio_step_cap * child_sizing.grow_weight);
-                                    }
-
-                                    let previous = *child_size_ref;
-                                    let proposed = previous + growth_share;
-                                    if proposed >= max_size {
-                                        *child_size_ref = max_size;
-                                        resized_any = true;
-                                        resizable_container_buffer.swap_remove(j);
-                                        continue;
-                                    }
-
-                                    *child_size_ref = proposed;
-                                    distribute -= *child_size_ref - previous;
-                                    resized_any = true;
-                                    j += 1;
-                                }
-
-                                if !resized_any {
-                                    break;
-                                }
+                                            .percent;
                             }
+                            _ => {}
                         }
                     }
-                } else {
-                    // Off-axis sizing
-                    for &child_idx in &resizable_container_buffer {
-                        let child_idx = child_idx as usize;
-                        let child_layout_idx =
-                            self.layout_elements[child_idx].layout_config_index;
-                        let child_sizing = if x_axis {
-                            self.layout_configs[child_layout_idx].sizing.width
-                        } else {
-                            self.layout_configs[child_layout_idx].sizing.height
-                        };
-                        let min_size = if x_axis {
-                            self.layout_elements[child_idx].min_dimensions.width
-                        } else {
-                            self.layout_elements[child_idx].min_dimensions.height
-                        };
+                }
+            }
 
-                        let mut max_size = parent_size - parent_padding;
-                        if let Some(clip_idx) =
-                            self.find_element_config_index(parent_index, ElementConfigType::Clip)
-                        {
-                            let clip = &self.clip_element_configs[clip_idx];
-                            if (x_axis && clip.horizontal) || (!x_axis && clip.vertical) {
-                                max_size = f32::max(max_size, inner_content_size);
+            // Clamp root element
+            let root_layout_idx = self.layout_elements[root_elem_idx].layout_config_index;
+            if self.layout_configs[root_layout_idx].sizing.width.type_ != SizingType::Percent {
+                let min = self.layout_configs[root_layout_idx].sizing.width.min_max.min;
+                let max = self.layout_configs[root_layout_idx].sizing.width.min_max.max;
+                self.layout_elements[root_elem_idx].dimensions.width = f32::min(
+                    f32::max(self.layout_elements[root_elem_idx].dimensions.width, min),
+                    max,
+                );
+            }
+            if self.layout_configs[root_layout_idx].sizing.height.type_ != SizingType::Percent {
+                let min = self.layout_configs[root_layout_idx].sizing.height.min_max.min;
+                let max = self.layout_configs[root_layout_idx].sizing.height.min_max.max;
+                self.layout_elements[root_elem_idx].dimensions.height = f32::min(
+                    f32::max(self.layout_elements[root_elem_idx].dimensions.height, min),
+                    max,
+                );
+            }
+
+            // Size a level of the tree at a time: its parents only touch their own children
+            level.clear();
+            level.push(root.layout_element_index);
+            while !level.is_empty() {
+                let chunk_count = level.len().div_ceil(LAYOUT_CHUNK_LEN);
+                if chunks.len() < chunk_count {
+                    chunks.resize_with(chunk_count, SizingScratch::default);
+                }
+                let tree = self.sizing_tree();
+                let size_chunk = |(parents, scratch): (&[i32], &mut SizingScratch)| {
+                    scratch.dimensions.clear();
+                    for &parent_index in parents {
+                        tree.size_children(parent_index as usize, x_axis, scratch);
+                    }
+                };
+                #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+                if chunk_count > 1 {
+                    use rayon::prelude::*;
+                    level.par_chunks(LAYOUT_CHUNK_LEN).zip(chunks.par_iter_mut()).for_each(size_chunk);
+                } else {
+                    level.chunks(LAYOUT_CHUNK_LEN).zip(chunks.iter_mut()).for_each(size_chunk);
+                }
+                #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+                level.chunks(LAYOUT_CHUNK_LEN).zip(chunks.iter_mut()).for_each(size_chunk);
+
+                // Apply the new sizes and queue the children with children of their own
+                next_level.clear();
+                for (parents, scratch) in level.chunks(LAYOUT_CHUNK_LEN).zip(&chunks) {
+                    let mut sizes = scratch.dimensions.iter();
+                    for &parent_index in parents {
+                        let parent = self.layout_elements[parent_index as usize];
+                        for child_offset in 0..parent.children_length as usize {
+                            let child_index = self.layout_element_children[parent.children_start + child_offset];
+                            let child = &mut self.layout_elements[child_index as usize];
+                            child.dimensions = *sizes.next().unwrap();
+                            if child.children_length > 0
+                                && !self.element_has_config(child_index as usize, ElementConfigType::Text)
+                            {
+                                next_level.push(child_index);
                             }
                         }
+                    }
+                }
+                std::mem::swap(&mut level, &mut next_level);
+            }
+        }
 
-                        let is_cover_aspect = self
-                            .find_element_config_index(child_idx, ElementConfigType::Aspect)
-                            .map(|cfg_idx| self.aspect_ratio_cover_configs[cfg_idx])
-                            .unwrap_or(false);
+        self.sizing_level = level;
+        self.sizing_next_level = next_level;
+        self.sizing_scratch = chunks;
+    }
 
-                        let child_size_ref = if x_axis {
-                            &mut self.layout_elements[child_idx].dimensions.width
-                        } else {
-                            &mut self.layout_elements[child_idx].dimensions.height
-                        };
+    /// Borrows what sizing reads from this frame's tree.
+    fn sizing_tree(&self) -> SizingTree<'_> {
+        SizingTree {
+            layout_elements: &self.layout_elements,
+            layout_element_children: &self.layout_element_children,
+            layout_configs: &self.layout_configs,
+            element_configs: &self.element_configs,
+            text_element_configs: &self.text_element_configs,
+            clip_element_configs: &self.clip_element_configs,
+            aspect_ratio_configs: &self.aspect_ratio_configs,
+            aspect_ratio_cover_configs: &self.aspect_ratio_cover_configs,
+        }
+    }
+
+    fn calculate_final_layout(&mut self) {
+        crate::telemetry::span!("ply::layout");
+        let layout_start = crate::profiler::now_ms();
 
-                        if child_sizing.type_ == SizingType::Grow && child_sizing.grow_weight > 0.0 {
-                            if is_cover_aspect {
-                                *child_size_ref = f32::max(*child_size_ref, max_size);
-                            } else {
-                                *child_size_ref = f32::min(max_size, child_sizing.min_max.max);
-                            }
-                        }
+        let fingerprint = self.finish_layout_fingerprint();
+        if self.restore_layout_snapshot(fingerprint) {
+            self.frame_stats.layout_cached = true;
+        } else {
+            self.size_and_wrap();
+            self.store_layout_snapshot(fingerprint);
+        }
 
-                        if is_cover_aspect {
-                            *child_size_ref = f32::max(min_size, *child_size_ref);
-                        } else {
-                            *child_size_ref = f32::max(min_size, f32::min(*child_size_ref, max_size));
-                        }
-                    }
+        // Sort tree roots by z-index (bubble sort)
+        let mut sort_max = self.layout_element_tree_roots.len().saturating_sub(1);
+        while sort_max > 0 {
+            for i in 0..sort_max {
+                if self.layout_element_tree_roots[i + 1].z_index
+                    < self.layout_element_tree_roots[i].z_index
+                {
+                    self.layout_element_tree_roots.swap(i, i + 1);
                 }
             }
+            sort_max -= 1;
         }
+
+        // Generate render commands
+        let commands_start = crate::profiler::now_ms();
+        self.frame_stats.layout_ms = commands_start - layout_start;
+        self.generate_render_commands();
+        self.elements_positioned = true;
+        self.frame_stats.render_commands_ms = crate::profiler::now_ms() - commands_start;
     }
 
-    fn calculate_final_layout(&mut self) {
+    /// Sizes every element and wraps text, the bulk of layout.
+    fn size_and_wrap(&mut self) {
         // Size along X axis
         self.size_containers_along_axis(true);
 
         // Wrap text
+        let wrap_start = crate::profiler::now_ms();
         self.wrap_text();
+        self.frame_stats.text_ms += crate::profiler::now_ms() - wrap_start;
 
         // Scale vertical heights by aspect ratio
         for i in 0..self.aspect_ratio_element_indexes.len() {
@@ -3387,138 +4473,159 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 };
             }
         }
+    }
 
-        // Sort tree roots by z-index (bubble sort)
-        let mut sort_max = self.layout_element_tree_roots.len().saturating_sub(1);
-        while sort_max > 0 {
-            for i in 0..sort_max {
-                if self.layout_element_tree_roots[i + 1].z_index
-                    < self.layout_element_tree_roots[i].z_index
-                {
-                    self.layout_element_tree_roots.swap(i, i + 1);
-                }
+    /// Completes the frame's layout fingerprint with what can change after
+    /// elements are declared.
+    fn finish_layout_fingerprint(&mut self) -> u64 {
+        // The root may have been resized by `set_root_dimensions`
+        if let Some(root) = self.layout_elements.first() {
+            hash_layout_config(&mut self.layout_fingerprint, &self.layout_configs[root.layout_config_index]);
+        }
+        self.layout_fingerprint.write_usize(self.layout_elements.len());
+        self.layout_fingerprint.finish()
+    }
+
+    /// Takes sizes and text wrapping from the last frame if it declared the
+    /// same layout. Returns whether it did.
+    fn restore_layout_snapshot(&mut self, fingerprint: u64) -> bool {
+        let Some(snapshot) = &self.layout_snapshot else {
+            return false;
+        };
+        if !self.layout_cache_enabled
+            || snapshot.fingerprint != fingerprint
+            || snapshot.dimensions.len() != self.layout_elements.len()
+            || snapshot.layout_configs.len() != self.layout_configs.len()
+            || snapshot.text_lines.len() != self.text_element_data.len()
+        {
+            return false;
+        }
+        for (element, dimensions) in self.layout_elements.iter_mut().zip(&snapshot.dimensions) {
+            element.dimensions = *dimensions;
+        }
+        self.layout_configs.clone_from(&snapshot.layout_configs);
+        self.wrapped_text_lines.clone_from(&snapshot.wrapped_text_lines);
+        for (text_data, &(start, length)) in self.text_element_data.iter_mut().zip(&snapshot.text_lines) {
+            text_data.wrapped_lines_start = start;
+            text_data.wrapped_lines_length = length;
+        }
+        for id in &snapshot.clamped {
+            if let Some(item) = self.layout_element_map.get_mut(id) {
+                item.text_clamped = true;
             }
-            sort_max -= 1;
         }
+        true
+    }
 
-        // Generate render commands
-        self.generate_render_commands();
+    /// Keeps this frame's sizes and text wrapping for the next frame.
+    fn store_layout_snapshot(&mut self, fingerprint: u64) {
+        if !self.layout_cache_enabled {
+            self.layout_snapshot = None;
+            return;
+        }
+        // Reuses the last snapshot's buffers
+        let mut snapshot = self.layout_snapshot.take().unwrap_or_default();
+        snapshot.fingerprint = fingerprint;
+        snapshot.dimensions.clear();
+        snapshot.dimensions.extend(self.layout_elements.iter().map(|element| element.dimensions));
+        snapshot.layout_configs.clone_from(&self.layout_configs);
+        snapshot.wrapped_text_lines.clone_from(&self.wrapped_text_lines);
+        snapshot.text_lines.clear();
+        snapshot.text_lines.extend(
+            self.text_element_data
+                .iter()
+                .map(|text_data| (text_data.wrapped_lines_start, text_data.wrapped_lines_length)),
+        );
+        snapshot.clamped.clear();
+        snapshot.clamped.extend(
+            self.text_element_data
+                .iter()
+                .map(|text_data| text_data.parent_id)
+                .filter(|id| self.layout_element_map.get(id).is_some_and(|item| item.text_clamped)),
+        );
+        self.layout_snapshot = Some(snapshot);
     }
 
     fn wrap_text(&mut self) {
-        for text_idx in 0..self.text_element_data.len() {
-            let elem_index = self.text_element_data[text_idx].element_index as usize;
-            let text = self.text_element_data[text_idx].text.clone();
-            let preferred_dims = self.text_element_data[text_idx].preferred_dimensions;
-
-            self.text_element_data[text_idx].wrapped_lines_start = self.wrapped_text_lines.len();
-            self.text_element_data[text_idx].wrapped_lines_length = 0;
-
-            let container_width = self.layout_elements[elem_index].dimensions.width;
-
-            // Find text config
-            let text_config_idx = self
-                .find_element_config_index(elem_index, ElementConfigType::Text)
-                .unwrap_or(0);
-            let text_config = self.text_element_configs[text_config_idx].clone();
-
-            let measured = self.measure_text_cached(&text, &text_config);
-
-            let line_height = if text_config.line_height > 0 {
-                text_config.line_height as f32
-            } else {
-                preferred_dims.height
-            };
-
-            if !measured.contains_newlines && preferred_dims.width <= container_width {
-                // Single line
-                self.wrapped_text_lines.push(WrappedTextLine {
-                    dimensions: self.layout_elements[elem_index].dimensions,
-                    start: 0,
-                    length: text.len(),
-                });
-                self.text_element_data[text_idx].wrapped_lines_length = 1;
-                continue;
+        crate::telemetry::span!("ply::text_wrap");
+        for text_data in &self.text_element_data {
+            if let Some(item) = self.layout_element_map.get_mut(&text_data.parent_id) {
+                item.text_clamped = false;
             }
+        }
 
-            // Multi-line wrapping
-            let measure_fn = self.measure_text_fn.as_ref().unwrap();
-            let space_width = {
-                let space_config = text_config.clone();
-                measure_fn(" ", &space_config).width
-            };
-
-            let mut word_index = measured.measured_words_start_index;
-            let mut line_width: f32 = 0.0;
-            let mut line_length_chars: i32 = 0;
-            let mut line_start_offset: i32 = 0;
-
-            while word_index != -1 {
-                let measured_word = self.measured_words[word_index as usize];
-
-                // Word doesn't fit but it's the only word on the line
-                if line_length_chars == 0 && line_width + measured_word.width > container_width {
-                    self.wrapped_text_lines.push(WrappedTextLine {
-                        dimensions: Dimensions::new(measured_word.width, line_height),
-                        start: measured_word.start_offset as usize,
-                        length: measured_word.length as usize,
-                    });
-                    self.text_element_data[text_idx].wrapped_lines_length += 1;
-                    word_index = measured_word.next;
-                    line_start_offset = measured_word.start_offset + measured_word.length;
-                }
-                // Newline or overflow
-                else if measured_word.length == 0
-                    || line_width + measured_word.width > container_width
-                {
-                    let text_bytes = text.as_bytes();
-                    let final_char_idx = (line_start_offset + line_length_chars - 1).max(0) as usize;
-                    let final_char_is_space =
-                        final_char_idx < text_bytes.len() && text_bytes[final_char_idx] == b' ';
-                    let adj_width = line_width
-                        + if final_char_is_space {
-                            -space_width
-                        } else {
-                            0.0
-                        };
-                    let adj_length = line_length_chars
-                        + if final_char_is_space { -1 } else { 0 };
-
-                    self.wrapped_text_lines.push(WrappedTextLine {
-                        dimensions: Dimensions::new(adj_width, line_height),
-                        start: line_start_offset as usize,
-                        length: adj_length as usize,
-                    });
-                    self.text_element_data[text_idx].wrapped_lines_length += 1;
-
-                    if line_length_chars == 0 || measured_word.length == 0 {
-                        word_index = measured_word.next;
-                    }
-                    line_width = 0.0;
-                    line_length_chars = 0;
-                    line_start_offset = measured_word.start_offset;
-                } else {
-                    line_width += measured_word.width + text_config.letter_spacing as f32;
-                    line_length_chars += measured_word.length;
-                    word_index = measured_word.next;
+        // Each element wraps on its own, a chunk of elements per thread when allowed
+        let text_count = self.text_element_data.len();
+        let chunk_count = text_count.div_ceil(LAYOUT_CHUNK_LEN);
+        let mut chunks = std::mem::take(&mut self.wrap_scratch);
+        if chunks.len() < chunk_count {
+            chunks.resize_with(chunk_count, WrapScratch::default);
+        }
+        let job = TextWrapJob {
+            text_element_data: &self.text_element_data,
+            text_element_configs: &self.text_element_configs,
+            layout_elements: &self.layout_elements,
+            measured_words: &self.measured_words,
+        };
+        let wrap_chunk = |chunk_idx: usize, scratch: &mut WrapScratch, measure_fn: &dyn Fn(&str, &TextConfig) -> Dimensions| {
+            scratch.lines.clear();
+            scratch.texts.clear();
+            let first = chunk_idx * LAYOUT_CHUNK_LEN;
+            for text_idx in first..(first + LAYOUT_CHUNK_LEN).min(text_count) {
+                let wrapped = wrap_text_element(job, text_idx, measure_fn, &mut scratch.lines);
+                scratch.texts.push(wrapped);
+            }
+        };
+        let no_measure = |_: &str, _: &TextConfig| Dimensions::default();
+        let measure_fn = self.measure_text_fn.as_deref().unwrap_or(&no_measure);
+        #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+        match self.parallel_measure_text_fn.as_deref() {
+            Some(measure_fn) if chunk_count > 1 => {
+                use rayon::prelude::*;
+                chunks[..chunk_count]
+                    .par_iter_mut()
+                    .enumerate()
+                    .for_each(|(chunk_idx, scratch)| wrap_chunk(chunk_idx, scratch, measure_fn));
+            }
+            _ => {
+                for (chunk_idx, scratch) in chunks[..chunk_count].iter_mut().enumerate() {
+                    wrap_chunk(chunk_idx, scratch, measure_fn);
                 }
             }
-
-            if line_length_chars > 0 {
-                self.wrapped_text_lines.push(WrappedTextLine {
-                    dimensions: Dimensions::new(
-                        line_width - text_config.letter_spacing as f32,
-                        line_height,
-                    ),
-                    start: line_start_offset as usize,
-                    length: line_length_chars as usize,
-                });
-                self.text_element_data[text_idx].wrapped_lines_length += 1;
+        }
+        #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+        for (chunk_idx, scratch) in chunks[..chunk_count].iter_mut().enumerate() {
+            wrap_chunk(chunk_idx, scratch, measure_fn);
+        }
+
+        let mut text_idx = 0;
+        for scratch in &chunks[..chunk_count] {
+            let mut lines = scratch.lines.as_slice();
+            for &wrapped in &scratch.texts {
+                let (text_lines, rest) = lines.split_at(wrapped.line_count);
+                lines = rest;
+                let start = self.wrapped_text_lines.len();
+                self.wrapped_text_lines.extend_from_slice(text_lines);
+                self.apply_wrapped_text(text_idx, start, wrapped);
+                text_idx += 1;
             }
+        }
+        self.wrap_scratch = chunks;
+    }
 
-            let num_lines = self.text_element_data[text_idx].wrapped_lines_length;
-            self.layout_elements[elem_index].dimensions.height =
-                line_height * num_lines as f32;
+    /// Points a text element at its wrapped lines and resizes it to them.
+    fn apply_wrapped_text(&mut self, text_idx: usize, start: usize, wrapped: WrappedText) {
+        let text_data = &mut self.text_element_data[text_idx];
+        text_data.wrapped_lines_start = start;
+        text_data.wrapped_lines_length = wrapped.line_count as i32;
+        let (elem_index, parent_id) = (text_data.element_index as usize, text_data.parent_id);
+        if let Some(height) = wrapped.height {
+            self.layout_elements[elem_index].dimensions.height = height;
+        }
+        if wrapped.clamped {
+            if let Some(item) = self.layout_element_map.get_mut(&parent_id) {
+                item.text_clamped = true;
+            }
         }
     }
 
@@ -3583,7 +4690,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         let child_idx =
                             self.layout_element_children[children_start + j] as usize;
                         let child_height_with_padding = f32::max(
-                            self.layout_elements[child_idx].dimensions.height
+                            self.child_size_on_axis(child_idx, false)
                                 + layout_config.padding.top as f32
                                 + layout_config.padding.bottom as f32,
                             self.layout_elements[current_elem_idx].dimensions.height,
@@ -3596,6 +4703,19 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             layout_config.sizing.height.min_max.max,
                         );
                     }
+                    if layout_config.child_alignment.y == AlignY::Baseline {
+                        let (above, below) =
+                            self.baseline_extent(current_elem_idx, 0, children_length as usize);
+                        let content_height = above
+                            + below
+                            + layout_config.padding.top as f32
+                            + layout_config.padding.bottom as f32;
+                        let current_height = self.layout_elements[current_elem_idx].dimensions.height;
+                        self.layout_elements[current_elem_idx].dimensions.height = f32::min(
+                            f32::max(current_height, content_height),
+                            layout_config.sizing.height.min_max.max,
+                        );
+                    }
                 }
             } else if layout_config.wrap {
                 let lines = self.compute_wrapped_lines(current_elem_idx, false);
@@ -3632,7 +4752,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 for j in 0..children_length as usize {
                     let child_idx =
                         self.layout_element_children[children_start + j] as usize;
-                    content_height += self.layout_elements[child_idx].dimensions.height;
+                    content_height += self.child_size_on_axis(child_idx, false);
                 }
                 content_height += children_length.saturating_sub(1) as f32
                     * layout_config.child_gap as f32;
@@ -3654,14 +4774,33 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             || bbox.y + bbox.height < 0.0
     }
 
+    /// Whether `bbox` lies entirely outside the part of `area` that is on
+    /// screen, along an axis `clip` clips.
+    fn clipped_out(&self, bbox: BoundingBox, area: BoundingBox, clip: ClipConfig) -> bool {
+        let left = area.x.max(0.0);
+        let right = (area.x + area.width).min(self.layout_dimensions.width);
+        let top = area.y.max(0.0);
+        let bottom = (area.y + area.height).min(self.layout_dimensions.height);
+        (clip.horizontal && (bbox.x > right || bbox.x + bbox.width < left))
+            || (clip.vertical && (bbox.y > bottom || bbox.y + bbox.height < top))
+    }
+
     fn add_render_command(&mut self, cmd: InternalRenderCommand<CustomElementData>) {
+        if self.skip_render_commands {
+            return;
+        }
         self.render_commands.push(cmd);
     }
 
     fn generate_render_commands(&mut self) {
+        crate::telemetry::span!("ply::render_commands");
         self.render_commands.clear();
+        self.selectable_text_layouts.clear();
+        self.link_regions.clear();
+        self.text_input_handle_centers = None;
         let mut dfs_buffer: Vec<LayoutElementTreeNode> = Vec::new();
         let mut visited: Vec<bool> = Vec::new();
+        let mut duplicate_bounds: Vec<BoundingBox> = Vec::new();
 
         for root_index in 0..self.layout_element_tree_roots.len() {
             dfs_buffer.clear();
@@ -3674,11 +4813,17 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             // Position floating containers
             if self.element_has_config(root_elem_idx, ElementConfigType::Floating) {
                 if let Some(parent_item) = self.layout_element_map.get(&root.parent_id) {
-                    let parent_bbox = parent_item.bounding_box;
                     if let Some(float_cfg_idx) = self
                         .find_element_config_index(root_elem_idx, ElementConfigType::Floating)
                     {
                         let config = &self.floating_element_configs[float_cfg_idx];
+                        let follows_pointer = config.attach_to == FloatingAttachToElement::Pointer;
+                        let parent_bbox = if follows_pointer {
+                            let pointer = self.pointer_info.position;
+                            BoundingBox::new(pointer.x, pointer.y, 0.0, 0.0)
+                        } else {
+                            parent_item.bounding_box
+                        };
                         let root_dims = root_element.dimensions;
                         let mut target = Vector2::default();
 
@@ -3715,6 +4860,13 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             AlignY::Bottom => {
                                 target.y = parent_bbox.y + parent_bbox.height;
                             }
+                            AlignY::Baseline if follows_pointer => {
+                                target.y = parent_bbox.y;
+                            }
+                            AlignY::Baseline => {
+                                target.y = parent_bbox.y
+                                    + self.element_baseline(parent_item.layout_element_index as usize);
+                            }
                         }
                         // Y position - element attach point
                         match config.attach_points.element_y {
@@ -3725,6 +4877,9 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             AlignY::Bottom => {
                                 target.y -= root_dims.height;
                             }
+                            AlignY::Baseline => {
+                                target.y -= self.element_baseline(root_elem_idx);
+                            }
                         }
                         target.x += config.offset.x;
                         target.y += config.offset.y;
@@ -3759,12 +4914,15 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 layout_element_index: root.layout_element_index,
                 position: root_position,
                 next_child_offset: Vector2::new(root_padding_left, root_padding_top),
+                culled: false,
             });
             visited.push(false);
 
             while !dfs_buffer.is_empty() {
                 let buf_idx = dfs_buffer.len() - 1;
                 let current_node = dfs_buffer[buf_idx];
+                // A culled subtree is still positioned, but draws nothing
+                self.skip_render_commands = current_node.culled;
                 let current_elem_idx = current_node.layout_element_index as usize;
                 let layout_idx = self.layout_elements[current_elem_idx].layout_config_index;
                 let layout_config = self.layout_configs[layout_idx];
@@ -3793,6 +4951,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 {
                                     _scroll_container_data_idx = Some(si);
                                     self.scroll_container_datas[si].bounding_box = current_bbox;
+                                    self.scroll_container_datas[si].laid_out_offset = clip_config.child_offset;
                                     scroll_offset = clip_config.child_offset;
                                     break;
                                 }
@@ -3804,6 +4963,11 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     let elem_id = self.layout_elements[current_elem_idx].id;
                     if let Some(item) = self.layout_element_map.get_mut(&elem_id) {
                         item.bounding_box = current_bbox;
+                        item.z_index = root.z_index;
+                        // The debug view lists duplicates under their ids too
+                        if item.collision && self.debug_mode_enabled && current_elem_idx < self.debug_view_start {
+                            duplicate_bounds.push(current_bbox);
+                        }
                     }
 
                     // Generate render commands for this element
@@ -3816,7 +4980,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     let should_render_base = !offscreen;
 
                     // Get per-element shader effects
-                    let elem_effects = self.element_effects.get(current_elem_idx).cloned().unwrap_or_default();
+                    let elem_effects = self.element_effects_at(current_elem_idx);
 
                     // Get per-element visual rotation
                     let elem_visual_rotation = self.element_visual_rotations.get(current_elem_idx).cloned().flatten();
@@ -3848,17 +5012,17 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     // Emit GroupBegin commands for group shaders BEFORE element drawing
                     // so that the element's background, children, and border are all captured.
                     // If visual_rotation is present, it is attached to the outermost group.
-                    let elem_shaders = self.element_shaders.get(current_elem_idx).cloned().unwrap_or_default();
+                    let elem_shaders = self.element_shaders_at(current_elem_idx);
 
                     if !elem_shaders.is_empty() {
                         // Emit GroupBegin for each shader (outermost first = reversed order)
-                        for (i, shader) in elem_shaders.iter().rev().enumerate() {
+                        for (i, shader) in elem_shaders.range().rev().enumerate() {
                             // Attach visual_rotation to the outermost GroupBegin (i == 0)
                             let vr = if i == 0 { elem_visual_rotation } else { None };
                             self.add_render_command(InternalRenderCommand {
                                 bounding_box: current_bbox,
                                 command_type: RenderCommandType::GroupBegin,
-                                effects: vec![shader.clone()],
+                                effects: ShaderSlice { start: shader, length: 1 },
                                 id: elem_id,
                                 z_index: root.z_index,
                                 visual_rotation: vr,
@@ -3870,7 +5034,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         self.add_render_command(InternalRenderCommand {
                             bounding_box: current_bbox,
                             command_type: RenderCommandType::GroupBegin,
-                            effects: vec![],
+                            effects: ShaderSlice::default(),
                             id: elem_id,
                             z_index: root.z_index,
                             visual_rotation: Some(vr),
@@ -3906,7 +5070,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                     z_index: root.z_index,
                                     visual_rotation: None,
                                     shape_rotation: None,
-                                    effects: Vec::new(),
+                                    effects: ShaderSlice::default(),
                                 });
                             }
                             ElementConfigType::Image => {
@@ -3926,7 +5090,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                         z_index: root.z_index,
                                         visual_rotation: None,
                                         shape_rotation: elem_shape_rotation,
-                                        effects: elem_effects.clone(),
+                                        effects: elem_effects,
                                     });
                                 }
                                 emit_rectangle = false;
@@ -3935,15 +5099,17 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 if !should_render {
                                     continue;
                                 }
-                                let text_config =
-                                    self.text_element_configs[config.config_index].clone();
                                 let text_data_idx =
                                     self.layout_elements[current_elem_idx].text_data_index;
                                 if text_data_idx < 0 {
                                     continue;
                                 }
+                                // Taken out while emitting lines, put back at the end of the arm
+                                let text_config =
+                                    std::mem::take(&mut self.text_element_configs[config.config_index]);
                                 let text_data = &self.text_element_data[text_data_idx as usize];
                                 let natural_line_height = text_data.preferred_dimensions.height;
+                                let text_baseline = text_data.baseline;
                                 let final_line_height = if text_config.line_height > 0 {
                                     text_config.line_height as f32
                                 } else {
@@ -3956,23 +5122,30 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 let lines_start = text_data.wrapped_lines_start;
                                 let lines_length = text_data.wrapped_lines_length;
                                 let parent_text = text_data.text.clone();
+                                #[cfg(feature = "text-styling")]
+                                let link_owner_id = text_data.parent_id;
+                                #[cfg(feature = "text-styling")]
+                                let link_spans = if parent_text.contains("{link=") {
+                                    crate::text_styling::link_spans(&parent_text)
+                                } else {
+                                    Vec::new()
+                                };
+                                let selection = self
+                                    .static_text_selection
+                                    .filter(|sel| text_config.selectable && sel.element_id == elem_id)
+                                    .map(|sel| sel.range())
+                                    .filter(|(sel_start, sel_end)| sel_start < sel_end);
+                                let mut selectable_lines = Vec::new();
 
                                 // Collect line data first to avoid borrow issues
                                 let lines_data: Vec<_> = (0..lines_length)
                                     .map(|li| {
                                         let line = &self.wrapped_text_lines[lines_start + li as usize];
-                                        (line.start, line.length, line.dimensions)
+                                        (line.start, line.length, line.dimensions, line.ellipsis)
                                     })
                                     .collect();
 
-                                for (line_index, &(start, length, line_dims)) in lines_data.iter().enumerate() {
-                                    if length == 0 {
-                                        y_position += final_line_height;
-                                        continue;
-                                    }
-
-                                    let line_text = parent_text[start..start + length].to_string();
-
+                                for (line_index, &(start, length, line_dims, ellipsis)) in lines_data.iter().enumerate() {
                                     let align_width = if buf_idx > 0 {
                                         let parent_node = dfs_buffer[buf_idx - 1];
                                         let parent_elem_idx =
@@ -3988,39 +5161,275 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                         current_bbox.width
                                     };
 
+                                    // Byte positions that get extra space, for justified lines that wrapped
+                                    let justify_points: Vec<usize> = if text_config.justify
+                                        && !ellipsis
+                                        && line_index + 1 < lines_data.len()
+                                        && !parent_text[start + length..].trim_start_matches(' ').starts_with('\n')
+                                    {
+                                        justification_points(&parent_text[start..start + length])
+                                            .into_iter()
+                                            .map(|point| start + point)
+                                            .collect()
+                                    } else {
+                                        Vec::new()
+                                    };
+
                                     let mut offset = align_width - line_dims.width;
-                                    if text_config.alignment == AlignX::Left {
+                                    if text_config.alignment == AlignX::Left || !justify_points.is_empty() {
                                         offset = 0.0;
                                     }
                                     if text_config.alignment == AlignX::CenterX {
                                         offset /= 2.0;
                                     }
 
-                                    self.add_render_command(InternalRenderCommand {
-                                        bounding_box: BoundingBox::new(
-                                            current_bbox.x + offset,
-                                            current_bbox.y + y_position,
-                                            line_dims.width,
-                                            line_dims.height,
-                                        ),
-                                        command_type: RenderCommandType::Text,
-                                        render_data: InternalRenderData::Text {
-                                            text: line_text,
-                                            text_color: text_config.color,
-                                            font_size: text_config.font_size,
-                                            letter_spacing: text_config.letter_spacing,
-                                            line_height: text_config.line_height,
-                                            font_asset: text_config.font_asset,
-                                        },
-                                        user_data: text_config.user_data,
-                                        id: hash_number(line_index as u32, elem_id).id,
-                                        z_index: root.z_index,
-                                        visual_rotation: None,
-                                        shape_rotation: None,
-                                        effects: text_config.effects.clone(),
+                                    let line_box = BoundingBox::new(
+                                        current_bbox.x + offset,
+                                        current_bbox.y + y_position - line_height_offset,
+                                        line_dims.width,
+                                        final_line_height,
+                                    );
+                                    if text_config.selectable {
+                                        selectable_lines.push((line_box, start, length));
+                                    }
+
+                                    // Selection highlight behind this line
+                                    let selection_box = selection.and_then(|(sel_start, sel_end)| {
+                                        let from = sel_start.clamp(start, start + length);
+                                        let to = sel_end.clamp(start, start + length);
+                                        if from >= to
+                                            || !parent_text.is_char_boundary(from)
+                                            || !parent_text.is_char_boundary(to)
+                                        {
+                                            return None;
+                                        }
+                                        let measure_fn = self.measure_text_fn.as_ref()?;
+                                        let line_text = &parent_text[start..start + length];
+                                        let positions = crate::text_input::compute_char_x_positions(
+                                            line_text,
+                                            text_config.font_asset,
+                                            text_config.font_size,
+                                            measure_fn.as_ref(),
+                                        );
+                                        let x_of = |byte: usize| {
+                                            let chars = line_text[..byte - start].chars().count();
+                                            positions.get(chars).copied().unwrap_or(0.0)
+                                        };
+                                        let (x_start, x_end) = (x_of(from), x_of(to));
+                                        Some(BoundingBox::new(
+                                            line_box.x + x_start,
+                                            line_box.y,
+                                            x_end - x_start,
+                                            line_box.height,
+                                        ))
                                     });
+                                    #[cfg(feature = "text-styling")]
+                                    if let Some(measure_fn) = self.measure_text_fn.as_ref() {
+                                        let mut regions = Vec::new();
+                                        for span in &link_spans {
+                                            let from = span.start.max(start);
+                                            let to = span.end.min(start + length);
+                                            if from >= to {
+                                                continue;
+                                            }
+                                            // Markup in the prefix is stripped by the measure function
+                                            let x_start = measure_span(&parent_text[start..from], start, &text_config, measure_fn.as_ref()).width;
+                                            let x_end = measure_span(&parent_text[start..to], start, &text_config, measure_fn.as_ref()).width;
+                                            regions.push(LinkRegion {
+                                                bounding_box: BoundingBox::new(
+                                                    line_box.x + x_start,
+                                                    line_box.y,
+                                                    x_end - x_start,
+                                                    line_box.height,
+                                                ),
+                                                owner_id: link_owner_id,
+                                                url: span.url.clone(),
+                                            });
+                                        }
+                                        self.link_regions.extend(regions);
+                                    }
+
+                                    if let Some(selection_box) = selection_box {
+                                        self.add_render_command(InternalRenderCommand {
+                                            bounding_box: selection_box,
+                                            command_type: RenderCommandType::Rectangle,
+                                            render_data: InternalRenderData::Rectangle {
+                                                background_color: text_config.selection_color,
+                                                corner_radius: CornerRadius::default(),
+                                            },
+                                            user_data: 0,
+                                            id: hash_number(STATIC_TEXT_SELECTION_ID_OFFSET + line_index as u32, elem_id).id,
+                                            z_index: root.z_index,
+                                            visual_rotation: None,
+                                            shape_rotation: None,
+                                            effects: ShaderSlice::default(),
+                                        });
+                                    }
+
+                                    if length == 0 && !ellipsis {
+                                        y_position += final_line_height;
+                                        continue;
+                                    }
+
+                                    let line_x = current_bbox.x + offset;
+                                    let line_y = current_bbox.y + y_position;
+                                    let justify_gap = if justify_points.is_empty() {
+                                        0.0
+                                    } else {
+                                        (align_width - line_dims.width).max(0.0) / justify_points.len() as f32
+                                    };
+                                    let justify_shift = |byte: usize| {
+                                        justify_points.partition_point(|&point| point <= byte) as f32 * justify_gap
+                                    };
+                                    // (bounding box, text) runs of this line, split around inline atoms
+                                    // and justification points
+                                    let mut text_runs: Vec<(BoundingBox, String)> = Vec::new();
+                                    if text_config.inline_atoms.is_empty() && justify_points.is_empty() {
+                                        let mut line_text = parent_text[start..start + length].to_string();
+                                        if ellipsis {
+                                            line_text.push_str(ELLIPSIS);
+                                        }
+                                        text_runs.push((
+                                            BoundingBox::new(line_x, line_y, line_dims.width, line_dims.height),
+                                            line_text,
+                                        ));
+                                    } else if let Some(measure_fn) = self.measure_text_fn.as_ref() {
+                                        let line_end = start + length;
+                                        let width_to = |byte: usize| {
+                                            measure_span(&parent_text[start..byte], start, &text_config, measure_fn.as_ref()).width
+                                        };
+                                        let push_runs = |text_runs: &mut Vec<(BoundingBox, String)>, from: usize, to: usize| {
+                                            let mut piece_start = from;
+                                            let inner_points = justify_points.iter().copied().filter(|&point| point > from && point < to);
+                                            for piece_end in inner_points.chain(std::iter::once(to)) {
+                                                let x = width_to(piece_start);
+                                                text_runs.push((
+                                                    BoundingBox::new(
+                                                        line_x + x + justify_shift(piece_start),
+                                                        line_y,
+                                                        width_to(piece_end) - x,
+                                                        line_dims.height,
+                                                    ),
+                                                    parent_text[piece_start..piece_end].to_string(),
+                                                ));
+                                                piece_start = piece_end;
+                                            }
+                                        };
+                                        let mut run_start = start;
+                                        let mut atom_boxes = Vec::new();
+                                        for (i, atom_index) in inline_atoms_in(&parent_text[start..line_end], start, &text_config) {
+                                            let atom_pos = start + i;
+                                            if atom_pos > run_start {
+                                                push_runs(&mut text_runs, run_start, atom_pos);
+                                            }
+                                            let atom = &text_config.inline_atoms[atom_index];
+                                            let text_height = natural_line_height;
+                                            let atom_y = match text_config.inline_align {
+                                                InlineAlign::Top => line_y,
+                                                InlineAlign::Center => line_y + (text_height - atom.height) / 2.0,
+                                                InlineAlign::Bottom => line_y + text_height - atom.height,
+                                                InlineAlign::Baseline => {
+                                                    line_y + text_baseline - atom.height
+                                                }
+                                            };
+                                            atom_boxes.push((
+                                                atom_index,
+                                                BoundingBox::new(
+                                                    line_x + width_to(atom_pos) + justify_shift(atom_pos),
+                                                    atom_y,
+                                                    atom.width,
+                                                    atom.height,
+                                                ),
+                                            ));
+                                            run_start = atom_pos + INLINE_ATOM.len_utf8();
+                                        }
+                                        if !justify_points.is_empty() {
+                                            if run_start < line_end {
+                                                push_runs(&mut text_runs, run_start, line_end);
+                                            }
+                                        } else if run_start < line_end || ellipsis {
+                                            let x = width_to(run_start);
+                                            let mut run_text = parent_text[run_start..line_end].to_string();
+                                            if ellipsis {
+                                                run_text.push_str(ELLIPSIS);
+                                            }
+                                            let width = measure_fn(&run_text, &text_config).width;
+                                            text_runs.push((
+                                                BoundingBox::new(line_x + x, line_y, width, line_dims.height),
+                                                run_text,
+                                            ));
+                                        }
+
+                                        for (atom_index, atom_box) in atom_boxes {
+                                            match &text_config.inline_atoms[atom_index].content {
+                                                InlineAtomContent::Image(image_data) => {
+                                                    self.add_render_command(InternalRenderCommand {
+                                                        bounding_box: atom_box,
+                                                        command_type: RenderCommandType::Image,
+                                                        render_data: InternalRenderData::Image {
+                                                            background_color: Color::default(),
+                                                            corner_radius: CornerRadius::default(),
+                                                            image_data: image_data.clone(),
+                                                        },
+                                                        user_data: text_config.user_data,
+                                                        id: hash_number(INLINE_ATOM_ID_OFFSET + atom_index as u32, elem_id).id,
+                                                        z_index: root.z_index,
+                                                        visual_rotation: None,
+                                                        shape_rotation: None,
+                                                        effects: ShaderSlice::default(),
+                                                    });
+                                                }
+                                                InlineAtomContent::Element(atom_id) => {
+                                                    if let Some(item) = self.layout_element_map.get_mut(&atom_id.id) {
+                                                        item.bounding_box = atom_box;
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+
+                                    for (run_index, (run_box, run_text)) in text_runs.into_iter().enumerate() {
+                                        let id = if run_index == 0 {
+                                            hash_number(line_index as u32, elem_id).id
+                                        } else {
+                                            hash_number(line_index as u32, hash_number(run_index as u32, elem_id).id).id
+                                        };
+                                        self.add_render_command(InternalRenderCommand {
+                                            bounding_box: run_box,
+                                            command_type: RenderCommandType::Text,
+                                            render_data: InternalRenderData::Text {
+                                                text: run_text,
+                                                text_color: text_config.color,
+                                                font_size: text_config.font_size,
+                                                letter_spacing: text_config.letter_spacing,
+                                                line_height: text_config.line_height,
+                                                font_asset: text_config.font_asset,
+                                                shadow: text_config.shadow,
+                                                outline: text_config.outline,
+                                            },
+                                            user_data: text_config.user_data,
+                                            id,
+                                            z_index: root.z_index,
+                                            visual_rotation: None,
+                                            shape_rotation: None,
+                                            effects: self.text_element_effects[config.config_index],
+                                        });
+                                    }
                                     y_position += final_line_height;
                                 }
+
+                                if text_config.selectable {
+                                    self.selectable_text_layouts.insert(
+                                        elem_id,
+                                        SelectableTextLayout {
+                                            text: parent_text,
+                                            font_asset: text_config.font_asset,
+                                            font_size: text_config.font_size,
+                                            lines: selectable_lines,
+                                        },
+                                    );
+                                }
+                                self.text_element_configs[config.config_index] = text_config;
                             }
                             ElementConfigType::Custom => {
                                 if should_render {
@@ -4039,7 +5448,47 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                         z_index: root.z_index,
                                         visual_rotation: None,
                                         shape_rotation: elem_shape_rotation,
-                                        effects: elem_effects.clone(),
+                                        effects: elem_effects,
+                                    });
+                                }
+                                emit_rectangle = false;
+                            }
+                            ElementConfigType::Canvas | ElementConfigType::Draw => {
+                                if should_render {
+                                    // Background first so the drawing lands on top
+                                    if emit_rectangle {
+                                        self.add_render_command(InternalRenderCommand {
+                                            bounding_box: shape_draw_bbox,
+                                            command_type: RenderCommandType::Rectangle,
+                                            render_data: InternalRenderData::Rectangle {
+                                                background_color: shared.background_color,
+                                                corner_radius: shared.corner_radius,
+                                            },
+                                            user_data: shared.user_data,
+                                            id: elem_id,
+                                            z_index: root.z_index,
+                                            visual_rotation: None,
+                                            shape_rotation: elem_shape_rotation,
+                                            effects: elem_effects,
+                                        });
+                                    }
+                                    let (command_type, render_data) = if config.config_type == ElementConfigType::Canvas {
+                                        let commands = self.canvas_element_configs[config.config_index].clone();
+                                        (RenderCommandType::Canvas, InternalRenderData::Canvas { commands })
+                                    } else {
+                                        let hook = self.draw_hook_configs[config.config_index].clone();
+                                        (RenderCommandType::Draw, InternalRenderData::Draw { hook })
+                                    };
+                                    self.add_render_command(InternalRenderCommand {
+                                        bounding_box: shape_draw_bbox,
+                                        command_type,
+                                        render_data,
+                                        user_data: shared.user_data,
+                                        id: elem_id,
+                                        z_index: root.z_index,
+                                        visual_rotation: None,
+                                        shape_rotation: elem_shape_rotation,
+                                        effects: ShaderSlice::default(),
                                     });
                                 }
                                 emit_rectangle = false;
@@ -4063,14 +5512,14 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                             z_index: root.z_index,
                                             visual_rotation: None,
                                             shape_rotation: elem_shape_rotation,
-                                            effects: elem_effects.clone(),
+                                            effects: elem_effects,
                                         });
                                     }
 
                                     // Get or create edit state
                                     let state = self.text_edit_states
                                         .entry(elem_id)
-                                        .or_insert_with(crate::text_input::TextEditState::default)
+                                        .or_default()
                                         .clone();
 
                                     let disp_text = crate::text_input::display_text(
@@ -4089,6 +5538,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                     let mut content_height = 0.0_f32;
                                     let mut scroll_pos_x = state.scroll_offset;
                                     let mut scroll_pos_y = state.scroll_offset_y;
+                                    let mut touch_overlay: Option<TextInputTouchOverlay> = None;
 
                                     // Measure font height for cursor
                                     let natural_font_height = self.font_height(ti_config.font_asset, ti_config.font_size);
@@ -4113,7 +5563,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                         z_index: root.z_index,
                                         visual_rotation: None,
                                         shape_rotation: None,
-                                        effects: Vec::new(),
+                                        effects: ShaderSlice::default(),
                                     });
 
                                     if ti_config.is_multiline {
@@ -4207,7 +5657,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                                             z_index: root.z_index,
                                                             visual_rotation: None,
                                                             shape_rotation: None,
-                                                            effects: Vec::new(),
+                                                            effects: ShaderSlice::default(),
                                                         });
                                                     }
                                                 }
@@ -4235,13 +5685,15 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                                         letter_spacing: 0,
                                                         line_height: 0,
                                                         font_asset: ti_config.font_asset,
+                                                        shadow: None,
+                                                        outline: None,
                                                     },
                                                     user_data: 0,
                                                     id: hash_number(2000 + line_idx as u32, elem_id).id,
                                                     z_index: root.z_index,
                                                     visual_rotation: None,
                                                     shape_rotation: None,
-                                                    effects: Vec::new(),
+                                                    effects: ShaderSlice::default(),
                                                 });
                                             }
                                         }
@@ -4268,7 +5720,35 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                                 z_index: root.z_index,
                                                 visual_rotation: None,
                                                 shape_rotation: None,
-                                                effects: Vec::new(),
+                                                effects: ShaderSlice::default(),
+                                            });
+                                        }
+
+                                        if is_focused && self.text_input_touch_handles && !is_placeholder {
+                                            #[cfg(feature = "text-styling")]
+                                            let (sel_range, cursor_raw) = (state.selection_range_raw(), state.cursor_pos_raw());
+                                            #[cfg(not(feature = "text-styling"))]
+                                            let (sel_range, cursor_raw) = (state.selection_range(), state.cursor_pos);
+                                            let (sel_start, sel_end) = sel_range.unwrap_or((cursor_raw, cursor_raw));
+                                            let caret_top = |raw_pos: usize| {
+                                                let (line, col) = crate::text_input::cursor_to_visual_pos(&visual_lines, raw_pos);
+                                                let line = line.min(line_positions.len() - 1);
+                                                let x = line_positions[line].get(col).copied().unwrap_or(0.0);
+                                                Vector2::new(
+                                                    current_bbox.x - scroll_offset_x + x,
+                                                    current_bbox.y + line as f32 * line_step - scroll_offset_y,
+                                                )
+                                            };
+                                            let cursor_line = cursor_line.min(visual_lines.len().saturating_sub(1));
+                                            touch_overlay = Some(TextInputTouchOverlay {
+                                                carets: [caret_top(sel_start), caret_top(sel_end), caret_top(cursor_raw)],
+                                                caret_height: line_step,
+                                                line: visual_lines.get(cursor_line).map(|vl| vl.text.clone()).unwrap_or_default(),
+                                                line_caret_x: line_positions
+                                                    .get(cursor_line)
+                                                    .and_then(|p| p.get(cursor_col))
+                                                    .copied()
+                                                    .unwrap_or(0.0),
                                             });
                                         }
                                     } else {
@@ -4324,12 +5804,28 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                                         z_index: root.z_index,
                                                         visual_rotation: None,
                                                         shape_rotation: None,
-                                                        effects: Vec::new(),
+                                                        effects: ShaderSlice::default(),
                                                     });
                                                 }
                                             }
                                         }
 
+                                        if is_focused && self.text_input_touch_handles && !is_placeholder {
+                                            let (sel_start, sel_end) = render_selection
+                                                .unwrap_or((render_cursor_pos, render_cursor_pos));
+                                            let caret_y = current_bbox.y + (current_bbox.height - font_height) / 2.0;
+                                            let caret_top = |raw_pos: usize| Vector2::new(
+                                                text_x + char_x_positions.get(raw_pos).copied().unwrap_or(0.0),
+                                                caret_y,
+                                            );
+                                            touch_overlay = Some(TextInputTouchOverlay {
+                                                carets: [caret_top(sel_start), caret_top(sel_end), caret_top(render_cursor_pos)],
+                                                caret_height: font_height,
+                                                line: disp_text.clone(),
+                                                line_caret_x: char_x_positions.get(render_cursor_pos).copied().unwrap_or(0.0),
+                                            });
+                                        }
+
                                         // Text
                                         if !disp_text.is_empty() {
                                             let text_width = char_x_positions.last().copied().unwrap_or(0.0);
@@ -4353,13 +5849,15 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                                     letter_spacing: 0,
                                                     line_height: 0,
                                                     font_asset: ti_config.font_asset,
+                                                    shadow: None,
+                                                    outline: None,
                                                 },
                                                 user_data: 0,
                                                 id: hash_number(1002, elem_id).id,
                                                 z_index: root.z_index,
                                                 visual_rotation: None,
                                                 shape_rotation: None,
-                                                effects: Vec::new(),
+                                                effects: ShaderSlice::default(),
                                             });
                                         }
 
@@ -4387,7 +5885,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                                 z_index: root.z_index,
                                                 visual_rotation: None,
                                                 shape_rotation: None,
-                                                effects: Vec::new(),
+                                                effects: ShaderSlice::default(),
                                             });
                                         }
                                     }
@@ -4441,8 +5939,19 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                         z_index: root.z_index,
                                         visual_rotation: None,
                                         shape_rotation: None,
-                                        effects: Vec::new(),
+                                        effects: ShaderSlice::default(),
                                     });
+
+                                    // Handles and magnifier draw outside the input's clip
+                                    if let Some(overlay) = touch_overlay {
+                                        self.render_text_input_touch_overlay(
+                                            elem_id,
+                                            root.z_index,
+                                            &ti_config,
+                                            shared.background_color,
+                                            overlay,
+                                        );
+                                    }
                                 }
                                 // Background already emitted above; skip the default rectangle
                                 emit_rectangle = false;
@@ -4463,7 +5972,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             z_index: root.z_index,
                             visual_rotation: None,
                             shape_rotation: elem_shape_rotation,
-                            effects: elem_effects.clone(),
+                            effects: elem_effects,
                         });
                     }
 
@@ -4497,7 +6006,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                     extra_space = extra_space.max(0.0);
                                 }
                                 match layout_config.child_alignment.y {
-                                    AlignY::Top => extra_space = 0.0,
+                                    AlignY::Top | AlignY::Baseline => extra_space = 0.0,
                                     AlignY::CenterY => extra_space /= 2.0,
                                     AlignY::Bottom => {}
                                 }
@@ -4507,8 +6016,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 for ci in 0..children_length {
                                     let child_idx =
                                         self.layout_element_children[children_start + ci] as usize;
-                                    content_width +=
-                                        self.layout_elements[child_idx].dimensions.width;
+                                    content_width += self.child_size_on_axis(child_idx, true);
                                 }
                                 content_width += children_length.saturating_sub(1) as f32
                                     * layout_config.child_gap as f32;
@@ -4558,8 +6066,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             for ci in 0..children_length {
                                 let child_idx =
                                     self.layout_element_children[children_start + ci] as usize;
-                                content_height +=
-                                    self.layout_elements[child_idx].dimensions.height;
+                                content_height += self.child_size_on_axis(child_idx, false);
                             }
                             content_height += children_length.saturating_sub(1) as f32
                                 * layout_config.child_gap as f32;
@@ -4572,7 +6079,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 extra_space = extra_space.max(0.0);
                             }
                             match layout_config.child_alignment.y {
-                                AlignY::Top => extra_space = 0.0,
+                                AlignY::Top | AlignY::Baseline => extra_space = 0.0,
                                 AlignY::CenterY => extra_space /= 2.0,
                                 AlignY::Bottom => {}
                             }
@@ -4611,7 +6118,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                             let idx = self.layout_element_children
                                                 [children_start + ci]
                                                 as usize;
-                                            self.layout_elements[idx].dimensions.width
+                                            self.child_size_on_axis(idx, true)
                                         })
                                         .sum::<f32>()
                                         + lr_padding
@@ -4621,7 +6128,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                             let idx = self.layout_element_children
                                                 [children_start + ci]
                                                 as usize;
-                                            self.layout_elements[idx].dimensions.height
+                                            self.child_size_on_axis(idx, false)
                                         })
                                         .fold(0.0_f32, |a, b| a.max(b))
                                         + tb_padding;
@@ -4633,7 +6140,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                             let idx = self.layout_element_children
                                                 [children_start + ci]
                                                 as usize;
-                                            self.layout_elements[idx].dimensions.width
+                                            self.child_size_on_axis(idx, true)
                                         })
                                         .fold(0.0_f32, |a, b| a.max(b))
                                         + lr_padding;
@@ -4642,7 +6149,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                             let idx = self.layout_element_children
                                                 [children_start + ci]
                                                 as usize;
-                                            self.layout_elements[idx].dimensions.height
+                                            self.child_size_on_axis(idx, false)
                                         })
                                         .sum::<f32>()
                                         + tb_padding
@@ -4749,7 +6256,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                                     z_index: root.z_index,
                                                     visual_rotation: None,
                                                     shape_rotation: None,
-                                                    effects: Vec::new(),
+                                                    effects: ShaderSlice::default(),
                                                 });
                                             }
                                             border_offset_x +=
@@ -4789,7 +6296,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                                     z_index: root.z_index,
                                                     visual_rotation: None,
                                                     shape_rotation: None,
-                                                    effects: Vec::new(),
+                                                    effects: ShaderSlice::default(),
                                                 });
                                             }
                                             border_offset_y +=
@@ -4856,7 +6363,14 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         });
                     }
 
-                    if self.element_has_config(current_elem_idx, ElementConfigType::Border) {
+                    // Invalid text inputs get their border in the invalid color
+                    let invalid_border_color = self
+                        .find_element_config_index(current_elem_idx, ElementConfigType::TextInput)
+                        .filter(|_| self.text_input_errors.contains_key(&self.layout_elements[current_elem_idx].id))
+                        .map(|idx| self.text_input_configs[idx].invalid_border_color);
+                    if self.element_has_config(current_elem_idx, ElementConfigType::Border)
+                        || invalid_border_color.is_some()
+                    {
                         let border_elem_id = self.layout_elements[current_elem_idx].id;
                         if let Some(border_bbox) = self.layout_element_map.get(&border_elem_id).map(|item| item.bounding_box) {
                             let bbox = border_bbox;
@@ -4868,13 +6382,25 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                     )
                                     .map(|idx| self.shared_element_configs[idx])
                                     .unwrap_or_default();
-                                let border_cfg_idx = self
+                                let mut border_config = self
                                     .find_element_config_index(
                                         current_elem_idx,
                                         ElementConfigType::Border,
                                     )
-                                    .unwrap();
-                                let border_config = self.border_element_configs[border_cfg_idx];
+                                    .map(|idx| self.border_element_configs[idx])
+                                    .unwrap_or(BorderConfig {
+                                        width: BorderWidth {
+                                            left: 2,
+                                            right: 2,
+                                            top: 2,
+                                            bottom: 2,
+                                            between_children: 0,
+                                        },
+                                        ..Default::default()
+                                    });
+                                if let Some(color) = invalid_border_color {
+                                    border_config.color = color;
+                                }
 
                                 let children_count =
                                     self.layout_elements[current_elem_idx].children_length;
@@ -4896,19 +6422,19 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                     z_index: root.z_index,
                                     visual_rotation: None,
                                     shape_rotation: None,
-                                    effects: Vec::new(),
+                                    effects: ShaderSlice::default(),
                                 });
                             }
                         }
                     }
 
                     // Emit GroupEnd commands AFTER border and scissor (innermost first, outermost last)
-                    let elem_shaders = self.element_shaders.get(current_elem_idx).cloned().unwrap_or_default();
+                    let elem_shaders = self.element_shaders_at(current_elem_idx);
                     let elem_visual_rotation = self.element_visual_rotations.get(current_elem_idx).cloned().flatten()
                         .filter(|vr| !vr.is_noop());
 
                     // GroupEnd for each shader
-                    for _shader in elem_shaders.iter() {
+                    for _shader in elem_shaders.range() {
                         self.add_render_command(InternalRenderCommand {
                             command_type: RenderCommandType::GroupEnd,
                             id: self.layout_elements[current_elem_idx].id,
@@ -5006,7 +6532,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                     line_extra_space = line_extra_space.max(0.0);
                                 }
                                 match layout_config.child_alignment.y {
-                                    AlignY::Top => line_extra_space = 0.0,
+                                    AlignY::Top | AlignY::Baseline => line_extra_space = 0.0,
                                     AlignY::CenterY => line_extra_space /= 2.0,
                                     AlignY::Bottom => {}
                                 }
@@ -5026,6 +6552,21 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         }
                     }
 
+                    // Children entirely outside a clipping element's visible
+                    // area can't show, so their subtrees are skipped outright
+                    let cull_area = self
+                        .find_element_config_index(current_elem_idx, ElementConfigType::Clip)
+                        .map(|idx| self.clip_element_configs[idx])
+                        .filter(|clip| {
+                            !self.culling_disabled && !current_node.culled && (clip.horizontal || clip.vertical)
+                        })
+                        .map(|clip| {
+                            let position = dfs_buffer[buf_idx].position;
+                            let dimensions = self.layout_elements[current_elem_idx].dimensions;
+                            (clip, BoundingBox::new(position.x, position.y, dimensions.width, dimensions.height))
+                        });
+
+                    let mut row_baseline: Option<f32> = None;
                     for ci in 0..children_length {
                         let child_idx =
                             self.layout_element_children[children_start + ci] as usize;
@@ -5033,6 +6574,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             self.layout_elements[child_idx].layout_config_index;
 
                         // Alignment along non-layout axis
+                        let child_margin = self.child_margin(child_idx);
                         let mut child_offset = dfs_buffer[buf_idx].next_child_offset;
                         if layout_config.wrap {
                             let Some(lines) = wrapped_lines.as_ref() else {
@@ -5045,8 +6587,8 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 child_offset.x = wrapped_line_main_cursors[line_idx];
                                 child_offset.y = wrapped_line_cross_starts[line_idx];
 
-                                let whitespace = line.cross_size
-                                    - self.layout_elements[child_idx].dimensions.height;
+                                let whitespace =
+                                    line.cross_size - self.child_size_on_axis(child_idx, false);
                                 match layout_config.child_alignment.y {
                                     AlignY::Top => {}
                                     AlignY::CenterY => {
@@ -5055,17 +6597,27 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                     AlignY::Bottom => {
                                         child_offset.y += whitespace;
                                     }
+                                    AlignY::Baseline => {
+                                        let (line_baseline, _) = self.baseline_extent(
+                                            current_elem_idx,
+                                            line.start_child_offset,
+                                            line.end_child_offset,
+                                        );
+                                        child_offset.y += line_baseline
+                                            - self.element_baseline(child_idx)
+                                            - child_margin.top as f32;
+                                    }
                                 }
 
                                 wrapped_line_main_cursors[line_idx] +=
-                                    self.layout_elements[child_idx].dimensions.width
+                                    self.child_size_on_axis(child_idx, true)
                                         + layout_config.child_gap as f32;
                             } else {
                                 child_offset.x = wrapped_line_cross_starts[line_idx];
                                 child_offset.y = wrapped_line_main_cursors[line_idx];
 
-                                let whitespace = line.cross_size
-                                    - self.layout_elements[child_idx].dimensions.width;
+                                let whitespace =
+                                    line.cross_size - self.child_size_on_axis(child_idx, true);
                                 match layout_config.child_alignment.x {
                                     AlignX::Left => {}
                                     AlignX::CenterX => {
@@ -5077,14 +6629,14 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 }
 
                                 wrapped_line_main_cursors[line_idx] +=
-                                    self.layout_elements[child_idx].dimensions.height
+                                    self.child_size_on_axis(child_idx, false)
                                         + layout_config.child_gap as f32;
                             }
                         } else if layout_config.layout_direction == LayoutDirection::LeftToRight {
                             child_offset.y = layout_config.padding.top as f32;
                             let whitespace = self.layout_elements[current_elem_idx].dimensions.height
                                 - (layout_config.padding.top + layout_config.padding.bottom) as f32
-                                - self.layout_elements[child_idx].dimensions.height;
+                                - self.child_size_on_axis(child_idx, false);
                             match layout_config.child_alignment.y {
                                 AlignY::Top => {}
                                 AlignY::CenterY => {
@@ -5093,12 +6645,19 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 AlignY::Bottom => {
                                     child_offset.y += whitespace;
                                 }
+                                AlignY::Baseline => {
+                                    let row_baseline = *row_baseline.get_or_insert_with(|| {
+                                        self.baseline_extent(current_elem_idx, 0, children_length).0
+                                    });
+                                    child_offset.y +=
+                                        row_baseline - self.element_baseline(child_idx) - child_margin.top as f32;
+                                }
                             }
                         } else {
                             child_offset.x = layout_config.padding.left as f32;
                             let whitespace = self.layout_elements[current_elem_idx].dimensions.width
                                 - (layout_config.padding.left + layout_config.padding.right) as f32
-                                - self.layout_elements[child_idx].dimensions.width;
+                                - self.child_size_on_axis(child_idx, true);
                             match layout_config.child_alignment.x {
                                 AlignX::Left => {}
                                 AlignX::CenterX => {
@@ -5110,6 +6669,10 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             }
                         }
 
+                        // The offsets so far place the margin box
+                        child_offset.x += child_margin.left as f32;
+                        child_offset.y += child_margin.top as f32;
+
                         let child_position = Vector2::new(
                             dfs_buffer[buf_idx].position.x + child_offset.x + scroll_offset.x,
                             dfs_buffer[buf_idx].position.y + child_offset.y + scroll_offset.y,
@@ -5120,10 +6683,22 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             self.layout_configs[child_layout_idx].padding.left as f32;
                         let child_padding_top =
                             self.layout_configs[child_layout_idx].padding.top as f32;
+                        let child_dims = self.layout_elements[child_idx].dimensions;
+                        let culled = cull_area.is_some_and(|(clip, area)| {
+                            self.clipped_out(
+                                BoundingBox::new(child_position.x, child_position.y, child_dims.width, child_dims.height),
+                                area,
+                                clip,
+                            )
+                        });
+                        if culled {
+                            self.frame_stats.culled_subtrees += 1;
+                        }
                         dfs_buffer[new_node_index] = LayoutElementTreeNode {
                             layout_element_index: child_idx as i32,
                             position: child_position,
                             next_child_offset: Vector2::new(child_padding_left, child_padding_top),
+                            culled: culled || current_node.culled,
                         };
                         visited[new_node_index] = false;
 
@@ -5131,11 +6706,11 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         if !layout_config.wrap {
                             if layout_config.layout_direction == LayoutDirection::LeftToRight {
                                 dfs_buffer[buf_idx].next_child_offset.x +=
-                                    self.layout_elements[child_idx].dimensions.width
+                                    self.child_size_on_axis(child_idx, true)
                                         + layout_config.child_gap as f32;
                             } else {
                                 dfs_buffer[buf_idx].next_child_offset.y +=
-                                    self.layout_elements[child_idx].dimensions.height
+                                    self.child_size_on_axis(child_idx, false)
                                         + layout_config.child_gap as f32;
                             }
                         }
@@ -5143,6 +6718,8 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 }
             }
 
+            self.skip_render_commands = false;
+
             // End clip
             if root.clip_element_id != 0 {
                 let root_elem = &self.layout_elements[root_elem_idx];
@@ -5154,11 +6731,29 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             }
         }
 
-        // Focus ring: render a border around the focused element (keyboard focus only)
-        if self.focused_element_id != 0 && self.focus_from_keyboard {
+        // High contrast: swap every color for the palette's
+        if let Some(palette) = self.high_contrast {
+            for cmd in &mut self.render_commands {
+                match &mut cmd.render_data {
+                    InternalRenderData::Rectangle { background_color, .. } if background_color.a > 0.0 => {
+                        *background_color = palette.background;
+                    }
+                    InternalRenderData::Text { text_color, shadow, .. } => {
+                        *text_color = palette.text;
+                        *shadow = None;
+                    }
+                    InternalRenderData::Border { color, .. } => *color = palette.border,
+                    _ => {}
+                }
+            }
+        }
+
+        // Focus ring: render a border around the focused element (keyboard
+        // focus only, unless high contrast forces it on)
+        if self.focused_element_id != 0 && (self.focus_from_keyboard || self.high_contrast.is_some()) {
             // Check if the element's accessibility config allows the ring
             let a11y = self.accessibility_configs.get(&self.focused_element_id);
-            let show_ring = a11y.map_or(true, |c| c.show_ring);
+            let show_ring = self.high_contrast.is_some() || a11y.is_none_or(|c| c.show_ring);
             if show_ring {
                 if let Some(item) = self.layout_element_map.get(&self.focused_element_id) {
                     let bbox = item.bounding_box;
@@ -5169,7 +6764,10 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             .map(|idx| self.shared_element_configs[idx].corner_radius)
                             .unwrap_or_default();
                         let ring_width = a11y.and_then(|c| c.ring_width).unwrap_or(2);
-                        let ring_color = a11y.and_then(|c| c.ring_color).unwrap_or(Color::rgba(255.0, 60.0, 40.0, 255.0));
+                        let ring_color = match self.high_contrast {
+                            Some(palette) => palette.focus_ring,
+                            None => a11y.and_then(|c| c.ring_color).unwrap_or(Color::rgba(255.0, 60.0, 40.0, 255.0)),
+                        };
                         // Expand bounding box outward by ring width so the ring doesn't overlap content
                         let expanded_bbox = BoundingBox::new(
                             bbox.x - ring_width as f32,
@@ -5198,31 +6796,70 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 position: BorderPosition::Middle,
                             },
                             id: hash_number(self.focused_element_id, 0xF0C5).id,
-                            z_index: 32764, // just below debug panel
+                            z_index: Layer::Debug.z_index(),
                             ..Default::default()
                         });
                     }
                 }
             }
         }
+
+        // Debug mode: outline every element sharing its id with another
+        for (i, bounds) in duplicate_bounds.into_iter().enumerate() {
+            self.add_render_command(InternalRenderCommand {
+                bounding_box: bounds,
+                command_type: RenderCommandType::Border,
+                render_data: InternalRenderData::Border {
+                    color: Self::DEBUG_DUPLICATE_COLOR,
+                    corner_radius: CornerRadius::default(),
+                    width: BorderWidth {
+                        left: 2,
+                        right: 2,
+                        top: 2,
+                        bottom: 2,
+                        between_children: 0,
+                    },
+                    position: BorderPosition::Inside,
+                },
+                id: hash_number(i as u32, 0xD0B1).id,
+                z_index: Layer::Debug.z_index(),
+                ..Default::default()
+            });
+        }
     }
 
     pub fn set_layout_dimensions(&mut self, dimensions: Dimensions) {
         self.layout_dimensions = dimensions;
     }
 
+    /// Resizes the root container of the layout being declared, so it lays
+    /// out at `dimensions` instead of the size it was begun with.
+    pub fn set_root_dimensions(&mut self, dimensions: Dimensions) {
+        self.layout_dimensions = dimensions;
+        if let Some(root) = self.layout_elements.first() {
+            let sizing = &mut self.layout_configs[root.layout_config_index].sizing;
+            sizing.width.min_max = SizingMinMax { min: dimensions.width, max: dimensions.width };
+            sizing.height.min_max = SizingMinMax { min: dimensions.height, max: dimensions.height };
+        }
+    }
+
     pub fn set_pointer_state(&mut self, position: Vector2, is_down: bool) {
-        if self.boolean_warnings.max_elements_exceeded {
+        if self.max_elements_exceeded {
             return;
         }
         self.pointer_info.position = position;
         self.pointer_over_ids.clear();
 
+        // Pointer in each visited element's untransformed space, for clips
+        let mut local_pointers: FxHashMap<u32, Vector2> = FxHashMap::default();
+
         // Check which elements are under the pointer
         for root_index in (0..self.layout_element_tree_roots.len()).rev() {
             let root = self.layout_element_tree_roots[root_index];
             let mut dfs: Vec<i32> = vec![root.layout_element_index];
             let mut vis: Vec<bool> = vec![false];
+            // The pointer as seen by each element on the stack, undoing visual transforms
+            let mut pointers: Vec<Vector2> = vec![position];
             let mut found = false;
 
             while !dfs.is_empty() {
@@ -5230,6 +6867,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 if vis[idx] {
                     dfs.pop();
                     vis.pop();
+                    pointers.pop();
                     continue;
                 }
                 vis[idx] = true;
@@ -5245,6 +6883,18 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     elem_box.x -= root.pointer_offset.x;
                     elem_box.y -= root.pointer_offset.y;
 
+                    let mut pointer = pointers[idx];
+                    let visual_rotation = self.element_visual_rotations.get(current_idx).copied().flatten();
+                    if let Some(rotation) = visual_rotation.filter(|rotation| !rotation.is_noop()) {
+                        pointer = match rotation.unproject(&elem_box, (pointer.x, pointer.y)) {
+                            Some((x, y)) => Vector2::new(x, y),
+                            // Squashed flat: nothing inside can be hit
+                            None => Vector2::new(f32::NAN, f32::NAN),
+                        };
+                    }
+                    pointers[idx] = pointer;
+                    local_pointers.insert(elem_id, pointer);
+
                     let clip_id =
                         self.layout_element_clip_element_ids[current_idx] as u32;
                     let clip_ok = clip_id == 0
@@ -5253,13 +6903,13 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             .get(&clip_id)
                             .map(|ci| {
                                 point_is_inside_rect(
-                                    position,
+                                    local_pointers.get(&clip_id).copied().unwrap_or(position),
                                     ci.bounding_box,
                                 )
                             })
                             .unwrap_or(false);
 
-                    if point_is_inside_rect(position, elem_box) && clip_ok {
+                    if point_is_inside_rect(pointer, elem_box) && clip_ok {
                         // Call hover callbacks
                         if has_hover {
                             let pointer_data = self.pointer_info;
@@ -5276,6 +6926,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     if self.element_has_config(current_idx, ElementConfigType::Text) {
                         dfs.pop();
                         vis.pop();
+                        pointers.pop();
                         continue;
                     }
                     let children_start = self.layout_elements[current_idx].children_start;
@@ -5285,10 +6936,12 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         let child = self.layout_element_children[children_start + ci];
                         dfs.push(child);
                         vis.push(false);
+                        pointers.push(pointer);
                     }
                 } else {
                     dfs.pop();
                     vis.pop();
+                    pointers.pop();
                 }
             }
 
@@ -5308,6 +6961,12 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             }
         }
 
+        // Links only count as hovered while their owner is under the pointer (respects clipping)
+        self.hovered_link = self.link_regions.iter().position(|region| {
+            point_is_inside_rect(position, region.bounding_box)
+                && self.pointer_over_ids.iter().any(|eid| eid.id == region.owner_id)
+        });
+
         // Update pointer state
         if is_down {
             match self.pointer_info.state {
@@ -5331,16 +6990,47 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             }
         }
 
+        // While picking, the debug view takes clicks on the UI for itself
+        if self.debug_mode_enabled && self.debug_picking {
+            self.debug_hovered_element_id = self.debug_pick_target();
+            if self.debug_hovered_element_id != 0 {
+                if self.pointer_info.state == PointerDataInteractionState::PressedThisFrame {
+                    self.debug_selected_element_id = self.debug_hovered_element_id;
+                    self.debug_picking = false;
+                }
+                return;
+            }
+        }
+
         // Fire on_press / on_release callbacks and track pressed element
         match self.pointer_info.state {
             PointerDataInteractionState::PressedThisFrame => {
+                // Start a static text selection, or drop the old one when clicking elsewhere
+                self.static_text_selection = self
+                    .pointer_over_ids
+                    .last()
+                    .and_then(|top| {
+                        let offset = self.static_text_offset_at(top.id, self.pointer_info.position)?;
+                        Some(StaticTextSelection {
+                            element_id: top.id,
+                            anchor: offset,
+                            cursor: offset,
+                            dragging: true,
+                        })
+                    });
+
                 // Check if clicked element is a text input
                 let clicked_text_input = self.pointer_over_ids.last()
                     .and_then(|top| self.layout_element_map.get(&top.id))
                     .map(|item| item.is_text_input)
                     .unwrap_or(false);
 
-                if clicked_text_input {
+                // A touch on a selection handle drags it instead of moving focus or the cursor
+                let grabbed_handle = self.grab_text_input_handle(self.pointer_info.position);
+
+                if grabbed_handle {
+                    self.pressed_element_ids = self.pointer_over_ids.clone();
+                } else if clicked_text_input {
                     // Focus the text input (or keep focus if already focused)
                     self.focus_from_keyboard = false;
                     if let Some(top) = self.pointer_over_ids.last().cloned() {
@@ -5384,8 +7074,41 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
                 let pressed_now = self.pressed_element_ids.clone();
                 self.track_just_pressed_ids(&pressed_now);
+
+                self.pressed_link = self
+                    .hovered_link
+                    .map(|i| (self.link_regions[i].owner_id, self.link_regions[i].url.clone()));
+            }
+            PointerDataInteractionState::Pressed => {
+                // Extend an active static text selection while dragging
+                if let Some(sel) = self.static_text_selection.filter(|sel| sel.dragging) {
+                    if let Some(offset) = self.static_text_offset_at(sel.element_id, position) {
+                        self.static_text_selection = Some(StaticTextSelection {
+                            cursor: offset,
+                            ..sel
+                        });
+                    }
+                }
             }
             PointerDataInteractionState::ReleasedThisFrame => {
+                if let Some(sel) = self.static_text_selection.as_mut() {
+                    sel.dragging = false;
+                }
+
+                // A link is clicked when pressed and released over the same link
+                if let Some((owner_id, url)) = self.pressed_link.take() {
+                    let released_on_same = self.hovered_link.is_some_and(|i| {
+                        self.link_regions[i].owner_id == owner_id && self.link_regions[i].url == url
+                    });
+                    if released_on_same {
+                        if let Some(item) = self.layout_element_map.get_mut(&owner_id) {
+                            if let Some(ref mut callback) = item.on_link_clicked_fn {
+                                callback(&url);
+                            }
+                        }
+                    }
+                }
+
                 // Fire on_release for all elements that were in the pressed chain
                 let pressed = std::mem::take(&mut self.pressed_element_ids);
                 self.track_just_released_ids(&pressed);
@@ -5401,6 +7124,41 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         }
     }
 
+    /// Maps a point to a byte offset in a selectable text element, using the
+    /// line layout from the last frame.
+    fn static_text_offset_at(&self, element_id: u32, point: Vector2) -> Option<usize> {
+        let layout = self.selectable_text_layouts.get(&element_id)?;
+        let measure_fn = self.measure_text_fn.as_ref()?;
+        let last = layout.lines.last()?;
+        let &(line_bbox, start, length) = layout
+            .lines
+            .iter()
+            .find(|(bbox, _, _)| point.y < bbox.y + bbox.height)
+            .unwrap_or(last);
+        let line_text = &layout.text[start..start + length];
+        let positions = crate::text_input::compute_char_x_positions(
+            line_text,
+            layout.font_asset,
+            layout.font_size,
+            measure_fn.as_ref(),
+        );
+        let char_idx =
+            crate::text_input::find_nearest_char_boundary(point.x - line_bbox.x, &positions);
+        Some(start + crate::text_input::char_index_to_byte(line_text, char_idx))
+    }
+
+    /// Returns the text currently selected in a `.selectable()` text element.
+    pub fn selected_static_text(&self) -> Option<String> {
+        let sel = self.static_text_selection?;
+        let layout = self.selectable_text_layouts.get(&sel.element_id)?;
+        let (start, end) = sel.range();
+        layout
+            .text
+            .get(start..end)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    }
+
     /// Physics constants for scroll momentum
     const SCROLL_DECEL: f32 = 5.0; // Exponential decay rate (reaches ~0.7% after 1s)
     const SCROLL_MIN_VELOCITY: f32 = 5.0; // px/s below which momentum stops
@@ -5413,6 +7171,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         delta_time: f32,
         touch_input_active: bool,
     ) {
+        self.wheel_delta = scroll_delta;
         let pointer = self.pointer_info.position;
         let dt = delta_time.max(0.0001); // Guard against zero/negative dt
 
@@ -5437,6 +7196,10 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
             match pointer_state {
                 PointerDataInteractionState::PressedThisFrame => {
+                    // Selecting static text takes over mouse drags
+                    let selecting_text = !touch_input_active
+                        && self.static_text_selection.is_some_and(|sel| sel.dragging);
+
                     // Find the deepest scroll container under the pointer and start drag
                     let mut best: Option<usize> = None;
                     for si in 0..self.scroll_container_datas.len() {
@@ -5498,7 +7261,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             scd.scrollbar_drag_scroll_origin =
                                 Vector2::new(-scd.scroll_position.x, -scd.scroll_position.y);
                             scd.scrollbar_activity_this_frame = true;
-                        } else if !(scd.no_drag_scroll && !touch_input_active) {
+                        } else if (!scd.no_drag_scroll || touch_input_active) && !selecting_text {
                             scd.pointer_scroll_active = true;
                             scd.pointer_origin = pointer;
                             scd.scroll_origin = scd.scroll_position;
@@ -5751,6 +7514,11 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
     }
 
     pub fn on_hover(&mut self, callback: Box<dyn FnMut(Id, PointerData)>) {
+        self.set_hover_callback(Box::new(callback));
+    }
+
+    /// Like `on_hover`, with a box from `pointer_callbacks`.
+    pub(crate) fn set_hover_callback(&mut self, callback: Box<dyn PointerCallback>) {
         let open_idx = self.get_open_layout_element();
         let elem_id = self.layout_elements[open_idx].id;
         if let Some(item) = self.layout_element_map.get_mut(&elem_id) {
@@ -5797,6 +7565,18 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         &mut self,
         on_press: Option<Box<dyn FnMut(Id, PointerData)>>,
         on_release: Option<Box<dyn FnMut(Id, PointerData)>>,
+    ) {
+        self.set_pooled_press_callbacks(
+            on_press.map(|f| Box::new(f) as Box<dyn PointerCallback>),
+            on_release.map(|f| Box::new(f) as Box<dyn PointerCallback>),
+        );
+    }
+
+    /// Like `set_press_callbacks`, with boxes from `pointer_callbacks`.
+    pub(crate) fn set_pooled_press_callbacks(
+        &mut self,
+        on_press: Option<Box<dyn PointerCallback>>,
+        on_release: Option<Box<dyn PointerCallback>>,
     ) {
         let open_idx = self.get_open_layout_element();
         let elem_id = self.layout_elements[open_idx].id;
@@ -5844,6 +7624,9 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         if new_id == 0 {
             self.focus_from_keyboard = false;
         }
+        if let Some((group_id, _)) = self.focus_group_of(new_id) {
+            self.focus_group_stops.insert(group_id, new_id);
+        }
 
         // Fire on_unfocus on old element
         if old_id != 0 {
@@ -5858,8 +7641,8 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         // Fire on_focus on new element
         if new_id != 0 {
             // flush pressed chars
-            while let Some(_) = macroquad::prelude::get_char_pressed() {
-                // do nothing
+            if !self.headless {
+                while macroquad::prelude::get_char_pressed().is_some() {}
             }
             if let Some(item) = self.layout_element_map.get_mut(&new_id) {
                 let id_copy = item.element_id.clone();
@@ -5886,6 +7669,18 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         &mut self,
         on_focus: Option<Box<dyn FnMut(Id)>>,
         on_unfocus: Option<Box<dyn FnMut(Id)>>,
+    ) {
+        self.set_pooled_focus_callbacks(
+            on_focus.map(|f| Box::new(f) as Box<dyn FocusCallback>),
+            on_unfocus.map(|f| Box::new(f) as Box<dyn FocusCallback>),
+        );
+    }
+
+    /// Like `set_focus_callbacks`, with boxes from `focus_callbacks`.
+    pub(crate) fn set_pooled_focus_callbacks(
+        &mut self,
+        on_focus: Option<Box<dyn FocusCallback>>,
+        on_unfocus: Option<Box<dyn FocusCallback>>,
     ) {
         let open_idx = self.get_open_layout_element();
         let elem_id = self.layout_elements[open_idx].id;
@@ -5895,11 +7690,113 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         }
     }
 
+    /// Sets the link click callback for the currently open element.
+    pub fn set_link_clicked_callback(&mut self, on_link_clicked: Box<dyn FnMut(&str)>) {
+        let open_idx = self.get_open_layout_element();
+        let elem_id = self.layout_elements[open_idx].id;
+        if let Some(item) = self.layout_element_map.get_mut(&elem_id) {
+            item.on_link_clicked_fn = Some(on_link_clicked);
+        }
+    }
+
+    /// Sets the image paste callback for the currently open element.
+    #[cfg(feature = "clipboard")]
+    pub fn set_paste_image_callback(
+        &mut self,
+        on_paste_image: Box<dyn FnMut(&crate::clipboard::ClipboardImage)>,
+    ) {
+        let open_idx = self.get_open_layout_element();
+        let elem_id = self.layout_elements[open_idx].id;
+        if let Some(item) = self.layout_element_map.get_mut(&elem_id) {
+            item.on_paste_image_fn = Some(on_paste_image);
+        }
+    }
+
+    /// Returns the element that should receive a pasted image: the focused
+    /// element if it accepts images, otherwise the innermost hovered one that does.
+    #[cfg(feature = "clipboard")]
+    pub fn paste_image_target(&self) -> Option<u32> {
+        let accepts = |id: u32| {
+            self.layout_element_map
+                .get(&id)
+                .is_some_and(|item| item.on_paste_image_fn.is_some())
+        };
+        if self.focused_element_id != 0 && accepts(self.focused_element_id) {
+            return Some(self.focused_element_id);
+        }
+        self.pointer_over_ids
+            .iter()
+            .rev()
+            .map(|eid| eid.id)
+            .find(|&id| accepts(id))
+    }
+
+    /// Fires the image paste callback of `element_id`.
+    #[cfg(feature = "clipboard")]
+    pub fn paste_image(&mut self, element_id: u32, image: &crate::clipboard::ClipboardImage) {
+        if let Some(item) = self.layout_element_map.get_mut(&element_id) {
+            if let Some(ref mut callback) = item.on_paste_image_fn {
+                callback(image);
+            }
+        }
+    }
+
+    /// Sets the file drop callback for the currently open element.
+    pub fn set_file_drop_callback(&mut self, on_file_drop: FileDropCallback) {
+        let open_idx = self.get_open_layout_element();
+        let elem_id = self.layout_elements[open_idx].id;
+        if let Some(item) = self.layout_element_map.get_mut(&elem_id) {
+            item.on_file_drop_fn = Some(on_file_drop);
+        }
+    }
+
+    /// Returns the innermost element under the pointer that accepts dropped files.
+    pub fn file_drop_target(&self) -> Option<u32> {
+        self.pointer_over_ids.iter().rev().map(|eid| eid.id).find(|id| {
+            self.layout_element_map
+                .get(id)
+                .is_some_and(|item| item.on_file_drop_fn.is_some())
+        })
+    }
+
+    /// Returns if files are being dragged over the currently open element
+    /// and would be dropped onto it.
+    pub fn file_drag_hovered(&self) -> bool {
+        if self.file_drag_position.is_none() {
+            return false;
+        }
+        let open_idx = self.get_open_layout_element();
+        let elem_id = self.layout_elements[open_idx].id;
+        self.file_drop_target() == Some(elem_id)
+    }
+
+    /// Delivers dropped files to the element under the pointer that accepts them.
+    /// Returns false when no element took them.
+    pub fn drop_files(&mut self, files: &[DroppedFile]) -> bool {
+        self.file_drag_position = None;
+        let Some(target) = self.file_drop_target() else {
+            return false;
+        };
+        if let Some(item) = self.layout_element_map.get_mut(&target) {
+            if let Some(ref mut callback) = item.on_file_drop_fn {
+                callback(files);
+            }
+        }
+        true
+    }
+
+    /// Returns the URL of the link under the pointer, if any.
+    pub fn hovered_link(&self) -> Option<&str> {
+        self.hovered_link
+            .and_then(|i| self.link_regions.get(i))
+            .map(|region| region.url.as_str())
+    }
+
     /// Sets text input callbacks for the currently open element.
     pub fn set_text_input_callbacks(
         &mut self,
-        on_changed: Option<Box<dyn FnMut(&str)>>,
-        on_submit: Option<Box<dyn FnMut(&str)>>,
+        on_changed: Option<StrCallback>,
+        on_submit: Option<StrCallback>,
     ) {
         let open_idx = self.get_open_layout_element();
         let elem_id = self.layout_elements[open_idx].id;
@@ -5909,6 +7806,29 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         }
     }
 
+    /// Runs a text input validator for the currently open element against its
+    /// current text and records the result.
+    pub fn validate_text_input(&mut self, validate: &dyn Fn(&str) -> Result<(), String>) {
+        let open_idx = self.get_open_layout_element();
+        let elem_id = self.layout_elements[open_idx].id;
+        self.validated_text_inputs.insert(elem_id);
+        let text = self.text_edit_states.get(&elem_id).map_or("", |state| state.text.as_str());
+        match validate(text) {
+            Ok(()) => {
+                self.text_input_errors.remove(&elem_id);
+            }
+            Err(error) => {
+                self.text_input_errors.insert(elem_id, error);
+            }
+        }
+    }
+
+    /// Returns the validation error of a text input, or `None` if it is valid
+    /// or has no validator.
+    pub fn text_input_error(&self, element_id: u32) -> Option<&str> {
+        self.text_input_errors.get(&element_id).map(String::as_str)
+    }
+
     /// Returns true if the currently focused element is a text input.
     pub fn is_text_input_focused(&self) -> bool {
         if self.focused_element_id == 0 {
@@ -5928,6 +7848,34 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             .map_or(false, |cfg| cfg.is_multiline)
     }
 
+    /// Returns true if Escape should clear the focused text input rather than unfocus it.
+    pub fn should_focused_text_input_clear_on_escape(&self) -> bool {
+        if self.focused_element_id == 0 {
+            return false;
+        }
+        let clear_on_escape = self.text_input_element_ids.iter()
+            .position(|&id| id == self.focused_element_id)
+            .and_then(|idx| self.text_input_configs.get(idx))
+            .is_some_and(|cfg| cfg.clear_on_escape);
+        clear_on_escape && !self.get_text_value(self.focused_element_id).is_empty()
+    }
+
+    /// Returns true if the currently focused text input is numeric.
+    pub fn is_focused_text_input_numeric(&self) -> bool {
+        if self.focused_element_id == 0 {
+            return false;
+        }
+        self.text_input_element_ids.iter()
+            .position(|&id| id == self.focused_element_id)
+            .and_then(|idx| self.text_input_configs.get(idx))
+            .is_some_and(|cfg| cfg.numeric.is_some())
+    }
+
+    /// Returns true if the given text input got Backspace this frame while empty.
+    pub fn is_backspace_on_empty(&self, element_id: u32) -> bool {
+        self.empty_backspace == (element_id, self.generation)
+    }
+
     /// Returns the text value for a text input element, or empty string if not found.
     pub fn get_text_value(&self, element_id: u32) -> &str {
         self.text_edit_states
@@ -5940,7 +7888,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
     pub fn set_text_value(&mut self, element_id: u32, value: &str) {
         let state = self.text_edit_states
             .entry(element_id)
-            .or_insert_with(crate::text_input::TextEditState::default);
+            .or_default();
         state.text = value.to_string();
         #[cfg(feature = "text-styling")]
         let max_pos = crate::text_input::styling::cursor_len(&state.text);
@@ -6000,6 +7948,45 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         }
     }
 
+    /// Inserts `text` at the cursor of a text input element, replacing any selection.
+    /// Respects `max_length`, is undoable, and fires `on_changed`.
+    /// When text-styling is enabled, `text` is inserted as plain text.
+    pub fn insert_text_at_cursor(&mut self, element_id: u32, text: &str) {
+        let (max_length, numeric) = self.text_input_element_ids.iter()
+            .position(|&id| id == element_id)
+            .and_then(|idx| self.text_input_configs.get(idx))
+            .map_or((None, None), |cfg| (cfg.max_length, cfg.numeric));
+        let state = self.text_edit_states
+            .entry(element_id)
+            .or_default();
+        let old_text = state.text.clone();
+        let snapshot = numeric.map(|_| state.clone());
+        state.push_undo(crate::text_input::UndoActionKind::Paste);
+        #[cfg(feature = "text-styling")]
+        {
+            let escaped = crate::text_input::styling::escape_str(text);
+            state.insert_text_styled(&escaped, max_length);
+        }
+        #[cfg(not(feature = "text-styling"))]
+        {
+            state.insert_text(text, max_length);
+        }
+        if let (Some(numeric), Some(snapshot)) = (numeric, snapshot) {
+            if !numeric.accepts(&state.text) {
+                *state = snapshot;
+                return;
+            }
+        }
+        if state.text != old_text {
+            let new_text = state.text.clone();
+            if let Some(item) = self.layout_element_map.get_mut(&element_id) {
+                if let Some(ref mut callback) = item.on_text_changed_fn {
+                    callback(&new_text);
+                }
+            }
+        }
+    }
+
     /// Returns true if the given element ID is currently pressed.
     pub fn is_element_pressed(&self, element_id: u32) -> bool {
         self.pressed_element_ids.iter().any(|eid| eid.id == element_id)
@@ -6039,13 +8026,15 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         let elem_id = self.focused_element_id;
 
         // Get max_length from current config (if available this frame)
-        let max_length = self.text_input_element_ids.iter()
+        let (max_length, numeric) = self.text_input_element_ids.iter()
             .position(|&id| id == elem_id)
             .and_then(|idx| self.text_input_configs.get(idx))
-            .and_then(|cfg| cfg.max_length);
+            .map_or((None, None), |cfg| (cfg.max_length, cfg.numeric));
 
         if let Some(state) = self.text_edit_states.get_mut(&elem_id) {
             let old_text = state.text.clone();
+            // Numeric inputs roll back insertions that don't leave a number
+            let snapshot = numeric.map(|_| state.clone());
             state.push_undo(crate::text_input::UndoActionKind::InsertChar);
             #[cfg(feature = "text-styling")]
             {
@@ -6055,6 +8044,12 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             {
                 state.insert_text(&ch.to_string(), max_length);
             }
+            if let (Some(numeric), Some(snapshot)) = (numeric, snapshot) {
+                if !numeric.accepts(&state.text) {
+                    *state = snapshot;
+                    return true;
+                }
+            }
             if state.text != old_text {
                 let new_text = state.text.clone();
                 // Fire on_changed callback
@@ -6086,6 +8081,13 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             .and_then(|idx| self.text_input_configs.get(idx))
             .map(|cfg| (cfg.max_length, cfg.is_multiline, cfg.font_asset, cfg.font_size))
             .unwrap_or((None, false, None, 16));
+        let numeric = config_idx
+            .and_then(|idx| self.text_input_configs.get(idx))
+            .and_then(|cfg| cfg.numeric);
+        #[cfg(feature = "text-styling")]
+        let rich_text = config_idx
+            .and_then(|idx| self.text_input_configs.get(idx))
+            .map_or(false, |cfg| cfg.rich_text);
 
         // For multiline visual navigation, compute visual lines
         let visual_lines_opt = if is_multiline {
@@ -6108,8 +8110,16 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             } else { None }
         } else { None };
 
+        // Backspace with nothing to delete is left for widgets to act on, such as chips inputs
+        if matches!(action, TextInputAction::Backspace | TextInputAction::BackspaceWord)
+            && self.text_edit_states.get(&elem_id).is_some_and(|state| state.text.is_empty())
+        {
+            self.empty_backspace = (elem_id, self.release_query_generation());
+        }
+
         if let Some(state) = self.text_edit_states.get_mut(&elem_id) {
             let old_text = state.text.clone();
+            let snapshot = numeric.map(|_| state.clone());
 
             // Push undo before text-modifying actions
             match &action {
@@ -6118,8 +8128,16 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 TextInputAction::BackspaceWord => state.push_undo(crate::text_input::UndoActionKind::DeleteWord),
                 TextInputAction::DeleteWord => state.push_undo(crate::text_input::UndoActionKind::DeleteWord),
                 TextInputAction::Cut => state.push_undo(crate::text_input::UndoActionKind::Cut),
+                TextInputAction::Clear => state.push_undo(crate::text_input::UndoActionKind::Other),
                 TextInputAction::Paste { .. } => state.push_undo(crate::text_input::UndoActionKind::Paste),
                 TextInputAction::Submit if is_multiline => state.push_undo(crate::text_input::UndoActionKind::InsertChar),
+                TextInputAction::Increment | TextInputAction::Decrement if numeric.is_some() => {
+                    state.push_undo(crate::text_input::UndoActionKind::Other)
+                }
+                #[cfg(feature = "text-styling")]
+                TextInputAction::ToggleStyle { .. } if rich_text && state.selection_range().is_some() => {
+                    state.push_undo(crate::text_input::UndoActionKind::Other)
+                }
                 _ => {}
             }
 
@@ -6291,9 +8309,21 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         #[cfg(not(feature = "text-styling"))]
                         { state.insert_text("\n", max_length); }
                     } else {
+                        // Clamp numeric input before it's submitted
+                        if let Some(clamped) = numeric.and_then(|n| n.submitted(&state.text)) {
+                            if clamped != state.text {
+                                state.push_undo(crate::text_input::UndoActionKind::Other);
+                                state.replace_all(&clamped);
+                            }
+                        }
                         let text = state.text.clone();
-                        // Fire on_submit callback
                         if let Some(item) = self.layout_element_map.get_mut(&elem_id) {
+                            if text != old_text {
+                                if let Some(ref mut callback) = item.on_text_changed_fn {
+                                    callback(&text);
+                                }
+                            }
+                            // Fire on_submit callback
                             if let Some(ref mut callback) = item.on_text_submit_fn {
                                 callback(&text);
                             }
@@ -6307,6 +8337,30 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 TextInputAction::Redo => {
                     state.redo();
                 }
+                TextInputAction::Clear => {
+                    state.replace_all("");
+                }
+                action @ (TextInputAction::Increment | TextInputAction::Decrement) => {
+                    let Some(numeric) = numeric else {
+                        return false;
+                    };
+                    let steps = if matches!(action, TextInputAction::Increment) { 1.0 } else { -1.0 };
+                    let stepped = numeric.stepped(&state.text, steps);
+                    state.replace_all(&stepped);
+                }
+                #[cfg(feature = "text-styling")]
+                TextInputAction::ToggleStyle { tag } => {
+                    if !rich_text || !state.toggle_style_styled(&tag) {
+                        return false;
+                    }
+                }
+            }
+            // Edits may not turn a valid number into something else
+            if let (Some(numeric), Some(snapshot)) = (numeric, snapshot) {
+                if !numeric.accepts(&state.text) && numeric.accepts(&old_text) {
+                    *state = snapshot;
+                    return true;
+                }
             }
             if state.text != old_text {
                 let new_text = state.text.clone();
@@ -6478,6 +8532,8 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
         // --- Drag scrolling (focused text input only) ---
         if focused == 0 {
+            self.text_input_handle_drag = None;
+            self.text_input_touch_handles = false;
             if self.text_input_drag_active {
                 let pointer_state = self.pointer_info.state;
                 if matches!(pointer_state, PointerDataInteractionState::ReleasedThisFrame | PointerDataInteractionState::Released) {
@@ -6524,7 +8580,13 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
         match pointer_state {
             PointerDataInteractionState::PressedThisFrame => {
+                if self.text_input_handle_drag.is_some() {
+                    self.text_input_drag_active = false;
+                    self.text_input_scrollbar_drag_active = false;
+                    return true;
+                }
                 if pointer_over_focused {
+                    self.text_input_touch_handles = touch_input_active;
                     let mut started_scrollbar_drag = false;
 
                     if let Some(scrollbar_cfg) = ti_cfg.scrollbar {
@@ -6604,7 +8666,13 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 }
             }
             PointerDataInteractionState::Pressed => {
-                if self.text_input_scrollbar_drag_active {
+                if self.text_input_handle_drag.is_some() {
+                    let extend = self.text_edit_states
+                        .get(&focused)
+                        .is_some_and(|state| state.selection_anchor.is_some());
+                    self.text_input_select_to_pointer(focused, &ti_cfg, pointer, extend);
+                    consumed_scroll = true;
+                } else if self.text_input_scrollbar_drag_active {
                     if let Some(item) = self.layout_element_map.get(&self.text_input_drag_element_id)
                     {
                         if let Some(state_snapshot) = self
@@ -6663,148 +8731,192 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     let drag_id = self.text_input_drag_element_id;
                     if ti_cfg.drag_select && !self.text_input_drag_from_touch {
                         consumed_scroll = true;
+                        self.text_input_select_to_pointer(drag_id, &ti_cfg, pointer, true);
+                    } else if let Some(state) = self.text_edit_states.get_mut(&drag_id) {
+                        if is_multiline {
+                            let drag_delta_y = self.text_input_drag_origin.y - pointer.y;
+                            state.scroll_offset_y = (self.text_input_drag_scroll_origin.y + drag_delta_y).max(0.0);
+                        } else {
+                            let drag_delta_x = self.text_input_drag_origin.x - pointer.x;
+                            state.scroll_offset = (self.text_input_drag_scroll_origin.x + drag_delta_x).max(0.0);
+                        }
+                        self.text_input_scrollbar_idle_frames
+                            .insert(drag_id, 0);
+                    }
+                }
+            }
+            PointerDataInteractionState::ReleasedThisFrame
+            | PointerDataInteractionState::Released => {
+                self.text_input_drag_active = false;
+                self.text_input_handle_drag = None;
+                self.text_input_drag_from_touch = false;
+                self.text_input_scrollbar_drag_active = false;
+            }
+        }
+        consumed_scroll
+    }
 
-                        if let (Some(item), Some(measure_fn)) = (
-                            self.layout_element_map.get(&drag_id),
-                            self.measure_text_fn.as_ref(),
-                        ) {
-                            let bbox = item.bounding_box;
-                            let click_x = pointer.x - bbox.x;
-                            let click_y = pointer.y - bbox.y;
-
-                            if let Some(state) = self.text_edit_states.get_mut(&drag_id) {
-                                if ti_cfg.is_multiline {
-                                    if click_y < 0.0 {
-                                        state.scroll_offset_y = (state.scroll_offset_y + click_y).max(0.0);
-                                    } else if click_y > bbox.height {
-                                        state.scroll_offset_y += click_y - bbox.height;
-                                    }
-                                } else {
-                                    if click_x < 0.0 {
-                                        state.scroll_offset = (state.scroll_offset + click_x).max(0.0);
-                                    } else if click_x > bbox.width {
-                                        state.scroll_offset += click_x - bbox.width;
-                                    }
-                                }
-                            }
+    /// Starts dragging the touch selection handle under `position`, if any.
+    /// The opposite end of the selection becomes the anchor.
+    fn grab_text_input_handle(&mut self, position: Vector2) -> bool {
+        let Some((elem_id, centers)) = self.text_input_handle_centers else {
+            return false;
+        };
+        if !self.text_input_touch_handles || self.focused_element_id != elem_id {
+            return false;
+        }
+        let Some(handle) = centers.iter().position(|center| {
+            let dx = center.x - position.x;
+            let dy = center.y - position.y;
+            dx * dx + dy * dy <= TEXT_INPUT_HANDLE_HIT_RADIUS * TEXT_INPUT_HANDLE_HIT_RADIUS
+        }) else {
+            return false;
+        };
+        if let Some(state) = self.text_edit_states.get_mut(&elem_id) {
+            if let Some((start, end)) = state.selection_range() {
+                let (anchor, cursor) = if handle == 0 { (end, start) } else { (start, end) };
+                state.selection_anchor = Some(anchor);
+                state.cursor_pos = cursor;
+            }
+        }
+        self.text_input_handle_drag = Some(handle);
+        true
+    }
 
-                            if let Some(state_snapshot) = self.text_edit_states.get(&drag_id).cloned() {
-                                let clamped_x = click_x.clamp(0.0, bbox.width.max(0.0));
-                                let clamped_y = click_y.clamp(0.0, bbox.height.max(0.0));
-                                let disp_text = crate::text_input::display_text(
-                                    &state_snapshot.text,
-                                    &ti_cfg.placeholder,
-                                    ti_cfg.is_password,
-                                );
+    /// Moves the cursor of a text input to the pointer, scrolling when the pointer
+    /// is past an edge. When `extend` is true the selection grows to the new position.
+    fn text_input_select_to_pointer(
+        &mut self,
+        drag_id: u32,
+        ti_cfg: &crate::text_input::TextInputConfig,
+        pointer: Vector2,
+        extend: bool,
+    ) {
+        if let (Some(item), Some(measure_fn)) = (
+            self.layout_element_map.get(&drag_id),
+            self.measure_text_fn.as_ref(),
+        ) {
+            let bbox = item.bounding_box;
+            let click_x = pointer.x - bbox.x;
+            let click_y = pointer.y - bbox.y;
+
+            // Past an edge, scroll at a speed proportional to the overshoot.
+            // This runs every frame the pointer is held, even when it doesn't move.
+            let autoscroll = self.frame_delta_time * TEXT_INPUT_AUTOSCROLL_SPEED;
+            if let Some(state) = self.text_edit_states.get_mut(&drag_id) {
+                if ti_cfg.is_multiline {
+                    if click_y < 0.0 {
+                        state.scroll_offset_y = (state.scroll_offset_y + click_y * autoscroll).max(0.0);
+                    } else if click_y > bbox.height {
+                        state.scroll_offset_y += (click_y - bbox.height) * autoscroll;
+                    }
+                } else {
+                    if click_x < 0.0 {
+                        state.scroll_offset = (state.scroll_offset + click_x * autoscroll).max(0.0);
+                    } else if click_x > bbox.width {
+                        state.scroll_offset += (click_x - bbox.width) * autoscroll;
+                    }
+                }
+            }
 
-                                if !state_snapshot.text.is_empty() {
-                                    if ti_cfg.is_multiline {
-                                        let visual_lines = crate::text_input::wrap_lines(
-                                            &disp_text,
-                                            bbox.width,
-                                            ti_cfg.font_asset,
-                                            ti_cfg.font_size,
-                                            measure_fn.as_ref(),
-                                        );
-                                        if !visual_lines.is_empty() {
-                                            let line_height = if ti_cfg.line_height > 0 {
-                                                ti_cfg.line_height as f32
-                                            } else {
-                                                let config = crate::text::TextConfig {
-                                                    font_asset: ti_cfg.font_asset,
-                                                    font_size: ti_cfg.font_size,
-                                                    ..Default::default()
-                                                };
-                                                measure_fn("Mg", &config).height
-                                            };
+            if let Some(state_snapshot) = self.text_edit_states.get(&drag_id).cloned() {
+                let clamped_x = click_x.clamp(0.0, bbox.width.max(0.0));
+                let clamped_y = click_y.clamp(0.0, bbox.height.max(0.0));
+                let disp_text = crate::text_input::display_text(
+                    &state_snapshot.text,
+                    &ti_cfg.placeholder,
+                    ti_cfg.is_password,
+                );
 
-                                            let adjusted_y = clamped_y + state_snapshot.scroll_offset_y;
-                                            let clicked_line = (adjusted_y / line_height).floor().max(0.0) as usize;
-                                            let clicked_line = clicked_line.min(visual_lines.len().saturating_sub(1));
+                if !state_snapshot.text.is_empty() {
+                    if ti_cfg.is_multiline {
+                        let visual_lines = crate::text_input::wrap_lines(
+                            &disp_text,
+                            bbox.width,
+                            ti_cfg.font_asset,
+                            ti_cfg.font_size,
+                            measure_fn.as_ref(),
+                        );
+                        if !visual_lines.is_empty() {
+                            let line_height = if ti_cfg.line_height > 0 {
+                                ti_cfg.line_height as f32
+                            } else {
+                                let config = crate::text::TextConfig {
+                                    font_asset: ti_cfg.font_asset,
+                                    font_size: ti_cfg.font_size,
+                                    ..Default::default()
+                                };
+                                measure_fn("Mg", &config).height
+                            };
 
-                                            let vl = &visual_lines[clicked_line];
-                                            let line_char_x_positions = crate::text_input::compute_char_x_positions(
-                                                &vl.text,
-                                                ti_cfg.font_asset,
-                                                ti_cfg.font_size,
-                                                measure_fn.as_ref(),
-                                            );
-                                            let col = crate::text_input::find_nearest_char_boundary(
-                                                clamped_x,
-                                                &line_char_x_positions,
-                                            );
-                                            let raw_pos = vl.global_char_start + col;
+                            let adjusted_y = clamped_y + state_snapshot.scroll_offset_y;
+                            let clicked_line = (adjusted_y / line_height).floor().max(0.0) as usize;
+                            let clicked_line = clicked_line.min(visual_lines.len().saturating_sub(1));
 
-                                            if let Some(state) = self.text_edit_states.get_mut(&drag_id) {
-                                                #[cfg(feature = "text-styling")]
-                                                {
-                                                    let visual_pos = crate::text_input::styling::raw_to_cursor(&state.text, raw_pos);
-                                                    state.click_to_cursor_styled(visual_pos, true);
-                                                }
-                                                #[cfg(not(feature = "text-styling"))]
-                                                {
-                                                    if state.selection_anchor.is_none() {
-                                                        state.selection_anchor = Some(state.cursor_pos);
-                                                    }
-                                                    state.cursor_pos = raw_pos;
-                                                    if state.selection_anchor == Some(state.cursor_pos) {
-                                                        state.selection_anchor = None;
-                                                    }
-                                                    state.reset_blink();
-                                                }
-                                            }
-                                        }
-                                    } else {
-                                        let char_x_positions = crate::text_input::compute_char_x_positions(
-                                            &disp_text,
-                                            ti_cfg.font_asset,
-                                            ti_cfg.font_size,
-                                            measure_fn.as_ref(),
-                                        );
-                                        let adjusted_x = clamped_x + state_snapshot.scroll_offset;
+                            let vl = &visual_lines[clicked_line];
+                            let line_char_x_positions = crate::text_input::compute_char_x_positions(
+                                &vl.text,
+                                ti_cfg.font_asset,
+                                ti_cfg.font_size,
+                                measure_fn.as_ref(),
+                            );
+                            let col = crate::text_input::find_nearest_char_boundary(
+                                clamped_x,
+                                &line_char_x_positions,
+                            );
+                            let raw_pos = vl.global_char_start + col;
 
-                                        if let Some(state) = self.text_edit_states.get_mut(&drag_id) {
-                                            #[cfg(feature = "text-styling")]
-                                            {
-                                                let raw_pos = crate::text_input::find_nearest_char_boundary(
-                                                    adjusted_x,
-                                                    &char_x_positions,
-                                                );
-                                                let visual_pos = crate::text_input::styling::raw_to_cursor(&state.text, raw_pos);
-                                                state.click_to_cursor_styled(visual_pos, true);
-                                            }
-                                            #[cfg(not(feature = "text-styling"))]
-                                            {
-                                                state.click_to_cursor(adjusted_x, &char_x_positions, true);
-                                            }
-                                        }
+                            if let Some(state) = self.text_edit_states.get_mut(&drag_id) {
+                                #[cfg(feature = "text-styling")]
+                                {
+                                    let visual_pos = crate::text_input::styling::raw_to_cursor(&state.text, raw_pos);
+                                    state.click_to_cursor_styled(visual_pos, extend);
+                                }
+                                #[cfg(not(feature = "text-styling"))]
+                                {
+                                    if !extend {
+                                        state.selection_anchor = None;
+                                    } else if state.selection_anchor.is_none() {
+                                        state.selection_anchor = Some(state.cursor_pos);
+                                    }
+                                    state.cursor_pos = raw_pos;
+                                    if state.selection_anchor == Some(state.cursor_pos) {
+                                        state.selection_anchor = None;
                                     }
+                                    state.reset_blink();
                                 }
                             }
-
-                            self.text_input_scrollbar_idle_frames.insert(drag_id, 0);
                         }
-                    } else if let Some(state) = self.text_edit_states.get_mut(&drag_id) {
-                        if is_multiline {
-                            let drag_delta_y = self.text_input_drag_origin.y - pointer.y;
-                            state.scroll_offset_y = (self.text_input_drag_scroll_origin.y + drag_delta_y).max(0.0);
-                        } else {
-                            let drag_delta_x = self.text_input_drag_origin.x - pointer.x;
-                            state.scroll_offset = (self.text_input_drag_scroll_origin.x + drag_delta_x).max(0.0);
+                    } else {
+                        let char_x_positions = crate::text_input::compute_char_x_positions(
+                            &disp_text,
+                            ti_cfg.font_asset,
+                            ti_cfg.font_size,
+                            measure_fn.as_ref(),
+                        );
+                        let adjusted_x = clamped_x + state_snapshot.scroll_offset;
+
+                        if let Some(state) = self.text_edit_states.get_mut(&drag_id) {
+                            #[cfg(feature = "text-styling")]
+                            {
+                                let raw_pos = crate::text_input::find_nearest_char_boundary(
+                                    adjusted_x,
+                                    &char_x_positions,
+                                );
+                                let visual_pos = crate::text_input::styling::raw_to_cursor(&state.text, raw_pos);
+                                state.click_to_cursor_styled(visual_pos, extend);
+                            }
+                            #[cfg(not(feature = "text-styling"))]
+                            {
+                                state.click_to_cursor(adjusted_x, &char_x_positions, extend);
+                            }
                         }
-                        self.text_input_scrollbar_idle_frames
-                            .insert(drag_id, 0);
                     }
                 }
             }
-            PointerDataInteractionState::ReleasedThisFrame
-            | PointerDataInteractionState::Released => {
-                self.text_input_drag_active = false;
-                self.text_input_drag_from_touch = false;
-                self.text_input_scrollbar_drag_active = false;
-            }
+
+            self.text_input_scrollbar_idle_frames.insert(drag_id, 0);
         }
-        consumed_scroll
     }
 
     /// Clamp text input scroll offsets to valid ranges.
@@ -6890,8 +9002,37 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         }
         self.focus_from_keyboard = true;
 
+        // Roving focus groups are a single stop: the focused member, else the
+        // one focused last, else the first
+        let stops: FxHashMap<u32, u32> = self
+            .focus_groups
+            .iter()
+            .filter(|(_, group)| group.config.roving && !group.members.is_empty())
+            .map(|(&group_id, group)| {
+                let remembered = self.focus_group_stops.get(&group_id).copied().unwrap_or(0);
+                let stop = [self.focused_element_id, remembered]
+                    .into_iter()
+                    .find(|id| group.members.contains(id))
+                    .unwrap_or(group.members[0]);
+                (group_id, stop)
+            })
+            .collect();
+
         // Sort: explicit tab_index first (ascending), then insertion order
-        let mut sorted: Vec<FocusableEntry> = self.focusable_elements.clone();
+        let scope = self.focus_scope_elements();
+        let mut sorted: Vec<FocusableEntry> = self
+            .focusable_elements
+            .iter()
+            .filter(|entry| match entry.group.and_then(|group_id| stops.get(&group_id)) {
+                Some(&stop) => stop == entry.element_id,
+                None => true,
+            })
+            .filter(|entry| scope.as_ref().is_none_or(|scope| scope.contains(&entry.element_id)))
+            .copied()
+            .collect();
+        if sorted.is_empty() {
+            return;
+        }
         sorted.sort_by(|a, b| {
             match (a.tab_index, b.tab_index) {
                 (Some(ai), Some(bi)) => ai.cmp(&bi).then(a.insertion_order.cmp(&b.insertion_order)),
@@ -6929,6 +9070,9 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             return;
         }
         self.focus_from_keyboard = true;
+        self.arrow_key = (self.focused_element_id, Some(direction), self.release_query_generation());
+        let scope = self.focus_scope_elements();
+        let in_scope = |id: &u32| scope.as_ref().is_none_or(|scope| scope.contains(id));
         if let Some(config) = self.accessibility_configs.get(&self.focused_element_id) {
             let target = match direction {
                 ArrowDirection::Left => config.focus_left,
@@ -6936,13 +9080,77 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 ArrowDirection::Up => config.focus_up,
                 ArrowDirection::Down => config.focus_down,
             };
-            if let Some(target_id) = target {
+            if let Some(target_id) = target.filter(in_scope) {
+                self.change_focus(target_id);
+                return;
+            }
+        }
+
+        // Otherwise move within the focus group
+        let focused = self.focused_element_id;
+        let target = self.focus_group_of(focused).and_then(|(_, group)| {
+            let index = group.members.iter().position(|&member| member == focused)?;
+            let target = group.config.step(index, group.members.len(), direction)?;
+            Some(group.members[target])
+        });
+        if let Some(target_id) = target.filter(in_scope) {
+            self.change_focus(target_id);
+            return;
+        }
+
+        // Otherwise go to the nearest focusable element in that direction
+        if self.spatial_navigation {
+            if let Some(target_id) = self.nearest_focusable(focused, direction, scope.as_ref()) {
                 self.change_focus(target_id);
             }
         }
     }
 
-    /// Handle keyboard activation (Enter/Space) on the focused element.
+    /// Finds the focusable element closest to `from` in `direction`, preferring
+    /// ones in line with it over ones that are merely nearer.
+    fn nearest_focusable(&self, from: u32, direction: ArrowDirection, scope: Option<&FxHashSet<u32>>) -> Option<u32> {
+        let center = |id: u32| {
+            let bounds = self.layout_element_map.get(&id)?.bounding_box;
+            (bounds.width > 0.0 && bounds.height > 0.0)
+                .then(|| (bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0))
+        };
+        let (from_x, from_y) = center(from)?;
+        self.focusable_elements
+            .iter()
+            .filter(|entry| entry.element_id != from)
+            .filter(|entry| scope.is_none_or(|scope| scope.contains(&entry.element_id)))
+            .filter_map(|entry| {
+                let (x, y) = center(entry.element_id)?;
+                let (along, across) = match direction {
+                    ArrowDirection::Left => (from_x - x, y - from_y),
+                    ArrowDirection::Right => (x - from_x, y - from_y),
+                    ArrowDirection::Up => (from_y - y, x - from_x),
+                    ArrowDirection::Down => (y - from_y, x - from_x),
+                };
+                (along > 0.0).then_some((entry.element_id, along + across.abs() * 2.0))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(element_id, _)| element_id)
+    }
+
+    /// Returns the arrow key pressed this frame while the given element had focus.
+    /// Lets widgets like knobs step their value with the arrow keys.
+    pub fn arrow_key_on(&self, element_id: u32) -> Option<ArrowDirection> {
+        let (focused, direction, generation) = self.arrow_key;
+        direction.filter(|_| focused == element_id && generation == self.generation)
+    }
+
+    /// Keys that activate the element while it's focused: its
+    /// `activation_keys`, or Enter and Space.
+    pub fn activation_keys(&self, element_id: u32) -> &[macroquad::input::KeyCode] {
+        const DEFAULT: [macroquad::input::KeyCode; 2] = [macroquad::input::KeyCode::Enter, macroquad::input::KeyCode::Space];
+        self.accessibility_configs
+            .get(&element_id)
+            .and_then(|config| config.activation_keys.as_deref())
+            .unwrap_or(&DEFAULT)
+    }
+
+    /// Handle keyboard activation (see `activation_keys`) on the focused element.
     pub fn handle_keyboard_activation(&mut self, pressed: bool, released: bool) {
         if self.focused_element_id == 0 {
             return;
@@ -6981,16 +9189,53 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         self.pointer_over_ids.iter().any(|eid| eid.id == element_id.id)
     }
 
+    pub fn pointer_position(&self) -> Vector2 {
+        self.pointer_info.position
+    }
+
+    /// Returns the scroll wheel movement this frame, for widgets that zoom or step on scroll.
+    pub fn wheel_delta(&self) -> Vector2 {
+        self.wheel_delta
+    }
+
+    /// Returns true on the frame the pointer went down, wherever it is.
+    pub fn pointer_just_pressed(&self) -> bool {
+        self.pointer_info.state == PointerDataInteractionState::PressedThisFrame
+    }
+
     pub fn get_pointer_over_ids(&self) -> &[Id] {
         &self.pointer_over_ids
     }
 
+    /// While declaring a frame, the bounds are last frame's.
     pub fn get_element_data(&self, id: Id) -> Option<BoundingBox> {
+        if !self.elements_positioned {
+            return self.previous_element_bounds.get(&id.id).copied();
+        }
         self.layout_element_map
             .get(&id.id)
             .map(|item| item.bounding_box)
     }
 
+    pub fn get_element_z_index(&self, id: Id) -> Option<i16> {
+        self.layout_element_map
+            .get(&id.id)
+            .map(|item| item.z_index)
+    }
+
+    pub fn set_z_index(&mut self, id: Id, z_index: Option<i16>) {
+        match z_index {
+            Some(z_index) => self.z_index_overrides.insert(id.id, z_index),
+            None => self.z_index_overrides.remove(&id.id),
+        };
+    }
+
+    pub fn is_text_clamped(&self, id: Id) -> bool {
+        self.layout_element_map
+            .get(&id.id)
+            .is_some_and(|item| item.text_clamped)
+    }
+
     pub fn get_scroll_container_data(&self, id: Id) -> ScrollContainerData {
         for scd in &self.scroll_container_datas {
             if scd.element_id == id.id {
@@ -7021,6 +9266,44 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         Vector2::default()
     }
 
+    /// How far the open element has scrolled out of its scroll container,
+    /// per axis from `0.0` (its start at the viewport's start or below) to
+    /// `1.0` (scrolled past entirely). For a scroll container itself, how far
+    /// it is scrolled through its content.
+    pub fn scroll_progress(&self) -> Vector2 {
+        let open_idx = self.get_open_layout_element();
+        let elem_id = self.layout_elements[open_idx].id;
+        let fraction = |scrolled: f32, length: f32| {
+            if length > 0.0 {
+                (scrolled / length).clamp(0.0, 1.0)
+            } else if scrolled > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        };
+
+        if let Some(scd) = self.scroll_container_datas.iter().find(|scd| scd.element_id == elem_id) {
+            return Vector2::new(
+                fraction(-scd.scroll_position.x, scd.content_size.width - scd.bounding_box.width),
+                fraction(-scd.scroll_position.y, scd.content_size.height - scd.bounding_box.height),
+            );
+        }
+
+        // Bounds are last frame's, so move them by the scrolling since
+        let Some(bounds) = self.get_element_data(Id { id: elem_id, ..Default::default() }) else {
+            return Vector2::default();
+        };
+        let container = self.open_layout_element_stack.iter().rev().find_map(|&index| {
+            let id = self.layout_elements[index as usize].id;
+            self.scroll_container_datas.iter().find(|scd| scd.element_id == id)
+        });
+        let Some(scd) = container else { return Vector2::default() };
+        let x = bounds.x - scd.laid_out_offset.x + scd.scroll_position.x - scd.bounding_box.x;
+        let y = bounds.y - scd.laid_out_offset.y + scd.scroll_position.y - scd.bounding_box.y;
+        Vector2::new(fraction(-x, bounds.width), fraction(-y, bounds.height))
+    }
+
     pub fn set_scroll_position(&mut self, id: Id, position: Vector2) {
         for scd in &mut self.scroll_container_datas {
             if scd.element_id == id.id {
@@ -7068,6 +9351,140 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         }
     }
 
+    /// Draws touch selection handles under the carets of a focused text input and,
+    /// while a handle is dragged, the magnifier above the cursor.
+    fn render_text_input_touch_overlay(
+        &mut self,
+        elem_id: u32,
+        z_index: i16,
+        config: &crate::text_input::TextInputConfig,
+        background_color: Color,
+        overlay: TextInputTouchOverlay,
+    ) {
+        let [start, end, cursor] = overlay.carets;
+        let centers = [start, end].map(|caret| {
+            Vector2::new(caret.x, caret.y + overlay.caret_height + TEXT_INPUT_HANDLE_RADIUS)
+        });
+        // A collapsed selection only gets one handle
+        let handle_count = if start == end { 1 } else { 2 };
+        for (i, center) in centers.iter().take(handle_count).enumerate() {
+            self.add_render_command(InternalRenderCommand {
+                bounding_box: BoundingBox::new(
+                    center.x - TEXT_INPUT_HANDLE_RADIUS,
+                    center.y - TEXT_INPUT_HANDLE_RADIUS,
+                    TEXT_INPUT_HANDLE_RADIUS * 2.0,
+                    TEXT_INPUT_HANDLE_RADIUS * 2.0,
+                ),
+                command_type: RenderCommandType::Rectangle,
+                render_data: InternalRenderData::Rectangle {
+                    background_color: config.cursor_color,
+                    corner_radius: TEXT_INPUT_HANDLE_RADIUS.into(),
+                },
+                id: hash_number(1005 + i as u32, elem_id).id,
+                z_index,
+                ..Default::default()
+            });
+        }
+        self.text_input_handle_centers = Some((elem_id, centers));
+
+        if !config.magnifier || self.text_input_handle_drag.is_none() {
+            return;
+        }
+        let scale = TEXT_INPUT_MAGNIFIER_SCALE;
+        let padding = 4.0;
+        let loupe = BoundingBox::new(
+            cursor.x - TEXT_INPUT_MAGNIFIER_WIDTH / 2.0,
+            cursor.y - overlay.caret_height * scale - padding * 2.0 - TEXT_INPUT_HANDLE_RADIUS,
+            TEXT_INPUT_MAGNIFIER_WIDTH,
+            overlay.caret_height * scale + padding * 2.0,
+        );
+        let loupe_color = if background_color.a > 0.0 {
+            Color::rgba(background_color.r, background_color.g, background_color.b, 255.0)
+        } else {
+            Color::rgba(40.0, 40.0, 40.0, 255.0)
+        };
+        self.add_render_command(InternalRenderCommand {
+            bounding_box: loupe,
+            command_type: RenderCommandType::Rectangle,
+            render_data: InternalRenderData::Rectangle {
+                background_color: loupe_color,
+                corner_radius: (padding * 2.0).into(),
+            },
+            id: hash_number(1010, elem_id).id,
+            z_index,
+            ..Default::default()
+        });
+        self.add_render_command(InternalRenderCommand {
+            bounding_box: loupe,
+            command_type: RenderCommandType::ScissorStart,
+            render_data: InternalRenderData::Clip {
+                horizontal: true,
+                vertical: true,
+            },
+            id: hash_number(1011, elem_id).id,
+            z_index,
+            ..Default::default()
+        });
+        let center_x = loupe.x + loupe.width / 2.0;
+        if !overlay.line.is_empty() {
+            let font_height = self.font_height(config.font_asset, config.font_size);
+            let line_width = self.measure_text_fn.as_ref().map_or(0.0, |measure_fn| {
+                let text_config = TextConfig {
+                    font_asset: config.font_asset,
+                    font_size: config.font_size,
+                    ..Default::default()
+                };
+                measure_fn(&overlay.line, &text_config).width
+            });
+            self.add_render_command(InternalRenderCommand {
+                bounding_box: BoundingBox::new(
+                    center_x - overlay.line_caret_x * scale,
+                    loupe.y + padding + (overlay.caret_height - font_height) * scale / 2.0,
+                    line_width * scale,
+                    font_height * scale,
+                ),
+                command_type: RenderCommandType::Text,
+                render_data: InternalRenderData::Text {
+                    text: overlay.line,
+                    text_color: config.text_color,
+                    font_size: (config.font_size as f32 * scale).round() as u16,
+                    letter_spacing: 0,
+                    line_height: 0,
+                    font_asset: config.font_asset,
+                    shadow: None,
+                    outline: None,
+                },
+                id: hash_number(1012, elem_id).id,
+                z_index,
+                ..Default::default()
+            });
+        }
+        self.add_render_command(InternalRenderCommand {
+            bounding_box: BoundingBox::new(
+                center_x - 1.0,
+                loupe.y + padding,
+                2.0,
+                overlay.caret_height * scale,
+            ),
+            command_type: RenderCommandType::Rectangle,
+            render_data: InternalRenderData::Rectangle {
+                background_color: config.cursor_color,
+                corner_radius: CornerRadius::default(),
+            },
+            id: hash_number(1013, elem_id).id,
+            z_index,
+            ..Default::default()
+        });
+        self.add_render_command(InternalRenderCommand {
+            bounding_box: loupe,
+            command_type: RenderCommandType::ScissorEnd,
+            render_data: InternalRenderData::None,
+            id: hash_number(1014, elem_id).id,
+            z_index,
+            ..Default::default()
+        });
+    }
+
     fn render_scrollbar_geometry(
         &mut self,
         id: u32,
@@ -7219,6 +9636,9 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
     #[allow(dead_code)]
     const DEBUG_COLOR_SELECTED_ROW: Color = Color::rgba(102.0, 80.0, 78.0, 255.0);
     const DEBUG_HIGHLIGHT_COLOR: Color = Color::rgba(168.0, 66.0, 28.0, 100.0);
+    const DEBUG_PADDING_COLOR: Color = Color::rgba(120.0, 190.0, 90.0, 80.0);
+    const DEBUG_GAP_COLOR: Color = Color::rgba(170.0, 90.0, 200.0, 90.0);
+    const DEBUG_DUPLICATE_COLOR: Color = Color::rgba(255.0, 40.0, 40.0, 255.0);
 
     /// Escape text-styling special characters (`{`, `}`, `|`, `\`) so that
     /// debug view strings are never interpreted as styling markup.
@@ -7280,19 +9700,19 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
     }
 
     /// Helper: open an element, configure, return nothing. Caller must close_element().
-    fn debug_open(&mut self, decl: &ElementDeclaration<CustomElementData>) {
+    fn debug_open(&mut self, decl: ElementDeclaration<CustomElementData>) {
         self.open_element();
         self.configure_open_element(decl);
     }
 
     /// Helper: open a named element, configure. Caller must close_element().
-    fn debug_open_id(&mut self, name: &str, decl: &ElementDeclaration<CustomElementData>) {
+    fn debug_open_id(&mut self, name: &str, decl: ElementDeclaration<CustomElementData>) {
         self.open_element_with_id(&hash_string(name, 0));
         self.configure_open_element(decl);
     }
 
     /// Helper: open a named+indexed element, configure. Caller must close_element().
-    fn debug_open_idi(&mut self, name: &str, offset: u32, decl: &ElementDeclaration<CustomElementData>) {
+    fn debug_open_idi(&mut self, name: &str, offset: u32, decl: ElementDeclaration<CustomElementData>) {
         self.open_element_with_id(&hash_string_with_offset(name, offset, 0));
         self.configure_open_element(decl);
     }
@@ -7308,6 +9728,8 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             ElementConfigType::Border => ("Border", Color::rgba(108.0, 91.0, 123.0, 255.0)),
             ElementConfigType::Custom => ("Custom", Color::rgba(11.0, 72.0, 107.0, 255.0)),
             ElementConfigType::TextInput => ("TextInput", Color::rgba(52.0, 152.0, 219.0, 255.0)),
+            ElementConfigType::Canvas => ("Canvas", Color::rgba(155.0, 89.0, 182.0, 255.0)),
+            ElementConfigType::Draw => ("Draw", Color::rgba(231.0, 76.0, 60.0, 255.0)),
         }
     }
 
@@ -7318,7 +9740,10 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             SizingType::Grow => "GROW",
             SizingType::Percent => "PERCENT",
             SizingType::Fixed => "FIXED",
-            // Default handled by Grow arm above
+            SizingType::ViewportWidth => "VIEWPORT_WIDTH",
+            SizingType::ViewportHeight => "VIEWPORT_HEIGHT",
+            SizingType::MinContent => "MIN_CONTENT",
+            SizingType::MaxContent => "MAX_CONTENT",
         };
         self.debug_text(label, config_index);
         if matches!(sizing.type_, SizingType::Grow | SizingType::Fit | SizingType::Fixed) {
@@ -7373,7 +9798,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         element_id_string: StringId,
     ) {
         let bg = Color::rgba(label_color.r, label_color.g, label_color.b, 90.0);
-        self.debug_open(&ElementDeclaration {
+        self.debug_open(ElementDeclaration {
             layout: LayoutConfig {
                 sizing: SizingConfig {
                     width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -7392,7 +9817,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         });
         {
             // Badge
-            self.debug_open(&ElementDeclaration {
+            self.debug_open(ElementDeclaration {
                 layout: LayoutConfig {
                     padding: PaddingConfig { left: 8, right: 8, top: 2, bottom: 2 },
                     ..Default::default()
@@ -7416,7 +9841,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             }
             self.close_element();
             // Spacer
-            self.debug_open(&ElementDeclaration {
+            self.debug_open(ElementDeclaration {
                 layout: LayoutConfig {
                     sizing: SizingConfig {
                         width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -7443,7 +9868,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
     /// Render a color value in the debug view.
     fn render_debug_view_color(&mut self, color: Color, config_index: usize) {
-        self.debug_open(&ElementDeclaration {
+        self.debug_open(ElementDeclaration {
             layout: LayoutConfig {
                 child_alignment: ChildAlignmentConfig { x: AlignX::Left, y: AlignY::CenterY },
                 ..Default::default()
@@ -7461,7 +9886,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             self.debug_int_text(color.a, config_index);
             self.debug_text(" }", config_index);
             // Spacer
-            self.debug_open(&ElementDeclaration {
+            self.debug_open(ElementDeclaration {
                 layout: LayoutConfig {
                     sizing: SizingConfig {
                         width: SizingAxis {
@@ -7478,7 +9903,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             self.close_element();
             // Color swatch
             let swatch_size = Self::DEBUG_VIEW_ROW_HEIGHT - 8.0;
-            self.debug_open(&ElementDeclaration {
+            self.debug_open(ElementDeclaration {
                 layout: LayoutConfig {
                     sizing: SizingConfig {
                         width: SizingAxis {
@@ -7514,25 +9939,25 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         cr: CornerRadius,
         info_text_config: usize,
     ) {
-        self.debug_open(&ElementDeclaration::default());
+        self.debug_open(ElementDeclaration::default());
         {
             self.debug_text("topLeft: ", info_text_config);
             self.debug_float_text(cr.top_left, info_text_config);
         }
         self.close_element();
-        self.debug_open(&ElementDeclaration::default());
+        self.debug_open(ElementDeclaration::default());
         {
             self.debug_text("topRight: ", info_text_config);
             self.debug_float_text(cr.top_right, info_text_config);
         }
         self.close_element();
-        self.debug_open(&ElementDeclaration::default());
+        self.debug_open(ElementDeclaration::default());
         {
             self.debug_text("bottomLeft: ", info_text_config);
             self.debug_float_text(cr.bottom_left, info_text_config);
         }
         self.close_element();
-        self.debug_open(&ElementDeclaration::default());
+        self.debug_open(ElementDeclaration::default());
         {
             self.debug_text("bottomRight: ", info_text_config);
             self.debug_float_text(cr.bottom_right, info_text_config);
@@ -7621,6 +10046,15 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             ShaderUniformValue::Mat4(_) => {
                 self.debug_text("[mat4]", config_index);
             }
+            ShaderUniformValue::FloatArray(_) => {
+                self.debug_text("[float array]", config_index);
+            }
+            ShaderUniformValue::Vec2Array(_) => {
+                self.debug_text("[vec2 array]", config_index);
+            }
+            ShaderUniformValue::Vec4Array(_) => {
+                self.debug_text("[vec4 array]", config_index);
+            }
         }
     }
 
@@ -7665,7 +10099,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
             // Separator between roots
             if root_index > 0 {
-                self.debug_open_idi("Ply__DebugView_EmptyRowOuter", root_index as u32, &ElementDeclaration {
+                self.debug_open_idi("Ply__DebugView_EmptyRowOuter", root_index as u32, ElementDeclaration {
                     layout: LayoutConfig {
                         sizing: SizingConfig {
                             width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -7677,7 +10111,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     ..Default::default()
                 });
                 {
-                    self.debug_open_idi("Ply__DebugView_EmptyRow", root_index as u32, &ElementDeclaration {
+                    self.debug_open_idi("Ply__DebugView_EmptyRow", root_index as u32, ElementDeclaration {
                         layout: LayoutConfig {
                             sizing: SizingConfig {
                                 width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -7761,7 +10195,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 } else {
                     Color::rgba(0.0, 0.0, 0.0, 0.0)
                 };
-                self.debug_open_idi("Ply__DebugView_ElementOuter", current_elem_id, &ElementDeclaration {
+                self.debug_open_idi("Ply__DebugView_ElementOuter", current_elem_id, ElementDeclaration {
                     layout: scroll_item_layout,
                     background_color: row_bg,
                     ..Default::default()
@@ -7773,7 +10207,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     // Collapse icon / button or dot
                     if !is_text && children_len > 0 {
                         // Collapse button
-                        self.debug_open_idi("Ply__DebugView_CollapseElement", current_elem_id, &ElementDeclaration {
+                        self.debug_open_idi("Ply__DebugView_CollapseElement", current_elem_id, ElementDeclaration {
                             layout: LayoutConfig {
                                 sizing: SizingConfig {
                                     width: SizingAxis { type_: SizingType::Fixed, min_max: SizingMinMax { min: 16.0, max: 16.0 }, ..Default::default() },
@@ -7805,7 +10239,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         self.close_element();
                     } else {
                         // Empty dot for leaf elements
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 sizing: SizingConfig {
                                     width: SizingAxis { type_: SizingType::Fixed, min_max: SizingMinMax { min: 16.0, max: 16.0 }, ..Default::default() },
@@ -7817,7 +10251,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             ..Default::default()
                         });
                         {
-                            self.debug_open(&ElementDeclaration {
+                            self.debug_open(ElementDeclaration {
                                 layout: LayoutConfig {
                                     sizing: SizingConfig {
                                         width: SizingAxis { type_: SizingType::Fixed, min_max: SizingMinMax { min: 8.0, max: 8.0 }, ..Default::default() },
@@ -7836,7 +10270,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
                     // Collision warning badge
                     if collision {
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: PaddingConfig { left: 8, right: 8, top: 2, bottom: 2 },
                                 ..Default::default()
@@ -7861,7 +10295,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
                     // Offscreen badge
                     if offscreen {
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: PaddingConfig { left: 8, right: 8, top: 2, bottom: 2 },
                                 ..Default::default()
@@ -7912,7 +10346,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             let shared = self.shared_element_configs[ec.config_index];
                             let label_color = Color::rgba(243.0, 134.0, 48.0, 90.0);
                             if shared.background_color.a > 0.0 {
-                                self.debug_open(&ElementDeclaration {
+                                self.debug_open(ElementDeclaration {
                                     layout: LayoutConfig {
                                         padding: PaddingConfig { left: 8, right: 8, top: 2, bottom: 2 },
                                         ..Default::default()
@@ -7938,7 +10372,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             }
                             if !shared.corner_radius.is_zero() {
                                 let radius_color = Color::rgba(26.0, 188.0, 156.0, 90.0);
-                                self.debug_open(&ElementDeclaration {
+                                self.debug_open(ElementDeclaration {
                                     layout: LayoutConfig {
                                         padding: PaddingConfig { left: 8, right: 8, top: 2, bottom: 2 },
                                         ..Default::default()
@@ -7966,7 +10400,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         }
                         let (label, label_color) = Self::debug_get_config_type_label(ec.config_type);
                         let bg = Color::rgba(label_color.r, label_color.g, label_color.b, 90.0);
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: PaddingConfig { left: 8, right: 8, top: 2, bottom: 2 },
                                 ..Default::default()
@@ -8006,7 +10440,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         if has_scrollbar {
                             let bg = Color::rgba(242.0, 196.0, 90.0, 90.0);
                             let border_color = Color::rgba(242.0, 196.0, 90.0, 255.0);
-                            self.debug_open(&ElementDeclaration {
+                            self.debug_open(ElementDeclaration {
                                 layout: LayoutConfig {
                                     padding: PaddingConfig { left: 8, right: 8, top: 2, bottom: 2 },
                                     ..Default::default()
@@ -8033,11 +10467,10 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     }
 
                     // Shader badge
-                    let has_shaders = self.element_shaders.get(current_element_index)
-                        .map_or(false, |s| !s.is_empty());
+                    let has_shaders = !self.element_shaders_at(current_element_index).is_empty();
                     if has_shaders {
                         let badge_color = Color::rgba(155.0, 89.0, 182.0, 90.0);
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: PaddingConfig { left: 8, right: 8, top: 2, bottom: 2 },
                                 ..Default::default()
@@ -8063,11 +10496,10 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     }
 
                     // Effect badge
-                    let has_effects = self.element_effects.get(current_element_index)
-                        .map_or(false, |e| !e.is_empty());
+                    let has_effects = !self.element_effects_at(current_element_index).is_empty();
                     if has_effects {
                         let badge_color = Color::rgba(155.0, 89.0, 182.0, 90.0);
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: PaddingConfig { left: 8, right: 8, top: 2, bottom: 2 },
                                 ..Default::default()
@@ -8097,7 +10529,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         .map_or(false, |r| r.is_some());
                     if has_visual_rot {
                         let badge_color = Color::rgba(155.0, 89.0, 182.0, 90.0);
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: PaddingConfig { left: 8, right: 8, top: 2, bottom: 2 },
                                 ..Default::default()
@@ -8127,7 +10559,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         .map_or(false, |r| r.is_some());
                     if has_shape_rot {
                         let badge_color = Color::rgba(26.0, 188.0, 156.0, 90.0);
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: PaddingConfig { left: 8, right: 8, top: 2, bottom: 2 },
                                 ..Default::default()
@@ -8174,7 +10606,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     } else {
                         self.store_text_element_config(name_text_config.clone())
                     };
-                    self.debug_open(&ElementDeclaration {
+                    self.debug_open(ElementDeclaration {
                         layout: LayoutConfig {
                             sizing: SizingConfig {
                                 height: SizingAxis {
@@ -8191,7 +10623,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     });
                     {
                         // Indent spacer
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 sizing: SizingConfig {
                                     width: SizingAxis {
@@ -8224,7 +10656,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 } else if children_len > 0 {
                     // Open containers for child indentation
                     self.open_element();
-                    self.configure_open_element(&ElementDeclaration {
+                    self.configure_open_element(ElementDeclaration {
                         layout: LayoutConfig {
                             padding: PaddingConfig { left: 8, ..Default::default() },
                             ..Default::default()
@@ -8232,7 +10664,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         ..Default::default()
                     });
                     self.open_element();
-                    self.configure_open_element(&ElementDeclaration {
+                    self.configure_open_element(ElementDeclaration {
                         layout: LayoutConfig {
                             padding: PaddingConfig { left: indent_width, ..Default::default() },
                             ..Default::default()
@@ -8245,7 +10677,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         ..Default::default()
                     });
                     self.open_element();
-                    self.configure_open_element(&ElementDeclaration {
+                    self.configure_open_element(ElementDeclaration {
                         layout: LayoutConfig {
                             layout_direction: LayoutDirection::TopToBottom,
                             ..Default::default()
@@ -8287,55 +10719,151 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             }
         }
 
-        // Render highlight on hovered or selected element
-        // When an element is selected, show its bounding box; otherwise show hovered
-        let highlight_target = if self.debug_selected_element_id != 0 {
+        // Render highlight on the element picked on screen, or the selected
+        // element, or else the hovered row's
+        let highlight_target = if self.debug_picking && self.debug_hovered_element_id != 0 {
+            self.debug_hovered_element_id
+        } else if self.debug_selected_element_id != 0 {
             self.debug_selected_element_id
         } else {
             highlighted_element_id
         };
         if highlight_target != 0 {
-            self.debug_open_id("Ply__DebugView_ElementHighlight", &ElementDeclaration {
+            self.render_debug_element_highlight(highlight_target);
+        }
+
+        (row_count, selected_element_row_index)
+    }
+
+    /// Overlays an element with its content box, padding and the gaps between
+    /// its children.
+    fn render_debug_element_highlight(&mut self, element_id: u32) {
+        let (padding, direction, gaps) = match self.layout_element_map.get(&element_id) {
+            Some(item) => {
+                let idx = item.layout_element_index as usize;
+                match self.layout_elements.get(idx).filter(|element| element.id == element_id) {
+                    Some(element) => {
+                        let config = self.layout_configs[element.layout_config_index];
+                        let gaps = self.debug_child_gaps(idx, item.bounding_box, config);
+                        (config.padding, config.layout_direction, gaps)
+                    }
+                    None => (PaddingConfig::default(), LayoutDirection::LeftToRight, Vec::new()),
+                }
+            }
+            None => return,
+        };
+        let overlay = |offset: Vector2| FloatingConfig {
+            offset,
+            parent_id: element_id,
+            z_index: i16::MAX,
+            pointer_capture_mode: PointerCaptureMode::Passthrough,
+            attach_to: FloatingAttachToElement::ElementWithId,
+            ..Default::default()
+        };
+        let grow = SizingAxis { type_: SizingType::Grow, ..Default::default() };
+
+        // Padding around the content box
+        self.debug_open_id("Ply__DebugView_ElementHighlight", ElementDeclaration {
+            layout: LayoutConfig {
+                sizing: SizingConfig { width: grow, height: grow },
+                padding,
+                ..Default::default()
+            },
+            floating: overlay(Vector2::default()),
+            background_color: if padding.left + padding.right + padding.top + padding.bottom > 0 {
+                Self::DEBUG_PADDING_COLOR
+            } else {
+                Color::rgba(0.0, 0.0, 0.0, 0.0)
+            },
+            ..Default::default()
+        });
+        {
+            self.debug_open_id("Ply__DebugView_ElementHighlightRectangle", ElementDeclaration {
                 layout: LayoutConfig {
-                    sizing: SizingConfig {
-                        width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
-                        height: SizingAxis { type_: SizingType::Grow, ..Default::default() },
-                    },
+                    sizing: SizingConfig { width: grow, height: grow },
                     ..Default::default()
                 },
-                floating: FloatingConfig {
-                    parent_id: highlight_target,
-                    z_index: 32767,
-                    pointer_capture_mode: PointerCaptureMode::Passthrough,
-                    attach_to: FloatingAttachToElement::ElementWithId,
+                background_color: Self::DEBUG_HIGHLIGHT_COLOR,
+                ..Default::default()
+            });
+            self.close_element();
+        }
+        self.close_element();
+
+        // Gaps between children
+        for (i, (start, length, cross)) in gaps.into_iter().enumerate() {
+            let fixed = |size: f32| SizingAxis {
+                type_: SizingType::Fixed,
+                min_max: SizingMinMax { min: size, max: size },
+                ..Default::default()
+            };
+            let (offset, width, height) = match direction {
+                LayoutDirection::LeftToRight => (Vector2::new(start, padding.top as f32), fixed(length), fixed(cross)),
+                LayoutDirection::TopToBottom => (Vector2::new(padding.left as f32, start), fixed(cross), fixed(length)),
+            };
+            self.debug_open_idi("Ply__DebugView_ElementGap", i as u32, ElementDeclaration {
+                layout: LayoutConfig {
+                    sizing: SizingConfig { width, height },
                     ..Default::default()
                 },
+                floating: overlay(offset),
+                background_color: Self::DEBUG_GAP_COLOR,
                 ..Default::default()
             });
-            {
-                self.debug_open_id("Ply__DebugView_ElementHighlightRectangle", &ElementDeclaration {
-                    layout: LayoutConfig {
-                        sizing: SizingConfig {
-                            width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
-                            height: SizingAxis { type_: SizingType::Grow, ..Default::default() },
-                        },
-                        ..Default::default()
-                    },
-                    background_color: Self::DEBUG_HIGHLIGHT_COLOR,
-                    ..Default::default()
-                });
-                self.close_element();
-            }
             self.close_element();
         }
+    }
 
-        (row_count, selected_element_row_index)
+    /// The space between consecutive children of an element, as laid out last
+    /// frame: `(start along the layout axis, length, size across it)`, relative
+    /// to the element.
+    fn debug_child_gaps(&self, element_index: usize, bounds: BoundingBox, config: LayoutConfig) -> Vec<(f32, f32, f32)> {
+        if self.element_has_config(element_index, ElementConfigType::Text) {
+            return Vec::new();
+        }
+        let element = &self.layout_elements[element_index];
+        let children = &self.layout_element_children[element.children_start..element.children_start + element.children_length as usize];
+        let boxes: Vec<BoundingBox> = children
+            .iter()
+            .filter_map(|&child| self.layout_element_map.get(&self.layout_elements[child as usize].id))
+            .map(|item| item.bounding_box)
+            .collect();
+        boxes
+            .windows(2)
+            .filter_map(|pair| {
+                let (start, end, cross) = match config.layout_direction {
+                    LayoutDirection::LeftToRight => (
+                        pair[0].x + pair[0].width - bounds.x,
+                        pair[1].x - bounds.x,
+                        bounds.height - (config.padding.top + config.padding.bottom) as f32,
+                    ),
+                    LayoutDirection::TopToBottom => (
+                        pair[0].y + pair[0].height - bounds.y,
+                        pair[1].y - bounds.y,
+                        bounds.width - (config.padding.left + config.padding.right) as f32,
+                    ),
+                };
+                (end > start).then_some((start, end - start, cross.max(0.0)))
+            })
+            .collect()
+    }
+
+    /// The innermost element of the UI under the pointer, ignoring the debug
+    /// view's own elements, or `0`.
+    fn debug_pick_target(&self) -> u32 {
+        self.pointer_over_ids
+            .iter()
+            .rev()
+            .filter_map(|eid| self.layout_element_map.get(&eid.id))
+            .find(|item| (item.layout_element_index as usize) < self.debug_first_element_index)
+            .map_or(0, |item| item.element_id.id)
     }
 
     /// Main debug view rendering. Called from end_layout() when debug mode is enabled.
     fn render_debug_view(&mut self) {
         let initial_roots_length = self.layout_element_tree_roots.len();
         let initial_elements_length = self.layout_elements.len();
+        self.debug_first_element_index = initial_elements_length;
         let row_height = Self::DEBUG_VIEW_ROW_HEIGHT;
         let outer_padding = Self::DEBUG_VIEW_OUTER_PADDING;
         let debug_width = self.debug_view_width;
@@ -8383,7 +10911,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         };
 
         // Main debug view panel (floating)
-        self.debug_open_id("Ply__DebugView", &ElementDeclaration {
+        self.debug_open_id("Ply__DebugView", ElementDeclaration {
             layout: LayoutConfig {
                 sizing: SizingConfig {
                     width: SizingAxis {
@@ -8401,7 +10929,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 ..Default::default()
             },
             floating: FloatingConfig {
-                z_index: 32765,
+                z_index: Layer::Debug.z_index() + 1,
                 attach_points: FloatingAttachPoints {
                     element_x: AlignX::Right,
                     element_y: AlignY::CenterY,
@@ -8421,7 +10949,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         });
         {
             // Header bar
-            self.debug_open(&ElementDeclaration {
+            self.debug_open(ElementDeclaration {
                 layout: LayoutConfig {
                     sizing: SizingConfig {
                         width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -8432,6 +10960,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         },
                     },
                     padding: PaddingConfig { left: outer_padding, right: outer_padding, top: 0, bottom: 0 },
+                    child_gap: 6,
                     child_alignment: ChildAlignmentConfig { x: AlignX::Left, y: AlignY::CenterY },
                     ..Default::default()
                 },
@@ -8441,7 +10970,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             {
                 self.debug_text("Ply Debug Tools", info_text_config);
                 // Spacer
-                self.debug_open(&ElementDeclaration {
+                self.debug_open(ElementDeclaration {
                     layout: LayoutConfig {
                         sizing: SizingConfig {
                             width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -8452,9 +10981,38 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     ..Default::default()
                 });
                 self.close_element();
+                // Pick button, highlighted while picking
+                let pick_background = if self.debug_picking {
+                    Color::rgba(67.0, 140.0, 217.0, 160.0)
+                } else {
+                    Color::rgba(67.0, 140.0, 217.0, 50.0)
+                };
+                self.debug_open_id("Ply__DebugView_PickButton", ElementDeclaration {
+                    layout: LayoutConfig {
+                        sizing: SizingConfig {
+                            height: SizingAxis { type_: SizingType::Fixed, min_max: SizingMinMax { min: row_height - 10.0, max: row_height - 10.0 }, ..Default::default() },
+                            ..Default::default()
+                        },
+                        padding: PaddingConfig { left: 8, right: 8, top: 0, bottom: 0 },
+                        child_alignment: ChildAlignmentConfig { x: AlignX::CenterX, y: AlignY::CenterY },
+                        ..Default::default()
+                    },
+                    background_color: pick_background,
+                    corner_radius: CornerRadius { top_left: 4.0, top_right: 4.0, bottom_left: 4.0, bottom_right: 4.0 },
+                    border: BorderConfig {
+                        color: Color::rgba(67.0, 140.0, 217.0, 255.0),
+                        width: BorderWidth { left: 1, right: 1, top: 1, bottom: 1, between_children: 0 },
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                });
+                {
+                    self.debug_text("Pick", info_text_config);
+                }
+                self.close_element();
                 // Close button
                 let close_size = row_height - 10.0;
-                self.debug_open_id("Ply__DebugView_CloseButton", &ElementDeclaration {
+                self.debug_open_id("Ply__DebugView_CloseButton", ElementDeclaration {
                     layout: LayoutConfig {
                         sizing: SizingConfig {
                             width: SizingAxis { type_: SizingType::Fixed, min_max: SizingMinMax { min: close_size, max: close_size }, ..Default::default() },
@@ -8485,7 +11043,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             self.close_element();
 
             // Separator line
-            self.debug_open(&ElementDeclaration {
+            self.debug_open(ElementDeclaration {
                 layout: LayoutConfig {
                     sizing: SizingConfig {
                         width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -8500,7 +11058,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
             // Scroll pane
             self.open_element_with_id(&scroll_id);
-            self.configure_open_element(&ElementDeclaration {
+            self.configure_open_element(ElementDeclaration {
                 layout: LayoutConfig {
                     sizing: SizingConfig {
                         width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -8525,7 +11083,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     Self::DEBUG_COLOR_1
                 };
                 // Content container — Fit height so it extends beyond the scroll pane
-                self.debug_open(&ElementDeclaration {
+                self.debug_open(ElementDeclaration {
                     layout: LayoutConfig {
                         sizing: SizingConfig {
                             width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -8554,7 +11112,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             self.close_element(); // scroll pane
 
             // Separator
-            self.debug_open(&ElementDeclaration {
+            self.debug_open(ElementDeclaration {
                 layout: LayoutConfig {
                     sizing: SizingConfig {
                         width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -8577,6 +11135,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         // Handle close button click
         if self.pointer_info.state == PointerDataInteractionState::PressedThisFrame {
             let close_base_id = hash_string("Ply__DebugView_CloseButton", 0).id;
+            let pick_base_id = hash_string("Ply__DebugView_PickButton", 0).id;
             let header_base_id = hash_string("Ply__DebugView_LayoutConfigHeader", 0).id;
             for i in (0..self.pointer_over_ids.len()).rev() {
                 let id = self.pointer_over_ids[i].id;
@@ -8584,6 +11143,10 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     self.debug_mode_enabled = false;
                     break;
                 }
+                if id == pick_base_id {
+                    self.set_debug_picking(!self.debug_picking);
+                    break;
+                }
                 if id == header_base_id {
                     self.debug_selected_element_id = 0;
                     break;
@@ -8620,7 +11183,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         let layout_config_index = self.layout_elements[layout_elem_idx].layout_config_index;
         let layout_config = self.layout_configs[layout_config_index];
 
-        self.debug_open(&ElementDeclaration {
+        self.debug_open(ElementDeclaration {
             layout: LayoutConfig {
                 sizing: SizingConfig {
                     width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -8649,7 +11212,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         });
         {
             // Header: "Layout Config" + element ID
-            self.debug_open_id("Ply__DebugView_LayoutConfigHeader", &ElementDeclaration {
+            self.debug_open_id("Ply__DebugView_LayoutConfigHeader", ElementDeclaration {
                 layout: LayoutConfig {
                     sizing: SizingConfig {
                         width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -8668,7 +11231,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             {
                 self.debug_text("Layout Config", info_text_config);
                 // Spacer
-                self.debug_open(&ElementDeclaration {
+                self.debug_open(ElementDeclaration {
                     layout: LayoutConfig {
                         sizing: SizingConfig {
                             width: SizingAxis { type_: SizingType::Grow, ..Default::default() },
@@ -8693,7 +11256,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             self.close_element();
 
             // Layout config details
-            self.debug_open(&ElementDeclaration {
+            self.debug_open(ElementDeclaration {
                 layout: LayoutConfig {
                     padding: attr_padding,
                     child_gap: 8,
@@ -8705,7 +11268,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             {
                 // Bounding Box
                 self.debug_text("Bounding Box", info_title_config);
-                self.debug_open(&ElementDeclaration::default());
+                self.debug_open(ElementDeclaration::default());
                 {
                     self.debug_text("{ x: ", info_text_config);
                     self.debug_int_text(selected_item.bounding_box.x, info_text_config);
@@ -8729,13 +11292,13 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
                 // Sizing
                 self.debug_text("Sizing", info_title_config);
-                self.debug_open(&ElementDeclaration::default());
+                self.debug_open(ElementDeclaration::default());
                 {
                     self.debug_text("width: ", info_text_config);
                     self.render_debug_layout_sizing(layout_config.sizing.width, info_text_config);
                 }
                 self.close_element();
-                self.debug_open(&ElementDeclaration::default());
+                self.debug_open(ElementDeclaration::default());
                 {
                     self.debug_text("height: ", info_text_config);
                     self.render_debug_layout_sizing(layout_config.sizing.height, info_text_config);
@@ -8744,7 +11307,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
                 // Padding
                 self.debug_text("Padding", info_title_config);
-                self.debug_open_id("Ply__DebugViewElementInfoPadding", &ElementDeclaration::default());
+                self.debug_open_id("Ply__DebugViewElementInfoPadding", ElementDeclaration::default());
                 {
                     self.debug_text("{ left: ", info_text_config);
                     self.debug_int_text(layout_config.padding.left as f32, info_text_config);
@@ -8775,7 +11338,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
 
                 // Child Alignment
                 self.debug_text("Child Alignment", info_title_config);
-                self.debug_open(&ElementDeclaration::default());
+                self.debug_open(ElementDeclaration::default());
                 {
                     self.debug_text("{ x: ", info_text_config);
                     let align_x = Self::align_x_name(layout_config.child_alignment.x);
@@ -8809,15 +11372,16 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             // Per-element data (not in element_configs system)
             let shape_rot = self.element_shape_rotations.get(layout_elem_idx).copied().flatten();
             let visual_rot = self.element_visual_rotations.get(layout_elem_idx).cloned().flatten();
-            let effects = self.element_effects.get(layout_elem_idx).cloned().unwrap_or_default();
-            let shaders = self.element_shaders.get(layout_elem_idx).cloned().unwrap_or_default();
+            // Copied, as listing them declares debug elements
+            let effects = self.shaders(self.element_effects_at(layout_elem_idx)).to_vec();
+            let shaders = self.shaders(self.element_shaders_at(layout_elem_idx)).to_vec();
 
             // ── [Color] section ──
             let has_color = shared_bg_color.map_or(false, |c| c.a > 0.0);
             if has_color {
                 let color_label_color = Color::rgba(243.0, 134.0, 48.0, 255.0);
                 self.render_debug_view_category_header("Color", color_label_color, elem_id_string.clone());
-                self.debug_open(&ElementDeclaration {
+                self.debug_open(ElementDeclaration {
                     layout: LayoutConfig {
                         padding: attr_padding,
                         child_gap: 8,
@@ -8839,7 +11403,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             if has_corner_radius || has_shape_rot {
                 let shape_label_color = Color::rgba(26.0, 188.0, 156.0, 255.0);
                 self.render_debug_view_category_header("Shape", shape_label_color, elem_id_string.clone());
-                self.debug_open(&ElementDeclaration {
+                self.debug_open(ElementDeclaration {
                     layout: LayoutConfig {
                         padding: attr_padding,
                         child_gap: 8,
@@ -8857,14 +11421,14 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     }
                     if let Some(sr) = shape_rot {
                         self.debug_text("Shape Rotation", info_title_config);
-                        self.debug_open(&ElementDeclaration::default());
+                        self.debug_open(ElementDeclaration::default());
                         {
                             self.debug_text("angle: ", info_text_config);
                             self.debug_float_text(sr.rotation_radians, info_text_config);
                             self.debug_text(" rad", info_text_config);
                         }
                         self.close_element();
-                        self.debug_open(&ElementDeclaration::default());
+                        self.debug_open(ElementDeclaration::default());
                         {
                             self.debug_text("flip_x: ", info_text_config);
                             self.debug_text(if sr.flip_x { "true" } else { "false" }, info_text_config);
@@ -8885,7 +11449,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     ElementConfigType::Text => {
                         self.render_debug_view_element_config_header(elem_id_string.clone(), ec.config_type, info_title_config);
                         let text_config = self.text_element_configs[ec.config_index].clone();
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: attr_padding,
                                 child_gap: 8,
@@ -8918,11 +11482,13 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             let wrap = match text_config.wrap_mode {
                                 WrapMode::None => "NONE",
                                 WrapMode::Newline => "NEWLINES",
+                                WrapMode::Anywhere => "ANYWHERE",
                                 _ => "WORDS",
                             };
                             self.debug_text(wrap, info_text_config);
                             self.debug_text("Text Alignment", info_title_config);
                             let align = match text_config.alignment {
+                                _ if text_config.justify => "JUSTIFY",
                                 AlignX::CenterX => "CENTER",
                                 AlignX::Right => "RIGHT",
                                 _ => "LEFT",
@@ -8937,7 +11503,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         let image_label_color = Color::rgba(121.0, 189.0, 154.0, 255.0);
                         self.render_debug_view_category_header("Image", image_label_color, elem_id_string.clone());
                         let image_data = self.image_element_configs[ec.config_index].clone();
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: attr_padding,
                                 child_gap: 8,
@@ -8965,7 +11531,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             .get(ec.config_index)
                             .copied()
                             .unwrap_or(false);
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: attr_padding,
                                 child_gap: 8,
@@ -8988,7 +11554,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     ElementConfigType::Clip => {
                         self.render_debug_view_element_config_header(elem_id_string.clone(), ec.config_type, info_title_config);
                         let clip_config = self.clip_element_configs[ec.config_index];
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: attr_padding,
                                 child_gap: 8,
@@ -8999,7 +11565,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         });
                         {
                             self.debug_text("Overflow", info_title_config);
-                            self.debug_open(&ElementDeclaration::default());
+                            self.debug_open(ElementDeclaration::default());
                             {
                                 let x_label = if clip_config.scroll_x {
                                     "SCROLL"
@@ -9042,7 +11608,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 scrollbar_label_color,
                                 elem_id_string.clone(),
                             );
-                            self.debug_open(&ElementDeclaration {
+                            self.debug_open(ElementDeclaration {
                                 layout: LayoutConfig {
                                     padding: attr_padding,
                                     child_gap: 8,
@@ -9064,7 +11630,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     ElementConfigType::Floating => {
                         self.render_debug_view_element_config_header(elem_id_string.clone(), ec.config_type, info_title_config);
                         let float_config = self.floating_element_configs[ec.config_index];
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: attr_padding,
                                 child_gap: 8,
@@ -9075,7 +11641,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         });
                         {
                             self.debug_text("Offset", info_title_config);
-                            self.debug_open(&ElementDeclaration::default());
+                            self.debug_open(ElementDeclaration::default());
                             {
                                 self.debug_text("{ x: ", info_text_config);
                                 self.debug_int_text(float_config.offset.x, info_text_config);
@@ -9098,7 +11664,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             }
 
                             self.debug_text("Attach Points", info_title_config);
-                            self.debug_open(&ElementDeclaration::default());
+                            self.debug_open(ElementDeclaration::default());
                             {
                                 self.debug_text("{ element: (", info_text_config);
                                 self.debug_text(Self::align_x_name(float_config.attach_points.element_x), info_text_config);
@@ -9125,6 +11691,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 FloatingAttachToElement::Parent => "PARENT",
                                 FloatingAttachToElement::ElementWithId => "ELEMENT_WITH_ID",
                                 FloatingAttachToElement::Root => "ROOT",
+                                FloatingAttachToElement::Pointer => "POINTER",
                                 _ => "NONE",
                             };
                             self.debug_text(at, info_text_config);
@@ -9142,7 +11709,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     ElementConfigType::Border => {
                         self.render_debug_view_element_config_header(elem_id_string.clone(), ec.config_type, info_title_config);
                         let border_config = self.border_element_configs[ec.config_index];
-                        self.debug_open_id("Ply__DebugViewElementInfoBorderBody", &ElementDeclaration {
+                        self.debug_open_id("Ply__DebugViewElementInfoBorderBody", ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: attr_padding,
                                 child_gap: 8,
@@ -9153,31 +11720,31 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         });
                         {
                             self.debug_text("Border Widths", info_title_config);
-                            self.debug_open(&ElementDeclaration::default());
+                            self.debug_open(ElementDeclaration::default());
                             {
                                 self.debug_text("left: ", info_text_config);
                                 self.debug_int_text(border_config.width.left as f32, info_text_config);
                             }
                             self.close_element();
-                            self.debug_open(&ElementDeclaration::default());
+                            self.debug_open(ElementDeclaration::default());
                             {
                                 self.debug_text("right: ", info_text_config);
                                 self.debug_int_text(border_config.width.right as f32, info_text_config);
                             }
                             self.close_element();
-                            self.debug_open(&ElementDeclaration::default());
+                            self.debug_open(ElementDeclaration::default());
                             {
                                 self.debug_text("top: ", info_text_config);
                                 self.debug_int_text(border_config.width.top as f32, info_text_config);
                             }
                             self.close_element();
-                            self.debug_open(&ElementDeclaration::default());
+                            self.debug_open(ElementDeclaration::default());
                             {
                                 self.debug_text("bottom: ", info_text_config);
                                 self.debug_int_text(border_config.width.bottom as f32, info_text_config);
                             }
                             self.close_element();
-                            self.debug_open(&ElementDeclaration::default());
+                            self.debug_open(ElementDeclaration::default());
                             {
                                 self.debug_text("betweenChildren: ", info_text_config);
                                 self.debug_int_text(
@@ -9203,7 +11770,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         let input_label_color = Color::rgba(52.0, 152.0, 219.0, 255.0);
                         self.render_debug_view_category_header("Input", input_label_color, elem_id_string.clone());
                         let ti_cfg = self.text_input_configs[ec.config_index].clone();
-                        self.debug_open(&ElementDeclaration {
+                        self.debug_open(ElementDeclaration {
                             layout: LayoutConfig {
                                 padding: attr_padding,
                                 child_gap: 8,
@@ -9247,7 +11814,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                                 info_text_config,
                             );
                             self.debug_text("Font", info_title_config);
-                            self.debug_open(&ElementDeclaration::default());
+                            self.debug_open(ElementDeclaration::default());
                             {
                                 let label = if let Some(asset) = ti_cfg.font_asset {
                                     asset.key().to_string()
@@ -9306,7 +11873,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             if has_visual_rot || has_effects || has_shaders {
                 let effects_label_color = Color::rgba(155.0, 89.0, 182.0, 255.0);
                 self.render_debug_view_category_header("Effects", effects_label_color, elem_id_string.clone());
-                self.debug_open(&ElementDeclaration {
+                self.debug_open(ElementDeclaration {
                     layout: LayoutConfig {
                         padding: attr_padding,
                         child_gap: 8,
@@ -9318,14 +11885,14 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                 {
                     if let Some(vr) = visual_rot {
                         self.debug_text("Visual Rotation", info_title_config);
-                        self.debug_open(&ElementDeclaration::default());
+                        self.debug_open(ElementDeclaration::default());
                         {
                             self.debug_text("angle: ", info_text_config);
                             self.debug_float_text(vr.rotation_radians, info_text_config);
                             self.debug_text(" rad", info_text_config);
                         }
                         self.close_element();
-                        self.debug_open(&ElementDeclaration::default());
+                        self.debug_open(ElementDeclaration::default());
                         {
                             self.debug_text("pivot: (", info_text_config);
                             self.debug_float_text(vr.pivot_x, info_text_config);
@@ -9334,7 +11901,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                             self.debug_text(")", info_text_config);
                         }
                         self.close_element();
-                        self.debug_open(&ElementDeclaration::default());
+                        self.debug_open(ElementDeclaration::default());
                         {
                             self.debug_text("flip_x: ", info_text_config);
                             self.debug_text(if vr.flip_x { "true" } else { "false" }, info_text_config);
@@ -9346,7 +11913,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     for (i, effect) in effects.iter().enumerate() {
                         let label = format!("Effect {}", i + 1);
                         self.debug_text("Effect", info_title_config);
-                        self.debug_open(&ElementDeclaration::default());
+                        self.debug_open(ElementDeclaration::default());
                         {
                             self.debug_raw_text(&label, info_text_config);
                             self.debug_text(": ", info_text_config);
@@ -9354,7 +11921,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         }
                         self.close_element();
                         for uniform in &effect.uniforms {
-                            self.debug_open(&ElementDeclaration::default());
+                            self.debug_open(ElementDeclaration::default());
                             {
                                 self.debug_text("  ", info_text_config);
                                 self.debug_raw_text(&uniform.name, info_text_config);
@@ -9367,7 +11934,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                     for (i, shader) in shaders.iter().enumerate() {
                         let label = format!("Shader {}", i + 1);
                         self.debug_text("Shader", info_title_config);
-                        self.debug_open(&ElementDeclaration::default());
+                        self.debug_open(ElementDeclaration::default());
                         {
                             self.debug_raw_text(&label, info_text_config);
                             self.debug_text(": ", info_text_config);
@@ -9375,7 +11942,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
                         }
                         self.close_element();
                         for uniform in &shader.uniforms {
-                            self.debug_open(&ElementDeclaration::default());
+                            self.debug_open(ElementDeclaration::default());
                             {
                                 self.debug_text("  ", info_text_config);
                                 self.debug_raw_text(&uniform.name, info_text_config);
@@ -9405,6 +11972,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
             AlignY::Top => "TOP",
             AlignY::CenterY => "CENTER",
             AlignY::Bottom => "BOTTOM",
+            AlignY::Baseline => "BASELINE",
         }
     }
 
@@ -9428,16 +11996,51 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> PlyContext<CustomElem
         self.debug_mode_enabled
     }
 
+    /// Starts or stops picking: while on, hovering the UI highlights the
+    /// element under the pointer and clicking selects it in the debug view.
+    pub fn set_debug_picking(&mut self, enabled: bool) {
+        self.debug_picking = enabled;
+        self.debug_hovered_element_id = 0;
+    }
+
+    pub fn is_debug_picking(&self) -> bool {
+        self.debug_picking
+    }
+
     pub fn set_culling_enabled(&mut self, enabled: bool) {
         self.culling_disabled = !enabled;
     }
 
     pub fn set_measure_text_function(
         &mut self,
-        f: Box<dyn Fn(&str, &TextConfig) -> Dimensions>,
+        f: MeasureTextFn,
     ) {
         self.measure_text_fn = Some(f);
-        // Invalidate the font height cache since the measurement function changed.
+        #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+        {
+            self.parallel_measure_text_fn = None;
+        }
+        // Invalidate the caches since the measurement function changed.
+        self.font_height_cache.clear();
+        self.clear_text_cache();
+        self.layout_snapshot = None;
+    }
+
+    /// Like `set_measure_text_function`, but lets text wrap on several threads.
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    pub fn set_parallel_measure_text_function(
+        &mut self,
+        f: SharedMeasureTextFn,
+    ) {
+        let shared = f.clone();
+        self.measure_text_fn = Some(Box::new(move |text, config| shared(text, config)));
+        self.parallel_measure_text_fn = Some(f);
         self.font_height_cache.clear();
+        self.clear_text_cache();
+        self.layout_snapshot = None;
+    }
+
+    pub fn set_text_baseline_function(&mut self, f: Box<dyn Fn(&TextConfig) -> f32>) {
+        self.text_baseline_fn = Some(f);
     }
 }
@@ -0,0 +1,327 @@
+//! Input from event-driven windowing (winit, Bevy), gathered between frames
+//! and applied when the next one begins, the way macroquad's is polled.
+
+use macroquad::prelude::KeyCode;
+
+use crate::engine::{ArrowDirection, PlyContext, TextInputAction};
+use crate::math::Vector2;
+use crate::{Ply, PointerInput};
+
+/// Pixels scrolled per wheel notch, as with macroquad on native.
+pub(crate) const LINE_HEIGHT: f32 = 20.0;
+
+/// Maps a windowing library's physical key codes, named like winit's, to the
+/// macroquad keys Ply's input handling and activation keys use.
+macro_rules! map_key_code {
+    ($code:expr, $from:ident) => {
+        match $code {
+            $from::KeyA => Some(macroquad::prelude::KeyCode::A),
+            $from::KeyB => Some(macroquad::prelude::KeyCode::B),
+            $from::KeyC => Some(macroquad::prelude::KeyCode::C),
+            $from::KeyD => Some(macroquad::prelude::KeyCode::D),
+            $from::KeyE => Some(macroquad::prelude::KeyCode::E),
+            $from::KeyF => Some(macroquad::prelude::KeyCode::F),
+            $from::KeyG => Some(macroquad::prelude::KeyCode::G),
+            $from::KeyH => Some(macroquad::prelude::KeyCode::H),
+            $from::KeyI => Some(macroquad::prelude::KeyCode::I),
+            $from::KeyJ => Some(macroquad::prelude::KeyCode::J),
+            $from::KeyK => Some(macroquad::prelude::KeyCode::K),
+            $from::KeyL => Some(macroquad::prelude::KeyCode::L),
+            $from::KeyM => Some(macroquad::prelude::KeyCode::M),
+            $from::KeyN => Some(macroquad::prelude::KeyCode::N),
+            $from::KeyO => Some(macroquad::prelude::KeyCode::O),
+            $from::KeyP => Some(macroquad::prelude::KeyCode::P),
+            $from::KeyQ => Some(macroquad::prelude::KeyCode::Q),
+            $from::KeyR => Some(macroquad::prelude::KeyCode::R),
+            $from::KeyS => Some(macroquad::prelude::KeyCode::S),
+            $from::KeyT => Some(macroquad::prelude::KeyCode::T),
+            $from::KeyU => Some(macroquad::prelude::KeyCode::U),
+            $from::KeyV => Some(macroquad::prelude::KeyCode::V),
+            $from::KeyW => Some(macroquad::prelude::KeyCode::W),
+            $from::KeyX => Some(macroquad::prelude::KeyCode::X),
+            $from::KeyY => Some(macroquad::prelude::KeyCode::Y),
+            $from::KeyZ => Some(macroquad::prelude::KeyCode::Z),
+            $from::Digit0 => Some(macroquad::prelude::KeyCode::Key0),
+            $from::Digit1 => Some(macroquad::prelude::KeyCode::Key1),
+            $from::Digit2 => Some(macroquad::prelude::KeyCode::Key2),
+            $from::Digit3 => Some(macroquad::prelude::KeyCode::Key3),
+            $from::Digit4 => Some(macroquad::prelude::KeyCode::Key4),
+            $from::Digit5 => Some(macroquad::prelude::KeyCode::Key5),
+            $from::Digit6 => Some(macroquad::prelude::KeyCode::Key6),
+            $from::Digit7 => Some(macroquad::prelude::KeyCode::Key7),
+            $from::Digit8 => Some(macroquad::prelude::KeyCode::Key8),
+            $from::Digit9 => Some(macroquad::prelude::KeyCode::Key9),
+            $from::Space => Some(macroquad::prelude::KeyCode::Space),
+            $from::Enter => Some(macroquad::prelude::KeyCode::Enter),
+            $from::NumpadEnter => Some(macroquad::prelude::KeyCode::KpEnter),
+            $from::Tab => Some(macroquad::prelude::KeyCode::Tab),
+            $from::Escape => Some(macroquad::prelude::KeyCode::Escape),
+            $from::Backspace => Some(macroquad::prelude::KeyCode::Backspace),
+            $from::Delete => Some(macroquad::prelude::KeyCode::Delete),
+            $from::Insert => Some(macroquad::prelude::KeyCode::Insert),
+            $from::Home => Some(macroquad::prelude::KeyCode::Home),
+            $from::End => Some(macroquad::prelude::KeyCode::End),
+            $from::PageUp => Some(macroquad::prelude::KeyCode::PageUp),
+            $from::PageDown => Some(macroquad::prelude::KeyCode::PageDown),
+            $from::ArrowLeft => Some(macroquad::prelude::KeyCode::Left),
+            $from::ArrowRight => Some(macroquad::prelude::KeyCode::Right),
+            $from::ArrowUp => Some(macroquad::prelude::KeyCode::Up),
+            $from::ArrowDown => Some(macroquad::prelude::KeyCode::Down),
+            $from::F1 => Some(macroquad::prelude::KeyCode::F1),
+            $from::F2 => Some(macroquad::prelude::KeyCode::F2),
+            $from::F3 => Some(macroquad::prelude::KeyCode::F3),
+            $from::F4 => Some(macroquad::prelude::KeyCode::F4),
+            $from::F5 => Some(macroquad::prelude::KeyCode::F5),
+            $from::F6 => Some(macroquad::prelude::KeyCode::F6),
+            $from::F7 => Some(macroquad::prelude::KeyCode::F7),
+            $from::F8 => Some(macroquad::prelude::KeyCode::F8),
+            $from::F9 => Some(macroquad::prelude::KeyCode::F9),
+            $from::F10 => Some(macroquad::prelude::KeyCode::F10),
+            $from::F11 => Some(macroquad::prelude::KeyCode::F11),
+            $from::F12 => Some(macroquad::prelude::KeyCode::F12),
+            _ => None,
+        }
+    };
+}
+pub(crate) use map_key_code;
+
+/// Pointer, scroll and key state since the last frame.
+pub(crate) struct EventInput {
+    pub(crate) shift: bool,
+    pub(crate) ctrl: bool,
+    pub(crate) alt: bool,
+    /// Logical pointer position.
+    pub(crate) pointer: Vector2,
+    /// The finger standing in for the pointer.
+    pub(crate) touch: Option<u64>,
+    /// While on, typed text arrives as IME commits instead of key events.
+    pub(crate) ime_enabled: bool,
+    pub(crate) preedit: String,
+    /// Text copied or cut, waiting for the app to put it on the clipboard.
+    pub(crate) copied: Option<String>,
+    pointer_down: bool,
+    pressed: bool,
+    released: bool,
+    scroll: Vector2,
+    activate_pressed: bool,
+    activate_released: bool,
+    escape_pressed: bool,
+}
+
+impl EventInput {
+    pub(crate) fn new() -> Self {
+        Self {
+            shift: false,
+            ctrl: false,
+            alt: false,
+            pointer: Vector2::new(-1.0, -1.0),
+            touch: None,
+            ime_enabled: false,
+            preedit: String::new(),
+            copied: None,
+            pointer_down: false,
+            pressed: false,
+            released: false,
+            scroll: Vector2::new(0.0, 0.0),
+            activate_pressed: false,
+            activate_released: false,
+            escape_pressed: false,
+        }
+    }
+
+    pub(crate) fn set_pointer_down(&mut self, down: bool) {
+        if down && !self.pointer_down {
+            self.pressed = true;
+        } else if !down && self.pointer_down {
+            self.released = true;
+        }
+        self.pointer_down = down;
+    }
+
+    /// Adds wheel movement in pixels.
+    pub(crate) fn scroll_by(&mut self, delta: Vector2) {
+        self.scroll = Vector2::new(self.scroll.x + delta.x, self.scroll.y + delta.y);
+    }
+
+    /// Feeds what was gathered into `ply`, right before `begin()`.
+    pub(crate) fn apply<CustomElementData: Clone + Default + std::fmt::Debug>(&mut self, ply: &mut Ply<CustomElementData>) {
+        if !ply.context.is_text_input_focused() {
+            ply.context.handle_keyboard_activation(self.activate_pressed, self.activate_released);
+        }
+        ply.shift_down = self.shift;
+        ply.escape_pressed = self.escape_pressed;
+        ply.apply_pointer_input(PointerInput {
+            position: self.pointer,
+            is_down: self.pointer_down,
+            pressed: self.pressed,
+            released: self.released,
+            scroll: self.scroll,
+            shift: self.shift,
+            touch: self.touch.is_some(),
+        });
+        self.pressed = false;
+        self.released = false;
+        self.scroll = Vector2::new(0.0, 0.0);
+        self.activate_pressed = false;
+        self.activate_released = false;
+        self.escape_pressed = false;
+    }
+
+    /// Typed text, into the focused text input.
+    pub(crate) fn type_text<CustomElementData: Clone + Default + std::fmt::Debug>(
+        &self,
+        ply: &mut Ply<CustomElementData>,
+        text: &str,
+    ) {
+        let context = &mut ply.context;
+        if !context.is_text_input_focused() {
+            return;
+        }
+        for ch in text.chars().filter(|ch| !ch.is_control()) {
+            context.process_text_input_char(ch);
+        }
+        context.update_text_input_scroll();
+    }
+
+    /// Typed text from a key event, unless the IME delivers it or it's a
+    /// Ctrl shortcut. AltGr arrives as Ctrl+Alt on some platforms.
+    pub(crate) fn type_key_text<CustomElementData: Clone + Default + std::fmt::Debug>(
+        &self,
+        ply: &mut Ply<CustomElementData>,
+        text: &str,
+    ) {
+        if !self.ime_enabled && (!self.ctrl || self.alt) {
+            self.type_text(ply, text);
+        }
+    }
+
+    pub(crate) fn paste<CustomElementData: Clone + Default + std::fmt::Debug>(ply: &mut Ply<CustomElementData>, text: &str) {
+        let context = &mut ply.context;
+        if context.is_text_input_focused() {
+            context.process_text_input_action(TextInputAction::Paste { text: text.to_string() });
+            context.update_text_input_scroll();
+        }
+    }
+
+    pub(crate) fn release_key<CustomElementData: Clone + Default + std::fmt::Debug>(
+        &mut self,
+        ply: &Ply<CustomElementData>,
+        key: KeyCode,
+    ) {
+        if ply.context.activation_keys(ply.context.focused_element_id).contains(&key) {
+            self.activate_released = true;
+        }
+    }
+
+    /// Handles a key press like macroquad's input does: Tab cycles focus,
+    /// text inputs take editing keys and shortcuts, arrows move focus and
+    /// activation keys activate. Held keys repeat only for editing.
+    pub(crate) fn press_key<CustomElementData: Clone + Default + std::fmt::Debug>(
+        &mut self,
+        ply: &mut Ply<CustomElementData>,
+        key: KeyCode,
+        repeat: bool,
+    ) {
+        let (shift, ctrl) = (self.shift, self.ctrl);
+        if key == KeyCode::Escape && !repeat {
+            self.escape_pressed = true;
+        }
+        let context = &mut ply.context;
+        if key == KeyCode::Tab {
+            context.cycle_focus(shift);
+            return;
+        }
+
+        if !context.is_text_input_focused() {
+            match key {
+                KeyCode::Left => context.arrow_focus(ArrowDirection::Left),
+                KeyCode::Right => context.arrow_focus(ArrowDirection::Right),
+                KeyCode::Up => context.arrow_focus(ArrowDirection::Up),
+                KeyCode::Down => context.arrow_focus(ArrowDirection::Down),
+                KeyCode::C if ctrl => {
+                    if let Some(selected) = context.selected_static_text() {
+                        self.copied = Some(selected);
+                    }
+                }
+                _ if !repeat && context.activation_keys(context.focused_element_id).contains(&key) => {
+                    self.activate_pressed = true;
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let multiline = context.is_focused_text_input_multiline();
+        let numeric = context.is_focused_text_input_numeric();
+        let action = match key {
+            KeyCode::Left if ctrl => Some(TextInputAction::MoveWordLeft { shift }),
+            KeyCode::Left => Some(TextInputAction::MoveLeft { shift }),
+            KeyCode::Right if ctrl => Some(TextInputAction::MoveWordRight { shift }),
+            KeyCode::Right => Some(TextInputAction::MoveRight { shift }),
+            KeyCode::Backspace if ctrl => Some(TextInputAction::BackspaceWord),
+            KeyCode::Backspace => Some(TextInputAction::Backspace),
+            KeyCode::Delete if ctrl => Some(TextInputAction::DeleteWord),
+            KeyCode::Delete => Some(TextInputAction::Delete),
+            KeyCode::Home => Some(TextInputAction::MoveHome { shift }),
+            KeyCode::End => Some(TextInputAction::MoveEnd { shift }),
+            KeyCode::Up if multiline => Some(TextInputAction::MoveUp { shift }),
+            KeyCode::Down if multiline => Some(TextInputAction::MoveDown { shift }),
+            KeyCode::Up if numeric => Some(TextInputAction::Increment),
+            KeyCode::Down if numeric => Some(TextInputAction::Decrement),
+            _ if repeat => None,
+            // Other single-line inputs follow their `focus_up` / `focus_down` overrides
+            KeyCode::Up => {
+                context.arrow_focus(ArrowDirection::Up);
+                None
+            }
+            KeyCode::Down => {
+                context.arrow_focus(ArrowDirection::Down);
+                None
+            }
+            KeyCode::Enter | KeyCode::KpEnter => Some(TextInputAction::Submit),
+            KeyCode::A if ctrl => Some(TextInputAction::SelectAll),
+            KeyCode::Z if ctrl && shift => Some(TextInputAction::Redo),
+            KeyCode::Z if ctrl => Some(TextInputAction::Undo),
+            KeyCode::Y if ctrl => Some(TextInputAction::Redo),
+            #[cfg(feature = "text-styling")]
+            KeyCode::B if ctrl => Some(TextInputAction::ToggleStyle { tag: "bold".to_string() }),
+            #[cfg(feature = "text-styling")]
+            KeyCode::I if ctrl => Some(TextInputAction::ToggleStyle { tag: "italic".to_string() }),
+            KeyCode::C if ctrl => {
+                if let Some(selected) = text_input_selection(context) {
+                    self.copied = Some(selected);
+                }
+                None
+            }
+            KeyCode::X if ctrl => {
+                if let Some(selected) = text_input_selection(context) {
+                    self.copied = Some(selected);
+                }
+                Some(TextInputAction::Cut)
+            }
+            // Escape unfocuses the text input, or clears it first with `clear_on_escape`
+            KeyCode::Escape if context.should_focused_text_input_clear_on_escape() => Some(TextInputAction::Clear),
+            KeyCode::Escape => {
+                context.clear_focus();
+                None
+            }
+            _ => None,
+        };
+        if let Some(action) = action {
+            context.process_text_input_action(action);
+            context.update_text_input_scroll();
+        }
+    }
+}
+
+/// The focused text input's selection, with style tags when styled.
+fn text_input_selection<CustomElementData: Clone + Default + std::fmt::Debug>(
+    context: &PlyContext<CustomElementData>,
+) -> Option<String> {
+    let state = context.text_edit_states.get(&context.focused_element_id)?;
+    #[cfg(feature = "text-styling")]
+    let selected = state.selected_text_styled();
+    #[cfg(not(feature = "text-styling"))]
+    let selected = state.selected_text().to_string();
+    (!selected.is_empty()).then_some(selected)
+}
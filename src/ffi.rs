@@ -0,0 +1,705 @@
+//! A C API over the layout engine, for embedding Ply's layout in C, C++ or
+//! any language with a C FFI, the way Clay is embedded.
+//!
+//! Build a library with `cargo rustc --release --features ffi --crate-type staticlib`
+//! (or `cdylib`) and include `include/ply.h`. A frame looks like:
+//!
+//! ```c
+//! PlyContext *ply = ply_create((PlyDimensions){ 800, 600 });
+//! ply_set_measure_text_function(ply, measure_text, fonts);
+//!
+//! ply_set_pointer_state(ply, mouse_position, mouse_down);
+//! ply_update_scroll_containers(ply, true, wheel_delta, delta_time);
+//! ply_begin_layout(ply);
+//! ply_open_element_with_id(ply, PLY_STRING("sidebar"));
+//! ply_configure_open_element(ply, &(PlyElementDeclaration){ .background_color = { 40, 40, 40, 255 } });
+//! ply_open_text_element(ply, PLY_STRING("Hello"), &(PlyTextConfig){ .font_size = 16, .color = { 255, 255, 255, 255 } });
+//! ply_close_element(ply);
+//! PlyRenderCommandArray commands = ply_end_layout(ply);
+//! ```
+//!
+//! Declarations are plain structs that are meant to be zero-initialized:
+//! zero means fit sizing, no padding, a transparent background and so on,
+//! and a `max` of zero means unbounded. Enums are `uint8_t`s, and values out
+//! of range fall back to the zero variant. Render commands and their text stay
+//! valid until the next `ply_begin_layout`. Colors are 0-255 per channel.
+//!
+//! Images, canvases, shaders, text inputs and callbacks are Rust-only.
+
+use std::borrow::Cow;
+use std::ffi::{c_char, c_void};
+
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::elements::{FloatingAttachToElement, FloatingClipToElement, PointerCaptureMode};
+use crate::engine::{
+    self, BorderConfig, BorderWidth, ChildAlignmentConfig, ClipConfig, ElementDeclaration, FloatingAttachPoints,
+    FloatingConfig, InternalRenderData, LayoutConfig, PaddingConfig, PlyContext, RenderCommandType, SizingAxis,
+    SizingConfig, SizingMinMax, SizingType,
+};
+use crate::id::Id;
+use crate::layout::{CornerRadius, LayoutDirection};
+use crate::math::{BoundingBox, Dimensions, Vector2};
+use crate::text::{TextConfig, WrapMode};
+
+/// UTF-8 text that isn't null-terminated.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlyString {
+    pub length: usize,
+    pub chars: *const c_char,
+}
+
+impl PlyString {
+    fn new(text: &str) -> Self {
+        Self { length: text.len(), chars: text.as_ptr().cast() }
+    }
+
+    /// Invalid UTF-8 is replaced rather than rejected.
+    ///
+    /// # Safety
+    /// `chars` must point to `length` readable bytes, or be null.
+    unsafe fn to_str<'a>(self) -> Cow<'a, str> {
+        if self.chars.is_null() || self.length == 0 {
+            return Cow::Borrowed("");
+        }
+        String::from_utf8_lossy(std::slice::from_raw_parts(self.chars.cast(), self.length))
+    }
+}
+
+/// Converts the enums C passes as `u8`s, in declaration order.
+macro_rules! c_enum {
+    ($($ty:ty => [$($variant:expr),+ $(,)?];)+) => {$(
+        impl TryFrom<u8> for $ty {
+            type Error = u8;
+
+            fn try_from(value: u8) -> Result<Self, u8> {
+                [$($variant),+].get(value as usize).copied().ok_or(value)
+            }
+        }
+    )+};
+}
+
+c_enum! {
+    SizingType => [
+        SizingType::Fit,
+        SizingType::Grow,
+        SizingType::Percent,
+        SizingType::Fixed,
+        SizingType::ViewportWidth,
+        SizingType::ViewportHeight,
+        SizingType::MinContent,
+        SizingType::MaxContent,
+    ];
+    AlignX => [AlignX::Left, AlignX::CenterX, AlignX::Right];
+    AlignY => [AlignY::Top, AlignY::CenterY, AlignY::Bottom, AlignY::Baseline];
+    LayoutDirection => [LayoutDirection::LeftToRight, LayoutDirection::TopToBottom];
+    PointerCaptureMode => [PointerCaptureMode::Capture, PointerCaptureMode::Passthrough];
+    FloatingAttachToElement => [
+        FloatingAttachToElement::None,
+        FloatingAttachToElement::Parent,
+        FloatingAttachToElement::ElementWithId,
+        FloatingAttachToElement::Root,
+        FloatingAttachToElement::Pointer,
+    ];
+    FloatingClipToElement => [FloatingClipToElement::None, FloatingClipToElement::AttachedParent];
+    WrapMode => [WrapMode::Words, WrapMode::Newline, WrapMode::None, WrapMode::Anywhere];
+}
+
+/// A C enum value, or the default variant when it's out of range.
+fn from_c<T: TryFrom<u8> + Default>(value: u8) -> T {
+    T::try_from(value).unwrap_or_default()
+}
+
+/// One axis of an element's size, a `PlySizingType`. For `Fixed`, `min` is
+/// the size; for `Percent` and the viewport types, `percent` is the fraction
+/// of the parent or layout from 0 to 1.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlySizingAxis {
+    pub type_: u8,
+    pub min: f32,
+    /// Zero or less means unbounded.
+    pub max: f32,
+    pub percent: f32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlyPadding {
+    pub left: u16,
+    pub right: u16,
+    pub top: u16,
+    pub bottom: u16,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlyLayoutConfig {
+    pub width: PlySizingAxis,
+    pub height: PlySizingAxis,
+    pub padding: PlyPadding,
+    pub child_gap: u16,
+    pub child_align_x: u8,
+    pub child_align_y: u8,
+    pub direction: u8,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlyCornerRadius {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_left: f32,
+    pub bottom_right: f32,
+}
+
+/// Borders are drawn outside the bounding box.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlyBorder {
+    pub color: Color,
+    pub left: u16,
+    pub right: u16,
+    pub top: u16,
+    pub bottom: u16,
+    pub between_children: u16,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlyClip {
+    pub horizontal: bool,
+    pub vertical: bool,
+    /// Usually the scroll offset from `ply_get_scroll_offset`.
+    pub child_offset: Vector2,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlyFloating {
+    pub offset: Vector2,
+    /// Element to attach to with `FloatingAttachToElement::ElementWithId`.
+    pub parent_id: u32,
+    pub z_index: i16,
+    pub element_x: u8,
+    pub element_y: u8,
+    pub parent_x: u8,
+    pub parent_y: u8,
+    pub pointer_capture_mode: u8,
+    pub attach_to: u8,
+    pub clip_to: u8,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlyElementDeclaration {
+    pub layout: PlyLayoutConfig,
+    pub background_color: Color,
+    pub corner_radius: PlyCornerRadius,
+    /// Width over height, or zero for none.
+    pub aspect_ratio: f32,
+    pub floating: PlyFloating,
+    pub clip: PlyClip,
+    pub border: PlyBorder,
+    /// Handed back on the element's render commands.
+    pub user_data: usize,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlyTextConfig {
+    /// Handed to the measure function and back on the text's render
+    /// commands, e.g. a font ID.
+    pub user_data: usize,
+    pub color: Color,
+    pub font_size: u16,
+    pub letter_spacing: u16,
+    /// Zero means the font's own line height.
+    pub line_height: u16,
+    pub wrap_mode: u8,
+    pub alignment: u8,
+    pub justify: bool,
+}
+
+/// A render command. Which fields are set depends on `command_type`:
+/// `color` is the background, border or text color, `text` and the font
+/// fields are for text, `border_width` for borders.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlyRenderCommand {
+    pub bounding_box: BoundingBox,
+    pub command_type: RenderCommandType,
+    pub z_index: i16,
+    pub id: u32,
+    pub user_data: usize,
+    pub color: Color,
+    pub corner_radius: PlyCornerRadius,
+    pub border_width: PlyPadding,
+    pub text: PlyString,
+    pub font_size: u16,
+    pub letter_spacing: u16,
+    pub line_height: u16,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlyRenderCommandArray {
+    pub length: usize,
+    pub commands: *const PlyRenderCommand,
+}
+
+/// Measures `text` as drawn with `config`, given back the `user_data` it was set with.
+pub type PlyMeasureTextFunction =
+    unsafe extern "C" fn(text: PlyString, config: *const PlyTextConfig, user_data: *mut c_void) -> Dimensions;
+
+/// The layout engine and the last frame's converted render commands.
+pub struct FfiContext {
+    context: PlyContext,
+    commands: Vec<PlyRenderCommand>,
+}
+
+/// Creates a context laying out at `dimensions`. Free it with `ply_destroy`.
+#[no_mangle]
+pub extern "C" fn ply_create(dimensions: Dimensions) -> *mut FfiContext {
+    Box::into_raw(Box::new(FfiContext { context: PlyContext::new(dimensions), commands: Vec::new() }))
+}
+
+/// # Safety
+/// `ply` must come from `ply_create` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ply_destroy(ply: *mut FfiContext) {
+    if !ply.is_null() {
+        drop(Box::from_raw(ply));
+    }
+}
+
+/// # Safety
+/// `ply` must be a live context from `ply_create`.
+#[no_mangle]
+pub unsafe extern "C" fn ply_set_layout_dimensions(ply: *mut FfiContext, dimensions: Dimensions) {
+    (*ply).context.set_layout_dimensions(dimensions);
+}
+
+/// Sets how text is measured. `user_data` is passed to every call.
+///
+/// # Safety
+/// `ply` must be a live context from `ply_create`, and `measure` must be
+/// safe to call with `user_data` for as long as the context lives.
+#[no_mangle]
+pub unsafe extern "C" fn ply_set_measure_text_function(
+    ply: *mut FfiContext,
+    measure: PlyMeasureTextFunction,
+    user_data: *mut c_void,
+) {
+    (*ply).context.set_measure_text_function(Box::new(move |text: &str, config: &TextConfig| {
+        let config = PlyTextConfig {
+            user_data: config.user_data,
+            color: config.color,
+            font_size: config.font_size,
+            letter_spacing: config.letter_spacing,
+            line_height: config.line_height,
+            wrap_mode: config.wrap_mode as u8,
+            alignment: config.alignment as u8,
+            justify: config.justify,
+        };
+        measure(PlyString::new(text), &config, user_data)
+    }));
+}
+
+/// Updates what the pointer is over and pressing, before `ply_begin_layout`.
+///
+/// # Safety
+/// `ply` must be a live context from `ply_create`.
+#[no_mangle]
+pub unsafe extern "C" fn ply_set_pointer_state(ply: *mut FfiContext, position: Vector2, is_down: bool) {
+    (*ply).context.set_pointer_state(position, is_down);
+}
+
+/// Scrolls the container under the pointer by `scroll_delta` pixels, and
+/// drags scroll containers with the pointer when `enable_drag_scrolling`.
+///
+/// # Safety
+/// `ply` must be a live context from `ply_create`.
+#[no_mangle]
+pub unsafe extern "C" fn ply_update_scroll_containers(
+    ply: *mut FfiContext,
+    enable_drag_scrolling: bool,
+    scroll_delta: Vector2,
+    delta_time: f32,
+) {
+    (*ply).context.update_scroll_containers(enable_drag_scrolling, scroll_delta, delta_time, false);
+}
+
+/// # Safety
+/// `ply` must be a live context from `ply_create`.
+#[no_mangle]
+pub unsafe extern "C" fn ply_begin_layout(ply: *mut FfiContext) {
+    (*ply).context.begin_layout();
+}
+
+/// Opens an element without an ID. Close it with `ply_close_element`.
+///
+/// # Safety
+/// `ply` must be a live context from `ply_create`.
+#[no_mangle]
+pub unsafe extern "C" fn ply_open_element(ply: *mut FfiContext) {
+    (*ply).context.open_element();
+}
+
+/// Opens an element with the ID `ply_get_element_id(label)`.
+///
+/// # Safety
+/// `ply` must be a live context from `ply_create`, and `label` valid.
+#[no_mangle]
+pub unsafe extern "C" fn ply_open_element_with_id(ply: *mut FfiContext, label: PlyString) {
+    let label = label.to_str();
+    (*ply).context.open_element_with_id(&engine::hash_string_with_offset(&label, 0, 0));
+}
+
+/// Configures the element opened last, before any of its children.
+///
+/// # Safety
+/// `ply` must be a live context from `ply_create`, and `declaration` valid.
+#[no_mangle]
+pub unsafe extern "C" fn ply_configure_open_element(ply: *mut FfiContext, declaration: *const PlyElementDeclaration) {
+    (*ply).context.configure_open_element(element_declaration(&*declaration));
+}
+
+/// # Safety
+/// `ply` must be a live context from `ply_create`.
+#[no_mangle]
+pub unsafe extern "C" fn ply_close_element(ply: *mut FfiContext) {
+    (*ply).context.close_element();
+}
+
+/// Adds a text element to the open element.
+///
+/// # Safety
+/// `ply` must be a live context from `ply_create`, and `text` and `config` valid.
+#[no_mangle]
+pub unsafe extern "C" fn ply_open_text_element(ply: *mut FfiContext, text: PlyString, config: *const PlyTextConfig) {
+    let config = &*config;
+    let text_config = TextConfig {
+        user_data: config.user_data,
+        color: config.color,
+        font_size: config.font_size,
+        letter_spacing: config.letter_spacing,
+        line_height: config.line_height,
+        wrap_mode: from_c(config.wrap_mode),
+        alignment: from_c(config.alignment),
+        justify: config.justify,
+        ..TextConfig::new()
+    };
+    let context = &mut (*ply).context;
+    let index = context.store_text_element_config(text_config);
+    context.open_text_element(&text.to_str(), index);
+}
+
+/// Lays out what was declared since `ply_begin_layout` and returns its
+/// render commands, valid until the next `ply_begin_layout`.
+///
+/// # Safety
+/// `ply` must be a live context from `ply_create`.
+#[no_mangle]
+pub unsafe extern "C" fn ply_end_layout(ply: *mut FfiContext) -> PlyRenderCommandArray {
+    let FfiContext { context, commands } = &mut *ply;
+    commands.clear();
+    commands.extend(context.end_layout().iter().map(render_command));
+    PlyRenderCommandArray { length: commands.len(), commands: commands.as_ptr() }
+}
+
+/// The ID an element opened with `ply_open_element_with_id(label)` gets.
+///
+/// # Safety
+/// `label` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn ply_get_element_id(label: PlyString) -> u32 {
+    engine::hash_string_with_offset(&label.to_str(), 0, 0).id
+}
+
+/// Whether the pointer is over the element, as of the last `ply_set_pointer_state`.
+///
+/// # Safety
+/// `ply` must be a live context from `ply_create`.
+#[no_mangle]
+pub unsafe extern "C" fn ply_pointer_over(ply: *const FfiContext, id: u32) -> bool {
+    (*ply).context.pointer_over(Id { id, ..Default::default() })
+}
+
+/// Writes the element's bounding box from the last layout into `out`.
+/// Returns `false`, leaving `out` alone, if the element wasn't laid out.
+///
+/// # Safety
+/// `ply` must be a live context from `ply_create`, and `out` writable.
+#[no_mangle]
+pub unsafe extern "C" fn ply_get_element_bounding_box(ply: *const FfiContext, id: u32, out: *mut BoundingBox) -> bool {
+    match (*ply).context.get_element_data(Id { id, ..Default::default() }) {
+        Some(bounding_box) => {
+            *out = bounding_box;
+            true
+        }
+        None => false,
+    }
+}
+
+/// The scroll offset of the open scroll container, for its `child_offset`.
+///
+/// # Safety
+/// `ply` must be a live context from `ply_create`.
+#[no_mangle]
+pub unsafe extern "C" fn ply_get_scroll_offset(ply: *const FfiContext) -> Vector2 {
+    (*ply).context.get_scroll_offset()
+}
+
+fn sizing_axis(axis: &PlySizingAxis) -> SizingAxis {
+    let type_ = from_c(axis.type_);
+    let (min, max) = match type_ {
+        SizingType::Fixed => (axis.min, axis.min),
+        // Resolved to a fixed size from `percent` when declared
+        SizingType::ViewportWidth | SizingType::ViewportHeight => (0.0, 0.0),
+        // Resolved to a fixed size from the children when closed
+        SizingType::MinContent | SizingType::MaxContent => (0.0, f32::MAX),
+        _ if axis.max <= 0.0 => (axis.min, f32::MAX),
+        _ => (axis.min, axis.max),
+    };
+    SizingAxis { type_, min_max: SizingMinMax { min, max }, percent: axis.percent, grow_weight: 1.0 }
+}
+
+fn corner_radius(radius: &PlyCornerRadius) -> CornerRadius {
+    CornerRadius {
+        top_left: radius.top_left,
+        top_right: radius.top_right,
+        bottom_left: radius.bottom_left,
+        bottom_right: radius.bottom_right,
+    }
+}
+
+fn element_declaration(declaration: &PlyElementDeclaration) -> ElementDeclaration {
+    let layout = &declaration.layout;
+    let floating = &declaration.floating;
+    let border = &declaration.border;
+    ElementDeclaration {
+        layout: LayoutConfig {
+            sizing: SizingConfig { width: sizing_axis(&layout.width), height: sizing_axis(&layout.height) },
+            padding: PaddingConfig {
+                left: layout.padding.left,
+                right: layout.padding.right,
+                top: layout.padding.top,
+                bottom: layout.padding.bottom,
+            },
+            child_gap: layout.child_gap,
+            child_alignment: ChildAlignmentConfig { x: from_c(layout.child_align_x), y: from_c(layout.child_align_y) },
+            layout_direction: from_c(layout.direction),
+            ..Default::default()
+        },
+        background_color: declaration.background_color,
+        corner_radius: corner_radius(&declaration.corner_radius),
+        aspect_ratio: declaration.aspect_ratio,
+        floating: FloatingConfig {
+            offset: floating.offset,
+            parent_id: floating.parent_id,
+            z_index: floating.z_index,
+            attach_points: FloatingAttachPoints {
+                element_x: from_c(floating.element_x),
+                element_y: from_c(floating.element_y),
+                parent_x: from_c(floating.parent_x),
+                parent_y: from_c(floating.parent_y),
+            },
+            pointer_capture_mode: from_c(floating.pointer_capture_mode),
+            attach_to: from_c(floating.attach_to),
+            clip_to: from_c(floating.clip_to),
+        },
+        clip: ClipConfig {
+            horizontal: declaration.clip.horizontal,
+            vertical: declaration.clip.vertical,
+            child_offset: declaration.clip.child_offset,
+            ..Default::default()
+        },
+        border: BorderConfig {
+            color: border.color,
+            width: BorderWidth {
+                left: border.left,
+                right: border.right,
+                top: border.top,
+                bottom: border.bottom,
+                between_children: border.between_children,
+            },
+            ..Default::default()
+        },
+        user_data: declaration.user_data,
+        ..Default::default()
+    }
+}
+
+fn render_command(command: &engine::InternalRenderCommand) -> PlyRenderCommand {
+    let mut converted = PlyRenderCommand {
+        bounding_box: command.bounding_box,
+        command_type: command.command_type,
+        z_index: command.z_index,
+        id: command.id,
+        user_data: command.user_data,
+        color: Color::rgba(0.0, 0.0, 0.0, 0.0),
+        corner_radius: PlyCornerRadius { top_left: 0.0, top_right: 0.0, bottom_left: 0.0, bottom_right: 0.0 },
+        border_width: PlyPadding { left: 0, right: 0, top: 0, bottom: 0 },
+        text: PlyString::new(""),
+        font_size: 0,
+        letter_spacing: 0,
+        line_height: 0,
+    };
+    let radius = |radius: &CornerRadius| PlyCornerRadius {
+        top_left: radius.top_left,
+        top_right: radius.top_right,
+        bottom_left: radius.bottom_left,
+        bottom_right: radius.bottom_right,
+    };
+    match &command.render_data {
+        InternalRenderData::Rectangle { background_color, corner_radius }
+        | InternalRenderData::Image { background_color, corner_radius, .. }
+        | InternalRenderData::Custom { background_color, corner_radius, .. } => {
+            converted.color = *background_color;
+            converted.corner_radius = radius(corner_radius);
+        }
+        InternalRenderData::Text { text, text_color, font_size, letter_spacing, line_height, .. } => {
+            converted.color = *text_color;
+            converted.text = PlyString::new(text);
+            converted.font_size = *font_size;
+            converted.letter_spacing = *letter_spacing;
+            converted.line_height = *line_height;
+        }
+        InternalRenderData::Border { color, corner_radius, width, .. } => {
+            converted.color = *color;
+            converted.corner_radius = radius(corner_radius);
+            converted.border_width = PlyPadding { left: width.left, right: width.right, top: width.top, bottom: width.bottom };
+        }
+        _ => {}
+    }
+    converted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn measure(text: PlyString, config: *const PlyTextConfig, _user_data: *mut c_void) -> Dimensions {
+        Dimensions::new(text.length as f32 * 10.0, (*config).font_size as f32)
+    }
+
+    /// Measures with `user_data` as the font ID: font 2 is twice as wide as font 1.
+    unsafe extern "C" fn measure_by_font(text: PlyString, config: *const PlyTextConfig, _user_data: *mut c_void) -> Dimensions {
+        Dimensions::new(text.length as f32 * 10.0 * (*config).user_data as f32, (*config).font_size as f32)
+    }
+
+    fn fixed(size: f32) -> PlySizingAxis {
+        PlySizingAxis { type_: SizingType::Fixed as u8, min: size, max: 0.0, percent: 0.0 }
+    }
+
+    #[test]
+    fn test_ffi_lays_out_declared_elements() {
+        // Zero-initialized like a C `(PlyElementDeclaration){ 0 }`
+        let zeroed: PlyElementDeclaration = unsafe { std::mem::zeroed() };
+        let text_config = PlyTextConfig { font_size: 16, ..unsafe { std::mem::zeroed() } };
+        unsafe {
+            let ply = ply_create(Dimensions::new(400.0, 300.0));
+            ply_set_measure_text_function(ply, measure, std::ptr::null_mut());
+            ply_begin_layout(ply);
+            ply_open_element_with_id(ply, PlyString::new("panel"));
+            ply_configure_open_element(
+                ply,
+                &PlyElementDeclaration {
+                    layout: PlyLayoutConfig {
+                        padding: PlyPadding { left: 8, right: 8, top: 8, bottom: 8 },
+                        ..zeroed.layout
+                    },
+                    background_color: Color::rgb(40.0, 40.0, 40.0),
+                    ..zeroed
+                },
+            );
+            ply_open_element(ply);
+            ply_configure_open_element(
+                ply,
+                &PlyElementDeclaration {
+                    layout: PlyLayoutConfig { width: fixed(100.0), height: fixed(50.0), ..zeroed.layout },
+                    background_color: Color::rgb(200.0, 0.0, 0.0),
+                    ..zeroed
+                },
+            );
+            ply_close_element(ply);
+            ply_open_text_element(ply, PlyString::new("Hello"), &text_config);
+            ply_close_element(ply);
+            let array = ply_end_layout(ply);
+
+            let commands = std::slice::from_raw_parts(array.commands, array.length);
+            let text = commands.iter().find(|c| c.command_type == RenderCommandType::Text).unwrap();
+            assert_eq!(text.text.to_str(), "Hello");
+            assert_eq!(text.font_size, 16);
+            assert_eq!(text.bounding_box.x, 108.0);
+
+            // Fit sizing with a zero max is unbounded
+            let mut panel = BoundingBox::default();
+            assert!(ply_get_element_bounding_box(ply, ply_get_element_id(PlyString::new("panel")), &mut panel));
+            assert_eq!((panel.width, panel.height), (166.0, 66.0));
+            assert_eq!(commands.iter().filter(|c| c.command_type == RenderCommandType::Rectangle).count(), 2);
+            ply_destroy(ply);
+        }
+    }
+
+    #[test]
+    fn test_ffi_measures_text_per_user_data() {
+        let zeroed: PlyElementDeclaration = unsafe { std::mem::zeroed() };
+        let font = |user_data: usize| PlyTextConfig { user_data, font_size: 16, ..unsafe { std::mem::zeroed() } };
+        unsafe {
+            let ply = ply_create(Dimensions::new(400.0, 300.0));
+            ply_set_measure_text_function(ply, measure_by_font, std::ptr::null_mut());
+            let frame = |fonts: [usize; 2]| {
+                ply_begin_layout(ply);
+                for (name, user_data) in ["first", "second"].into_iter().zip(fonts) {
+                    ply_open_element_with_id(ply, PlyString::new(name));
+                    ply_configure_open_element(ply, &zeroed);
+                    ply_open_text_element(ply, PlyString::new("Hello"), &font(user_data));
+                    ply_close_element(ply);
+                }
+                ply_end_layout(ply);
+                ["first", "second"].map(|name| {
+                    let mut bounds = BoundingBox::default();
+                    ply_get_element_bounding_box(ply, ply_get_element_id(PlyString::new(name)), &mut bounds);
+                    bounds.width
+                })
+            };
+
+            // The same text in another font is measured again, in the same frame or the next
+            assert_eq!(frame([1, 2]), [50.0, 100.0]);
+            assert_eq!(frame([2, 2]), [100.0, 100.0]);
+            ply_destroy(ply);
+        }
+    }
+
+    #[test]
+    fn test_ffi_converts_enum_bytes() {
+        let zeroed: PlyElementDeclaration = unsafe { std::mem::zeroed() };
+        let viewport_width = PlySizingAxis { type_: SizingType::ViewportWidth as u8, min: 0.0, max: 0.0, percent: 0.5 };
+        let out_of_range = PlySizingAxis { type_: 200, ..fixed(10.0) };
+        unsafe {
+            let ply = ply_create(Dimensions::new(400.0, 300.0));
+            ply_begin_layout(ply);
+            ply_open_element_with_id(ply, PlyString::new("half"));
+            ply_configure_open_element(
+                ply,
+                &PlyElementDeclaration {
+                    layout: PlyLayoutConfig { width: viewport_width, height: out_of_range, direction: 7, ..zeroed.layout },
+                    ..zeroed
+                },
+            );
+            ply_close_element(ply);
+            ply_end_layout(ply);
+
+            // The unknown sizing type falls back to fit, at least `min` tall
+            let mut half = BoundingBox::default();
+            assert!(ply_get_element_bounding_box(ply, ply_get_element_id(PlyString::new("half")), &mut half));
+            assert_eq!((half.width, half.height), (200.0, 10.0));
+            ply_destroy(ply);
+        }
+        assert_eq!(AlignY::try_from(3), Ok(AlignY::Baseline));
+        assert_eq!(WrapMode::try_from(4), Err(4));
+    }
+}
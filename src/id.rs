@@ -2,6 +2,7 @@ use crate::engine;
 
 /// Owned string for debug/display purposes.
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StringId {
     text: String,
 }
@@ -30,6 +31,7 @@ impl StringId {
 
 /// A hashed identifier used to uniquely address UI elements across frames.
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Id {
     pub id: u32,
     pub offset: u32,
@@ -54,6 +56,19 @@ impl Id {
     pub fn new_index_seed(label: &'static str, index: u32, seed: u32) -> Id {
         engine::hash_string_with_offset(label, index, seed)
     }
+
+    /// Creates an id scoped to this one, for an element inside a widget.
+    /// The same `label` under different parents gives different ids.
+    #[inline]
+    pub fn child(&self, label: &str) -> Id {
+        engine::hash_string_with_offset(label, 0, self.id)
+    }
+
+    /// Creates an id scoped to this one with an index, for repeated elements.
+    #[inline]
+    pub fn child_index(&self, label: &str, index: u32) -> Id {
+        engine::hash_string_with_offset(label, index, self.id)
+    }
 }
 
 impl From<&'static str> for Id {
@@ -89,6 +104,15 @@ mod tests {
         assert_eq!(a.base_id, b.base_id);
     }
 
+    #[test]
+    fn child_ids_are_scoped_to_parent() {
+        let a = Id::new("list_a").child("row");
+        let b = Id::new("list_b").child("row");
+        assert_ne!(a.id, b.id);
+        assert_eq!(a.id, Id::new("list_a").child("row").id);
+        assert_ne!(a.id, Id::new("list_a").child_index("row", 1).id);
+    }
+
     #[test]
     fn from_tuple_zero_offset_matches_from_str() {
         let a = Id::from(("test", 0));
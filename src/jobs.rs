@@ -54,7 +54,7 @@ static NATIVE_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
 );
 
 #[cfg(not(target_arch = "wasm32"))]
-fn block_on_native<F: Future>(future: F) -> F::Output {
+pub(crate) fn block_on_native<F: Future>(future: F) -> F::Output {
     let thread = std::thread::current();
     let raw_waker = RawWaker::new(
         Box::into_raw(Box::new(thread)) as *const (),
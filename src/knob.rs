@@ -0,0 +1,365 @@
+//! Knobs: rotary controls for a value in a range, as in audio and creative tools.
+//!
+//! ```ignore
+//! ui.knob("cutoff", &mut cutoff, |k| k
+//!     .label("Cutoff")
+//!     .range(20.0, 20_000.0)
+//!     .detents(&[1_000.0])
+//! );
+//! ```
+//!
+//! Dragging up or right turns the knob up; with `.circular()` it follows the
+//! pointer around its center instead. Holding Shift turns it ten times slower
+//! for fine adjustment. While dragging, the value snaps to nearby detents.
+//! When focused, the arrow keys step the value. Clicking a knob focuses it.
+
+use std::f32::consts::PI;
+
+use macroquad::miniquad::CursorIcon;
+
+use crate::accessibility::AccessibilityRole;
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::engine::ArrowDirection;
+use crate::id::Id;
+use crate::layout::Sizing;
+use crate::math::Vector2;
+use crate::Ui;
+
+/// Angle the knob turns through from its minimum to its maximum, in radians.
+const SWEEP: f32 = PI * 1.5;
+/// Share of normal speed while Shift is held.
+const FINE_FACTOR: f32 = 0.1;
+/// How close to a detent, as a share of the range, the value snaps to it.
+const DETENT_SNAP: f32 = 0.02;
+/// Dots in the ring showing the value.
+const RING_DOTS: usize = 21;
+
+/// An in-progress drag.
+#[derive(Debug, Clone, Copy)]
+struct KnobDrag {
+    /// Pointer position last frame.
+    last: Vector2,
+    /// Value before snapping to detents, so a detent doesn't hold the drag.
+    raw: f32,
+}
+
+/// Builder for configuring a knob via closure.
+#[derive(Debug, Clone)]
+pub struct KnobBuilder {
+    pub(crate) label: String,
+    pub(crate) min: f32,
+    pub(crate) max: f32,
+    pub(crate) step: Option<f32>,
+    pub(crate) detents: Vec<f32>,
+    pub(crate) circular: bool,
+    pub(crate) sensitivity: f32,
+    pub(crate) disabled: bool,
+    pub(crate) size: f32,
+    pub(crate) knob_color: Color,
+    pub(crate) indicator_color: Color,
+    pub(crate) track_color: Color,
+    pub(crate) value_color: Color,
+}
+
+impl Default for KnobBuilder {
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            min: 0.0,
+            max: 1.0,
+            step: None,
+            detents: Vec::new(),
+            circular: false,
+            sensitivity: 200.0,
+            disabled: false,
+            size: 48.0,
+            knob_color: Color::rgba(70.0, 70.0, 70.0, 255.0),
+            indicator_color: Color::rgba(255.0, 255.0, 255.0, 255.0),
+            track_color: Color::rgba(50.0, 50.0, 50.0, 255.0),
+            value_color: Color::rgba(58.0, 123.0, 213.0, 255.0),
+        }
+    }
+}
+
+impl KnobBuilder {
+    /// Sets the accessible name of the knob.
+    #[inline]
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// Sets the range of the value. Defaults to `0.0..=1.0`.
+    #[inline]
+    pub fn range(&mut self, min: f32, max: f32) -> &mut Self {
+        self.min = min.min(max);
+        self.max = max.max(min);
+        self
+    }
+
+    /// Sets how much an arrow key changes the value. Defaults to a hundredth of the range.
+    #[inline]
+    pub fn step(&mut self, step: f32) -> &mut Self {
+        self.step = Some(step.abs());
+        self
+    }
+
+    /// Sets values that dragging snaps to when close, like the center of a pan knob.
+    #[inline]
+    pub fn detents(&mut self, detents: &[f32]) -> &mut Self {
+        self.detents = detents.to_vec();
+        self
+    }
+
+    /// Turns the knob by dragging around its center instead of up and down.
+    #[inline]
+    pub fn circular(&mut self) -> &mut Self {
+        self.circular = true;
+        self
+    }
+
+    /// Sets the pixels of drag that cover the whole range. Defaults to 200.
+    #[inline]
+    pub fn sensitivity(&mut self, pixels: f32) -> &mut Self {
+        self.sensitivity = pixels.max(1.0);
+        self
+    }
+
+    /// Stops the knob from changing and removes it from the Tab order.
+    #[inline]
+    pub fn disabled(&mut self, disabled: bool) -> &mut Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Sets the width and height of the knob. Defaults to 48.
+    #[inline]
+    pub fn size(&mut self, size: f32) -> &mut Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets the color of the knob's body.
+    #[inline]
+    pub fn knob_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.knob_color = color.into();
+        self
+    }
+
+    /// Sets the color of the dot marking the knob's angle.
+    #[inline]
+    pub fn indicator_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.indicator_color = color.into();
+        self
+    }
+
+    /// Sets the color of the ring past the value.
+    #[inline]
+    pub fn track_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.track_color = color.into();
+        self
+    }
+
+    /// Sets the color of the ring up to the value.
+    #[inline]
+    pub fn value_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.value_color = color.into();
+        self
+    }
+}
+
+/// Angle of `point` around `center`, clockwise from straight up.
+fn angle_around(center: Vector2, point: Vector2) -> f32 {
+    (point.x - center.x).atan2(center.y - point.y)
+}
+
+/// Offset from the center of a circle to the point at `angle`, clockwise from straight up.
+fn on_circle(angle: f32, radius: f32) -> (f32, f32) {
+    (angle.sin() * radius, -angle.cos() * radius)
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Adds a knob for `value`.
+    ///
+    /// Returns `true` on the frame `value` changed.
+    ///
+    /// ```ignore
+    /// ui.knob("pan", &mut pan, |k| k.label("Pan").range(-1.0, 1.0).detents(&[0.0]));
+    /// ```
+    pub fn knob(
+        &mut self,
+        id: impl Into<Id>,
+        value: &mut f32,
+        config_fn: impl for<'a> FnOnce(&'a mut KnobBuilder) -> &'a mut KnobBuilder,
+    ) -> bool {
+        let mut config = KnobBuilder::default();
+        config_fn(&mut config);
+        let id = id.into();
+        let (min, max) = (config.min, config.max);
+        let span = max - min;
+        let factor = if self.ply.shift_down { FINE_FACTOR } else { 1.0 };
+        let previous = *value;
+        let mut new_value = value.clamp(min, max);
+
+        let pressed = self.ply.is_pressed(id.clone()) && !config.disabled;
+        let pointer = self.ply.context.pointer_position();
+        let bounds = self.ply.context.get_element_data(id.clone());
+        let drag = self.ply.component_states.widget_state::<Option<KnobDrag>>(id.id, || None);
+        if !pressed {
+            *drag = None;
+        }
+        let drag = pressed.then(|| {
+            let drag = drag.get_or_insert(KnobDrag { last: pointer, raw: new_value });
+            // Movement since last frame, as a share of the range
+            let moved = match bounds.filter(|_| config.circular) {
+                Some(bounds) => {
+                    let center = Vector2::new(bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0);
+                    let turned = angle_around(center, pointer) - angle_around(center, drag.last);
+                    // Crossing straight down wraps the angle around
+                    ((turned + PI).rem_euclid(2.0 * PI) - PI) / SWEEP
+                }
+                None => ((pointer.x - drag.last.x) + (drag.last.y - pointer.y)) / config.sensitivity,
+            };
+            drag.raw = (drag.raw + moved * span * factor).clamp(min, max);
+            drag.last = pointer;
+            *drag
+        });
+        if let Some(drag) = drag {
+            new_value = config
+                .detents
+                .iter()
+                .copied()
+                .find(|detent| (detent - drag.raw).abs() <= span * DETENT_SNAP)
+                .unwrap_or(drag.raw);
+        }
+
+        if let Some(direction) = self.ply.context.arrow_key_on(id.id).filter(|_| !config.disabled) {
+            let step = config.step.unwrap_or(span / 100.0) * factor;
+            new_value = match direction {
+                ArrowDirection::Up | ArrowDirection::Right => new_value + step,
+                ArrowDirection::Down | ArrowDirection::Left => new_value - step,
+            }
+            .clamp(min, max);
+        }
+        *value = new_value;
+
+        let fraction = if span > 0.0 { (new_value - min) / span } else { 0.0 };
+        let angle = -SWEEP / 2.0 + fraction * SWEEP;
+        let dim = |mut color: Color| {
+            if config.disabled {
+                color.a *= 0.5;
+            }
+            color
+        };
+        let dot_size = (config.size / 12.0).max(2.0);
+        let ring_radius = (config.size - dot_size) / 2.0;
+        let body_size = config.size * 0.7;
+        let indicator_size = (body_size / 6.0).max(2.0);
+
+        self.element()
+            .id(id.clone())
+            .width(Sizing::Fixed(config.size))
+            .height(Sizing::Fixed(config.size))
+            .accessibility(|a| {
+                a.role(AccessibilityRole::Slider)
+                    .label(&config.label)
+                    .value(&format!("{new_value}"))
+                    .value_min(min)
+                    .value_max(max);
+                if !config.disabled {
+                    a.focusable();
+                }
+                a
+            })
+            .layout(|l| l.align(AlignX::CenterX, AlignY::CenterY))
+            .children(|ui| {
+                if ui.just_pressed() && !config.disabled {
+                    ui.set_focus(id.clone());
+                }
+                if !config.circular && !config.disabled && (ui.hovered() || ui.pressed()) {
+                    ui.ply.requested_cursor = Some(CursorIcon::NSResize);
+                }
+
+                for dot in 0..RING_DOTS {
+                    let share = dot as f32 / (RING_DOTS - 1) as f32;
+                    let color = if share <= fraction + f32::EPSILON { config.value_color } else { config.track_color };
+                    ui.element()
+                        .width(Sizing::Fixed(dot_size))
+                        .height(Sizing::Fixed(dot_size))
+                        .background_color(dim(color))
+                        .corner_radius(dot_size / 2.0)
+                        .floating(|f| f
+                            .attach_parent()
+                            .anchor((AlignX::CenterX, AlignY::CenterY), (AlignX::CenterX, AlignY::CenterY))
+                            .offset(on_circle(-SWEEP / 2.0 + share * SWEEP, ring_radius))
+                        )
+                        .empty();
+                }
+
+                ui.element()
+                    .width(Sizing::Fixed(body_size))
+                    .height(Sizing::Fixed(body_size))
+                    .background_color(dim(config.knob_color))
+                    .corner_radius(body_size / 2.0)
+                    .children(|ui| {
+                        ui.element()
+                            .width(Sizing::Fixed(indicator_size))
+                            .height(Sizing::Fixed(indicator_size))
+                            .background_color(dim(config.indicator_color))
+                            .corner_radius(indicator_size / 2.0)
+                            .floating(|f| f
+                                .attach_parent()
+                                .anchor((AlignX::CenterX, AlignY::CenterY), (AlignX::CenterX, AlignY::CenterY))
+                                .offset(on_circle(angle, body_size / 2.0 - indicator_size * 1.5))
+                            )
+                            .empty();
+                    });
+            });
+
+        *value != previous
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Dimensions;
+    use crate::Ply;
+
+    #[test]
+    fn test_knob_drags_snaps_and_steps() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 400.0));
+        let mut value = 0.0;
+        let mut frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.knob("knob", &mut value, |k| k.range(0.0, 10.0).step(1.0).detents(&[5.0]));
+            ui.eval();
+            value
+        };
+
+        // Dragging up 40 of 200 pixels covers a fifth of the range
+        frame(&mut ply);
+        ply.context.set_pointer_state(Vector2::new(24.0, 40.0), true);
+        frame(&mut ply);
+        ply.context.set_pointer_state(Vector2::new(24.0, 0.0), true);
+        assert!((frame(&mut ply) - 2.0).abs() < 0.001);
+
+        // Close to a detent, it snaps
+        ply.context.set_pointer_state(Vector2::new(24.0, -58.0), true);
+        assert_eq!(frame(&mut ply), 5.0);
+
+        // Shift slows the drag down
+        ply.shift_down = true;
+        ply.context.set_pointer_state(Vector2::new(24.0, -158.0), true);
+        assert!((frame(&mut ply) - 5.4).abs() < 0.001);
+        ply.shift_down = false;
+        ply.context.set_pointer_state(Vector2::new(24.0, -158.0), false);
+        frame(&mut ply);
+
+        // Clicking focused it, so the arrow keys step it
+        assert_eq!(ply.context.focused_element_id, Id::new("knob").id);
+        ply.context.arrow_focus(ArrowDirection::Down);
+        assert!((frame(&mut ply) - 4.4).abs() < 0.001);
+    }
+}
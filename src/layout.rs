@@ -3,6 +3,7 @@ use crate::engine;
 
 /// Per-corner border radius for rounded rectangles.
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CornerRadius {
     pub top_left: f32,
     pub top_right: f32,
@@ -46,7 +47,9 @@ impl From<(f32, f32, f32, f32)> for CornerRadius {
 
 /// Defines different sizing behaviors for an element.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum SizingType {
     /// The element's size is determined by its content and constrained by min/max values.
     Fit,
@@ -56,10 +59,27 @@ pub enum SizingType {
     Percent,
     /// The element's size is set to a fixed value.
     Fixed,
+    /// The element's size is a fraction of the layout's width.
+    ViewportWidth,
+    /// The element's size is a fraction of the layout's height.
+    ViewportHeight,
+    /// The element's size is its children's smallest, fully wrapped size.
+    MinContent,
+    /// The element's size is its children's unwrapped size.
+    MaxContent,
+}
+
+/// Which side of the layout a [`Sizing::ViewportPercent`] is measured against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ViewportAxis {
+    Width,
+    Height,
 }
 
 /// Represents different sizing strategies for layout elements.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Sizing {
     /// Fits the element’s width/height within a min and max constraint.
     Fit(f32, f32),
@@ -72,6 +92,28 @@ pub enum Sizing {
     Fixed(f32),
     /// Sets width/height as a percentage of its parent. Value should be between `0.0` and `1.0`.
     Percent(f32),
+    /// Sets width/height as a fraction of the layout's width or height, however
+    /// its parents are sized. `1.0` is the whole side.
+    ViewportPercent(f32, ViewportAxis),
+    /// Sizes the element to the smallest its children can get: text wrapped
+    /// at every opportunity, fit containers shrunk. It doesn't grow or shrink.
+    MinContent,
+    /// Sizes the element to its children's natural size, e.g. unwrapped text.
+    /// It doesn't grow or shrink, even when the parent is too small.
+    MaxContent,
+}
+
+impl Sizing {
+    /// Parses `"50vw"` or `"100vh"`. Anything else, like a token name, is `None`.
+    #[cfg(any(feature = "stylesheet", feature = "templates"))]
+    pub(crate) fn parse_viewport(value: &str) -> Option<Self> {
+        let (percent, axis) = match value.strip_suffix("vw") {
+            Some(percent) => (percent, ViewportAxis::Width),
+            None => (value.strip_suffix("vh")?, ViewportAxis::Height),
+        };
+        let percent = percent.trim().parse::<f32>().ok()?;
+        Some(Sizing::ViewportPercent((percent / 100.0).max(0.0), axis))
+    }
 }
 
 /// Converts a `Sizing` value into an engine `SizingAxis`.
@@ -118,12 +160,31 @@ impl From<Sizing> for engine::SizingAxis {
                 percent,
                 grow_weight: 1.0,
             },
+            Sizing::ViewportPercent(percent, axis) => Self {
+                type_: match axis {
+                    ViewportAxis::Width => engine::SizingType::ViewportWidth,
+                    ViewportAxis::Height => engine::SizingType::ViewportHeight,
+                },
+                min_max: engine::SizingMinMax { min: 0.0, max: 0.0 },
+                percent,
+                grow_weight: 1.0,
+            },
+            Sizing::MinContent | Sizing::MaxContent => Self {
+                type_: match value {
+                    Sizing::MinContent => engine::SizingType::MinContent,
+                    _ => engine::SizingType::MaxContent,
+                },
+                min_max: engine::SizingMinMax { min: 0.0, max: f32::MAX },
+                percent: 0.0,
+                grow_weight: 1.0,
+            },
         }
     }
 }
 
 /// Represents padding values for each side of an element.
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Padding {
     /// Padding on the left side.
     pub left: u16,
@@ -180,6 +241,7 @@ impl From<(u16, u16, u16, u16)> for Padding {
 
 /// Defines the layout direction for arranging child elements.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum LayoutDirection {
     /// Arranges elements from left to right.
@@ -244,6 +306,63 @@ impl LayoutBuilder {
     }
 }
 
+/// Builder for an element's margins, used in the closure passed to `.margin()`.
+pub struct MarginBuilder {
+    pub(crate) config: engine::MarginConfig,
+}
+
+impl MarginBuilder {
+    /// Sets the same margin for all sides.
+    #[inline]
+    pub fn all(&mut self, margin: i16) -> &mut Self {
+        self.x(margin).y(margin)
+    }
+
+    /// Sets the same margin for left and right sides.
+    #[inline]
+    pub fn x(&mut self, margin: i16) -> &mut Self {
+        self.config.left = margin;
+        self.config.right = margin;
+        self
+    }
+
+    /// Sets the same margin for top and bottom sides.
+    #[inline]
+    pub fn y(&mut self, margin: i16) -> &mut Self {
+        self.config.top = margin;
+        self.config.bottom = margin;
+        self
+    }
+
+    /// Sets the left margin.
+    #[inline]
+    pub fn left(&mut self, margin: i16) -> &mut Self {
+        self.config.left = margin;
+        self
+    }
+
+    /// Sets the right margin.
+    #[inline]
+    pub fn right(&mut self, margin: i16) -> &mut Self {
+        self.config.right = margin;
+        self
+    }
+
+    /// Sets the top margin.
+    #[inline]
+    pub fn top(&mut self, margin: i16) -> &mut Self {
+        self.config.top = margin;
+        self
+    }
+
+    /// Sets the bottom margin.
+    #[inline]
+    pub fn bottom(&mut self, margin: i16) -> &mut Self {
+        self.config.bottom = margin;
+        self
+    }
+}
+
 /// Shorthand macro for [`Sizing::Fit`]. Defaults max to `f32::MAX` if omitted.
 #[macro_export]
 macro_rules! fit {
@@ -337,6 +456,24 @@ macro_rules! percent {
     }};
 }
 
+/// Shorthand macro for [`Sizing::ViewportPercent`] of the layout's width,
+/// e.g. `vw!(0.5)` for half of it.
+#[macro_export]
+macro_rules! vw {
+    ($fraction:expr) => {
+        $crate::layout::Sizing::ViewportPercent($fraction, $crate::layout::ViewportAxis::Width)
+    };
+}
+
+/// Shorthand macro for [`Sizing::ViewportPercent`] of the layout's height,
+/// e.g. `vh!(1.0)` for all of it.
+#[macro_export]
+macro_rules! vh {
+    ($fraction:expr) => {
+        $crate::layout::Sizing::ViewportPercent($fraction, $crate::layout::ViewportAxis::Height)
+    };
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -414,4 +551,64 @@ mod test {
         let value = percent!(0.5);
         assert!(matches!(value, Sizing::Percent(0.5)));
     }
+
+    #[test]
+    fn viewport_sizing_ignores_parents() {
+        assert!(matches!(vw!(0.5), Sizing::ViewportPercent(0.5, ViewportAxis::Width)));
+        assert!(matches!(vh!(1.0), Sizing::ViewportPercent(1.0, ViewportAxis::Height)));
+
+        let mut ply = crate::Ply::<()>::new_headless(crate::math::Dimensions::new(400.0, 300.0));
+        let mut ui = ply.begin();
+        ui.element().width(fixed!(100.0)).height(fit!()).children(|ui| {
+            ui.element().id("hero").width(vw!(0.5)).height(vh!(0.25)).empty();
+        });
+        ui.eval();
+        let hero = ply.bounding_box("hero").unwrap();
+        assert_eq!((hero.width, hero.height), (200.0, 75.0));
+    }
+
+    #[test]
+    fn content_sizing_uses_wrapped_and_unwrapped_text() {
+        let mut ply = crate::Ply::<()>::new_headless(crate::math::Dimensions::new(400.0, 300.0));
+        ply.set_measure_text_function(|text, _| crate::math::Dimensions::new(text.chars().count() as f32 * 10.0, 20.0));
+        let mut ui = ply.begin();
+        ui.element().width(fixed!(60.0)).children(|ui| {
+            ui.element().id("max").width(Sizing::MaxContent).children(|ui| {
+                ui.text("aaaa bbbb cccc", |t| t.font_size(16));
+            });
+        });
+        ui.element().id("min").width(Sizing::MinContent).children(|ui| {
+            ui.text("aaaa bbbb cccc", |t| t.font_size(16));
+        });
+        ui.eval();
+
+        // Kept unwrapped though the parent is narrower
+        let max = ply.bounding_box("max").unwrap();
+        assert_eq!(max.width, 140.0);
+        // As narrow as the longest word, so every word gets its own line
+        let min = ply.bounding_box("min").unwrap();
+        assert_eq!(min.width, 40.0);
+        assert_eq!(min.height, 60.0);
+    }
+
+    #[test]
+    fn margins_space_and_overlap_siblings() {
+        let mut ply = crate::Ply::<()>::new_headless(crate::math::Dimensions::new(400.0, 300.0));
+        let mut ui = ply.begin();
+        ui.element().id("row").layout(|l| l.padding(10).gap(5)).children(|ui| {
+            ui.element().id("a").width(fixed!(50.0)).height(fixed!(20.0)).margin(|m| m.all(8).top(16)).empty();
+            ui.element().id("b").width(fixed!(50.0)).height(fixed!(20.0)).margin(|m| m.left(-20)).empty();
+        });
+        ui.eval();
+
+        let a = ply.bounding_box("a").unwrap();
+        assert_eq!((a.x, a.y), (18.0, 26.0));
+        // Pulled back over `a` by its negative margin
+        let b = ply.bounding_box("b").unwrap();
+        assert_eq!((b.x, b.y), (18.0 + 50.0 + 8.0 + 5.0 - 20.0, 10.0));
+        // The parent fits the margin boxes
+        let row = ply.bounding_box("row").unwrap();
+        assert_eq!(row.width, 10.0 + 66.0 + 5.0 + 30.0 + 10.0);
+        assert_eq!(row.height, 10.0 + 44.0 + 10.0);
+    }
 }
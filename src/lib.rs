@@ -4,20 +4,73 @@ pub mod accessibility_web;
 #[cfg(all(feature = "a11y", not(target_arch = "wasm32")))]
 pub mod accessibility_native;
 pub mod align;
+pub mod canvas;
+pub mod capture;
 pub mod color;
+pub mod component;
+pub mod diagnostics;
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
+#[cfg(feature = "syntax-highlighting")]
+pub mod code;
 pub mod easing;
 pub mod elements;
 pub mod engine;
 pub mod id;
 pub mod lerp;
 pub mod layout;
+pub mod mask;
 pub mod math;
+pub mod pan_zoom;
+pub mod portal;
 pub mod render_commands;
 pub mod shader_build;
 pub mod shaders;
+pub mod style;
+#[cfg(feature = "stylesheet")]
+pub mod stylesheet;
+#[cfg(feature = "templates")]
+pub mod template;
 pub mod text;
 pub mod text_input;
+pub mod theme;
+pub mod toggle;
 pub mod renderer;
+pub mod search_input;
+pub mod dropdown;
+pub mod toast;
+pub mod transition;
+pub mod split;
+pub mod switch;
+pub mod chips;
+pub mod progress;
+pub mod knob;
+pub mod chart;
+#[cfg(feature = "plot")]
+pub mod plot;
+#[cfg(feature = "scripting")]
+pub mod script;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "software")]
+pub mod software;
+#[cfg(all(feature = "winit", not(target_arch = "wasm32")))]
+pub mod winit;
+#[cfg(feature = "bevy")]
+pub mod bevy_ply;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(any(all(feature = "winit", not(target_arch = "wasm32")), feature = "bevy"))]
+mod event_input;
+pub mod system;
+mod telemetry;
+pub mod window;
+#[cfg(any(feature = "stylesheet", feature = "templates"))]
+mod watch;
+#[cfg(feature = "sdf-text")]
+pub mod sdf_text;
 #[cfg(feature = "text-styling")]
 pub mod text_styling;
 #[cfg(feature = "built-in-shaders")]
@@ -26,8 +79,12 @@ pub mod built_in_shaders;
 pub mod net;
 #[cfg(feature = "storage")]
 pub mod storage;
+#[cfg(feature = "dialogs")]
+pub mod dialogs;
 pub mod jobs;
 pub mod prelude;
+pub mod profiler;
+pub mod render_diff;
 
 use id::Id;
 use math::{Dimensions, Vector2};
@@ -51,6 +108,57 @@ pub struct Ply<CustomElementData: Clone + Default + std::fmt::Debug = ()> {
     text_input_repeat_focus_id: u32,
     /// Track virtual keyboard state to avoid redundant show/hide calls
     was_text_input_focused: bool,
+    /// Mouse cursor currently shown
+    cursor: macroquad::miniquad::CursorIcon,
+    /// Cursor a widget asked for this frame, like a split pane's resize cursor
+    requested_cursor: Option<macroquad::miniquad::CursorIcon>,
+    /// Whether Escape was pressed this frame, for dismissing popups like dropdowns
+    escape_pressed: bool,
+    /// Whether Shift is held, for fine adjustment in widgets like knobs
+    shift_down: bool,
+    /// Files dropped onto the window this frame
+    dropped_files: Vec<macroquad::DroppedFile>,
+    /// Window settings and the `on_resize` hook
+    window_state: window::WindowState,
+    /// Design tokens that `token!` resolves against
+    theme: std::rc::Rc<theme::Theme>,
+    /// Per-instance state of components, see `Ui::component`
+    component_states: component::ComponentStates,
+    /// Keys and start times of transitions, see `ElementBuilder::transition`
+    transitions: transition::Transitions,
+    /// Elements used as masks, see `ElementBuilder::mask_element`
+    masks: mask::Masks,
+    /// Views of pan/zoom containers, see `ElementBuilder::pan_zoom`
+    pan_zooms: pan_zoom::PanZooms,
+    /// Queued notifications, see `toast`
+    toasts: toast::Toasts,
+    /// Stats of the last evaluated frame
+    frame_stats: profiler::FrameStats,
+    /// How `show` smooths shape edges, see `set_edge_antialiasing`
+    edge_antialiasing: renderer::EdgeAntialiasing,
+    profiler_hud: bool,
+    /// Last frame's render commands to diff against, while the diff viewer is on
+    render_diff: Option<render_diff::RenderDiff>,
+    /// Hash of the last frame's render commands, while change detection is on
+    last_frame_hash: Option<u64>,
+    frame_changed: bool,
+    diagnostics_handler: Option<diagnostics::DiagnosticsHandler>,
+    /// Last frame's diagnostics, already sent to `log`
+    #[cfg(feature = "log")]
+    logged_diagnostics: Vec<diagnostics::Diagnostic>,
+    /// Whether to outline accessible elements, see `set_accessibility_overlay`
+    accessibility_overlay: bool,
+    /// Messages for screen readers queued by `announce`, sent on the next `eval`
+    announcements: Vec<(String, accessibility::LiveRegionMode)>,
+    /// Watched stylesheet file, see `load_stylesheet`
+    #[cfg(feature = "stylesheet")]
+    stylesheet_state: stylesheet::StylesheetState,
+    /// Watched template files, see `Ui::template_file`
+    #[cfg(feature = "templates")]
+    template_files: template::TemplateFiles,
+    /// Watched script files, see `Ui::script_file`
+    #[cfg(feature = "scripting")]
+    script_files: script::ScriptFiles,
     #[cfg(all(feature = "a11y", target_arch = "wasm32"))]
     web_a11y_state: accessibility_web::WebAccessibilityState,
     #[cfg(all(feature = "a11y", not(target_arch = "wasm32")))]
@@ -68,13 +176,25 @@ pub struct ElementBuilder<'ply, CustomElementData: Clone + Default + std::fmt::D
     ply: &'ply mut Ply<CustomElementData>,
     inner: engine::ElementDeclaration<CustomElementData>,
     id: Option<Id>,
-    on_hover_fn: Option<Box<dyn FnMut(Id, engine::PointerData) + 'static>>,
-    on_press_fn: Option<Box<dyn FnMut(Id, engine::PointerData) + 'static>>,
-    on_release_fn: Option<Box<dyn FnMut(Id, engine::PointerData) + 'static>>,
-    on_focus_fn: Option<Box<dyn FnMut(Id) + 'static>>,
-    on_unfocus_fn: Option<Box<dyn FnMut(Id) + 'static>>,
-    text_input_on_changed_fn: Option<Box<dyn FnMut(&str) + 'static>>,
-    text_input_on_submit_fn: Option<Box<dyn FnMut(&str) + 'static>>,
+    /// Where `.id()` was called, for duplicate id reports
+    id_location: Option<&'static std::panic::Location<'static>>,
+    on_hover_fn: Option<Box<dyn engine::PointerCallback>>,
+    on_press_fn: Option<Box<dyn engine::PointerCallback>>,
+    on_release_fn: Option<Box<dyn engine::PointerCallback>>,
+    on_focus_fn: Option<Box<dyn engine::FocusCallback>>,
+    on_unfocus_fn: Option<Box<dyn engine::FocusCallback>>,
+    text_input_on_changed_fn: Option<engine::StrCallback>,
+    text_input_on_submit_fn: Option<engine::StrCallback>,
+    text_input_validate_fn: Option<engine::ValidateFn>,
+    on_link_clicked_fn: Option<engine::StrCallback>,
+    #[cfg(feature = "clipboard")]
+    on_paste_image_fn: Option<engine::PasteImageCallback>,
+    on_file_drop_fn: Option<engine::FileDropCallback>,
+    style_states: Option<style::StyleStates>,
+    transition: Option<(u64, transition::TransitionBuilder)>,
+    mask: Option<mask::MaskSource>,
+    clip_path: Option<Vec<Vector2>>,
+    pan_zoom: Option<pan_zoom::PanZoomBuilder>,
 }
 
 impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
@@ -109,12 +229,54 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
         self
     }
 
+    /// Applies the settings made on a [`Style`](style::Style).
+    /// Settings made after this call override the style.
+    #[inline]
+    pub fn style(mut self, style: &style::Style) -> Self {
+        style.apply(&mut self.inner);
+        self
+    }
+
+    /// Applies the style with the given name from the loaded stylesheet.
+    /// Does nothing until the stylesheet is loaded or if it has no such style.
+    #[cfg(feature = "stylesheet")]
+    #[inline]
+    pub fn class(mut self, name: &str) -> Self {
+        if let Some(style) = self.ply.stylesheet().and_then(|sheet| sheet.get(name)) {
+            style.apply(&mut self.inner);
+        }
+        self
+    }
+
+    /// Sets style overrides for hover, focus, press and disabled states,
+    /// applied on top of all other settings while the state is active.
+    ///
+    /// ```rust,ignore
+    /// ui.element()
+    ///     .background_color(0x3A3A3A)
+    ///     .style_states(|s| s
+    ///         .hover(|h| h.background_color(0x4A4A4A))
+    ///         .pressed(|p| p.background_color(0x2A2A2A))
+    ///         .disabled(!can_save, |d| d.background_color(0x222222))
+    ///     )
+    ///     .empty();
+    /// ```
+    #[inline]
+    pub fn style_states(mut self, f: impl for<'a> FnOnce(&'a mut style::StyleStates) -> &'a mut style::StyleStates) -> Self {
+        let mut states = self.style_states.take().unwrap_or_default();
+        f(&mut states);
+        self.style_states = Some(states);
+        self
+    }
+
     /// Sets the element's ID.
     ///
     /// Accepts an `Id` or a `&'static str` label.
     #[inline]
+    #[track_caller]
     pub fn id(mut self, id: impl Into<Id>) -> Self {
         self.id = Some(id.into());
+        self.id_location = Some(std::panic::Location::caller());
         self
     }
 
@@ -161,6 +323,49 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
         self
     }
 
+    /// Draws vector shapes recorded with a [`canvas::Painter`], in pixels
+    /// from the element's top-left corner.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// ui.element()
+    ///     .width(fixed!(200.0)).height(fixed!(100.0))
+    ///     .canvas(|p| p
+    ///         .line((0.0, 100.0), (200.0, 0.0), 2.0, WHITE)
+    ///         .circle((100.0, 50.0), 8.0, |s| s.fill(RED))
+    ///     )
+    ///     .empty();
+    /// ```
+    #[inline]
+    pub fn canvas(mut self, f: impl for<'a> FnOnce(&'a mut canvas::Painter) -> &'a mut canvas::Painter) -> Self {
+        let mut painter = canvas::Painter::default();
+        f(&mut painter);
+        self.inner.canvas = Some(painter.commands);
+        self
+    }
+
+    /// Runs `f` while rendering, at this element's place in the draw order:
+    /// above its background, below its children.
+    ///
+    /// `f` gets the element's bounding box with the scissor and shape
+    /// rotation already applied, so plain macroquad draw calls land in place.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// ui.element()
+    ///     .width(grow!()).height(fixed!(80.0))
+    ///     .on_draw(|bbox, _ctx| draw_circle(bbox.x + 40.0, bbox.y + 40.0, 30.0, RED))
+    ///     .empty();
+    /// ```
+    #[inline]
+    pub fn on_draw<F>(mut self, f: F) -> Self
+    where
+        F: Fn(math::BoundingBox, &canvas::DrawContext) + Send + Sync + 'static,
+    {
+        self.inner.on_draw = Some(canvas::DrawHook::new(f));
+        self
+    }
+
     /// Configures layout properties using a closure.
     #[inline]
     pub fn layout(mut self, f: impl for<'a> FnOnce(&'a mut layout::LayoutBuilder) -> &'a mut layout::LayoutBuilder) -> Self {
@@ -170,6 +375,25 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
         self
     }
 
+    /// Sets the space outside this element using a closure. Margins push
+    /// siblings and the parent's edges away; negative margins let them overlap.
+    ///
+    /// Floating elements are placed by their offset and ignore margins.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// ui.element()
+    ///     .margin(|m| m.all(8).top(16))
+    ///     .empty();
+    /// ```
+    #[inline]
+    pub fn margin(mut self, f: impl for<'a> FnOnce(&'a mut layout::MarginBuilder) -> &'a mut layout::MarginBuilder) -> Self {
+        let mut builder = layout::MarginBuilder { config: self.inner.layout.margin };
+        f(&mut builder);
+        self.inner.layout.margin = builder.config;
+        self
+    }
+
     /// Configures floating properties using a closure.
     #[inline]
     pub fn floating(mut self, f: impl for<'a> FnOnce(&'a mut elements::FloatingBuilder) -> &'a mut elements::FloatingBuilder) -> Self {
@@ -248,6 +472,83 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
         self
     }
 
+    /// Blends the element's subtree from how it looked before `key` changed
+    /// to how it looks now, e.g. keyed by the current screen.
+    ///
+    /// Renders the subtree offscreen like `.shader()`. Give the element an
+    /// `.id()` that stays the same across the change.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// ui.element()
+    ///     .id("screen")
+    ///     .transition(current_screen, |t| t
+    ///         .duration(0.4)
+    ///         .shader(&TRANSITION_WIPE, |s| { s.uniform("u_angle", 0.0f32); })
+    ///     )
+    ///     .children(|ui| draw_screen(ui, current_screen));
+    /// ```
+    #[inline]
+    pub fn transition(
+        mut self,
+        key: impl std::hash::Hash,
+        f: impl for<'a> FnOnce(&'a mut transition::TransitionBuilder) -> &'a mut transition::TransitionBuilder,
+    ) -> Self {
+        let mut builder = transition::TransitionBuilder::new();
+        f(&mut builder);
+        self.transition = Some((transition::hash_key(key), builder));
+        self
+    }
+
+    /// Multiplies the subtree's alpha by an image's, stretched over the
+    /// element. Renders the subtree offscreen like `.shader()`.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// ui.element()
+    ///     .mask(&BLOB_MASK)
+    ///     .children(|ui| draw_panel(ui));
+    /// ```
+    #[inline]
+    pub fn mask(mut self, image: impl Into<renderer::ImageSource>) -> Self {
+        self.mask = Some(mask::MaskSource::Image(image.into()));
+        self
+    }
+
+    /// Multiplies the subtree's alpha by the rendered silhouette of the
+    /// element with the given ID, where that element is drawn. The subtree
+    /// is hidden outside of it.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// ui.element()
+    ///     .mask_element("spotlight")
+    ///     .children(|ui| draw_content(ui));
+    /// ```
+    #[inline]
+    pub fn mask_element(mut self, id: impl Into<Id>) -> Self {
+        self.mask = Some(mask::MaskSource::Element(id.into().id));
+        self
+    }
+
+    /// Clips the subtree to the polygon through `points`, given in fractions
+    /// of the element's size (`(0.0, 0.0)` top-left, `(1.0, 1.0)`
+    /// bottom-right). Up to [`mask::MAX_CLIP_PATH_POINTS`] points; edges are
+    /// antialiased. Renders the subtree offscreen like `.shader()`.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // Diagonal panel
+    /// ui.element()
+    ///     .clip_path(&[(0.1, 0.0), (1.0, 0.0), (0.9, 1.0), (0.0, 1.0)])
+    ///     .children(|ui| draw_panel(ui));
+    /// ```
+    #[inline]
+    pub fn clip_path(mut self, points: &[impl Into<Vector2> + Copy]) -> Self {
+        self.clip_path = Some(points.iter().map(|&point| point.into()).collect());
+        self
+    }
+
     /// Applies a visual rotation to the element and all its children.
     ///
     /// This renders the element to an offscreen buffer and draws it back with
@@ -271,7 +572,52 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
     #[inline]
     pub fn rotate_visual(mut self, f: impl for<'a> FnOnce(&'a mut elements::VisualRotationBuilder) -> &'a mut elements::VisualRotationBuilder) -> Self {
         let mut builder = elements::VisualRotationBuilder {
-            config: engine::VisualRotationConfig::default(),
+            config: self.inner.visual_rotation.unwrap_or_default(),
+        };
+        f(&mut builder);
+        self.inner.visual_rotation = Some(builder.config);
+        self
+    }
+
+    /// Makes the element a pan/zoom viewport over its children: the wheel or
+    /// a pinch zooms about the pointer and dragging the background pans.
+    /// Children are clipped to the element and hit where they're drawn.
+    /// Give the element an `.id()` that stays the same across frames.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// ui.element()
+    ///     .id("board")
+    ///     .width(grow!()).height(grow!())
+    ///     .pan_zoom(|p| p.min_zoom(0.25).max_zoom(4.0))
+    ///     .children(|ui| draw_nodes(ui));
+    /// ```
+    #[inline]
+    pub fn pan_zoom(
+        mut self,
+        f: impl for<'a> FnOnce(&'a mut pan_zoom::PanZoomBuilder) -> &'a mut pan_zoom::PanZoomBuilder,
+    ) -> Self {
+        let mut builder = pan_zoom::PanZoomBuilder::default();
+        f(&mut builder);
+        self.pan_zoom = Some(builder);
+        self
+    }
+
+    /// Scales, skews and moves the element visually, without affecting
+    /// layout. Shares the render-target pipeline and pivot with
+    /// [`rotate_visual`](Self::rotate_visual), e.g. for hover-grow or
+    /// press-shrink.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// ui.element()
+    ///     .transform(|t| t.scale(if hovered { 1.05 } else { 1.0 }).translate(0.0, -4.0))
+    ///     .children(|ui| { /* ... */ });
+    /// ```
+    #[inline]
+    pub fn transform(mut self, f: impl for<'a> FnOnce(&'a mut elements::TransformBuilder) -> &'a mut elements::TransformBuilder) -> Self {
+        let mut builder = elements::TransformBuilder {
+            config: self.inner.visual_rotation.unwrap_or_default(),
         };
         f(&mut builder);
         self.inner.visual_rotation = Some(builder.config);
@@ -334,13 +680,33 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
         self
     }
 
+    /// Groups the focusable elements inside this one so arrow keys move between them.
+    ///
+    /// With `.roving()` the whole group is a single Tab stop, like a toolbar
+    /// or a tab list:
+    /// ```ignore
+    /// ui.element()
+    ///     .focus_group(|g| g.roving().horizontal())
+    ///     .children(|ui| { /* buttons */ });
+    /// ```
+    #[inline]
+    pub fn focus_group(
+        mut self,
+        f: impl for<'a> FnOnce(&'a mut accessibility::FocusGroupBuilder) -> &'a mut accessibility::FocusGroupBuilder,
+    ) -> Self {
+        let mut builder = accessibility::FocusGroupBuilder::new();
+        f(&mut builder);
+        self.inner.focus_group = Some(builder.config);
+        self
+    }
+
     /// Registers a callback invoked every frame the pointer is over this element.
     #[inline]
     pub fn on_hover<F>(mut self, callback: F) -> Self
     where
         F: FnMut(Id, engine::PointerData) + 'static,
     {
-        self.on_hover_fn = Some(Box::new(callback));
+        self.on_hover_fn = Some(self.ply.context.pointer_callbacks.boxed(callback, |f| Box::new(f)));
         self
     }
 
@@ -351,7 +717,7 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
     where
         F: FnMut(Id, engine::PointerData) + 'static,
     {
-        self.on_press_fn = Some(Box::new(callback));
+        self.on_press_fn = Some(self.ply.context.pointer_callbacks.boxed(callback, |f| Box::new(f)));
         self
     }
 
@@ -362,7 +728,7 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
     where
         F: FnMut(Id, engine::PointerData) + 'static,
     {
-        self.on_release_fn = Some(Box::new(callback));
+        self.on_release_fn = Some(self.ply.context.pointer_callbacks.boxed(callback, |f| Box::new(f)));
         self
     }
 
@@ -373,7 +739,45 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
     where
         F: FnMut(Id) + 'static,
     {
-        self.on_focus_fn = Some(Box::new(callback));
+        self.on_focus_fn = Some(self.ply.context.focus_callbacks.boxed(callback, |f| Box::new(f)));
+        self
+    }
+
+    /// Registers a callback that fires when a `{link=URL|...}` span in this
+    /// element's text children is clicked. Receives the URL.
+    #[cfg(feature = "text-styling")]
+    #[inline]
+    pub fn on_link_clicked<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&str) + 'static,
+    {
+        self.on_link_clicked_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Registers a callback that fires when an image is pasted with Ctrl+V
+    /// while this element is focused or hovered. Receives the pasted image.
+    #[cfg(feature = "clipboard")]
+    #[inline]
+    pub fn on_paste_image<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&clipboard::ClipboardImage) + 'static,
+    {
+        self.on_paste_image_fn = Some(Box::new(callback));
+        self
+    }
+
+    /// Registers a callback that fires when files from the OS are dropped onto
+    /// this element. Native drops carry paths, web drops carry the file bytes.
+    ///
+    /// Use [`Ui::file_drag_hovered`] inside the element to highlight it while
+    /// files are dragged over it (web only, native platforms report no drag).
+    #[inline]
+    pub fn on_file_drop<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&[macroquad::DroppedFile]) + 'static,
+    {
+        self.on_file_drop_fn = Some(Box::new(callback));
         self
     }
 
@@ -383,7 +787,7 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
     where
         F: FnMut(Id) + 'static,
     {
-        self.on_unfocus_fn = Some(Box::new(callback));
+        self.on_unfocus_fn = Some(self.ply.context.focus_callbacks.boxed(callback, |f| Box::new(f)));
         self
     }
 
@@ -413,41 +817,138 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug>
         let mut builder = text_input::TextInputBuilder::new();
         f(&mut builder);
         self.inner.text_input = Some(builder.config);
-        self.text_input_on_changed_fn = builder.on_changed_fn;
+        self.text_input_on_changed_fn = match builder.on_changed_value_fn {
+            Some(mut on_value) => {
+                let mut on_changed = builder.on_changed_fn;
+                Some(Box::new(move |text: &str| {
+                    if let Some(ref mut on_changed) = on_changed {
+                        on_changed(text);
+                    }
+                    if let Ok(value) = text.parse::<f64>() {
+                        on_value(value);
+                    }
+                }))
+            }
+            None => builder.on_changed_fn,
+        };
         self.text_input_on_submit_fn = builder.on_submit_fn;
+        self.text_input_validate_fn = builder.validate_fn;
         self
     }
 
     /// Finalizes the element with children defined in a closure.
     pub fn children(self, f: impl FnOnce(&mut Ui<'_, CustomElementData>)) -> Id {
         let ElementBuilder {
-            ply, inner, id,
+            ply, mut inner, id, id_location,
             on_hover_fn, on_press_fn, on_release_fn, on_focus_fn, on_unfocus_fn,
-            text_input_on_changed_fn, text_input_on_submit_fn,
+            text_input_on_changed_fn, text_input_on_submit_fn, text_input_validate_fn,
+            on_link_clicked_fn,
+            #[cfg(feature = "clipboard")]
+            on_paste_image_fn,
+            on_file_drop_fn,
+            style_states,
+            transition,
+            mask,
+            clip_path,
+            pan_zoom,
         } = self;
         if let Some(ref id) = id {
-            ply.context.open_element_with_id(id);
+            ply.context.open_element_with_id_at(id, id_location);
         } else {
             ply.context.open_element();
         }
-        ply.context.configure_open_element(&inner);
+        if let Some(states) = style_states {
+            let (hovered, focused, pressed) =
+                (ply.context.hovered(), ply.context.focused(), ply.context.pressed());
+            states.apply(hovered, focused, pressed, &mut inner);
+        }
+        if let Some((key, builder)) = transition {
+            let id = ply.context.get_open_element_id();
+            let shader = ply.transitions.shader(id, key, ply.context.current_time, builder);
+            // Innermost, so the element's other shaders apply on top
+            inner.shaders.insert(0, shader);
+        }
+        if let Some(source) = mask {
+            // Outermost, so the element's other shaders are masked too
+            inner.shaders.push(ply.masks.shader(source));
+        }
+        if let Some(points) = clip_path {
+            inner.shaders.push(mask::clip_path_shader(&points));
+        }
+        if inner.shaders.is_empty() {
+            if let Some(shader) = ply.masks.silhouette_shader(ply.context.get_open_element_id()) {
+                inner.shaders.push(shader);
+            }
+        }
+        let pan_zoom_content = pan_zoom.map(|config| {
+            let id = Id { id: ply.context.get_open_element_id(), ..Default::default() };
+            let content_id = id.child("ply_pan_zoom_content");
+            let over_ids = ply.context.get_pointer_over_ids();
+            let input = pan_zoom::PanZoomInput {
+                bounds: ply.context.get_element_data(id.clone()),
+                pointer: ply.context.pointer_position(),
+                hovered: over_ids.iter().any(|over| over.id == id.id),
+                pressed: ply.context.is_element_pressed(id.id),
+                over_background: over_ids.last().is_some_and(|top| top.id == id.id || top.id == content_id.id),
+                wheel: ply.context.wheel_delta().y,
+                pinch: if ply.headless {
+                    None
+                } else {
+                    match macroquad::prelude::touches().as_slice() {
+                        [a, b] => Some((
+                            Vector2::new(a.position.x, a.position.y),
+                            Vector2::new(b.position.x, b.position.y),
+                        )),
+                        _ => None,
+                    }
+                },
+            };
+            let view = ply.pan_zooms.update(id.id, &config, input);
+            inner.clip.horizontal = true;
+            inner.clip.vertical = true;
+            (content_id, view, inner.layout.layout_direction, inner.layout.child_gap)
+        });
+        ply.context.configure_open_element(inner);
         let element_id = ply.context.get_open_element_id();
 
         if let Some(hover_fn) = on_hover_fn {
-            ply.context.on_hover(hover_fn);
+            ply.context.set_hover_callback(hover_fn);
         }
         if on_press_fn.is_some() || on_release_fn.is_some() {
-            ply.context.set_press_callbacks(on_press_fn, on_release_fn);
+            ply.context.set_pooled_press_callbacks(on_press_fn, on_release_fn);
         }
         if on_focus_fn.is_some() || on_unfocus_fn.is_some() {
-            ply.context.set_focus_callbacks(on_focus_fn, on_unfocus_fn);
+            ply.context.set_pooled_focus_callbacks(on_focus_fn, on_unfocus_fn);
         }
         if text_input_on_changed_fn.is_some() || text_input_on_submit_fn.is_some() {
             ply.context.set_text_input_callbacks(text_input_on_changed_fn, text_input_on_submit_fn);
         }
+        if let Some(validate_fn) = text_input_validate_fn {
+            ply.context.validate_text_input(validate_fn.as_ref());
+        }
+        if let Some(link_fn) = on_link_clicked_fn {
+            ply.context.set_link_clicked_callback(link_fn);
+        }
+        #[cfg(feature = "clipboard")]
+        if let Some(paste_image_fn) = on_paste_image_fn {
+            ply.context.set_paste_image_callback(paste_image_fn);
+        }
+        if let Some(file_drop_fn) = on_file_drop_fn {
+            ply.context.set_file_drop_callback(file_drop_fn);
+        }
 
         let mut ui = Ui { ply };
-        f(&mut ui);
+        match pan_zoom_content {
+            Some((content_id, view, direction, gap)) => {
+                let mut content = ui.element().id(content_id);
+                content.inner.layout.layout_direction = direction;
+                content.inner.layout.child_gap = gap;
+                content
+                    .transform(|t| t.pivot((0.0, 0.0)).scale(view.zoom).translate(view.pan.x, view.pan.y))
+                    .children(f);
+            }
+            None => f(&mut ui),
+        }
         ui.ply.context.close_element();
 
         Id { id: element_id, ..Default::default() }
@@ -485,6 +986,7 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, Custom
             ply: &mut *self.ply,
             inner: engine::ElementDeclaration::default(),
             id: None,
+            id_location: None,
             on_hover_fn: None,
             on_press_fn: None,
             on_release_fn: None,
@@ -492,6 +994,16 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, Custom
             on_unfocus_fn: None,
             text_input_on_changed_fn: None,
             text_input_on_submit_fn: None,
+            text_input_validate_fn: None,
+            on_link_clicked_fn: None,
+            #[cfg(feature = "clipboard")]
+            on_paste_image_fn: None,
+            on_file_drop_fn: None,
+            style_states: None,
+            transition: None,
+            mask: None,
+            clip_path: None,
+            pan_zoom: None,
         }
     }
 
@@ -508,6 +1020,13 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, Custom
         self.ply.context.get_scroll_offset()
     }
 
+    /// Returns how far the current element has scrolled out of its scroll
+    /// container, from `0.0` to `1.0` per axis, e.g. for parallax headers or
+    /// fading on scroll. On a scroll container, how far it's scrolled.
+    pub fn scroll_progress(&self) -> Vector2 {
+        self.ply.context.scroll_progress()
+    }
+
     /// Returns if the current element you are creating is hovered
     pub fn hovered(&self) -> bool {
         self.ply.context.hovered()
@@ -533,6 +1052,12 @@ impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, Custom
     pub fn focused(&self) -> bool {
         self.ply.context.focused()
     }
+
+    /// Returns if files from the OS are being dragged over the current element
+    /// and would be dropped onto it. Only reported on the web.
+    pub fn file_drag_hovered(&self) -> bool {
+        self.ply.context.file_drag_hovered()
+    }
 }
 
 impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData> {
@@ -556,7 +1081,26 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
     ) -> Ui<'_, CustomElementData> {
         jobs::poll_completions();
 
+        // Reload the stylesheet, templates and scripts when their files changed
+        #[cfg(feature = "stylesheet")]
+        self.update_stylesheet();
+        #[cfg(feature = "templates")]
+        self.template_files.update(self.context.current_time);
+        #[cfg(feature = "scripting")]
+        self.script_files.update(self.context.current_time);
+
+        // Components and widgets not drawn last frame lose their state
+        self.component_states.sweep();
+        self.transitions.sweep();
+        self.masks.sweep();
+        self.pan_zooms.sweep();
+
+        // Tokens declared this frame resolve against this Ply's theme
+        theme::activate(&self.theme);
+
         if !self.headless {
+            // Layout stays in logical pixels; the renderer rasterizes at this scale
+            renderer::set_display_scale(macroquad::miniquad::window::dpi_scale());
             self.context.set_layout_dimensions(Dimensions::new(
                 macroquad::prelude::screen_width(),
                 macroquad::prelude::screen_height(),
@@ -567,95 +1111,47 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
             self.context.frame_delta_time = macroquad::prelude::get_frame_time();
         }
 
+        // Apply window size limits and fire `on_resize`
+        self.update_window();
+
         // Update blink timers for text inputs
         self.context.update_text_input_blink_timers();
 
         // Auto-update pointer state from macroquad
         if !self.headless {
             let (mx, my) = macroquad::prelude::mouse_position();
-            let pointer_pos = Vector2::new(mx, my);
-            let is_down = macroquad::prelude::is_mouse_button_down(
-                macroquad::prelude::MouseButton::Left,
-            );
-            let pressed_this_frame = macroquad::prelude::is_mouse_button_pressed(
-                macroquad::prelude::MouseButton::Left,
-            );
-            let released_this_frame = macroquad::prelude::is_mouse_button_released(
-                macroquad::prelude::MouseButton::Left,
-            );
-            
-            match (pressed_this_frame, released_this_frame) {
-                (true, true) => {
-                    if is_down {
-                        self.context.set_pointer_state(pointer_pos, false);
-                        self.context.set_pointer_state(pointer_pos, true);
-                    } else {
-                        self.context.set_pointer_state(pointer_pos, true);
-                        self.context.set_pointer_state(pointer_pos, false);
-                    }
-                }
-                (true, false) => self.context.set_pointer_state(pointer_pos, true),
-                (false, true) => self.context.set_pointer_state(pointer_pos, false),
-                (false, false) => self.context.set_pointer_state(pointer_pos, is_down),
-            }
-
-            {
-                use macroquad::prelude::{is_key_down, KeyCode};
-                let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
-                if shift {
-                    // If shift is held and there's a pending text click, update it
-                    if let Some(ref mut pending) = self.context.pending_text_click {
-                        pending.3 = true;
-                    }
-                }
-            }
-
+            // While files are dragged over the window, the drag stands in for the pointer
+            let file_drag_position = file_drag_position();
+            self.context.file_drag_position = file_drag_position;
             let (scroll_x, scroll_y) = macroquad::prelude::mouse_wheel();
             #[cfg(target_arch = "wasm32")]
             const SCROLL_SPEED: f32 = 1.0;
             #[cfg(not(target_arch = "wasm32"))]
             const SCROLL_SPEED: f32 = 20.0;
-            // Shift+scroll wheel swaps vertical to horizontal scrolling
-            let scroll_shift = {
-                use macroquad::prelude::{is_key_down, KeyCode};
-                is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)
-            };
-            let scroll_delta = if scroll_shift {
-                // Shift held: vertical scroll becomes horizontal
-                Vector2::new(
-                    (scroll_x + scroll_y) * SCROLL_SPEED,
-                    0.0,
-                )
-            } else {
-                Vector2::new(scroll_x * SCROLL_SPEED, scroll_y * SCROLL_SPEED)
-            };
-            let touch_input_active = !macroquad::prelude::touches().is_empty();
-
-            // Text input pointer scrolling (scroll wheel + drag) — consumes scroll if applicable
-            let text_consumed_scroll = self.context.update_text_input_pointer_scroll(
-                scroll_delta,
-                touch_input_active,
-            );
-            self.context.clamp_text_input_scroll();
+            let shift = macroquad::prelude::is_key_down(macroquad::prelude::KeyCode::LeftShift)
+                || macroquad::prelude::is_key_down(macroquad::prelude::KeyCode::RightShift);
+            self.apply_pointer_input(PointerInput {
+                position: file_drag_position.unwrap_or(Vector2::new(mx, my)),
+                is_down: macroquad::prelude::is_mouse_button_down(macroquad::prelude::MouseButton::Left),
+                pressed: macroquad::prelude::is_mouse_button_pressed(macroquad::prelude::MouseButton::Left),
+                released: macroquad::prelude::is_mouse_button_released(macroquad::prelude::MouseButton::Left),
+                scroll: Vector2::new(scroll_x * SCROLL_SPEED, scroll_y * SCROLL_SPEED),
+                shift,
+                touch: !macroquad::prelude::touches().is_empty(),
+            });
 
-            // Only pass scroll to scroll containers if text input didn't consume it
-            let container_scroll = if text_consumed_scroll {
-                Vector2::new(0.0, 0.0)
-            } else {
-                scroll_delta
-            };
-            self.context.update_scroll_containers(
-                true,
-                container_scroll,
-                macroquad::prelude::get_frame_time(),
-                touch_input_active,
-            );
+            self.dropped_files = macroquad::input::get_dropped_files();
+            if !self.dropped_files.is_empty() {
+                self.context.drop_files(&self.dropped_files);
+            }
 
             // Keyboard input handling
             use macroquad::prelude::{is_key_pressed, is_key_down, is_key_released, KeyCode};
 
             let text_input_focused = self.context.is_text_input_focused();
             let current_focused_id = self.context.focused_element_id;
+            self.escape_pressed = is_key_pressed(KeyCode::Escape);
+            self.shift_down = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
 
             // Clear key-repeat state when focus changes (prevents stale
             // repeat from one text input bleeding into another).
@@ -755,6 +1251,20 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
                         self.context.process_text_input_action(engine::TextInputAction::MoveDown { shift });
                         cursor_moved = true;
                     }
+                } else if self.context.is_focused_text_input_numeric() {
+                    // Up/Down step numeric inputs
+                    if key_fires!(KeyCode::Up, 7) {
+                        self.context.process_text_input_action(engine::TextInputAction::Increment);
+                        cursor_moved = true;
+                    }
+                    if key_fires!(KeyCode::Down, 8) {
+                        self.context.process_text_input_action(engine::TextInputAction::Decrement);
+                        cursor_moved = true;
+                    }
+                } else {
+                    // Other single-line inputs follow their `focus_up` / `focus_down` overrides
+                    if is_key_pressed(KeyCode::Up)   { self.context.arrow_focus(engine::ArrowDirection::Up); }
+                    if is_key_pressed(KeyCode::Down) { self.context.arrow_focus(engine::ArrowDirection::Down); }
                 }
 
                 // Non-repeating keys
@@ -777,45 +1287,63 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
                     self.context.process_text_input_action(engine::TextInputAction::Redo);
                     cursor_moved = true;
                 }
+                #[cfg(feature = "text-styling")]
+                if ctrl && is_key_pressed(KeyCode::B) {
+                    self.context.process_text_input_action(engine::TextInputAction::ToggleStyle { tag: "bold".to_string() });
+                }
+                #[cfg(feature = "text-styling")]
+                if ctrl && is_key_pressed(KeyCode::I) {
+                    self.context.process_text_input_action(engine::TextInputAction::ToggleStyle { tag: "italic".to_string() });
+                }
                 if ctrl && is_key_pressed(KeyCode::C) {
                     // Copy selected text to clipboard
                     let elem_id = self.context.focused_element_id;
                     if let Some(state) = self.context.text_edit_states.get(&elem_id) {
-                        #[cfg(feature = "text-styling")]
-                        let selected = state.selected_text_styled();
-                        #[cfg(not(feature = "text-styling"))]
-                        let selected = state.selected_text().to_string();
-                        if !selected.is_empty() {
-                            macroquad::miniquad::window::clipboard_set(&selected);
-                        }
+                        copy_text_input_selection(state);
                     }
                 }
                 if ctrl && is_key_pressed(KeyCode::X) {
                     // Cut: copy then delete selection
                     let elem_id = self.context.focused_element_id;
                     if let Some(state) = self.context.text_edit_states.get(&elem_id) {
-                        #[cfg(feature = "text-styling")]
-                        let selected = state.selected_text_styled();
-                        #[cfg(not(feature = "text-styling"))]
-                        let selected = state.selected_text().to_string();
-                        if !selected.is_empty() {
-                            macroquad::miniquad::window::clipboard_set(&selected);
-                        }
+                        copy_text_input_selection(state);
                     }
                     self.context.process_text_input_action(engine::TextInputAction::Cut);
                     cursor_moved = true;
                 }
                 if ctrl && is_key_pressed(KeyCode::V) {
-                    // Paste from clipboard
-                    if let Some(text) = macroquad::miniquad::window::clipboard_get() {
-                        self.context.process_text_input_action(engine::TextInputAction::Paste { text });
-                        cursor_moved = true;
+                    // Paste an image if the focused input accepts one, otherwise text
+                    #[cfg(feature = "clipboard")]
+                    let pasted_image = match self.context.paste_image_target() {
+                        Some(target) if target == self.context.focused_element_id => {
+                            match clipboard::get_image() {
+                                Some(image) => {
+                                    self.context.paste_image(target, &image);
+                                    true
+                                }
+                                None => false,
+                            }
+                        }
+                        _ => false,
+                    };
+                    #[cfg(not(feature = "clipboard"))]
+                    let pasted_image = false;
+                    if !pasted_image {
+                        if let Some(text) = macroquad::miniquad::window::clipboard_get() {
+                            self.context.process_text_input_action(engine::TextInputAction::Paste { text });
+                            cursor_moved = true;
+                        }
                     }
                 }
 
-                // Escape unfocuses the text input
+                // Escape unfocuses the text input, or clears it first with `clear_on_escape`
                 if is_key_pressed(KeyCode::Escape) {
-                    self.context.clear_focus();
+                    if self.context.should_focused_text_input_clear_on_escape() {
+                        self.context.process_text_input_action(engine::TextInputAction::Clear);
+                        cursor_moved = true;
+                    } else {
+                        self.context.clear_focus();
+                    }
                 }
 
                 // Clear repeat state if the tracked key was released
@@ -839,7 +1367,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
                 // Drain character input queue
                 while let Some(ch) = macroquad::prelude::get_char_pressed() {
                     // Filter out control characters and Ctrl-key combos
-                    if !ch.is_control() && ((ctrl && right_alt) || !ctrl) {
+                    if !ch.is_control() && (right_alt || !ctrl) {
                         self.context.process_text_input_char(ch);
                         cursor_moved = true;
                     }
@@ -858,8 +1386,27 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
                 if is_key_pressed(KeyCode::Up)    { self.context.arrow_focus(engine::ArrowDirection::Up); }
                 if is_key_pressed(KeyCode::Down)  { self.context.arrow_focus(engine::ArrowDirection::Down); }
 
-                let activate_pressed = is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space);
-                let activate_released = is_key_released(KeyCode::Enter) || is_key_released(KeyCode::Space);
+                // Copy selected static text
+                let ctrl = shortcut_modifier_down();
+                if ctrl && is_key_pressed(KeyCode::C) {
+                    if let Some(selected) = self.context.selected_static_text() {
+                        macroquad::miniquad::window::clipboard_set(&selected);
+                    }
+                }
+
+                // Paste an image into the focused or hovered element that accepts one
+                #[cfg(feature = "clipboard")]
+                if ctrl && is_key_pressed(KeyCode::V) {
+                    if let Some(target) = self.context.paste_image_target() {
+                        if let Some(image) = clipboard::get_image() {
+                            self.context.paste_image(target, &image);
+                        }
+                    }
+                }
+
+                let activation_keys = self.context.activation_keys(self.context.focused_element_id);
+                let activate_pressed = activation_keys.iter().any(|&key| is_key_pressed(key));
+                let activate_released = activation_keys.iter().any(|&key| is_key_released(key));
                 self.context.handle_keyboard_activation(activate_pressed, activate_released);
             }
         }
@@ -880,12 +1427,95 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
             }
         }
 
+        // Pointer cursor while hovering a text link, else the one a widget asked for last frame
+        let requested_cursor = self.requested_cursor.take();
+        {
+            use macroquad::miniquad::{window::set_mouse_cursor, CursorIcon};
+            let cursor = if self.context.hovered_link().is_some() {
+                CursorIcon::Pointer
+            } else {
+                requested_cursor.unwrap_or(CursorIcon::Default)
+            };
+            if cursor != self.cursor {
+                // Headless instances only track it, for other windowing to show
+                if !self.headless {
+                    set_mouse_cursor(cursor);
+                }
+                self.cursor = cursor;
+            }
+        }
+
         self.context.begin_layout();
         Ui {
             ply: self,
         }
     }
 
+    /// Feeds the pointer and scroll input gathered since the last frame into
+    /// hovering, pressing and scrolling. Called once per frame.
+    pub(crate) fn apply_pointer_input(&mut self, input: PointerInput) {
+        let PointerInput { position, is_down, pressed, released, scroll, shift, touch } = input;
+        match (pressed, released) {
+            (true, true) => {
+                if is_down {
+                    self.context.set_pointer_state(position, false);
+                    self.context.set_pointer_state(position, true);
+                } else {
+                    self.context.set_pointer_state(position, true);
+                    self.context.set_pointer_state(position, false);
+                }
+            }
+            (true, false) => self.context.set_pointer_state(position, true),
+            (false, true) => self.context.set_pointer_state(position, false),
+            (false, false) => self.context.set_pointer_state(position, is_down),
+        }
+
+        if shift {
+            // If shift is held and there's a pending text click, update it
+            if let Some(ref mut pending) = self.context.pending_text_click {
+                pending.3 = true;
+            }
+        }
+
+        // Shift+scroll wheel swaps vertical to horizontal scrolling
+        let scroll_delta = if shift {
+            Vector2::new(scroll.x + scroll.y, 0.0)
+        } else {
+            scroll
+        };
+
+        // Scrolling over a focused numeric input steps its value
+        let numeric_consumed_scroll = scroll.y != 0.0
+            && self.context.is_focused_text_input_numeric()
+            && self
+                .context
+                .get_pointer_over_ids()
+                .iter()
+                .any(|id| id.id == self.context.focused_element_id);
+        if numeric_consumed_scroll {
+            let action = if scroll.y > 0.0 {
+                engine::TextInputAction::Increment
+            } else {
+                engine::TextInputAction::Decrement
+            };
+            self.context.process_text_input_action(action);
+        }
+
+        // Text input pointer scrolling (scroll wheel + drag) — consumes scroll if applicable
+        let text_consumed_scroll = numeric_consumed_scroll
+            || self.context.update_text_input_pointer_scroll(scroll_delta, touch);
+        self.context.clamp_text_input_scroll();
+
+        // Only pass scroll to scroll containers if text input didn't consume it
+        let container_scroll = if text_consumed_scroll {
+            Vector2::new(0.0, 0.0)
+        } else {
+            scroll_delta
+        };
+        let delta_time = self.context.frame_delta_time;
+        self.context.update_scroll_containers(true, container_scroll, delta_time, touch);
+    }
+
     /// Create a new Ply engine with the given default font.
     pub async fn new(default_font: &'static renderer::FontAsset) -> Self {
         renderer::FontManager::load_default(default_font).await;
@@ -902,13 +1532,46 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
             text_input_repeat_last: 0.0,
             text_input_repeat_focus_id: 0,
             was_text_input_focused: false,
+            cursor: macroquad::miniquad::CursorIcon::Default,
+            requested_cursor: None,
+            escape_pressed: false,
+            shift_down: false,
+            dropped_files: Vec::new(),
+            window_state: window::WindowState::default(),
+            theme: std::rc::Rc::default(),
+            component_states: component::ComponentStates::default(),
+            transitions: transition::Transitions::default(),
+            masks: mask::Masks::default(),
+            pan_zooms: pan_zoom::PanZooms::default(),
+            toasts: toast::Toasts::default(),
+            frame_stats: profiler::FrameStats::default(),
+            edge_antialiasing: renderer::EdgeAntialiasing::Off,
+            profiler_hud: false,
+            render_diff: None,
+            last_frame_hash: None,
+            frame_changed: true,
+            diagnostics_handler: None,
+            #[cfg(feature = "log")]
+            logged_diagnostics: Vec::new(),
+            accessibility_overlay: false,
+            announcements: Vec::new(),
+            #[cfg(feature = "stylesheet")]
+            stylesheet_state: stylesheet::StylesheetState::default(),
+            #[cfg(feature = "templates")]
+            template_files: template::TemplateFiles::default(),
+            #[cfg(feature = "scripting")]
+            script_files: script::ScriptFiles::default(),
             #[cfg(all(feature = "a11y", target_arch = "wasm32"))]
             web_a11y_state: accessibility_web::WebAccessibilityState::default(),
             #[cfg(all(feature = "a11y", not(target_arch = "wasm32")))]
             native_a11y_state: accessibility_native::NativeAccessibilityState::default(),
         };
         ply.context.default_font_key = default_font.key();
+        #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+        ply.set_parallel_measure_text_function(renderer::create_measure_text_function());
+        #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
         ply.set_measure_text_function(renderer::create_measure_text_function());
+        ply.set_text_baseline_function(renderer::create_text_baseline_function());
         ply
     }
 
@@ -917,14 +1580,45 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
     /// Use [`Ply::set_measure_text_function`] to configure text measurement
     /// before rendering any text elements.
     pub fn new_headless(dimensions: Dimensions) -> Self {
+        let mut context = engine::PlyContext::new(dimensions);
+        context.headless = true;
         Self {
-            context: engine::PlyContext::new(dimensions),
+            context,
             headless: true,
             text_input_repeat_key: 0,
             text_input_repeat_first: 0.0,
             text_input_repeat_last: 0.0,
             text_input_repeat_focus_id: 0,
             was_text_input_focused: false,
+            cursor: macroquad::miniquad::CursorIcon::Default,
+            requested_cursor: None,
+            escape_pressed: false,
+            shift_down: false,
+            dropped_files: Vec::new(),
+            window_state: window::WindowState::default(),
+            theme: std::rc::Rc::default(),
+            component_states: component::ComponentStates::default(),
+            transitions: transition::Transitions::default(),
+            masks: mask::Masks::default(),
+            pan_zooms: pan_zoom::PanZooms::default(),
+            toasts: toast::Toasts::default(),
+            frame_stats: profiler::FrameStats::default(),
+            edge_antialiasing: renderer::EdgeAntialiasing::Off,
+            profiler_hud: false,
+            render_diff: None,
+            last_frame_hash: None,
+            frame_changed: true,
+            diagnostics_handler: None,
+            #[cfg(feature = "log")]
+            logged_diagnostics: Vec::new(),
+            accessibility_overlay: false,
+            announcements: Vec::new(),
+            #[cfg(feature = "stylesheet")]
+            stylesheet_state: stylesheet::StylesheetState::default(),
+            #[cfg(feature = "templates")]
+            template_files: template::TemplateFiles::default(),
+            #[cfg(feature = "scripting")]
+            script_files: script::ScriptFiles::default(),
             #[cfg(all(feature = "a11y", target_arch = "wasm32"))]
             web_a11y_state: accessibility_web::WebAccessibilityState::default(),
             #[cfg(all(feature = "a11y", not(target_arch = "wasm32")))]
@@ -937,6 +1631,40 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
         self.context.pointer_over(cfg.into())
     }
 
+    /// Files dropped onto the window this frame, whether or not an element took them.
+    pub fn dropped_files(&self) -> &[macroquad::DroppedFile] {
+        &self.dropped_files
+    }
+
+    /// Replaces the theme that `token!` resolves against. Elements pick up
+    /// the new tokens the next time they are declared.
+    pub fn set_theme(&mut self, theme: theme::Theme) {
+        self.theme = std::rc::Rc::new(theme);
+        theme::activate(&self.theme);
+    }
+
+    /// Returns the current theme.
+    pub fn theme(&self) -> &theme::Theme {
+        &self.theme
+    }
+
+    /// Physical pixels per logical pixel on the current display, e.g. `2.0`
+    /// on retina screens. Layout sizes are logical, see [`renderer::display_scale`].
+    pub fn display_scale(&self) -> f32 {
+        renderer::display_scale()
+    }
+
+    /// Sets how rounded corners, borders and rotated rectangles smooth their
+    /// edges. Off by default.
+    pub fn set_edge_antialiasing(&mut self, mode: renderer::EdgeAntialiasing) {
+        self.edge_antialiasing = mode;
+    }
+
+    /// Returns the edge anti-aliasing set with [`Ply::set_edge_antialiasing`].
+    pub fn edge_antialiasing(&self) -> renderer::EdgeAntialiasing {
+        self.edge_antialiasing
+    }
+
     /// Z-sorted list of element IDs that the cursor is currently over
     pub fn pointer_over_ids(&self) -> Vec<Id> {
         self.context.get_pointer_over_ids().to_vec()
@@ -954,14 +1682,56 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
         ));
     }
 
+    /// Measures text with a backend shared through a `RefCell`, so layout
+    /// matches what it draws, as the winit adapters do. It's borrowed while
+    /// measuring, so [`show_with`](Ply::show_with) can't be given the same
+    /// backend: use a measure function that doesn't borrow it there.
+    pub fn measure_text_with<R>(&mut self, backend: std::rc::Rc<std::cell::RefCell<R>>)
+    where
+        R: renderer::Renderer<CustomElementData> + 'static,
+    {
+        self.set_measure_text_function(move |text, config| backend.borrow().measure_text(text, config));
+    }
+
+    /// Set a thread-safe callback for text measurement, so long texts are
+    /// measured and many text elements wrapped across threads.
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    pub fn set_parallel_measure_text_function<F>(&mut self, callback: F)
+    where
+        F: Fn(&str, &TextConfig) -> Dimensions + Send + Sync + 'static,
+    {
+        self.context.set_parallel_measure_text_function(std::sync::Arc::new(callback));
+    }
+
+    /// Set the callback reporting the distance from the top of a text line to its baseline.
+    ///
+    /// Used by [`AlignY::Baseline`](crate::align::AlignY::Baseline) and baseline-aligned
+    /// inline atoms. Without one, the baseline is estimated from the line height.
+    pub fn set_text_baseline_function<F>(&mut self, callback: F)
+    where
+        F: Fn(&TextConfig) -> f32 + 'static,
+    {
+        self.context.set_text_baseline_function(Box::new(callback));
+    }
+
+    /// Returns the problems found while building the last evaluated frame.
+    pub fn diagnostics(&self) -> &[diagnostics::Diagnostic] {
+        &self.context.diagnostics
+    }
+
+    /// Calls `handler` with every problem found, once per frame, at `eval()`.
+    pub fn set_diagnostics_handler(&mut self, handler: impl FnMut(&diagnostics::Diagnostic) + 'static) {
+        self.diagnostics_handler = Some(Box::new(handler));
+    }
+
     /// Sets the maximum number of elements that ply supports
     /// **Use only if you know what you are doing or you're getting errors from ply**
     pub fn max_element_count(&mut self, max_element_count: u32) {
         self.context.set_max_element_count(max_element_count as i32);
     }
 
-    /// Sets the capacity of the cache used for text in the measure text function
-    /// **Use only if you know what you are doing or you're getting errors from ply**
+    /// Sets how many measured words the text cache holds before evicting the
+    /// least recently used texts
     pub fn max_measure_text_cache_word_count(&mut self, count: u32) {
         self.context.set_max_measure_text_cache_word_count(count as i32);
     }
@@ -981,6 +1751,126 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
         self.context.is_debug_mode_enabled()
     }
 
+    /// Returns where the last evaluated frame's time went, with element,
+    /// render command and text cache counts.
+    pub fn frame_stats(&self) -> profiler::FrameStats {
+        self.frame_stats
+    }
+
+    /// Returns the text measurement cache's size and its hit, miss and
+    /// eviction totals.
+    pub fn text_cache_stats(&self) -> profiler::TextCacheStats {
+        profiler::TextCacheStats {
+            capacity: self.context.max_measure_text_cache_word_count.max(0) as u32,
+            ..self.context.text_cache_stats
+        }
+    }
+
+    /// Hashes every frame's render commands so `frame_changed()` can tell
+    /// whether a frame looks any different from the one before it.
+    pub fn set_change_detection(&mut self, enable: bool) {
+        if enable != self.last_frame_hash.is_some() {
+            self.last_frame_hash = enable.then_some(0);
+            self.frame_changed = true;
+        }
+    }
+
+    /// Returns whether the last `eval()` produced anything different from
+    /// the frame before. Always `true` unless `set_change_detection(true)`,
+    /// and while shaders on `ply_time`, `ply_mouse` or `ply_resolution` or
+    /// animated styled text are on screen. An idle app can then redraw the
+    /// last frame instead of rendering a new one, see [`render_diff`].
+    pub fn frame_changed(&self) -> bool {
+        self.frame_changed
+    }
+
+    /// Reuses the last frame's element sizes and text wrapping when nothing
+    /// that affects layout was declared differently. On by default; colors,
+    /// borders and other purely visual changes keep the cache.
+    pub fn set_layout_cache(&mut self, enable: bool) {
+        self.context.layout_cache_enabled = enable;
+    }
+
+    /// Compares every frame's render commands with the previous frame's and
+    /// outlines what changed, to find layout that never settles.
+    pub fn set_render_diff(&mut self, enable: bool) {
+        if enable != self.render_diff.is_some() {
+            self.render_diff = enable.then(render_diff::RenderDiff::default);
+        }
+    }
+
+    /// Returns the render commands that changed since the previous frame, as
+    /// of the last `eval()`. Empty unless `set_render_diff(true)`.
+    pub fn render_changes(&self) -> &[render_diff::RenderChange] {
+        self.render_diff.as_ref().map_or(&[], |diff| &diff.changes)
+    }
+
+    /// Shows the last frame's stats in the top-left corner.
+    pub fn set_profiler_hud(&mut self, enable: bool) {
+        self.profiler_hud = enable;
+    }
+
+    /// Starts or stops picking in the debug view, like its "Pick" button:
+    /// hovering the UI highlights the element under the pointer with its
+    /// padding and child gaps, and clicking selects it instead of pressing it.
+    pub fn set_debug_picking(&mut self, enable: bool) {
+        self.context.set_debug_picking(enable);
+    }
+
+    /// Outlines every element in the accessibility tree: orange when
+    /// focused, blue when focusable and green otherwise.
+    pub fn set_accessibility_overlay(&mut self, enable: bool) {
+        self.accessibility_overlay = enable;
+    }
+
+    /// Turns high-contrast mode on with the given palette, or off with `None`.
+    ///
+    /// Backgrounds, borders and text take the palette's colors, and the
+    /// focus ring is always shown, even after mouse clicks and on elements
+    /// with `disable_ring()`. Images keep their colors.
+    pub fn set_high_contrast(&mut self, palette: Option<accessibility::HighContrastPalette>) {
+        self.context.high_contrast = palette;
+    }
+
+    /// Returns the high-contrast palette, if high-contrast mode is on.
+    pub fn high_contrast(&self) -> Option<accessibility::HighContrastPalette> {
+        self.context.high_contrast
+    }
+
+    /// Sets whether arrow keys move focus to the nearest focusable element
+    /// in that direction when no `focus_left`/`focus_right`/... override or
+    /// focus group applies. Enabled by default.
+    pub fn set_spatial_navigation(&mut self, enable: bool) {
+        self.context.spatial_navigation = enable;
+    }
+
+    /// Has screen readers speak `text` without it being on screen, e.g.
+    /// "3 results found". `Polite` waits for current speech to finish,
+    /// `Assertive` interrupts it.
+    pub fn announce(&mut self, text: impl Into<String>, politeness: accessibility::LiveRegionMode) {
+        if politeness != accessibility::LiveRegionMode::Off {
+            self.announcements.push((text.into(), politeness));
+        }
+    }
+
+    /// Returns the elements screen readers see after the last `eval()`, in
+    /// the order they are announced, with their roles, labels, states and bounds.
+    pub fn accessibility_tree(&self) -> Vec<accessibility::AccessibilityNode> {
+        self.context
+            .accessibility_element_order
+            .iter()
+            .filter_map(|&elem_id| {
+                let config = self.context.accessibility_configs.get(&elem_id)?;
+                let bounds = self.context.get_element_data(Id {
+                    id: elem_id,
+                    ..Default::default()
+                })?;
+                let focused = elem_id == self.context.focused_element_id;
+                Some(accessibility::AccessibilityNode::new(elem_id, config, bounds, focused))
+            })
+            .collect()
+    }
+
     /// Enables or disables culling
     pub fn set_culling(&mut self, enable: bool) {
         self.context.set_culling_enabled(enable);
@@ -1029,12 +1919,43 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
         self.context.clear_focus();
     }
 
+    /// Traps Tab and arrow key focus movement inside the element with the
+    /// given ID, e.g. while a modal dialog is open. Scopes nest.
+    pub fn push_focus_scope(&mut self, root_id: impl Into<Id>) {
+        self.context.push_focus_scope(root_id.into().id);
+    }
+
+    /// Removes the innermost focus scope and refocuses whatever was focused
+    /// when it was pushed.
+    pub fn pop_focus_scope(&mut self) {
+        self.context.pop_focus_scope();
+    }
+
+    /// Returns `false` if the text input's validator rejected its current text.
+    ///
+    /// Inputs without a validator are always valid.
+    pub fn is_valid(&self, id: impl Into<Id>) -> bool {
+        self.context.text_input_error(id.into().id).is_none()
+    }
+
+    /// Returns the error message from the text input's validator, if it failed.
+    pub fn validation_error(&self, id: impl Into<Id>) -> Option<&str> {
+        self.context.text_input_error(id.into().id)
+    }
+
     /// Returns the text value of a text input element.
     /// Returns an empty string if the element is not a text input or doesn't exist.
     pub fn get_text_value(&self, id: impl Into<Id>) -> &str {
         self.context.get_text_value(id.into().id)
     }
 
+    /// Returns the content of a text input element as [`StyledText`],
+    /// including any spans added in a [`rich_text`](text_input::TextInputBuilder::rich_text) input.
+    #[cfg(feature = "text-styling")]
+    pub fn get_styled_text(&self, id: impl Into<Id>) -> text_styling::StyledText {
+        text_styling::StyledText::from_markup(self.context.get_text_value(id.into().id))
+    }
+
     /// Sets the text value of a text input element.
     pub fn set_text_value(&mut self, id: impl Into<Id>, value: &str) {
         self.context.set_text_value(id.into().id, value);
@@ -1063,6 +1984,54 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
         self.context.set_selection(id.into().id, anchor, cursor);
     }
 
+    /// Returns the selected range of a text input element, or None if nothing is selected.
+    /// Positions are character indices (visual positions with `text-styling`).
+    pub fn get_text_selection(&self, id: impl Into<Id>) -> Option<std::ops::Range<usize>> {
+        self.context
+            .get_selection_range(id.into().id)
+            .map(|(start, end)| start..end)
+    }
+
+    /// Selects `range` in a text input element, placing the cursor at its end.
+    /// An empty range just moves the cursor. Positions are clamped to the text length.
+    pub fn set_text_selection(&mut self, id: impl Into<Id>, range: std::ops::Range<usize>) {
+        let id = id.into().id;
+        if range.is_empty() {
+            self.context.set_cursor_pos(id, range.start);
+        } else {
+            self.context.set_selection(id, range.start, range.end);
+        }
+    }
+
+    /// Inserts text at the cursor of a text input element, replacing the selection.
+    /// Useful for autocomplete and mention insertion. Fires `on_changed` and can be undone.
+    pub fn insert_text_at_cursor(&mut self, id: impl Into<Id>, text: &str) {
+        self.context.insert_text_at_cursor(id.into().id, text);
+    }
+
+    /// Returns the text currently selected in a [`selectable`](TextConfig::selectable) text element.
+    pub fn selected_text(&self) -> Option<String> {
+        self.context.selected_static_text()
+    }
+
+    /// Returns the URL of the `{link=URL|...}` span under the pointer, if any.
+    #[cfg(feature = "text-styling")]
+    pub fn hovered_link(&self) -> Option<&str> {
+        self.context.hovered_link()
+    }
+
+    /// Returns how the pan/zoom container with the given ID shows its
+    /// content, or `None` if it wasn't drawn.
+    pub fn pan_zoom_view(&self, id: impl Into<Id>) -> Option<pan_zoom::PanZoomView> {
+        self.pan_zooms.view(id.into().id)
+    }
+
+    /// Sets how the pan/zoom container with the given ID shows its content,
+    /// e.g. to reset or fit it.
+    pub fn set_pan_zoom_view(&mut self, id: impl Into<Id>, view: pan_zoom::PanZoomView) {
+        self.pan_zooms.set_view(id.into().id, view);
+    }
+
     /// Returns true if the given element is currently pressed.
     pub fn is_pressed(&self, id: impl Into<Id>) -> bool {
         self.context.is_element_pressed(id.into().id)
@@ -1079,10 +2048,57 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
     }
 
     /// Returns the bounding box of the element with the given ID, if it exists.
+    /// While declaring a frame, this is the box from the last one.
     pub fn bounding_box(&self, id: impl Into<Id>) -> Option<math::BoundingBox> {
         self.context.get_element_data(id.into())
     }
 
+    /// Returns the z-index the element with the given ID was drawn at: that
+    /// of the floating element it's in, or `0` in the regular layout.
+    pub fn z_index(&self, id: impl Into<Id>) -> Option<i16> {
+        self.context.get_element_z_index(id.into())
+    }
+
+    /// Replaces a floating element's declared z-index whenever it's declared,
+    /// e.g. to bring a clicked window to the front. Kept until
+    /// [`reset_z_index`](Self::reset_z_index) or a frame without the element.
+    pub fn set_z_index(&mut self, id: impl Into<Id>, z_index: i16) {
+        self.context.set_z_index(id.into(), Some(z_index));
+    }
+
+    /// Goes back to the z-index the floating element declares.
+    pub fn reset_z_index(&mut self, id: impl Into<Id>) {
+        self.context.set_z_index(id.into(), None);
+    }
+
+    /// Reads back the pixels drawn to the window this frame.
+    ///
+    /// Call after `show()` and before `next_frame()`. Needs a window, so it
+    /// can't be used on headless instances.
+    pub fn capture_frame(&self) -> capture::ImageBuffer {
+        capture::ImageBuffer::from_screen()
+    }
+
+    /// Reads back the pixels under the element with the given ID, as laid out
+    /// in the last `show()`. Returns `None` if the element doesn't exist.
+    pub fn capture_element(&self, id: impl Into<Id>) -> Option<capture::ImageBuffer> {
+        let bb = self.context.get_element_data(id.into())?;
+        let scale = renderer::display_scale();
+        let frame = self.capture_frame();
+        Some(frame.crop(
+            (bb.x.max(0.0) * scale).round() as u16,
+            (bb.y.max(0.0) * scale).round() as u16,
+            (bb.width * scale).round() as u16,
+            (bb.height * scale).round() as u16,
+        ))
+    }
+
+    /// Returns true if text directly inside the given element was cut off by
+    /// [`TextConfig::max_lines`](text::TextConfig::max_lines) in the last layout.
+    pub fn is_text_clamped(&self, id: impl Into<Id>) -> bool {
+        self.context.is_text_clamped(id.into())
+    }
+
     /// Returns scroll container state for the element with the given ID, if it is a scroll container.
     pub fn scroll_container_data(&self, id: impl Into<Id>) -> Option<engine::ScrollContainerData> {
         let data = self.context.get_scroll_container_data(id.into());
@@ -1100,16 +2116,101 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
 
     /// Evaluate the layout and return all render commands.
     pub fn eval(&mut self) -> Vec<RenderCommand<CustomElementData>> {
-        // Clean up stale networking entries (feature-gated)
+        // Clean up stale networking and dialog entries (feature-gated)
         #[cfg(feature = "net")]
         net::NET_MANAGER.lock().unwrap().clean();
+        #[cfg(feature = "dialogs")]
+        dialogs::DIALOG_MANAGER.lock().unwrap().clean();
+
+        // Toasts float above everything declared this frame
+        self.draw_toasts();
+        if self.profiler_hud {
+            self.draw_profiler_hud();
+        }
 
-        let commands = self.context.end_layout();
+        self.context.end_layout();
         let mut result = Vec::new();
-        for cmd in commands {
-            result.push(RenderCommand::from_engine_render_command(cmd));
+        for cmd in self.context.render_commands() {
+            result.push(RenderCommand::from_engine_render_command(cmd, self.context.shaders(cmd.effects)));
+        }
+        render_diff::assign_group_capture_keys(&mut result);
+        self.frame_stats = self.context.frame_stats;
+        let pointer = self.context.pointer_position();
+        renderer::set_shader_globals(renderer::ShaderGlobals {
+            time: self.context.current_time as f32,
+            resolution: (self.context.layout_dimensions.width, self.context.layout_dimensions.height),
+            mouse: (pointer.x, pointer.y),
+        });
+        self.context.diagnostics.extend(diagnostics::take_pending());
+        if let Some(handler) = &mut self.diagnostics_handler {
+            for diagnostic in &self.context.diagnostics {
+                handler(diagnostic);
+            }
+        }
+        #[cfg(feature = "log")]
+        {
+            // Only what's new, so a lasting problem isn't logged every frame
+            for diagnostic in &self.context.diagnostics {
+                if !self.logged_diagnostics.contains(diagnostic) {
+                    log::warn!(target: "ply", "{diagnostic}");
+                }
+            }
+            self.logged_diagnostics.clone_from(&self.context.diagnostics);
+        }
+
+        if let Some(diff) = &mut self.render_diff {
+            diff.update(&result);
+            for change in &diff.changes {
+                result.push(RenderCommand {
+                    bounding_box: change.bounds,
+                    config: render_commands::RenderCommandConfig::Border(render_commands::Border {
+                        color: change.kind.color(),
+                        corner_radii: layout::CornerRadius::default().into(),
+                        width: render_commands::BorderWidth {
+                            left: 1,
+                            right: 1,
+                            top: 1,
+                            bottom: 1,
+                            between_children: 0,
+                        },
+                        position: elements::BorderPosition::Inside,
+                    }),
+                    id: change.id,
+                    z_index: i16::MAX,
+                    effects: Vec::new(),
+                    shape_rotation: None,
+                });
+            }
+        }
+
+        if self.accessibility_overlay {
+            for node in self.accessibility_tree() {
+                let width = if node.focused { 2 } else { 1 };
+                result.push(RenderCommand {
+                    bounding_box: node.bounds,
+                    config: render_commands::RenderCommandConfig::Border(render_commands::Border {
+                        color: node.overlay_color(),
+                        corner_radii: layout::CornerRadius::default().into(),
+                        width: render_commands::BorderWidth {
+                            left: width,
+                            right: width,
+                            top: width,
+                            bottom: width,
+                            between_children: 0,
+                        },
+                        position: elements::BorderPosition::Inside,
+                    }),
+                    id: node.id,
+                    z_index: i16::MAX,
+                    effects: Vec::new(),
+                    shape_rotation: None,
+                });
+            }
         }
 
+        #[cfg_attr(not(feature = "a11y"), allow(unused_variables))]
+        let announcements = std::mem::take(&mut self.announcements);
+
         // Sync the hidden DOM accessibility tree (web/WASM only)
         #[cfg(all(feature = "a11y", target_arch = "wasm32"))]
         {
@@ -1122,6 +2223,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
                 &self.context.accessibility_element_order,
                 self.context.focused_element_id,
                 self.context.layout_dimensions,
+                &announcements,
             );
         }
 
@@ -1137,6 +2239,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
                 &self.context.accessibility_element_order,
                 self.context.focused_element_id,
                 self.context.layout_dimensions,
+                &announcements,
             );
             for action in a11y_actions {
                 match action {
@@ -1150,22 +2253,150 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData
             }
         }
 
+        if let Some(last_hash) = &mut self.last_frame_hash {
+            let hash = render_diff::frame_hash(&result);
+            self.frame_changed = hash != *last_hash || render_diff::frame_animates(&result);
+            *last_hash = hash;
+        }
+
         result
     }
 
-    /// Evaluate the layout and render all commands.
+    /// Evaluate the layout and render all commands with the macroquad backend,
+    /// [`MacroquadRenderer`](renderer::MacroquadRenderer).
     pub async fn show(
         &mut self,
         handle_custom_command: impl Fn(&RenderCommand<CustomElementData>),
     ) {
         let commands = self.eval();
-        renderer::render(commands, handle_custom_command).await;
+        let edge_antialiasing = self.edge_antialiasing;
+        self.timed_render(renderer::render_antialiased(commands, edge_antialiasing, handle_custom_command)).await;
+    }
+
+    /// Evaluates the layout and draws it with any [`Renderer`](renderer::Renderer),
+    /// such as the software renderer or your own backend.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut software = SoftwareRenderer::new();
+    /// ply.set_measure_text_function(software.measure_text_function());
+    ///
+    /// let mut ui = ply.begin();
+    /// ui.text("Hello", |t| t.font_size(16));
+    /// ui.show_with(&mut software).await;
+    /// let image = software.take_frame();
+    /// ```
+    pub async fn show_with<R: renderer::Renderer<CustomElementData>>(&mut self, backend: &mut R) {
+        let commands = self.eval();
+        self.draw_with(&commands, backend).await;
+    }
+
+    /// Draws evaluated `commands` with `backend`, timing it into the frame stats.
+    pub(crate) async fn draw_with<R: renderer::Renderer<CustomElementData>>(
+        &mut self,
+        commands: &[RenderCommand<CustomElementData>],
+        backend: &mut R,
+    ) {
+        backend.set_edge_antialiasing(self.edge_antialiasing);
+        let dimensions = self.context.layout_dimensions;
+        self.timed_render(renderer::render_with(backend, commands, dimensions)).await;
+    }
+
+    /// Awaits `render`, timing it into the frame stats.
+    async fn timed_render(&mut self, render: impl std::future::Future<Output = ()>) {
+        let render_start = profiler::now_ms();
+        // Entered spans mustn't be held across an await
+        #[cfg(feature = "tracing")]
+        let render = tracing::Instrument::instrument(render, tracing::debug_span!("ply::render"));
+        render.await;
+        self.frame_stats.rendering_ms = profiler::now_ms() - render_start;
+    }
+
+    /// Evaluates the layout at `size` and renders it into a texture instead
+    /// of the window, for in-world screens or cached static panels.
+    ///
+    /// Pointer input still arrives in window coordinates.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut ui = panel.begin();
+    /// ui.text("Reactor online", |t| t.font_size(32));
+    /// let screen = ui.eval_to_texture((512.0, 256.0), |_| {}).await;
+    /// ```
+    pub async fn eval_to_texture(
+        &mut self,
+        size: impl Into<Dimensions>,
+        handle_custom_command: impl Fn(&RenderCommand<CustomElementData>),
+    ) -> macroquad::prelude::Texture2D {
+        let size = size.into();
+        self.context.set_root_dimensions(size);
+        let commands = self.eval();
+        renderer::render_offscreen_antialiased(commands, size.width, size.height, self.edge_antialiasing, handle_custom_command).await
+    }
+}
+
+/// Copies a text input's selection. When the selection is styled and the
+/// `clipboard` feature is on, its styles are copied as HTML as well.
+/// Pointer and scroll input since the last frame, from macroquad or another
+/// event source.
+pub(crate) struct PointerInput {
+    pub(crate) position: Vector2,
+    pub(crate) is_down: bool,
+    pub(crate) pressed: bool,
+    pub(crate) released: bool,
+    /// In pixels, before shift turns it horizontal.
+    pub(crate) scroll: Vector2,
+    pub(crate) shift: bool,
+    /// Whether a finger is on the screen, for touch drag scrolling.
+    pub(crate) touch: bool,
+}
+
+fn copy_text_input_selection(state: &text_input::TextEditState) {
+    #[cfg(feature = "text-styling")]
+    let selected = state.selected_text_styled();
+    #[cfg(not(feature = "text-styling"))]
+    let selected = state.selected_text().to_string();
+    if selected.is_empty() {
+        return;
+    }
+    #[cfg(all(feature = "clipboard", feature = "text-styling"))]
+    if let Some(markup) = state.selected_markup_styled() {
+        if markup != text_input::styling::escape_str(&selected) {
+            clipboard::set_html(&clipboard::styled_to_html(&markup), &selected);
+            return;
+        }
     }
+    macroquad::miniquad::window::clipboard_set(&selected);
+}
+
+/// Whether the platform's shortcut modifier is held: Cmd on macOS, Ctrl elsewhere.
+fn shortcut_modifier_down() -> bool {
+    use macroquad::input::{is_key_down, KeyCode};
+    if cfg!(target_os = "macos") {
+        is_key_down(KeyCode::LeftSuper) || is_key_down(KeyCode::RightSuper)
+    } else {
+        is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl)
+    }
+}
+
+/// Position of files being dragged over the window from the OS, if any.
+/// Only the web reports drags; native platforms report just the drop.
+fn file_drag_position() -> Option<Vector2> {
+    #[cfg(target_arch = "wasm32")]
+    unsafe {
+        if ply_file_drag_active() {
+            return Some(Vector2::new(ply_file_drag_x(), ply_file_drag_y()));
+        }
+    }
+    None
 }
 
 #[cfg(target_arch = "wasm32")]
 extern "C" {
     fn ply_show_virtual_keyboard(show: bool);
+    fn ply_file_drag_active() -> bool;
+    fn ply_file_drag_x() -> f32;
+    fn ply_file_drag_y() -> f32;
 }
 
 #[cfg(test)]
@@ -1689,6 +2920,73 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_floating_attached_to_pointer_follows_it() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let frame = |ply: &mut Ply<()>, pointer: Vector2| {
+            let mut ui = ply.begin();
+            ui.ply.context.set_pointer_state(pointer, false);
+            ui.element().id("page").width(grow!()).height(grow!()).children(|ui| {
+                ui.element()
+                    .id("tip")
+                    .width(fixed!(60.0))
+                    .height(fixed!(20.0))
+                    .floating(|f| f.attach_pointer().anchor((crate::align::AlignX::Left, crate::align::AlignY::Bottom), (crate::align::AlignX::Left, crate::align::AlignY::Top)).offset((12.0, -4.0)))
+                    .background_color(0x222222)
+                    .empty();
+            });
+            ui.eval();
+            ply.bounding_box("tip").unwrap()
+        };
+
+        let tip = frame(&mut ply, Vector2::new(100.0, 120.0));
+        assert_eq!((tip.x, tip.y, tip.width, tip.height), (112.0, 96.0, 60.0, 20.0));
+        let tip = frame(&mut ply, Vector2::new(250.0, 40.0));
+        assert_eq!((tip.x, tip.y), (262.0, 16.0));
+    }
+
+    #[test]
+    fn test_layers_and_z_index_overrides() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.element().id("page").width(grow!()).height(grow!()).children(|ui| {
+                for (name, color) in [("palette", 0x336699), ("inspector", 0x993366)] {
+                    ui.element()
+                        .id(name)
+                        .width(fixed!(50.0))
+                        .height(fixed!(50.0))
+                        .floating(|f| f.attach_root().layer(elements::Layer::Overlay))
+                        .background_color(color)
+                        .empty();
+                }
+            });
+            let commands = ui.eval();
+            commands.iter().map(|command| command.id).collect::<Vec<_>>()
+        };
+        let (palette, inspector) = (Id::new("palette").id, Id::new("inspector").id);
+
+        let order = frame(&mut ply);
+        assert_eq!(ply.z_index("palette"), Some(elements::Layer::Overlay.z_index()));
+        assert_eq!(ply.z_index("page"), Some(0));
+        assert!(order.iter().position(|&id| id == palette) < order.iter().position(|&id| id == inspector));
+
+        ply.set_z_index("palette", elements::Layer::Overlay.z_index() + 1);
+        let order = frame(&mut ply);
+        assert_eq!(ply.z_index("palette"), Some(elements::Layer::Overlay.z_index() + 1));
+        assert!(order.iter().position(|&id| id == palette) > order.iter().position(|&id| id == inspector));
+
+        ply.reset_z_index("palette");
+        frame(&mut ply);
+        assert_eq!(ply.z_index("palette"), Some(elements::Layer::Overlay.z_index()));
+
+        // Dropped once a frame doesn't declare the element
+        ply.set_z_index("palette", elements::Layer::Overlay.z_index() + 1);
+        ply.begin().eval();
+        frame(&mut ply);
+        assert_eq!(ply.z_index("palette"), Some(elements::Layer::Overlay.z_index()));
+    }
+
     #[rustfmt::skip]
     #[test]
     fn test_simple_text_measure() {
@@ -1798,6 +3096,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_eval_sets_builtin_shader_uniforms() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(640.0, 480.0));
+        let mut ui = ply.begin();
+        ui.ply.context.current_time = 2.5;
+        ui.ply.context.set_pointer_state(Vector2::new(120.0, 80.0), false);
+        ui.element().width(fixed!(10.0)).height(fixed!(10.0)).empty();
+        ui.eval();
+        assert_eq!(
+            renderer::shader_globals(),
+            renderer::ShaderGlobals { time: 2.5, resolution: (640.0, 480.0), mouse: (120.0, 80.0) }
+        );
+    }
+
     #[rustfmt::skip]
     #[test]
     fn test_multiple_shaders_nested() {
@@ -1911,6 +3223,36 @@ mod tests {
         assert_eq!(items[0].effects[0].uniforms[1].name, "color_b");
     }
 
+    #[test]
+    fn test_effects_stay_with_their_elements_across_frames() {
+        use shaders::ShaderAsset;
+
+        let shader = ShaderAsset::Source {
+            file_name: "tint.glsl",
+            fragment: "#version 100\nprecision lowp float;\nvoid main() { gl_FragColor = vec4(1.0); }",
+        };
+
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        for _ in 0..2 {
+            let mut ui = ply.begin();
+            ui.element().width(fixed!(50.0)).height(fixed!(50.0)).background_color(0xFF0000)
+                .effect(&shader, |s| { s.uniform("first", 1.0f32); })
+                .empty();
+            ui.element().width(fixed!(50.0)).height(fixed!(50.0)).background_color(0x00FF00).empty();
+            ui.element().width(fixed!(50.0)).height(fixed!(50.0)).background_color(0x0000FF)
+                .effect(&shader, |s| { s.uniform("third", 1.0f32); })
+                .effect(&shader, |s| { s.uniform("third_again", 1.0f32); })
+                .empty();
+            let items = ui.eval();
+
+            let names: Vec<Vec<&str>> = items
+                .iter()
+                .map(|item| item.effects.iter().map(|effect| effect.uniforms[0].name.as_str()).collect())
+                .collect();
+            assert_eq!(names, vec![vec!["first"], vec![], vec!["third", "third_again"]]);
+        }
+    }
+
     #[rustfmt::skip]
     #[test]
     fn test_visual_rotation_emits_group() {
@@ -1990,6 +3332,73 @@ mod tests {
         }
     }
 
+    #[rustfmt::skip]
+    #[test]
+    fn test_visual_rotation_tilt_projects_with_perspective() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        let mut ui = ply.begin();
+
+        // A tilt alone still needs a group
+        ui.element()
+            .width(fixed!(100.0)).height(fixed!(100.0))
+            .background_color(0xFF0000)
+            .rotate_visual(|r| r.rotate_y(30.0).perspective(400.0))
+            .empty();
+
+        let items = ui.eval();
+        assert_eq!(items.len(), 3, "Expected GroupBegin, Rectangle, GroupEnd, got {:?}", items);
+        let render_commands::RenderCommandConfig::GroupBegin { visual_rotation: Some(vr), .. } = &items[0].config else {
+            panic!("Expected a tilted GroupBegin, got {:?}", items[0].config);
+        };
+        assert!(vr.is_tilted());
+        assert_eq!(vr.perspective, 400.0);
+
+        // The right edge turns away: it gets shorter and moves in
+        let bb = items[0].bounding_box;
+        let (top_left, bottom_left) = (vr.project(&bb, (bb.x, bb.y)), vr.project(&bb, (bb.x, bb.y + bb.height)));
+        let (top_right, bottom_right) = (
+            vr.project(&bb, (bb.x + bb.width, bb.y)),
+            vr.project(&bb, (bb.x + bb.width, bb.y + bb.height)),
+        );
+        assert!(bottom_right.1 - top_right.1 < bb.height);
+        assert!(bottom_left.1 - top_left.1 > bb.height);
+        assert!(top_right.0 < bb.x + bb.width);
+        // The pivot stays put
+        let center = (bb.x + bb.width / 2.0, bb.y + bb.height / 2.0);
+        let projected = vr.project(&bb, center);
+        assert!((projected.0 - center.0).abs() < 0.001 && (projected.1 - center.1).abs() < 0.001);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_transform_shares_visual_rotation_group() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        let mut ui = ply.begin();
+
+        ui.element()
+            .width(fixed!(100.0)).height(fixed!(100.0))
+            .background_color(0xFF0000)
+            .rotate_visual(|r| r.degrees(90.0))
+            .transform(|t| t.scale(2.0).translate(0.0, -4.0))
+            .empty();
+
+        let items = ui.eval();
+        assert_eq!(items.len(), 3, "Expected one group for rotation and transform, got {:?}", items);
+        let render_commands::RenderCommandConfig::GroupBegin { visual_rotation: Some(vr), .. } = &items[0].config else {
+            panic!("Expected a transformed GroupBegin, got {:?}", items[0].config);
+        };
+        assert!(vr.is_transformed());
+        assert!((vr.rotation_radians - 90.0_f32.to_radians()).abs() < 0.001);
+
+        // Layout is untouched; the top-left corner is scaled from the center,
+        // turned a quarter and moved up
+        let bb = items[0].bounding_box;
+        assert_eq!((bb.width, bb.height), (100.0, 100.0));
+        let corner = vr.project(&bb, (bb.x, bb.y));
+        assert!((corner.0 - (bb.x + 150.0)).abs() < 0.01, "{corner:?}");
+        assert!((corner.1 - (bb.y - 50.0 - 4.0)).abs() < 0.01, "{corner:?}");
+    }
+
     #[rustfmt::skip]
     #[test]
     fn test_visual_rotation_with_multiple_shaders() {
@@ -2437,29 +3846,108 @@ mod tests {
     }
 
     #[test]
-    fn test_pressed_query() {
-        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+    fn test_callback_boxes_are_reused_across_frames() {
+        use std::rc::Rc;
 
-        // Frame 1: layout
-        {
+        let mut pool = engine::CallbackPool::<dyn engine::PointerCallback>::default();
+        let make = |n: u32| move |_: Id, _: engine::PointerData| assert!(n > 0);
+        let first = pool.boxed(make(1), |f| Box::new(f));
+        let address = &*first as *const dyn engine::PointerCallback as *const u8;
+        pool.recycle(Some(first));
+        let second = pool.boxed(make(2), |f| Box::new(f));
+        assert_eq!(&*second as *const dyn engine::PointerCallback as *const u8, address);
+
+        // Redeclared callbacks fire with this frame's captures, and last
+        // frame's are dropped
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        let presses = Rc::new(std::cell::RefCell::new(Vec::new()));
+        for frame in 0..3u32 {
+            let presses = presses.clone();
             let mut ui = ply.begin();
             ui.element()
                 .id("btn")
                 .width(fixed!(100.0))
                 .height(fixed!(100.0))
+                .on_press(move |_, _| presses.borrow_mut().push(frame))
                 .empty();
             ui.eval();
         }
-
-        // Simulate pointer press at (50, 50)
+        assert_eq!(Rc::strong_count(&presses), 2);
         ply.context.set_pointer_state(Vector2::new(50.0, 50.0), true);
+        assert_eq!(*presses.borrow(), vec![2]);
+    }
 
-        // Frame 2: check pressed() during layout
-        {
+    #[test]
+    fn test_clipped_out_subtrees_are_culled() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let frame = |ply: &mut Ply<()>| {
             let mut ui = ply.begin();
             ui.element()
-                .id("btn")
-                .width(fixed!(100.0))
+                .id("list")
+                .width(fixed!(200.0))
+                .height(fixed!(100.0))
+                .layout(|l| l.direction(crate::layout::LayoutDirection::TopToBottom))
+                .overflow(|o| o.clip_y())
+                .children(|ui| {
+                    for i in 0..50 {
+                        ui.element()
+                            .id(("row", i))
+                            .width(fixed!(200.0))
+                            .height(fixed!(20.0))
+                            .background_color(0x336699)
+                            .children(|ui| {
+                                // Floats from a culled row still follow it
+                                if i == 40 {
+                                    ui.element()
+                                        .id("badge")
+                                        .width(fixed!(10.0))
+                                        .height(fixed!(10.0))
+                                        .floating(|f| f.attach_parent())
+                                        .empty();
+                                }
+                            });
+                    }
+                });
+            ui.eval().len()
+        };
+
+        let culled_commands = frame(&mut ply);
+        // Rows 0 to 5 overlap the list, the rest draw nothing but keep their bounds
+        assert_eq!(ply.frame_stats().culled_subtrees, 44);
+        assert_eq!(ply.bounding_box(("row", 20)).map(|bbox| bbox.y), Some(400.0));
+        assert_eq!(ply.bounding_box(("row", 40)).map(|bbox| bbox.y), Some(800.0));
+        assert_eq!(ply.bounding_box("badge").map(|bbox| bbox.y), Some(800.0));
+
+        ply.set_culling(false);
+        let all_commands = frame(&mut ply);
+        assert_eq!(ply.frame_stats().culled_subtrees, 0);
+        assert!(all_commands > culled_commands);
+    }
+
+    #[test]
+    fn test_pressed_query() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+
+        // Frame 1: layout
+        {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("btn")
+                .width(fixed!(100.0))
+                .height(fixed!(100.0))
+                .empty();
+            ui.eval();
+        }
+
+        // Simulate pointer press at (50, 50)
+        ply.context.set_pointer_state(Vector2::new(50.0, 50.0), true);
+
+        // Frame 2: check pressed() during layout
+        {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("btn")
+                .width(fixed!(100.0))
                 .height(fixed!(100.0))
                 .children(|ui| {
                     assert!(ui.pressed(), "element should report as pressed");
@@ -2856,6 +4344,253 @@ mod tests {
         assert_eq!(ply.context.focused_element_id, id_a);
     }
 
+    #[test]
+    fn test_high_contrast_overrides_colors_and_forces_ring() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        ply.set_measure_text_function(|_, _| Dimensions::new(60.0, 16.0));
+        let frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("card")
+                .background_color(0x3050A0)
+                .border(|b| b.color(0x808080).all(1))
+                .accessibility(|a| a.button("Card").disable_ring())
+                .children(|ui| {
+                    ui.text("Hello", |t| t.color(0x999999));
+                });
+            ui.eval()
+        };
+
+        let palette = accessibility::HighContrastPalette::default();
+        ply.set_high_contrast(Some(palette));
+        ply.set_focus("card");
+        let items = frame(&mut ply);
+
+        let mut ring = None;
+        for item in &items {
+            match &item.config {
+                render_commands::RenderCommandConfig::Rectangle(rect) => assert_eq!(rect.color, palette.background),
+                render_commands::RenderCommandConfig::Text(text) => assert_eq!(text.color, palette.text),
+                render_commands::RenderCommandConfig::Border(border) if border.color == palette.focus_ring => {
+                    ring = Some(item.bounding_box);
+                }
+                render_commands::RenderCommandConfig::Border(border) => assert_eq!(border.color, palette.border),
+                _ => {}
+            }
+        }
+        // Shown despite disable_ring() and focus coming from code, not the keyboard
+        assert!(ring.is_some());
+
+        ply.set_high_contrast(None);
+        let items = frame(&mut ply);
+        assert!(items.iter().all(|item| match &item.config {
+            render_commands::RenderCommandConfig::Border(border) => border.color != palette.focus_ring,
+            _ => true,
+        }));
+    }
+
+    #[test]
+    fn test_debug_picking_selects_instead_of_pressing() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| Dimensions::new(text.len() as f32 * 8.0, 16.0));
+        ply.set_debug_mode(true);
+        let pressed = std::rc::Rc::new(std::cell::Cell::new(false));
+        let frame = |ply: &mut Ply<()>| {
+            let pressed = pressed.clone();
+            let mut ui = ply.begin();
+            ui.element()
+                .id("card")
+                .width(fixed!(200.0))
+                .height(fixed!(100.0))
+                .layout(|l| l.padding(10).gap(8))
+                .children(|ui| {
+                    ui.element()
+                        .id("avatar")
+                        .width(fixed!(40.0))
+                        .height(fixed!(40.0))
+                        .on_press(move |_, _| pressed.set(true))
+                        .empty();
+                    ui.element().width(fixed!(40.0)).height(fixed!(40.0)).empty();
+                });
+            ui.eval();
+        };
+        frame(&mut ply);
+        frame(&mut ply);
+
+        ply.set_debug_picking(true);
+        ply.context.set_pointer_state(Vector2::new(20.0, 20.0), false);
+        frame(&mut ply);
+        ply.context.set_pointer_state(Vector2::new(20.0, 20.0), true);
+
+        assert_eq!(ply.context.debug_selected_element_id, Id::from("avatar").id);
+        assert!(!ply.context.is_debug_picking());
+        assert!(!pressed.get());
+        frame(&mut ply);
+    }
+
+    #[test]
+    fn test_spatial_arrow_navigation() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        use engine::ArrowDirection;
+
+        // A 2×2 grid with no overrides, except "a" skipping right to "d"
+        {
+            let mut ui = ply.begin();
+            ui.element()
+                .layout(|l| l.direction(crate::layout::LayoutDirection::TopToBottom))
+                .children(|ui| {
+                    for row in [["a", "b"], ["c", "d"]] {
+                        ui.element().children(|ui| {
+                            for label in row {
+                                ui.element()
+                                    .id(label)
+                                    .width(fixed!(100.0))
+                                    .height(fixed!(50.0))
+                                    .accessibility(|a| {
+                                        a.button(label);
+                                        if label == "a" {
+                                            a.focus_right("d");
+                                        }
+                                        a
+                                    })
+                                    .empty();
+                            }
+                        });
+                    }
+                });
+            ui.eval();
+        }
+
+        let id = |label: &'static str| Id::from(label).id;
+
+        // Overrides take precedence over geometry
+        ply.context.set_focus(id("a"));
+        ply.context.arrow_focus(ArrowDirection::Right);
+        assert_eq!(ply.context.focused_element_id, id("d"));
+
+        ply.context.arrow_focus(ArrowDirection::Left);
+        assert_eq!(ply.context.focused_element_id, id("c"));
+        ply.context.arrow_focus(ArrowDirection::Up);
+        assert_eq!(ply.context.focused_element_id, id("a"));
+        ply.context.arrow_focus(ArrowDirection::Down);
+        assert_eq!(ply.context.focused_element_id, id("c"));
+
+        // Nothing further down
+        ply.context.arrow_focus(ArrowDirection::Down);
+        assert_eq!(ply.context.focused_element_id, id("c"));
+
+        ply.set_spatial_navigation(false);
+        ply.context.arrow_focus(ArrowDirection::Up);
+        assert_eq!(ply.context.focused_element_id, id("c"));
+    }
+
+    #[test]
+    fn test_focus_scope_traps_and_restores_focus() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+
+        {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("open")
+                .width(fixed!(100.0))
+                .height(fixed!(50.0))
+                .accessibility(|a| a.button("Open"))
+                .empty();
+            ui.element()
+                .id("dialog")
+                .floating(|f| f.attach_root())
+                .children(|ui| {
+                    for label in ["ok", "cancel"] {
+                        ui.element()
+                            .id(label)
+                            .width(fixed!(100.0))
+                            .height(fixed!(50.0))
+                            .accessibility(|a| a.button(label))
+                            .empty();
+                    }
+                });
+            ui.eval();
+        }
+
+        let id_open = Id::from("open").id;
+        let id_ok = Id::from("ok").id;
+        let id_cancel = Id::from("cancel").id;
+
+        ply.set_focus("open");
+        ply.push_focus_scope("dialog");
+
+        // Tab only visits the dialog's buttons
+        ply.context.cycle_focus(false);
+        assert_eq!(ply.context.focused_element_id, id_ok);
+        ply.context.cycle_focus(false);
+        assert_eq!(ply.context.focused_element_id, id_cancel);
+        ply.context.cycle_focus(false);
+        assert_eq!(ply.context.focused_element_id, id_ok);
+
+        // Popping the scope goes back to the button that opened it
+        ply.pop_focus_scope();
+        assert_eq!(ply.context.focused_element_id, id_open);
+        ply.context.cycle_focus(false);
+        assert_eq!(ply.context.focused_element_id, id_ok);
+    }
+
+    #[test]
+    fn test_roving_focus_group_is_one_tab_stop() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        use engine::ArrowDirection;
+
+        {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("toolbar")
+                .focus_group(|g| g.roving().horizontal())
+                .children(|ui| {
+                    for label in ["bold", "italic", "underline"] {
+                        ui.element()
+                            .id(label)
+                            .width(fixed!(50.0))
+                            .height(fixed!(50.0))
+                            .accessibility(|a| a.button(label))
+                            .empty();
+                    }
+                });
+            ui.element()
+                .id("save")
+                .width(fixed!(100.0))
+                .height(fixed!(50.0))
+                .accessibility(|a| a.button("Save"))
+                .empty();
+            ui.eval();
+        }
+
+        let id_bold = Id::from("bold").id;
+        let id_italic = Id::from("italic").id;
+        let id_underline = Id::from("underline").id;
+        let id_save = Id::from("save").id;
+
+        // Tab enters at the first member, then leaves the group
+        ply.context.cycle_focus(false);
+        assert_eq!(ply.context.focused_element_id, id_bold);
+        ply.context.cycle_focus(false);
+        assert_eq!(ply.context.focused_element_id, id_save);
+
+        // Arrows move within the group and wrap, Up/Down don't
+        ply.context.set_focus(id_bold);
+        ply.context.arrow_focus(ArrowDirection::Right);
+        assert_eq!(ply.context.focused_element_id, id_italic);
+        ply.context.arrow_focus(ArrowDirection::Down);
+        assert_eq!(ply.context.focused_element_id, id_italic);
+        ply.context.arrow_focus(ArrowDirection::Left);
+        ply.context.arrow_focus(ArrowDirection::Left);
+        assert_eq!(ply.context.focused_element_id, id_underline);
+
+        // Tab back in re-enters at the member focused last
+        ply.context.cycle_focus(false);
+        assert_eq!(ply.context.focused_element_id, id_save);
+        ply.context.cycle_focus(false);
+        assert_eq!(ply.context.focused_element_id, id_underline);
+    }
+
     #[test]
     fn test_focused_query() {
         let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
@@ -3008,9 +4743,9 @@ mod tests {
                 .empty();
             let items = ui.eval();
 
-            // Look for a border render command with z_index 32764 (the focus ring)
+            // Look for a border render command on the debug layer (the focus ring)
             let focus_ring = items.iter().find(|cmd| {
-                cmd.z_index == 32764 && matches!(cmd.config, RenderCommandConfig::Border(_))
+                cmd.z_index == elements::Layer::Debug.z_index() && matches!(cmd.config, RenderCommandConfig::Border(_))
             });
             assert!(focus_ring.is_some(), "Focus ring border should be in render commands");
 
@@ -3094,4 +4829,930 @@ mod tests {
             assert!(h_thumb.bounding_box.x > 0.0);
         }
     }
+
+    #[test]
+    fn test_scroll_progress_follows_scroll_position() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let frame = |ply: &mut Ply<()>| {
+            let (mut list, mut header) = (Vector2::default(), Vector2::default());
+            let mut ui = ply.begin();
+            ui.element()
+                .id("list")
+                .width(fixed!(100.0))
+                .height(fixed!(100.0))
+                .layout(|l| l.direction(crate::layout::LayoutDirection::TopToBottom))
+                .overflow(|o| o.scroll_y())
+                .children(|ui| {
+                    list = ui.scroll_progress();
+                    ui.element().id("header").width(fixed!(100.0)).height(fixed!(40.0)).children(|ui| {
+                        header = ui.scroll_progress();
+                    });
+                    ui.element().width(fixed!(100.0)).height(fixed!(160.0)).empty();
+                });
+            ui.eval();
+            (list, header)
+        };
+
+        frame(&mut ply);
+        assert_eq!(frame(&mut ply), (Vector2::default(), Vector2::default()));
+
+        // Read on the frame the position changes, not one frame late
+        ply.set_scroll_position("list", (0.0, 20.0));
+        let (list, header) = frame(&mut ply);
+        assert!((list.y - 0.2).abs() < 1e-4, "list progress {list:?}");
+        assert!((header.y - 0.5).abs() < 1e-4, "header progress {header:?}");
+
+        ply.set_scroll_position("list", (0.0, 100.0));
+        let (list, header) = frame(&mut ply);
+        assert_eq!((list.y, header.y), (1.0, 1.0));
+    }
+
+    #[test]
+    fn test_text_max_lines_clamps_with_ellipsis() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| {
+            Dimensions::new(text.chars().count() as f32 * 10.0, 20.0)
+        });
+
+        let mut ui = ply.begin();
+        ui.element()
+            .id("card")
+            .width(fixed!(100.0))
+            .children(|ui| {
+                ui.text("aaaa bbbb cccc dddd eeee", |t| t.font_size(16).max_lines(2));
+            });
+        let items = ui.eval();
+
+        let lines: Vec<_> = items
+            .iter()
+            .filter_map(|cmd| match &cmd.config {
+                render_commands::RenderCommandConfig::Text(text) => Some(text.text.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "aaaa bbbb");
+        assert!(lines[1].ends_with('\u{2026}'), "last line should end with an ellipsis");
+        assert!(lines[1].chars().count() * 10 <= 100);
+
+        let card = ply.bounding_box("card").unwrap();
+        assert_eq!(card.height, 40.0);
+        assert!(ply.is_text_clamped("card"));
+    }
+
+    #[test]
+    fn test_selectable_text_drag_selects_and_highlights() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| {
+            Dimensions::new(text.chars().count() as f32 * 10.0, 20.0)
+        });
+
+        let build = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.element()
+                .width(fixed!(400.0))
+                .children(|ui| {
+                    ui.text("hello world", |t| t.font_size(16).selectable());
+                });
+            ui.eval()
+        };
+
+        build(&mut ply);
+
+        ply.context.set_pointer_state(Vector2::new(1.0, 5.0), true);
+        ply.context.set_pointer_state(Vector2::new(52.0, 5.0), true);
+        assert_eq!(ply.selected_text().as_deref(), Some("hello"));
+
+        let items = build(&mut ply);
+        let highlight = items.iter().find_map(|cmd| match &cmd.config {
+            render_commands::RenderCommandConfig::Rectangle(rect) => Some((cmd.bounding_box, rect.color)),
+            _ => None,
+        });
+        let (bounding_box, color) = highlight.expect("selection highlight should be rendered");
+        assert_eq!(bounding_box.x, 0.0);
+        assert_eq!(bounding_box.width, 50.0);
+        // Highlighted like a text input's selection
+        assert_eq!(color, text_input::TextInputConfig::default().selection_color);
+
+        // Clicking elsewhere clears the selection
+        ply.context.set_pointer_state(Vector2::new(700.0, 500.0), false);
+        ply.context.set_pointer_state(Vector2::new(700.0, 500.0), true);
+        assert_eq!(ply.selected_text(), None);
+    }
+
+    #[cfg(feature = "text-styling")]
+    #[test]
+    fn test_link_span_hover_and_click() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| {
+            let visible = text_input::styling::strip_styling(text);
+            Dimensions::new(visible.chars().count() as f32 * 10.0, 20.0)
+        });
+
+        let clicked = Rc::new(RefCell::new(Vec::<String>::new()));
+        let build = |ply: &mut Ply<()>, clicked: Rc<RefCell<Vec<String>>>| {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("paragraph")
+                .width(fixed!(400.0))
+                .on_link_clicked(move |url| clicked.borrow_mut().push(url.to_string()))
+                .children(|ui| {
+                    ui.text("see {link=https://a.b/c_d|docs} now", |t| t.font_size(16));
+                });
+            ui.eval();
+        };
+
+        build(&mut ply, clicked.clone());
+
+        // "see " is 40px wide, "docs" spans 40..80
+        ply.context.set_pointer_state(Vector2::new(20.0, 5.0), false);
+        assert_eq!(ply.hovered_link(), None);
+        ply.context.set_pointer_state(Vector2::new(60.0, 5.0), false);
+        assert_eq!(ply.hovered_link(), Some("https://a.b/c_d"));
+
+        ply.context.set_pointer_state(Vector2::new(60.0, 5.0), true);
+        ply.context.set_pointer_state(Vector2::new(62.0, 5.0), false);
+        assert_eq!(*clicked.borrow(), vec!["https://a.b/c_d".to_string()]);
+
+        // Releasing outside the link does not count as a click
+        build(&mut ply, clicked.clone());
+        ply.context.set_pointer_state(Vector2::new(60.0, 5.0), true);
+        ply.context.set_pointer_state(Vector2::new(150.0, 5.0), false);
+        assert_eq!(clicked.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_file_drop_targets_hovered_element() {
+        use macroquad::DroppedFile;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        let dropped = Rc::new(RefCell::new(Vec::<std::path::PathBuf>::new()));
+
+        // Returns whether the drop zone reported a file drag over it
+        let build = |ply: &mut Ply<()>, dropped: Rc<RefCell<Vec<std::path::PathBuf>>>| {
+            let mut drag_hovered = false;
+            let mut ui = ply.begin();
+            ui.element()
+                .id("drop_zone")
+                .width(fixed!(100.0))
+                .height(fixed!(100.0))
+                .on_file_drop(move |files| {
+                    dropped.borrow_mut().extend(files.iter().filter_map(|f| f.path.clone()));
+                })
+                .children(|ui| {
+                    drag_hovered = ui.file_drag_hovered();
+                    ui.element().id("icon").width(fixed!(20.0)).height(fixed!(20.0)).empty();
+                });
+            ui.element().id("other").width(fixed!(100.0)).height(fixed!(100.0)).empty();
+            ui.eval();
+            drag_hovered
+        };
+        let files = [DroppedFile {
+            path: Some(std::path::PathBuf::from("photo.png")),
+            bytes: None,
+        }];
+
+        build(&mut ply, dropped.clone());
+        ply.context.set_pointer_state(Vector2::new(10.0, 10.0), false);
+        assert!(!build(&mut ply, dropped.clone()));
+
+        // Dragging over the inner icon highlights the drop zone around it
+        ply.context.file_drag_position = Some(Vector2::new(10.0, 10.0));
+        assert!(build(&mut ply, dropped.clone()));
+        assert!(ply.context.drop_files(&files));
+        assert_eq!(*dropped.borrow(), vec![std::path::PathBuf::from("photo.png")]);
+        assert_eq!(ply.context.file_drag_position, None);
+
+        // Elements without `on_file_drop` ignore drops
+        ply.context.set_pointer_state(Vector2::new(150.0, 10.0), false);
+        ply.context.file_drag_position = Some(Vector2::new(150.0, 10.0));
+        assert!(!build(&mut ply, dropped.clone()));
+        assert!(!ply.context.drop_files(&files));
+        assert_eq!(dropped.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_window_on_resize_and_min_size() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        let sizes = Rc::new(RefCell::new(Vec::<Dimensions>::new()));
+        let sizes_clone = sizes.clone();
+        ply.window()
+            .set_title("Editor")
+            .set_min_size(400.0, 300.0)
+            .on_resize(move |size| sizes_clone.borrow_mut().push(size));
+        assert_eq!(ply.window().title(), "Editor");
+
+        ply.begin().eval();
+        assert!(sizes.borrow().is_empty());
+
+        ply.set_layout_dimensions(Dimensions::new(1024.0, 768.0));
+        ply.begin().eval();
+        assert_eq!(*sizes.borrow(), vec![Dimensions::new(1024.0, 768.0)]);
+
+        // Sizes below the minimum are clamped before layout
+        ply.set_layout_dimensions(Dimensions::new(200.0, 500.0));
+        ply.begin().eval();
+        assert_eq!(sizes.borrow().last(), Some(&Dimensions::new(400.0, 500.0)));
+        assert_eq!(ply.window().size(), Dimensions::new(400.0, 500.0));
+
+        ply.window().set_size(640.0, 480.0).toggle_fullscreen();
+        assert_eq!(ply.window().size(), Dimensions::new(640.0, 480.0));
+        assert!(ply.window().is_fullscreen());
+    }
+
+    #[test]
+    fn test_style_applies_and_is_overridable() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(200.0, 200.0));
+        let card = style::Style::new()
+            .width(fixed!(100.0))
+            .height(fixed!(40.0))
+            .background_color(0xFF0000)
+            .padding(10)
+            .corner_radius(8.0);
+        let accent = card.clone().merged(&style::Style::new().background_color(0x00FF00));
+
+        let mut ui = ply.begin();
+        ui.element().style(&card).width(fixed!(60.0)).empty();
+        ui.element().style(&accent).empty();
+        let items = ui.eval();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].bounding_box.width, 60.0);
+        assert_eq!(items[0].bounding_box.height, 40.0);
+        assert_eq!(items[1].bounding_box.width, 100.0);
+        match (&items[0].config, &items[1].config) {
+            (
+                render_commands::RenderCommandConfig::Rectangle(first),
+                render_commands::RenderCommandConfig::Rectangle(second),
+            ) => {
+                assert_eq!(first.color, Color::u_rgb(255, 0, 0));
+                assert_eq!(first.corner_radii.top_left, 8.0);
+                assert_eq!(second.color, Color::u_rgb(0, 255, 0));
+                assert_eq!(second.corner_radii.top_left, 8.0);
+            }
+            other => panic!("Expected two rectangles, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_style_states_follow_interaction() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+
+        let button_color = |ply: &mut Ply<()>, disabled: bool| {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("btn")
+                .width(fixed!(100.0))
+                .height(fixed!(100.0))
+                .background_color(0x101010)
+                .style_states(|s| s
+                    .hover(|h| h.background_color(0x202020))
+                    .pressed(|p| p.background_color(0x303030))
+                    .disabled(disabled, |d| d.background_color(0x404040))
+                )
+                .empty();
+            let items = ui.eval();
+            match &items[0].config {
+                render_commands::RenderCommandConfig::Rectangle(rect) => rect.color,
+                other => panic!("Expected a rectangle, got {:?}", other),
+            }
+        };
+
+        assert_eq!(button_color(&mut ply, false), Color::from(0x101010));
+
+        ply.context.set_pointer_state(Vector2::new(50.0, 50.0), false);
+        assert_eq!(button_color(&mut ply, false), Color::from(0x202020));
+
+        ply.context.set_pointer_state(Vector2::new(50.0, 50.0), true);
+        assert_eq!(button_color(&mut ply, false), Color::from(0x303030));
+        assert_eq!(button_color(&mut ply, true), Color::from(0x404040));
+    }
+
+    #[test]
+    fn test_theme_swap_restyles_next_frame() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(100.0, 100.0));
+        ply.set_theme(theme::Theme::new().color("surface", 0xFFFFFF).number("space", 10.0));
+
+        let surface_color = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.element()
+                .width(fixed!(50.0))
+                .height(fixed!(50.0))
+                .background_color(token!("surface"))
+                .corner_radius(token!("space"))
+                .empty();
+            let items = ui.eval();
+            match &items[0].config {
+                render_commands::RenderCommandConfig::Rectangle(rect) => {
+                    (rect.color, rect.corner_radii.top_left)
+                }
+                other => panic!("Expected a rectangle, got {:?}", other),
+            }
+        };
+
+        assert_eq!(surface_color(&mut ply), (Color::u_rgb(255, 255, 255), 10.0));
+
+        let dark = ply.theme().clone().merged(&theme::Theme::new().color("surface", 0x202020));
+        ply.set_theme(dark);
+        assert_eq!(surface_color(&mut ply), (Color::u_rgb(0x20, 0x20, 0x20), 10.0));
+    }
+
+    #[test]
+    fn test_raster_font_size_for_display_scale() {
+        use crate::renderer::raster_font_size;
+
+        assert_eq!(raster_font_size(16, 1.0), (16, 1.0));
+        assert_eq!(raster_font_size(16, 2.0), (32, 0.5));
+        // Fractional scales round to a whole raster size, scaled back exactly
+        let (raster_size, raster_scale) = raster_font_size(15, 1.5);
+        assert_eq!(raster_size, 23);
+        assert!((raster_size as f32 * raster_scale - 15.0).abs() < 1e-4);
+        assert_eq!(raster_font_size(0, 2.0), (0, 1.0));
+    }
+
+    #[test]
+    fn test_feathered_rounded_rectangle_fades_curved_edges_only() {
+        use crate::renderer::{push_rounded_rectangle, EdgeAntialiasing};
+
+        let bb = math::BoundingBox::new(0.0, 0.0, 100.0, 50.0);
+        let cr = render_commands::CornerRadii { top_left: 8.0, top_right: 8.0, bottom_left: 8.0, bottom_right: 8.0 };
+        let color = macroquad::prelude::Color::new(1.0, 0.0, 0.0, 1.0);
+        let mesh = |edge_antialiasing| {
+            let (mut vertices, mut indices) = (Vec::new(), Vec::new());
+            push_rounded_rectangle(&mut vertices, &mut indices, &bb, &cr, color, edge_antialiasing);
+            (vertices, indices)
+        };
+
+        // Four 6-segment arcs: a 28-point outline around the center
+        let (vertices, indices) = mesh(EdgeAntialiasing::Off);
+        assert_eq!((vertices.len(), indices.len()), (29, 84));
+
+        let (vertices, indices) = mesh(EdgeAntialiasing::Feathered);
+        // A transparent ring outside the outline, joined only along the 24 arc edges
+        assert_eq!((vertices.len(), indices.len()), (57, 84 + 24 * 6));
+        let center = macroquad::prelude::vec2(50.0, 25.0);
+        for i in 0..28 {
+            let (inner, outer) = (vertices[1 + i], vertices[29 + i]);
+            assert_eq!((inner.color[3], outer.color[3]), (255, 0));
+            let distance = |v: macroquad::prelude::Vertex| (v.position.truncate() - center).length();
+            assert!(distance(outer) > distance(inner));
+        }
+    }
+
+    #[test]
+    fn test_inline_atoms_wrap_with_text() {
+        static EMOTE: renderer::GraphicAsset = renderer::GraphicAsset::Path("emote.png");
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| {
+            Dimensions::new(text.chars().count() as f32 * 10.0, 20.0)
+        });
+
+        let mut ui = ply.begin();
+        ui.element()
+            .width(fixed!(100.0))
+            .children(|ui| {
+                let line = format!("gg {} well {}", text::INLINE_ATOM, text::INLINE_ATOM);
+                ui.text(&line, |t| t
+                    .font_size(16)
+                    .inline_image(&EMOTE, (16.0, 16.0))
+                    .inline_element("slot", (30.0, 20.0))
+                    .inline_align(text::InlineAlign::Bottom)
+                );
+            });
+        let items = ui.eval();
+
+        // "gg " is 30px wide, so the image follows it on the first line
+        let image = items
+            .iter()
+            .find(|cmd| matches!(cmd.config, render_commands::RenderCommandConfig::Image(_)))
+            .expect("inline image should be rendered");
+        assert_eq!(image.bounding_box.x, 30.0);
+        assert_eq!(image.bounding_box.y, 4.0);
+
+        let texts: Vec<(f32, f32, String)> = items
+            .iter()
+            .filter_map(|cmd| match &cmd.config {
+                render_commands::RenderCommandConfig::Text(t) => {
+                    Some((cmd.bounding_box.x, cmd.bounding_box.y, t.text.to_string()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, vec![
+            (0.0, 0.0, "gg ".to_string()),
+            (0.0, 20.0, "well ".to_string()),
+        ]);
+
+        // The element slot wrapped onto the second line, after "well "
+        let slot = ply.bounding_box("slot").expect("slot should be registered");
+        assert_eq!((slot.x, slot.y, slot.width), (50.0, 20.0, 30.0));
+    }
+
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_parallel_text_matches_serial() {
+        fn layout(ply: &mut Ply<()>) -> Vec<(math::BoundingBox, String)> {
+            let long = "word ".repeat(300);
+            let mut ui = ply.begin();
+            ui.element()
+                .width(grow!())
+                .layout(|l| l.direction(crate::layout::LayoutDirection::TopToBottom))
+                .children(|ui| {
+                    for row in 0..20 {
+                        ui.element().id(("row", row)).width(grow!()).children(|ui| {
+                            for _ in 0..3 {
+                                ui.element().width(grow!()).children(|ui| {
+                                    ui.text("more words than one cell fits on a single line", |t| t.max_lines(2));
+                                });
+                            }
+                        });
+                    }
+                    ui.text(&long, |t| t);
+                });
+            ui.eval()
+                .iter()
+                .map(|cmd| match &cmd.config {
+                    render_commands::RenderCommandConfig::Text(t) => (cmd.bounding_box, t.text.to_string()),
+                    _ => (cmd.bounding_box, String::new()),
+                })
+                .collect()
+        }
+        let measure = |text: &str, _: &TextConfig| Dimensions::new(text.chars().count() as f32 * 10.0, 20.0);
+
+        let mut serial = Ply::<()>::new_headless(Dimensions::new(800.0, 4000.0));
+        serial.set_measure_text_function(measure);
+        let expected = layout(&mut serial);
+        assert!(expected.iter().filter(|(_, text)| !text.is_empty()).count() > 100);
+
+        let mut parallel = Ply::<()>::new_headless(Dimensions::new(800.0, 4000.0));
+        parallel.set_parallel_measure_text_function(measure);
+        assert_eq!(layout(&mut parallel), expected);
+    }
+
+    #[test]
+    fn test_inline_atoms_leave_other_text_alone() {
+        static EMOTE: renderer::GraphicAsset = renderer::GraphicAsset::Path("emote.png");
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| {
+            Dimensions::new(text.chars().count() as f32 * 10.0, 20.0)
+        });
+
+        // Private-use characters are plain text, as is a marker without an atom
+        let line = format!("a\u{F0000}b{} c{}", text::INLINE_ATOM, text::INLINE_ATOM);
+        let mut ui = ply.begin();
+        ui.text(&line, |t| t.inline_image(&EMOTE, (16.0, 16.0)));
+        let items = ui.eval();
+
+        let image = items
+            .iter()
+            .find(|cmd| matches!(cmd.config, render_commands::RenderCommandConfig::Image(_)))
+            .expect("inline image should be rendered");
+        assert_eq!(image.bounding_box.x, 30.0);
+        let texts: Vec<(f32, String)> = items
+            .iter()
+            .filter_map(|cmd| match &cmd.config {
+                render_commands::RenderCommandConfig::Text(t) => Some((cmd.bounding_box.x, t.text.to_string())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, vec![
+            (0.0, "a\u{F0000}b".to_string()),
+            (46.0, format!(" c{}", text::INLINE_ATOM)),
+        ]);
+    }
+
+    #[test]
+    fn test_text_input_validation_marks_border() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| {
+            Dimensions::new(text.chars().count() as f32 * 10.0, 20.0)
+        });
+        ply.set_text_value("email", "not an email");
+
+        let validate = |text: &str| {
+            if text.contains('@') { Ok(()) } else { Err("Missing @".to_string()) }
+        };
+        let mut ui = ply.begin();
+        ui.element()
+            .id("email")
+            .width(fixed!(200.0))
+            .height(fixed!(30.0))
+            .text_input(|t| t.validate(validate).invalid_border_color(0xFF0000))
+            .empty();
+        let items = ui.eval();
+
+        assert!(!ply.is_valid("email"));
+        assert_eq!(ply.validation_error("email"), Some("Missing @"));
+        let border = items
+            .iter()
+            .find_map(|cmd| match &cmd.config {
+                render_commands::RenderCommandConfig::Border(border) => Some(border),
+                _ => None,
+            })
+            .expect("invalid input should get a border");
+        assert_eq!(border.color, Color::from(0xFF0000));
+
+        ply.set_text_value("email", "me@example.com");
+        let mut ui = ply.begin();
+        ui.element()
+            .id("email")
+            .width(fixed!(200.0))
+            .height(fixed!(30.0))
+            .text_input(|t| t.validate(validate).invalid_border_color(0xFF0000))
+            .empty();
+        let items = ui.eval();
+
+        assert!(ply.is_valid("email"));
+        assert!(!items
+            .iter()
+            .any(|cmd| matches!(cmd.config, render_commands::RenderCommandConfig::Border(_))));
+
+        // An error is dropped with its validator
+        ply.set_text_value("email", "not an email");
+        let mut ui = ply.begin();
+        ui.element().id("email").width(fixed!(200.0)).height(fixed!(30.0)).text_input(|t| t.validate(validate)).empty();
+        ui.eval();
+        assert!(!ply.is_valid("email"));
+        let mut ui = ply.begin();
+        ui.element().id("email").width(fixed!(200.0)).height(fixed!(30.0)).text_input(|t| t).empty();
+        ui.eval();
+        assert!(ply.is_valid("email"));
+    }
+
+    #[test]
+    fn test_accessibility_tree_and_overlay() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("title")
+                .width(fixed!(200.0))
+                .height(fixed!(40.0))
+                .accessibility(|a| a.heading("Settings", 1))
+                .empty();
+            ui.element()
+                .id("save")
+                .width(fixed!(80.0))
+                .height(fixed!(30.0))
+                .accessibility(|a| a.button("Save"))
+                .empty();
+            ui.element()
+                .id("flourish")
+                .width(fixed!(20.0))
+                .height(fixed!(20.0))
+                .accessibility(|a| a.image("Flourish").decorative())
+                .empty();
+            ui.eval()
+        };
+
+        frame(&mut ply);
+        ply.set_focus("save");
+        ply.set_accessibility_overlay(true);
+        let items = frame(&mut ply);
+
+        let tree = ply.accessibility_tree();
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].role, accessibility::AccessibilityRole::Heading { level: 1 });
+        assert_eq!(tree[0].label, "Settings");
+        assert!(!tree[0].focusable && !tree[0].focused);
+        assert_eq!(tree[1].label, "Save");
+        assert!(tree[1].focusable && tree[1].focused);
+        assert_eq!(tree[1].bounds, ply.bounding_box("save").unwrap());
+
+        let outlines: Vec<_> = items
+            .iter()
+            .filter_map(|cmd| match &cmd.config {
+                render_commands::RenderCommandConfig::Border(border) if cmd.z_index == i16::MAX => {
+                    Some((cmd.bounding_box, border.width.left))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(outlines, vec![(tree[0].bounds, 1), (tree[1].bounds, 2)]);
+    }
+
+    #[test]
+    fn test_text_selection_and_insert_at_cursor() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_text_value("search", "hello world");
+
+        ply.set_text_selection("search", 6..11);
+        assert_eq!(ply.get_text_selection("search"), Some(6..11));
+
+        ply.insert_text_at_cursor("search", "there");
+        assert_eq!(ply.get_text_value("search"), "hello there");
+        assert_eq!(ply.get_text_selection("search"), None);
+        assert_eq!(ply.get_cursor_pos("search"), 11);
+
+        ply.set_text_selection("search", 0..0);
+        ply.insert_text_at_cursor("search", "oh, ");
+        assert_eq!(ply.get_text_value("search"), "oh, hello there");
+    }
+
+    #[test]
+    fn test_touch_selection_handles_drag_selection() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| {
+            Dimensions::new(text.chars().count() as f32 * 10.0, 20.0)
+        });
+        ply.set_text_value("field", "hello world");
+        ply.set_focus("field");
+        ply.set_selection("field", 0, 5);
+        ply.context.text_input_touch_handles = true;
+
+        let mut ui = ply.begin();
+        ui.element()
+            .id("field")
+            .width(fixed!(200.0))
+            .height(fixed!(30.0))
+            .text_input(|t| t)
+            .empty();
+        let items = ui.eval();
+
+        // Handles sit under the carets at x = 0 and x = 50
+        let handles: Vec<_> = items
+            .iter()
+            .filter(|cmd| {
+                matches!(cmd.config, render_commands::RenderCommandConfig::Rectangle(_))
+                    && cmd.bounding_box.width == 16.0
+                    && cmd.bounding_box.height == 16.0
+            })
+            .map(|cmd| (cmd.bounding_box.x + 8.0, cmd.bounding_box.y + 8.0))
+            .collect();
+        assert_eq!(handles, vec![(0.0, 33.0), (50.0, 33.0)]);
+
+        // Dragging the end handle extends the selection
+        ply.pointer_state(Vector2::new(50.0, 33.0), true);
+        ply.context.update_text_input_pointer_scroll(Vector2::default(), true);
+        ply.pointer_state(Vector2::new(80.0, 33.0), true);
+        ply.context.update_text_input_pointer_scroll(Vector2::default(), true);
+        assert_eq!(ply.get_selection_range("field"), Some((0, 8)));
+        assert_eq!(ply.focused_element().map(|id| id.id), Some(Id::from("field").id));
+
+        ply.pointer_state(Vector2::new(80.0, 33.0), false);
+        ply.context.update_text_input_pointer_scroll(Vector2::default(), true);
+        assert_eq!(ply.context.text_input_handle_drag, None);
+    }
+
+    #[test]
+    fn test_drag_select_autoscrolls_multiline_input() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| {
+            Dimensions::new(text.chars().count() as f32 * 10.0, 20.0)
+        });
+        ply.set_text_value("notes", "0\n1\n2\n3\n4\n5\n6\n7");
+        ply.set_focus("notes");
+
+        let mut ui = ply.begin();
+        ui.element()
+            .id("notes")
+            .width(fixed!(200.0))
+            .height(fixed!(40.0))
+            .text_input(|t| t.multiline().drag_select())
+            .empty();
+        ui.eval();
+
+        ply.context.frame_delta_time = 0.1;
+        ply.context.set_pointer_state(Vector2::new(2.0, 5.0), true);
+        ply.context.update_text_input_pointer_scroll(Vector2::default(), false);
+
+        // 20px below the bottom edge scrolls 20 * 10 * 0.1 = 20px per frame
+        ply.context.set_pointer_state(Vector2::new(2.0, 60.0), true);
+        ply.context.update_text_input_pointer_scroll(Vector2::default(), false);
+        let id = Id::from("notes").id;
+        assert_eq!(ply.context.text_edit_states[&id].scroll_offset_y, 20.0);
+        assert_eq!(ply.get_selection_range("notes"), Some((0, 6)));
+
+        // Holding the pointer still keeps scrolling
+        ply.context.set_pointer_state(Vector2::new(2.0, 60.0), true);
+        ply.context.update_text_input_pointer_scroll(Vector2::default(), false);
+        assert_eq!(ply.context.text_edit_states[&id].scroll_offset_y, 40.0);
+        assert_eq!(ply.get_selection_range("notes"), Some((0, 8)));
+    }
+
+    #[test]
+    fn test_search_input_debounces_on_search() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| {
+            Dimensions::new(text.chars().count() as f32 * 10.0, 20.0)
+        });
+        let mut searches: Vec<String> = Vec::new();
+        let mut frame = |ply: &mut Ply<()>, time: f64| {
+            ply.context.current_time = time;
+            let mut ui = ply.begin();
+            ui.search_input(|s| s
+                .id("query")
+                .debounce(0.3)
+                .on_search(|query| searches.push(query.to_string()))
+            );
+            let items = ui.eval();
+            items.iter().any(|cmd| matches!(
+                &cmd.config,
+                render_commands::RenderCommandConfig::Text(text) if text.text == "×"
+            ))
+        };
+
+        assert!(!frame(&mut ply, 0.0));
+        ply.set_text_value("query", "ply");
+        assert!(frame(&mut ply, 1.0), "clear button shows once there is text");
+        frame(&mut ply, 1.2);
+        ply.set_text_value("query", "ply engine");
+        frame(&mut ply, 1.4);
+        frame(&mut ply, 1.6);
+        frame(&mut ply, 1.8);
+        assert_eq!(searches, vec!["ply engine".to_string()]);
+    }
+
+    #[test]
+    fn test_justified_text_fills_wrapped_lines() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| {
+            Dimensions::new(text.chars().count() as f32 * 10.0, 20.0)
+        });
+
+        let mut ui = ply.begin();
+        ui.element()
+            .width(fixed!(100.0))
+            .children(|ui| {
+                ui.text("aa bb cc dd ee", |t| t.justify());
+            });
+        let items = ui.eval();
+
+        let texts: Vec<(f32, f32, String)> = items
+            .iter()
+            .filter_map(|cmd| match &cmd.config {
+                render_commands::RenderCommandConfig::Text(t) => {
+                    Some((cmd.bounding_box.x, cmd.bounding_box.y, t.text.to_string()))
+                }
+                _ => None,
+            })
+            .collect();
+        // "aa bb cc" is 80px wide, so each of its two word gaps grows by 10px.
+        // The last line stays left-aligned.
+        assert_eq!(texts, vec![
+            (0.0, 0.0, "aa ".to_string()),
+            (40.0, 0.0, "bb ".to_string()),
+            (80.0, 0.0, "cc".to_string()),
+            (0.0, 20.0, "dd ee".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_align_baseline_aligns_row_text() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, config| {
+            Dimensions::new(text.chars().count() as f32 * 10.0, config.font_size as f32)
+        });
+        ply.set_text_baseline_function(|config| config.font_size as f32 * 0.75);
+
+        let mut ui = ply.begin();
+        ui.element()
+            .id("row")
+            .layout(|l| l.align(crate::align::AlignX::Left, crate::align::AlignY::Baseline))
+            .children(|ui| {
+                ui.text("Small", |t| t.font_size(20));
+                ui.text("Big", |t| t.font_size(40));
+                ui.element().width(fixed!(10.0)).height(fixed!(10.0)).background_color(0xFFFFFF).empty();
+            });
+        let items = ui.eval();
+
+        let text_ys: Vec<f32> = items
+            .iter()
+            .filter(|cmd| matches!(cmd.config, render_commands::RenderCommandConfig::Text(_)))
+            .map(|cmd| cmd.bounding_box.y)
+            .collect();
+        // Baselines at 15 and 30 line up at the row baseline of 30
+        assert_eq!(text_ys, vec![15.0, 0.0]);
+
+        // The box has no text, so its bottom edge sits on the baseline
+        let square = items
+            .iter()
+            .find(|cmd| cmd.bounding_box.width == 10.0)
+            .expect("square should be rendered");
+        assert_eq!(square.bounding_box.y, 20.0);
+
+        // 30 above the baseline plus the largest descent of 10
+        assert_eq!(ply.bounding_box("row").unwrap().height, 40.0);
+    }
+
+    #[test]
+    fn test_text_shadow_and_outline_in_render_command() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|_, _| Dimensions::new(100.0, 24.0));
+
+        let mut ui = ply.begin();
+        ui.text("HUD", |t| t
+            .font_size(16)
+            .shadow((2.0, 3.0), 4.0, 0x000000)
+            .outline(1.5, 0x202020)
+        );
+        let items = ui.eval();
+
+        match &items[0].config {
+            render_commands::RenderCommandConfig::Text(text) => {
+                let shadow = text.shadow.expect("shadow should be set");
+                assert_eq!(shadow.offset.x, 2.0);
+                assert_eq!(shadow.offset.y, 3.0);
+                assert_eq!(shadow.blur, 4.0);
+                let outline = text.outline.expect("outline should be set");
+                assert_eq!(outline.width, 1.5);
+                assert_eq!(outline.color.r, 32.0);
+            }
+            other => panic!("Expected Text config, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_wrap_mode_anywhere_breaks_long_words() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        ply.set_measure_text_function(|text, _| {
+            Dimensions::new(text.chars().count() as f32 * 10.0, 20.0)
+        });
+
+        let mut ui = ply.begin();
+        ui.element()
+            .width(fixed!(100.0))
+            .children(|ui| {
+                ui.text("see abcdefghijklmnopqrstuvwxyz ok", |t| t
+                    .font_size(16)
+                    .wrap_mode(text::WrapMode::Anywhere)
+                );
+            });
+        let items = ui.eval();
+
+        let lines: Vec<_> = items
+            .iter()
+            .filter_map(|cmd| match &cmd.config {
+                render_commands::RenderCommandConfig::Text(text) => Some(text.text.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lines, vec!["see", "abcdefghij", "klmnopqrst", "uvwxyz ok"]);
+        for cmd in &items {
+            assert!(cmd.bounding_box.width <= 100.0);
+        }
+    }
+    #[test]
+    fn test_root_dimensions_apply_to_declared_layout() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(800.0, 600.0));
+        let mut ui = ply.begin();
+        ui.element()
+            .id("panel")
+            .width(grow!())
+            .height(grow!())
+            .background_color(0xFF0000)
+            .empty();
+
+        // Offscreen rendering resizes the root after the tree is declared
+        ui.context.set_root_dimensions(Dimensions::new(256.0, 128.0));
+        let items = ui.eval();
+        assert_eq!(items[0].bounding_box, math::BoundingBox::new(0.0, 0.0, 256.0, 128.0));
+    }
+
+    #[test]
+    fn test_layout_cache_reuses_unchanged_layout() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        ply.set_measure_text_function(|text, _| Dimensions::new(text.len() as f32 * 10.0, 16.0));
+        let frame = |ply: &mut Ply<()>, width: f32, color: u32| {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("card")
+                .width(fixed!(width))
+                .background_color(color)
+                .children(|ui| {
+                    ui.text("one two three four five", |t| t.font_size(16));
+                });
+            let items = ui.eval();
+            let lines = items
+                .iter()
+                .filter(|cmd| matches!(cmd.config, render_commands::RenderCommandConfig::Text(_)))
+                .count();
+            (ply.frame_stats().layout_cached, ply.bounding_box("card").unwrap(), lines)
+        };
+
+        let (cached, card, lines) = frame(&mut ply, 100.0, 0x202020);
+        assert!(!cached);
+        assert!(lines > 1);
+
+        // Same layout, and a color change doesn't affect sizes
+        assert_eq!(frame(&mut ply, 100.0, 0x202020), (true, card, lines));
+        assert_eq!(frame(&mut ply, 100.0, 0xFF0000), (true, card, lines));
+
+        // A new width lays out again
+        let (cached, wider, lines) = frame(&mut ply, 300.0, 0xFF0000);
+        assert!(!cached);
+        assert_eq!(lines, 1);
+        assert!(wider.height < card.height);
+
+        ply.set_layout_cache(false);
+        assert!(!frame(&mut ply, 300.0, 0xFF0000).0);
+    }
 }
@@ -0,0 +1,269 @@
+//! Masking a subtree by the alpha of an image or of another element.
+//!
+//! ```ignore
+//! // Irregular panel: the subtree only shows where the image is opaque
+//! ui.element()
+//!     .width(fixed!(240.0)).height(fixed!(160.0))
+//!     .mask(&BLOB_MASK)
+//!     .children(|ui| draw_panel(ui));
+//!
+//! // Reveal: the content shows through a growing circle
+//! ui.element()
+//!     .width(grow!()).height(grow!())
+//!     .mask_element("spotlight")
+//!     .children(|ui| draw_content(ui));
+//! ui.element()
+//!     .id("spotlight")
+//!     .floating(|f| f.offset(center))
+//!     .width(fixed!(radius * 2.0)).height(fixed!(radius * 2.0))
+//!     .corner_radius(radius)
+//!     .background_color(0xFFFFFF)
+//!     .empty();
+//! ```
+//!
+//! An image mask is stretched over the masked element. An element mask stays
+//! where that element is drawn, and the subtree is hidden outside of it. The
+//! mask element is drawn as usual; declaring it after the masked subtree
+//! uses its previous frame.
+//!
+//! Clip paths cut the subtree to a polygon instead, given in fractions of
+//! the element's size so the shape follows the layout:
+//!
+//! ```ignore
+//! // Hexagonal avatar
+//! ui.element()
+//!     .width(fixed!(64.0)).height(fixed!(64.0))
+//!     .clip_path(&[(0.25, 0.0), (0.75, 0.0), (1.0, 0.5), (0.75, 1.0), (0.25, 1.0), (0.0, 0.5)])
+//!     .image(&AVATAR)
+//!     .empty();
+//! ```
+
+use rustc_hash::FxHashSet;
+
+use crate::math::Vector2;
+use crate::renderer::ImageSource;
+use crate::shaders::{ShaderAsset, ShaderBuilder, ShaderConfig};
+
+/// Most points a clip path can have.
+pub const MAX_CLIP_PATH_POINTS: usize = 64;
+
+/// Keeps the part of the group inside the polygon in `ply_clip_points`,
+/// antialiased by the distance to its edges. The last point repeats the
+/// first, so no edge needs a computed index.
+const CLIP_PATH: ShaderAsset = ShaderAsset::Source {
+    file_name: "ply_clip_path",
+    fragment: "#version 100
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+precision highp float;
+#else
+precision mediump float;
+#endif
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+uniform vec4 ply_element_rect;
+uniform vec2 ply_clip_points[65];
+uniform int ply_clip_count;
+
+void main() {
+    // Group captures are drawn flipped vertically
+    vec2 p = vec2(uv.x, 1.0 - uv.y) * ply_element_rect.zw;
+    vec2 a = ply_clip_points[0] * ply_element_rect.zw;
+    float d = dot(p - a, p - a);
+    float s = 1.0;
+    for (int i = 1; i < 65; i++) {
+        if (i >= ply_clip_count) break;
+        vec2 b = ply_clip_points[i] * ply_element_rect.zw;
+        vec2 e = b - a;
+        vec2 w = p - a;
+        vec2 q = w - e * clamp(dot(w, e) / max(dot(e, e), 1e-6), 0.0, 1.0);
+        d = min(d, dot(q, q));
+        bvec3 c = bvec3(p.y >= a.y, p.y < b.y, e.x * w.y > e.y * w.x);
+        if (all(c) || all(not(c))) s = -s;
+        a = b;
+    }
+    // Negative inside, in logical pixels
+    float distance = s * sqrt(d);
+    vec4 pixel = texture2D(Texture, uv) * color;
+    gl_FragColor = vec4(pixel.rgb, pixel.a * clamp(0.5 - distance, 0.0, 1.0));
+}
+",
+};
+
+/// Multiplies the group's alpha by the mask's, with the mask placed over
+/// `ply_mask_rect` in logical pixels.
+const MASK: ShaderAsset = ShaderAsset::Source {
+    file_name: "ply_mask",
+    fragment: "#version 100
+precision mediump float;
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+uniform sampler2D ply_mask;
+uniform vec4 ply_element_rect;
+uniform vec4 ply_mask_rect;
+
+void main() {
+    // Group captures are drawn flipped vertically
+    vec2 position = ply_element_rect.xy + vec2(uv.x, 1.0 - uv.y) * ply_element_rect.zw;
+    vec2 mask_uv = (position - ply_mask_rect.xy) / ply_mask_rect.zw;
+    float inside = step(0.0, mask_uv.x) * step(mask_uv.x, 1.0) * step(0.0, mask_uv.y) * step(mask_uv.y, 1.0);
+    vec4 pixel = texture2D(Texture, uv) * color;
+    gl_FragColor = vec4(pixel.rgb, pixel.a * texture2D(ply_mask, mask_uv).a * inside);
+}
+",
+};
+
+const SILHOUETTE_NAME: &str = "ply_mask_silhouette";
+
+/// Draws a group unchanged. Renders mask elements offscreen so their
+/// silhouette can be sampled.
+const SILHOUETTE: ShaderAsset = ShaderAsset::Source {
+    file_name: SILHOUETTE_NAME,
+    fragment: "#version 100
+precision mediump float;
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+
+void main() {
+    gl_FragColor = texture2D(Texture, uv) * color;
+}
+",
+};
+
+/// Where a mask's alpha comes from, see `ElementBuilder::mask`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MaskSource {
+    /// An image stretched over the masked element. TinyVG images aren't supported.
+    Image(ImageSource),
+    /// The rendered silhouette of the element with this id.
+    Element(u32),
+}
+
+/// Ids of the elements used as masks, which get rendered offscreen.
+#[derive(Default)]
+pub(crate) struct Masks {
+    requested: FxHashSet<u32>,
+    previous: FxHashSet<u32>,
+}
+
+impl Masks {
+    /// Forgets mask elements not asked for last frame. Called from `begin()`.
+    pub(crate) fn sweep(&mut self) {
+        self.previous = std::mem::take(&mut self.requested);
+    }
+
+    /// Builds the group shader masking a subtree by `source`.
+    pub(crate) fn shader(&mut self, source: MaskSource) -> ShaderConfig {
+        if let MaskSource::Element(id) = source {
+            self.requested.insert(id);
+        }
+        let mut config = ShaderBuilder::new(&MASK).into_config();
+        config.mask = Some(source);
+        config
+    }
+
+    /// The group shader capturing element `id` when it's used as a mask.
+    pub(crate) fn silhouette_shader(&self, id: u32) -> Option<ShaderConfig> {
+        (self.requested.contains(&id) || self.previous.contains(&id))
+            .then(|| ShaderBuilder::new(&SILHOUETTE).into_config())
+    }
+}
+
+/// Builds the group shader clipping a subtree to the polygon through
+/// `points`, in fractions of the element's size.
+pub(crate) fn clip_path_shader(points: &[Vector2]) -> ShaderConfig {
+    if points.len() > MAX_CLIP_PATH_POINTS {
+        crate::telemetry::warning!(
+            "Clip path has {} points, only the first {MAX_CLIP_PATH_POINTS} are used",
+            points.len()
+        );
+    }
+    let points = &points[..points.len().min(MAX_CLIP_PATH_POINTS)];
+    let mut closed: Vec<[f32; 2]> = points.iter().map(|p| [p.x, p.y]).collect();
+    closed.extend(points.first().map(|p| [p.x, p.y]));
+    let count = closed.len() as i32;
+    // The declared array length, for the uniform to be accepted
+    closed.resize(MAX_CLIP_PATH_POINTS + 1, [0.0, 0.0]);
+
+    let mut builder = ShaderBuilder::new(&CLIP_PATH);
+    builder.uniform("ply_clip_points", closed).uniform("ply_clip_count", count);
+    builder.into_config()
+}
+
+/// Whether a group shader masks by another element or captures one as a
+/// mask, so it has to be drawn even when its own commands didn't change.
+pub(crate) fn links_elements(config: &ShaderConfig) -> bool {
+    matches!(config.mask, Some(MaskSource::Element(_))) || config.name == SILHOUETTE_NAME
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Dimensions;
+    use crate::render_commands::RenderCommandConfig;
+    use crate::shaders::ShaderUniformValue;
+    use crate::{fixed, Ply};
+
+    #[test]
+    fn test_mask_element_is_rendered_offscreen() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(200.0, 200.0));
+        let frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.element().id("shape").width(fixed!(40.0)).height(fixed!(40.0)).corner_radius(20.0).background_color(0xFFFFFF).empty();
+            ui.element()
+                .id("content")
+                .width(fixed!(100.0))
+                .height(fixed!(100.0))
+                .mask_element("shape")
+                .children(|ui| {
+                    ui.element().width(fixed!(50.0)).height(fixed!(50.0)).background_color(0x336699).empty();
+                });
+            ui.eval()
+        };
+        let shape = crate::id::Id::new("shape").id;
+        let groups = |commands: &[crate::render_commands::RenderCommand<()>]| {
+            commands
+                .iter()
+                .filter_map(|command| match &command.config {
+                    RenderCommandConfig::GroupBegin { shader: Some(shader), .. } => Some((command.id, shader.mask.clone())),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Declared before the masked element, so the shape is captured from the next frame on
+        let first = groups(&frame(&mut ply));
+        assert_eq!(first.len(), 1);
+        assert!(matches!(first[0].1, Some(MaskSource::Element(id)) if id == shape));
+
+        let second = groups(&frame(&mut ply));
+        assert_eq!(second.len(), 2);
+        assert!(second.iter().any(|(id, mask)| *id == shape && mask.is_none()));
+    }
+
+    #[test]
+    fn test_clip_path_closes_polygon() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(200.0, 200.0));
+        let mut ui = ply.begin();
+        ui.element()
+            .width(fixed!(100.0))
+            .height(fixed!(100.0))
+            .clip_path(&[(0.5, 0.0), (1.0, 1.0), (0.0, 1.0)])
+            .background_color(0x336699)
+            .empty();
+        let commands = ui.eval();
+        let RenderCommandConfig::GroupBegin { shader: Some(shader), .. } = &commands[0].config else {
+            panic!("expected a clip group, got {commands:?}");
+        };
+        let uniform = |name: &str| shader.uniforms.iter().find(|u| u.name == name).map(|u| u.value.clone());
+        assert!(matches!(uniform("ply_clip_count"), Some(ShaderUniformValue::Int(4))));
+        let Some(ShaderUniformValue::Vec2Array(points)) = uniform("ply_clip_points") else {
+            panic!("clip points were dropped");
+        };
+        assert_eq!(points.len(), MAX_CLIP_PATH_POINTS + 1);
+        assert_eq!(&points[..4], &[[0.5, 0.0], [1.0, 1.0], [0.0, 1.0], [0.5, 0.0]]);
+    }
+}
@@ -1,4 +1,5 @@
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Vector2 {
     pub x: f32,
@@ -24,6 +25,7 @@ impl From<macroquad::prelude::Vec2> for Vector2 {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Dimensions {
     pub width: f32,
@@ -44,6 +46,7 @@ impl From<(f32, f32)> for Dimensions {
 
 /// An axis-aligned rectangle defined by its top-left position and dimensions.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct BoundingBox {
     pub x: f32,
@@ -437,6 +437,128 @@ pub fn ws_connect(
     }
 }
 
+/// Listen for WebSocket clients on `addr`, e.g. `"127.0.0.1:9870"` (native only).
+/// Idempotent like `ws_connect`, and used through the same `ws(id)` handle.
+///
+/// One client is served at a time: `Connected` arrives when it connects and
+/// `Closed` when it leaves, after which the next client is accepted. Messages
+/// sent while no client is connected are dropped.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn ws_listen(id: &str, addr: &str) {
+    let key = hash_id(id);
+    let mut mgr = NET_MANAGER.lock().unwrap();
+
+    if mgr.websockets.contains_key(&key) {
+        return;
+    }
+
+    let (incoming_tx, incoming_rx) = std::sync::mpsc::channel();
+    let (outgoing_tx, mut outgoing_rx) = tokio::sync::mpsc::unbounded_channel();
+
+    // Bound up front so clients can connect as soon as this returns
+    let listener = std::net::TcpListener::bind(addr).and_then(|listener| {
+        listener.set_nonblocking(true)?;
+        Ok(listener)
+    });
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to create tokio runtime for WebSocket");
+
+    runtime.spawn(async move {
+        use futures::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite;
+        use websocket::OutgoingWsMessage;
+
+        let listener = match listener.and_then(tokio::net::TcpListener::from_std) {
+            Ok(listener) => listener,
+            Err(e) => {
+                let _ = incoming_tx.send(WsMessage::Error(e.to_string()));
+                return;
+            }
+        };
+
+        loop {
+            // Until a client connects there is nobody to send to
+            let stream = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        let _ = incoming_tx.send(WsMessage::Error(e.to_string()));
+                        continue;
+                    }
+                },
+                msg = outgoing_rx.recv() => match msg {
+                    Some(OutgoingWsMessage::Close) | None => return,
+                    Some(_) => continue,
+                },
+            };
+
+            let mut socket = match tokio_tungstenite::accept_async(stream).await {
+                Ok(socket) => socket,
+                Err(e) => {
+                    let _ = incoming_tx.send(WsMessage::Error(e.to_string()));
+                    continue;
+                }
+            };
+            let _ = incoming_tx.send(WsMessage::Connected);
+
+            loop {
+                tokio::select! {
+                    msg = socket.next() => match msg {
+                        Some(Ok(tungstenite::Message::Binary(data))) => {
+                            let _ = incoming_tx.send(WsMessage::Binary(data.into()));
+                        }
+                        Some(Ok(tungstenite::Message::Text(text))) => {
+                            let _ = incoming_tx.send(WsMessage::Text(text.to_string()));
+                        }
+                        Some(Ok(tungstenite::Message::Close(_))) | None => {
+                            let _ = incoming_tx.send(WsMessage::Closed);
+                            break;
+                        }
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            let _ = incoming_tx.send(WsMessage::Error(e.to_string()));
+                            break;
+                        }
+                    },
+                    msg = outgoing_rx.recv() => {
+                        let sent = match msg {
+                            Some(OutgoingWsMessage::Text(text)) => {
+                                socket.send(tungstenite::Message::Text(text.into())).await
+                            }
+                            Some(OutgoingWsMessage::Binary(data)) => {
+                                socket.send(tungstenite::Message::Binary(data.into())).await
+                            }
+                            Some(OutgoingWsMessage::Close) | None => {
+                                let _ = socket.close(None).await;
+                                return;
+                            }
+                        };
+                        if let Err(e) = sent {
+                            let _ = incoming_tx.send(WsMessage::Error(e.to_string()));
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    mgr.websockets.insert(
+        key,
+        Tracked {
+            frames_not_accessed: 0,
+            state: WebSocketState {
+                tx: outgoing_tx,
+                rx: incoming_rx,
+                _runtime: runtime,
+            },
+        },
+    );
+}
+
 /// Get a handle to an existing WebSocket. Returns `None` if no such ID.
 pub fn ws(id: &str) -> Option<WebSocket> {
     let key = hash_id(id);
@@ -0,0 +1,249 @@
+//! Pan/zoom containers for node editors, maps and image viewers.
+//!
+//! ```ignore
+//! ui.element()
+//!     .id("board")
+//!     .width(grow!()).height(grow!())
+//!     .pan_zoom(|p| p.min_zoom(0.25).max_zoom(4.0))
+//!     .children(|ui| {
+//!         for node in &nodes {
+//!             ui.element()
+//!                 .id(("node", node.id))
+//!                 .floating(|f| f.offset(node.position))
+//!                 .children(|ui| draw_node(ui, node));
+//!         }
+//!     });
+//! ```
+//!
+//! The children are laid out unzoomed in a content element at the
+//! container's top-left, then drawn scaled and moved through the visual
+//! transform pipeline, clipped to the container. Hit testing undoes the
+//! transform, so children hover and press where they're drawn. The wheel or
+//! a pinch zooms about the pointer; dragging the background pans.
+
+use rustc_hash::FxHashMap;
+
+use crate::math::{BoundingBox, Vector2};
+
+/// Builder for a pan/zoom container, used in the closure passed to `.pan_zoom()`.
+#[derive(Debug, Clone, Copy)]
+pub struct PanZoomBuilder {
+    min_zoom: f32,
+    max_zoom: f32,
+    zoom_speed: f32,
+    drag_pan: bool,
+}
+
+impl Default for PanZoomBuilder {
+    fn default() -> Self {
+        Self { min_zoom: 0.1, max_zoom: 10.0, zoom_speed: 0.005, drag_pan: true }
+    }
+}
+
+impl PanZoomBuilder {
+    /// Smallest zoom factor. Defaults to `0.1`.
+    pub fn min_zoom(&mut self, zoom: f32) -> &mut Self {
+        self.min_zoom = zoom;
+        self
+    }
+
+    /// Largest zoom factor. Defaults to `10.0`.
+    pub fn max_zoom(&mut self, zoom: f32) -> &mut Self {
+        self.max_zoom = zoom;
+        self
+    }
+
+    /// Zoom per pixel of wheel movement, exponentially. Defaults to `0.005`.
+    pub fn zoom_speed(&mut self, speed: f32) -> &mut Self {
+        self.zoom_speed = speed;
+        self
+    }
+
+    /// Whether dragging the background pans. Defaults to `true`.
+    pub fn drag_pan(&mut self, enabled: bool) -> &mut Self {
+        self.drag_pan = enabled;
+        self
+    }
+}
+
+/// How a pan/zoom container shows its content: a content point `p` is drawn
+/// at `p * zoom + pan` from the container's top-left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanZoomView {
+    pub zoom: f32,
+    pub pan: Vector2,
+}
+
+impl Default for PanZoomView {
+    fn default() -> Self {
+        Self { zoom: 1.0, pan: Vector2::new(0.0, 0.0) }
+    }
+}
+
+impl PanZoomView {
+    /// The content point drawn at `point`, relative to the container's top-left.
+    pub fn to_content(&self, point: Vector2) -> Vector2 {
+        Vector2::new((point.x - self.pan.x) / self.zoom, (point.y - self.pan.y) / self.zoom)
+    }
+
+    /// Where content point `point` is drawn, relative to the container's top-left.
+    pub fn to_container(&self, point: Vector2) -> Vector2 {
+        Vector2::new(point.x * self.zoom + self.pan.x, point.y * self.zoom + self.pan.y)
+    }
+
+    /// Zooms by `factor` within `min..=max`, keeping what's under `point`
+    /// (relative to the container's top-left) in place.
+    pub fn zoomed_about(self, factor: f32, point: Vector2, min: f32, max: f32) -> Self {
+        let anchor = self.to_content(point);
+        let zoom = (self.zoom * factor).clamp(min, max);
+        Self { zoom, pan: Vector2::new(point.x - anchor.x * zoom, point.y - anchor.y * zoom) }
+    }
+}
+
+/// What a pan/zoom container reacts to this frame.
+pub(crate) struct PanZoomInput {
+    /// The container's bounds last frame.
+    pub bounds: Option<BoundingBox>,
+    pub pointer: Vector2,
+    pub hovered: bool,
+    /// Whether the pointer is down on the container.
+    pub pressed: bool,
+    /// Whether the topmost element under the pointer is the container or
+    /// its content element, not one of the children.
+    pub over_background: bool,
+    pub wheel: f32,
+    /// The two fingers of a pinch, if there are exactly two.
+    pub pinch: Option<(Vector2, Vector2)>,
+}
+
+struct PanZoomState {
+    view: PanZoomView,
+    drag_from: Option<Vector2>,
+    was_pressed: bool,
+    pinch_distance: Option<f32>,
+    drawn: bool,
+}
+
+/// View and gesture state of each pan/zoom container.
+#[derive(Default)]
+pub(crate) struct PanZooms {
+    states: FxHashMap<u32, PanZoomState>,
+}
+
+impl PanZooms {
+    /// Drops the state of containers not drawn last frame. Called from `begin()`.
+    pub(crate) fn sweep(&mut self) {
+        self.states.retain(|_, state| std::mem::take(&mut state.drawn));
+    }
+
+    pub(crate) fn view(&self, id: u32) -> Option<PanZoomView> {
+        self.states.get(&id).map(|state| state.view)
+    }
+
+    pub(crate) fn set_view(&mut self, id: u32, view: PanZoomView) {
+        self.state(id).view = view;
+    }
+
+    fn state(&mut self, id: u32) -> &mut PanZoomState {
+        self.states.entry(id).or_insert(PanZoomState {
+            view: PanZoomView::default(),
+            drag_from: None,
+            was_pressed: false,
+            pinch_distance: None,
+            drawn: true,
+        })
+    }
+
+    /// Applies this frame's wheel, pinch and drag to container `id` and
+    /// returns the view to draw it with.
+    pub(crate) fn update(&mut self, id: u32, config: &PanZoomBuilder, input: PanZoomInput) -> PanZoomView {
+        let state = self.state(id);
+        state.drawn = true;
+        // Sizes come from last frame's layout, so the first frame only lays out
+        let Some(bounds) = input.bounds else { return state.view };
+        let local = |point: Vector2| Vector2::new(point.x - bounds.x, point.y - bounds.y);
+        let (min, max) = (config.min_zoom, config.max_zoom);
+
+        if input.hovered && input.wheel != 0.0 {
+            let factor = (input.wheel * config.zoom_speed).exp();
+            state.view = state.view.zoomed_about(factor, local(input.pointer), min, max);
+        }
+
+        match input.pinch.filter(|_| input.hovered) {
+            Some((a, b)) => {
+                let distance = ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt();
+                if let Some(previous) = state.pinch_distance.filter(|previous| *previous > 0.0) {
+                    let center = Vector2::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+                    state.view = state.view.zoomed_about(distance / previous, local(center), min, max);
+                }
+                state.pinch_distance = Some(distance);
+                // Two fingers zoom rather than drag
+                state.drag_from = None;
+            }
+            None => state.pinch_distance = None,
+        }
+
+        if config.drag_pan && input.pressed {
+            if !state.was_pressed && input.over_background && state.pinch_distance.is_none() {
+                state.drag_from = Some(input.pointer);
+            }
+            if let Some(from) = state.drag_from {
+                state.view.pan = Vector2::new(
+                    state.view.pan.x + input.pointer.x - from.x,
+                    state.view.pan.y + input.pointer.y - from.y,
+                );
+                state.drag_from = Some(input.pointer);
+            }
+        } else {
+            state.drag_from = None;
+        }
+        state.was_pressed = input.pressed;
+        state.view
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Dimensions;
+    use crate::{fixed, Ply};
+
+    #[test]
+    fn test_zoom_keeps_point_under_cursor() {
+        let view = PanZoomView { zoom: 1.5, pan: Vector2::new(20.0, -10.0) };
+        let pointer = Vector2::new(120.0, 80.0);
+        let zoomed = view.zoomed_about(2.0, pointer, 0.1, 10.0);
+        assert_eq!(zoomed.zoom, 3.0);
+        let (before, after) = (view.to_content(pointer), zoomed.to_content(pointer));
+        assert!((before.x - after.x).abs() < 1e-4 && (before.y - after.y).abs() < 1e-4);
+        assert_eq!(view.zoomed_about(100.0, pointer, 0.1, 4.0).zoom, 4.0);
+    }
+
+    #[test]
+    fn test_pan_zoom_children_are_hit_where_drawn() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("board")
+                .width(fixed!(200.0))
+                .height(fixed!(200.0))
+                .pan_zoom(|p| p)
+                .children(|ui| {
+                    ui.element().id("node").width(fixed!(20.0)).height(fixed!(20.0)).background_color(0x336699).empty();
+                });
+            ui.eval();
+        };
+        frame(&mut ply);
+        ply.set_pan_zoom_view("board", PanZoomView { zoom: 2.0, pan: Vector2::new(50.0, 50.0) });
+        frame(&mut ply);
+
+        // The node is drawn at 50..90 now
+        ply.context.set_pointer_state(Vector2::new(65.0, 65.0), false);
+        assert!(ply.pointer_over("node"));
+        ply.context.set_pointer_state(Vector2::new(15.0, 15.0), false);
+        assert!(!ply.pointer_over("node"));
+        assert!(ply.pointer_over("board"));
+        assert_eq!(ply.pan_zoom_view("board").map(|view| view.zoom), Some(2.0));
+    }
+}
@@ -0,0 +1,648 @@
+//! Plots: line, scatter and bar series over labeled axes, with zoom and pan.
+//!
+//! ```ignore
+//! let view = ui.plot("temperature", |p| p
+//!     .label("Temperature")
+//!     .line("Inside", &inside, |s| s)
+//!     .line("Outside", &outside, |s| s.color(ORANGE))
+//!     .scatter("Samples", &samples, |s| s.point_size(4.0))
+//! );
+//! ```
+//!
+//! Series take `(x, y)` points. The view fits the data, or the ranges set
+//! with `x_range` / `y_range`, until the plot is zoomed with the scroll wheel
+//! or panned by dragging; double-clicking fits it again. Everything is drawn
+//! with regular elements, so plots work on every renderer backend.
+
+use macroquad::miniquad::CursorIcon;
+
+use crate::accessibility::AccessibilityRole;
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::id::Id;
+use crate::layout::{LayoutDirection, Sizing};
+use crate::math::Vector2;
+use crate::Ui;
+
+/// Colors given to series without one, in order.
+const PALETTE: [Color; 6] = [
+    Color::rgba(58.0, 123.0, 213.0, 255.0),
+    Color::rgba(224.0, 150.0, 20.0, 255.0),
+    Color::rgba(46.0, 160.0, 67.0, 255.0),
+    Color::rgba(210.0, 55.0, 55.0, 255.0),
+    Color::rgba(150.0, 90.0, 200.0, 255.0),
+    Color::rgba(30.0, 170.0, 170.0, 255.0),
+];
+/// Width of the space left of the plot area for y tick labels.
+const Y_AXIS_WIDTH: u16 = 44;
+/// Seconds between two clicks for them to count as a double-click.
+const DOUBLE_CLICK_TIME: f64 = 0.3;
+
+/// The ranges of data a plot shows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlotView {
+    pub x: (f32, f32),
+    pub y: (f32, f32),
+}
+
+impl PlotView {
+    fn zoomed(self, factor: f32, around: (f32, f32)) -> Self {
+        let scale = |(min, max): (f32, f32), center: f32| (center + (min - center) * factor, center + (max - center) * factor);
+        Self { x: scale(self.x, around.0), y: scale(self.y, around.1) }
+    }
+}
+
+/// Per-plot state kept between frames.
+#[derive(Debug, Default, Clone, Copy)]
+struct PlotState {
+    /// Set once zoomed or panned; until then the view follows the data.
+    view: Option<PlotView>,
+    /// Pointer position last frame, while panning.
+    drag_from: Option<Vector2>,
+    /// When the plot area was last clicked, to catch double-clicks.
+    last_click: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SeriesKind {
+    Line,
+    Scatter,
+    Bars,
+}
+
+/// Builder for configuring a plot series via closure.
+#[derive(Debug, Clone, Default)]
+pub struct SeriesBuilder {
+    pub(crate) color: Option<Color>,
+    pub(crate) thickness: Option<f32>,
+    pub(crate) point_size: Option<f32>,
+    pub(crate) bar_width: Option<f32>,
+}
+
+impl SeriesBuilder {
+    /// Sets the color. Defaults to the next color of the plot's palette.
+    #[inline]
+    pub fn color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Sets the thickness of a line. Defaults to 2.
+    #[inline]
+    pub fn thickness(&mut self, thickness: f32) -> &mut Self {
+        self.thickness = Some(thickness);
+        self
+    }
+
+    /// Sets the size of scatter points, or adds points to a line. Scatter points default to 6.
+    #[inline]
+    pub fn point_size(&mut self, size: f32) -> &mut Self {
+        self.point_size = Some(size);
+        self
+    }
+
+    /// Sets the width of bars, in x units. Defaults to 80% of the closest spacing between bars.
+    #[inline]
+    pub fn bar_width(&mut self, width: f32) -> &mut Self {
+        self.bar_width = Some(width);
+        self
+    }
+}
+
+struct Series<'d> {
+    name: String,
+    kind: SeriesKind,
+    points: &'d [(f32, f32)],
+    config: SeriesBuilder,
+}
+
+/// Builder for configuring a plot via closure.
+pub struct PlotBuilder<'d> {
+    pub(crate) label: String,
+    pub(crate) width: Sizing,
+    pub(crate) height: f32,
+    pub(crate) x_range: Option<(f32, f32)>,
+    pub(crate) y_range: Option<(f32, f32)>,
+    pub(crate) ticks: usize,
+    pub(crate) grid: bool,
+    pub(crate) legend: bool,
+    pub(crate) interactive: bool,
+    pub(crate) font_size: u16,
+    pub(crate) background_color: Color,
+    pub(crate) axis_color: Color,
+    pub(crate) grid_color: Color,
+    pub(crate) text_color: Color,
+    series: Vec<Series<'d>>,
+}
+
+impl Default for PlotBuilder<'_> {
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            width: Sizing::Grow(0.0, f32::MAX, 1.0),
+            height: 240.0,
+            x_range: None,
+            y_range: None,
+            ticks: 5,
+            grid: true,
+            legend: true,
+            interactive: true,
+            font_size: 12,
+            background_color: Color::rgba(30.0, 30.0, 30.0, 255.0),
+            axis_color: Color::rgba(120.0, 120.0, 120.0, 255.0),
+            grid_color: Color::rgba(55.0, 55.0, 55.0, 255.0),
+            text_color: Color::rgba(180.0, 180.0, 180.0, 255.0),
+            series: Vec::new(),
+        }
+    }
+}
+
+impl<'d> PlotBuilder<'d> {
+    /// Sets the accessible name of the plot.
+    #[inline]
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// Sets the width of the plot. Defaults to growing.
+    #[inline]
+    pub fn width(&mut self, width: Sizing) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the height of the plot, including tick labels and legend. Defaults to 240.
+    #[inline]
+    pub fn height(&mut self, height: f32) -> &mut Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the x range shown before zooming or panning. Defaults to fitting the data.
+    #[inline]
+    pub fn x_range(&mut self, min: f32, max: f32) -> &mut Self {
+        self.x_range = Some((min.min(max), max.max(min)));
+        self
+    }
+
+    /// Sets the y range shown before zooming or panning. Defaults to fitting the data.
+    #[inline]
+    pub fn y_range(&mut self, min: f32, max: f32) -> &mut Self {
+        self.y_range = Some((min.min(max), max.max(min)));
+        self
+    }
+
+    /// Sets about how many ticks each axis has. Defaults to 5.
+    #[inline]
+    pub fn ticks(&mut self, count: usize) -> &mut Self {
+        self.ticks = count.max(1);
+        self
+    }
+
+    /// Shows or hides grid lines at the ticks. Defaults to shown.
+    #[inline]
+    pub fn grid(&mut self, grid: bool) -> &mut Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Shows or hides the legend under the plot. Defaults to shown.
+    #[inline]
+    pub fn legend(&mut self, legend: bool) -> &mut Self {
+        self.legend = legend;
+        self
+    }
+
+    /// Enables or disables zooming and panning. Defaults to enabled.
+    #[inline]
+    pub fn interactive(&mut self, interactive: bool) -> &mut Self {
+        self.interactive = interactive;
+        self
+    }
+
+    /// Sets the font size of tick labels and the legend. Defaults to 12.
+    #[inline]
+    pub fn font_size(&mut self, size: u16) -> &mut Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Sets the background color.
+    #[inline]
+    pub fn background_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.background_color = color.into();
+        self
+    }
+
+    /// Sets the color of the axes.
+    #[inline]
+    pub fn axis_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.axis_color = color.into();
+        self
+    }
+
+    /// Sets the color of the grid lines.
+    #[inline]
+    pub fn grid_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.grid_color = color.into();
+        self
+    }
+
+    /// Sets the color of tick labels and the legend.
+    #[inline]
+    pub fn text_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.text_color = color.into();
+        self
+    }
+
+    /// Adds a series drawn as a line through its points, in order.
+    #[inline]
+    pub fn line(
+        &mut self,
+        name: &str,
+        points: &'d [(f32, f32)],
+        config_fn: impl for<'a> FnOnce(&'a mut SeriesBuilder) -> &'a mut SeriesBuilder,
+    ) -> &mut Self {
+        self.add_series(name, SeriesKind::Line, points, config_fn)
+    }
+
+    /// Adds a series drawn as a dot at each point.
+    #[inline]
+    pub fn scatter(
+        &mut self,
+        name: &str,
+        points: &'d [(f32, f32)],
+        config_fn: impl for<'a> FnOnce(&'a mut SeriesBuilder) -> &'a mut SeriesBuilder,
+    ) -> &mut Self {
+        self.add_series(name, SeriesKind::Scatter, points, config_fn)
+    }
+
+    /// Adds a series drawn as a bar from zero to each point.
+    #[inline]
+    pub fn bars(
+        &mut self,
+        name: &str,
+        points: &'d [(f32, f32)],
+        config_fn: impl for<'a> FnOnce(&'a mut SeriesBuilder) -> &'a mut SeriesBuilder,
+    ) -> &mut Self {
+        self.add_series(name, SeriesKind::Bars, points, config_fn)
+    }
+
+    fn add_series(
+        &mut self,
+        name: &str,
+        kind: SeriesKind,
+        points: &'d [(f32, f32)],
+        config_fn: impl for<'a> FnOnce(&'a mut SeriesBuilder) -> &'a mut SeriesBuilder,
+    ) -> &mut Self {
+        let mut config = SeriesBuilder::default();
+        config_fn(&mut config);
+        self.series.push(Series { name: name.to_string(), kind, points, config });
+        self
+    }
+
+    /// The view fitting all series, within the set ranges.
+    fn fitted_view(&self) -> PlotView {
+        let mut x = (f32::MAX, f32::MIN);
+        let mut y = (f32::MAX, f32::MIN);
+        for series in &self.series {
+            for &(px, py) in series.points.iter().filter(|(px, py)| px.is_finite() && py.is_finite()) {
+                x = (x.0.min(px), x.1.max(px));
+                y = (y.0.min(py), y.1.max(py));
+            }
+            if series.kind == SeriesKind::Bars {
+                y = (y.0.min(0.0), y.1.max(0.0));
+                // Leave room for the outer bars' halves
+                let half = bar_width(series) / 2.0;
+                x = (x.0 - half, x.1 + half);
+            }
+        }
+        let pad = |(min, max): (f32, f32), margin: f32| {
+            if min > max {
+                (0.0, 1.0)
+            } else if max - min <= f32::EPSILON {
+                (min - 1.0, max + 1.0)
+            } else {
+                let margin = (max - min) * margin;
+                (min - margin, max + margin)
+            }
+        };
+        PlotView {
+            x: self.x_range.unwrap_or_else(|| pad(x, 0.0)),
+            y: self.y_range.unwrap_or_else(|| pad(y, 0.05)),
+        }
+    }
+}
+
+fn bar_width(series: &Series) -> f32 {
+    series.config.bar_width.unwrap_or_else(|| {
+        let mut xs: Vec<f32> = series.points.iter().map(|(x, _)| *x).filter(|x| x.is_finite()).collect();
+        xs.sort_by(f32::total_cmp);
+        let spacing = xs.windows(2).map(|pair| pair[1] - pair[0]).filter(|gap| *gap > 0.0).fold(f32::MAX, f32::min);
+        if spacing == f32::MAX { 0.8 } else { spacing * 0.8 }
+    })
+}
+
+/// Round tick positions covering `min..=max`, about `count` of them.
+fn ticks(min: f32, max: f32, count: usize) -> (Vec<f32>, usize) {
+    let span = max - min;
+    if span <= 0.0 || !span.is_finite() {
+        return (Vec::new(), 0);
+    }
+    // Steps of 1, 2 or 5 times a power of ten
+    let rough = span / count as f32;
+    let magnitude = 10f32.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= rough)
+        .unwrap_or(10.0 * magnitude);
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    ((first..=last).map(|i| i as f32 * step).collect(), decimals)
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Adds a plot of the series added in `config_fn`.
+    ///
+    /// Returns the ranges shown, to load data for them for example.
+    ///
+    /// ```ignore
+    /// ui.plot("signal", |p| p.line("Signal", &samples, |s| s).x_range(0.0, 10.0));
+    /// ```
+    pub fn plot<'d>(
+        &mut self,
+        id: impl Into<Id>,
+        config_fn: impl for<'a> FnOnce(&'a mut PlotBuilder<'d>) -> &'a mut PlotBuilder<'d>,
+    ) -> PlotView {
+        let mut config = PlotBuilder::default();
+        config_fn(&mut config);
+        let id = id.into();
+        let area_id = id.child("area");
+        let now = self.ply.context.current_time;
+        let fitted = config.fitted_view();
+        let mut state = *self.ply.component_states.widget_state(id.id, PlotState::default);
+        let mut view = state.view.unwrap_or(fitted);
+
+        // Sizes come from last frame's layout, so the first frame only lays out
+        let area = self.ply.context.get_element_data(area_id.clone());
+        let pointer = self.ply.context.pointer_position();
+        if let Some(area) = area.filter(|area| config.interactive && area.width > 0.0 && area.height > 0.0) {
+            let to_data = |point: Vector2, view: PlotView| {
+                (
+                    view.x.0 + (point.x - area.x) / area.width * (view.x.1 - view.x.0),
+                    view.y.1 - (point.y - area.y) / area.height * (view.y.1 - view.y.0),
+                )
+            };
+            let over = self.ply.context.pointer_over(area_id.clone());
+            let wheel = self.ply.context.wheel_delta().y;
+            if over && wheel != 0.0 {
+                let factor = (1.0 - wheel * 0.005).clamp(0.5, 2.0);
+                view = view.zoomed(factor, to_data(pointer, view));
+                state.view = Some(view);
+            }
+            if self.ply.is_just_pressed(area_id.clone()) {
+                if state.last_click.is_some_and(|last| now - last <= DOUBLE_CLICK_TIME) {
+                    state.view = None;
+                    state.last_click = None;
+                    view = fitted;
+                } else {
+                    state.last_click = Some(now);
+                }
+            }
+            if self.ply.is_pressed(area_id.clone()) {
+                if let Some(from) = state.drag_from.filter(|from| *from != pointer) {
+                    let (from_x, from_y) = to_data(from, view);
+                    let (to_x, to_y) = to_data(pointer, view);
+                    let (dx, dy) = (from_x - to_x, from_y - to_y);
+                    view = PlotView { x: (view.x.0 + dx, view.x.1 + dx), y: (view.y.0 + dy, view.y.1 + dy) };
+                    state.view = Some(view);
+                }
+                state.drag_from = Some(pointer);
+            } else {
+                state.drag_from = None;
+            }
+        }
+        *self.ply.component_states.widget_state(id.id, PlotState::default) = state;
+
+        let (x_ticks, x_decimals) = ticks(view.x.0, view.x.1, config.ticks);
+        let (y_ticks, y_decimals) = ticks(view.y.0, view.y.1, config.ticks);
+        let label_height = (config.font_size as f32 * 1.5).round();
+        let description = config
+            .series
+            .iter()
+            .map(|series| format!("{}: {} points", series.name, series.points.len()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let series_color = |index: usize, series: &Series| series.config.color.unwrap_or(PALETTE[index % PALETTE.len()]);
+        let panning = state.drag_from.is_some();
+
+        self.element()
+            .id(id.clone())
+            .width(config.width)
+            .height(Sizing::Fixed(config.height))
+            .background_color(config.background_color)
+            .accessibility(|a| a
+                .role(AccessibilityRole::Image)
+                .label(&config.label)
+                .description(&description)
+            )
+            .layout(|l| l
+                .direction(LayoutDirection::TopToBottom)
+                .padding((8, 12, 8, Y_AXIS_WIDTH))
+                .gap(label_height as u16)
+            )
+            .children(|ui| {
+                ui.element()
+                    .id(area_id.clone())
+                    .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+                    .height(Sizing::Grow(0.0, f32::MAX, 1.0))
+                    .border(|b| b.color(config.axis_color).left(1).bottom(1))
+                    .children(|ui| {
+                        if panning {
+                            ui.ply.requested_cursor = Some(CursorIcon::Move);
+                        }
+                        let Some(area) = area else {
+                            return;
+                        };
+                        let (width, height) = (area.width, area.height);
+                        let to_x = |x: f32| (x - view.x.0) / (view.x.1 - view.x.0) * width;
+                        let to_y = |y: f32| (view.y.1 - y) / (view.y.1 - view.y.0) * height;
+                        let text_color = config.text_color;
+                        let font_size = config.font_size;
+
+                        // Grid lines and tick labels
+                        for &tick in &x_ticks {
+                            let x = to_x(tick);
+                            if config.grid {
+                                plot_rect(ui, (x, 0.0), (1.0, height), config.grid_color);
+                            }
+                            ui.element()
+                                .floating(|f| f
+                                    .attach_parent()
+                                    .anchor((AlignX::CenterX, AlignY::Top), (AlignX::Left, AlignY::Bottom))
+                                    .offset((x, 4.0))
+                                    .passthrough()
+                                )
+                                .children(|ui| {
+                                    ui.text(&format!("{tick:.x_decimals$}"), |t| t.font_size(font_size).color(text_color));
+                                });
+                        }
+                        for &tick in &y_ticks {
+                            let y = to_y(tick);
+                            if config.grid {
+                                plot_rect(ui, (0.0, y), (width, 1.0), config.grid_color);
+                            }
+                            ui.element()
+                                .floating(|f| f
+                                    .attach_parent()
+                                    .anchor((AlignX::Right, AlignY::CenterY), (AlignX::Left, AlignY::Top))
+                                    .offset((-4.0, y))
+                                    .passthrough()
+                                )
+                                .children(|ui| {
+                                    ui.text(&format!("{tick:.y_decimals$}"), |t| t.font_size(font_size).color(text_color));
+                                });
+                        }
+
+                        // Series, clipped to the area
+                        for (index, series) in config.series.iter().enumerate() {
+                            let color = series_color(index, series);
+                            let points = series.points.iter().filter(|(x, y)| x.is_finite() && y.is_finite());
+                            match series.kind {
+                                SeriesKind::Line => {
+                                    let thickness = series.config.thickness.unwrap_or(2.0);
+                                    let pixels: Vec<(f32, f32)> = points.map(|&(x, y)| (to_x(x), to_y(y))).collect();
+                                    for pair in pixels.windows(2) {
+                                        ui.line_segment(None, pair[0], pair[1], thickness, color, true);
+                                    }
+                                    if let Some(size) = series.config.point_size {
+                                        for &(x, y) in &pixels {
+                                            plot_dot(ui, (x, y), size, color);
+                                        }
+                                    }
+                                }
+                                SeriesKind::Scatter => {
+                                    let size = series.config.point_size.unwrap_or(6.0);
+                                    for &(x, y) in points {
+                                        plot_dot(ui, (to_x(x), to_y(y)), size, color);
+                                    }
+                                }
+                                SeriesKind::Bars => {
+                                    let half = bar_width(series) / 2.0;
+                                    let zero = to_y(0.0);
+                                    for &(x, y) in points {
+                                        let (left, right) = (to_x(x - half), to_x(x + half));
+                                        let top = to_y(y).min(zero);
+                                        let bottom = to_y(y).max(zero);
+                                        plot_rect(ui, (left, top), (right - left, bottom - top), color);
+                                    }
+                                }
+                            }
+                        }
+                    });
+
+                if config.legend && !config.series.is_empty() {
+                    ui.element()
+                        .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+                        .layout(|l| l.wrap().gap(12).align(AlignX::Left, AlignY::CenterY))
+                        .children(|ui| {
+                            for (index, series) in config.series.iter().enumerate() {
+                                let color = series_color(index, series);
+                                ui.element()
+                                    .layout(|l| l.gap(6).align(AlignX::Left, AlignY::CenterY))
+                                    .children(|ui| {
+                                        let swatch = config.font_size as f32 * 0.75;
+                                        ui.element()
+                                            .width(Sizing::Fixed(swatch))
+                                            .height(Sizing::Fixed(swatch))
+                                            .background_color(color)
+                                            .corner_radius(if series.kind == SeriesKind::Scatter { swatch / 2.0 } else { 2.0 })
+                                            .empty();
+                                        ui.text(&series.name, |t| t.font_size(config.font_size).color(config.text_color));
+                                    });
+                            }
+                        });
+                }
+            });
+
+        view
+    }
+}
+
+/// A rectangle at `(x, y)` pixels from the plot area's top-left, clipped to it.
+/// The pointer passes through to the area, so it can still zoom and pan.
+fn plot_rect<C: Clone + Default + std::fmt::Debug>(ui: &mut Ui<'_, C>, (x, y): (f32, f32), (width, height): (f32, f32), color: Color) {
+    ui.element()
+        .width(Sizing::Fixed(width.max(0.0)))
+        .height(Sizing::Fixed(height.max(0.0)))
+        .background_color(color)
+        .floating(|f| f.attach_parent().anchor((AlignX::Left, AlignY::Top), (AlignX::Left, AlignY::Top)).offset((x, y)).clip_by_parent().passthrough())
+        .empty();
+}
+
+/// A dot centered at `(x, y)` pixels from the plot area's top-left, clipped to
+/// it and letting the pointer through.
+fn plot_dot<C: Clone + Default + std::fmt::Debug>(ui: &mut Ui<'_, C>, (x, y): (f32, f32), size: f32, color: Color) {
+    ui.element()
+        .width(Sizing::Fixed(size))
+        .height(Sizing::Fixed(size))
+        .background_color(color)
+        .corner_radius(size / 2.0)
+        .floating(|f| f.attach_parent().anchor((AlignX::CenterX, AlignY::CenterY), (AlignX::Left, AlignY::Top)).offset((x, y)).clip_by_parent().passthrough())
+        .empty();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Dimensions;
+    use crate::Ply;
+
+    #[test]
+    fn test_plot_fits_data_then_zooms_pans_and_resets() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let points = [(0.0, 0.0), (5.0, 10.0), (10.0, 5.0)];
+        let frame = |ply: &mut Ply<()>| {
+            ply.context.current_time += 1.0;
+            let mut ui = ply.begin();
+            let view = ui.plot("plot", |p| p.y_range(0.0, 10.0).line("Data", &points, |s| s));
+            ui.eval();
+            view
+        };
+        assert_eq!(ticks(0.0, 10.0, 5), (vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0], 0));
+
+        assert_eq!(frame(&mut ply), PlotView { x: (0.0, 10.0), y: (0.0, 10.0) });
+        frame(&mut ply);
+        let area = ply.context.get_element_data(Id::new("plot").child("area")).unwrap();
+        let center = Vector2::new(area.x + area.width / 2.0, area.y + area.height / 2.0);
+
+        // Scrolling up over the center zooms in around it
+        ply.context.set_pointer_state(center, false);
+        ply.update_scroll_containers(false, Vector2::new(0.0, 100.0), 0.016);
+        let zoomed = frame(&mut ply);
+        assert!((zoomed.x.0 - 2.5).abs() < 0.01 && (zoomed.x.1 - 7.5).abs() < 0.01);
+        ply.update_scroll_containers(false, Vector2::new(0.0, 0.0), 0.016);
+
+        // Dragging left by a quarter of the area pans right by a quarter of the view
+        ply.context.set_pointer_state(center, true);
+        frame(&mut ply);
+        ply.context.set_pointer_state(Vector2::new(center.x - area.width / 4.0, center.y), true);
+        let panned = frame(&mut ply);
+        assert!((panned.x.0 - 3.75).abs() < 0.01 && (panned.x.1 - 8.75).abs() < 0.01);
+        ply.context.set_pointer_state(Vector2::new(center.x - area.width / 4.0, center.y), false);
+        frame(&mut ply);
+
+        // A double-click fits the data again
+        ply.context.set_pointer_state(center, true);
+        frame(&mut ply);
+        ply.context.set_pointer_state(center, false);
+        ply.context.current_time -= 0.9;
+        frame(&mut ply);
+        ply.context.set_pointer_state(center, true);
+        ply.context.current_time -= 0.9;
+        assert_eq!(frame(&mut ply), PlotView { x: (0.0, 10.0), y: (0.0, 10.0) });
+    }
+}
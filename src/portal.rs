@@ -0,0 +1,151 @@
+//! Portals: elements declared in place but laid out and drawn elsewhere.
+//!
+//! ```ignore
+//! ui.element().id("list").overflow(|o| o.scroll_y()).children(|ui| {
+//!     for row in &rows {
+//!         let row_id = Id::new_index("row", row.index);
+//!         ui.element().id(row_id).children(|ui| {
+//!             ui.text(&row.name, |t| t);
+//!             if row.menu_open {
+//!                 // Not clipped by the list, above everything in the content layer
+//!                 ui.portal(Layer::Overlay, |ui| {
+//!                     ui.element()
+//!                         .floating(|f| f.attach_id(row_id).anchor((Left, Top), (Left, Bottom)))
+//!                         .children(|ui| row_menu(ui, row));
+//!                 });
+//!             }
+//!         });
+//!     }
+//! });
+//! ```
+//!
+//! The portal's children are declared where the portal is, so they can use
+//! local ids and state, but they're laid out in a container covering the
+//! target: the whole layout for a [`Layer`], or the element for an id. They
+//! aren't clipped by the elements around the portal, and are drawn at the
+//! layer's z-index, or the target element's. Floating elements inside count
+//! their z-index from the portal's. The container passes the pointer
+//! through; floating children capture it as usual.
+
+use crate::align::{AlignX, AlignY};
+use crate::elements::Layer;
+use crate::id::Id;
+use crate::layout::Sizing;
+use crate::Ui;
+
+/// Where a portal's children are laid out, see [`Ui::portal`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortalTarget {
+    /// Over the whole layout, at the layer's z-index.
+    Layer(Layer),
+    /// Over the element with this id, at the z-index it was drawn at.
+    Element(Id),
+}
+
+impl From<Layer> for PortalTarget {
+    fn from(layer: Layer) -> Self {
+        PortalTarget::Layer(layer)
+    }
+}
+
+impl From<Id> for PortalTarget {
+    fn from(id: Id) -> Self {
+        PortalTarget::Element(id)
+    }
+}
+
+impl From<&'static str> for PortalTarget {
+    fn from(label: &'static str) -> Self {
+        PortalTarget::Element(label.into())
+    }
+}
+
+impl From<(&str, u32)> for PortalTarget {
+    fn from(label: (&str, u32)) -> Self {
+        PortalTarget::Element(label.into())
+    }
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Declares elements here but lays them out over `target`, a [`Layer`]
+    /// or an element id, escaping the clipping and z-order around them.
+    pub fn portal(&mut self, target: impl Into<PortalTarget>, f: impl FnOnce(&mut Ui<'_, CustomElementData>)) {
+        let target = target.into();
+        let z_index = match &target {
+            PortalTarget::Layer(layer) => layer.z_index(),
+            PortalTarget::Element(id) => self.ply.context.get_element_z_index(id.clone()).unwrap_or(0),
+        };
+        let grow = Sizing::Grow(0.0, f32::MAX, 1.0);
+        // The container's own z-index isn't relative to an enclosing portal
+        let outer = std::mem::take(&mut self.ply.context.portal_z_index);
+        self.element()
+            .width(grow)
+            .height(grow)
+            .floating(|f| {
+                match target {
+                    PortalTarget::Layer(_) => f.attach_root(),
+                    PortalTarget::Element(id) => f.attach_id(id),
+                };
+                f.anchor((AlignX::Left, AlignY::Top), (AlignX::Left, AlignY::Top))
+                    .z_index(z_index)
+                    .passthrough()
+            })
+            .children(|ui| {
+                ui.ply.context.portal_z_index = z_index;
+                f(ui);
+            });
+        self.ply.context.portal_z_index = outer;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Dimensions;
+    use crate::{fixed, Ply};
+
+    #[test]
+    fn test_portal_escapes_clipping_scroll_container() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("list")
+                .width(fixed!(100.0))
+                .height(fixed!(50.0))
+                .overflow(|o| o.scroll_y())
+                .children(|ui| {
+                    ui.element().id("row").width(fixed!(100.0)).height(fixed!(40.0)).children(|ui| {
+                        ui.portal(Layer::Overlay, |ui| {
+                            ui.element()
+                                .id("menu")
+                                .width(fixed!(80.0))
+                                .height(fixed!(120.0))
+                                .floating(|f| f.attach_id("row").anchor((AlignX::Left, AlignY::Top), (AlignX::Left, AlignY::Bottom)))
+                                .background_color(0x336699)
+                                .empty();
+                        });
+                    });
+                });
+            ui.eval()
+        };
+
+        frame(&mut ply);
+        let commands = frame(&mut ply);
+        let menu = Id::new("menu").id;
+        let index = commands.iter().position(|command| command.id == menu).expect("the menu was drawn");
+        assert_eq!(commands[index].bounding_box.y, 40.0);
+        assert_eq!(commands[index].z_index, Layer::Overlay.z_index());
+        // Not inside the list's scissor
+        let scissors_open = commands[..index]
+            .iter()
+            .filter(|command| matches!(command.config, crate::render_commands::RenderCommandConfig::ScissorStart()))
+            .count();
+        let scissors_closed = commands[..index]
+            .iter()
+            .filter(|command| matches!(command.config, crate::render_commands::RenderCommandConfig::ScissorEnd()))
+            .count();
+        assert_eq!(scissors_open, scissors_closed);
+        assert_eq!(ply.z_index("menu"), Some(Layer::Overlay.z_index()));
+    }
+}
@@ -10,16 +10,26 @@ pub use crate::Ui;
 pub use crate::id::Id;
 pub use crate::renderer::GraphicAsset;
 pub use crate::renderer::FontAsset;
+pub use crate::renderer::EdgeAntialiasing;
 pub use crate::shaders::ShaderAsset;
 pub use crate::lerp::Lerp;
 pub use crate::easing::*;
 
+// Transitions
+pub use crate::transition::CROSSFADE;
+
+// Pan/zoom containers
+pub use crate::pan_zoom::PanZoomView;
+
 // Utility functions
 pub use crate::renderer::render_to_texture;
 pub use crate::renderer::set_shader_source;
 
 // Macros
-pub use crate::{grow, fit, fixed, percent};
+pub use crate::{grow, fit, fixed, percent, vw, vh, token};
+
+// Z-order layers — type only, NOT globbed
+pub use crate::elements::Layer;
 
 // Alignment — globbed
 pub use crate::align::AlignX::{self, *};
@@ -34,8 +44,90 @@ pub use crate::layout::LayoutDirection::{self, *};
 // WrapMode — type only, NOT globbed
 pub use crate::text::WrapMode;
 
+// Inline atoms in text
+pub use crate::text::{InlineAlign, INLINE_ATOM};
+
 // AccessibilityRole — type only, NOT globbed
 pub use crate::accessibility::AccessibilityRole;
+pub use crate::accessibility::AccessibilityNode;
+pub use crate::accessibility::HighContrastPalette;
+pub use crate::accessibility::LiveRegionMode;
+
+// Theming
+pub use crate::theme::{Theme, Token, TokenValue};
+
+// Style classes
+pub use crate::style::Style;
+pub use crate::component::Component;
+#[cfg(feature = "stylesheet")]
+pub use crate::stylesheet::Stylesheet;
+#[cfg(feature = "templates")]
+pub use crate::template::{Template, TemplateBuilder};
+#[cfg(feature = "scripting")]
+pub use crate::script::Script;
+
+// Search field composite
+pub use crate::search_input::SearchInputBuilder;
+
+// Checkbox and radio group styling
+pub use crate::toggle::ToggleBuilder;
+
+// Dropdown
+pub use crate::dropdown::DropdownBuilder;
+
+// Split panes
+pub use crate::split::SplitBuilder;
+
+// Toggle switches
+pub use crate::switch::SwitchBuilder;
+
+// Chips inputs
+pub use crate::chips::ChipsBuilder;
+
+// Progress indicators
+pub use crate::progress::{ProgressBarBuilder, SpinnerBuilder};
+
+// Knobs
+pub use crate::knob::KnobBuilder;
+
+// Charts
+pub use crate::chart::ChartBuilder;
+
+// Captured pixels
+pub use crate::capture::ImageBuffer;
+
+// Profiling
+pub use crate::profiler::{FrameStats, TextCacheStats};
+
+// Diagnostics
+pub use crate::diagnostics::Diagnostic;
+
+// Render diffs
+pub use crate::render_diff::{RenderChange, RenderChangeKind};
+
+// Render backends
+pub use crate::renderer::{MacroquadRenderer, Renderer};
+#[cfg(feature = "software")]
+pub use crate::software::SoftwareRenderer;
+#[cfg(all(feature = "winit", not(target_arch = "wasm32")))]
+pub use crate::winit::{WinitAdapter, WinitWindows};
+#[cfg(feature = "bevy")]
+pub use crate::bevy_ply::{PlyNode, PlyPlugin, PlyUi};
+#[cfg(all(feature = "remote", not(target_arch = "wasm32")))]
+pub use crate::remote::RemoteRenderer;
+#[cfg(feature = "remote")]
+pub use crate::remote::RemoteView;
+
+// Plots — feature-gated
+#[cfg(feature = "plot")]
+pub use crate::plot::{PlotBuilder, PlotView, SeriesBuilder};
+
+// Toasts — ToastLevel globbed
+pub use crate::toast::ToastBuilder;
+pub use crate::toast::ToastLevel::{self, *};
+
+// System appearance preferences
+pub use crate::system;
 
 // Built-in shaders — feature-gated, globbed
 #[cfg(feature = "built-in-shaders")]
@@ -47,16 +139,30 @@ pub use crate::net;
 #[cfg(feature = "net")]
 pub use crate::net::WsMessage;
 
+// File dialogs — feature-gated
+#[cfg(feature = "dialogs")]
+pub use crate::dialogs;
+
 // Storage — feature-gated
 #[cfg(feature = "storage")]
 pub use crate::storage::Storage;
 
+// Clipboard images — feature-gated
+#[cfg(feature = "clipboard")]
+pub use crate::clipboard::ClipboardImage;
+
+// Syntax-highlighted code blocks — feature-gated
+#[cfg(feature = "syntax-highlighting")]
+pub use crate::code::CodeBuilder;
+
 // Jobs
 pub use crate::jobs;
 
 // Text styling cursor utilities — feature-gated
 #[cfg(feature = "text-styling")]
 pub use crate::text_input::styling;
+#[cfg(feature = "text-styling")]
+pub use crate::text_styling::{SpanStyle, StyledText};
 
 // Full macroquad prelude, with Color shadowed by ply's version
 pub use macroquad::prelude::*;
@@ -0,0 +1,201 @@
+//! Per-frame timings and counters, for finding out where a frame goes.
+//!
+//! ```ignore
+//! ply.set_profiler_hud(true);
+//! // or, to log them:
+//! let stats = ply.frame_stats();
+//! println!("{:.2} ms, {} elements", stats.total_ms(), stats.element_count);
+//! ```
+//!
+//! The stats describe the last evaluated frame. The HUD shows them in the
+//! top-left corner, one frame behind.
+
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::elements::Layer;
+use crate::layout::LayoutDirection;
+use crate::{Ply, Ui};
+
+/// Where the last frame's time went, in milliseconds, and what it contained.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    /// Declaring elements, from `begin()` until `eval()`.
+    pub declaration_ms: f64,
+    /// Sizing, wrapping and positioning elements.
+    pub layout_ms: f64,
+    /// Measuring and wrapping text. Part of declaration and layout, not extra.
+    pub text_ms: f64,
+    /// Turning the layout into render commands.
+    pub render_commands_ms: f64,
+    /// Drawing the render commands in `show()`. Zero after `eval()` alone.
+    pub rendering_ms: f64,
+    pub element_count: u32,
+    pub render_command_count: u32,
+    /// Text measurements answered from the cache.
+    pub text_cache_hits: u32,
+    /// Text measurements that had to call the measure function.
+    pub text_cache_misses: u32,
+    /// Whether sizes and text wrapping were reused from the previous frame,
+    /// which declared the same layout.
+    pub layout_cached: bool,
+    /// Subtrees skipped for lying outside a clipping element's visible area.
+    pub culled_subtrees: u32,
+}
+
+impl FrameStats {
+    /// Time spent in Ply during the frame.
+    pub fn total_ms(&self) -> f64 {
+        self.declaration_ms + self.layout_ms + self.render_commands_ms + self.rendering_ms
+    }
+
+    /// Share of text measurements answered from the cache, `1.0` when there were none.
+    pub fn text_cache_hit_rate(&self) -> f32 {
+        let total = self.text_cache_hits + self.text_cache_misses;
+        if total == 0 {
+            1.0
+        } else {
+            self.text_cache_hits as f32 / total as f32
+        }
+    }
+}
+
+/// What the text measurement cache holds, and how it has fared since the
+/// measure function was set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextCacheStats {
+    /// Distinct text and config combinations measured.
+    pub entries: u32,
+    /// Words held, counting empty texts as one.
+    pub words: u32,
+    /// `max_measure_text_cache_word_count`. Least recently used entries are
+    /// evicted past it.
+    pub capacity: u32,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// Milliseconds on a monotonic-enough clock that also works on the web.
+pub(crate) fn now_ms() -> f64 {
+    macroquad::miniquad::date::now() * 1000.0
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData> {
+    /// Declares the profiler HUD with the previous frame's stats. Called from `eval()`.
+    pub(crate) fn draw_profiler_hud(&mut self) {
+        let stats = self.frame_stats;
+        let lines = [
+            format!("frame      {:6.2} ms", stats.total_ms()),
+            format!("declare    {:6.2} ms", stats.declaration_ms),
+            format!("layout     {:6.2} ms{}", stats.layout_ms, if stats.layout_cached { " (cached)" } else { "" }),
+            format!("  text     {:6.2} ms", stats.text_ms),
+            format!("commands   {:6.2} ms", stats.render_commands_ms),
+            format!("render     {:6.2} ms", stats.rendering_ms),
+            format!("elements   {:6}", stats.element_count),
+            format!("draw cmds  {:6}", stats.render_command_count),
+            format!("culled     {:6}", stats.culled_subtrees),
+            format!("text cache {:5.1} %", stats.text_cache_hit_rate() * 100.0),
+        ];
+        let mut ui = Ui { ply: &mut *self };
+        ui.element()
+            .id("ply_profiler_hud")
+            .floating(|f| f
+                .attach_root()
+                .anchor((AlignX::Left, AlignY::Top), (AlignX::Left, AlignY::Top))
+                .offset((8.0, 8.0))
+                .layer(Layer::Debug)
+                .passthrough()
+            )
+            .background_color(Color::rgba(0.0, 0.0, 0.0, 190.0))
+            .corner_radius(4.0)
+            .layout(|l| l.direction(LayoutDirection::TopToBottom).padding(8).gap(2))
+            .children(|ui| {
+                for line in &lines {
+                    ui.text(line, |t| t.font_size(14).color(Color::rgba(120.0, 255.0, 140.0, 255.0)));
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed;
+    use crate::math::Dimensions;
+
+    #[test]
+    fn test_frame_stats_count_elements_and_text_cache() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        ply.set_measure_text_function(|text, _| Dimensions::new(text.len() as f32 * 8.0, 16.0));
+        let frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.element().width(fixed!(100.0)).height(fixed!(40.0)).background_color(0x202020).children(|ui| {
+                ui.text("Hello", |t| t.font_size(16));
+            });
+            ui.eval();
+        };
+
+        frame(&mut ply);
+        let first = ply.frame_stats();
+        assert!(first.element_count >= 3);
+        assert!(first.render_command_count >= 2);
+        assert!(first.text_cache_misses > 0);
+        assert_eq!(first.rendering_ms, 0.0);
+
+        // The same text again comes from the cache
+        frame(&mut ply);
+        let second = ply.frame_stats();
+        assert_eq!(second.text_cache_misses, 0);
+        assert!(second.text_cache_hits > 0);
+        assert_eq!(second.text_cache_hit_rate(), 1.0);
+
+        // The HUD adds itself on top
+        ply.set_profiler_hud(true);
+        frame(&mut ply);
+        assert!(ply.frame_stats().element_count > second.element_count);
+        assert!(ply.bounding_box("ply_profiler_hud").is_some());
+    }
+
+    #[test]
+    fn test_text_cache_survives_font_size_switches_and_evicts_by_capacity() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        ply.set_measure_text_function(|text, config| {
+            Dimensions::new(text.len() as f32 * config.font_size as f32 / 2.0, config.font_size as f32)
+        });
+        let frame = |ply: &mut Ply<()>, font_size: u16, labels: &[&str]| {
+            let mut ui = ply.begin();
+            for label in labels {
+                ui.text(label, |t| t.font_size(font_size));
+            }
+            ui.eval();
+        };
+
+        frame(&mut ply, 16, &["Hello world"]);
+        // A "theme switch" that lasts a few frames, then back
+        for _ in 0..5 {
+            frame(&mut ply, 24, &["Hello world"]);
+        }
+        frame(&mut ply, 16, &["Hello world"]);
+        assert_eq!(ply.frame_stats().text_cache_misses, 0);
+        let stats = ply.text_cache_stats();
+        assert_eq!((stats.entries, stats.words, stats.misses, stats.evictions), (2, 4, 2, 0));
+
+        // Past capacity, the least recently used entries go
+        ply.max_measure_text_cache_word_count(8);
+        for labels in [["a b", "c d"], ["e f", "g h"], ["i j", "k l"]] {
+            frame(&mut ply, 16, &labels);
+        }
+        assert_eq!(ply.text_cache_stats().evictions, 3);
+        // Eviction happens between frames and spares the last frame's text
+        frame(&mut ply, 16, &["i j", "k l"]);
+        assert_eq!(ply.frame_stats().text_cache_misses, 0);
+        let stats = ply.text_cache_stats();
+        assert_eq!(stats.capacity, 8);
+        assert!(stats.words <= 8);
+        assert_eq!(stats.evictions, 5);
+
+        // A new measure function starts the cache over
+        ply.set_measure_text_function(|text, _| Dimensions::new(text.len() as f32 * 8.0, 16.0));
+        assert_eq!((ply.text_cache_stats().entries, ply.text_cache_stats().words), (0, 0));
+    }
+}
@@ -0,0 +1,317 @@
+//! Progress indicators: progress bars and spinners.
+//!
+//! ```ignore
+//! ui.progress_bar("download", downloaded as f32 / total as f32, |p| p.label("Downloading"));
+//! ui.progress_bar("connecting", 0.0, |p| p.label("Connecting").indeterminate());
+//! ui.spinner("loading", |s| s.size(32.0));
+//! ```
+//!
+//! Both animate on their own from the frame time. A progress bar's fill
+//! glides to new values instead of jumping; an indeterminate bar, for work
+//! of unknown length, sweeps a segment across instead. Screen readers see
+//! both as progress bars, with the percentage when it is known.
+
+use crate::accessibility::AccessibilityRole;
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::component::ComponentStates;
+use crate::id::Id;
+use crate::layout::Sizing;
+use crate::lerp::Lerp;
+use crate::Ui;
+
+/// How quickly a bar's fill catches up with its value. Higher is faster.
+const FILL_SPEED: f32 = 12.0;
+/// Seconds an indeterminate bar's segment takes to cross the track.
+const SWEEP_DURATION: f32 = 1.2;
+/// Width of an indeterminate bar's segment, as a share of the track.
+const SWEEP_WIDTH: f32 = 0.3;
+/// Dots around a spinner.
+const SPINNER_DOTS: usize = 8;
+
+/// Per-indicator animation state kept between frames.
+#[derive(Debug, Default, Clone, Copy)]
+struct ProgressState {
+    /// Fraction the bar's fill shows right now.
+    shown: f32,
+    /// Position in the looping animation, from 0 to 1.
+    phase: f32,
+}
+
+/// Builder for configuring a progress bar via closure.
+#[derive(Debug, Clone)]
+pub struct ProgressBarBuilder {
+    pub(crate) label: String,
+    pub(crate) indeterminate: bool,
+    pub(crate) width: Sizing,
+    pub(crate) height: f32,
+    pub(crate) corner_radius: f32,
+    pub(crate) track_color: Color,
+    pub(crate) fill_color: Color,
+}
+
+impl Default for ProgressBarBuilder {
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            indeterminate: false,
+            width: Sizing::Grow(0.0, f32::MAX, 1.0),
+            height: 8.0,
+            corner_radius: 4.0,
+            track_color: Color::rgba(60.0, 60.0, 60.0, 255.0),
+            fill_color: Color::rgba(58.0, 123.0, 213.0, 255.0),
+        }
+    }
+}
+
+impl ProgressBarBuilder {
+    /// Sets the accessible name of the bar.
+    #[inline]
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// Ignores the fraction and sweeps a segment across the track, for work of unknown length.
+    #[inline]
+    pub fn indeterminate(&mut self) -> &mut Self {
+        self.indeterminate = true;
+        self
+    }
+
+    /// Sets the width of the bar. Defaults to growing.
+    #[inline]
+    pub fn width(&mut self, width: Sizing) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the height of the bar. Defaults to 8.
+    #[inline]
+    pub fn height(&mut self, height: f32) -> &mut Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the corner radius of the track and fill. Defaults to 4.
+    #[inline]
+    pub fn corner_radius(&mut self, radius: f32) -> &mut Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Sets the color of the track.
+    #[inline]
+    pub fn track_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.track_color = color.into();
+        self
+    }
+
+    /// Sets the color of the fill.
+    #[inline]
+    pub fn fill_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.fill_color = color.into();
+        self
+    }
+}
+
+/// Builder for configuring a spinner via closure.
+#[derive(Debug, Clone)]
+pub struct SpinnerBuilder {
+    pub(crate) label: String,
+    pub(crate) size: f32,
+    pub(crate) color: Color,
+    pub(crate) duration: f32,
+}
+
+impl Default for SpinnerBuilder {
+    fn default() -> Self {
+        Self {
+            label: "Loading".to_string(),
+            size: 24.0,
+            color: Color::rgba(255.0, 255.0, 255.0, 255.0),
+            duration: 1.0,
+        }
+    }
+}
+
+impl SpinnerBuilder {
+    /// Sets the accessible name of the spinner. Defaults to `"Loading"`.
+    #[inline]
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// Sets the width and height of the spinner. Defaults to 24.
+    #[inline]
+    pub fn size(&mut self, size: f32) -> &mut Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets the color of the dots.
+    #[inline]
+    pub fn color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Sets the seconds per turn. Defaults to 1.
+    #[inline]
+    pub fn duration(&mut self, seconds: f32) -> &mut Self {
+        self.duration = seconds.max(0.01);
+        self
+    }
+}
+
+/// Advances the animation state of `id` and returns it.
+fn advance(states: &mut ComponentStates, id: u32, target: f32, delta: f32, period: f32) -> ProgressState {
+    let state = states.widget_state(id, || ProgressState { shown: target, phase: 0.0 });
+    state.shown = state.shown.lerp(target, 1.0 - (-delta * FILL_SPEED).exp());
+    state.phase = (state.phase + delta / period).fract();
+    *state
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Adds a progress bar filled to `fraction`, from `0.0` to `1.0`.
+    ///
+    /// ```ignore
+    /// ui.progress_bar("upload", sent as f32 / size as f32, |p| p.label("Uploading"));
+    /// ```
+    pub fn progress_bar(
+        &mut self,
+        id: impl Into<Id>,
+        fraction: f32,
+        config_fn: impl for<'a> FnOnce(&'a mut ProgressBarBuilder) -> &'a mut ProgressBarBuilder,
+    ) {
+        let mut config = ProgressBarBuilder::default();
+        config_fn(&mut config);
+        let id = id.into();
+        let fraction = if fraction.is_nan() { 0.0 } else { fraction.clamp(0.0, 1.0) };
+        let delta = self.ply.context.frame_delta_time;
+        let state = advance(&mut self.ply.component_states, id.id, fraction, delta, SWEEP_DURATION);
+
+        // The segment enters from the left edge and leaves past the right one
+        let (start, end) = if config.indeterminate {
+            let start = state.phase * (1.0 + SWEEP_WIDTH) - SWEEP_WIDTH;
+            (start.max(0.0), (start + SWEEP_WIDTH).min(1.0))
+        } else {
+            (0.0, state.shown)
+        };
+
+        self.element()
+            .id(id.clone())
+            .width(config.width)
+            .height(Sizing::Fixed(config.height))
+            .background_color(config.track_color)
+            .corner_radius(config.corner_radius)
+            .overflow(|o| o.clip())
+            .accessibility(|a| {
+                a.role(AccessibilityRole::ProgressBar).label(&config.label);
+                if !config.indeterminate {
+                    a.value(&format!("{}", (fraction * 100.0).round()))
+                        .value_min(0.0)
+                        .value_max(100.0);
+                }
+                a
+            })
+            .layout(|l| l.align(AlignX::Left, AlignY::Top))
+            .children(|ui| {
+                ui.element()
+                    .width(Sizing::Percent(start))
+                    .height(Sizing::Grow(0.0, f32::MAX, 1.0))
+                    .empty();
+                ui.element()
+                    .id(id.child("fill"))
+                    .width(Sizing::Percent(end - start))
+                    .height(Sizing::Grow(0.0, f32::MAX, 1.0))
+                    .background_color(config.fill_color)
+                    .corner_radius(config.corner_radius)
+                    .empty();
+            });
+    }
+
+    /// Adds a spinning ring of dots, for work of unknown length.
+    ///
+    /// ```ignore
+    /// if loading {
+    ///     ui.spinner("loading", |s| s.size(32.0).label("Loading results"));
+    /// }
+    /// ```
+    pub fn spinner(
+        &mut self,
+        id: impl Into<Id>,
+        config_fn: impl for<'a> FnOnce(&'a mut SpinnerBuilder) -> &'a mut SpinnerBuilder,
+    ) {
+        let mut config = SpinnerBuilder::default();
+        config_fn(&mut config);
+        let id = id.into();
+        let delta = self.ply.context.frame_delta_time;
+        let state = advance(&mut self.ply.component_states, id.id, 0.0, delta, config.duration);
+
+        let dot_size = (config.size / 5.0).max(2.0);
+        let radius = (config.size - dot_size) / 2.0;
+        let head = state.phase * SPINNER_DOTS as f32;
+
+        self.element()
+            .id(id.clone())
+            .width(Sizing::Fixed(config.size))
+            .height(Sizing::Fixed(config.size))
+            .accessibility(|a| a.role(AccessibilityRole::ProgressBar).label(&config.label))
+            .children(|ui| {
+                for dot in 0..SPINNER_DOTS {
+                    let angle = dot as f32 / SPINNER_DOTS as f32 * std::f32::consts::TAU;
+                    // Dots fade out behind the head as it goes round
+                    let behind = (head - dot as f32).rem_euclid(SPINNER_DOTS as f32);
+                    let mut color = config.color;
+                    color.a *= 1.0 - behind / SPINNER_DOTS as f32 * 0.85;
+                    ui.element()
+                        .width(Sizing::Fixed(dot_size))
+                        .height(Sizing::Fixed(dot_size))
+                        .background_color(color)
+                        .corner_radius(dot_size / 2.0)
+                        .floating(|f| f
+                            .attach_parent()
+                            .anchor((AlignX::CenterX, AlignY::CenterY), (AlignX::CenterX, AlignY::CenterY))
+                            .offset((angle.sin() * radius, -angle.cos() * radius))
+                        )
+                        .empty();
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Dimensions;
+    use crate::Ply;
+
+    #[test]
+    fn test_progress_bar_fill_glides_to_value() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(200.0, 100.0));
+        let frame = |ply: &mut Ply<()>, fraction: f32| {
+            ply.context.frame_delta_time = 0.05;
+            let mut ui = ply.begin();
+            ui.progress_bar("bar", fraction, |p| p.label("Copying").width(Sizing::Fixed(200.0)));
+            ui.spinner("spinner", |s| s);
+            ui.eval();
+            ply.context.get_element_data(Id::new("bar").child("fill")).unwrap().width
+        };
+
+        assert_eq!(frame(&mut ply, 0.25), 50.0);
+        let first = frame(&mut ply, 0.75);
+        assert!(first > 50.0 && first < 150.0);
+        for _ in 0..40 {
+            frame(&mut ply, 0.75);
+        }
+        assert!((frame(&mut ply, 0.75) - 150.0).abs() < 0.5);
+
+        let config = &ply.context.accessibility_configs[&Id::new("bar").id];
+        assert_eq!(config.role, AccessibilityRole::ProgressBar);
+        assert_eq!(config.value, "75");
+        let spinner = &ply.context.accessibility_configs[&Id::new("spinner").id];
+        assert!(spinner.value.is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+//! Streaming a running app's render commands over WebSocket, so another
+//! process can mirror or inspect its UI frame by frame.
+//!
+//! The app side draws into a [`RemoteRenderer`] (native only), which listens
+//! with [`net::ws_listen`](crate::net::ws_listen) and sends each frame that
+//! changed as JSON to the connected viewer:
+//!
+//! ```ignore
+//! let mut remote = RemoteRenderer::listen("127.0.0.1:9870");
+//!
+//! // Headless: the frame only goes over the wire
+//! let mut ui = ply.begin();
+//! build_ui(&mut ui);
+//! ui.show_with(&mut remote).await;
+//!
+//! // Mirroring an app that draws itself
+//! let commands = ui.eval();
+//! render_with(&mut remote, &commands, dimensions).await;
+//! ```
+//!
+//! A [`RemoteView`] connects to it and draws what it receives:
+//!
+//! ```ignore
+//! let mut view = RemoteView::<()>::connect("ws://127.0.0.1:9870");
+//! loop {
+//!     view.update();
+//!     view.show(|_| {}).await;
+//!     next_frame().await;
+//! }
+//! ```
+//!
+//! Commands that can't be rebuilt on the viewer, like `.on_draw()` closures,
+//! are skipped. Assets are sent by path, so the viewer needs the same files.
+
+
+use serde::{Deserialize, Serialize};
+
+use crate::math::Dimensions;
+use crate::net;
+use crate::net::WsMessage;
+use crate::render_commands::RenderCommand;
+use crate::renderer::{self, Renderer};
+
+/// One frame on the wire. Commands are kept as JSON values so a command the
+/// viewer can't read is dropped alone instead of the whole frame.
+#[derive(Serialize, Deserialize)]
+struct Frame {
+    dimensions: Dimensions,
+    commands: Vec<serde_json::Value>,
+}
+
+/// A [`Renderer`] that sends each frame to a connected [`RemoteView`]
+/// instead of drawing it.
+///
+/// It doesn't measure text: give the [`Ply`](crate::Ply) a measure function
+/// of its own, ideally matching the viewer's fonts.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct RemoteRenderer {
+    id: String,
+    frame: Frame,
+    connected: bool,
+    last_sent: Option<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RemoteRenderer {
+    /// Starts listening for a viewer on `addr`, e.g. `"127.0.0.1:9870"`.
+    pub fn listen(addr: &str) -> Self {
+        let id = format!("ply_remote_{addr}");
+        net::ws_listen(&id, addr);
+        Self {
+            id,
+            frame: Frame { dimensions: Dimensions::default(), commands: Vec::new() },
+            connected: false,
+            last_sent: None,
+        }
+    }
+
+    /// Whether a viewer is connected.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<CustomElementData: Serialize + Sync> Renderer<CustomElementData> for RemoteRenderer {
+    fn measure_text(&self, _text: &str, _config: &crate::TextConfig) -> Dimensions {
+        Dimensions::default()
+    }
+
+    fn begin_frame(&mut self, dimensions: Dimensions) {
+        self.frame.dimensions = dimensions;
+        self.frame.commands.clear();
+    }
+
+    async fn draw_command(&mut self, command: &RenderCommand<CustomElementData>) {
+        if let Ok(command) = serde_json::to_value(command) {
+            self.frame.commands.push(command);
+        }
+    }
+
+    fn end_frame(&mut self) {
+        let Some(ws) = net::ws(&self.id) else { return };
+        while let Some(message) = ws.recv() {
+            match message {
+                WsMessage::Connected => {
+                    self.connected = true;
+                    // A new viewer needs the current frame even if unchanged
+                    self.last_sent = None;
+                }
+                WsMessage::Closed => self.connected = false,
+                WsMessage::Error(error) => crate::telemetry::warning!("Remote UI: {error}"),
+                WsMessage::Text(_) | WsMessage::Binary(_) => {}
+            }
+        }
+        if !self.connected {
+            return;
+        }
+        let Ok(json) = serde_json::to_string(&self.frame) else { return };
+        if self.last_sent.as_deref() != Some(json.as_str()) {
+            ws.send_text(&json);
+            self.last_sent = Some(json);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for RemoteRenderer {
+    fn drop(&mut self) {
+        if let Some(ws) = net::ws(&self.id) {
+            ws.close();
+        }
+    }
+}
+
+/// Receives frames from a [`RemoteRenderer`] and draws them.
+pub struct RemoteView<CustomElementData = ()> {
+    id: String,
+    commands: Vec<RenderCommand<CustomElementData>>,
+    dimensions: Dimensions,
+    connected: bool,
+}
+
+impl<CustomElementData> RemoteView<CustomElementData>
+where
+    CustomElementData: Clone + Default + std::fmt::Debug + serde::de::DeserializeOwned,
+{
+    /// Connects to a [`RemoteRenderer`], e.g. `"ws://127.0.0.1:9870"`.
+    pub fn connect(url: &str) -> Self {
+        let id = format!("ply_remote_view_{url}");
+        net::ws_connect(&id, url, |c| c);
+        Self {
+            id,
+            commands: Vec::new(),
+            dimensions: Dimensions::default(),
+            connected: false,
+        }
+    }
+
+    /// Takes in what arrived since the last call, keeping the newest frame.
+    /// Returns whether there is a new frame. Call once per frame.
+    pub fn update(&mut self) -> bool {
+        let Some(ws) = net::ws(&self.id) else { return false };
+        let mut latest = None;
+        while let Some(message) = ws.recv() {
+            match message {
+                WsMessage::Connected => self.connected = true,
+                WsMessage::Text(json) => latest = Some(json),
+                WsMessage::Closed => self.connected = false,
+                WsMessage::Error(error) => {
+                    self.connected = false;
+                    crate::telemetry::warning!("Remote UI: {error}");
+                }
+                WsMessage::Binary(_) => {}
+            }
+        }
+        let Some(frame) = latest.and_then(|json| serde_json::from_str::<Frame>(&json).ok()) else {
+            return false;
+        };
+        self.dimensions = frame.dimensions;
+        self.commands = frame
+            .commands
+            .into_iter()
+            .filter_map(|command| serde_json::from_value(command).ok())
+            .collect();
+        true
+    }
+
+    /// Whether the connection to the app is open.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// The newest frame's layout size in logical pixels.
+    pub fn dimensions(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    /// The newest frame's render commands.
+    pub fn commands(&self) -> &[RenderCommand<CustomElementData>] {
+        &self.commands
+    }
+
+    /// Draws the newest frame with macroquad. Custom elements are passed to
+    /// `handle_custom_command` to draw.
+    pub async fn show(&self, handle_custom_command: impl Fn(&RenderCommand<CustomElementData>)) {
+        renderer::render(self.commands.clone(), handle_custom_command).await;
+    }
+
+    /// Draws the newest frame with any [`Renderer`].
+    pub async fn show_with<R: Renderer<CustomElementData>>(&self, backend: &mut R) {
+        renderer::render_with(backend, &self.commands, self.dimensions).await;
+    }
+}
+
+impl<CustomElementData> Drop for RemoteView<CustomElementData> {
+    fn drop(&mut self) {
+        if let Some(ws) = net::ws(&self.id) {
+            ws.close();
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::render_commands::RenderCommandConfig;
+    use crate::{fixed, grow, Ply};
+
+    #[test]
+    fn test_remote_view_receives_streamed_frames() {
+        let mut remote = RemoteRenderer::listen("127.0.0.1:47391");
+        let mut view = RemoteView::<()>::connect("ws://127.0.0.1:47391");
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(320.0, 240.0));
+        ply.set_measure_text_function(|text, _| Dimensions::new(text.len() as f32 * 8.0, 16.0));
+
+        let mut received = false;
+        for _ in 0..500 {
+            let mut ui = ply.begin();
+            ui.element().width(grow!()).height(grow!()).background_color(0x202020).children(|ui| {
+                ui.element().width(fixed!(50.0)).height(fixed!(30.0)).background_color(0x336699).empty();
+                ui.text("Remote", |t| t.font_size(16));
+            });
+            crate::jobs::block_on_native(ui.show_with(&mut remote));
+            if view.update() {
+                received = true;
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        assert!(received, "no frame arrived over the loopback connection");
+        assert!(view.is_connected());
+        assert_eq!(view.dimensions(), Dimensions::new(320.0, 240.0));
+        assert_eq!(view.commands().len(), 3);
+        assert!(view.commands().iter().any(|command| matches!(
+            &command.config,
+            RenderCommandConfig::Text(text) if text.text == "Remote"
+        )));
+    }
+}
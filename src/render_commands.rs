@@ -1,7 +1,8 @@
-use crate::{color::Color, engine::{self, ShapeRotationConfig, VisualRotationConfig}, math::BoundingBox, elements::BorderPosition, renderer::ImageSource, shaders::ShaderConfig};
+use crate::{canvas::{DrawHook, PaintCommand}, color::Color, engine::{self, ShapeRotationConfig, VisualRotationConfig}, math::BoundingBox, elements::BorderPosition, renderer::ImageSource, shaders::ShaderConfig, text::{TextOutline, TextShadow}};
 
 /// Represents a rectangle with a specified color and corner radii.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rectangle {
     /// The fill color of the rectangle.
     pub color: Color,
@@ -11,6 +12,7 @@ pub struct Rectangle {
 
 /// Represents a text element with styling attributes.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Text {
     /// The text content.
     pub text: String,
@@ -23,11 +25,17 @@ pub struct Text {
     /// The line height.
     pub line_height: u16,
     /// The font asset, if specified via `.font()`.
+    #[cfg_attr(feature = "serde", serde(with = "serde_impls::font_asset"))]
     pub font_asset: Option<&'static crate::renderer::FontAsset>,
+    /// Drop shadow drawn behind the text, if any.
+    pub shadow: Option<TextShadow>,
+    /// Outline drawn around the glyphs, if any.
+    pub outline: Option<TextOutline>,
 }
 
 /// Defines individual corner radii for an element.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CornerRadii {
     /// The radius for the top-left corner.
     pub top_left: f32,
@@ -41,6 +49,7 @@ pub struct CornerRadii {
 
 /// Defines the border width for each side of an element.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BorderWidth {
     /// Border width on the left side.
     pub left: u16,
@@ -56,6 +65,7 @@ pub struct BorderWidth {
 
 /// Represents a border with a specified color, width, and corner radii.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Border {
     /// The border color.
     pub color: Color,
@@ -69,6 +79,7 @@ pub struct Border {
 
 /// Represents an image with defined dimensions and data.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Image {
     /// Background color
     pub background_color: Color,
@@ -80,6 +91,7 @@ pub struct Image {
 
 /// Represents a custom element with a background color, corner radii, and associated data.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Custom<CustomElementData> {
     /// The background color of the custom element.
     pub background_color: Color,
@@ -89,6 +101,14 @@ pub struct Custom<CustomElementData> {
     pub data: CustomElementData,
 }
 
+/// Represents vector drawing recorded with `.canvas()`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Canvas {
+    /// The drawing commands, in pixels from the bounding box's top-left corner.
+    pub commands: Vec<PaintCommand>,
+}
+
 impl CornerRadii {
     pub fn clamp_to_size(&mut self, width: f32, height: f32) {
         let max_r = width.min(height) / 2.0;
@@ -111,6 +131,7 @@ impl From<crate::layout::CornerRadius> for CornerRadii {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RenderCommandConfig<CustomElementData> {
     None(),
     Rectangle(Rectangle),
@@ -120,6 +141,13 @@ pub enum RenderCommandConfig<CustomElementData> {
     ScissorStart(),
     ScissorEnd(),
     Custom(Custom<CustomElementData>),
+    /// Vector drawing; apply `shape_rotation` about the bounding box center
+    /// and clip to the bounding box.
+    Canvas(Canvas),
+    /// A closure set with `.on_draw()`; call it with the element's bounding box.
+    /// Serializes as a marker only, so it can't be deserialized.
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    Draw(DrawHook),
     /// Begin a group: Renders children to an offscreen buffer.
     /// Optionally applies a fragment shader and/or visual rotation.
     GroupBegin {
@@ -134,7 +162,10 @@ pub enum RenderCommandConfig<CustomElementData> {
 impl<CustomElementData: Clone + Default + std::fmt::Debug>
     RenderCommandConfig<CustomElementData>
 {
-    pub(crate) fn from_engine_render_command(value: &engine::InternalRenderCommand<CustomElementData>) -> Self {
+    pub(crate) fn from_engine_render_command(
+        value: &engine::InternalRenderCommand<CustomElementData>,
+        effects: &[ShaderConfig],
+    ) -> Self {
         match value.command_type {
             engine::RenderCommandType::None => Self::None(),
             engine::RenderCommandType::Rectangle => {
@@ -148,7 +179,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug>
                 }
             }
             engine::RenderCommandType::Text => {
-                if let engine::InternalRenderData::Text { text, text_color, font_size, letter_spacing, line_height, font_asset } = &value.render_data {
+                if let engine::InternalRenderData::Text { text, text_color, font_size, letter_spacing, line_height, font_asset, shadow, outline } = &value.render_data {
                     Self::Text(Text {
                         text: text.clone(),
                         color: *text_color,
@@ -156,6 +187,8 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug>
                         letter_spacing: *letter_spacing,
                         line_height: *line_height,
                         font_asset: *font_asset,
+                        shadow: *shadow,
+                        outline: *outline,
                     })
                 } else {
                     Self::None()
@@ -195,11 +228,25 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug>
             engine::RenderCommandType::GroupBegin => {
                 // GroupBegin uses the first effect from the render command as its shader config,
                 // and carries the visual_rotation from the render command.
-                let shader = value.effects.first().cloned();
+                let shader = effects.first().cloned();
                 let visual_rotation = value.visual_rotation;
                 Self::GroupBegin { shader, visual_rotation }
             }
             engine::RenderCommandType::GroupEnd => Self::GroupEnd,
+            engine::RenderCommandType::Canvas => {
+                if let engine::InternalRenderData::Canvas { commands } = &value.render_data {
+                    Self::Canvas(Canvas { commands: commands.clone() })
+                } else {
+                    Self::None()
+                }
+            }
+            engine::RenderCommandType::Draw => {
+                if let engine::InternalRenderData::Draw { hook } = &value.render_data {
+                    Self::Draw(hook.clone())
+                } else {
+                    Self::None()
+                }
+            }
             engine::RenderCommandType::Custom => {
                 if let engine::InternalRenderData::Custom { background_color, corner_radius, custom_data } = &value.render_data {
                     Self::Custom(Custom {
@@ -217,6 +264,7 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug>
 
 /// Represents a render command for drawing an element on the screen.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RenderCommand<CustomElementData> {
     /// The bounding box defining the area occupied by the element.
     pub bounding_box: BoundingBox,
@@ -229,13 +277,17 @@ pub struct RenderCommand<CustomElementData> {
     pub z_index: i16,
     /// Per-element shader effects (chained in order).
     pub effects: Vec<ShaderConfig>,
-    /// Shape rotation applied at the vertex level (only for Rectangle / Image / Custom / Border).
+    /// Shape rotation applied at the vertex level (only for Rectangle / Image / Custom / Border / Canvas / Draw).
     pub shape_rotation: Option<ShapeRotationConfig>,
 }
 
 impl<CustomElementData: Clone + Default + std::fmt::Debug> RenderCommand<CustomElementData> {
-    pub(crate) fn from_engine_render_command(value: &engine::InternalRenderCommand<CustomElementData>) -> Self {
-        let mut config = RenderCommandConfig::from_engine_render_command(value);
+    /// `effects` are the command's effects, resolved from the frame's shader configs.
+    pub(crate) fn from_engine_render_command(
+        value: &engine::InternalRenderCommand<CustomElementData>,
+        effects: &[ShaderConfig],
+    ) -> Self {
+        let mut config = RenderCommandConfig::from_engine_render_command(value, effects);
         let bb = value.bounding_box;
         match &mut config {
             RenderCommandConfig::Rectangle(r)  => r.corner_radii.clamp_to_size(bb.width, bb.height),
@@ -249,8 +301,167 @@ impl<CustomElementData: Clone + Default + std::fmt::Debug> RenderCommand<CustomE
             z_index: value.z_index,
             bounding_box: bb,
             config,
-            effects: value.effects.clone(),
+            effects: effects.to_vec(),
             shape_rotation: value.shape_rotation,
         }
     }
 }
+
+/// Serde support for the parts of render commands and declarations that hold
+/// references or GPU handles. Fonts and image assets round-trip by path;
+/// textures, TinyVG images and draw hooks serialize as markers and fail to
+/// deserialize.
+#[cfg(feature = "serde")]
+pub(crate) mod serde_impls {
+    use std::sync::Mutex;
+
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::canvas::DrawHook;
+    use crate::renderer::{FontAsset, GraphicAsset, ImageSource};
+
+    static FONTS: Mutex<Vec<&'static FontAsset>> = Mutex::new(Vec::new());
+    static GRAPHICS: Mutex<Vec<&'static GraphicAsset>> = Mutex::new(Vec::new());
+
+    /// Assets are referenced as `&'static`, so each deserialized path is
+    /// leaked once and reused afterwards.
+    fn intern<T: 'static>(
+        assets: &Mutex<Vec<&'static T>>,
+        path: String,
+        key: impl Fn(&T) -> &str,
+        from_path: impl FnOnce(&'static str) -> T,
+    ) -> &'static T {
+        let mut assets = assets.lock().unwrap();
+        if let Some(asset) = assets.iter().copied().find(|asset| key(*asset) == path) {
+            return asset;
+        }
+        let asset: &'static T = Box::leak(Box::new(from_path(Box::leak(path.into_boxed_str()))));
+        assets.push(asset);
+        asset
+    }
+
+    pub(crate) mod font_asset {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(font: &Option<&'static FontAsset>, serializer: S) -> Result<S::Ok, S::Error> {
+            font.map(|font| font.key()).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<&'static FontAsset>, D::Error> {
+            let key = Option::<String>::deserialize(deserializer)?;
+            Ok(key.map(|key| intern(&FONTS, key, |font| font.key(), FontAsset::Path)))
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    enum ImageSourceRepr {
+        Asset(String),
+        Texture,
+        TinyVg,
+    }
+
+    impl Serialize for ImageSource {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                ImageSource::Asset(asset) => ImageSourceRepr::Asset(asset.get_name().to_owned()),
+                ImageSource::Texture(_) => ImageSourceRepr::Texture,
+                #[cfg(feature = "tinyvg")]
+                ImageSource::TinyVg(_) => ImageSourceRepr::TinyVg,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ImageSource {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            match ImageSourceRepr::deserialize(deserializer)? {
+                ImageSourceRepr::Asset(path) => Ok(ImageSource::Asset(intern(
+                    &GRAPHICS,
+                    path,
+                    |asset| asset.get_name(),
+                    GraphicAsset::Path,
+                ))),
+                ImageSourceRepr::Texture => Err(de::Error::custom("GPU textures can't be deserialized")),
+                ImageSourceRepr::TinyVg => Err(de::Error::custom("TinyVG images can't be deserialized")),
+            }
+        }
+    }
+
+    impl Serialize for DrawHook {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_unit_struct("DrawHook")
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::math::Dimensions;
+    use crate::Ply;
+
+    #[test]
+    fn test_render_commands_round_trip_through_json() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(200.0, 100.0));
+        ply.set_measure_text_function(|_, _| Dimensions::new(40.0, 16.0));
+        let mut ui = ply.begin();
+        ui.element()
+            .background_color(0x202020)
+            .corner_radius(6.0)
+            .border(|b| b.color(0xFFFFFF).all(2))
+            .children(|ui| {
+                ui.text("Score", |t| t.color(0xFFCC00).font_size(16));
+                ui.element()
+                    .width(crate::layout::Sizing::Fixed(20.0))
+                    .height(crate::layout::Sizing::Fixed(20.0))
+                    .canvas(|p| p.line((0.0, 0.0), (20.0, 20.0), 2.0, 0xFF0000))
+                    .empty();
+            });
+        let commands = ui.eval();
+
+        let json = serde_json::to_string(&commands).unwrap();
+        let parsed: Vec<RenderCommand<()>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.len(), commands.len());
+        assert!(matches!(&parsed[0].config, RenderCommandConfig::Rectangle(r) if r.corner_radii.top_left == 6.0));
+        assert!(parsed.iter().any(|c| matches!(&c.config, RenderCommandConfig::Text(t) if t.text == "Score")));
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn test_declarations_ids_and_themes_round_trip_through_json() {
+        use crate::engine::{ElementDeclaration, SizingType};
+        use crate::id::Id;
+        use crate::theme::Theme;
+
+        let mut declaration = ElementDeclaration::<()>::default();
+        declaration.layout.sizing.width = crate::layout::Sizing::Grow(0.0, 300.0, 2.0).into();
+        declaration.layout.padding.left = 12;
+        declaration.background_color = Color::u_rgb(0x20, 0x21, 0x24);
+        declaration.border.width.top = 2;
+        declaration.text_input = Some(crate::text_input::TextInputConfig {
+            placeholder: "Search".to_string(),
+            ..Default::default()
+        });
+        let json = serde_json::to_string(&declaration).unwrap();
+        let parsed: ElementDeclaration = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.layout.sizing.width.type_, SizingType::Grow);
+        assert_eq!(parsed.layout.sizing.width.min_max.max, 300.0);
+        assert_eq!(parsed.layout.padding.left, 12);
+        assert_eq!(parsed.background_color, declaration.background_color);
+        assert_eq!(parsed.border.width.top, 2);
+        assert_eq!(parsed.text_input.unwrap().placeholder, "Search");
+
+        // Missing fields fall back to their defaults
+        let partial: ElementDeclaration = serde_json::from_str(r#"{"aspect_ratio":1.5}"#).unwrap();
+        assert_eq!(partial.aspect_ratio, 1.5);
+        assert!(partial.text_input.is_none());
+
+        let id = Id::new_index("row", 3);
+        assert_eq!(serde_json::from_str::<Id>(&serde_json::to_string(&id).unwrap()).unwrap(), id);
+
+        let theme = Theme::new().color("surface", 0x202124).number("radius", 8.0);
+        let theme: Theme = serde_json::from_str(&serde_json::to_string(&theme).unwrap()).unwrap();
+        assert_eq!(theme.get_number("radius"), Some(8.0));
+        assert_eq!(theme.get_color("surface"), Some(Color::u_rgb(0x20, 0x21, 0x24)));
+    }
+}
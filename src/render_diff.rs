@@ -0,0 +1,420 @@
+//! Frame-over-frame render command diffs, for hunting down layout that never
+//! settles and elements that redraw for no reason.
+//!
+//! ```ignore
+//! ply.set_render_diff(true);
+//! // ...after show():
+//! for change in ply.render_changes() {
+//!     println!("{:?} {:?}", change.kind, change.bounds);
+//! }
+//! ```
+//!
+//! While on, changed elements are outlined on screen: green when added, red
+//! where removed, orange when moved or resized, magenta when restyled and
+//! yellow when both. A still UI shows no outlines.
+//!
+//! For idle apps, change detection tells whether a frame needs drawing at all.
+//! Macroquad still presents a frame every `next_frame`, so keep the last one
+//! in a texture and redraw that instead:
+//!
+//! ```ignore
+//! ply.set_change_detection(true);
+//! let mut screen = None;
+//! loop {
+//!     let mut ui = ply.begin();
+//!     // ...declare the UI...
+//!     let commands = ui.eval();
+//!     if ply.frame_changed() || screen.is_none() {
+//!         let (width, height) = (screen_width(), screen_height());
+//!         screen = Some(ply_engine::renderer::render_offscreen(commands, width, height, |_| {}).await);
+//!     }
+//!     if let Some(screen) = &screen {
+//!         let dest_size = Some(vec2(screen_width(), screen_height()));
+//!         draw_texture_ex(screen, 0.0, 0.0, WHITE, DrawTextureParams { dest_size, ..Default::default() });
+//!     }
+//!     next_frame().await;
+//! }
+//! ```
+
+use std::fmt::{self, Write};
+use std::hash::Hasher;
+
+use rustc_hash::{FxHashMap, FxHasher};
+
+use crate::color::Color;
+use crate::math::BoundingBox;
+use crate::render_commands::{RenderCommand, RenderCommandConfig};
+
+/// How a render command differs from the previous frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderChangeKind {
+    Added,
+    Removed,
+    /// Same look, different bounding box.
+    Moved,
+    /// Same bounding box, different colors, text, image or other config.
+    Restyled,
+    MovedAndRestyled,
+}
+
+impl RenderChangeKind {
+    /// Outline color on screen.
+    pub(crate) fn color(self) -> Color {
+        match self {
+            RenderChangeKind::Added => Color::rgba(60.0, 200.0, 90.0, 255.0),
+            RenderChangeKind::Removed => Color::rgba(230.0, 50.0, 50.0, 255.0),
+            RenderChangeKind::Moved => Color::rgba(255.0, 150.0, 0.0, 255.0),
+            RenderChangeKind::Restyled => Color::rgba(220.0, 60.0, 220.0, 255.0),
+            RenderChangeKind::MovedAndRestyled => Color::rgba(255.0, 230.0, 0.0, 255.0),
+        }
+    }
+}
+
+/// A render command that differs from the previous frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderChange {
+    /// The render command's id.
+    pub id: u32,
+    pub kind: RenderChangeKind,
+    /// Where it is drawn this frame, or was drawn last frame if removed.
+    pub bounds: BoundingBox,
+}
+
+/// Render commands are matched across frames by id and kind, as an element
+/// can emit several commands with the same id.
+type CommandKey = (u32, u8);
+
+#[derive(Default)]
+pub(crate) struct RenderDiff {
+    /// Bounds and a hash of the config of last frame's commands, `None`
+    /// until a frame has been seen.
+    previous: Option<FxHashMap<CommandKey, (BoundingBox, u64)>>,
+    pub(crate) changes: Vec<RenderChange>,
+}
+
+impl RenderDiff {
+    /// Compares this frame's commands with the last frame's.
+    pub(crate) fn update<CustomElementData: std::fmt::Debug>(&mut self, commands: &[RenderCommand<CustomElementData>]) {
+        let mut current = FxHashMap::default();
+        for command in commands {
+            if let Some(kind) = command_kind(&command.config) {
+                current.insert((command.id, kind), (command.bounding_box, config_hash(command)));
+            }
+        }
+
+        self.changes.clear();
+        if let Some(previous) = &self.previous {
+            for command in commands {
+                let Some(kind) = command_kind(&command.config) else {
+                    continue;
+                };
+                let change = match previous.get(&(command.id, kind)) {
+                    None => Some(RenderChangeKind::Added),
+                    Some(&(bounds, hash)) => {
+                        let moved = bounds != command.bounding_box;
+                        let restyled = hash != current[&(command.id, kind)].1;
+                        match (moved, restyled) {
+                            (true, true) => Some(RenderChangeKind::MovedAndRestyled),
+                            (true, false) => Some(RenderChangeKind::Moved),
+                            (false, true) => Some(RenderChangeKind::Restyled),
+                            (false, false) => None,
+                        }
+                    }
+                };
+                if let Some(kind) = change {
+                    self.changes.push(RenderChange { id: command.id, kind, bounds: command.bounding_box });
+                }
+            }
+            for (&(id, kind), &(bounds, _)) in previous {
+                if !current.contains_key(&(id, kind)) {
+                    self.changes.push(RenderChange { id, kind: RenderChangeKind::Removed, bounds });
+                }
+            }
+        }
+        self.previous = Some(current);
+    }
+}
+
+/// Distinguishes commands sharing an id. `None` for scissors and groups,
+/// which only frame other commands.
+fn command_kind<CustomElementData>(config: &RenderCommandConfig<CustomElementData>) -> Option<u8> {
+    match config {
+        RenderCommandConfig::None()
+        | RenderCommandConfig::ScissorStart()
+        | RenderCommandConfig::ScissorEnd()
+        | RenderCommandConfig::GroupBegin { .. }
+        | RenderCommandConfig::GroupEnd => None,
+        RenderCommandConfig::Rectangle(_) => Some(0),
+        RenderCommandConfig::Border(_) => Some(1),
+        RenderCommandConfig::Text(_) => Some(2),
+        RenderCommandConfig::Image(_) => Some(3),
+        RenderCommandConfig::Custom(_) => Some(4),
+        RenderCommandConfig::Canvas(_) => Some(5),
+        RenderCommandConfig::Draw(_) => Some(6),
+    }
+}
+
+/// Hashes everything about a command but its bounds.
+fn config_hash<CustomElementData: std::fmt::Debug>(command: &RenderCommand<CustomElementData>) -> u64 {
+    let mut writer = HashWriter(FxHasher::default());
+    let _ = write!(writer, "{:?}{:?}{:?}{}", command.config, command.effects, command.shape_rotation, command.z_index);
+    writer.0.finish()
+}
+
+/// Hashes a whole frame of render commands, to tell whether it looks any
+/// different from the last. Draw hooks only count by position, as their
+/// closures can't be compared.
+pub(crate) fn frame_hash<CustomElementData: std::fmt::Debug>(commands: &[RenderCommand<CustomElementData>]) -> u64 {
+    let mut writer = HashWriter(FxHasher::default());
+    for command in commands {
+        writer.0.write_u32(command.id);
+        let bounds = command.bounding_box;
+        for value in [bounds.x, bounds.y, bounds.width, bounds.height] {
+            writer.0.write_u32(value.to_bits());
+        }
+        let _ = write!(writer, "{:?}{:?}{:?}{}", command.config, command.effects, command.shape_rotation, command.z_index);
+    }
+    writer.0.finish()
+}
+
+/// Whether a shader reads the automatic uniforms, which change between frames
+/// without its config changing.
+fn animated_shader(shader: &crate::shaders::ShaderConfig) -> bool {
+    ["ply_time", "ply_mouse", "ply_resolution"].iter().any(|name| shader.fragment.contains(name))
+}
+
+/// Whether a frame draws anything that changes over time with the same
+/// commands: shaders on the automatic uniforms and animated styled text.
+pub(crate) fn frame_animates<CustomElementData>(commands: &[RenderCommand<CustomElementData>]) -> bool {
+    commands.iter().any(|command| {
+        let animated = match &command.config {
+            RenderCommandConfig::GroupBegin { shader: Some(shader), .. } => animated_shader(shader),
+            #[cfg(feature = "text-styling")]
+            RenderCommandConfig::Text(text) => crate::text_styling::is_animated(&text.text),
+            _ => false,
+        };
+        animated || command.effects.iter().any(animated_shader)
+    })
+}
+
+/// Sets `capture_key` on the shader of every group whose contents can be
+/// cached between frames: a hash of its commands relative to the group, so
+/// a group that only moved keeps its capture.
+///
+/// Groups drawing custom elements, draw hooks, runtime textures, element
+/// masks or shaders animated by the automatic `ply_time`, `ply_mouse` or
+/// `ply_resolution` uniforms can change without their commands changing, so
+/// they get none.
+pub(crate) fn assign_group_capture_keys<CustomElementData: std::fmt::Debug>(commands: &mut [RenderCommand<CustomElementData>]) {
+    let mut open_groups = Vec::new();
+    for index in 0..commands.len() {
+        match commands[index].config {
+            RenderCommandConfig::GroupBegin { .. } => open_groups.push(index),
+            RenderCommandConfig::GroupEnd => {
+                let Some(begin) = open_groups.pop() else { continue };
+                let key = group_capture_key(&commands[begin], &commands[begin + 1..index]);
+                if let RenderCommandConfig::GroupBegin { shader: Some(shader), .. } = &mut commands[begin].config {
+                    shader.capture_key = key;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn group_capture_key<CustomElementData: std::fmt::Debug>(
+    group: &RenderCommand<CustomElementData>,
+    contents: &[RenderCommand<CustomElementData>],
+) -> Option<u64> {
+    let origin = group.bounding_box;
+    let mut writer = HashWriter(FxHasher::default());
+    writer.0.write_u32(crate::renderer::display_scale().to_bits());
+    writer.0.write_u32(origin.width.to_bits());
+    writer.0.write_u32(origin.height.to_bits());
+    for command in contents {
+        let cacheable = match &command.config {
+            RenderCommandConfig::Custom(_) | RenderCommandConfig::Draw(_) => false,
+            RenderCommandConfig::Image(image) => !matches!(image.data, crate::renderer::ImageSource::Texture(_)),
+            RenderCommandConfig::GroupBegin { shader, .. } => {
+                !shader.as_ref().is_some_and(|shader| animated_shader(shader) || crate::mask::links_elements(shader))
+            }
+            // Animated styled text changes on its own
+            #[cfg(feature = "text-styling")]
+            RenderCommandConfig::Text(text) => !text.text.contains('{'),
+            _ => true,
+        };
+        if !cacheable || command.effects.iter().any(animated_shader) {
+            return None;
+        }
+        writer.0.write_u32(command.id);
+        let bounds = command.bounding_box;
+        for value in [bounds.x - origin.x, bounds.y - origin.y, bounds.width, bounds.height] {
+            writer.0.write_u32(value.to_bits());
+        }
+        let _ = write!(writer, "{:?}{:?}{:?}{}", command.config, command.effects, command.shape_rotation, command.z_index);
+    }
+    Some(writer.0.finish())
+}
+
+/// Feeds formatted output straight into a hasher, without building a string.
+struct HashWriter(FxHasher);
+
+impl Write for HashWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Dimensions;
+    use crate::{fixed, Ply};
+
+    #[test]
+    fn test_render_diff_reports_moved_restyled_added_and_removed() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let frame = |ply: &mut Ply<()>, step: u32| {
+            let mut ui = ply.begin();
+            ui.element()
+                .id("steady")
+                .width(fixed!(50.0))
+                .height(fixed!(50.0))
+                .background_color(0x336699)
+                .empty();
+            ui.element()
+                .id("blinking")
+                .width(fixed!(50.0))
+                .height(fixed!(50.0))
+                .background_color(if step == 0 { 0xFF0000 } else { 0x00FF00 })
+                .empty();
+            if step == 0 {
+                ui.element().id("leaving").width(fixed!(20.0)).height(fixed!(20.0)).background_color(0xFFFFFF).empty();
+            } else {
+                ui.element().id("arriving").width(fixed!(20.0)).height(fixed!(20.0)).background_color(0xFFFFFF).empty();
+            }
+            // Last, so its growth doesn't push the others around
+            ui.element()
+                .id("growing")
+                .width(fixed!(50.0 + step as f32))
+                .height(fixed!(50.0))
+                .background_color(0x336699)
+                .empty();
+            ui.eval()
+        };
+
+        ply.set_render_diff(true);
+        frame(&mut ply, 0);
+        // Nothing to compare the first frame with
+        assert!(ply.render_changes().is_empty());
+
+        let items = frame(&mut ply, 1);
+        let kind_of = |name: &'static str| {
+            let id = crate::id::Id::from(name).id;
+            ply.render_changes().iter().find(|change| change.id == id).map(|change| change.kind)
+        };
+        assert_eq!(kind_of("steady"), None);
+        assert_eq!(kind_of("growing"), Some(RenderChangeKind::Moved));
+        assert_eq!(kind_of("blinking"), Some(RenderChangeKind::Restyled));
+        assert_eq!(kind_of("leaving"), Some(RenderChangeKind::Removed));
+        // "arriving" sits where "leaving" was, but the ids differ
+        assert_eq!(kind_of("arriving"), Some(RenderChangeKind::Added));
+        assert_eq!(ply.render_changes().len(), 4);
+
+        let outlines = items.iter().filter(|item| item.z_index == i16::MAX).count();
+        assert_eq!(outlines, 4);
+    }
+
+    #[test]
+    fn test_change_detection_reports_idle_frames() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let frame = |ply: &mut Ply<()>, color: u32| {
+            let mut ui = ply.begin();
+            ui.element().width(fixed!(50.0)).height(fixed!(50.0)).background_color(color).empty();
+            ui.eval();
+            ply.frame_changed()
+        };
+
+        // Without change detection every frame counts as changed
+        assert!(frame(&mut ply, 0x336699));
+        assert!(frame(&mut ply, 0x336699));
+
+        ply.set_change_detection(true);
+        assert!(frame(&mut ply, 0x336699));
+        assert!(!frame(&mut ply, 0x336699));
+        assert!(frame(&mut ply, 0xFF0000));
+        assert!(!frame(&mut ply, 0xFF0000));
+
+        // Animated styled text changes every frame without new commands
+        #[cfg(feature = "text-styling")]
+        {
+            ply.set_measure_text_function(|text, _| Dimensions::new(text.len() as f32 * 10.0, 20.0));
+            let waving = |ply: &mut Ply<()>, text: &str| {
+                let mut ui = ply.begin();
+                ui.text(text, |t| t.font_size(16));
+                ui.eval();
+                ply.frame_changed()
+            };
+            assert!(waving(&mut ply, "{color=red|Hi}"));
+            assert!(!waving(&mut ply, "{color=red|Hi}"));
+            assert!(waving(&mut ply, "{wave|Hi}"));
+            assert!(waving(&mut ply, "{wave|Hi}"));
+        }
+    }
+
+    #[test]
+    fn test_group_capture_keys_follow_contents_not_position() {
+        use crate::shaders::ShaderAsset;
+
+        const CARD: ShaderAsset = ShaderAsset::Source {
+            file_name: "card",
+            fragment: "uniform float u_shine;",
+        };
+        const ANIMATED: ShaderAsset = ShaderAsset::Source {
+            file_name: "animated",
+            fragment: "uniform float ply_time;",
+        };
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        let capture_key = |ply: &mut Ply<()>, offset: f32, color: u32, shader: &ShaderAsset, shine: f32| {
+            let mut ui = ply.begin();
+            ui.element().width(fixed!(offset)).height(fixed!(10.0)).empty();
+            ui.element()
+                .width(fixed!(100.0))
+                .height(fixed!(100.0))
+                .shader(shader, |s| {
+                    s.uniform("u_shine", shine);
+                })
+                .children(|ui| {
+                    ui.element().width(fixed!(50.0)).height(fixed!(50.0)).background_color(color).empty();
+                });
+            let commands = ui.eval();
+            commands
+                .iter()
+                .find_map(|command| match &command.config {
+                    RenderCommandConfig::GroupBegin { shader: Some(shader), .. } => Some(shader.capture_key),
+                    _ => None,
+                })
+                .expect("no group")
+        };
+
+        let key = capture_key(&mut ply, 10.0, 0x336699, &CARD, 0.0);
+        assert!(key.is_some());
+        // Moving the group or changing its own uniforms keeps the capture
+        assert_eq!(capture_key(&mut ply, 60.0, 0x336699, &CARD, 0.5), key);
+        assert_ne!(capture_key(&mut ply, 10.0, 0xFF0000, &CARD, 0.0), key);
+        // Groups whose look changes by itself aren't cached
+        let mut ui = ply.begin();
+        ui.element()
+            .width(fixed!(100.0))
+            .height(fixed!(100.0))
+            .shader(&CARD, |_| {})
+            .children(|ui| {
+                ui.element().width(fixed!(50.0)).height(fixed!(50.0)).background_color(0x336699).effect(&ANIMATED, |_| {}).empty();
+            });
+        let commands = ui.eval();
+        assert!(matches!(&commands[0].config, RenderCommandConfig::GroupBegin { shader: Some(shader), .. } if shader.capture_key.is_none()));
+        // ...but an animated group shader only applies on top of its capture
+        assert!(capture_key(&mut ply, 10.0, 0x336699, &ANIMATED, 0.0).is_some());
+    }
+}
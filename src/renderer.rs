@@ -5,7 +5,7 @@ use macroquad::miniquad::{BlendState, BlendFactor, BlendValue, Equation};
 use crate::{math::BoundingBox, render_commands::{CornerRadii, RenderCommand, RenderCommandConfig}, shaders::{ShaderConfig, ShaderUniformValue}, elements::BorderPosition};
 
 #[cfg(feature = "text-styling")]
-use crate::text_styling::{render_styled_text, StyledSegment};
+use crate::text_styling::{parse_styled_line, render_styled_text};
 #[cfg(feature = "text-styling")]
 use rustc_hash::FxHashMap;
 
@@ -13,7 +13,7 @@ const PIXELS_PER_POINT: f32 = 2.0;
 
 /// On Android, the APK asset root is the `assets/` directory,
 /// so paths like `"assets/fonts/x.ttf"` need the prefix stripped.
-fn resolve_asset_path(path: &str) -> &str {
+pub(crate) fn resolve_asset_path(path: &str) -> &str {
     #[cfg(target_os = "android")]
     if let Some(stripped) = path.strip_prefix("assets/") {
         return stripped;
@@ -105,6 +105,80 @@ impl FontAsset {
     }
 }
 
+/// Display scale as `f32` bits, see [`display_scale`].
+static DISPLAY_SCALE: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0x3f80_0000);
+
+/// Physical pixels per logical pixel, as detected in [`Ply::begin`](crate::Ply::begin).
+///
+/// Layout and drawing use logical pixels. Glyphs and offscreen textures are
+/// rasterized at `display_scale()` times their logical size, so text stays
+/// crisp on high-DPI displays. Needs `high_dpi: true` in the window config,
+/// otherwise the platform scales the window up and this stays `1.0`.
+pub fn display_scale() -> f32 {
+    f32::from_bits(DISPLAY_SCALE.load(std::sync::atomic::Ordering::Relaxed))
+}
+
+pub(crate) fn set_display_scale(scale: f32) {
+    let scale = if scale.is_finite() && scale > 0.0 { scale } else { 1.0 };
+    DISPLAY_SCALE.store(scale.to_bits(), std::sync::atomic::Ordering::Relaxed);
+}
+
+/// How the edges of rounded rectangles, borders and rotated rectangles are
+/// smoothed. Straight axis-aligned edges are left sharp.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum EdgeAntialiasing {
+    /// Hard edges, smoothed only by MSAA if the window has it.
+    #[default]
+    Off,
+    /// Curved and rotated edges fade out over one physical pixel.
+    Feathered,
+    /// Feathered, with arcs split into twice as many segments.
+    High,
+}
+
+/// Width of the feathered fringe in logical pixels, `0.0` when off.
+fn edge_feather(edge_antialiasing: EdgeAntialiasing) -> f32 {
+    match edge_antialiasing {
+        EdgeAntialiasing::Off => 0.0,
+        EdgeAntialiasing::Feathered | EdgeAntialiasing::High => 1.0 / display_scale(),
+    }
+}
+
+/// Arc length each segment of a rounded corner covers.
+fn arc_segment_length(edge_antialiasing: EdgeAntialiasing) -> f32 {
+    match edge_antialiasing {
+        EdgeAntialiasing::High => PIXELS_PER_POINT / 2.0,
+        EdgeAntialiasing::Off | EdgeAntialiasing::Feathered => PIXELS_PER_POINT,
+    }
+}
+
+/// Font size to rasterize glyphs at on a display with the given scale, and
+/// the factor that maps the rasterized glyphs back to `font_size`.
+pub(crate) fn raster_font_size(font_size: u16, scale: f32) -> (u16, f32) {
+    if font_size == 0 {
+        return (0, 1.0);
+    }
+    let raster_size = (font_size as f32 * scale).round().clamp(1.0, u16::MAX as f32) as u16;
+    (raster_size, font_size as f32 / raster_size as f32)
+}
+
+/// `measure_text` in logical pixels, for glyphs rasterized at the display scale.
+fn measure_text_logical(text: &str, font: Option<&Font>, font_size: u16) -> TextDimensions {
+    let (raster_size, raster_scale) = raster_font_size(font_size, display_scale());
+    macroquad::text::measure_text(text, font, raster_size, raster_scale)
+}
+
+/// Render target sized for the display scale. Cameras map logical
+/// coordinates onto it, so callers draw in logical pixels as usual.
+fn scaled_render_target(width: f32, height: f32) -> RenderTarget {
+    let scale = display_scale();
+    render_target_msaa(
+        ((width * scale).round() as u32).max(1),
+        ((height * scale).round() as u32).max(1),
+    )
+}
+
 /// Global FontManager. Manages font loading, caching, and eviction.
 pub static FONT_MANAGER: std::sync::LazyLock<std::sync::Mutex<FontManager>> =
     std::sync::LazyLock::new(|| std::sync::Mutex::new(FontManager::new()));
@@ -114,7 +188,8 @@ pub struct FontManager {
     fonts: rustc_hash::FxHashMap<&'static str, FontData>,
     default_font: Option<DefaultFont>,
     pub max_frames_not_used: usize,
-    font_metrics: rustc_hash::FxHashMap<(u16, usize), FontMetrics>,
+    /// Keyed by font size, font asset address and display scale bits.
+    font_metrics: rustc_hash::FxHashMap<(u16, usize, u32), FontMetrics>,
 }
 struct DefaultFont {
     key: &'static str,
@@ -157,9 +232,14 @@ impl FontManager {
         self.default_font.as_ref().map(|d| &d.font)
     }
 
+    /// Key of the default font, if one is loaded.
+    pub fn default_key(&self) -> Option<&'static str> {
+        self.default_font.as_ref().map(|d| d.key)
+    }
+
     fn metrics(&mut self, font_size: u16, font_asset: Option<&'static FontAsset>) -> FontMetrics {
         let font_ptr = font_asset.map_or(0usize, |a| a as *const _ as usize);
-        let key = (font_size, font_ptr);
+        let key = (font_size, font_ptr, display_scale().to_bits());
         if let Some(&m) = self.font_metrics.get(&key) {
             return m;
         }
@@ -175,7 +255,7 @@ impl FontManager {
         } else {
             (self.default_font.as_ref().map(|d| &d.font), true)
         };
-        let ref_dims = macroquad::text::measure_text("Xig", font, font_size, 1.0);
+        let ref_dims = measure_text_logical("Xig", font, font_size);
         let m = FontMetrics {
             height: ref_dims.height,
             baseline_offset: ref_dims.offset_y,
@@ -201,6 +281,9 @@ impl FontManager {
         };
         let mut fm = FONT_MANAGER.lock().unwrap();
         fm.default_font = Some(DefaultFont { key: asset.key(), font });
+        drop(fm);
+        #[cfg(feature = "sdf-text")]
+        crate::sdf_text::register_font(asset).await;
     }
 
     /// Ensure a font is loaded (no-op if already cached).
@@ -232,6 +315,9 @@ impl FontManager {
             }
         };
 
+        #[cfg(feature = "sdf-text")]
+        crate::sdf_text::register_font(asset).await;
+
         // Insert with lock
         let mut fm = FONT_MANAGER.lock().unwrap();
         let key = asset.key();
@@ -413,10 +499,17 @@ pub static MATERIAL_MANAGER: std::sync::LazyLock<std::sync::Mutex<MaterialManage
 /// Also holds a runtime shader storage (`name → source`) for [`ShaderAsset::Stored`]
 /// shaders. Update stored sources with [`set_source`](Self::set_source); the old
 /// compiled material is evicted automatically when the source changes.
+///
+/// A source that fails to compile is reported as
+/// [`Diagnostic::ShaderCompileFailed`](crate::diagnostics::Diagnostic::ShaderCompileFailed)
+/// and drawn with the last version of that shader that compiled, so a typo
+/// while hot-reloading doesn't blank the element.
 pub struct MaterialManager {
     materials: rustc_hash::FxHashMap<std::borrow::Cow<'static, str>, MaterialData>,
     /// Runtime shader storage: name → fragment source.
     shader_storage: rustc_hash::FxHashMap<String, String>,
+    /// Shader name → the last fragment source that compiled.
+    last_compiled: rustc_hash::FxHashMap<String, std::borrow::Cow<'static, str>>,
     /// How many frames a material can go unused before being evicted.
     pub max_frames_not_used: usize,
 }
@@ -431,6 +524,7 @@ impl MaterialManager {
         Self {
             materials: rustc_hash::FxHashMap::default(),
             shader_storage: rustc_hash::FxHashMap::default(),
+            last_compiled: rustc_hash::FxHashMap::default(),
             max_frames_not_used: 60, // Keep materials longer than textures
         }
     }
@@ -445,17 +539,28 @@ impl MaterialManager {
                 // Auto-uniforms
                 UniformDesc::new("u_resolution", UniformType::Float2),
                 UniformDesc::new("u_position", UniformType::Float2),
+                UniformDesc::new("ply_time", UniformType::Float1),
+                UniformDesc::new("ply_resolution", UniformType::Float2),
+                UniformDesc::new("ply_element_rect", UniformType::Float4),
+                UniformDesc::new("ply_mouse", UniformType::Float2),
             ];
+            if config.mask.is_some() {
+                uniform_decls.push(UniformDesc::new("ply_mask_rect", UniformType::Float4));
+            }
             for u in &config.uniforms {
                 let utype = match &u.value {
-                    ShaderUniformValue::Float(_) => UniformType::Float1,
-                    ShaderUniformValue::Vec2(_) => UniformType::Float2,
+                    ShaderUniformValue::Float(_) | ShaderUniformValue::FloatArray(_) => UniformType::Float1,
+                    ShaderUniformValue::Vec2(_) | ShaderUniformValue::Vec2Array(_) => UniformType::Float2,
                     ShaderUniformValue::Vec3(_) => UniformType::Float3,
-                    ShaderUniformValue::Vec4(_) => UniformType::Float4,
+                    ShaderUniformValue::Vec4(_) | ShaderUniformValue::Vec4Array(_) => UniformType::Float4,
                     ShaderUniformValue::Int(_) => UniformType::Int1,
                     ShaderUniformValue::Mat4(_) => UniformType::Mat4,
                 };
-                uniform_decls.push(UniformDesc::new(&u.name, utype));
+                let desc = UniformDesc::new(&u.name, utype);
+                uniform_decls.push(match u.value.glsl_type() {
+                    (_, Some(len)) => desc.array(len),
+                    (_, None) => desc,
+                });
             }
 
             let blend_pipeline_params = PipelineParams {
@@ -472,7 +577,7 @@ impl MaterialManager {
                 ..Default::default()
             };
 
-            let material = load_material(
+            let compiled = load_material(
                 ShaderSource::Glsl {
                     vertex: DEFAULT_VERTEX_SHADER,
                     fragment: &config.fragment,
@@ -480,21 +585,43 @@ impl MaterialManager {
                 MaterialParams {
                     pipeline_params: blend_pipeline_params,
                     uniforms: uniform_decls,
-                    ..Default::default()
+                    // Transitions also sample the kept frame, masks the mask
+                    textures: [
+                        config.transition.is_some().then_some("ply_previous"),
+                        config.mask.is_some().then_some("ply_mask"),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .map(str::to_string)
+                    .collect(),
                 },
-            )
-            .unwrap_or_else(|e| {
-                eprintln!("Failed to compile shader material: {:?}", e);
-                // Fall back to default material 
-                load_material(
-                    ShaderSource::Glsl {
-                        vertex: DEFAULT_VERTEX_SHADER,
-                        fragment: DEFAULT_FRAGMENT_SHADER,
-                    },
-                    MaterialParams::default(),
-                )
-                .unwrap()
-            });
+            );
+            let material = match compiled {
+                Ok(material) => {
+                    self.last_compiled.insert(config.name.clone(), config.fragment.clone());
+                    material
+                }
+                Err(e) => {
+                    crate::diagnostics::report(crate::diagnostics::Diagnostic::ShaderCompileFailed {
+                        name: config.name.clone(),
+                        error: format!("{e:?}"),
+                    });
+                    // Cached under the failing source too, so it isn't recompiled every frame
+                    let previous = self.last_compiled.get(&config.name)
+                        .and_then(|fragment| self.materials.get(fragment.as_ref()));
+                    match previous {
+                        Some(previous) => previous.material.clone(),
+                        None => load_material(
+                            ShaderSource::Glsl {
+                                vertex: DEFAULT_VERTEX_SHADER,
+                                fragment: DEFAULT_FRAGMENT_SHADER,
+                            },
+                            MaterialParams::default(),
+                        )
+                        .unwrap(),
+                    }
+                }
+            };
 
             self.materials.insert(config.fragment.clone(), MaterialData {
                 frames_not_used: 0,
@@ -558,11 +685,163 @@ pub fn set_shader_source(name: &str, fragment: &str) {
     MATERIAL_MANAGER.lock().unwrap().set_source(name, fragment);
 }
 
+/// Frames kept for transitions, see [`ElementBuilder::transition`](crate::ElementBuilder::transition).
+struct TransitionFrames {
+    generation: u32,
+    /// The subtree as last drawn.
+    last: Option<Texture2D>,
+    /// The subtree as drawn before the key changed.
+    previous: Option<Texture2D>,
+    drawn: bool,
+}
+
+thread_local! {
+    static TRANSITION_FRAMES: std::cell::RefCell<rustc_hash::FxHashMap<u32, TransitionFrames>> =
+        std::cell::RefCell::new(rustc_hash::FxHashMap::default());
+}
+
+/// Records `current` as the latest frame of a transition and returns the
+/// frame to blend from. When the generation changed, the last frame drawn
+/// before it becomes the one to blend from.
+fn transition_previous_frame(capture: &crate::transition::TransitionCapture, current: &Texture2D) -> Texture2D {
+    TRANSITION_FRAMES.with(|frames| {
+        let mut frames = frames.borrow_mut();
+        let entry = frames.entry(capture.id).or_insert(TransitionFrames {
+            generation: capture.generation,
+            last: None,
+            previous: None,
+            drawn: true,
+        });
+        if entry.generation != capture.generation {
+            entry.generation = capture.generation;
+            entry.previous = entry.last.take();
+        }
+        entry.last = Some(current.clone());
+        entry.drawn = true;
+        // Before any change there is nothing else to show
+        entry.previous.clone().unwrap_or_else(|| current.clone())
+    })
+}
+
+/// Offscreen captures of groups by `ShaderConfig::capture_key`, reused while
+/// their contents don't change.
+struct GroupCapture {
+    target: RenderTarget,
+    drawn: bool,
+}
+
+thread_local! {
+    static GROUP_CAPTURES: std::cell::RefCell<rustc_hash::FxHashMap<u64, GroupCapture>> =
+        std::cell::RefCell::new(rustc_hash::FxHashMap::default());
+}
+
+fn cached_group_capture(key: u64) -> Option<RenderTarget> {
+    GROUP_CAPTURES.with(|captures| {
+        let mut captures = captures.borrow_mut();
+        let capture = captures.get_mut(&key)?;
+        capture.drawn = true;
+        Some(capture.target.clone())
+    })
+}
+
+fn store_group_capture(key: u64, target: &RenderTarget) {
+    GROUP_CAPTURES.with(|captures| {
+        captures.borrow_mut().insert(key, GroupCapture { target: target.clone(), drawn: true });
+    });
+}
+
+/// Offscreen captures of elements by id, sampled by `.mask_element()`.
+struct Silhouette {
+    texture: Texture2D,
+    bounds: BoundingBox,
+    drawn: bool,
+}
+
+thread_local! {
+    static SILHOUETTES: std::cell::RefCell<rustc_hash::FxHashMap<u32, Silhouette>> =
+        std::cell::RefCell::new(rustc_hash::FxHashMap::default());
+}
+
+fn store_silhouette(id: u32, texture: &Texture2D, bounds: BoundingBox) {
+    SILHOUETTES.with(|silhouettes| {
+        silhouettes.borrow_mut().insert(id, Silhouette { texture: texture.clone(), bounds, drawn: true });
+    });
+}
+
+/// The cached texture for a file, loaded first if needed. The cache isn't
+/// locked while loading, so its lock is never held across an await.
+async fn cached_texture(path: &'static str) -> Texture2D {
+    if let Some(texture) = TEXTURE_MANAGER.lock().unwrap().get(path) {
+        return texture.clone();
+    }
+    let texture = load_texture(resolve_asset_path(path)).await.unwrap();
+    TEXTURE_MANAGER.lock().unwrap().cache(path.to_owned(), texture).clone()
+}
+
+/// The mask texture and where it's placed, in logical pixels. A negative
+/// height samples it flipped, as render targets are.
+async fn mask_texture(source: &crate::mask::MaskSource, bb: &BoundingBox) -> Option<(Texture2D, (f32, f32, f32, f32))> {
+    let rect = (bb.x, bb.y, bb.width, bb.height);
+    match source {
+        crate::mask::MaskSource::Element(id) => SILHOUETTES.with(|silhouettes| {
+            let silhouettes = silhouettes.borrow();
+            let silhouette = silhouettes.get(id)?;
+            let b = silhouette.bounds;
+            Some((silhouette.texture.clone(), (b.x, b.y + b.height, b.width, -b.height)))
+        }),
+        crate::mask::MaskSource::Image(ImageSource::Texture(texture)) => Some((texture.clone(), rect)),
+        crate::mask::MaskSource::Image(ImageSource::Asset(asset)) => {
+            let texture = match asset {
+                GraphicAsset::Path(path) => cached_texture(path).await,
+                GraphicAsset::Bytes { file_name, data } => TEXTURE_MANAGER
+                    .lock()
+                    .unwrap()
+                    .get_or_create(file_name.to_string(), || Texture2D::from_file_with_format(data, None))
+                    .clone(),
+            };
+            Some((texture, rect))
+        }
+        #[cfg(feature = "tinyvg")]
+        crate::mask::MaskSource::Image(ImageSource::TinyVg(_)) => None,
+    }
+}
+
+/// Frame values behind the automatic `ply_*` shader uniforms.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ShaderGlobals {
+    /// Seconds since the app started.
+    pub time: f32,
+    /// Layout size in logical pixels.
+    pub resolution: (f32, f32),
+    /// Pointer position in logical pixels.
+    pub mouse: (f32, f32),
+}
+
+thread_local! {
+    /// Set when a frame is evaluated, read when its shaders are drawn.
+    static SHADER_GLOBALS: std::cell::Cell<ShaderGlobals> = const {
+        std::cell::Cell::new(ShaderGlobals { time: 0.0, resolution: (0.0, 0.0), mouse: (0.0, 0.0) })
+    };
+}
+
+pub(crate) fn shader_globals() -> ShaderGlobals {
+    SHADER_GLOBALS.with(std::cell::Cell::get)
+}
+
+pub(crate) fn set_shader_globals(globals: ShaderGlobals) {
+    SHADER_GLOBALS.with(|cell| cell.set(globals));
+}
+
 /// Apply shader uniforms to a material, including auto-uniforms.
 fn apply_shader_uniforms(material: &Material, config: &ShaderConfig, bb: &BoundingBox) {
     // Auto-uniforms
     material.set_uniform("u_resolution", (bb.width, bb.height));
     material.set_uniform("u_position", (bb.x, bb.y));
+    let globals = shader_globals();
+    material.set_uniform("ply_time", globals.time);
+    material.set_uniform("ply_resolution", globals.resolution);
+    material.set_uniform("ply_element_rect", (bb.x, bb.y, bb.width, bb.height));
+    material.set_uniform("ply_mouse", globals.mouse);
 
     // User-defined uniforms
     for u in &config.uniforms {
@@ -573,6 +852,9 @@ fn apply_shader_uniforms(material: &Material, config: &ShaderConfig, bb: &Boundi
             ShaderUniformValue::Vec4(v) => material.set_uniform(&u.name, *v),
             ShaderUniformValue::Int(v) => material.set_uniform(&u.name, *v),
             ShaderUniformValue::Mat4(v) => material.set_uniform(&u.name, *v),
+            ShaderUniformValue::FloatArray(v) => material.set_uniform_array(&u.name, v.as_slice()),
+            ShaderUniformValue::Vec2Array(v) => material.set_uniform_array(&u.name, v.as_flattened()),
+            ShaderUniformValue::Vec4Array(v) => material.set_uniform_array(&u.name, v.as_flattened()),
         }
     }
 }
@@ -586,30 +868,211 @@ fn ply_to_macroquad_color(ply_color: &crate::color::Color) -> Color {
     }
 }
 
+/// Draws a text run with the SDF renderer when an atlas exists for `sdf_font`,
+/// otherwise with macroquad's rasterized glyphs.
+#[cfg_attr(not(feature = "sdf-text"), allow(unused_variables))]
+fn draw_text_run(text: &str, x: f32, y: f32, params: TextParams, sdf_font: Option<&str>) {
+    #[cfg(feature = "sdf-text")]
+    if let Some(key) = sdf_font {
+        if crate::sdf_text::SDF_TEXT_CACHE.lock().unwrap().draw_text(key, text, x, y, &params) {
+            return;
+        }
+    }
+    // Rasterize at physical size and scale back down to logical size
+    let (raster_size, raster_scale) = raster_font_size(params.font_size, display_scale());
+    draw_text_ex(
+        text,
+        x,
+        y,
+        TextParams {
+            font_size: raster_size,
+            font_scale: params.font_scale * raster_scale,
+            ..params
+        },
+    );
+}
+
+/// Draws the shadow and outline passes of a text run, before its fill pass.
+///
+/// `draw` renders the run offset by `(dx, dy)` in the given color. Blur and
+/// outlines are approximated with rings of offset copies.
+/// Draws a run of styled text with its decorations through `draw_at`,
+/// sheared around `baseline` for faux italic and overdrawn with a small
+/// horizontal offset for faux bold.
+#[cfg(feature = "text-styling")]
+fn draw_styled_run(
+    tr: &crate::text_styling::Transform,
+    baseline: f32,
+    shadow: Option<crate::text::TextShadow>,
+    outline: Option<crate::text::TextOutline>,
+    color: Color,
+    draw_at: &impl Fn(f32, f32, Color),
+) {
+    if tr.skew != 0.0 {
+        let shear = Mat4::from_cols_array(&[
+            1.0, 0.0, 0.0, 0.0,
+            -tr.skew, 1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            tr.skew * baseline, 0.0, 0.0, 1.0,
+        ]);
+        unsafe {
+            get_internal_gl().quad_gl.push_model_matrix(shear);
+        }
+    }
+    draw_text_decorations(shadow, outline, color.a, draw_at);
+    draw_at(0.0, 0.0, color);
+    if tr.weight > 0.0 {
+        draw_at(tr.weight, 0.0, color);
+    }
+    if tr.skew != 0.0 {
+        unsafe {
+            get_internal_gl().quad_gl.pop_model_matrix();
+        }
+    }
+}
+
+fn draw_text_decorations(
+    shadow: Option<crate::text::TextShadow>,
+    outline: Option<crate::text::TextOutline>,
+    fill_alpha: f32,
+    mut draw: impl FnMut(f32, f32, Color),
+) {
+    if let Some(shadow) = shadow {
+        let mut color = ply_to_macroquad_color(&shadow.color);
+        color.a *= fill_alpha;
+        if shadow.blur <= 0.0 {
+            draw(shadow.offset.x, shadow.offset.y, color);
+        } else {
+            const RING_SAMPLES: usize = 8;
+            let rings = (shadow.blur.ceil() as usize).clamp(1, 4);
+            let passes = 1 + rings * RING_SAMPLES;
+            color.a = (color.a * 2.0 / passes as f32).min(color.a);
+            draw(shadow.offset.x, shadow.offset.y, color);
+            for ring in 1..=rings {
+                let radius = shadow.blur * ring as f32 / rings as f32;
+                for i in 0..RING_SAMPLES {
+                    let angle = i as f32 / RING_SAMPLES as f32 * 2.0 * PI;
+                    draw(
+                        shadow.offset.x + angle.cos() * radius,
+                        shadow.offset.y + angle.sin() * radius,
+                        color,
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(outline) = outline {
+        if outline.width > 0.0 {
+            let mut color = ply_to_macroquad_color(&outline.color);
+            color.a *= fill_alpha;
+            let samples = if outline.width <= 1.5 { 8 } else { 16 };
+            // Thick outlines get an inner ring too so glyph edges have no gaps
+            let rings = (outline.width / 2.0).ceil().max(1.0) as usize;
+            for ring in 1..=rings {
+                let radius = outline.width * ring as f32 / rings as f32;
+                for i in 0..samples {
+                    let angle = i as f32 / samples as f32 * 2.0 * PI;
+                    draw(angle.cos() * radius, angle.sin() * radius, color);
+                }
+            }
+        }
+    }
+}
+
 /// Draws a rounded rectangle as a single triangle-fan mesh.
 /// This avoids the visual artifacts of multi-shape rendering and handles alpha correctly.
-fn draw_good_rounded_rectangle(x: f32, y: f32, w: f32, h: f32, cr: &CornerRadii, color: Color) {
+fn draw_good_rounded_rectangle(x: f32, y: f32, w: f32, h: f32, cr: &CornerRadii, color: Color, edge_antialiasing: EdgeAntialiasing) {
+    let mut mesh = Mesh { vertices: Vec::new(), indices: Vec::new(), texture: None };
+    push_rounded_rectangle(&mut mesh.vertices, &mut mesh.indices, &BoundingBox::new(x, y, w, h), cr, color, edge_antialiasing);
+    draw_mesh(&mesh);
+}
+
+/// Vertices a rounded rectangle's outline has, at most.
+fn rounded_rectangle_vertex_count(cr: &CornerRadii, edge_antialiasing: EdgeAntialiasing) -> usize {
+    use std::f32::consts::FRAC_PI_2;
+    let segment = arc_segment_length(edge_antialiasing);
+    // Each corner produces ~(FRAC_PI_2 * radius / segment).max(6) + 1 vertices
+    [cr.top_left, cr.top_right, cr.bottom_left, cr.bottom_right]
+        .iter()
+        .map(|&r| if r <= 0.0 { 1 } else { ((FRAC_PI_2 * r) / segment).max(6.0) as usize + 1 })
+        .sum::<usize>()
+}
+
+/// Fades the edge of a convex triangle fan out over `feather` pixels with a
+/// transparent fringe. The fan's outline is the `count` vertices from
+/// `first`. Axis-aligned edges get no fringe, so they stay crisp and
+/// touching rectangles don't overlap.
+fn push_feathered_edge(vertices: &mut Vec<Vertex>, indices: &mut Vec<u16>, first: u16, count: usize, center: Vec2, feather: f32) {
+    let outline: Vec<Vec2> = vertices[first as usize..first as usize + count]
+        .iter()
+        .map(|vertex| vertex.position.truncate())
+        .collect();
+    let edge_normals: Vec<Vec2> = (0..count)
+        .map(|i| {
+            let (a, b) = (outline[i], outline[(i + 1) % count]);
+            let normal = Vec2::new(b.y - a.y, a.x - b.x).normalize_or_zero();
+            if normal.dot((a + b) / 2.0 - center) < 0.0 { -normal } else { normal }
+        })
+        .collect();
+
+    let ring = vertices.len() as u16;
+    for i in 0..count {
+        let inner = vertices[first as usize + i];
+        let normal = (edge_normals[(i + count - 1) % count] + edge_normals[i]).normalize_or_zero();
+        let position = outline[i] + normal * feather;
+        vertices.push(Vertex {
+            position: position.extend(0.0),
+            color: [inner.color[0], inner.color[1], inner.color[2], 0],
+            ..inner
+        });
+    }
+    for i in 0..count {
+        let j = (i + 1) % count;
+        let edge = outline[j] - outline[i];
+        let length = edge.length();
+        if length == 0.0 || edge.x.abs() <= length * 1e-3 || edge.y.abs() <= length * 1e-3 {
+            continue;
+        }
+        let (inner_i, inner_j) = (first + i as u16, first + j as u16);
+        let (outer_i, outer_j) = (ring + i as u16, ring + j as u16);
+        indices.extend([inner_i, outer_i, outer_j, inner_i, outer_j, inner_j]);
+    }
+}
+
+/// Appends a rounded rectangle's triangle fan to a mesh, or a plain quad
+/// without corner radii.
+pub(crate) fn push_rounded_rectangle(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u16>,
+    bb: &BoundingBox,
+    cr: &CornerRadii,
+    color: Color,
+    edge_antialiasing: EdgeAntialiasing,
+) {
     use std::f32::consts::{FRAC_PI_2, PI};
 
+    let (x, y, w, h) = (bb.x, bb.y, bb.width, bb.height);
+    let base = vertices.len() as u16;
     if cr.top_left == 0.0 && cr.top_right == 0.0 && cr.bottom_left == 0.0 && cr.bottom_right == 0.0 {
-        draw_rectangle(x, y, w, h, color);
+        vertices.extend([
+            Vertex::new(x, y, 0.0, 0.0, 0.0, color),
+            Vertex::new(x + w, y, 0.0, 1.0, 0.0, color),
+            Vertex::new(x + w, y + h, 0.0, 1.0, 1.0, color),
+            Vertex::new(x, y + h, 0.0, 0.0, 1.0, color),
+        ]);
+        indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
         return;
     }
 
     // Generate outline vertices for the rounded rectangle
-    // Pre-allocate: each corner produces ~(FRAC_PI_2 * radius / PIXELS_PER_POINT).max(6) + 1 vertices
-    let est_verts = [cr.top_left, cr.top_right, cr.bottom_left, cr.bottom_right]
-        .iter()
-        .map(|&r| if r <= 0.0 { 1 } else { ((FRAC_PI_2 * r) / PIXELS_PER_POINT).max(6.0) as usize + 1 })
-        .sum::<usize>();
-    let mut outline: Vec<Vec2> = Vec::with_capacity(est_verts);
-
+    let mut outline: Vec<Vec2> = Vec::with_capacity(rounded_rectangle_vertex_count(cr, edge_antialiasing));
     let add_arc = |outline: &mut Vec<Vec2>, cx: f32, cy: f32, radius: f32, start_angle: f32, end_angle: f32| {
         if radius <= 0.0 {
             outline.push(Vec2::new(cx, cy));
             return;
         }
-        let sides = ((FRAC_PI_2 * radius) / PIXELS_PER_POINT).max(6.0) as usize;
+        let sides = ((FRAC_PI_2 * radius) / arc_segment_length(edge_antialiasing)).max(6.0) as usize;
         // Use incremental rotation to avoid per-point cos/sin
         let step = (end_angle - start_angle) / sides as f32;
         let step_cos = step.cos();
@@ -651,7 +1114,7 @@ fn draw_good_rounded_rectangle(x: f32, y: f32, w: f32, h: f32, cr: &CornerRadii,
     let cx = x + w / 2.0;
     let cy = y + h / 2.0;
 
-    let mut vertices = Vec::with_capacity(n + 1);
+    vertices.reserve(n + 1);
     // Center vertex (index 0)
     vertices.push(Vertex {
         position: Vec3::new(cx, cy, 0.0),
@@ -669,38 +1132,35 @@ fn draw_good_rounded_rectangle(x: f32, y: f32, w: f32, h: f32, cr: &CornerRadii,
         });
     }
 
-    let mut indices = Vec::with_capacity(n * 3);
+    indices.reserve(n * 3);
     for i in 0..n {
-        indices.push(0u16); // center
-        indices.push((i + 1) as u16);
-        indices.push(((i + 1) % n + 1) as u16);
+        indices.push(base); // center
+        indices.push(base + (i + 1) as u16);
+        indices.push(base + ((i + 1) % n + 1) as u16);
     }
 
-    let mesh = Mesh {
-        vertices,
-        indices,
-        texture: None,
-    };
-    draw_mesh(&mesh);
+    let feather = edge_feather(edge_antialiasing);
+    if feather > 0.0 {
+        push_feathered_edge(vertices, indices, base + 1, n, Vec2::new(cx, cy), feather);
+    }
 }
 
-/// Draws a rounded rectangle rotated by `rotation_radians` around its center.
+/// Draws a rounded rectangle rotated by `rotation.rotation_radians` around its center.
 /// All outline vertices are rotated before building the triangle fan mesh.
-/// `(x, y, w, h)` is the *original* (unrotated) bounding box — the centre of
-/// rotation is `(x + w/2, y + h/2)`.
+/// `bb` is the *original* (unrotated) bounding box — the centre of
+/// rotation is its center.
 fn draw_good_rotated_rounded_rectangle(
-    x: f32,
-    y: f32,
-    w: f32,
-    h: f32,
+    bb: &BoundingBox,
     cr: &CornerRadii,
     color: Color,
-    rotation_radians: f32,
-    flip_x: bool,
-    flip_y: bool,
+    rotation: crate::engine::ShapeRotationConfig,
+    edge_antialiasing: EdgeAntialiasing,
 ) {
     use std::f32::consts::{FRAC_PI_2, PI};
 
+    let (x, y, w, h) = (bb.x, bb.y, bb.width, bb.height);
+    let crate::engine::ShapeRotationConfig { rotation_radians, flip_x, flip_y } = rotation;
+
     let cx = x + w / 2.0;
     let cy = y + h / 2.0;
 
@@ -724,10 +1184,7 @@ fn draw_good_rotated_rounded_rectangle(
     let est_verts = if cr.top_left == 0.0 && cr.top_right == 0.0 && cr.bottom_left == 0.0 && cr.bottom_right == 0.0 {
         4
     } else {
-        [cr.top_left, cr.top_right, cr.bottom_left, cr.bottom_right]
-            .iter()
-            .map(|&r| if r <= 0.0 { 1 } else { ((FRAC_PI_2 * r) / PIXELS_PER_POINT).max(6.0) as usize + 1 })
-            .sum::<usize>()
+        rounded_rectangle_vertex_count(cr, edge_antialiasing)
     };
     let mut outline: Vec<Vec2> = Vec::with_capacity(est_verts);
 
@@ -736,7 +1193,7 @@ fn draw_good_rotated_rounded_rectangle(
             outline.push(rotate_point(arc_cx, arc_cy));
             return;
         }
-        let sides = ((FRAC_PI_2 * radius) / PIXELS_PER_POINT).max(6.0) as usize;
+        let sides = ((FRAC_PI_2 * radius) / arc_segment_length(edge_antialiasing)).max(6.0) as usize;
         // Use incremental rotation to avoid per-point cos/sin
         let step = (end_angle - start_angle) / sides as f32;
         let step_cos = step.cos();
@@ -804,6 +1261,11 @@ fn draw_good_rotated_rounded_rectangle(
         indices.push(((i + 1) % n + 1) as u16);
     }
 
+    let feather = edge_feather(edge_antialiasing);
+    if feather > 0.0 {
+        push_feathered_edge(&mut vertices, &mut indices, 1, n, center_rot, feather);
+    }
+
     draw_mesh(&Mesh { vertices, indices, texture: None });
 }
 
@@ -859,13 +1321,20 @@ struct RenderState {
     style_stack: Vec<String>,
     #[cfg(feature = "text-styling")]
     total_char_index: usize,
+    /// Consecutive rectangles and borders, drawn together.
+    shapes: ShapeBatch,
+    /// How deep into a group whose cached capture is being reused; its
+    /// commands are skipped until the matching `GroupEnd`.
+    skip_depth: usize,
 }
 
 impl RenderState {
-    fn new() -> Self {
+    fn new(edge_antialiasing: EdgeAntialiasing) -> Self {
         Self {
             clip_stack: Vec::new(),
             rt_stack: Vec::new(),
+            shapes: ShapeBatch { edge_antialiasing, ..Default::default() },
+            skip_depth: 0,
             #[cfg(feature = "text-styling")]
             style_stack: Vec::new(),
             #[cfg(feature = "text-styling")]
@@ -874,6 +1343,60 @@ impl RenderState {
     }
 }
 
+/// Stays under macroquad's default draw call capacity of 10000 vertices and
+/// 5000 indices, past which it drops geometry.
+const SHAPE_BATCH_MAX_VERTICES: usize = 9600;
+const SHAPE_BATCH_MAX_INDICES: usize = 4800;
+
+/// Untextured geometry of consecutive rectangles and borders, drawn as one
+/// mesh instead of one call per command. Anything that changes the draw
+/// state in between (scissors, materials, cameras, other commands) flushes
+/// it first.
+#[derive(Default)]
+struct ShapeBatch {
+    vertices: Vec<Vertex>,
+    indices: Vec<u16>,
+    /// How this frame smooths the edges of the shapes it draws.
+    edge_antialiasing: EdgeAntialiasing,
+}
+
+impl ShapeBatch {
+    /// Makes room for a shape of up to this many vertices and indices,
+    /// drawing what's batched if it wouldn't fit.
+    fn reserve(&mut self, vertices: usize, indices: usize) {
+        if self.vertices.len() + vertices > SHAPE_BATCH_MAX_VERTICES
+            || self.indices.len() + indices > SHAPE_BATCH_MAX_INDICES
+        {
+            self.flush();
+        }
+    }
+
+    fn push_rounded_rectangle(&mut self, bb: &BoundingBox, cr: &CornerRadii, color: Color) {
+        // Fan plus feathered fringe
+        let vertex_count = rounded_rectangle_vertex_count(cr, self.edge_antialiasing) * 2 + 1;
+        self.reserve(vertex_count, vertex_count * 6);
+        push_rounded_rectangle(&mut self.vertices, &mut self.indices, bb, cr, color, self.edge_antialiasing);
+    }
+
+    /// Draws everything batched so far.
+    fn flush(&mut self) {
+        if self.indices.is_empty() {
+            return;
+        }
+        let mut mesh = Mesh {
+            vertices: std::mem::take(&mut self.vertices),
+            indices: std::mem::take(&mut self.indices),
+            texture: None,
+        };
+        draw_mesh(&mesh);
+        // Keep the buffers for the next batch
+        mesh.vertices.clear();
+        mesh.indices.clear();
+        self.vertices = mesh.vertices;
+        self.indices = mesh.indices;
+    }
+}
+
 fn intersect_scissor(
     a: (i32, i32, i32, i32),
     b: (i32, i32, i32, i32),
@@ -891,6 +1414,114 @@ fn intersect_scissor(
     (x1, y1, (x2 - x1).max(0), (y2 - y1).max(0))
 }
 
+/// Sides for a circle of `radius` that still looks round.
+fn circle_sides(radius: f32) -> u8 {
+    (radius.abs().sqrt() * 6.0).clamp(12.0, 96.0) as u8
+}
+
+/// Draws a canvas element's commands, clipped to its (rotated) bounds.
+fn draw_canvas(
+    canvas: &crate::render_commands::Canvas,
+    bb: &BoundingBox,
+    rotation: Option<&crate::engine::ShapeRotationConfig>,
+    clip: Option<(i32, i32, i32, i32)>,
+) {
+    use crate::canvas::PaintCommand;
+    use crate::math::Vector2;
+
+    let to_screen = crate::canvas::transform(bb, rotation);
+    let corners = [(0.0, 0.0), (bb.width, 0.0), (0.0, bb.height), (bb.width, bb.height)]
+        .map(|(x, y)| to_screen(Vector2::new(x, y)));
+    let min_x = corners.iter().map(|c| c.x).fold(f32::MAX, f32::min);
+    let min_y = corners.iter().map(|c| c.y).fold(f32::MAX, f32::min);
+    let max_x = corners.iter().map(|c| c.x).fold(f32::MIN, f32::max);
+    let max_y = corners.iter().map(|c| c.y).fold(f32::MIN, f32::max);
+    let dpi = miniquad::window::dpi_scale();
+    let own_clip = (
+        (min_x * dpi) as i32,
+        (min_y * dpi) as i32,
+        ((max_x - min_x) * dpi).ceil() as i32,
+        ((max_y - min_y) * dpi).ceil() as i32,
+    );
+    unsafe {
+        get_internal_gl().quad_gl.scissor(Some(clip.map_or(own_clip, |parent| intersect_scissor(parent, own_clip))));
+    }
+
+    let stroke_polyline = |points: &[Vec2], closed: bool, thickness: f32, color: Color| {
+        for pair in points.windows(2) {
+            draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, thickness, color);
+        }
+        if closed && points.len() > 2 {
+            let (first, last) = (points[0], points[points.len() - 1]);
+            draw_line(last.x, last.y, first.x, first.y, thickness, color);
+        }
+        // Round joins so thick strokes don't show notches at the corners
+        if thickness > 2.0 {
+            let joints = if closed { points } else { &points[1..points.len() - 1] };
+            for joint in joints {
+                draw_circle(joint.x, joint.y, thickness / 2.0, color);
+            }
+        }
+    };
+
+    for paint in &canvas.commands {
+        match paint {
+            PaintCommand::Line { from, to, thickness, color } => {
+                let (from, to) = (to_screen(*from), to_screen(*to));
+                draw_line(from.x, from.y, to.x, to.y, *thickness, ply_to_macroquad_color(color));
+            }
+            PaintCommand::Circle { center, radius, fill, stroke } => {
+                let center = to_screen(*center);
+                let sides = circle_sides(*radius);
+                if let Some(fill) = fill {
+                    draw_poly(center.x, center.y, sides, *radius, 0.0, ply_to_macroquad_color(fill));
+                }
+                if let Some(stroke) = stroke {
+                    draw_poly_lines(center.x, center.y, sides, *radius, 0.0, stroke.thickness, ply_to_macroquad_color(&stroke.color));
+                }
+            }
+            PaintCommand::Path { points, closed, fill, stroke } => {
+                let points: Vec<Vec2> = points.iter().map(|p| {
+                    let p = to_screen(*p);
+                    Vec2::new(p.x, p.y)
+                }).collect();
+                if points.len() < 2 {
+                    continue;
+                }
+                if let Some(fill) = fill {
+                    let color = ply_to_macroquad_color(fill);
+                    for pair in points[1..].windows(2) {
+                        draw_triangle(points[0], pair[0], pair[1], color);
+                    }
+                }
+                if let Some(stroke) = stroke {
+                    stroke_polyline(&points, *closed, stroke.thickness, ply_to_macroquad_color(&stroke.color));
+                }
+            }
+        }
+    }
+
+    unsafe {
+        get_internal_gl().quad_gl.scissor(clip);
+    }
+}
+
+thread_local! {
+    /// Target and area the current frame renders into, when not the window.
+    static BASE_TARGET: std::cell::RefCell<Option<(RenderTarget, Rect)>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Points drawing back at the frame's target after drawing into another one.
+fn set_base_camera() {
+    BASE_TARGET.with(|target| match &*target.borrow() {
+        Some((render_target, rect)) => set_camera(&Camera2D {
+            render_target: Some(render_target.clone()),
+            ..Camera2D::from_display_rect(*rect)
+        }),
+        None => set_default_camera(),
+    });
+}
+
 /// Render custom content to a [`Texture2D`]
 ///
 /// Sets up a render target, points a camera at it, calls your closure, then
@@ -920,8 +1551,13 @@ pub fn render_to_texture(width: f32, height: f32, draw: impl FnOnce()) -> Textur
     render_target.texture
 }
 
-fn rounded_rectangle_texture(cr: &CornerRadii, bb: &BoundingBox, clip: &Option<(i32, i32, i32, i32)>) -> Texture2D {
-    let render_target = render_target_msaa(bb.width as u32, bb.height as u32);
+fn rounded_rectangle_texture(
+    cr: &CornerRadii,
+    bb: &BoundingBox,
+    clip: &Option<(i32, i32, i32, i32)>,
+    edge_antialiasing: EdgeAntialiasing,
+) -> Texture2D {
+    let render_target = scaled_render_target(bb.width, bb.height);
     render_target.texture.set_filter(FilterMode::Linear);
     let mut cam = Camera2D::from_display_rect(Rect::new(0.0, 0.0, bb.width, bb.height));
     cam.render_target = Some(render_target.clone());
@@ -930,9 +1566,9 @@ fn rounded_rectangle_texture(cr: &CornerRadii, bb: &BoundingBox, clip: &Option<(
         get_internal_gl().quad_gl.scissor(None);
     };
 
-    draw_good_rounded_rectangle(0.0, 0.0, bb.width, bb.height, cr, WHITE);
+    draw_good_rounded_rectangle(0.0, 0.0, bb.width, bb.height, cr, WHITE, edge_antialiasing);
 
-    set_default_camera();
+    set_base_camera();
     unsafe {
         get_internal_gl().quad_gl.scissor(*clip);
     }
@@ -980,7 +1616,7 @@ fn render_tinyvg_image(
     let scale_x = dest_width / tvg_width;
     let scale_y = dest_height / tvg_height;
     
-    let render_target = render_target_msaa(dest_width as u32, dest_height as u32);
+    let render_target = scaled_render_target(dest_width, dest_height);
     render_target.texture.set_filter(FilterMode::Linear);
     let mut cam = Camera2D::from_display_rect(Rect::new(0.0, 0.0, dest_width, dest_height));
     cam.render_target = Some(render_target.clone());
@@ -1543,7 +2179,7 @@ fn render_tinyvg_image(
         }
     }
     
-    set_default_camera();
+    set_base_camera();
     unsafe {
         get_internal_gl().quad_gl.scissor(*clip);
     }
@@ -1552,7 +2188,7 @@ fn render_tinyvg_image(
 }
 
 fn resize(texture: &Texture2D, height: f32, width: f32, clip: &Option<(i32, i32, i32, i32)>) -> Texture2D {
-    let render_target = render_target_msaa(width as u32, height as u32);
+    let render_target = scaled_render_target(width, height);
     render_target.texture.set_filter(FilterMode::Linear);
     let mut cam = Camera2D::from_display_rect(Rect::new(0.0, 0.0, width, height));
     cam.render_target = Some(render_target.clone());
@@ -1571,479 +2207,608 @@ fn resize(texture: &Texture2D, height: f32, width: f32, clip: &Option<(i32, i32,
             ..Default::default()
         },
     );
-    set_default_camera();
+    set_base_camera();
     unsafe {
         get_internal_gl().quad_gl.scissor(*clip);
     }
     render_target.texture
 }
 
-/// Draws all render commands to the screen using macroquad.
-pub async fn render<CustomElementData: Clone + Default + std::fmt::Debug>(
+/// Draws all render commands into a new texture of `width` × `height` logical pixels.
+pub async fn render_offscreen<CustomElementData: Clone + Default + std::fmt::Debug>(
+    commands: Vec<RenderCommand<CustomElementData>>,
+    width: f32,
+    height: f32,
+    handle_custom_command: impl Fn(&RenderCommand<CustomElementData>),
+) -> Texture2D {
+    render_offscreen_antialiased(commands, width, height, EdgeAntialiasing::Off, handle_custom_command).await
+}
+
+pub(crate) async fn render_offscreen_antialiased<CustomElementData: Clone + Default + std::fmt::Debug>(
     commands: Vec<RenderCommand<CustomElementData>>,
+    width: f32,
+    height: f32,
+    edge_antialiasing: EdgeAntialiasing,
     handle_custom_command: impl Fn(&RenderCommand<CustomElementData>),
+) -> Texture2D {
+    let render_target = scaled_render_target(width, height);
+    render_target.texture.set_filter(FilterMode::Linear);
+    BASE_TARGET.with(|target| {
+        *target.borrow_mut() = Some((render_target.clone(), Rect::new(0.0, 0.0, width, height)));
+    });
+    set_base_camera();
+    clear_background(Color::new(0.0, 0.0, 0.0, 0.0));
+
+    render_antialiased(commands, edge_antialiasing, handle_custom_command).await;
+
+    BASE_TARGET.with(|target| *target.borrow_mut() = None);
+    set_default_camera();
+    unsafe {
+        get_internal_gl().quad_gl.scissor(None);
+    }
+    render_target.texture
+}
+
+/// A backend that draws Ply's render commands, for [`Ply::show_with`](crate::Ply::show_with).
+///
+/// Each frame is `begin_frame`, then every command in order, then
+/// `end_frame`. Group commands go to `push_group` and `pop_group`, which
+/// draw them like any other command unless overridden. `measure_text` sizes
+/// text for layout, see [`Ply::measure_text_with`](crate::Ply::measure_text_with).
+///
+/// The drawing methods return `Send` futures, so frames can be drawn from a
+/// multithreaded executor. Implementations can still be `async fn`s.
+pub trait Renderer<CustomElementData> {
+    /// Measures text the way this backend draws it.
+    fn measure_text(&self, text: &str, config: &crate::TextConfig) -> crate::Dimensions;
+
+    /// Called before `begin_frame` with [`Ply::edge_antialiasing`](crate::Ply::edge_antialiasing),
+    /// for backends that smooth edges the same way. Ignored by default.
+    fn set_edge_antialiasing(&mut self, _mode: EdgeAntialiasing) {}
+
+    /// Called before the first command, with the layout size in logical pixels.
+    fn begin_frame(&mut self, _dimensions: crate::Dimensions) {}
+
+    fn draw_command(&mut self, command: &RenderCommand<CustomElementData>) -> impl std::future::Future<Output = ()> + Send;
+
+    /// Called with `GroupBegin`. Commands until the matching `GroupEnd` belong
+    /// to the group.
+    fn push_group(&mut self, command: &RenderCommand<CustomElementData>) -> impl std::future::Future<Output = ()> + Send {
+        self.draw_command(command)
+    }
+
+    /// Called with `GroupEnd`.
+    fn pop_group(&mut self, command: &RenderCommand<CustomElementData>) -> impl std::future::Future<Output = ()> + Send {
+        self.draw_command(command)
+    }
+
+    /// Called after the last command.
+    fn end_frame(&mut self) {}
+}
+
+/// Draws render commands in order through `renderer`, as one frame of
+/// `dimensions` logical pixels.
+pub async fn render_with<CustomElementData, R: Renderer<CustomElementData>>(
+    renderer: &mut R,
+    commands: &[RenderCommand<CustomElementData>],
+    dimensions: crate::Dimensions,
 ) {
-    let mut state = RenderState::new();
+    renderer.begin_frame(dimensions);
     for command in commands {
-        let current_clip = state.clip_stack.last().copied();
-        match &command.config {
-            RenderCommandConfig::Image(image) => {
-                let bb = command.bounding_box;
-                let cr = &image.corner_radii;
-                let mut tint = ply_to_macroquad_color(&image.background_color);
-                if tint == Color::new(0.0, 0.0, 0.0, 0.0) {
-                    tint = Color::new(1.0, 1.0, 1.0, 1.0);
-                }
+        match command.config {
+            RenderCommandConfig::GroupBegin { .. } => renderer.push_group(command).await,
+            RenderCommandConfig::GroupEnd => renderer.pop_group(command).await,
+            _ => renderer.draw_command(command).await,
+        }
+    }
+    renderer.end_frame();
+}
 
-                match &image.data {
-                    ImageSource::Texture(tex) => {
-                        // Direct GPU texture — draw immediately, no TextureManager
-                        let has_corner_radii = cr.top_left > 0.0 || cr.top_right > 0.0 || cr.bottom_left > 0.0 || cr.bottom_right > 0.0;
-                        if !has_corner_radii {
-                            draw_texture_ex(
-                                tex,
-                                bb.x,
-                                bb.y,
-                                tint,
-                                DrawTextureParams {
-                                    dest_size: Some(Vec2::new(bb.width, bb.height)),
-                                    ..Default::default()
-                                },
-                            );
-                        } else {
-                            let mut manager = TEXTURE_MANAGER.lock().unwrap();
-                            // Use texture raw pointer as a unique key for the corner-radii variant
-                            let key = format!(
-                                "tex-proc:{:?}:{}:{}:{}:{}:{}:{}:{:?}",
-                                tex.raw_miniquad_id(),
-                                bb.width, bb.height,
-                                cr.top_left, cr.top_right, cr.bottom_left, cr.bottom_right,
-                                current_clip
-                            );
-                            let texture = manager.get_or_create(key, || {
-                                let mut resized_image: Image = resize(tex, bb.height, bb.width, &current_clip).get_texture_data();
-                                let rounded_rect: Image = rounded_rectangle_texture(cr, &bb, &current_clip).get_texture_data();
-                                for i in 0..resized_image.bytes.len()/4 {
-                                    let this_alpha = resized_image.bytes[i * 4 + 3] as f32 / 255.0;
-                                    let mask_alpha = rounded_rect.bytes[i * 4 + 3] as f32 / 255.0;
-                                    resized_image.bytes[i * 4 + 3] = (this_alpha * mask_alpha * 255.0) as u8;
-                                }
-                                Texture2D::from_image(&resized_image)
-                            });
-                            draw_texture_ex(
-                                texture,
-                                bb.x,
-                                bb.y,
-                                tint,
-                                DrawTextureParams {
-                                    dest_size: Some(Vec2::new(bb.width, bb.height)),
-                                    ..Default::default()
-                                },
-                            );
-                        }
-                    }
-                    #[cfg(feature = "tinyvg")]
-                    ImageSource::TinyVg(tvg_image) => {
-                        // Procedural TinyVG — rasterize every frame (no caching, content may change)
-                        let has_corner_radii = cr.top_left > 0.0 || cr.top_right > 0.0 || cr.bottom_left > 0.0 || cr.bottom_right > 0.0;
-                        if let Some(tvg_rt) = render_tinyvg_image(tvg_image, bb.width, bb.height, &current_clip) {
-                            let final_texture = if has_corner_radii {
-                                let mut tvg_img: Image = tvg_rt.texture.get_texture_data();
-                                let rounded_rect: Image = rounded_rectangle_texture(cr, &bb, &current_clip).get_texture_data();
-                                for i in 0..tvg_img.bytes.len()/4 {
-                                    let this_alpha = tvg_img.bytes[i * 4 + 3] as f32 / 255.0;
-                                    let mask_alpha = rounded_rect.bytes[i * 4 + 3] as f32 / 255.0;
-                                    tvg_img.bytes[i * 4 + 3] = (this_alpha * mask_alpha * 255.0) as u8;
-                                }
-                                Texture2D::from_image(&tvg_img)
-                            } else {
-                                tvg_rt.texture.clone()
-                            };
-                            draw_texture_ex(
-                                &final_texture,
-                                bb.x,
-                                bb.y,
-                                tint,
-                                DrawTextureParams {
-                                    dest_size: Some(Vec2::new(bb.width, bb.height)),
-                                    flip_y: true,
-                                    ..Default::default()
-                                },
-                            );
-                        }
-                    }
-                    ImageSource::Asset(ga) => {
-                        // Static asset — existing behavior
-                        let mut manager = TEXTURE_MANAGER.lock().unwrap();
+/// Measures text with macroquad's fonts.
+type MeasureText = Box<dyn Fn(&str, &crate::TextConfig) -> crate::Dimensions + Send + Sync>;
 
-                        #[cfg(feature = "tinyvg")]
-                        let is_tvg = ga.get_name().to_lowercase().ends_with(".tvg");
-                        #[cfg(not(feature = "tinyvg"))]
-                        let is_tvg = false;
+/// The macroquad backend as a [`Renderer`], drawing to the screen. This is
+/// what [`Ply::show`](crate::Ply::show) draws with.
+///
+/// Custom elements are passed to `handle_custom_command` to draw. Edges are
+/// smoothed as [`Ply::set_edge_antialiasing`](crate::Ply::set_edge_antialiasing) says.
+pub struct MacroquadRenderer<F> {
+    state: RenderState,
+    edge_antialiasing: EdgeAntialiasing,
+    handle_custom_command: F,
+    measure_text: MeasureText,
+}
 
-                        #[cfg(feature = "tinyvg")]
-                        if is_tvg {
-                            let key = format!(
-                                "tvg:{}:{}:{}:{}:{}:{}:{}:{:?}",
-                                ga.get_name(),
-                                bb.width, bb.height,
-                                cr.top_left, cr.top_right, cr.bottom_left, cr.bottom_right,
-                                current_clip
-                            );
-                            let has_corner_radii = cr.top_left > 0.0 || cr.top_right > 0.0 || cr.bottom_left > 0.0 || cr.bottom_right > 0.0;
-                            let texture = if !has_corner_radii {
-                                // No corner radii — cache the render target to keep its GL texture alive
-                                if let Some(cached) = manager.get(&key) {
-                                    cached
-                                } else {
-                                    match ga {
-                                        GraphicAsset::Path(path) => {
-                                            match load_file(resolve_asset_path(path)).await {
-                                                Ok(tvg_bytes) => {
-                                                    if let Some(tvg_rt) = render_tinyvg_texture(&tvg_bytes, bb.width, bb.height, &current_clip) {
-                                                        manager.cache(key.clone(), tvg_rt)
-                                                    } else {
-                                                        warn!("Failed to load TinyVG image: {}", path);
-                                                        manager.cache(key.clone(), Texture2D::from_rgba8(1, 1, &[0, 0, 0, 0]))
-                                                    }
-                                                }
-                                                Err(error) => {
-                                                    warn!("Failed to load TinyVG file: {}. Error: {}", path, error);
-                                                    manager.cache(key.clone(), Texture2D::from_rgba8(1, 1, &[0, 0, 0, 0]))
-                                                }
-                                            }
-                                        }
-                                        GraphicAsset::Bytes { file_name, data: tvg_bytes } => {
-                                            if let Some(tvg_rt) = render_tinyvg_texture(tvg_bytes, bb.width, bb.height, &current_clip) {
-                                                manager.cache(key.clone(), tvg_rt)
-                                            } else {
-                                                warn!("Failed to load TinyVG image: {}", file_name);
+impl<F> MacroquadRenderer<F> {
+    pub fn new(handle_custom_command: F) -> Self {
+        Self {
+            state: RenderState::new(EdgeAntialiasing::Off),
+            edge_antialiasing: EdgeAntialiasing::Off,
+            handle_custom_command,
+            measure_text: Box::new(create_measure_text_function()),
+        }
+    }
+
+    /// Draws a frame like [`render_with`], for handlers that aren't `Send + Sync`.
+    async fn draw_frame<CustomElementData>(&mut self, commands: &[RenderCommand<CustomElementData>])
+    where
+        CustomElementData: Clone + Default + std::fmt::Debug,
+        F: Fn(&RenderCommand<CustomElementData>),
+    {
+        self.state = RenderState::new(self.edge_antialiasing);
+        for command in commands {
+            draw_command(&mut self.state, command, &self.handle_custom_command).await;
+        }
+        end_frame(&mut self.state);
+    }
+}
+
+impl<CustomElementData, F> Renderer<CustomElementData> for MacroquadRenderer<F>
+where
+    CustomElementData: Clone + Default + std::fmt::Debug + Sync,
+    F: Fn(&RenderCommand<CustomElementData>) + Send + Sync,
+{
+    fn measure_text(&self, text: &str, config: &crate::TextConfig) -> crate::Dimensions {
+        (self.measure_text)(text, config)
+    }
+
+    fn set_edge_antialiasing(&mut self, mode: EdgeAntialiasing) {
+        self.edge_antialiasing = mode;
+    }
+
+    fn begin_frame(&mut self, _dimensions: crate::Dimensions) {
+        self.state = RenderState::new(self.edge_antialiasing);
+    }
+
+    async fn draw_command(&mut self, command: &RenderCommand<CustomElementData>) {
+        draw_command(&mut self.state, command, &self.handle_custom_command).await;
+    }
+
+    fn end_frame(&mut self) {
+        end_frame(&mut self.state);
+    }
+}
+
+/// Draws all render commands to the screen using macroquad, with hard edges.
+pub async fn render<CustomElementData: Clone + Default + std::fmt::Debug>(
+    commands: Vec<RenderCommand<CustomElementData>>,
+    handle_custom_command: impl Fn(&RenderCommand<CustomElementData>),
+) {
+    render_antialiased(commands, EdgeAntialiasing::Off, handle_custom_command).await;
+}
+
+/// Like [`render`], smoothing edges as `edge_antialiasing` says.
+pub async fn render_antialiased<CustomElementData: Clone + Default + std::fmt::Debug>(
+    commands: Vec<RenderCommand<CustomElementData>>,
+    edge_antialiasing: EdgeAntialiasing,
+    handle_custom_command: impl Fn(&RenderCommand<CustomElementData>),
+) {
+    let mut backend = MacroquadRenderer::new(handle_custom_command);
+    backend.edge_antialiasing = edge_antialiasing;
+    backend.draw_frame(&commands).await;
+}
+
+async fn draw_command<CustomElementData: Clone + Default + std::fmt::Debug>(
+    state: &mut RenderState,
+    command: &RenderCommand<CustomElementData>,
+    handle_custom_command: &impl Fn(&RenderCommand<CustomElementData>),
+) {
+    if state.skip_depth > 0 {
+        match command.config {
+            RenderCommandConfig::GroupBegin { .. } => state.skip_depth += 1,
+            RenderCommandConfig::GroupEnd => state.skip_depth -= 1,
+            _ => {}
+        }
+        // The group's own GroupEnd still composites the cached capture
+        if state.skip_depth > 0 || !matches!(command.config, RenderCommandConfig::GroupEnd) {
+            return;
+        }
+    }
+    let current_clip = state.clip_stack.last().copied();
+    if !matches!(command.config, RenderCommandConfig::Rectangle(_) | RenderCommandConfig::Border(_)) {
+        state.shapes.flush();
+    }
+    match &command.config {
+        RenderCommandConfig::Image(image) => {
+            let bb = command.bounding_box;
+            let cr = &image.corner_radii;
+            let mut tint = ply_to_macroquad_color(&image.background_color);
+            if tint == Color::new(0.0, 0.0, 0.0, 0.0) {
+                tint = Color::new(1.0, 1.0, 1.0, 1.0);
+            }
+
+            match &image.data {
+                ImageSource::Texture(tex) => {
+                    // Direct GPU texture — draw immediately, no TextureManager
+                    let has_corner_radii = cr.top_left > 0.0 || cr.top_right > 0.0 || cr.bottom_left > 0.0 || cr.bottom_right > 0.0;
+                    if !has_corner_radii {
+                        draw_texture_ex(
+                            tex,
+                            bb.x,
+                            bb.y,
+                            tint,
+                            DrawTextureParams {
+                                dest_size: Some(Vec2::new(bb.width, bb.height)),
+                                ..Default::default()
+                            },
+                        );
+                    } else {
+                        let mut manager = TEXTURE_MANAGER.lock().unwrap();
+                        // Use texture raw pointer as a unique key for the corner-radii variant
+                        let key = format!(
+                            "tex-proc:{:?}:{}:{}:{}:{}:{}:{}:{:?}:{}",
+                            tex.raw_miniquad_id(),
+                            bb.width, bb.height,
+                            cr.top_left, cr.top_right, cr.bottom_left, cr.bottom_right,
+                            current_clip,
+                            display_scale()
+                        );
+                        let texture = manager.get_or_create(key, || {
+                            let mut resized_image: Image = resize(tex, bb.height, bb.width, &current_clip).get_texture_data();
+                            let rounded_rect: Image = rounded_rectangle_texture(cr, &bb, &current_clip, state.shapes.edge_antialiasing).get_texture_data();
+                            for i in 0..resized_image.bytes.len()/4 {
+                                let this_alpha = resized_image.bytes[i * 4 + 3] as f32 / 255.0;
+                                let mask_alpha = rounded_rect.bytes[i * 4 + 3] as f32 / 255.0;
+                                resized_image.bytes[i * 4 + 3] = (this_alpha * mask_alpha * 255.0) as u8;
+                            }
+                            Texture2D::from_image(&resized_image)
+                        });
+                        draw_texture_ex(
+                            texture,
+                            bb.x,
+                            bb.y,
+                            tint,
+                            DrawTextureParams {
+                                dest_size: Some(Vec2::new(bb.width, bb.height)),
+                                ..Default::default()
+                            },
+                        );
+                    }
+                }
+                #[cfg(feature = "tinyvg")]
+                ImageSource::TinyVg(tvg_image) => {
+                    // Procedural TinyVG — rasterize every frame (no caching, content may change)
+                    let has_corner_radii = cr.top_left > 0.0 || cr.top_right > 0.0 || cr.bottom_left > 0.0 || cr.bottom_right > 0.0;
+                    if let Some(tvg_rt) = render_tinyvg_image(tvg_image, bb.width, bb.height, &current_clip) {
+                        let final_texture = if has_corner_radii {
+                            let mut tvg_img: Image = tvg_rt.texture.get_texture_data();
+                            let rounded_rect: Image = rounded_rectangle_texture(cr, &bb, &current_clip, state.shapes.edge_antialiasing).get_texture_data();
+                            for i in 0..tvg_img.bytes.len()/4 {
+                                let this_alpha = tvg_img.bytes[i * 4 + 3] as f32 / 255.0;
+                                let mask_alpha = rounded_rect.bytes[i * 4 + 3] as f32 / 255.0;
+                                tvg_img.bytes[i * 4 + 3] = (this_alpha * mask_alpha * 255.0) as u8;
+                            }
+                            Texture2D::from_image(&tvg_img)
+                        } else {
+                            tvg_rt.texture.clone()
+                        };
+                        draw_texture_ex(
+                            &final_texture,
+                            bb.x,
+                            bb.y,
+                            tint,
+                            DrawTextureParams {
+                                dest_size: Some(Vec2::new(bb.width, bb.height)),
+                                flip_y: true,
+                                ..Default::default()
+                            },
+                        );
+                    }
+                }
+                ImageSource::Asset(ga) => {
+                    #[cfg(feature = "tinyvg")]
+                    let is_tvg = ga.get_name().to_lowercase().ends_with(".tvg");
+                    #[cfg(not(feature = "tinyvg"))]
+                    let is_tvg = false;
+
+                    // The texture at this size without corner radii, which TinyVG
+                    // images are rendered into
+                    #[cfg(feature = "tinyvg")]
+                    let zerocr_key = format!(
+                        "tvg:{}:{}:{}:{}:{}:{}:{}:{:?}:{}",
+                        ga.get_name(),
+                        bb.width, bb.height,
+                        0.0, 0.0, 0.0, 0.0,
+                        current_clip,
+                        display_scale()
+                    );
+
+                    // Files load before the cache is locked, so no lock is held across an await
+                    let mut path_texture = None;
+                    #[cfg(feature = "tinyvg")]
+                    let mut tvg_file = None;
+                    if let GraphicAsset::Path(path) = ga {
+                        #[cfg(feature = "tinyvg")]
+                        if is_tvg && !TEXTURE_MANAGER.lock().unwrap().textures.contains_key(&zerocr_key) {
+                            tvg_file = Some(load_file(resolve_asset_path(path)).await);
+                        }
+                        if !is_tvg {
+                            path_texture = Some(cached_texture(path).await);
+                        }
+                    }
+                    let mut manager = TEXTURE_MANAGER.lock().unwrap();
+
+                    #[cfg(feature = "tinyvg")]
+                    if is_tvg {
+                        let key = format!(
+                            "tvg:{}:{}:{}:{}:{}:{}:{}:{:?}:{}",
+                            ga.get_name(),
+                            bb.width, bb.height,
+                            cr.top_left, cr.top_right, cr.bottom_left, cr.bottom_right,
+                            current_clip,
+                            display_scale()
+                        );
+                        let has_corner_radii = cr.top_left > 0.0 || cr.top_right > 0.0 || cr.bottom_left > 0.0 || cr.bottom_right > 0.0;
+                        let texture = if !has_corner_radii {
+                            // No corner radii — cache the render target to keep its GL texture alive
+                            if let Some(cached) = manager.get(&key) {
+                                cached
+                            } else {
+                                match ga {
+                                    GraphicAsset::Path(path) => {
+                                        match tvg_file.take().expect("loaded before locking the cache") {
+                                            Ok(tvg_bytes) => {
+                                                if let Some(tvg_rt) = render_tinyvg_texture(&tvg_bytes, bb.width, bb.height, &current_clip) {
+                                                    manager.cache(key.clone(), tvg_rt)
+                                                } else {
+                                                    crate::telemetry::warning!("Failed to load TinyVG image: {}", path);
+                                                    manager.cache(key.clone(), Texture2D::from_rgba8(1, 1, &[0, 0, 0, 0]))
+                                                }
+                                            }
+                                            Err(error) => {
+                                                crate::telemetry::warning!("Failed to load TinyVG file: {}. Error: {}", path, error);
                                                 manager.cache(key.clone(), Texture2D::from_rgba8(1, 1, &[0, 0, 0, 0]))
                                             }
                                         }
                                     }
+                                    GraphicAsset::Bytes { file_name, data: tvg_bytes } => {
+                                        if let Some(tvg_rt) = render_tinyvg_texture(tvg_bytes, bb.width, bb.height, &current_clip) {
+                                            manager.cache(key.clone(), tvg_rt)
+                                        } else {
+                                            crate::telemetry::warning!("Failed to load TinyVG image: {}", file_name);
+                                            manager.cache(key.clone(), Texture2D::from_rgba8(1, 1, &[0, 0, 0, 0]))
+                                        }
+                                    }
                                 }
+                            }
+                        } else {
+                            let base_texture = if let Some(cached) = manager.get(&zerocr_key) {
+                                cached
                             } else {
-                                let zerocr_key = format!(
-                                    "tvg:{}:{}:{}:{}:{}:{}:{}:{:?}",
-                                    ga.get_name(),
-                                    bb.width, bb.height,
-                                    0.0, 0.0, 0.0, 0.0,
-                                    current_clip
-                                );
-                                let base_texture = if let Some(cached) = manager.get(&zerocr_key) {
-                                    cached
-                                } else {
-                                    match ga {
-                                        GraphicAsset::Path(path) => {
-                                            match load_file(resolve_asset_path(path)).await {
-                                                Ok(tvg_bytes) => {
-                                                    if let Some(tvg_rt) = render_tinyvg_texture(&tvg_bytes, bb.width, bb.height, &current_clip) {
-                                                        manager.cache(zerocr_key.clone(), tvg_rt)
-                                                    } else {
-                                                        warn!("Failed to load TinyVG image: {}", path);
-                                                        manager.cache(zerocr_key.clone(), Texture2D::from_rgba8(1, 1, &[0, 0, 0, 0]))
-                                                    }
-                                                }
-                                                Err(error) => {
-                                                    warn!("Failed to load TinyVG file: {}. Error: {}", path, error);
+                                match ga {
+                                    GraphicAsset::Path(path) => {
+                                        match tvg_file.take().expect("loaded before locking the cache") {
+                                            Ok(tvg_bytes) => {
+                                                if let Some(tvg_rt) = render_tinyvg_texture(&tvg_bytes, bb.width, bb.height, &current_clip) {
+                                                    manager.cache(zerocr_key.clone(), tvg_rt)
+                                                } else {
+                                                    crate::telemetry::warning!("Failed to load TinyVG image: {}", path);
                                                     manager.cache(zerocr_key.clone(), Texture2D::from_rgba8(1, 1, &[0, 0, 0, 0]))
                                                 }
                                             }
-                                        }
-                                        GraphicAsset::Bytes { file_name, data: tvg_bytes } => {
-                                            if let Some(tvg_rt) = render_tinyvg_texture(tvg_bytes, bb.width, bb.height, &current_clip) {
-                                                manager.cache(zerocr_key.clone(), tvg_rt)
-                                            } else {
-                                                warn!("Failed to load TinyVG image: {}", file_name);
+                                            Err(error) => {
+                                                crate::telemetry::warning!("Failed to load TinyVG file: {}. Error: {}", path, error);
                                                 manager.cache(zerocr_key.clone(), Texture2D::from_rgba8(1, 1, &[0, 0, 0, 0]))
                                             }
                                         }
                                     }
-                                }.clone();
-                                manager.get_or_create(key, || {
-                                    let mut tvg_image: Image = base_texture.get_texture_data();
-                                    let rounded_rect: Image = rounded_rectangle_texture(cr, &bb, &current_clip).get_texture_data();
-                                    for i in 0..tvg_image.bytes.len()/4 {
-                                        let this_alpha = tvg_image.bytes[i * 4 + 3] as f32 / 255.0;
-                                        let mask_alpha = rounded_rect.bytes[i * 4 + 3] as f32 / 255.0;
-                                        tvg_image.bytes[i * 4 + 3] = (this_alpha * mask_alpha * 255.0) as u8;
+                                    GraphicAsset::Bytes { file_name, data: tvg_bytes } => {
+                                        if let Some(tvg_rt) = render_tinyvg_texture(tvg_bytes, bb.width, bb.height, &current_clip) {
+                                            manager.cache(zerocr_key.clone(), tvg_rt)
+                                        } else {
+                                            crate::telemetry::warning!("Failed to load TinyVG image: {}", file_name);
+                                            manager.cache(zerocr_key.clone(), Texture2D::from_rgba8(1, 1, &[0, 0, 0, 0]))
+                                        }
                                     }
-                                    Texture2D::from_image(&tvg_image)
-                                })
-                            };
-                            draw_texture_ex(
-                                texture,
-                                bb.x,
-                                bb.y,
-                                tint,
-                                DrawTextureParams {
-                                    dest_size: Some(Vec2::new(bb.width, bb.height)),
-                                    flip_y: true,
-                                    ..Default::default()
-                                },
-                            );
-                            continue;
-                        }
-
-                        if !is_tvg && cr.top_left == 0.0 && cr.top_right == 0.0 && cr.bottom_left == 0.0 && cr.bottom_right == 0.0 {
-                            let texture = match ga {
-                                GraphicAsset::Path(path) => manager.get_or_load(path).await,
-                                GraphicAsset::Bytes { file_name, data } => {
-                                    manager.get_or_create(file_name.to_string(), || {
-                                        Texture2D::from_file_with_format(data, None)
-                                    })
-                                }
-                            };
-                            draw_texture_ex(
-                                texture,
-                                bb.x,
-                                bb.y,
-                                tint,
-                                DrawTextureParams {
-                                    dest_size: Some(Vec2::new(bb.width, bb.height)),
-                                    ..Default::default()
-                                },
-                            );
-                        } else {
-                            let source_texture = match ga {
-                                GraphicAsset::Path(path) => manager.get_or_load(path).await.clone(),
-                                GraphicAsset::Bytes { file_name, data } => {
-                                    manager.get_or_create(file_name.to_string(), || {
-                                        Texture2D::from_file_with_format(data, None)
-                                    }).clone()
                                 }
-                            };
-                            let key = format!(
-                                "image:{}:{}:{}:{}:{}:{}:{}:{:?}",
-                                ga.get_name(),
-                                bb.width, bb.height,
-                                cr.top_left, cr.top_right, cr.bottom_left, cr.bottom_right,
-                                current_clip
-                            );
-                            let texture = manager.get_or_create(key, || {
-                                let mut resized_image: Image = resize(&source_texture, bb.height, bb.width, &current_clip).get_texture_data();
-                                let rounded_rect: Image = rounded_rectangle_texture(cr, &bb, &current_clip).get_texture_data();
-                                for i in 0..resized_image.bytes.len()/4 {
-                                    let this_alpha = resized_image.bytes[i * 4 + 3] as f32 / 255.0;
+                            }.clone();
+                            manager.get_or_create(key, || {
+                                let mut tvg_image: Image = base_texture.get_texture_data();
+                                let rounded_rect: Image = rounded_rectangle_texture(cr, &bb, &current_clip, state.shapes.edge_antialiasing).get_texture_data();
+                                for i in 0..tvg_image.bytes.len()/4 {
+                                    let this_alpha = tvg_image.bytes[i * 4 + 3] as f32 / 255.0;
                                     let mask_alpha = rounded_rect.bytes[i * 4 + 3] as f32 / 255.0;
-                                    resized_image.bytes[i * 4 + 3] = (this_alpha * mask_alpha * 255.0) as u8;
+                                    tvg_image.bytes[i * 4 + 3] = (this_alpha * mask_alpha * 255.0) as u8;
                                 }
-                                Texture2D::from_image(&resized_image)
-                            });
-                            draw_texture_ex(
-                                texture,
-                                bb.x,
-                                bb.y,
-                                tint,
-                                DrawTextureParams {
-                                    dest_size: Some(Vec2::new(bb.width, bb.height)),
-                                    ..Default::default()
-                                },
-                            );
-                        }
+                                Texture2D::from_image(&tvg_image)
+                            })
+                        };
+                        draw_texture_ex(
+                            texture,
+                            bb.x,
+                            bb.y,
+                            tint,
+                            DrawTextureParams {
+                                dest_size: Some(Vec2::new(bb.width, bb.height)),
+                                flip_y: true,
+                                ..Default::default()
+                            },
+                        );
+                        return;
                     }
-                }
-            }
-            RenderCommandConfig::Rectangle(config) => {
-                let bb = command.bounding_box;
-                let color = ply_to_macroquad_color(&config.color);
-                let cr = &config.corner_radii;
-
-                // Activate effect material if present (Phase 1: single effect only)
-                let has_effect = !command.effects.is_empty();
-                if has_effect {
-                    let effect = &command.effects[0];
-                    let mut mat_mgr = MATERIAL_MANAGER.lock().unwrap();
-                    let material = mat_mgr.get_or_create(effect);
-                    apply_shader_uniforms(material, effect, &bb);
-                    gl_use_material(material);
-                }
 
-                if let Some(ref sr) = command.shape_rotation {
-                    use crate::math::{classify_angle, AngleType};
-                    let flip_x = sr.flip_x;
-                    let flip_y = sr.flip_y;
-                    match classify_angle(sr.rotation_radians) {
-                        AngleType::Zero => {
-                            // Flips only — remap corner radii
-                            let cr = flip_corner_radii(cr, flip_x, flip_y);
-                            draw_good_rounded_rectangle(bb.x, bb.y, bb.width, bb.height, &cr, color);
-                        }
-                        AngleType::Right90 => {
-                            let cr = rotate_corner_radii_90(&flip_corner_radii(cr, flip_x, flip_y));
-                            draw_good_rounded_rectangle(bb.x, bb.y, bb.width, bb.height, &cr, color);
-                        }
-                        AngleType::Straight180 => {
-                            let cr = rotate_corner_radii_180(&flip_corner_radii(cr, flip_x, flip_y));
-                            draw_good_rounded_rectangle(bb.x, bb.y, bb.width, bb.height, &cr, color);
-                        }
-                        AngleType::Right270 => {
-                            let cr = rotate_corner_radii_270(&flip_corner_radii(cr, flip_x, flip_y));
-                            draw_good_rounded_rectangle(bb.x, bb.y, bb.width, bb.height, &cr, color);
-                        }
-                        AngleType::Arbitrary(theta) => {
-                            draw_good_rotated_rounded_rectangle(
-                                bb.x, bb.y, bb.width, bb.height,
-                                cr, color, theta, flip_x, flip_y,
-                            );
-                        }
+                    if !is_tvg && cr.top_left == 0.0 && cr.top_right == 0.0 && cr.bottom_left == 0.0 && cr.bottom_right == 0.0 {
+                        let texture = match ga {
+                            GraphicAsset::Path(_) => path_texture.as_ref().unwrap(),
+                            GraphicAsset::Bytes { file_name, data } => {
+                                manager.get_or_create(file_name.to_string(), || {
+                                    Texture2D::from_file_with_format(data, None)
+                                })
+                            }
+                        };
+                        draw_texture_ex(
+                            texture,
+                            bb.x,
+                            bb.y,
+                            tint,
+                            DrawTextureParams {
+                                dest_size: Some(Vec2::new(bb.width, bb.height)),
+                                ..Default::default()
+                            },
+                        );
+                    } else {
+                        let source_texture = match ga {
+                            GraphicAsset::Path(_) => path_texture.unwrap(),
+                            GraphicAsset::Bytes { file_name, data } => {
+                                manager.get_or_create(file_name.to_string(), || {
+                                    Texture2D::from_file_with_format(data, None)
+                                }).clone()
+                            }
+                        };
+                        let key = format!(
+                            "image:{}:{}:{}:{}:{}:{}:{}:{:?}:{}",
+                            ga.get_name(),
+                            bb.width, bb.height,
+                            cr.top_left, cr.top_right, cr.bottom_left, cr.bottom_right,
+                            current_clip,
+                            display_scale()
+                        );
+                        let texture = manager.get_or_create(key, || {
+                            let mut resized_image: Image = resize(&source_texture, bb.height, bb.width, &current_clip).get_texture_data();
+                            let rounded_rect: Image = rounded_rectangle_texture(cr, &bb, &current_clip, state.shapes.edge_antialiasing).get_texture_data();
+                            for i in 0..resized_image.bytes.len()/4 {
+                                let this_alpha = resized_image.bytes[i * 4 + 3] as f32 / 255.0;
+                                let mask_alpha = rounded_rect.bytes[i * 4 + 3] as f32 / 255.0;
+                                resized_image.bytes[i * 4 + 3] = (this_alpha * mask_alpha * 255.0) as u8;
+                            }
+                            Texture2D::from_image(&resized_image)
+                        });
+                        draw_texture_ex(
+                            texture,
+                            bb.x,
+                            bb.y,
+                            tint,
+                            DrawTextureParams {
+                                dest_size: Some(Vec2::new(bb.width, bb.height)),
+                                ..Default::default()
+                            },
+                        );
                     }
-                } else if cr.top_left == 0.0 && cr.top_right == 0.0 && cr.bottom_left == 0.0 && cr.bottom_right == 0.0 {
-                    draw_rectangle(
-                        bb.x,
-                        bb.y,
-                        bb.width,
-                        bb.height,
-                        color
-                    );
-                } else {
-                    draw_good_rounded_rectangle(bb.x, bb.y, bb.width, bb.height, cr, color);
                 }
+            }
+        }
+        RenderCommandConfig::Rectangle(config) => {
+            let bb = command.bounding_box;
+            let color = ply_to_macroquad_color(&config.color);
+            let cr = &config.corner_radii;
+
+            // Activate effect material if present (Phase 1: single effect only)
+            let has_effect = !command.effects.is_empty();
+            if has_effect {
+                state.shapes.flush();
+                let effect = &command.effects[0];
+                let mut mat_mgr = MATERIAL_MANAGER.lock().unwrap();
+                let material = mat_mgr.get_or_create(effect);
+                apply_shader_uniforms(material, effect, &bb);
+                gl_use_material(material);
+            }
 
-                // Deactivate effect material
-                if has_effect {
-                    gl_use_default_material();
+            if let Some(ref sr) = command.shape_rotation {
+                use crate::math::{classify_angle, AngleType};
+                let flip_x = sr.flip_x;
+                let flip_y = sr.flip_y;
+                match classify_angle(sr.rotation_radians) {
+                    AngleType::Zero => {
+                        // Flips only — remap corner radii
+                        let cr = flip_corner_radii(cr, flip_x, flip_y);
+                        state.shapes.push_rounded_rectangle(&bb, &cr, color);
+                    }
+                    AngleType::Right90 => {
+                        let cr = rotate_corner_radii_90(&flip_corner_radii(cr, flip_x, flip_y));
+                        state.shapes.push_rounded_rectangle(&bb, &cr, color);
+                    }
+                    AngleType::Straight180 => {
+                        let cr = rotate_corner_radii_180(&flip_corner_radii(cr, flip_x, flip_y));
+                        state.shapes.push_rounded_rectangle(&bb, &cr, color);
+                    }
+                    AngleType::Right270 => {
+                        let cr = rotate_corner_radii_270(&flip_corner_radii(cr, flip_x, flip_y));
+                        state.shapes.push_rounded_rectangle(&bb, &cr, color);
+                    }
+                    AngleType::Arbitrary(theta) => {
+                        state.shapes.flush();
+                        let rotation = crate::engine::ShapeRotationConfig { rotation_radians: theta, flip_x, flip_y };
+                        draw_good_rotated_rounded_rectangle(&bb, cr, color, rotation, state.shapes.edge_antialiasing);
+                    }
                 }
+            } else {
+                state.shapes.push_rounded_rectangle(&bb, cr, color);
             }
-            #[cfg(feature = "text-styling")]
-            RenderCommandConfig::Text(config) => {
-                let bb = command.bounding_box;
-                let font_size = config.font_size as f32;
-                // Ensure font is loaded
-                if let Some(asset) = config.font_asset {
-                    FontManager::ensure(asset).await;
-                }
-                // Hold the FM lock for the duration of text rendering — no clone needed
-                let mut fm = FONT_MANAGER.lock().unwrap();
-                let baseline_y = bb.y + fm.metrics(config.font_size, config.font_asset).baseline_offset;
-                let font = if let Some(asset) = config.font_asset {
-                    fm.get(asset)
-                } else {
-                    fm.get_default()
-                };
-                let default_color = ply_to_macroquad_color(&config.color);
 
-                // Activate effect material if present
-                let has_effect = !command.effects.is_empty();
-                if has_effect {
-                    let effect = &command.effects[0];
-                    let mut mat_mgr = MATERIAL_MANAGER.lock().unwrap();
-                    let material = mat_mgr.get_or_create(effect);
-                    apply_shader_uniforms(material, effect, &bb);
-                    gl_use_material(material);
-                }
+            // Deactivate effect material
+            if has_effect {
+                state.shapes.flush();
+                gl_use_default_material();
+            }
+        }
+        #[cfg(feature = "text-styling")]
+        RenderCommandConfig::Text(config) => {
+            let bb = command.bounding_box;
+            let font_size = config.font_size as f32;
+            // Ensure font is loaded
+            if let Some(asset) = config.font_asset {
+                FontManager::ensure(asset).await;
+            }
+            // Hold the FM lock for the duration of text rendering — no clone needed
+            let mut fm = FONT_MANAGER.lock().unwrap();
+            // SDF glyphs can't be combined with a per-element shader material
+            let sdf_font = if command.effects.is_empty() {
+                config.font_asset.map(|a| a.key()).or(fm.default_key())
+            } else {
+                None
+            };
+            let baseline_y = bb.y + fm.metrics(config.font_size, config.font_asset).baseline_offset;
+            let font = if let Some(asset) = config.font_asset {
+                fm.get(asset)
+            } else {
+                fm.get_default()
+            };
+            let default_color = ply_to_macroquad_color(&config.color);
+
+            // Activate effect material if present
+            let has_effect = !command.effects.is_empty();
+            if has_effect {
+                let effect = &command.effects[0];
+                let mut mat_mgr = MATERIAL_MANAGER.lock().unwrap();
+                let material = mat_mgr.get_or_create(effect);
+                apply_shader_uniforms(material, effect, &bb);
+                gl_use_material(material);
+            }
 
-                let normal_render = || {
-                    let x_scale = compute_letter_spacing_x_scale(
-                        bb.width,
-                        count_visible_chars(&config.text),
-                        config.letter_spacing,
-                    );
-                    draw_text_ex(
+            let normal_render = || {
+                let x_scale = compute_letter_spacing_x_scale(
+                    bb.width,
+                    count_visible_chars(&config.text),
+                    config.letter_spacing,
+                );
+                let draw_at = |dx: f32, dy: f32, color: Color| {
+                    draw_text_run(
                         &config.text,
-                        bb.x,
-                        baseline_y,
+                        bb.x + dx,
+                        baseline_y + dy,
                         TextParams {
                             font_size: config.font_size as u16,
                             font,
                             font_scale: 1.0,
                             font_scale_aspect: x_scale,
                             rotation: 0.0,
-                            color: default_color
-                        }
+                            color
+                        },
+                        sdf_font,
                     );
                 };
-                
-                let mut in_style_def = false;
-                let mut escaped = false;
-                let mut failed = false;
-                
-                let mut text_buffer = String::new();
-                let mut style_buffer = String::new();
-
-                let line = config.text.to_string();
-                let mut segments: Vec<StyledSegment> = Vec::new();
-
-                for c in line.chars() {
-                    if escaped {
-                        if in_style_def {
-                            style_buffer.push(c);
-                        } else {
-                            text_buffer.push(c);
-                        }
-                        escaped = false;
-                        continue;
-                    }
-
-                    match c {
-                        '\\' => {
-                            escaped = true;
-                        }
-                        '{' => {
-                            if in_style_def {
-                                style_buffer.push(c); 
-                            } else {
-                                if !text_buffer.is_empty() {
-                                    segments.push(StyledSegment {
-                                        text: text_buffer.clone(),
-                                        styles: state.style_stack.clone(),
-                                    });
-                                    text_buffer.clear();
-                                }
-                                in_style_def = true;
-                            }
-                        }
-                        '|' => {
-                            if in_style_def {
-                                state.style_stack.push(style_buffer.clone());
-                                style_buffer.clear();
-                                in_style_def = false;
-                            } else {
-                                text_buffer.push(c);
-                            }
-                        }
-                        '}' => {
-                            if in_style_def {
-                                style_buffer.push(c);
-                            } else {
-                                if !text_buffer.is_empty() {
-                                    segments.push(StyledSegment {
-                                        text: text_buffer.clone(),
-                                        styles: state.style_stack.clone(),
-                                    });
-                                    text_buffer.clear();
-                                }
-                                
-                                if state.style_stack.pop().is_none() {
-                                    failed = true;
-                                    break;
-                                }
-                            }
-                        }
-                        _ => {
-                            if in_style_def {
-                                style_buffer.push(c);
-                            } else {
-                                text_buffer.push(c);
-                            }
-                        }
-                    }
-                }
-                if !(failed || in_style_def) {
-                    if !text_buffer.is_empty() {
-                        segments.push(StyledSegment {
-                            text: text_buffer.clone(),
-                            styles: state.style_stack.clone(),
-                        });
-                    }
-                    
+                draw_text_decorations(config.shadow, config.outline, default_color.a, &draw_at);
+                draw_at(0.0, 0.0, default_color);
+            };
+            
+            match parse_styled_line(&config.text, &mut state.style_stack) {
+                Ok(segments) => {
                     let time = get_time();
-                    
+                
                     let cursor_x = std::cell::Cell::new(bb.x);
                     let cursor_y = baseline_y;
                     let mut pending_renders = Vec::new();
-                    
+                
                     let x_scale = compute_letter_spacing_x_scale(
                         bb.width,
                         count_visible_chars(&config.text),
@@ -2066,101 +2831,114 @@ pub async fn render<CustomElementData: Clone + Default + std::fmt::Debug>(
                             &mut state.total_char_index,
                             |text, tr, style_color| {
                                 let text_string = text.to_string();
-                                let text_width = measure_text(&text_string, font, config.font_size as u16, 1.0).width;
-                                
+                                let text_width = measure_text_logical(&text_string, font, config.font_size as u16).width;
+                            
                                 let color = Color::new(style_color.r / 255.0, style_color.g / 255.0, style_color.b / 255.0, style_color.a / 255.0);
                                 let x = cursor_x.get();
-                                
+                            
                                 pending_renders.push((x, text_string, tr, color));
-                                
+                            
                                 cursor_x.set(x + text_width*x_scale);
                             },
                             |text, tr, style_color| {
                                 let text_string = text.to_string();
                                 let color = Color::new(style_color.r / 255.0, style_color.g / 255.0, style_color.b / 255.0, style_color.a / 255.0);
                                 let x = cursor_x.get();
-                                
-                                draw_text_ex(
-                                    &text_string,
-                                    x + tr.x*x_scale,
-                                    cursor_y + tr.y,
-                                    TextParams {
-                                        font_size: config.font_size as u16,
-                                        font,
-                                        font_scale: tr.scale_y.max(0.01),
-                                        font_scale_aspect: if tr.scale_y > 0.01 { tr.scale_x / tr.scale_y * x_scale } else { x_scale },
-                                        rotation: tr.rotation.to_radians(),
-                                        color
-                                    }
-                                );
+                            
+                                let draw_at = |dx: f32, dy: f32, color: Color| {
+                                    draw_text_run(
+                                        &text_string,
+                                        x + tr.x*x_scale + dx,
+                                        cursor_y + tr.y + dy,
+                                        TextParams {
+                                            font_size: config.font_size as u16,
+                                            font,
+                                            font_scale: tr.scale_y.max(0.01),
+                                            font_scale_aspect: if tr.scale_y > 0.01 { tr.scale_x / tr.scale_y * x_scale } else { x_scale },
+                                            rotation: tr.rotation.to_radians(),
+                                            color
+                                        },
+                                        sdf_font,
+                                    );
+                                };
+                                draw_styled_run(&tr, cursor_y + tr.y, config.shadow, config.outline, color, &draw_at);
                             }
                         );
                     }
                     for (x, text_string, tr, color) in pending_renders {
-                        draw_text_ex(
-                            &text_string,
-                            x + tr.x*x_scale,
-                            cursor_y + tr.y,
-                            TextParams {
-                                font_size: config.font_size as u16,
-                                font,
-                                font_scale: tr.scale_y.max(0.01),
-                                font_scale_aspect: if tr.scale_y > 0.01 { tr.scale_x / tr.scale_y * x_scale } else { x_scale },
-                                rotation: tr.rotation.to_radians(),
-                                color
-                            }
-                        );
-                    }
-                } else {
-                    if in_style_def {
-                        warn!("Style definition didn't end! Here is what we tried to render: {}", config.text);
-                    } else if failed {
-                        warn!("Encountered }} without opened style! Make sure to escape curly braces with \\. Here is what we tried to render: {}", config.text);
+                        let draw_at = |dx: f32, dy: f32, color: Color| {
+                            draw_text_run(
+                                &text_string,
+                                x + tr.x*x_scale + dx,
+                                cursor_y + tr.y + dy,
+                                TextParams {
+                                    font_size: config.font_size as u16,
+                                    font,
+                                    font_scale: tr.scale_y.max(0.01),
+                                    font_scale_aspect: if tr.scale_y > 0.01 { tr.scale_x / tr.scale_y * x_scale } else { x_scale },
+                                    rotation: tr.rotation.to_radians(),
+                                    color
+                                },
+                                sdf_font,
+                            );
+                        };
+                        draw_styled_run(&tr, cursor_y + tr.y, config.shadow, config.outline, color, &draw_at);
                     }
+                }
+                Err(error) => {
+                    crate::telemetry::warning!("{} Here is what we tried to render: {}", error, config.text);
                     normal_render();
                 }
+            }
 
-                // Deactivate effect material
-                if has_effect {
-                    gl_use_default_material();
-                }
+            // Deactivate effect material
+            if has_effect {
+                gl_use_default_material();
             }
-            #[cfg(not(feature = "text-styling"))]
-            RenderCommandConfig::Text(config) => {
-                let bb = command.bounding_box;
-                let color = ply_to_macroquad_color(&config.color);
-                // Ensure font is loaded
-                if let Some(asset) = config.font_asset {
-                    FontManager::ensure(asset).await;
-                }
-                // Hold the FM lock for the duration of text rendering — no clone needed
-                let mut fm = FONT_MANAGER.lock().unwrap();
-                let baseline_y = bb.y + fm.metrics(config.font_size, config.font_asset).baseline_offset;
-                let font = if let Some(asset) = config.font_asset {
-                    fm.get(asset)
-                } else {
-                    fm.get_default()
-                };
+        }
+        #[cfg(not(feature = "text-styling"))]
+        RenderCommandConfig::Text(config) => {
+            let bb = command.bounding_box;
+            let color = ply_to_macroquad_color(&config.color);
+            // Ensure font is loaded
+            if let Some(asset) = config.font_asset {
+                FontManager::ensure(asset).await;
+            }
+            // Hold the FM lock for the duration of text rendering — no clone needed
+            let mut fm = FONT_MANAGER.lock().unwrap();
+            // SDF glyphs can't be combined with a per-element shader material
+            let sdf_font = if command.effects.is_empty() {
+                config.font_asset.map(|a| a.key()).or(fm.default_key())
+            } else {
+                None
+            };
+            let baseline_y = bb.y + fm.metrics(config.font_size, config.font_asset).baseline_offset;
+            let font = if let Some(asset) = config.font_asset {
+                fm.get(asset)
+            } else {
+                fm.get_default()
+            };
 
-                // Activate effect material if present
-                let has_effect = !command.effects.is_empty();
-                if has_effect {
-                    let effect = &command.effects[0];
-                    let mut mat_mgr = MATERIAL_MANAGER.lock().unwrap();
-                    let material = mat_mgr.get_or_create(effect);
-                    apply_shader_uniforms(material, effect, &bb);
-                    gl_use_material(material);
-                }
+            // Activate effect material if present
+            let has_effect = !command.effects.is_empty();
+            if has_effect {
+                let effect = &command.effects[0];
+                let mut mat_mgr = MATERIAL_MANAGER.lock().unwrap();
+                let material = mat_mgr.get_or_create(effect);
+                apply_shader_uniforms(material, effect, &bb);
+                gl_use_material(material);
+            }
 
-                let x_scale = compute_letter_spacing_x_scale(
-                    bb.width,
-                    config.text.chars().count(),
-                    config.letter_spacing,
-                );
-                draw_text_ex(
+            let x_scale = compute_letter_spacing_x_scale(
+                bb.width,
+                config.text.chars().count(),
+                config.letter_spacing,
+            );
+            let draw_at = |dx: f32, dy: f32, color: Color| {
+                draw_text_run(
                     &config.text,
-                    bb.x,
-                    baseline_y,
+                    bb.x + dx,
+                    baseline_y + dy,
                     TextParams {
                         font_size: config.font_size as u16,
                         font,
@@ -2168,251 +2946,338 @@ pub async fn render<CustomElementData: Clone + Default + std::fmt::Debug>(
                         font_scale_aspect: x_scale,
                         rotation: 0.0,
                         color
-                    }
+                    },
+                    sdf_font,
                 );
+            };
+            draw_text_decorations(config.shadow, config.outline, color.a, &draw_at);
+            draw_at(0.0, 0.0, color);
 
-                // Deactivate effect material
-                if has_effect {
-                    gl_use_default_material();
-                }
+            // Deactivate effect material
+            if has_effect {
+                gl_use_default_material();
             }
-            RenderCommandConfig::Border(config) => {
-                let bb = command.bounding_box;
-                let bw = &config.width;
-                let cr = &config.corner_radii;
-                let color = ply_to_macroquad_color(&config.color);
-                let s = match config.position {
-                    BorderPosition::Outside => 1.,
-                    BorderPosition::Middle => 0.5,
-                    BorderPosition::Inside => 0.0,
-                };
+        }
+        RenderCommandConfig::Border(config) => {
+            let bb = command.bounding_box;
+            let bw = &config.width;
+            let cr = &config.corner_radii;
+            let color = ply_to_macroquad_color(&config.color);
+            let s = match config.position {
+                BorderPosition::Outside => 1.,
+                BorderPosition::Middle => 0.5,
+                BorderPosition::Inside => 0.0,
+            };
 
-                let get_sides = |corner: f32| {
-                    (std::f32::consts::PI * corner / (2.0 * PIXELS_PER_POINT)).max(5.0) as usize
-                };
-                let v = |x: f32, y: f32| Vertex::new(x, y, 0., 0., 0., color);
-
-                let top = bw.top as f32;
-                let left = bw.left as f32;
-                let bottom = bw.bottom as f32;
-                let right = bw.right as f32;
-                let tl_r = cr.top_left;
-                let tr_r = cr.top_right;
-                let bl_r = cr.bottom_left;
-                let br_r = cr.bottom_right;
-
-                let ox1 = bb.x - left * s;
-                let ox2 = bb.x + bb.width + right * s;
-                let oy1 = bb.y - top * s;
-                let oy2 = bb.y + bb.height + bottom * s;
-                let ix1 = bb.x + left * (1.0 - s);
-                let ix2 = bb.x + bb.width - right * (1.0 - s);
-                let iy1 = bb.y + top * (1.0 - s);
-                let iy2 = bb.y + bb.height - bottom * (1.0 - s);
-
-                let o_tl_rx = tl_r + left * s;
-                let o_tl_ry = tl_r + top * s;
-                let o_tr_rx = tr_r + right * s;
-                let o_tr_ry = tr_r + top * s;
-                let o_bl_rx = bl_r + left * s;
-                let o_bl_ry = bl_r + bottom * s;
-                let o_br_rx = br_r + right * s;
-                let o_br_ry = br_r + bottom * s;
-                let i_tl_rx = (tl_r - left * (1.0 - s)).max(0.0);
-                let i_tl_ry = (tl_r - top * (1.0 - s)).max(0.0);
-                let i_tr_rx = (tr_r - right * (1.0 - s)).max(0.0);
-                let i_tr_ry = (tr_r - top * (1.0 - s)).max(0.0);
-                let i_bl_rx = (bl_r - left * (1.0 - s)).max(0.0);
-                let i_bl_ry = (bl_r - bottom * (1.0 - s)).max(0.0);
-                let i_br_rx = (br_r - right * (1.0 - s)).max(0.0);
-                let i_br_ry = (br_r - bottom * (1.0 - s)).max(0.0);
-
-                let tl_sides = get_sides(o_tl_rx.max(o_tl_ry).max(i_tl_rx).max(i_tl_ry));
-                let tr_sides = get_sides(o_tr_rx.max(o_tr_ry).max(i_tr_rx).max(i_tr_ry));
-                let bl_sides = get_sides(o_bl_rx.max(o_bl_ry).max(i_bl_rx).max(i_bl_ry));
-                let br_sides = get_sides(o_br_rx.max(o_br_ry).max(i_br_rx).max(i_br_ry));
-                let side_count = tl_sides + tr_sides + bl_sides + br_sides;
-
-                let mut vertices = Vec::<Vertex>::with_capacity(16 + side_count * 4);
-                let mut indices = Vec::<u16>::with_capacity(24 + side_count * 6);
-
-                // 4 quads
-                vertices.extend([
-                    // Top edge
-                    v(ox1 + o_tl_rx, oy1),
-                    v(ox2 - o_tr_rx, oy1),
-                    v(ix1 + i_tl_rx, iy1),
-                    v(ix2 - i_tr_rx, iy1),
-                    // Bottom edge
-                    v(ox1 + o_bl_rx, oy2),
-                    v(ox2 - o_br_rx, oy2),
-                    v(ix1 + i_bl_rx, iy2),
-                    v(ix2 - i_br_rx, iy2),
-                    // Left edge
-                    v(ox1, oy1 + o_tl_ry),
-                    v(ox1, oy2 - o_bl_ry),
-                    v(ix1, iy1 + i_tl_ry),
-                    v(ix1, iy2 - i_bl_ry),
-                    // Right edge
-                    v(ox2, oy1 + o_tr_ry),
-                    v(ox2, oy2 - o_br_ry),
-                    v(ix2, iy1 + i_tr_ry),
-                    v(ix2, iy2 - i_br_ry),
+            let get_sides = |corner: f32| {
+                (std::f32::consts::PI * corner / (2.0 * arc_segment_length(state.shapes.edge_antialiasing))).max(5.0) as usize
+            };
+            let feather = edge_feather(state.shapes.edge_antialiasing);
+            let fringe = |x: f32, y: f32| Vertex::new(x, y, 0., 0., 0., Color { a: 0.0, ..color });
+            let v = |x: f32, y: f32| Vertex::new(x, y, 0., 0., 0., color);
+
+            let top = bw.top as f32;
+            let left = bw.left as f32;
+            let bottom = bw.bottom as f32;
+            let right = bw.right as f32;
+            let tl_r = cr.top_left;
+            let tr_r = cr.top_right;
+            let bl_r = cr.bottom_left;
+            let br_r = cr.bottom_right;
+
+            let ox1 = bb.x - left * s;
+            let ox2 = bb.x + bb.width + right * s;
+            let oy1 = bb.y - top * s;
+            let oy2 = bb.y + bb.height + bottom * s;
+            let ix1 = bb.x + left * (1.0 - s);
+            let ix2 = bb.x + bb.width - right * (1.0 - s);
+            let iy1 = bb.y + top * (1.0 - s);
+            let iy2 = bb.y + bb.height - bottom * (1.0 - s);
+
+            let o_tl_rx = tl_r + left * s;
+            let o_tl_ry = tl_r + top * s;
+            let o_tr_rx = tr_r + right * s;
+            let o_tr_ry = tr_r + top * s;
+            let o_bl_rx = bl_r + left * s;
+            let o_bl_ry = bl_r + bottom * s;
+            let o_br_rx = br_r + right * s;
+            let o_br_ry = br_r + bottom * s;
+            let i_tl_rx = (tl_r - left * (1.0 - s)).max(0.0);
+            let i_tl_ry = (tl_r - top * (1.0 - s)).max(0.0);
+            let i_tr_rx = (tr_r - right * (1.0 - s)).max(0.0);
+            let i_tr_ry = (tr_r - top * (1.0 - s)).max(0.0);
+            let i_bl_rx = (bl_r - left * (1.0 - s)).max(0.0);
+            let i_bl_ry = (bl_r - bottom * (1.0 - s)).max(0.0);
+            let i_br_rx = (br_r - right * (1.0 - s)).max(0.0);
+            let i_br_ry = (br_r - bottom * (1.0 - s)).max(0.0);
+
+            let tl_sides = get_sides(o_tl_rx.max(o_tl_ry).max(i_tl_rx).max(i_tl_ry));
+            let tr_sides = get_sides(o_tr_rx.max(o_tr_ry).max(i_tr_rx).max(i_tr_ry));
+            let bl_sides = get_sides(o_bl_rx.max(o_bl_ry).max(i_bl_rx).max(i_bl_ry));
+            let br_sides = get_sides(o_br_rx.max(o_br_ry).max(i_br_rx).max(i_br_ry));
+            let side_count = tl_sides + tr_sides + bl_sides + br_sides;
+
+            // Quads plus a fringe on each side of the corner arcs
+            state.shapes.reserve(16 + side_count * 12, 24 + side_count * 18);
+            let ShapeBatch { vertices, indices, .. } = &mut state.shapes;
+            let base = vertices.len() as u16;
+
+            // 4 quads
+            vertices.extend([
+                // Top edge
+                v(ox1 + o_tl_rx, oy1),
+                v(ox2 - o_tr_rx, oy1),
+                v(ix1 + i_tl_rx, iy1),
+                v(ix2 - i_tr_rx, iy1),
+                // Bottom edge
+                v(ox1 + o_bl_rx, oy2),
+                v(ox2 - o_br_rx, oy2),
+                v(ix1 + i_bl_rx, iy2),
+                v(ix2 - i_br_rx, iy2),
+                // Left edge
+                v(ox1, oy1 + o_tl_ry),
+                v(ox1, oy2 - o_bl_ry),
+                v(ix1, iy1 + i_tl_ry),
+                v(ix1, iy2 - i_bl_ry),
+                // Right edge
+                v(ox2, oy1 + o_tr_ry),
+                v(ox2, oy2 - o_br_ry),
+                v(ix2, iy1 + i_tr_ry),
+                v(ix2, iy2 - i_br_ry),
+            ]);
+            for l in [base, base + 4, base + 8, base + 12] {
+                indices.extend([
+                    l, l + 1, l + 2,
+                    l + 1, l + 3, l + 2
                 ]);
-                for l in [0, 4, 8, 12] {
+            }
+
+            let corners = [
+                (
+                    tl_sides,
+                    PI,
+                    ox1 + o_tl_rx,
+                    oy1 + o_tl_ry,
+                    ix1 + i_tl_rx,
+                    iy1 + i_tl_ry,
+                    o_tl_rx,
+                    o_tl_ry,
+                    i_tl_rx,
+                    i_tl_ry,
+                ),
+                (
+                    tr_sides,
+                    PI * 1.5,
+                    ox2 - o_tr_rx,
+                    oy1 + o_tr_ry,
+                    ix2 - i_tr_rx,
+                    iy1 + i_tr_ry,
+                    o_tr_rx,
+                    o_tr_ry,
+                    i_tr_rx,
+                    i_tr_ry,
+                ),
+                (
+                    bl_sides,
+                    PI * 0.5,
+                    ox1 + o_bl_rx,
+                    oy2 - o_bl_ry,
+                    ix1 + i_bl_rx,
+                    iy2 - i_bl_ry,
+                    o_bl_rx,
+                    o_bl_ry,
+                    i_bl_rx,
+                    i_bl_ry,
+                ),
+                (
+                    br_sides,
+                    0.,
+                    ox2 - o_br_rx,
+                    oy2 - o_br_ry,
+                    ix2 - i_br_rx,
+                    iy2 - i_br_ry,
+                    o_br_rx,
+                    o_br_ry,
+                    i_br_rx,
+                    i_br_ry,
+                ),
+            ];
+
+            for (sides, start, ocx, ocy, icx, icy, o_rx, o_ry, i_rx, i_ry) in corners {
+                let step = (PI / 2.) / (sides as f32);
+
+                for i in 0..sides {
+                    let i = i as f32;
+                    let a1 = start + i * step;
+                    let a2 = a1 + step;
+                    let l = vertices.len() as u16;
+
+                    // quad
+                    vertices.extend([
+                        v(ocx + a1.cos() * o_rx, ocy + a1.sin() * o_ry),
+                        v(ocx + a2.cos() * o_rx, ocy + a2.sin() * o_ry),
+                        v(icx + a1.cos() * i_rx, icy + a1.sin() * i_ry),
+                        v(icx + a2.cos() * i_rx, icy + a2.sin() * i_ry),
+                    ]);
                     indices.extend([
                         l, l + 1, l + 2,
                         l + 1, l + 3, l + 2
                     ]);
-                }
 
-                let corners = [
-                    (
-                        tl_sides,
-                        PI,
-                        ox1 + o_tl_rx,
-                        oy1 + o_tl_ry,
-                        ix1 + i_tl_rx,
-                        iy1 + i_tl_ry,
-                        o_tl_rx,
-                        o_tl_ry,
-                        i_tl_rx,
-                        i_tl_ry,
-                    ),
-                    (
-                        tr_sides,
-                        PI * 1.5,
-                        ox2 - o_tr_rx,
-                        oy1 + o_tr_ry,
-                        ix2 - i_tr_rx,
-                        iy1 + i_tr_ry,
-                        o_tr_rx,
-                        o_tr_ry,
-                        i_tr_rx,
-                        i_tr_ry,
-                    ),
-                    (
-                        bl_sides,
-                        PI * 0.5,
-                        ox1 + o_bl_rx,
-                        oy2 - o_bl_ry,
-                        ix1 + i_bl_rx,
-                        iy2 - i_bl_ry,
-                        o_bl_rx,
-                        o_bl_ry,
-                        i_bl_rx,
-                        i_bl_ry,
-                    ),
-                    (
-                        br_sides,
-                        0.,
-                        ox2 - o_br_rx,
-                        oy2 - o_br_ry,
-                        ix2 - i_br_rx,
-                        iy2 - i_br_ry,
-                        o_br_rx,
-                        o_br_ry,
-                        i_br_rx,
-                        i_br_ry,
-                    ),
-                ];
-
-                for (sides, start, ocx, ocy, icx, icy, o_rx, o_ry, i_rx, i_ry) in corners {
-                    let step = (PI / 2.) / (sides as f32);
-
-                    for i in 0..sides {
-                        let i = i as f32;
-                        let a1 = start + i * step;
-                        let a2 = a1 + step;
-                        let l = vertices.len() as u16;
-
-                        // quad
-                        vertices.extend([
-                            v(ocx + a1.cos() * o_rx, ocy + a1.sin() * o_ry),
-                            v(ocx + a2.cos() * o_rx, ocy + a2.sin() * o_ry),
-                            v(icx + a1.cos() * i_rx, icy + a1.sin() * i_ry),
-                            v(icx + a2.cos() * i_rx, icy + a2.sin() * i_ry),
-                        ]);
-                        indices.extend([
-                            l, l + 1, l + 2,
-                            l + 1, l + 3, l + 2
-                        ]);
+                    if feather > 0.0 {
+                        let (d1, d2) = (Vec2::new(a1.cos(), a1.sin()), Vec2::new(a2.cos(), a2.sin()));
+                        // Fade out beyond the outer arc and before the inner one, where they're round
+                        let arcs = [(l, 1.0, o_rx > 0.0 && o_ry > 0.0), (l + 2, -1.0, i_rx > 0.0 && i_ry > 0.0)];
+                        for (edge, outward, _) in arcs.into_iter().filter(|&(_, _, round)| round) {
+                            let (p1, p2) = (vertices[edge as usize].position, vertices[edge as usize + 1].position);
+                            let f = vertices.len() as u16;
+                            vertices.extend([
+                                fringe(p1.x + d1.x * feather * outward, p1.y + d1.y * feather * outward),
+                                fringe(p2.x + d2.x * feather * outward, p2.y + d2.y * feather * outward),
+                            ]);
+                            indices.extend([
+                                edge, f, f + 1,
+                                edge, f + 1, edge + 1
+                            ]);
+                        }
                     }
                 }
-
-                draw_mesh(&Mesh { vertices, indices, texture: None });
             }
-            RenderCommandConfig::ScissorStart() => {
-                let bb = command.bounding_box;
-                // Layout coordinates are in logical pixels, but macroquad's
-                // quad_gl.scissor() passes values to glScissor which operates
-                // in physical (framebuffer) pixels.  Scale by DPI so the
-                // scissor rectangle matches on high-DPI displays (e.g. WASM).
-                let dpi = miniquad::window::dpi_scale();
-                let next_clip = (
-                    (bb.x * dpi) as i32,
-                    (bb.y * dpi) as i32,
-                    (bb.width * dpi) as i32,
-                    (bb.height * dpi) as i32,
-                );
 
-                let effective_clip = if let Some(parent_clip) = state.clip_stack.last().copied() {
-                    intersect_scissor(parent_clip, next_clip)
-                } else {
-                    next_clip
-                };
+        }
+        RenderCommandConfig::ScissorStart() => {
+            let bb = command.bounding_box;
+            // Layout coordinates are in logical pixels, but macroquad's
+            // quad_gl.scissor() passes values to glScissor which operates
+            // in physical (framebuffer) pixels.  Scale by DPI so the
+            // scissor rectangle matches on high-DPI displays (e.g. WASM).
+            let dpi = miniquad::window::dpi_scale();
+            let next_clip = (
+                (bb.x * dpi) as i32,
+                (bb.y * dpi) as i32,
+                (bb.width * dpi) as i32,
+                (bb.height * dpi) as i32,
+            );
+
+            let effective_clip = if let Some(parent_clip) = state.clip_stack.last().copied() {
+                intersect_scissor(parent_clip, next_clip)
+            } else {
+                next_clip
+            };
 
-                state.clip_stack.push(effective_clip);
+            state.clip_stack.push(effective_clip);
+            unsafe {
+                get_internal_gl().quad_gl.scissor(state.clip_stack.last().copied());
+            }
+        }
+        RenderCommandConfig::ScissorEnd() => {
+            state.clip_stack.pop();
+            unsafe {
+                get_internal_gl().quad_gl.scissor(state.clip_stack.last().copied());
+            }
+        }
+        RenderCommandConfig::Custom(_) => {
+            handle_custom_command(command);
+        }
+        RenderCommandConfig::Canvas(canvas) => {
+            draw_canvas(canvas, &command.bounding_box, command.shape_rotation.as_ref(), current_clip);
+        }
+        RenderCommandConfig::Draw(hook) => {
+            let bb = command.bounding_box;
+            let ctx = crate::canvas::DrawContext {
+                scissor: current_clip,
+                rotation: command.shape_rotation,
+                dpi_scale: miniquad::window::dpi_scale(),
+            };
+            // Shape rotation turns the closure's draw calls about the element's center
+            let rotated = if let Some(ref sr) = command.shape_rotation {
+                let center = vec3(bb.x + bb.width / 2.0, bb.y + bb.height / 2.0, 0.0);
+                let flip = vec3(if sr.flip_x { -1.0 } else { 1.0 }, if sr.flip_y { -1.0 } else { 1.0 }, 1.0);
+                let matrix = Mat4::from_translation(center)
+                    * Mat4::from_rotation_z(sr.rotation_radians)
+                    * Mat4::from_scale(flip)
+                    * Mat4::from_translation(-center);
                 unsafe {
-                    get_internal_gl().quad_gl.scissor(state.clip_stack.last().copied());
+                    get_internal_gl().quad_gl.push_model_matrix(matrix);
                 }
-            }
-            RenderCommandConfig::ScissorEnd() => {
-                state.clip_stack.pop();
+                true
+            } else {
+                false
+            };
+            hook.call(bb, &ctx);
+            if rotated {
                 unsafe {
-                    get_internal_gl().quad_gl.scissor(state.clip_stack.last().copied());
+                    get_internal_gl().quad_gl.pop_model_matrix();
                 }
             }
-            RenderCommandConfig::Custom(_) => {
-                handle_custom_command(&command);
-            }
-            RenderCommandConfig::GroupBegin { ref shader, ref visual_rotation } => {
-                let bb = command.bounding_box;
-                let rt = render_target_msaa(bb.width as u32, bb.height as u32);
-                rt.texture.set_filter(FilterMode::Linear);
-                let cam = Camera2D {
-                    render_target: Some(rt.clone()),
-                    ..Camera2D::from_display_rect(Rect::new(
-                        bb.x, bb.y, bb.width, bb.height,
-                    ))
-                };
-                set_camera(&cam);
-                clear_background(Color::new(0.0, 0.0, 0.0, 0.0));
+        }
+        RenderCommandConfig::GroupBegin { ref shader, ref visual_rotation } => {
+            let bb = command.bounding_box;
+            let capture_key = shader.as_ref().and_then(|shader| shader.capture_key);
+            if let Some(rt) = capture_key.and_then(cached_group_capture) {
+                // Unchanged since it was captured: skip to GroupEnd and draw it again
                 state.rt_stack.push((rt, shader.clone(), *visual_rotation, bb));
+                state.skip_depth = 1;
+                return;
             }
-            RenderCommandConfig::GroupEnd => {
-                if let Some((rt, shader_config, visual_rotation, bb)) = state.rt_stack.pop() {
-                    // Restore previous camera
-                    if let Some((prev_rt, _, _, prev_bb)) = state.rt_stack.last() {
-                        let cam = Camera2D {
-                            render_target: Some(prev_rt.clone()),
-                            ..Camera2D::from_display_rect(Rect::new(
-                                prev_bb.x, prev_bb.y, prev_bb.width, prev_bb.height,
-                            ))
-                        };
-                        set_camera(&cam);
-                    } else {
-                        set_default_camera();
-                    }
+            let rt = scaled_render_target(bb.width, bb.height);
+            rt.texture.set_filter(FilterMode::Linear);
+            let cam = Camera2D {
+                render_target: Some(rt.clone()),
+                ..Camera2D::from_display_rect(Rect::new(
+                    bb.x, bb.y, bb.width, bb.height,
+                ))
+            };
+            set_camera(&cam);
+            clear_background(Color::new(0.0, 0.0, 0.0, 0.0));
+            state.rt_stack.push((rt, shader.clone(), *visual_rotation, bb));
+        }
+        RenderCommandConfig::GroupEnd => {
+            if let Some((rt, shader_config, visual_rotation, bb)) = state.rt_stack.pop() {
+                if let Some(key) = shader_config.as_ref().and_then(|shader| shader.capture_key) {
+                    store_group_capture(key, &rt);
+                }
+                store_silhouette(command.id, &rt.texture, bb);
+                // Restore previous camera
+                if let Some((prev_rt, _, _, prev_bb)) = state.rt_stack.last() {
+                    let cam = Camera2D {
+                        render_target: Some(prev_rt.clone()),
+                        ..Camera2D::from_display_rect(Rect::new(
+                            prev_bb.x, prev_bb.y, prev_bb.width, prev_bb.height,
+                        ))
+                    };
+                    set_camera(&cam);
+                } else {
+                    set_base_camera();
+                }
+
+                // Masked by an element that wasn't drawn yet: nothing shows
+                let mask = match shader_config.as_ref().and_then(|config| config.mask.as_ref()) {
+                    Some(source) => match mask_texture(source, &bb).await {
+                        Some(mask) => Some(mask),
+                        None => return,
+                    },
+                    None => None,
+                };
 
-                    // Apply the shader material if present
-                    if let Some(ref config) = shader_config {
-                        let mut mat_mgr = MATERIAL_MANAGER.lock().unwrap();
-                        let material = mat_mgr.get_or_create(config);
-                        apply_shader_uniforms(material, config, &bb);
-                        gl_use_material(material);
+                // Apply the shader material if present
+                if let Some(ref config) = shader_config {
+                    let mut mat_mgr = MATERIAL_MANAGER.lock().unwrap();
+                    let material = mat_mgr.get_or_create(config);
+                    apply_shader_uniforms(material, config, &bb);
+                    if let Some(capture) = &config.transition {
+                        material.set_texture("ply_previous", transition_previous_frame(capture, &rt.texture));
                     }
+                    if let Some((texture, rect)) = mask {
+                        material.set_texture("ply_mask", texture);
+                        material.set_uniform("ply_mask_rect", rect);
+                    }
+                    gl_use_material(material);
+                }
 
+                if let Some(rot) = visual_rotation.filter(|rot| rot.is_tilted() || rot.is_transformed()) {
+                    draw_projected_group(&rt.texture, &bb, &rot);
+                } else {
                     // Compute draw params — apply visual rotation if present
                     let (rotation, flip_x, flip_y, pivot) = match &visual_rotation {
                         Some(rot) => {
@@ -2440,22 +3305,56 @@ pub async fn render<CustomElementData: Clone + Default + std::fmt::Debug>(
                             ..Default::default()
                         },
                     );
+                }
 
-                    if shader_config.is_some() {
-                        gl_use_default_material();
-                    }
+                if shader_config.is_some() {
+                    gl_use_default_material();
                 }
             }
-            RenderCommandConfig::None() => {}
         }
+        RenderCommandConfig::None() => {}
     }
+}
+
+/// Draws a group's capture onto its transformed, perspective-projected quad.
+/// The quad is split into a grid so the texture follows the perspective
+/// instead of bending along the diagonal.
+fn draw_projected_group(texture: &Texture2D, bb: &BoundingBox, rotation: &crate::engine::VisualRotationConfig) {
+    const CELLS: u16 = 16;
+    let mut mesh = Mesh { vertices: Vec::new(), indices: Vec::new(), texture: Some(texture.clone()) };
+    for row in 0..=CELLS {
+        for column in 0..=CELLS {
+            let (u, v) = (column as f32 / CELLS as f32, row as f32 / CELLS as f32);
+            let (x, y) = rotation.project(bb, (bb.x + u * bb.width, bb.y + v * bb.height));
+            // Render targets are flipped in OpenGL
+            mesh.vertices.push(Vertex::new(x, y, 0.0, u, 1.0 - v, WHITE));
+        }
+    }
+    for row in 0..CELLS {
+        for column in 0..CELLS {
+            let top_left = row * (CELLS + 1) + column;
+            let bottom_left = top_left + CELLS + 1;
+            mesh.indices.extend([top_left, top_left + 1, bottom_left + 1, top_left, bottom_left + 1, bottom_left]);
+        }
+    }
+    draw_mesh(&mesh);
+}
+
+/// Flushes what is still batched and drops resources unused this frame.
+fn end_frame(state: &mut RenderState) {
+    state.shapes.flush();
+    TRANSITION_FRAMES.with(|frames| frames.borrow_mut().retain(|_, entry| std::mem::take(&mut entry.drawn)));
+    GROUP_CAPTURES.with(|captures| captures.borrow_mut().retain(|_, capture| std::mem::take(&mut capture.drawn)));
+    SILHOUETTES.with(|silhouettes| silhouettes.borrow_mut().retain(|_, silhouette| std::mem::take(&mut silhouette.drawn)));
     TEXTURE_MANAGER.lock().unwrap().clean();
     MATERIAL_MANAGER.lock().unwrap().clean();
     FONT_MANAGER.lock().unwrap().clean();
 }
 
+/// Measures text with the loaded fonts. Thread-safe, so `Ply::new` uses it
+/// to measure and wrap text in parallel with the `parallel` feature.
 pub fn create_measure_text_function(
-) -> impl Fn(&str, &crate::TextConfig) -> crate::Dimensions + 'static {
+) -> impl Fn(&str, &crate::TextConfig) -> crate::Dimensions + Send + Sync + 'static {
     move |text: &str, config: &crate::TextConfig| {
         #[cfg(feature = "text-styling")]
         let cleaned_text = {
@@ -2494,31 +3393,37 @@ pub fn create_measure_text_function(
                 }
             }
             if in_style_def {
-                warn!("Ended inside a style definition while cleaning text for measurement! Make sure to escape curly braces with \\. Here is what we tried to measure: {}", text);
+                crate::telemetry::warning!("Ended inside a style definition while cleaning text for measurement! Make sure to escape curly braces with \\. Here is what we tried to measure: {}", text);
             }
             result
         };
         #[cfg(not(feature = "text-styling"))]
         let cleaned_text = text.to_string();
-        let mut fm = FONT_MANAGER.lock().unwrap();
-        // Resolve font: use asset font if available, otherwise default
-        let font = if let Some(asset) = config.font_asset {
-            fm.get(asset)
-        } else {
-            fm.get_default()
+        // Resolve font: use asset font if available, otherwise default. The
+        // lock is let go before measuring, so threads measure side by side
+        let (font, metrics) = {
+            let mut fm = FONT_MANAGER.lock().unwrap();
+            let font = if let Some(asset) = config.font_asset {
+                fm.get(asset).cloned()
+            } else {
+                fm.get_default().cloned()
+            };
+            (font, fm.metrics(config.font_size as u16, config.font_asset))
         };
-        let measured = macroquad::text::measure_text(
-            &cleaned_text,
-            font,
-            config.font_size,
-            1.0,
-        );
-        let metrics = fm.metrics(config.font_size as u16, config.font_asset);
+        let measured = measure_text_logical(&cleaned_text, font.as_ref(), config.font_size);
         let added_space = (cleaned_text.chars().count().max(1) - 1) as f32 * config.letter_spacing as f32;
         crate::Dimensions::new(measured.width + added_space, metrics.height)
     }
 }
 
+/// Baseline callback matching [`create_measure_text_function`]'s line boxes.
+pub fn create_text_baseline_function() -> impl Fn(&crate::TextConfig) -> f32 + 'static {
+    move |config: &crate::TextConfig| {
+        let mut fm = FONT_MANAGER.lock().unwrap();
+        fm.metrics(config.font_size, config.font_asset).baseline_offset
+    }
+}
+
 /// Count visible characters in text, skipping style tag markup.
 /// This handles `{style_name|` openers, `}` closers, and `\` escapes.
 #[cfg(feature = "text-styling")]
@@ -0,0 +1,368 @@
+//! Scripted UI: screens written in [Rhai](https://rhai.rs), loaded at runtime
+//! so modders and designers can change them without rebuilding the game.
+//!
+//! A script defines `fn view(values)`, which returns the elements to draw:
+//!
+//! ```rhai
+//! fn view(values) {
+//!     let play_color = if hovered("play") { "accent" } else { "surface" };
+//!     element(#{ id: "menu", width: "grow", direction: "top_to_bottom", gap: 8 }, [
+//!         text("Score: " + values.score, #{ font_size: 24, color: "text" }),
+//!         element(#{ id: "play", padding: 12, background_color: play_color, on_press: "play" }, [
+//!             text("Play"),
+//!         ]),
+//!         slot("inventory"),
+//!     ])
+//! }
+//! ```
+//!
+//! ```ignore
+//! ui.script_file("mods/menu.rhai", |s| s
+//!     .value("score", score)
+//!     .content("inventory", |ui| draw_inventory(ui, &inventory))
+//!     .on_action(|action| if action == "play" { start_game() })
+//! );
+//! ```
+//!
+//! Scripts build elements with `element(attributes)`,
+//! `element(attributes, children)`, `text(content)`,
+//! `text(content, attributes)` and `slot(name)`. Attributes and their values
+//! are the ones of [templates](crate::template); children may be nested
+//! arrays, and `()` is skipped so `if` without `else` works. `values` is a map
+//! of the values set with [`TemplateBuilder::value`], as strings.
+//!
+//! `hovered(id)`, `pressed(id)`, `just_pressed(id)` and `focused(id)` query
+//! elements the script drew on the previous frame. Runtime errors and scripts
+//! that run too long are reported with a warning and draw nothing.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use rustc_hash::FxHashMap;
+
+use crate::id::Id;
+use crate::template::{check_attributes, draw_nodes, warn_once, Node, TemplateBuilder};
+use crate::watch::WatchedFile;
+use crate::Ui;
+
+/// Operations a single `view` call may run before it is stopped.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Interaction {
+    hovered: bool,
+    pressed: bool,
+    just_pressed: bool,
+    focused: bool,
+}
+
+type Interactions = Rc<RefCell<FxHashMap<String, Interaction>>>;
+type InteractionQuery = fn(&Interaction) -> bool;
+
+/// A compiled UI script, drawn with [`Ui::script`].
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    /// Interaction state of `ids`, read by the query functions.
+    interactions: Interactions,
+    /// Ids of the elements drawn last time.
+    ids: RefCell<Vec<String>>,
+}
+
+impl Script {
+    /// Compiles a script. It must define `fn view(values)`.
+    pub fn compile(source: &str) -> Result<Self, String> {
+        let interactions = Interactions::default();
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        engine.register_fn("element", |attributes: Map| node("element", attributes, Array::new()));
+        engine.register_fn("element", |attributes: Map, children: Array| {
+            node("element", attributes, children)
+        });
+        engine.register_fn("text", |content: &str| text(content, Map::new()));
+        engine.register_fn("text", text);
+        engine.register_fn("slot", |name: &str| {
+            let mut attributes = Map::new();
+            attributes.insert("name".into(), name.into());
+            node("slot", attributes, Array::new())
+        });
+
+        let queries: [(&str, InteractionQuery); 4] = [
+            ("hovered", |state| state.hovered),
+            ("pressed", |state| state.pressed),
+            ("just_pressed", |state| state.just_pressed),
+            ("focused", |state| state.focused),
+        ];
+        for (name, query) in queries {
+            let interactions = interactions.clone();
+            engine.register_fn(name, move |id: &str| {
+                interactions.borrow().get(id).is_some_and(query)
+            });
+        }
+
+        let ast = engine.compile(source).map_err(|e| e.to_string())?;
+        if !ast.iter_functions().any(|f| f.name == "view" && f.params.len() == 1) {
+            return Err("script has no fn view(values)".to_owned());
+        }
+        Ok(Self {
+            engine,
+            ast,
+            interactions,
+            ids: RefCell::default(),
+        })
+    }
+
+    /// Runs `view` and converts what it returned to nodes.
+    fn view<CustomElementData: Clone + Default + std::fmt::Debug>(
+        &self,
+        builder: &TemplateBuilder<'_, CustomElementData>,
+    ) -> Result<Vec<Node>, String> {
+        let values: Map = builder
+            .values()
+            .iter()
+            .map(|(name, value)| (name.as_str().into(), Dynamic::from(value.clone())))
+            .collect();
+        let result = self
+            .engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, "view", (Dynamic::from(values),))
+            .map_err(|e| e.to_string())?;
+        let mut nodes = Vec::new();
+        to_nodes(result, &mut nodes)?;
+        Ok(nodes)
+    }
+}
+
+fn node(tag: &str, attributes: Map, children: Array) -> Map {
+    let mut node = Map::new();
+    node.insert("tag".into(), tag.into());
+    node.insert("attributes".into(), attributes.into());
+    node.insert("children".into(), children.into());
+    node
+}
+
+fn text(content: &str, attributes: Map) -> Map {
+    let mut node = node("text", attributes, Array::new());
+    node.insert("content".into(), content.into());
+    node
+}
+
+/// Escapes braces, since script strings are never placeholders.
+fn literal(value: &Dynamic) -> String {
+    value.to_string().replace('{', "{{").replace('}', "}}")
+}
+
+fn to_nodes(value: Dynamic, nodes: &mut Vec<Node>) -> Result<(), String> {
+    if value.is_unit() {
+        return Ok(());
+    }
+    if value.is_array() {
+        for item in value.cast::<Array>() {
+            to_nodes(item, nodes)?;
+        }
+        return Ok(());
+    }
+    if !value.is_map() {
+        return Err(format!("view returned a {} instead of an element", value.type_name()));
+    }
+
+    let mut map = value.cast::<Map>();
+    let tag = map.remove("tag").map(|tag| tag.to_string()).unwrap_or_default();
+    let attributes: Vec<(String, String)> = map
+        .remove("attributes")
+        .and_then(|attributes| attributes.try_cast::<Map>())
+        .unwrap_or_default()
+        .iter()
+        .map(|(name, value)| (name.to_string(), literal(value)))
+        .collect();
+    check_attributes(&tag, &attributes)?;
+
+    nodes.push(match tag.as_str() {
+        "element" => {
+            let mut children = Vec::new();
+            if let Some(value) = map.remove("children") {
+                to_nodes(value, &mut children)?;
+            }
+            Node::Element { attributes, children }
+        }
+        "text" => Node::Text {
+            attributes,
+            content: map.get("content").map(literal).unwrap_or_default(),
+        },
+        _ => Node::Slot {
+            name: attributes.into_iter().next().map(|(_, name)| name).unwrap_or_default(),
+        },
+    });
+    Ok(())
+}
+
+fn collect_ids<CustomElementData: Clone + Default + std::fmt::Debug>(
+    nodes: &[Node],
+    builder: &TemplateBuilder<'_, CustomElementData>,
+    ids: &mut Vec<String>,
+) {
+    for node in nodes {
+        if let Node::Element { attributes, children } = node {
+            if let Some((_, id)) = attributes.iter().find(|(name, _)| name == "id") {
+                ids.push(builder.fill(id));
+            }
+            collect_ids(children, builder, ids);
+        }
+    }
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Runs a script's `view` and draws the elements it returned, with
+    /// values, content and callbacks for its slots.
+    pub fn script<'f>(
+        &mut self,
+        script: &Script,
+        f: impl for<'a> FnOnce(&'a mut TemplateBuilder<'f, CustomElementData>) -> &'a mut TemplateBuilder<'f, CustomElementData>,
+    ) {
+        let mut builder = TemplateBuilder::new();
+        f(&mut builder);
+
+        {
+            let focused = self.focused_element().map(|id| id.id);
+            let mut interactions = script.interactions.borrow_mut();
+            interactions.clear();
+            for name in script.ids.borrow().iter() {
+                let id = Id::from((name.as_str(), 0));
+                let interaction = Interaction {
+                    hovered: self.pointer_over(id.clone()),
+                    pressed: self.is_pressed(id.clone()),
+                    just_pressed: self.is_just_pressed(id.clone()),
+                    focused: focused == Some(id.id),
+                };
+                interactions.insert(name.clone(), interaction);
+            }
+        }
+
+        match script.view(&builder) {
+            Ok(nodes) => {
+                let mut ids = Vec::new();
+                collect_ids(&nodes, &builder, &mut ids);
+                *script.ids.borrow_mut() = ids;
+                draw_nodes(self, &nodes, &mut builder);
+            }
+            Err(e) => warn_once(format!("Script error: {e}")),
+        }
+    }
+
+    /// Runs the script in a file, recompiling it whenever it changes.
+    ///
+    /// Draws nothing until the file has been read, which on the web takes a
+    /// frame or two. A file that fails to compile is reported with a warning
+    /// and the previous version is kept.
+    pub fn script_file<'f>(
+        &mut self,
+        path: &str,
+        f: impl for<'a> FnOnce(&'a mut TemplateBuilder<'f, CustomElementData>) -> &'a mut TemplateBuilder<'f, CustomElementData>,
+    ) {
+        let now = self.ply.context.current_time;
+        if let Some(script) = self.ply.script_files.get(path, now) {
+            self.script(&script, f);
+        }
+    }
+}
+
+struct WatchedScript {
+    file: WatchedFile,
+    script: Option<Rc<Script>>,
+}
+
+impl WatchedScript {
+    fn poll(&mut self, now: f64) {
+        let Some(contents) = self.file.poll(now) else {
+            return;
+        };
+        match Script::compile(&String::from_utf8_lossy(contents)) {
+            Ok(script) => self.script = Some(Rc::new(script)),
+            Err(e) => {
+                crate::telemetry::warning!("Failed to compile script {}: {}", self.file.path(), e);
+            }
+        }
+    }
+}
+
+/// Script files run with `script_file`, kept by [`Ply`](crate::Ply).
+#[derive(Default)]
+pub(crate) struct ScriptFiles {
+    files: FxHashMap<String, WatchedScript>,
+}
+
+impl ScriptFiles {
+    /// Recompiles the files that changed. Called from `begin()`.
+    pub(crate) fn update(&mut self, now: f64) {
+        for file in self.files.values_mut() {
+            file.poll(now);
+        }
+    }
+
+    /// Returns the script in a file, starting to watch it on first use.
+    fn get(&mut self, path: &str, now: f64) -> Option<Rc<Script>> {
+        let file = self.files.entry(path.to_owned()).or_insert_with(|| {
+            let mut file = WatchedScript {
+                file: WatchedFile::new(path),
+                script: None,
+            };
+            file.poll(now);
+            file
+        });
+        file.script.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::math::{Dimensions, Vector2};
+    use crate::render_commands::RenderCommandConfig;
+    use crate::Ply;
+
+    #[test]
+    fn test_script_draws_elements_and_queries_interaction() {
+        let script = Script::compile(
+            r##"
+            fn view(values) {
+                let color = if hovered("panel") { "#00FF00" } else { values.color };
+                element(#{ id: "panel", width: "grow", height: 100, background_color: color }, [
+                    text("Hello {" + values.name + "}", #{ font_size: 10 }),
+                    if false { text("hidden") },
+                ])
+            }
+            "##,
+        )
+        .unwrap();
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 200.0));
+
+        let draw = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.script(&script, |s| s.value("color", "#FF0000").value("name", "Ada"));
+            ui.eval()
+        };
+
+        let items = draw(&mut ply);
+        assert_eq!(items.len(), 2);
+        match &items[0].config {
+            RenderCommandConfig::Rectangle(rect) => assert_eq!(rect.color, Color::u_rgb(255, 0, 0)),
+            other => panic!("Expected a rectangle, got {:?}", other),
+        }
+        match &items[1].config {
+            RenderCommandConfig::Text(text) => assert_eq!(text.text, "Hello {Ada}"),
+            other => panic!("Expected text, got {:?}", other),
+        }
+
+        ply.context.set_pointer_state(Vector2::new(50.0, 50.0), false);
+        let items = draw(&mut ply);
+        match &items[0].config {
+            RenderCommandConfig::Rectangle(rect) => assert_eq!(rect.color, Color::u_rgb(0, 255, 0)),
+            other => panic!("Expected a rectangle, got {:?}", other),
+        }
+
+        assert!(Script::compile("fn other() {}").is_err());
+        assert!(Script::compile("fn view(values) {").is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! Signed-distance-field text rendering (feature: `sdf-text`).
+//!
+//! Glyphs are rasterized once at [`SDF_BASE_SIZE`] with fontdue, converted to
+//! distance fields and packed into a per-font atlas. Drawing scales the glyph
+//! quads and resolves the edge in a shader, so text stays sharp under
+//! `rotate_visual`, scaled group shaders and fractional DPI scales.
+//!
+//! The renderer uses this automatically for every font loaded through
+//! [`FontManager`](crate::renderer::FontManager) when the feature is enabled.
+//! Text with a per-element shader effect keeps using rasterized glyphs.
+
+use std::sync::{LazyLock, Mutex};
+
+use macroquad::miniquad::{BlendFactor, BlendState, BlendValue, Equation};
+use macroquad::prelude::*;
+use rustc_hash::FxHashMap;
+
+use crate::renderer::{resolve_asset_path, FontAsset};
+
+/// Pixel size glyphs are rasterized at before conversion to distance fields.
+pub const SDF_BASE_SIZE: f32 = 48.0;
+
+/// Distance in base pixels covered by the field on each side of an edge.
+const SDF_SPREAD: usize = 6;
+
+/// Width and height of each font's glyph atlas.
+const ATLAS_SIZE: u16 = 1024;
+
+const SDF_VERTEX_SHADER: &str = "#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+varying lowp vec2 uv;
+varying lowp vec4 color;
+uniform mat4 Model;
+uniform mat4 Projection;
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    color = color0 / 255.0;
+    uv = texcoord;
+}
+";
+
+const SDF_FRAGMENT_SHADER: &str = "#version 100
+precision mediump float;
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+uniform float Smoothing;
+void main() {
+    float distance = texture2D(Texture, uv).a;
+    float alpha = smoothstep(0.5 - Smoothing, 0.5 + Smoothing, distance);
+    gl_FragColor = vec4(color.rgb, color.a * alpha);
+}
+";
+
+/// Global SDF glyph cache.
+pub static SDF_TEXT_CACHE: LazyLock<Mutex<SdfTextCache>> =
+    LazyLock::new(|| Mutex::new(SdfTextCache::new()));
+
+#[derive(Debug, Clone, Copy)]
+struct SdfGlyph {
+    /// Pixel rect in the atlas. Empty for glyphs without a bitmap (spaces).
+    source: Rect,
+    /// Top-left of the padded bitmap relative to the pen position on the baseline.
+    offset_x: f32,
+    offset_y: f32,
+    advance: f32,
+}
+
+struct SdfFont {
+    font: fontdue::Font,
+    atlas: Image,
+    texture: Option<Texture2D>,
+    dirty: bool,
+    glyphs: FxHashMap<char, SdfGlyph>,
+    cursor_x: u16,
+    cursor_y: u16,
+    row_height: u16,
+    /// Bumped whenever the full atlas starts over.
+    generation: u32,
+}
+
+impl SdfFont {
+    fn new(font: fontdue::Font) -> Self {
+        Self {
+            font,
+            atlas: Image::gen_image_color(ATLAS_SIZE, ATLAS_SIZE, Color::new(1.0, 1.0, 1.0, 0.0)),
+            texture: None,
+            dirty: false,
+            glyphs: FxHashMap::default(),
+            cursor_x: 0,
+            cursor_y: 0,
+            row_height: 0,
+            generation: 0,
+        }
+    }
+
+    fn glyph(&mut self, c: char) -> SdfGlyph {
+        if let Some(glyph) = self.glyphs.get(&c) {
+            return *glyph;
+        }
+        let (metrics, field, width, height) = rasterize_sdf(&self.font, c);
+        let mut glyph = SdfGlyph {
+            source: Rect::default(),
+            offset_x: metrics.xmin as f32 - SDF_SPREAD as f32,
+            offset_y: -(metrics.ymin as f32 + metrics.height as f32) - SDF_SPREAD as f32,
+            advance: metrics.advance_width,
+        };
+        if metrics.width > 0 && metrics.height > 0 {
+            let (x, y) = self.allocate(width as u16, height as u16);
+            for row in 0..height {
+                for col in 0..width {
+                    let i = ((y as usize + row) * ATLAS_SIZE as usize + x as usize + col) * 4;
+                    self.atlas.bytes[i..i + 4].copy_from_slice(&[255, 255, 255, field[row * width + col]]);
+                }
+            }
+            glyph.source = Rect::new(x as f32, y as f32, width as f32, height as f32);
+            self.dirty = true;
+        }
+        self.glyphs.insert(c, glyph);
+        glyph
+    }
+
+    /// Shelf-packs a `width` x `height` region. Starts over when the atlas is full.
+    fn allocate(&mut self, width: u16, height: u16) -> (u16, u16) {
+        if self.cursor_x + width > ATLAS_SIZE {
+            self.cursor_x = 0;
+            self.cursor_y += self.row_height;
+            self.row_height = 0;
+        }
+        if self.cursor_y + height > ATLAS_SIZE {
+            self.glyphs.clear();
+            self.atlas.bytes.chunks_exact_mut(4).for_each(|px| px[3] = 0);
+            self.cursor_x = 0;
+            self.cursor_y = 0;
+            self.row_height = 0;
+            self.generation += 1;
+        }
+        let position = (self.cursor_x, self.cursor_y);
+        self.cursor_x += width;
+        self.row_height = self.row_height.max(height);
+        position
+    }
+
+    fn texture(&mut self) -> &Texture2D {
+        let texture = self.texture.get_or_insert_with(|| {
+            let texture = Texture2D::from_image(&self.atlas);
+            texture.set_filter(FilterMode::Linear);
+            texture
+        });
+        if self.dirty {
+            texture.update(&self.atlas);
+            self.dirty = false;
+        }
+        texture
+    }
+}
+
+/// Rasterizes `c` and converts its coverage to a distance field padded by
+/// [`SDF_SPREAD`]. Returns the metrics, the field and its dimensions.
+fn rasterize_sdf(font: &fontdue::Font, c: char) -> (fontdue::Metrics, Vec<u8>, usize, usize) {
+    let (metrics, coverage) = font.rasterize(c, SDF_BASE_SIZE);
+    let spread = SDF_SPREAD as isize;
+    let width = metrics.width + 2 * SDF_SPREAD;
+    let height = metrics.height + 2 * SDF_SPREAD;
+    let inside = |x: isize, y: isize| {
+        let (gx, gy) = (x - spread, y - spread);
+        gx >= 0
+            && gy >= 0
+            && (gx as usize) < metrics.width
+            && (gy as usize) < metrics.height
+            && coverage[gy as usize * metrics.width + gx as usize] >= 128
+    };
+
+    let mut field = vec![0u8; width * height];
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let is_inside = inside(x, y);
+            let mut nearest_sq = (spread * spread) as f32;
+            for dy in -spread..=spread {
+                for dx in -spread..=spread {
+                    if inside(x + dx, y + dy) != is_inside {
+                        nearest_sq = nearest_sq.min((dx * dx + dy * dy) as f32);
+                    }
+                }
+            }
+            // The edge lies halfway between the pixel centers
+            let distance = (nearest_sq.sqrt() - 0.5).max(0.0);
+            let signed = if is_inside { distance } else { -distance };
+            let value = 0.5 + signed / (2.0 * SDF_SPREAD as f32);
+            field[y as usize * width + x as usize] = (value.clamp(0.0, 1.0) * 255.0) as u8;
+        }
+    }
+    (metrics, field, width, height)
+}
+
+/// Per-font SDF atlases plus the shared SDF material.
+pub struct SdfTextCache {
+    fonts: FxHashMap<&'static str, SdfFont>,
+    material: Option<Material>,
+}
+
+impl SdfTextCache {
+    fn new() -> Self {
+        Self {
+            fonts: FxHashMap::default(),
+            material: None,
+        }
+    }
+
+    /// Registers font bytes under `key`. No-op if the key is already loaded.
+    pub fn load(&mut self, key: &'static str, bytes: &[u8]) {
+        if self.fonts.contains_key(key) {
+            return;
+        }
+        match fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default()) {
+            Ok(font) => {
+                self.fonts.insert(key, SdfFont::new(font));
+            }
+            Err(e) => crate::telemetry::warning!("Failed to load SDF font '{}': {}", key, e),
+        }
+    }
+
+    /// Returns true if an SDF atlas exists for `key`.
+    pub fn is_loaded(&self, key: &str) -> bool {
+        self.fonts.contains_key(key)
+    }
+
+    fn material(&mut self) -> &Material {
+        self.material.get_or_insert_with(|| {
+            let blend = BlendState::new(
+                Equation::Add,
+                BlendFactor::Value(BlendValue::SourceAlpha),
+                BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+            );
+            load_material(
+                ShaderSource::Glsl {
+                    vertex: SDF_VERTEX_SHADER,
+                    fragment: SDF_FRAGMENT_SHADER,
+                },
+                MaterialParams {
+                    pipeline_params: PipelineParams {
+                        color_blend: Some(blend),
+                        alpha_blend: Some(blend),
+                        ..Default::default()
+                    },
+                    uniforms: vec![UniformDesc::new("Smoothing", UniformType::Float1)],
+                    ..Default::default()
+                },
+            )
+            .expect("Failed to compile SDF text shader")
+        })
+    }
+
+    /// Draws `text` with its baseline starting at (`x`, `y`), honoring the size,
+    /// scale, aspect, rotation and color in `params`. Returns false if no SDF
+    /// atlas is loaded for `key`, so the caller can fall back.
+    pub fn draw_text(&mut self, key: &str, text: &str, x: f32, y: f32, params: &TextParams) -> bool {
+        if !self.fonts.contains_key(key) {
+            return false;
+        }
+        let scale = params.font_size as f32 * params.font_scale / SDF_BASE_SIZE;
+        let scale_x = scale * params.font_scale_aspect;
+        // One screen pixel expressed in normalized distance units
+        let smoothing = (1.0 / (2.0 * SDF_SPREAD as f32 * scale.max(0.01))).min(0.5);
+
+        let material = self.material().clone();
+        material.set_uniform("Smoothing", smoothing);
+
+        let font = self.fonts.get_mut(key).unwrap();
+        // Filling the atlas midway drops the string's earlier glyphs, so they're
+        // collected again into the fresh atlas, once
+        let mut glyphs: Vec<SdfGlyph> = Vec::new();
+        for _ in 0..2 {
+            let generation = font.generation;
+            glyphs.clear();
+            glyphs.extend(text.chars().map(|c| font.glyph(c)));
+            if font.generation == generation {
+                break;
+            }
+        }
+        let texture = font.texture().clone();
+
+        gl_use_material(&material);
+        let mut pen = 0.0;
+        for glyph in glyphs {
+            if glyph.source.w > 0.0 {
+                draw_texture_ex(
+                    &texture,
+                    x + (pen + glyph.offset_x) * scale_x,
+                    y + glyph.offset_y * scale,
+                    params.color,
+                    DrawTextureParams {
+                        dest_size: Some(vec2(glyph.source.w * scale_x, glyph.source.h * scale)),
+                        source: Some(glyph.source),
+                        rotation: params.rotation,
+                        pivot: Some(vec2(x, y)),
+                        ..Default::default()
+                    },
+                );
+            }
+            pen += glyph.advance;
+        }
+        gl_use_default_material();
+        true
+    }
+}
+
+/// Loads the font's bytes into the SDF cache, reading the file for path assets.
+pub async fn register_font(asset: &'static FontAsset) {
+    if SDF_TEXT_CACHE.lock().unwrap().is_loaded(asset.key()) {
+        return;
+    }
+    match asset {
+        FontAsset::Bytes { data, .. } => SDF_TEXT_CACHE.lock().unwrap().load(asset.key(), data),
+        FontAsset::Path(path) => match load_file(resolve_asset_path(path)).await {
+            Ok(bytes) => SDF_TEXT_CACHE.lock().unwrap().load(asset.key(), &bytes),
+            Err(e) => crate::telemetry::warning!("Failed to read SDF font '{}': {:?}", path, e),
+        },
+    }
+}
@@ -0,0 +1,292 @@
+//! Search field composite: a text input with a leading icon slot, a clear
+//! button, Escape-to-clear and debounced search events.
+//!
+//! ```ignore
+//! ui.search_input(|s| s
+//!     .id("search")
+//!     .placeholder("Search files")
+//!     .leading(|ui| ui.text("🔍", |t| t.font_size(16)))
+//!     .debounce(0.3)
+//!     .on_search(|query| results = run_search(query))
+//! );
+//! let query = ply.get_text_value("search");
+//! ```
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::id::Id;
+use crate::layout::{LayoutDirection, Sizing};
+use crate::renderer::FontAsset;
+use crate::Ui;
+
+/// Delay in seconds between the last edit and `on_search`, when none is set.
+pub const DEFAULT_DEBOUNCE: f64 = 0.3;
+
+/// Per-input state kept between frames.
+#[derive(Debug, Default)]
+struct SearchState {
+    /// Text seen on the previous frame.
+    text: String,
+    /// When the text last changed, while a search is pending.
+    pending_since: Option<f64>,
+    /// Set by Enter or the clear button to search on the next frame.
+    flush: Rc<Cell<bool>>,
+}
+
+/// Declares what goes before the text field.
+type LeadingFn<'f, CustomElementData> = Box<dyn FnOnce(&mut Ui<'_, CustomElementData>) + 'f>;
+/// Called with the query when a search is due.
+type SearchFn<'f> = Box<dyn FnMut(&str) + 'f>;
+
+/// Builder for configuring a search field via closure.
+pub struct SearchInputBuilder<'f, CustomElementData: Clone + Default + std::fmt::Debug = ()> {
+    pub(crate) id: Id,
+    pub(crate) placeholder: String,
+    pub(crate) debounce: f64,
+    pub(crate) font_asset: Option<&'static FontAsset>,
+    pub(crate) font_size: u16,
+    pub(crate) padding: u16,
+    pub(crate) width: Sizing,
+    pub(crate) corner_radius: f32,
+    pub(crate) background_color: Color,
+    pub(crate) text_color: Color,
+    pub(crate) placeholder_color: Color,
+    pub(crate) clear_button_color: Color,
+    pub(crate) leading: Option<LeadingFn<'f, CustomElementData>>,
+    pub(crate) on_search: Option<SearchFn<'f>>,
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> Default
+    for SearchInputBuilder<'_, CustomElementData>
+{
+    fn default() -> Self {
+        Self {
+            id: Id::new("search_input"),
+            placeholder: "Search".to_string(),
+            debounce: DEFAULT_DEBOUNCE,
+            font_asset: None,
+            font_size: 16,
+            padding: 8,
+            width: Sizing::Grow(0.0, f32::MAX, 1.0),
+            corner_radius: 6.0,
+            background_color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+            text_color: Color::rgba(255.0, 255.0, 255.0, 255.0),
+            placeholder_color: Color::rgba(128.0, 128.0, 128.0, 255.0),
+            clear_button_color: Color::rgba(160.0, 160.0, 160.0, 255.0),
+            leading: None,
+            on_search: None,
+        }
+    }
+}
+
+impl<'f, CustomElementData: Clone + Default + std::fmt::Debug> SearchInputBuilder<'f, CustomElementData> {
+    /// Sets the ID of the inner text input, used with `get_text_value` and `set_focus`.
+    /// Defaults to `"search_input"`, so set it when there is more than one search field.
+    #[inline]
+    pub fn id(&mut self, id: impl Into<Id>) -> &mut Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Sets the placeholder text. Defaults to `"Search"`.
+    #[inline]
+    pub fn placeholder(&mut self, text: &str) -> &mut Self {
+        self.placeholder = text.to_string();
+        self
+    }
+
+    /// Sets how long the text must stay unchanged, in seconds, before `on_search` fires.
+    #[inline]
+    pub fn debounce(&mut self, seconds: f64) -> &mut Self {
+        self.debounce = seconds.max(0.0);
+        self
+    }
+
+    /// Registers a callback fired with the query once typing pauses for the
+    /// debounce time, and right away on Enter or when the field is cleared.
+    #[inline]
+    pub fn on_search(&mut self, callback: impl FnMut(&str) + 'f) -> &mut Self {
+        self.on_search = Some(Box::new(callback));
+        self
+    }
+
+    /// Declares content shown before the input, such as a magnifier icon.
+    #[inline]
+    pub fn leading(&mut self, content: impl FnOnce(&mut Ui<'_, CustomElementData>) + 'f) -> &mut Self {
+        self.leading = Some(Box::new(content));
+        self
+    }
+
+    /// Sets the font asset.
+    #[inline]
+    pub fn font(&mut self, asset: &'static FontAsset) -> &mut Self {
+        self.font_asset = Some(asset);
+        self
+    }
+
+    /// Sets the font size. Defaults to 16.
+    #[inline]
+    pub fn font_size(&mut self, size: u16) -> &mut Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Sets the padding around and gap between the icon, input and clear button. Defaults to 8.
+    #[inline]
+    pub fn padding(&mut self, padding: u16) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the width of the field. Defaults to growing.
+    #[inline]
+    pub fn width(&mut self, width: Sizing) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the corner radius of the field. Defaults to 6.
+    #[inline]
+    pub fn corner_radius(&mut self, radius: f32) -> &mut Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Sets the background color of the field.
+    #[inline]
+    pub fn background_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.background_color = color.into();
+        self
+    }
+
+    /// Sets the color of the query text.
+    #[inline]
+    pub fn text_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.text_color = color.into();
+        self
+    }
+
+    /// Sets the color of the placeholder text.
+    #[inline]
+    pub fn placeholder_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.placeholder_color = color.into();
+        self
+    }
+
+    /// Sets the color of the clear ("×") button.
+    #[inline]
+    pub fn clear_button_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.clear_button_color = color.into();
+        self
+    }
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Adds a search field: a text input with an optional leading icon, a clear
+    /// button while it has text, Escape-to-clear and a debounced `on_search`.
+    ///
+    /// Returns the ID of the inner text input.
+    ///
+    /// ```ignore
+    /// ui.search_input(|s| s.id("search").debounce(0.3).on_search(|q| println!("{q}")));
+    /// ```
+    pub fn search_input<'f>(
+        &mut self,
+        config_fn: impl for<'a> FnOnce(
+            &'a mut SearchInputBuilder<'f, CustomElementData>,
+        ) -> &'a mut SearchInputBuilder<'f, CustomElementData>,
+    ) -> Id {
+        let mut config = SearchInputBuilder::default();
+        config_fn(&mut config);
+        let SearchInputBuilder {
+            id,
+            placeholder,
+            debounce,
+            font_asset,
+            font_size,
+            padding,
+            width,
+            corner_radius,
+            background_color,
+            text_color,
+            placeholder_color,
+            clear_button_color,
+            leading,
+            mut on_search,
+        } = config;
+
+        let input_id = id.id;
+        let now = self.ply.context.current_time;
+        let text = self.ply.context.get_text_value(input_id).to_string();
+        let state = self.ply.component_states.widget_state(input_id, SearchState::default);
+        if state.text != text {
+            state.text = text.clone();
+            state.pending_since = Some(now);
+        }
+        let flush = state.flush.clone();
+        if flush.take() || state.pending_since.is_some_and(|since| now - since >= debounce) {
+            state.pending_since = None;
+            if let Some(ref mut callback) = on_search {
+                callback(&text);
+            }
+        }
+
+        let input_height = (font_size as f32 * 1.5).round();
+        self.element()
+            .width(width)
+            .background_color(background_color)
+            .corner_radius(corner_radius)
+            .layout(|l| l
+                .direction(LayoutDirection::LeftToRight)
+                .padding(padding)
+                .gap(padding)
+                .align(AlignX::Left, AlignY::CenterY)
+            )
+            .children(|ui| {
+                if let Some(leading) = leading {
+                    leading(ui);
+                }
+
+                ui.element()
+                    .id(id.clone())
+                    .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+                    .height(Sizing::Fixed(input_height))
+                    .text_input(|t| {
+                        if let Some(font) = font_asset {
+                            t.font(font);
+                        }
+                        let submit = flush.clone();
+                        t.placeholder(&placeholder)
+                            .font_size(font_size)
+                            .text_color(text_color)
+                            .placeholder_color(placeholder_color)
+                            .clear_on_escape()
+                            .on_submit(move |_| submit.set(true))
+                    })
+                    .empty();
+
+                if !text.is_empty() {
+                    ui.element()
+                        .preserve_focus()
+                        .accessibility(|a| a.button("Clear search"))
+                        .children(|ui| {
+                            if ui.just_pressed() {
+                                ui.set_text_value(id.clone(), "");
+                                flush.set(true);
+                            }
+                            ui.text("×", |t| {
+                                if let Some(font) = font_asset {
+                                    t.font(font);
+                                }
+                                t.font_size(font_size).color(clear_button_color)
+                            });
+                        });
+                }
+            });
+
+        id
+    }
+}
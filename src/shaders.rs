@@ -1,9 +1,31 @@
 use std::borrow::Cow;
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+use rustc_hash::FxHashMap;
+
+use crate::diagnostics::{self, Diagnostic};
+
+/// How often a `Path` shader's file is checked for changes.
+const RELOAD_CHECK_MS: f64 = 250.0;
+
+/// `Path` shaders by path, so files are read once per change instead of
+/// once per use.
+static SHADER_FILES: LazyLock<Mutex<FxHashMap<&'static str, ShaderFile>>> =
+    LazyLock::new(|| Mutex::new(FxHashMap::default()));
+
+struct ShaderFile {
+    /// The last source that was read successfully.
+    source: Option<String>,
+    /// The file's modification time when last read.
+    modified: Option<SystemTime>,
+    checked_ms: Option<f64>,
+}
 
 /// Represents a shader asset that can be loaded from a file path, embedded as source,
 /// or looked up from the runtime shader storage in [`MaterialManager`].
 ///
-/// `Path` is loaded from the filesystem at runtime (useful for development/hot-reloading).
+/// `Path` is loaded from the filesystem at runtime and reloaded when the file changes (native).
 /// `Source` embeds the shader in the binary (via `include_str!`).
 /// `Stored` references a named entry in the shader storage, enabling runtime-updateable shader code.
 #[derive(Debug, Clone)]
@@ -23,22 +45,20 @@ pub enum ShaderAsset {
 
 impl ShaderAsset {
     /// Returns the fragment shader source.
-    /// For `Path` variant, reads the file synchronously.
+    /// For `Path` variant, reads the file synchronously, again whenever it changes.
+    /// Read errors are reported as [`Diagnostic::ShaderLoadFailed`].
     /// For `Source` variant, returns a borrowed reference (zero-copy).
     /// For `Stored` variant, looks up the source from the global shader storage.
     pub fn fragment_source(&self) -> Cow<'static, str> {
         match self {
-            ShaderAsset::Path(path) => {
-                Cow::Owned(std::fs::read_to_string(path)
-                    .unwrap_or_else(|e| panic!("Failed to read shader file '{}': {}", path, e)))
-            }
+            ShaderAsset::Path(path) => load_shader_file(path),
             ShaderAsset::Source { fragment, .. } => Cow::Borrowed(fragment),
             ShaderAsset::Stored(name) => {
                 let mgr = crate::renderer::MATERIAL_MANAGER.lock().unwrap();
                 match mgr.get_source(name) {
                     Some(src) => Cow::Owned(src.to_string()),
                     None => {
-                        eprintln!("Shader storage '{}' not found, using fallback", name);
+                        crate::telemetry::warning!("Shader storage '{}' not found, using fallback", name);
                         Cow::Borrowed(crate::renderer::DEFAULT_FRAGMENT_SHADER)
                     }
                 }
@@ -56,9 +76,38 @@ impl ShaderAsset {
     }
 }
 
+/// Reads a `Path` shader, checking the file for changes at most every
+/// [`RELOAD_CHECK_MS`]. Changed sources get new materials, since materials are
+/// cached by source.
+fn load_shader_file(path: &'static str) -> Cow<'static, str> {
+    let now = crate::profiler::now_ms();
+    let mut files = SHADER_FILES.lock().unwrap();
+    let file = files.entry(path).or_insert(ShaderFile { source: None, modified: None, checked_ms: None });
+    let first = file.checked_ms.is_none();
+    if file.checked_ms.is_none_or(|checked| now - checked >= RELOAD_CHECK_MS) {
+        file.checked_ms = Some(now);
+        let modified = std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+        if first || modified != file.modified {
+            file.modified = modified;
+            match std::fs::read_to_string(path) {
+                Ok(source) => file.source = Some(source),
+                Err(e) => diagnostics::report(Diagnostic::ShaderLoadFailed {
+                    path: path.to_string(),
+                    error: e.to_string(),
+                }),
+            }
+        }
+    }
+    match &file.source {
+        Some(source) => Cow::Owned(source.clone()),
+        None => Cow::Borrowed(crate::renderer::DEFAULT_FRAGMENT_SHADER),
+    }
+}
+
 /// Configuration for a shader effect, stored in render commands.
 /// Contains the fragment shader source and uniform values.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShaderConfig {
     /// The GLSL ES 3.00 fragment shader source (resolved from ShaderAsset).
     pub fragment: Cow<'static, str>,
@@ -66,10 +115,22 @@ pub struct ShaderConfig {
     pub uniforms: Vec<ShaderUniform>,
     /// Debug-friendly name derived from the shader asset.
     pub name: String,
+    /// Set on transition shaders, see `ElementBuilder::transition`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub transition: Option<crate::transition::TransitionCapture>,
+    /// For group shaders, a hash of what the group draws when it can be
+    /// cached: while it stays the same, the renderer reuses the last
+    /// offscreen capture instead of drawing the group's commands again.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub capture_key: Option<u64>,
+    /// Set on mask shaders, see `ElementBuilder::mask`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mask: Option<crate::mask::MaskSource>,
 }
 
 /// A single shader uniform with a name and typed value.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShaderUniform {
     /// The uniform variable name in the shader.
     pub name: String,
@@ -79,6 +140,7 @@ pub struct ShaderUniform {
 
 /// Typed values for shader uniforms.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ShaderUniformValue {
     /// A single float value.
     Float(f32),
@@ -92,6 +154,29 @@ pub enum ShaderUniformValue {
     Int(i32),
     /// A 4x4 matrix.
     Mat4([[f32; 4]; 4]),
+    /// A `float[N]` array.
+    FloatArray(Vec<f32>),
+    /// A `vec2[N]` array.
+    Vec2Array(Vec<[f32; 2]>),
+    /// A `vec4[N]` array.
+    Vec4Array(Vec<[f32; 4]>),
+}
+
+impl ShaderUniformValue {
+    /// The GLSL type this value sets, and its length for arrays.
+    pub fn glsl_type(&self) -> (&'static str, Option<usize>) {
+        match self {
+            ShaderUniformValue::Float(_) => ("float", None),
+            ShaderUniformValue::Vec2(_) => ("vec2", None),
+            ShaderUniformValue::Vec3(_) => ("vec3", None),
+            ShaderUniformValue::Vec4(_) => ("vec4", None),
+            ShaderUniformValue::Int(_) => ("int", None),
+            ShaderUniformValue::Mat4(_) => ("mat4", None),
+            ShaderUniformValue::FloatArray(v) => ("float", Some(v.len())),
+            ShaderUniformValue::Vec2Array(v) => ("vec2", Some(v.len())),
+            ShaderUniformValue::Vec4Array(v) => ("vec4", Some(v.len())),
+        }
+    }
 }
 
 impl From<f32> for ShaderUniformValue {
@@ -130,6 +215,86 @@ impl From<[[f32; 4]; 4]> for ShaderUniformValue {
     }
 }
 
+impl From<Vec<f32>> for ShaderUniformValue {
+    fn from(v: Vec<f32>) -> Self {
+        ShaderUniformValue::FloatArray(v)
+    }
+}
+
+impl From<&[f32]> for ShaderUniformValue {
+    fn from(v: &[f32]) -> Self {
+        ShaderUniformValue::FloatArray(v.to_vec())
+    }
+}
+
+impl From<Vec<[f32; 2]>> for ShaderUniformValue {
+    fn from(v: Vec<[f32; 2]>) -> Self {
+        ShaderUniformValue::Vec2Array(v)
+    }
+}
+
+impl From<&[[f32; 2]]> for ShaderUniformValue {
+    fn from(v: &[[f32; 2]]) -> Self {
+        ShaderUniformValue::Vec2Array(v.to_vec())
+    }
+}
+
+impl From<Vec<[f32; 4]>> for ShaderUniformValue {
+    fn from(v: Vec<[f32; 4]>) -> Self {
+        ShaderUniformValue::Vec4Array(v)
+    }
+}
+
+impl From<&[[f32; 4]]> for ShaderUniformValue {
+    fn from(v: &[[f32; 4]]) -> Self {
+        ShaderUniformValue::Vec4Array(v.to_vec())
+    }
+}
+
+/// A `uniform` declared in GLSL source.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct UniformDeclaration {
+    pub name: String,
+    pub glsl_type: String,
+    /// The array length, for array uniforms.
+    pub len: Option<usize>,
+}
+
+/// Finds the `uniform` declarations in GLSL source, like
+/// `uniform highp vec2 u_points[8];`. Samplers are included.
+pub(crate) fn parse_uniform_declarations(source: &str) -> Vec<UniformDeclaration> {
+    let code: String = source
+        .lines()
+        .map(|line| line.split("//").next().unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut declarations = Vec::new();
+    for statement in code.split(';') {
+        let mut words = statement.split_whitespace();
+        if words.next() != Some("uniform") {
+            continue;
+        }
+        let mut words = words.skip_while(|word| matches!(*word, "lowp" | "mediump" | "highp"));
+        let Some(glsl_type) = words.next() else { continue };
+        // Names may be separated by commas and carry `[N]`, with or without spaces
+        let rest: String = words.collect();
+        for name in rest.split(',') {
+            let (name, len) = match name.split_once('[') {
+                Some((name, len)) => (name, len.trim_end_matches(']').parse().ok()),
+                None => (name, None),
+            };
+            if !name.is_empty() {
+                declarations.push(UniformDeclaration {
+                    name: name.to_string(),
+                    glsl_type: glsl_type.to_string(),
+                    len,
+                });
+            }
+        }
+    }
+    declarations
+}
+
 /// Builder for configuring shader uniforms.
 /// Used in the closure passed to `.effect()` and `.shader()` on `ElementBuilder`.
 pub struct ShaderBuilder<'a> {
@@ -148,7 +313,13 @@ impl<'a> ShaderBuilder<'a> {
 
     /// Sets a uniform value on the shader.
     ///
-    /// Supports `f32`, `[f32; 2]`, `[f32; 3]`, `[f32; 4]`, `i32`, and `[[f32; 4]; 4]`.
+    /// Supports `f32`, `[f32; 2]`, `[f32; 3]`, `[f32; 4]`, `i32`, and `[[f32; 4]; 4]`,
+    /// and `float[N]`, `vec2[N]` and `vec4[N]` arrays from `Vec`s or slices of
+    /// `f32`, `[f32; 2]` and `[f32; 4]`.
+    ///
+    /// Uniforms whose type or array length doesn't match the shader's
+    /// declaration are left out and reported as
+    /// [`Diagnostic::ShaderUniformMismatch`].
     pub fn uniform(&mut self, name: &str, value: impl Into<ShaderUniformValue>) -> &mut Self {
         self.uniforms.push(ShaderUniform {
             name: name.to_string(),
@@ -159,10 +330,120 @@ impl<'a> ShaderBuilder<'a> {
 
     /// Builds the ShaderConfig from this builder.
     pub(crate) fn into_config(&mut self) -> ShaderConfig {
-        ShaderConfig {
-            fragment: self.source.fragment_source(),
-            uniforms: std::mem::take(&mut self.uniforms),
-            name: self.source.cache_key().to_string(),
-        }
+        let fragment = self.source.fragment_source();
+        let name = self.source.cache_key().to_string();
+        let declarations = parse_uniform_declarations(&fragment);
+        let mut uniforms = std::mem::take(&mut self.uniforms);
+        uniforms.retain(|uniform| {
+            // Undeclared uniforms are ignored by the GPU, so they're let through
+            let Some(declared) = declarations.iter().find(|d| d.name == uniform.name) else {
+                return true;
+            };
+            let (glsl_type, len) = uniform.value.glsl_type();
+            if declared.glsl_type == glsl_type && declared.len == len {
+                return true;
+            }
+            let describe = |glsl_type: &str, len: Option<usize>| match len {
+                Some(len) => format!("{glsl_type}[{len}]"),
+                None => glsl_type.to_string(),
+            };
+            diagnostics::report(Diagnostic::ShaderUniformMismatch {
+                shader: name.clone(),
+                uniform: uniform.name.clone(),
+                declared: describe(&declared.glsl_type, declared.len),
+                given: describe(glsl_type, len),
+            });
+            false
+        });
+        ShaderConfig { fragment, uniforms, name, transition: None, capture_key: None, mask: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Dimensions;
+    use crate::Ply;
+
+    #[test]
+    fn test_path_shaders_reload_and_report_missing_files() {
+        let dir = std::env::temp_dir().join(format!("ply_shader_reload_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("tint.glsl");
+        let path: &'static str = Box::leak(file.to_string_lossy().into_owned().into_boxed_str());
+        let shader = ShaderAsset::Path(path);
+        let wait = || std::thread::sleep(std::time::Duration::from_millis(RELOAD_CHECK_MS as u64 + 50));
+
+        std::fs::write(&file, "// first").unwrap();
+        assert_eq!(shader.fragment_source(), "// first");
+
+        // A missing file keeps the last source and is reported instead of panicking
+        std::fs::remove_file(&file).unwrap();
+        wait();
+        assert_eq!(shader.fragment_source(), "// first");
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(100.0, 100.0));
+        let mut ui = ply.begin();
+        ui.eval();
+        assert!(ply.diagnostics().iter().any(|diagnostic| matches!(
+            diagnostic,
+            Diagnostic::ShaderLoadFailed { path: failed, .. } if failed == path
+        )));
+
+        std::fs::write(&file, "// second").unwrap();
+        wait();
+        assert_eq!(shader.fragment_source(), "// second");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_array_uniforms_are_validated_against_declarations() {
+        let shader = ShaderAsset::Source {
+            file_name: "gradient_stops",
+            fragment: "#version 100
+precision mediump float;
+uniform sampler2D Texture;
+uniform highp float u_stops[3]; // stop offsets
+uniform vec2 u_points [2], u_center;
+uniform vec4 u_colors[3];
+uniform mat4 u_transform;
+void main() {}",
+        };
+        let declarations = parse_uniform_declarations(shader.fragment_source().as_ref());
+        let names: Vec<_> = declarations.iter().map(|d| (d.name.as_str(), d.glsl_type.as_str(), d.len)).collect();
+        assert_eq!(names, [
+            ("Texture", "sampler2D", None),
+            ("u_stops", "float", Some(3)),
+            ("u_points", "vec2", Some(2)),
+            ("u_center", "vec2", None),
+            ("u_colors", "vec4", Some(3)),
+            ("u_transform", "mat4", None),
+        ]);
+
+        diagnostics::take_pending();
+        let colors: [[f32; 4]; 3] = [[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]];
+        let config = ShaderBuilder::new(&shader)
+            .uniform("u_stops", vec![0.0f32, 0.5, 1.0])
+            .uniform("u_points", &[[0.0f32, 0.0], [1.0, 1.0]][..])
+            .uniform("u_colors", &colors[..2])
+            .uniform("u_center", [0.5f32, 0.5])
+            .uniform("u_transform", 1.0f32)
+            .uniform("u_time", 2.0f32)
+            .into_config();
+        let kept: Vec<_> = config.uniforms.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(kept, ["u_stops", "u_points", "u_center", "u_time"]);
+        assert_eq!(diagnostics::take_pending(), [
+            Diagnostic::ShaderUniformMismatch {
+                shader: "gradient_stops".into(),
+                uniform: "u_colors".into(),
+                declared: "vec4[3]".into(),
+                given: "vec4[2]".into(),
+            },
+            Diagnostic::ShaderUniformMismatch {
+                shader: "gradient_stops".into(),
+                uniform: "u_transform".into(),
+                declared: "mat4".into(),
+                given: "float".into(),
+            },
+        ]);
     }
 }
@@ -0,0 +1,992 @@
+//! Drawing render commands on the CPU with tiny-skia, for server-side
+//! rendering, CI golden images and embedding into existing framebuffers.
+//!
+//! ```ignore
+//! static FONT: FontAsset = FontAsset::Bytes { file_name: "inter.ttf", data: include_bytes!("inter.ttf") };
+//!
+//! let mut software = SoftwareRenderer::new();
+//! software.default_font(&FONT).scale(2.0);
+//! let mut ply = Ply::<()>::new_headless(Dimensions::new(320.0, 240.0));
+//! ply.set_measure_text_function(software.measure_text_function());
+//!
+//! let mut ui = ply.begin();
+//! // ...declare the UI...
+//! let commands = ui.eval();
+//! let image = software.render(&commands, 640, 480);
+//! ```
+//!
+//! Layout stays in logical pixels and is drawn at `scale` physical pixels
+//! each. Rectangles, borders, text (with its styling markup under
+//! `text-styling`), text inputs with their selection and caret, PNG and TGA
+//! images, custom elements (as their background), canvases, nested scissors,
+//! shape rotation and visual rotation are drawn. Per-element effects and
+//! group shaders aren't supported yet, built-in ones included: elements and
+//! groups draw without them. `.on_draw()` hooks can't run on the CPU and are
+//! skipped. Both are reported once with a warning.
+//! Rotations from animated text styles aren't drawn. GPU textures live on
+//! the GPU and TinyVG images need macroquad to tessellate, so both draw as
+//! their tint, or gray.
+
+use std::borrow::Cow;
+#[cfg(feature = "text-styling")]
+use std::cell::Cell;
+use std::sync::{Arc, Mutex};
+
+use rustc_hash::FxHashMap;
+use tiny_skia::{
+    ColorU8, FillRule, FilterQuality, LineCap, Mask, Paint, Path, PathBuilder, Pattern, Pixmap, PixmapMut, PixmapPaint,
+    PremultipliedColorU8, Rect, Shader, SpreadMode, Stroke, Transform,
+};
+
+use crate::canvas::PaintCommand;
+use crate::capture::ImageBuffer;
+use crate::color::Color;
+use crate::elements::BorderPosition;
+use crate::engine::{ShapeRotationConfig, VisualRotationConfig};
+use crate::math::{BoundingBox, Dimensions};
+use crate::render_commands::{CornerRadii, RenderCommand, RenderCommandConfig};
+use crate::renderer::{FontAsset, GraphicAsset, ImageSource, Renderer};
+use crate::text::TextConfig;
+
+/// Color of images that can't be decoded, without a tint.
+const IMAGE_PLACEHOLDER: Color = Color::rgba(128.0, 128.0, 128.0, 255.0);
+/// Length of a cubic Bézier handle approximating a quarter circle, per unit of radius.
+const ARC_HANDLE: f32 = 0.552_284_8;
+
+/// Draws render commands into RGBA buffers without a GPU or window.
+///
+/// Fonts and decoded images are cached across frames.
+pub struct SoftwareRenderer {
+    scale: f32,
+    fonts: Arc<Mutex<FontCache>>,
+    images: FxHashMap<&'static str, Option<Pixmap>>,
+    warned_shaders: bool,
+    warned_draw_hooks: bool,
+    /// Time animated text styles are drawn at, in seconds.
+    #[cfg(feature = "text-styling")]
+    time: f64,
+    /// Where each animated text style with an `id` started.
+    #[cfg(feature = "text-styling")]
+    animations: FxHashMap<String, (usize, f64)>,
+    /// The frame being drawn through [`Renderer`], and the last one finished.
+    canvas: Option<Canvas>,
+    frame: Option<ImageBuffer>,
+}
+
+impl Default for SoftwareRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SoftwareRenderer {
+    pub fn new() -> Self {
+        Self {
+            scale: 1.0,
+            fonts: Arc::new(Mutex::new(FontCache::default())),
+            images: FxHashMap::default(),
+            warned_shaders: false,
+            warned_draw_hooks: false,
+            #[cfg(feature = "text-styling")]
+            time: 0.0,
+            #[cfg(feature = "text-styling")]
+            animations: FxHashMap::default(),
+            canvas: None,
+            frame: None,
+        }
+    }
+
+    /// Sets the font for text without one. Without it, such text isn't drawn.
+    pub fn default_font(&mut self, font: &'static FontAsset) -> &mut Self {
+        self.fonts.lock().unwrap().default = Some(font);
+        self
+    }
+
+    /// Sets physical pixels per logical pixel. Defaults to `1.0`.
+    pub fn scale(&mut self, scale: f32) -> &mut Self {
+        self.scale = if scale.is_finite() && scale > 0.0 { scale } else { 1.0 };
+        self
+    }
+
+    /// Sets the time in seconds animated text styles are drawn at. Defaults
+    /// to `0.0`, so the same commands always draw the same image.
+    #[cfg(feature = "text-styling")]
+    pub fn time(&mut self, seconds: f64) -> &mut Self {
+        self.time = seconds;
+        self
+    }
+
+    /// Returns a measure function using this renderer's fonts, for
+    /// `Ply::set_measure_text_function`, so layout matches what is drawn.
+    pub fn measure_text_function(&self) -> impl Fn(&str, &TextConfig) -> Dimensions + Send + Sync + 'static {
+        let fonts = self.fonts.clone();
+        move |text, config| fonts.lock().unwrap().measure(text, config)
+    }
+
+    /// Draws `commands` into a new `width` × `height` image, starting transparent.
+    pub fn render<CustomElementData>(
+        &mut self,
+        commands: &[RenderCommand<CustomElementData>],
+        width: u16,
+        height: u16,
+    ) -> ImageBuffer {
+        let mut rgba = vec![0; width as usize * height as usize * 4];
+        self.render_into(commands, &mut rgba, width as u32, height as u32);
+        demultiply(&mut rgba);
+        ImageBuffer { width, height, rgba }
+    }
+
+    /// Draws `commands` over an existing buffer of premultiplied RGBA8
+    /// pixels, row by row from the top. Returns `false` without drawing when
+    /// the buffer doesn't hold `width` × `height` pixels.
+    pub fn render_into<CustomElementData>(
+        &mut self,
+        commands: &[RenderCommand<CustomElementData>],
+        rgba: &mut [u8],
+        width: u32,
+        height: u32,
+    ) -> bool {
+        if PixmapMut::from_bytes(rgba, width, height).is_none() {
+            return false;
+        }
+        let mut frame = FrameState::default();
+        for command in commands {
+            self.draw(&mut frame, command, rgba, width, height);
+        }
+        frame.finish(rgba, width, height);
+        true
+    }
+
+    /// Takes the image drawn by the last frame shown with
+    /// [`Ply::show_with`](crate::Ply::show_with), the layout size times `scale`.
+    pub fn take_frame(&mut self) -> Option<ImageBuffer> {
+        self.frame.take()
+    }
+
+    /// Draws one command into `rgba`, which holds `width` × `height` pixels.
+    fn draw<CustomElementData>(
+        &mut self,
+        frame: &mut FrameState,
+        command: &RenderCommand<CustomElementData>,
+        rgba: &mut [u8],
+        width: u32,
+        height: u32,
+    ) {
+        let scale = self.scale;
+        let base = Transform::from_scale(scale, scale);
+        let full = (0.0, 0.0, width as f32, height as f32);
+        let bb = command.bounding_box;
+        let transform = base.pre_concat(shape_transform(&bb, command.shape_rotation.as_ref()));
+        let clip_mask = frame.mask.as_ref();
+        let shaded = !command.effects.is_empty()
+            || matches!(command.config, RenderCommandConfig::GroupBegin { shader: Some(_), .. });
+        if shaded && !self.warned_shaders {
+            self.warned_shaders = true;
+            crate::telemetry::warning!("The software renderer can't run shaders, drawing without them");
+        }
+        if let RenderCommandConfig::GroupEnd = command.config {
+            let Some((layer, visual_rotation, group)) = frame.layers.pop() else {
+                return;
+            };
+            let mut pixmap = target(&mut frame.layers, rgba, width, height);
+            let transform = visual_rotation.map_or(Transform::identity(), |rotation| {
+                visual_transform(&group, &rotation).post_scale(scale, scale).pre_scale(1.0 / scale, 1.0 / scale)
+            });
+            pixmap.draw_pixmap(0, 0, layer.as_ref(), &PixmapPaint::default(), transform, clip_mask);
+            return;
+        }
+        let mut pixmap = target(&mut frame.layers, rgba, width, height);
+        match &command.config {
+            RenderCommandConfig::Rectangle(rect) => {
+                fill_rounded_rect(&mut pixmap, &bb, &rect.corner_radii, rect.color, transform, clip_mask);
+            }
+            RenderCommandConfig::Custom(custom) => {
+                fill_rounded_rect(&mut pixmap, &bb, &custom.corner_radii, custom.background_color, transform, clip_mask);
+            }
+            RenderCommandConfig::Image(image) => {
+                let decoded = match &image.data {
+                    &ImageSource::Asset(asset) => self
+                        .images
+                        .entry(asset.get_name())
+                        .or_insert_with(|| decode_image(asset))
+                        .as_ref(),
+                    _ => None,
+                };
+                match (decoded, rounded_rect_path(&bb, &image.corner_radii)) {
+                    (Some(decoded), Some(path)) => {
+                        let opacity = if image.background_color.a > 0.0 { image.background_color.a / 255.0 } else { 1.0 };
+                        let to_bounds = Transform::from_row(
+                            bb.width / decoded.width() as f32,
+                            0.0,
+                            0.0,
+                            bb.height / decoded.height() as f32,
+                            bb.x,
+                            bb.y,
+                        );
+                        let paint = Paint {
+                            shader: Pattern::new(decoded.as_ref(), SpreadMode::Pad, FilterQuality::Bilinear, opacity, to_bounds),
+                            anti_alias: true,
+                            ..Default::default()
+                        };
+                        pixmap.fill_path(&path, &paint, FillRule::Winding, transform, clip_mask);
+                    }
+                    _ => {
+                        let color = if image.background_color.a > 0.0 { image.background_color } else { IMAGE_PLACEHOLDER };
+                        fill_rounded_rect(&mut pixmap, &bb, &image.corner_radii, color, transform, clip_mask);
+                    }
+                }
+            }
+            RenderCommandConfig::Border(border) => {
+                fill_border(&mut pixmap, &bb, border, transform, clip_mask);
+            }
+            RenderCommandConfig::Text(text) => {
+                let clip = intersect(frame.clips.last().copied().unwrap_or(full), full);
+                let config = TextConfig {
+                    font_asset: text.font_asset,
+                    font_size: text.font_size,
+                    letter_spacing: text.letter_spacing,
+                    ..Default::default()
+                };
+                let mut fonts = self.fonts.lock().unwrap();
+                let plain = TextRun { text: &text.text, config: &config, line: bb, size: 1.0, weight: 0.0, skew: 0.0 };
+                #[cfg(feature = "text-styling")]
+                match crate::text_styling::parse_styled_line(&text.text, &mut frame.style_stack) {
+                    Ok(segments) => {
+                        // Runs are placed one after another, like the macroquad renderer does
+                        let pen = Cell::new(bb.x);
+                        let spacing = text.letter_spacing as f32;
+                        let mut runs = Vec::new();
+                        let mut shadows = Vec::new();
+                        crate::text_styling::render_styled_text(
+                            &segments,
+                            self.time,
+                            text.font_size as f32,
+                            text.color,
+                            &mut self.animations,
+                            &mut frame.total_char_index,
+                            |run, tr, color| {
+                                let x = pen.get();
+                                pen.set(x + fonts.measure_plain(run, &config).width + spacing);
+                                runs.push((x, run.to_string(), tr, color));
+                            },
+                            |run, tr, color| shadows.push((pen.get(), run.to_string(), tr, color)),
+                        );
+                        let styled = |x: f32, run, tr: crate::text_styling::Transform| TextRun {
+                            text: run,
+                            config: &config,
+                            line: BoundingBox::new(x + tr.x, bb.y + tr.y, bb.width, bb.height),
+                            size: tr.scale_y,
+                            weight: tr.weight,
+                            skew: tr.skew,
+                        };
+                        for (x, run, tr, color) in &shadows {
+                            fonts.draw(&mut pixmap, &styled(*x, run, *tr), (0.0, 0.0), *color, scale, clip);
+                        }
+                        for (x, run, tr, color) in &runs {
+                            fonts.draw_decorated(&mut pixmap, &styled(*x, run, *tr), text, *color, scale, clip);
+                        }
+                    }
+                    Err(error) => {
+                        crate::telemetry::warning!("{} Here is what we tried to render: {}", error, text.text);
+                        fonts.draw_decorated(&mut pixmap, &plain, text, text.color, scale, clip);
+                    }
+                }
+                #[cfg(not(feature = "text-styling"))]
+                fonts.draw_decorated(&mut pixmap, &plain, text, text.color, scale, clip);
+            }
+            RenderCommandConfig::Canvas(canvas) => {
+                let transform = transform.pre_translate(bb.x, bb.y);
+                for paint in &canvas.commands {
+                    draw_paint_command(&mut pixmap, paint, transform, clip_mask);
+                }
+            }
+            RenderCommandConfig::ScissorStart() => {
+                let area = (bb.x * scale, bb.y * scale, (bb.x + bb.width) * scale, (bb.y + bb.height) * scale);
+                let clip = intersect(frame.clips.last().copied().unwrap_or(full), area);
+                frame.clips.push(clip);
+                frame.mask = clip_to_mask(width, height, clip);
+            }
+            RenderCommandConfig::ScissorEnd() => {
+                frame.clips.pop();
+                frame.mask = frame.clips.last().and_then(|&clip| clip_to_mask(width, height, clip));
+            }
+            RenderCommandConfig::GroupBegin { visual_rotation, .. } => {
+                if let Some(layer) = Pixmap::new(width, height) {
+                    frame.layers.push((layer, *visual_rotation, bb));
+                }
+            }
+            RenderCommandConfig::Draw(_) => {
+                if !self.warned_draw_hooks {
+                    self.warned_draw_hooks = true;
+                    crate::telemetry::warning!("The software renderer can't run on_draw hooks, skipping them");
+                }
+            }
+            RenderCommandConfig::GroupEnd | RenderCommandConfig::None() => {}
+        }
+    }
+}
+
+impl<CustomElementData: Sync> Renderer<CustomElementData> for SoftwareRenderer {
+    fn measure_text(&self, text: &str, config: &TextConfig) -> Dimensions {
+        self.fonts.lock().unwrap().measure(text, config)
+    }
+
+    fn begin_frame(&mut self, dimensions: Dimensions) {
+        let size = |logical: f32| (logical * self.scale).ceil().clamp(1.0, u16::MAX as f32) as u16;
+        let (width, height) = (size(dimensions.width), size(dimensions.height));
+        self.canvas = Some(Canvas {
+            rgba: vec![0; width as usize * height as usize * 4],
+            width,
+            height,
+            state: FrameState::default(),
+        });
+    }
+
+    async fn draw_command(&mut self, command: &RenderCommand<CustomElementData>) {
+        if let Some(mut canvas) = self.canvas.take() {
+            self.draw(&mut canvas.state, command, &mut canvas.rgba, canvas.width as u32, canvas.height as u32);
+            self.canvas = Some(canvas);
+        }
+    }
+
+    fn end_frame(&mut self) {
+        if let Some(mut canvas) = self.canvas.take() {
+            canvas.state.finish(&mut canvas.rgba, canvas.width as u32, canvas.height as u32);
+            demultiply(&mut canvas.rgba);
+            self.frame = Some(ImageBuffer { width: canvas.width, height: canvas.height, rgba: canvas.rgba });
+        }
+    }
+}
+
+/// Clips, open groups and open text styles of the frame being drawn.
+#[derive(Default)]
+struct FrameState {
+    clips: Vec<(f32, f32, f32, f32)>,
+    mask: Option<Mask>,
+    /// Groups draw into their own layer, composited with their visual rotation when they end.
+    layers: Vec<(Pixmap, Option<VisualRotationConfig>, BoundingBox)>,
+    /// Styles stay open across wrapped lines of the same text.
+    #[cfg(feature = "text-styling")]
+    style_stack: Vec<String>,
+    #[cfg(feature = "text-styling")]
+    total_char_index: usize,
+}
+
+impl FrameState {
+    /// Composites groups left open, so they still show.
+    fn finish(&mut self, rgba: &mut [u8], width: u32, height: u32) {
+        while let Some((layer, _, _)) = self.layers.pop() {
+            let mut pixmap = target(&mut self.layers, rgba, width, height);
+            pixmap.draw_pixmap(0, 0, layer.as_ref(), &PixmapPaint::default(), Transform::identity(), None);
+        }
+    }
+}
+
+/// A frame drawn through [`Renderer`], into the renderer's own buffer.
+struct Canvas {
+    rgba: Vec<u8>,
+    width: u16,
+    height: u16,
+    state: FrameState,
+}
+
+/// Turns tiny-skia's premultiplied alpha into straight alpha.
+fn demultiply(rgba: &mut [u8]) {
+    for pixel in rgba.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u32;
+        if alpha > 0 && alpha < 255 {
+            for channel in &mut pixel[..3] {
+                *channel = ((*channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8;
+            }
+        }
+    }
+}
+
+/// The innermost open group's layer, or the caller's buffer outside groups.
+fn target<'a>(
+    layers: &'a mut [(Pixmap, Option<VisualRotationConfig>, BoundingBox)],
+    rgba: &'a mut [u8],
+    width: u32,
+    height: u32,
+) -> PixmapMut<'a> {
+    match layers.last_mut() {
+        Some((layer, _, _)) => layer.as_mut(),
+        // The size was checked before drawing
+        None => PixmapMut::from_bytes(rgba, width, height).unwrap(),
+    }
+}
+
+/// Turns a group about its pivot and mirrors it in place, like the
+/// macroquad renderer composites its render target.
+///
+/// Scale, skew and translation are placed by the top-left, top-right and
+/// bottom-left corners. Tilts can't be drawn with an affine transform, so
+/// they're approximated the same way.
+fn visual_transform(bb: &BoundingBox, rotation: &VisualRotationConfig) -> Transform {
+    if rotation.is_tilted() || rotation.is_transformed() {
+        let origin = rotation.project(bb, (bb.x, bb.y));
+        let right = rotation.project(bb, (bb.x + bb.width, bb.y));
+        let down = rotation.project(bb, (bb.x, bb.y + bb.height));
+        let (width, height) = (bb.width.max(f32::EPSILON), bb.height.max(f32::EPSILON));
+        return Transform::from_row(
+            (right.0 - origin.0) / width,
+            (right.1 - origin.1) / width,
+            (down.0 - origin.0) / height,
+            (down.1 - origin.1) / height,
+            origin.0,
+            origin.1,
+        )
+        .pre_translate(-bb.x, -bb.y);
+    }
+    let (pivot_x, pivot_y) = (bb.x + rotation.pivot_x * bb.width, bb.y + rotation.pivot_y * bb.height);
+    let (center_x, center_y) = (bb.x + bb.width / 2.0, bb.y + bb.height / 2.0);
+    Transform::from_translate(pivot_x, pivot_y)
+        .pre_rotate(rotation.rotation_radians.to_degrees())
+        .pre_translate(center_x - pivot_x, center_y - pivot_y)
+        .pre_scale(if rotation.flip_x { -1.0 } else { 1.0 }, if rotation.flip_y { -1.0 } else { 1.0 })
+        .pre_translate(-center_x, -center_y)
+}
+
+/// Loaded fonts and rasterized glyphs, shared with the measure function.
+#[derive(Default)]
+struct FontCache {
+    default: Option<&'static FontAsset>,
+    fonts: FxHashMap<&'static str, Option<fontdue::Font>>,
+    /// Keyed by font, character and pixel size bits.
+    glyphs: FxHashMap<(&'static str, char, u32), (fontdue::Metrics, Vec<u8>)>,
+}
+
+impl FontCache {
+    /// Loads the config's font, or the default one, and returns its key.
+    fn resolve(&mut self, config: &TextConfig) -> Option<&'static str> {
+        let asset = config.font_asset.or(self.default)?;
+        let font = self.fonts.entry(asset.key()).or_insert_with(|| load_font(asset));
+        font.as_ref().map(|_| asset.key())
+    }
+
+    /// Measures text as laid out, without its styling markup.
+    fn measure(&mut self, text: &str, config: &TextConfig) -> Dimensions {
+        #[cfg(feature = "text-styling")]
+        let text = &crate::text_input::styling::strip_styling(text);
+        self.measure_plain(text, config)
+    }
+
+    fn measure_plain(&mut self, text: &str, config: &TextConfig) -> Dimensions {
+        let size = config.font_size as f32;
+        let Some(font) = self.resolve(config).and_then(|key| self.fonts[key].as_ref()) else {
+            // No font to measure with: a rough monospace guess
+            return Dimensions::new(text.chars().count() as f32 * size / 2.0, size);
+        };
+        let count = text.chars().count();
+        let advance: f32 = text.chars().map(|ch| font.metrics(ch, size).advance_width).sum();
+        let spacing = count.saturating_sub(1) as f32 * config.letter_spacing as f32;
+        let height = font.horizontal_line_metrics(size).map_or(size, |line| line.ascent - line.descent);
+        Dimensions::new(advance + spacing, height)
+    }
+
+    /// Draws a run with the command's shadow and outline behind it.
+    fn draw_decorated(
+        &mut self,
+        pixmap: &mut PixmapMut,
+        run: &TextRun,
+        text: &crate::render_commands::Text,
+        color: Color,
+        scale: f32,
+        clip: (f32, f32, f32, f32),
+    ) {
+        if let Some(shadow) = text.shadow {
+            self.draw(pixmap, run, (shadow.offset.x, shadow.offset.y), shadow.color, scale, clip);
+        }
+        if let Some(outline) = text.outline.filter(|outline| outline.width > 0.0) {
+            for i in 0..8 {
+                let angle = i as f32 / 8.0 * std::f32::consts::TAU;
+                let offset = (angle.cos() * outline.width, angle.sin() * outline.width);
+                self.draw(pixmap, run, offset, outline.color, scale, clip);
+            }
+        }
+        self.draw(pixmap, run, (0.0, 0.0), color, scale, clip);
+        if run.weight > 0.0 {
+            self.draw(pixmap, run, (run.weight, 0.0), color, scale, clip);
+        }
+    }
+
+    /// Draws a run with its glyphs centered vertically on its line, moved by `offset`.
+    fn draw(
+        &mut self,
+        pixmap: &mut PixmapMut,
+        run: &TextRun,
+        offset: (f32, f32),
+        color: Color,
+        scale: f32,
+        clip: (f32, f32, f32, f32),
+    ) {
+        let Some(key) = self.resolve(run.config) else {
+            return;
+        };
+        let Some(font) = self.fonts[key].as_ref() else {
+            return;
+        };
+        let size = run.config.font_size as f32 * run.size * scale;
+        let Some(line) = font.horizontal_line_metrics(size) else {
+            return;
+        };
+        let bb = &run.line;
+        let baseline = (bb.y + offset.1 + bb.height / 2.0) * scale + (line.ascent + line.descent) / 2.0;
+        let mut pen = (bb.x + offset.0) * scale;
+        let mut scaled = None;
+        for ch in run.text.chars() {
+            // Scaled glyphs come from animations, so they aren't cached
+            let (metrics, coverage) = if run.size == 1.0 {
+                &*self.glyphs.entry((key, ch, size.to_bits())).or_insert_with(|| font.rasterize(ch, size))
+            } else {
+                &*scaled.insert(font.rasterize(ch, size))
+            };
+            let x = pen.round() as i32 + metrics.xmin;
+            let y = baseline.round() as i32 - metrics.height as i32 - metrics.ymin;
+            let glyph = Glyph { x, y, width: metrics.width, baseline_row: metrics.height as i32 + metrics.ymin };
+            blend_coverage(pixmap, &glyph, coverage, color, run.skew, clip);
+            pen += metrics.advance_width + run.config.letter_spacing as f32 * scale;
+        }
+    }
+}
+
+/// A run of text drawn with one color and transform.
+struct TextRun<'a> {
+    text: &'a str,
+    config: &'a TextConfig,
+    /// The line the glyphs are centered on, in logical pixels.
+    line: BoundingBox,
+    /// Size relative to the config's font size.
+    size: f32,
+    /// Faux bold stroke and faux italic shear, as in `text_styling::Transform`.
+    weight: f32,
+    skew: f32,
+}
+
+/// Where a glyph's coverage bitmap lands, in physical pixels.
+struct Glyph {
+    x: i32,
+    y: i32,
+    width: usize,
+    /// The bitmap row the baseline runs through, which italics shear about.
+    baseline_row: i32,
+}
+
+fn load_font(asset: &FontAsset) -> Option<fontdue::Font> {
+    let bytes: Cow<[u8]> = match asset {
+        FontAsset::Bytes { data, .. } => Cow::Borrowed(data),
+        FontAsset::Path(path) => Cow::Owned(std::fs::read(path).ok()?),
+    };
+    let font = fontdue::Font::from_bytes(&*bytes, fontdue::FontSettings::default());
+    if font.is_err() {
+        crate::telemetry::warning!("Failed to load font {} for software rendering", asset.key());
+    }
+    font.ok()
+}
+
+/// Decodes the formats macroquad is built with, PNG and TGA, picked by the
+/// file extension or else by the content.
+fn decode_image(asset: &GraphicAsset) -> Option<Pixmap> {
+    let bytes: Cow<[u8]> = match asset {
+        GraphicAsset::Bytes { data, .. } => Cow::Borrowed(data),
+        GraphicAsset::Path(path) => Cow::Owned(std::fs::read(path).ok()?),
+    };
+    let format = macroquad::prelude::ImageFormat::from_path(asset.get_name()).ok();
+    let image = macroquad::texture::Image::from_file_with_format(&bytes, format).ok()?;
+    let mut pixmap = Pixmap::new(image.width as u32, image.height as u32)?;
+    for (pixel, rgba) in pixmap.pixels_mut().iter_mut().zip(image.bytes.chunks_exact(4)) {
+        *pixel = ColorU8::from_rgba(rgba[0], rgba[1], rgba[2], rgba[3]).premultiply();
+    }
+    Some(pixmap)
+}
+
+/// Blends a glyph's coverage bitmap over the pixels, within `clip`. Rows
+/// shift right by `skew` per pixel above the baseline.
+fn blend_coverage(
+    pixmap: &mut PixmapMut,
+    glyph: &Glyph,
+    coverage: &[u8],
+    color: Color,
+    skew: f32,
+    clip: (f32, f32, f32, f32),
+) {
+    let Glyph { x, y, width, baseline_row } = *glyph;
+    if width == 0 || color.a <= 0.0 {
+        return;
+    }
+    let stride = pixmap.width() as usize;
+    let (x0, y0) = (clip.0.max(0.0) as i32, clip.1.max(0.0) as i32);
+    let (x1, y1) = (clip.2 as i32, clip.3 as i32);
+    let pixels = pixmap.pixels_mut();
+    for (row, line) in coverage.chunks_exact(width).enumerate() {
+        let py = y + row as i32;
+        if py < y0 || py >= y1 {
+            continue;
+        }
+        let shift = ((baseline_row - row as i32) as f32 * skew).round() as i32;
+        for (column, &cover) in line.iter().enumerate() {
+            let px = x + shift + column as i32;
+            if px < x0 || px >= x1 || cover == 0 {
+                continue;
+            }
+            let alpha = color.a / 255.0 * cover as f32 / 255.0;
+            let pixel = &mut pixels[py as usize * stride + px as usize];
+            let keep = 1.0 - alpha;
+            let blend = |source: f32, destination: u8| (source * alpha * 255.0 + destination as f32 * keep).round() as u8;
+            let blended = PremultipliedColorU8::from_rgba(
+                blend(color.r / 255.0, pixel.red()),
+                blend(color.g / 255.0, pixel.green()),
+                blend(color.b / 255.0, pixel.blue()),
+                blend(1.0, pixel.alpha()),
+            );
+            if let Some(blended) = blended {
+                *pixel = blended;
+            }
+        }
+    }
+}
+
+fn paint(color: Color) -> Paint<'static> {
+    Paint {
+        shader: Shader::SolidColor(tiny_skia::Color::from_rgba8(
+            color.r as u8,
+            color.g as u8,
+            color.b as u8,
+            color.a as u8,
+        )),
+        anti_alias: true,
+        ..Default::default()
+    }
+}
+
+/// Maps the element's unrotated space onto the screen, turning about its
+/// center like [`crate::canvas::transform`].
+fn shape_transform(bb: &BoundingBox, rotation: Option<&ShapeRotationConfig>) -> Transform {
+    let Some(rotation) = rotation else {
+        return Transform::identity();
+    };
+    let (center_x, center_y) = (bb.x + bb.width / 2.0, bb.y + bb.height / 2.0);
+    Transform::from_translate(center_x, center_y)
+        .pre_rotate(rotation.rotation_radians.to_degrees())
+        .pre_scale(if rotation.flip_x { -1.0 } else { 1.0 }, if rotation.flip_y { -1.0 } else { 1.0 })
+        .pre_translate(-center_x, -center_y)
+}
+
+fn push_rounded_rect(builder: &mut PathBuilder, bb: &BoundingBox, radii: &CornerRadii) {
+    let mut radii = radii.clone();
+    radii.clamp_to_size(bb.width, bb.height);
+    let (tl, tr, bl, br) = (radii.top_left, radii.top_right, radii.bottom_left, radii.bottom_right);
+    let (x0, y0, x1, y1) = (bb.x, bb.y, bb.x + bb.width, bb.y + bb.height);
+    let k = ARC_HANDLE;
+    builder.move_to(x0 + tl, y0);
+    builder.line_to(x1 - tr, y0);
+    builder.cubic_to(x1 - tr + tr * k, y0, x1, y0 + tr - tr * k, x1, y0 + tr);
+    builder.line_to(x1, y1 - br);
+    builder.cubic_to(x1, y1 - br + br * k, x1 - br + br * k, y1, x1 - br, y1);
+    builder.line_to(x0 + bl, y1);
+    builder.cubic_to(x0 + bl - bl * k, y1, x0, y1 - bl + bl * k, x0, y1 - bl);
+    builder.line_to(x0, y0 + tl);
+    builder.cubic_to(x0, y0 + tl - tl * k, x0 + tl - tl * k, y0, x0 + tl, y0);
+    builder.close();
+}
+
+fn rounded_rect_path(bb: &BoundingBox, radii: &CornerRadii) -> Option<Path> {
+    if bb.width <= 0.0 || bb.height <= 0.0 {
+        return None;
+    }
+    let mut builder = PathBuilder::new();
+    push_rounded_rect(&mut builder, bb, radii);
+    builder.finish()
+}
+
+fn fill_rounded_rect(
+    pixmap: &mut PixmapMut,
+    bb: &BoundingBox,
+    radii: &CornerRadii,
+    color: Color,
+    transform: Transform,
+    mask: Option<&Mask>,
+) {
+    if color.a <= 0.0 {
+        return;
+    }
+    if let Some(path) = rounded_rect_path(bb, radii) {
+        pixmap.fill_path(&path, &paint(color), FillRule::Winding, transform, mask);
+    }
+}
+
+/// Fills the ring between the border's outer and inner rounded rectangles.
+fn fill_border(
+    pixmap: &mut PixmapMut,
+    bb: &BoundingBox,
+    border: &crate::render_commands::Border,
+    transform: Transform,
+    mask: Option<&Mask>,
+) {
+    let s = match border.position {
+        BorderPosition::Outside => 1.0,
+        BorderPosition::Middle => 0.5,
+        BorderPosition::Inside => 0.0,
+    };
+    let w = &border.width;
+    let (left, right, top, bottom) = (w.left as f32, w.right as f32, w.top as f32, w.bottom as f32);
+    let outer = BoundingBox::new(
+        bb.x - left * s,
+        bb.y - top * s,
+        bb.width + (left + right) * s,
+        bb.height + (top + bottom) * s,
+    );
+    let inner = BoundingBox::new(
+        bb.x + left * (1.0 - s),
+        bb.y + top * (1.0 - s),
+        bb.width - (left + right) * (1.0 - s),
+        bb.height - (top + bottom) * (1.0 - s),
+    );
+    let cr = &border.corner_radii;
+    let outer_radii = CornerRadii {
+        top_left: cr.top_left + left.max(top) * s,
+        top_right: cr.top_right + right.max(top) * s,
+        bottom_left: cr.bottom_left + left.max(bottom) * s,
+        bottom_right: cr.bottom_right + right.max(bottom) * s,
+    };
+    let inner_radii = CornerRadii {
+        top_left: (cr.top_left - left.max(top) * (1.0 - s)).max(0.0),
+        top_right: (cr.top_right - right.max(top) * (1.0 - s)).max(0.0),
+        bottom_left: (cr.bottom_left - left.max(bottom) * (1.0 - s)).max(0.0),
+        bottom_right: (cr.bottom_right - right.max(bottom) * (1.0 - s)).max(0.0),
+    };
+    if border.color.a <= 0.0 || outer.width <= 0.0 || outer.height <= 0.0 {
+        return;
+    }
+    let mut builder = PathBuilder::new();
+    push_rounded_rect(&mut builder, &outer, &outer_radii);
+    if inner.width > 0.0 && inner.height > 0.0 {
+        push_rounded_rect(&mut builder, &inner, &inner_radii);
+    }
+    if let Some(path) = builder.finish() {
+        pixmap.fill_path(&path, &paint(border.color), FillRule::EvenOdd, transform, mask);
+    }
+}
+
+fn draw_paint_command(pixmap: &mut PixmapMut, command: &PaintCommand, transform: Transform, mask: Option<&Mask>) {
+    let stroke = |thickness: f32| Stroke { width: thickness, line_cap: LineCap::Round, ..Default::default() };
+    match command {
+        PaintCommand::Line { from, to, thickness, color } => {
+            let mut builder = PathBuilder::new();
+            builder.move_to(from.x, from.y);
+            builder.line_to(to.x, to.y);
+            if let Some(path) = builder.finish() {
+                pixmap.stroke_path(&path, &paint(*color), &stroke(*thickness), transform, mask);
+            }
+        }
+        PaintCommand::Circle { center, radius, fill, stroke: outline } => {
+            let Some(path) = PathBuilder::from_circle(center.x, center.y, *radius) else {
+                return;
+            };
+            if let Some(color) = fill {
+                pixmap.fill_path(&path, &paint(*color), FillRule::Winding, transform, mask);
+            }
+            if let Some(outline) = outline {
+                pixmap.stroke_path(&path, &paint(outline.color), &stroke(outline.thickness), transform, mask);
+            }
+        }
+        PaintCommand::Path { points, closed, fill, stroke: outline } => {
+            let mut builder = PathBuilder::new();
+            let mut points = points.iter();
+            let Some(first) = points.next() else {
+                return;
+            };
+            builder.move_to(first.x, first.y);
+            for point in points {
+                builder.line_to(point.x, point.y);
+            }
+            if *closed {
+                builder.close();
+            }
+            let Some(path) = builder.finish() else {
+                return;
+            };
+            if let Some(color) = fill {
+                pixmap.fill_path(&path, &paint(*color), FillRule::EvenOdd, transform, mask);
+            }
+            if let Some(outline) = outline {
+                pixmap.stroke_path(&path, &paint(outline.color), &stroke(outline.thickness), transform, mask);
+            }
+        }
+    }
+}
+
+fn intersect(a: (f32, f32, f32, f32), b: (f32, f32, f32, f32)) -> (f32, f32, f32, f32) {
+    (a.0.max(b.0), a.1.max(b.1), a.2.min(b.2), a.3.min(b.3))
+}
+
+/// A mask letting through only the pixels inside `clip`, in physical pixels.
+fn clip_to_mask(width: u32, height: u32, clip: (f32, f32, f32, f32)) -> Option<Mask> {
+    let mut mask = Mask::new(width, height)?;
+    if let Some(rect) = Rect::from_ltrb(clip.0.round(), clip.1.round(), clip.2.round(), clip.3.round()) {
+        mask.fill_path(&PathBuilder::from_rect(rect), FillRule::Winding, false, Transform::identity());
+    }
+    Some(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed;
+    use crate::layout::LayoutDirection;
+    use crate::Ply;
+
+    #[test]
+    fn test_software_renderer_draws_shapes_with_clipping_and_scale() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(40.0, 20.0));
+        let mut ui = ply.begin();
+        ui.element()
+            .width(fixed!(40.0))
+            .height(fixed!(20.0))
+            .layout(|l| l.direction(LayoutDirection::LeftToRight))
+            .children(|ui| {
+                ui.element()
+                    .width(fixed!(20.0))
+                    .height(fixed!(20.0))
+                    .background_color(0xFF0000)
+                    .corner_radius(6.0)
+                    .empty();
+                // Clipped to its left half
+                ui.element().width(fixed!(10.0)).height(fixed!(20.0)).overflow(|o| o.clip()).children(|ui| {
+                    ui.element()
+                        .width(fixed!(20.0))
+                        .height(fixed!(20.0))
+                        .background_color(0x0000FF)
+                        .border(|b| b.color(0x00FF00).all(2).position(BorderPosition::Inside))
+                        .empty();
+                });
+            });
+        let commands = ui.eval();
+
+        let mut software = SoftwareRenderer::new();
+        software.scale(2.0);
+        let image = software.render(&commands, 80, 40);
+        assert_eq!((image.width, image.height), (80, 40));
+        assert_eq!(image.pixel(20, 20), Some([255, 0, 0, 255]));
+        // The rounded-off corner stays transparent
+        assert_eq!(image.pixel(0, 0), Some([0, 0, 0, 0]));
+        // The border lies over the blue fill, which stops at the clip
+        assert_eq!(image.pixel(42, 20), Some([0, 255, 0, 255]));
+        assert_eq!(image.pixel(50, 20), Some([0, 0, 255, 255]));
+        assert_eq!(image.pixel(70, 20), Some([0, 0, 0, 0]));
+
+        // Drawing over a framebuffer of the wrong size does nothing
+        assert!(!software.render_into(&commands, &mut [0; 16], 80, 40));
+    }
+
+    #[test]
+    fn test_software_renderer_composites_visual_rotation() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(40.0, 20.0));
+        let mut ui = ply.begin();
+        ui.element()
+            .width(fixed!(40.0))
+            .height(fixed!(20.0))
+            .rotate_visual(|r| r.flip_x())
+            .children(|ui| {
+                ui.element().width(fixed!(20.0)).height(fixed!(20.0)).background_color(0xFF0000).empty();
+                ui.element().width(fixed!(20.0)).height(fixed!(20.0)).background_color(0x0000FF).empty();
+            });
+        let commands = ui.eval();
+
+        let image = SoftwareRenderer::new().render(&commands, 40, 20);
+        assert_eq!(image.pixel(10, 10), Some([0, 0, 255, 255]));
+        assert_eq!(image.pixel(30, 10), Some([255, 0, 0, 255]));
+
+        // Quarter turn about the top-left corner: the group swings down out of view
+        let mut ui = ply.begin();
+        ui.element()
+            .width(fixed!(40.0))
+            .height(fixed!(20.0))
+            .rotate_visual(|r| r.degrees(90.0).pivot((0.0, 0.0)))
+            .background_color(0xFF0000)
+            .empty();
+        let commands = ui.eval();
+        let image = SoftwareRenderer::new().render(&commands, 40, 20);
+        assert_eq!(image.pixel(30, 10), Some([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_software_renderer_decodes_tga_images() {
+        // 2×1 uncompressed true-color TGA, top-left origin: red, then blue
+        static TGA: [u8; 26] = [
+            0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 1, 0, 32, 0x28,
+            0, 0, 255, 255, 255, 0, 0, 255,
+        ];
+        static IMAGE: GraphicAsset = GraphicAsset::Bytes { file_name: "pair.tga", data: &TGA };
+
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(40.0, 20.0));
+        let mut ui = ply.begin();
+        ui.element().width(fixed!(40.0)).height(fixed!(20.0)).image(&IMAGE).empty();
+        let commands = ui.eval();
+
+        let image = SoftwareRenderer::new().render(&commands, 40, 20);
+        assert_eq!(image.pixel(5, 10), Some([255, 0, 0, 255]));
+        assert_eq!(image.pixel(35, 10), Some([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_software_renderer_draws_text_input_selection_and_caret() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(100.0, 20.0));
+        let software = SoftwareRenderer::new();
+        // Without a font, text measures 8px per character at size 16
+        ply.set_measure_text_function(software.measure_text_function());
+        ply.set_text_value("name", "abcdef");
+        ply.set_focus("name");
+        ply.set_selection("name", 0, 2);
+
+        let mut ui = ply.begin();
+        ui.element()
+            .id("name")
+            .width(fixed!(100.0))
+            .height(fixed!(20.0))
+            .text_input(|t| t.font_size(16).selection_color(0x00FF00).cursor_color(0xFF0000))
+            .empty();
+        let commands = ui.eval();
+
+        let image = SoftwareRenderer::new().render(&commands, 100, 20);
+        // The selection covers the first two characters, with the caret after them
+        assert_eq!(image.pixel(4, 10), Some([0, 255, 0, 255]));
+        assert_eq!(image.pixel(16, 10), Some([255, 0, 0, 255]));
+        assert_eq!(image.pixel(40, 10), Some([0, 0, 0, 0]));
+    }
+
+    #[cfg(feature = "text-styling")]
+    #[test]
+    fn test_software_renderer_measures_styled_text_without_markup() {
+        let measure = SoftwareRenderer::new().measure_text_function();
+        let config = TextConfig { font_size: 16, ..Default::default() };
+        assert_eq!(measure("{color=red|ab}c", &config), measure("abc", &config));
+    }
+
+    #[test]
+    fn test_software_renderer_draws_frames_through_renderer_trait() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(40.0, 20.0));
+        let mut software = SoftwareRenderer::new();
+        software.scale(2.0);
+        ply.set_measure_text_function(software.measure_text_function());
+
+        let mut ui = ply.begin();
+        ui.element()
+            .width(fixed!(40.0))
+            .height(fixed!(20.0))
+            .rotate_visual(|r| r.flip_x())
+            .layout(|l| l.direction(LayoutDirection::LeftToRight))
+            .children(|ui| {
+                ui.element().width(fixed!(20.0)).height(fixed!(20.0)).background_color(0xFF0000).empty();
+                ui.element().width(fixed!(20.0)).height(fixed!(20.0)).background_color(0x0000FF).empty();
+            });
+        crate::jobs::block_on_native(ply.show_with(&mut software));
+
+        // Sized by the layout and scale, with the group composited
+        let image = software.take_frame().unwrap();
+        assert_eq!((image.width, image.height), (80, 40));
+        assert_eq!(image.pixel(20, 20), Some([0, 0, 255, 255]));
+        assert_eq!(image.pixel(60, 20), Some([255, 0, 0, 255]));
+        assert!(software.take_frame().is_none());
+    }
+}
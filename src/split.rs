@@ -0,0 +1,301 @@
+//! Split panes: two panels with a draggable divider between them.
+//!
+//! ```ignore
+//! ui.split("editor", |s| s
+//!     .horizontal()
+//!     .initial(0.3)
+//!     .min_sizes(120.0, 200.0)
+//!     .first(|ui| file_tree(ui))
+//!     .second(|ui| editor(ui))
+//! );
+//! ```
+//!
+//! The split fills its parent. Its ratio is kept between frames per ID, so
+//! `initial` only applies the first time an ID is drawn. Dragging the divider
+//! resizes the panels within their minimum sizes and shows a resize cursor.
+
+use macroquad::miniquad::CursorIcon;
+
+use crate::color::Color;
+use crate::id::Id;
+use crate::layout::{LayoutDirection, Sizing};
+use crate::Ui;
+
+/// Share of the split taken by the first panel, kept per split ID.
+struct SplitRatio(f32);
+
+/// Declares the contents of one panel.
+type PanelFn<'f, CustomElementData> = Box<dyn FnOnce(&mut Ui<'_, CustomElementData>) + 'f>;
+
+/// Builder for configuring a split pane via closure.
+pub struct SplitBuilder<'f, CustomElementData: Clone + Default + std::fmt::Debug = ()> {
+    pub(crate) direction: LayoutDirection,
+    pub(crate) initial: f32,
+    pub(crate) min_sizes: (f32, f32),
+    pub(crate) handle_size: f32,
+    pub(crate) handle_color: Color,
+    pub(crate) handle_hover_color: Color,
+    pub(crate) first: Option<PanelFn<'f, CustomElementData>>,
+    pub(crate) second: Option<PanelFn<'f, CustomElementData>>,
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> Default for SplitBuilder<'_, CustomElementData> {
+    fn default() -> Self {
+        Self {
+            direction: LayoutDirection::LeftToRight,
+            initial: 0.5,
+            min_sizes: (0.0, 0.0),
+            handle_size: 6.0,
+            handle_color: Color::rgba(60.0, 60.0, 60.0, 255.0),
+            handle_hover_color: Color::rgba(58.0, 123.0, 213.0, 255.0),
+            first: None,
+            second: None,
+        }
+    }
+}
+
+impl<'f, CustomElementData: Clone + Default + std::fmt::Debug> SplitBuilder<'f, CustomElementData> {
+    /// Puts the panels side by side, with a vertical divider. This is the default.
+    #[inline]
+    pub fn horizontal(&mut self) -> &mut Self {
+        self.direction = LayoutDirection::LeftToRight;
+        self
+    }
+
+    /// Stacks the panels, with a horizontal divider.
+    #[inline]
+    pub fn vertical(&mut self) -> &mut Self {
+        self.direction = LayoutDirection::TopToBottom;
+        self
+    }
+
+    /// Sets the share of the split taken by the first panel, from `0.0` to `1.0`,
+    /// the first time it is drawn. Defaults to `0.5`.
+    #[inline]
+    pub fn initial(&mut self, ratio: f32) -> &mut Self {
+        self.initial = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the minimum sizes of the first and second panel, in pixels.
+    #[inline]
+    pub fn min_sizes(&mut self, first: f32, second: f32) -> &mut Self {
+        self.min_sizes = (first.max(0.0), second.max(0.0));
+        self
+    }
+
+    /// Sets the thickness of the divider. Defaults to 6.
+    #[inline]
+    pub fn handle_size(&mut self, size: f32) -> &mut Self {
+        self.handle_size = size;
+        self
+    }
+
+    /// Sets the color of the divider.
+    #[inline]
+    pub fn handle_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.handle_color = color.into();
+        self
+    }
+
+    /// Sets the color of the divider while hovered or dragged.
+    #[inline]
+    pub fn handle_hover_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.handle_hover_color = color.into();
+        self
+    }
+
+    /// Declares the contents of the first (left or top) panel.
+    #[inline]
+    pub fn first(&mut self, content: impl FnOnce(&mut Ui<'_, CustomElementData>) + 'f) -> &mut Self {
+        self.first = Some(Box::new(content));
+        self
+    }
+
+    /// Declares the contents of the second (right or bottom) panel.
+    #[inline]
+    pub fn second(&mut self, content: impl FnOnce(&mut Ui<'_, CustomElementData>) + 'f) -> &mut Self {
+        self.second = Some(Box::new(content));
+        self
+    }
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Adds two panels with a draggable divider, filling the parent.
+    ///
+    /// Returns the share of the split taken by the first panel.
+    ///
+    /// ```ignore
+    /// ui.split("inspector", |s| s.vertical().first(|ui| scene(ui)).second(|ui| properties(ui)));
+    /// ```
+    pub fn split<'f>(
+        &mut self,
+        id: impl Into<Id>,
+        config_fn: impl for<'a> FnOnce(
+            &'a mut SplitBuilder<'f, CustomElementData>,
+        ) -> &'a mut SplitBuilder<'f, CustomElementData>,
+    ) -> f32 {
+        let mut config = SplitBuilder::default();
+        config_fn(&mut config);
+        let SplitBuilder {
+            direction,
+            initial,
+            min_sizes,
+            handle_size,
+            handle_color,
+            handle_hover_color,
+            first,
+            second,
+        } = config;
+
+        let id = id.into();
+        let handle_id = id.child("handle");
+        let horizontal = direction == LayoutDirection::LeftToRight;
+        let mut ratio = self.ply.component_states.widget_state(id.id, || SplitRatio(initial)).0;
+
+        // Sizes come from last frame's layout, so the first frame uses the ratio as is
+        if let Some(bounds) = self.ply.context.get_element_data(id.clone()) {
+            let (start, total) = if horizontal { (bounds.x, bounds.width) } else { (bounds.y, bounds.height) };
+            if total > 0.0 {
+                let mut first_size = ratio * total;
+                if self.ply.is_pressed(handle_id.clone()) {
+                    let pointer = self.ply.context.pointer_position();
+                    let along = if horizontal { pointer.x } else { pointer.y };
+                    first_size = along - start - handle_size / 2.0;
+                }
+                // The first panel's minimum wins when both don't fit
+                let max_first = total - handle_size - min_sizes.1;
+                first_size = first_size.min(max_first).max(min_sizes.0);
+                ratio = (first_size / total).clamp(0.0, 1.0);
+            }
+        }
+        self.ply.component_states.widget_state(id.id, || SplitRatio(initial)).0 = ratio;
+
+        let (first_size, handle_width, handle_height, cursor) = if horizontal {
+            (
+                (Sizing::Percent(ratio), Sizing::Grow(0.0, f32::MAX, 1.0)),
+                Sizing::Fixed(handle_size),
+                Sizing::Grow(0.0, f32::MAX, 1.0),
+                CursorIcon::EWResize,
+            )
+        } else {
+            (
+                (Sizing::Grow(0.0, f32::MAX, 1.0), Sizing::Percent(ratio)),
+                Sizing::Grow(0.0, f32::MAX, 1.0),
+                Sizing::Fixed(handle_size),
+                CursorIcon::NSResize,
+            )
+        };
+
+        self.element()
+            .id(id.clone())
+            .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+            .height(Sizing::Grow(0.0, f32::MAX, 1.0))
+            .layout(|l| l.direction(direction))
+            .children(|ui| {
+                ui.element()
+                    .id(id.child("first"))
+                    .width(first_size.0)
+                    .height(first_size.1)
+                    .overflow(|o| o.clip())
+                    .children(|ui| {
+                        if let Some(first) = first {
+                            first(ui);
+                        }
+                    });
+
+                ui.element()
+                    .id(handle_id.clone())
+                    .width(handle_width)
+                    .height(handle_height)
+                    .background_color(handle_color)
+                    .style_states(|s| s
+                        .hover(|h| h.background_color(handle_hover_color))
+                        .pressed(|p| p.background_color(handle_hover_color))
+                    )
+                    .children(|ui| {
+                        if ui.hovered() || ui.pressed() {
+                            ui.ply.requested_cursor = Some(cursor);
+                        }
+                    });
+
+                ui.element()
+                    .id(id.child("second"))
+                    .width(Sizing::Grow(0.0, f32::MAX, 1.0))
+                    .height(Sizing::Grow(0.0, f32::MAX, 1.0))
+                    .overflow(|o| o.clip())
+                    .children(|ui| {
+                        if let Some(second) = second {
+                            second(ui);
+                        }
+                    });
+            });
+
+        ratio
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{Dimensions, Vector2};
+    use crate::Ply;
+
+    #[test]
+    fn test_split_drags_within_min_sizes() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 200.0));
+        let frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            let ratio = ui.split("split", |s| s.initial(0.25).min_sizes(50.0, 100.0));
+            ui.eval();
+            ratio
+        };
+        let first_width = |ply: &Ply<()>| ply.context.get_element_data(Id::new("split").child("first")).unwrap().width;
+
+        assert_eq!(frame(&mut ply), 0.25);
+        assert_eq!(first_width(&ply), 100.0);
+
+        // Drag the divider to x = 200
+        ply.context.set_pointer_state(Vector2::new(103.0, 100.0), true);
+        frame(&mut ply);
+        ply.context.set_pointer_state(Vector2::new(203.0, 100.0), true);
+        assert_eq!(frame(&mut ply), 0.5);
+        assert_eq!(first_width(&ply), 200.0);
+
+        // Past the second panel's minimum, it stops
+        ply.context.set_pointer_state(Vector2::new(390.0, 100.0), true);
+        frame(&mut ply);
+        assert!((first_width(&ply) - 294.0).abs() < 0.01);
+
+        // Released: the ratio stays
+        ply.context.set_pointer_state(Vector2::new(390.0, 100.0), false);
+        frame(&mut ply);
+        assert!((first_width(&ply) - 294.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_split_ratio_is_per_ply_and_dropped_when_not_drawn() {
+        let frame = |ply: &mut Ply<()>, draw: bool| {
+            let mut ui = ply.begin();
+            let ratio = draw.then(|| ui.split("split", |s| s.initial(0.25)));
+            ui.eval();
+            ratio
+        };
+        let mut first = Ply::<()>::new_headless(Dimensions::new(400.0, 200.0));
+        let mut second = Ply::<()>::new_headless(Dimensions::new(400.0, 200.0));
+        frame(&mut first, true);
+        first.context.set_pointer_state(Vector2::new(103.0, 100.0), true);
+        frame(&mut first, true);
+        first.context.set_pointer_state(Vector2::new(203.0, 100.0), true);
+        assert_eq!(frame(&mut first, true), Some(0.5));
+        first.context.set_pointer_state(Vector2::new(203.0, 100.0), false);
+
+        // Another Ply on the same thread has its own ratio for the same ID
+        assert_eq!(frame(&mut second, true), Some(0.25));
+        assert_eq!(frame(&mut first, true), Some(0.5));
+
+        // A frame without the split forgets its ratio
+        frame(&mut first, false);
+        assert_eq!(frame(&mut first, true), Some(0.25));
+    }
+}
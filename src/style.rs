@@ -0,0 +1,232 @@
+//! Reusable style classes: a bundle of element settings applied with
+//! [`ElementBuilder::style`](crate::ElementBuilder::style).
+//!
+//! ```ignore
+//! let card = Style::new()
+//!     .background_color(0x2E2A28)
+//!     .corner_radius(8.0)
+//!     .padding(12)
+//!     .gap(8);
+//! let highlighted = card.clone().merged(&Style::new().border(0xFFCC00, 2));
+//!
+//! ui.element()
+//!     .style(&card)
+//!     .width(grow!()) // settings after `.style()` override the style
+//!     .children(|ui| { ... });
+//! ```
+//!
+//! Only the settings made on a style are applied, so styles can be layered:
+//! `.style(&base).style(&accent)`. Theme tokens resolve when the style is
+//! built, so build styles inside the frame to follow theme swaps.
+
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::engine::{ElementDeclaration, PaddingConfig};
+use crate::layout::{CornerRadius, LayoutDirection, Padding, Sizing};
+
+/// A reusable bundle of element settings.
+#[derive(Debug, Clone, Default)]
+pub struct Style {
+    width: Option<Sizing>,
+    height: Option<Sizing>,
+    background_color: Option<Color>,
+    corner_radius: Option<CornerRadius>,
+    padding: Option<PaddingConfig>,
+    gap: Option<u16>,
+    direction: Option<LayoutDirection>,
+    align: Option<(AlignX, AlignY)>,
+    border: Option<(Color, u16)>,
+}
+
+impl Style {
+    /// Creates an empty style that changes nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the width.
+    pub fn width(mut self, width: Sizing) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Sets the height.
+    pub fn height(mut self, height: Sizing) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Sets the background color.
+    pub fn background_color(mut self, color: impl Into<Color>) -> Self {
+        self.background_color = Some(color.into());
+        self
+    }
+
+    /// Sets the corner radius.
+    pub fn corner_radius(mut self, radius: impl Into<CornerRadius>) -> Self {
+        self.corner_radius = Some(radius.into());
+        self
+    }
+
+    /// Sets the layout padding.
+    pub fn padding(mut self, padding: impl Into<Padding>) -> Self {
+        let padding = padding.into();
+        self.padding = Some(PaddingConfig {
+            left: padding.left,
+            right: padding.right,
+            top: padding.top,
+            bottom: padding.bottom,
+        });
+        self
+    }
+
+    /// Sets the spacing between children.
+    pub fn gap(mut self, gap: u16) -> Self {
+        self.gap = Some(gap);
+        self
+    }
+
+    /// Sets the layout direction.
+    pub fn direction(mut self, direction: LayoutDirection) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Sets the alignment of children.
+    pub fn align(mut self, x: AlignX, y: AlignY) -> Self {
+        self.align = Some((x, y));
+        self
+    }
+
+    /// Sets a border of the same width on all sides.
+    pub fn border(mut self, color: impl Into<Color>, width: u16) -> Self {
+        self.border = Some((color.into(), width));
+        self
+    }
+
+    /// Returns this style with the settings made on `other` on top.
+    pub fn merged(mut self, other: &Style) -> Self {
+        self.width = other.width.or(self.width);
+        self.height = other.height.or(self.height);
+        self.background_color = other.background_color.or(self.background_color);
+        self.corner_radius = other.corner_radius.or(self.corner_radius);
+        self.padding = other.padding.or(self.padding);
+        self.gap = other.gap.or(self.gap);
+        self.direction = other.direction.or(self.direction);
+        self.align = other.align.or(self.align);
+        self.border = other.border.or(self.border);
+        self
+    }
+
+    /// Writes the settings made on this style into an element declaration.
+    pub(crate) fn apply<CustomElementData: Clone + Default + std::fmt::Debug>(
+        &self,
+        declaration: &mut ElementDeclaration<CustomElementData>,
+    ) {
+        let layout = &mut declaration.layout;
+        if let Some(width) = self.width {
+            layout.sizing.width = width.into();
+        }
+        if let Some(height) = self.height {
+            layout.sizing.height = height.into();
+        }
+        if let Some(padding) = self.padding {
+            layout.padding = padding;
+        }
+        if let Some(gap) = self.gap {
+            layout.child_gap = gap;
+        }
+        if let Some(direction) = self.direction {
+            layout.layout_direction = direction;
+        }
+        if let Some((x, y)) = self.align {
+            layout.child_alignment.x = x;
+            layout.child_alignment.y = y;
+        }
+        if let Some(color) = self.background_color {
+            declaration.background_color = color;
+        }
+        if let Some(radius) = self.corner_radius {
+            declaration.corner_radius = radius;
+        }
+        if let Some((color, width)) = self.border {
+            let border = &mut declaration.border;
+            border.color = color;
+            border.width.left = width;
+            border.width.right = width;
+            border.width.top = width;
+            border.width.bottom = width;
+        }
+    }
+}
+
+/// Style overrides for interaction states, set with
+/// [`ElementBuilder::style_states`](crate::ElementBuilder::style_states).
+///
+/// Overrides are layered in the order hover, focused, pressed, so a pressed
+/// button that is also hovered gets the pressed look. A disabled element only
+/// gets the disabled overrides.
+#[derive(Debug, Clone, Default)]
+pub struct StyleStates {
+    hover: Option<Style>,
+    focused: Option<Style>,
+    pressed: Option<Style>,
+    disabled: Option<Style>,
+    is_disabled: bool,
+}
+
+impl StyleStates {
+    /// Overrides applied while the pointer is over the element.
+    #[inline]
+    pub fn hover(&mut self, f: impl FnOnce(Style) -> Style) -> &mut Self {
+        self.hover = Some(f(self.hover.take().unwrap_or_default()));
+        self
+    }
+
+    /// Overrides applied while the element has focus.
+    #[inline]
+    pub fn focused(&mut self, f: impl FnOnce(Style) -> Style) -> &mut Self {
+        self.focused = Some(f(self.focused.take().unwrap_or_default()));
+        self
+    }
+
+    /// Overrides applied while the element is held down by the pointer or keyboard.
+    #[inline]
+    pub fn pressed(&mut self, f: impl FnOnce(Style) -> Style) -> &mut Self {
+        self.pressed = Some(f(self.pressed.take().unwrap_or_default()));
+        self
+    }
+
+    /// Overrides applied instead of all others when `is_disabled` is true.
+    #[inline]
+    pub fn disabled(&mut self, is_disabled: bool, f: impl FnOnce(Style) -> Style) -> &mut Self {
+        self.disabled = Some(f(self.disabled.take().unwrap_or_default()));
+        self.is_disabled = is_disabled;
+        self
+    }
+
+    /// Applies the overrides for the given states to an element declaration.
+    pub(crate) fn apply<CustomElementData: Clone + Default + std::fmt::Debug>(
+        &self,
+        hovered: bool,
+        focused: bool,
+        pressed: bool,
+        declaration: &mut ElementDeclaration<CustomElementData>,
+    ) {
+        if self.is_disabled {
+            if let Some(ref style) = self.disabled {
+                style.apply(declaration);
+            }
+            return;
+        }
+        for (active, style) in [
+            (hovered, &self.hover),
+            (focused, &self.focused),
+            (pressed, &self.pressed),
+        ] {
+            if let (true, Some(style)) = (active, style) {
+                style.apply(declaration);
+            }
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! Stylesheets: a theme and named styles loaded from a JSON file, reloaded
+//! while the app runs.
+//!
+//! ```json
+//! {
+//!   "theme": {
+//!     "surface": "#202124",
+//!     "accent": [255, 204, 0],
+//!     "radius": 8,
+//!     "space": 12
+//!   },
+//!   "styles": {
+//!     "card": {
+//!       "background_color": "surface",
+//!       "corner_radius": "radius",
+//!       "padding": "space",
+//!       "gap": 8,
+//!       "direction": "top_to_bottom",
+//!       "align": ["center_x", "center_y"],
+//!       "border": { "color": "accent", "width": 2 },
+//!       "width": "grow",
+//!       "height": 120
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! ```ignore
+//! ply.load_stylesheet("assets/style.json");
+//!
+//! ui.element().class("card").children(|ui| { ... });
+//! ```
+//!
+//! Colors are `"#RRGGBB"`, `"#RRGGBBAA"`, `[r, g, b]` or `[r, g, b, a]`.
+//! Sizes are `120` (fixed), `"grow"`, `"fit"` or `"50%"`. Any color or
+//! number can be the name of a theme token instead. Padding is one number
+//! or `[top, right, bottom, left]`.
+//!
+//! The file is checked about once a second, through the file system natively
+//! and by fetching it on the web. When it changes, its theme replaces the
+//! current one and its styles apply from the next frame. A file that fails
+//! to parse is reported with a warning and the previous stylesheet is kept.
+
+use rustc_hash::FxHashMap;
+use serde_json::{Map, Value};
+
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::layout::{LayoutDirection, Padding, Sizing};
+use crate::style::Style;
+use crate::theme::{Theme, TokenValue};
+use crate::watch::WatchedFile;
+use crate::Ply;
+
+/// A theme and named styles, parsed from JSON.
+#[derive(Debug, Clone, Default)]
+pub struct Stylesheet {
+    theme: Option<Theme>,
+    styles: FxHashMap<String, Style>,
+}
+
+impl Stylesheet {
+    /// Parses a stylesheet. Token names in styles resolve against the
+    /// stylesheet's own theme, or against `fallback_theme` if it has none.
+    pub fn parse(json: &str, fallback_theme: &Theme) -> Result<Self, String> {
+        let root: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let root = as_object(&root, "stylesheet")?;
+
+        let theme = match root.get("theme") {
+            Some(theme) => Some(parse_theme(theme)?),
+            None => None,
+        };
+        let tokens = theme.as_ref().unwrap_or(fallback_theme);
+
+        let mut styles = FxHashMap::default();
+        if let Some(entries) = root.get("styles") {
+            for (name, style) in as_object(entries, "styles")? {
+                let path = format!("styles.{name}");
+                styles.insert(name.clone(), parse_style(style, tokens, &path)?);
+            }
+        }
+
+        Ok(Self { theme, styles })
+    }
+
+    /// Returns the theme section, if the stylesheet has one.
+    pub fn theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    /// Returns the style with the given name.
+    pub fn get(&self, name: &str) -> Option<&Style> {
+        self.styles.get(name)
+    }
+}
+
+fn as_object<'a>(value: &'a Value, path: &str) -> Result<&'a Map<String, Value>, String> {
+    value.as_object().ok_or_else(|| format!("{path}: expected an object"))
+}
+
+fn parse_theme(value: &Value) -> Result<Theme, String> {
+    let mut theme = Theme::new();
+    for (name, token) in as_object(value, "theme")? {
+        let path = format!("theme.{name}");
+        let value = match token {
+            Value::Number(number) => TokenValue::Number(number.as_f64().unwrap_or_default() as f32),
+            _ => TokenValue::Color(parse_color_literal(token, &path)?),
+        };
+        theme.set(name, value);
+    }
+    Ok(theme)
+}
+
+fn parse_color_literal(value: &Value, path: &str) -> Result<Color, String> {
+    match value {
+        Value::String(hex) if hex.starts_with('#') => {
+            let digits = &hex[1..];
+            let channel = |i: usize| {
+                digits
+                    .get(i * 2..i * 2 + 2)
+                    .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                    .ok_or_else(|| format!("{path}: invalid hex color \"{hex}\""))
+            };
+            match digits.len() {
+                6 => Ok(Color::u_rgb(channel(0)?, channel(1)?, channel(2)?)),
+                8 => Ok(Color::u_rgba(channel(0)?, channel(1)?, channel(2)?, channel(3)?)),
+                _ => Err(format!("{path}: invalid hex color \"{hex}\"")),
+            }
+        }
+        Value::Array(channels) if channels.len() == 3 || channels.len() == 4 => {
+            let mut rgba = [255.0; 4];
+            for (i, channel) in channels.iter().enumerate() {
+                rgba[i] = channel
+                    .as_f64()
+                    .ok_or_else(|| format!("{path}: color channels must be numbers"))?
+                    as f32;
+            }
+            Ok(Color::rgba(rgba[0], rgba[1], rgba[2], rgba[3]))
+        }
+        _ => Err(format!("{path}: expected a color")),
+    }
+}
+
+fn parse_color(value: &Value, tokens: &Theme, path: &str) -> Result<Color, String> {
+    match value {
+        Value::String(name) if !name.starts_with('#') => tokens
+            .get_color(name)
+            .ok_or_else(|| format!("{path}: no color token named \"{name}\"")),
+        _ => parse_color_literal(value, path),
+    }
+}
+
+fn parse_number(value: &Value, tokens: &Theme, path: &str) -> Result<f32, String> {
+    match value {
+        Value::Number(number) => Ok(number.as_f64().unwrap_or_default() as f32),
+        Value::String(name) => tokens
+            .get_number(name)
+            .ok_or_else(|| format!("{path}: no number token named \"{name}\"")),
+        _ => Err(format!("{path}: expected a number")),
+    }
+}
+
+fn parse_u16(value: &Value, tokens: &Theme, path: &str) -> Result<u16, String> {
+    Ok(parse_number(value, tokens, path)?.round().clamp(0.0, u16::MAX as f32) as u16)
+}
+
+fn parse_sizing(value: &Value, tokens: &Theme, path: &str) -> Result<Sizing, String> {
+    match value {
+        Value::String(keyword) if keyword == "grow" => Ok(Sizing::Grow(0.0, f32::MAX, 1.0)),
+        Value::String(keyword) if keyword == "fit" => Ok(Sizing::Fit(0.0, f32::MAX)),
+        Value::String(keyword) if keyword == "min_content" => Ok(Sizing::MinContent),
+        Value::String(keyword) if keyword == "max_content" => Ok(Sizing::MaxContent),
+        Value::String(percent) if percent.ends_with('%') => percent[..percent.len() - 1]
+            .trim()
+            .parse::<f32>()
+            .map(|percent| Sizing::Percent((percent / 100.0).clamp(0.0, 1.0)))
+            .map_err(|_| format!("{path}: invalid percentage \"{percent}\"")),
+        _ => match value.as_str().and_then(Sizing::parse_viewport) {
+            Some(sizing) => Ok(sizing),
+            None => Ok(Sizing::Fixed(parse_number(value, tokens, path)?)),
+        },
+    }
+}
+
+fn parse_padding(value: &Value, tokens: &Theme, path: &str) -> Result<Padding, String> {
+    match value {
+        Value::Array(sides) if sides.len() == 4 => {
+            let side = |i: usize| parse_u16(&sides[i], tokens, path);
+            Ok(Padding::from((side(0)?, side(1)?, side(2)?, side(3)?)))
+        }
+        _ => Ok(Padding::all(parse_u16(value, tokens, path)?)),
+    }
+}
+
+fn parse_style(value: &Value, tokens: &Theme, path: &str) -> Result<Style, String> {
+    let mut style = Style::new();
+    for (key, value) in as_object(value, path)? {
+        let path = format!("{path}.{key}");
+        style = match key.as_str() {
+            "width" => style.width(parse_sizing(value, tokens, &path)?),
+            "height" => style.height(parse_sizing(value, tokens, &path)?),
+            "background_color" => style.background_color(parse_color(value, tokens, &path)?),
+            "corner_radius" => style.corner_radius(parse_number(value, tokens, &path)?),
+            "padding" => style.padding(parse_padding(value, tokens, &path)?),
+            "gap" => style.gap(parse_u16(value, tokens, &path)?),
+            "direction" => style.direction(match value.as_str() {
+                Some("left_to_right") => LayoutDirection::LeftToRight,
+                Some("top_to_bottom") => LayoutDirection::TopToBottom,
+                _ => return Err(format!("{path}: expected \"left_to_right\" or \"top_to_bottom\"")),
+            }),
+            "align" => {
+                let pair = value.as_array().filter(|pair| pair.len() == 2);
+                let (x, y) = match pair {
+                    Some(pair) => (pair[0].as_str(), pair[1].as_str()),
+                    None => return Err(format!("{path}: expected [x, y]")),
+                };
+                let x = match x {
+                    Some("left") => AlignX::Left,
+                    Some("center_x") => AlignX::CenterX,
+                    Some("right") => AlignX::Right,
+                    _ => return Err(format!("{path}: x must be \"left\", \"center_x\" or \"right\"")),
+                };
+                let y = match y {
+                    Some("top") => AlignY::Top,
+                    Some("center_y") => AlignY::CenterY,
+                    Some("bottom") => AlignY::Bottom,
+                    Some("baseline") => AlignY::Baseline,
+                    _ => return Err(format!("{path}: y must be \"top\", \"center_y\", \"bottom\" or \"baseline\"")),
+                };
+                style.align(x, y)
+            }
+            "border" => {
+                let border = as_object(value, &path)?;
+                let color = border
+                    .get("color")
+                    .ok_or_else(|| format!("{path}: missing color"))?;
+                let width = border
+                    .get("width")
+                    .ok_or_else(|| format!("{path}: missing width"))?;
+                style.border(
+                    parse_color(color, tokens, &format!("{path}.color"))?,
+                    parse_u16(width, tokens, &format!("{path}.width"))?,
+                )
+            }
+            _ => return Err(format!("{path}: unknown setting")),
+        };
+    }
+    Ok(style)
+}
+
+/// A watched stylesheet file, kept by [`Ply`].
+#[derive(Default)]
+pub(crate) struct StylesheetState {
+    file: Option<WatchedFile>,
+    pub(crate) stylesheet: Option<Stylesheet>,
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData> {
+    /// Loads a stylesheet from a JSON file and reloads it whenever it changes.
+    ///
+    /// Loading is asynchronous on the web, so the styles may apply a frame or
+    /// two later. Replaces any previously loaded stylesheet.
+    pub fn load_stylesheet(&mut self, path: &str) {
+        self.stylesheet_state = StylesheetState {
+            file: Some(WatchedFile::new(path)),
+            stylesheet: None,
+        };
+        self.update_stylesheet();
+    }
+
+    /// Returns the loaded stylesheet, once the file has been read.
+    pub fn stylesheet(&self) -> Option<&Stylesheet> {
+        self.stylesheet_state.stylesheet.as_ref()
+    }
+
+    /// Requests the file when due and applies it when it changed.
+    /// Called from `begin()`.
+    pub(crate) fn update_stylesheet(&mut self) {
+        let now = self.context.current_time;
+        let Some(ref mut file) = self.stylesheet_state.file else {
+            return;
+        };
+        let Some(contents) = file.poll(now) else {
+            return;
+        };
+
+        let json = String::from_utf8_lossy(contents);
+        match Stylesheet::parse(&json, &self.theme) {
+            Ok(stylesheet) => {
+                let theme = stylesheet.theme().cloned();
+                self.stylesheet_state.stylesheet = Some(stylesheet);
+                if let Some(theme) = theme {
+                    self.set_theme(theme);
+                }
+            }
+            Err(e) => {
+                crate::telemetry::warning!("Failed to parse stylesheet {}: {}", file.path(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ElementDeclaration;
+
+    #[test]
+    fn test_parse_stylesheet() {
+        let sheet = Stylesheet::parse(
+            r##"{
+                "theme": { "surface": "#202124", "accent": [255, 204, 0, 128], "space": 12 },
+                "styles": {
+                    "card": {
+                        "background_color": "surface",
+                        "padding": "space",
+                        "gap": 8,
+                        "direction": "top_to_bottom",
+                        "align": ["center_x", "bottom"],
+                        "border": { "color": "accent", "width": 2 },
+                        "width": "50%",
+                        "height": 120
+                    }
+                }
+            }"##,
+            &Theme::new(),
+        )
+        .unwrap();
+
+        assert_eq!(sheet.theme().unwrap().get_number("space"), Some(12.0));
+
+        let mut declaration = ElementDeclaration::<()>::default();
+        sheet.get("card").unwrap().apply(&mut declaration);
+        assert_eq!(declaration.background_color, Color::u_rgb(0x20, 0x21, 0x24));
+        assert_eq!(declaration.layout.padding.left, 12);
+        assert_eq!(declaration.layout.child_gap, 8);
+        assert_eq!(declaration.layout.layout_direction, LayoutDirection::TopToBottom);
+        assert_eq!(declaration.layout.child_alignment.x, AlignX::CenterX);
+        assert_eq!(declaration.layout.child_alignment.y, AlignY::Bottom);
+        assert_eq!(declaration.border.color, Color::u_rgba(255, 204, 0, 128));
+        assert_eq!(declaration.border.width.top, 2);
+        assert!(sheet.get("missing").is_none());
+
+        let error = Stylesheet::parse(r#"{ "styles": { "card": { "gap": "space" } } }"#, &Theme::new());
+        assert_eq!(error.unwrap_err(), "styles.card.gap: no number token named \"space\"");
+    }
+}
@@ -0,0 +1,285 @@
+//! Toggle switches: an on/off control with a sliding thumb.
+//!
+//! ```ignore
+//! if ui.toggle("notifications", &mut settings.notifications, |t| t.label("Notifications")) {
+//!     save(&settings);
+//! }
+//! ```
+//!
+//! Flips on click, Enter or Space. The thumb can also be dragged: letting go
+//! past the middle switches to that side. The thumb slides between sides
+//! over [`ANIMATION_DURATION`] seconds.
+
+use crate::accessibility::AccessibilityRole;
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::easing::ease_in_out_cubic;
+use crate::id::Id;
+use crate::layout::Sizing;
+use crate::lerp::Lerp;
+use crate::renderer::FontAsset;
+use crate::Ui;
+
+/// Seconds the thumb takes to slide from one side to the other.
+pub const ANIMATION_DURATION: f32 = 0.15;
+
+/// Per-switch state kept between frames.
+#[derive(Debug, Default, Clone, Copy)]
+struct SwitchState {
+    /// Thumb position, from 0 (off) to 1 (on).
+    position: f32,
+    /// Pointer position and thumb position when a drag started.
+    drag_start: Option<(f32, f32)>,
+    /// Whether the pointer moved enough for the press to count as a drag.
+    dragged: bool,
+}
+
+/// Builder for configuring a toggle switch via closure.
+#[derive(Debug, Clone)]
+pub struct SwitchBuilder {
+    pub(crate) label: String,
+    pub(crate) font_asset: Option<&'static FontAsset>,
+    pub(crate) font_size: u16,
+    pub(crate) gap: u16,
+    pub(crate) label_color: Color,
+    pub(crate) track_color: Color,
+    pub(crate) on_color: Color,
+    pub(crate) thumb_color: Color,
+    pub(crate) disabled: bool,
+}
+
+impl Default for SwitchBuilder {
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            font_asset: None,
+            font_size: 16,
+            gap: 8,
+            label_color: Color::rgba(255.0, 255.0, 255.0, 255.0),
+            track_color: Color::rgba(80.0, 80.0, 80.0, 255.0),
+            on_color: Color::rgba(58.0, 123.0, 213.0, 255.0),
+            thumb_color: Color::rgba(255.0, 255.0, 255.0, 255.0),
+            disabled: false,
+        }
+    }
+}
+
+impl SwitchBuilder {
+    /// Sets the label shown next to the switch, also its accessible name.
+    #[inline]
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// Sets the font asset of the label.
+    #[inline]
+    pub fn font(&mut self, asset: &'static FontAsset) -> &mut Self {
+        self.font_asset = Some(asset);
+        self
+    }
+
+    /// Sets the font size of the label. The switch scales with it. Defaults to 16.
+    #[inline]
+    pub fn font_size(&mut self, size: u16) -> &mut Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Sets the gap between the switch and its label. Defaults to 8.
+    #[inline]
+    pub fn gap(&mut self, gap: u16) -> &mut Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Sets the color of the label.
+    #[inline]
+    pub fn label_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.label_color = color.into();
+        self
+    }
+
+    /// Sets the color of the track while off.
+    #[inline]
+    pub fn track_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.track_color = color.into();
+        self
+    }
+
+    /// Sets the color of the track while on.
+    #[inline]
+    pub fn on_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.on_color = color.into();
+        self
+    }
+
+    /// Sets the color of the thumb.
+    #[inline]
+    pub fn thumb_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.thumb_color = color.into();
+        self
+    }
+
+    /// Disables flipping and removes the switch from the Tab order.
+    #[inline]
+    pub fn disabled(&mut self, disabled: bool) -> &mut Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Adds a toggle switch. Flips `on` on click, Enter, Space or a drag
+    /// across the middle.
+    ///
+    /// Returns `true` on the frame `on` changed.
+    ///
+    /// ```ignore
+    /// ui.toggle("dark_mode", &mut dark_mode, |t| t.label("Dark mode"));
+    /// ```
+    pub fn toggle(
+        &mut self,
+        id: impl Into<Id>,
+        on: &mut bool,
+        config_fn: impl for<'a> FnOnce(&'a mut SwitchBuilder) -> &'a mut SwitchBuilder,
+    ) -> bool {
+        let mut config = SwitchBuilder::default();
+        config_fn(&mut config);
+        let id = id.into();
+        let track_id = id.child("track");
+
+        let track_height = (config.font_size as f32 * 1.25).round();
+        let track_width = track_height * 1.75;
+        let inset = 2.0;
+        let thumb_size = track_height - inset * 2.0;
+        let travel = track_width - thumb_size - inset * 2.0;
+
+        let delta = self.ply.context.frame_delta_time;
+        let pointer_x = self.ply.context.pointer_position().x;
+        let pressed = self.ply.is_pressed(id.clone()) && !config.disabled;
+        let initial = SwitchState { position: if *on { 1.0 } else { 0.0 }, ..Default::default() };
+        let mut state = *self.ply.component_states.widget_state(id.id, || initial);
+
+        // Follow the pointer while the thumb is dragged, else slide to the value
+        match (pressed, state.drag_start) {
+            (true, None) => state.drag_start = Some((pointer_x, state.position)),
+            (true, Some((start_x, start_position))) if travel > 0.0 => {
+                let moved = pointer_x - start_x;
+                state.dragged |= moved.abs() > 3.0;
+                if state.dragged {
+                    state.position = (start_position + moved / travel).clamp(0.0, 1.0);
+                }
+            }
+            // Released after a drag: settle from where the thumb was dropped
+            (false, _) if state.dragged => {}
+            _ => {
+                let target = if *on { 1.0 } else { 0.0 };
+                let step = delta / ANIMATION_DURATION;
+                state.position = if state.position < target {
+                    (state.position + step).min(target)
+                } else {
+                    (state.position - step).max(target)
+                };
+            }
+        }
+
+        let position = ease_in_out_cubic(state.position);
+        let track_color = config.track_color.lerp(config.on_color, position);
+        let checked = *on;
+        let mut released = false;
+
+        self.element()
+            .id(id.clone())
+            .accessibility(|a| {
+                a.role(AccessibilityRole::Switch).label(&config.label).checked(checked);
+                if !config.disabled {
+                    a.focusable();
+                }
+                a
+            })
+            .layout(|l| l.gap(config.gap).align(AlignX::Left, AlignY::CenterY))
+            .children(|ui| {
+                released = ui.just_released() && !config.disabled;
+
+                ui.element()
+                    .id(track_id.clone())
+                    .width(Sizing::Fixed(track_width))
+                    .height(Sizing::Fixed(track_height))
+                    .background_color(track_color)
+                    .corner_radius(track_height / 2.0)
+                    .layout(|l| l
+                        .padding((inset as u16, 0, 0, (inset + travel * position).round() as u16))
+                        .align(AlignX::Left, AlignY::Top)
+                    )
+                    .children(|ui| {
+                        ui.element()
+                            .width(Sizing::Fixed(thumb_size))
+                            .height(Sizing::Fixed(thumb_size))
+                            .background_color(config.thumb_color)
+                            .corner_radius(thumb_size / 2.0)
+                            .empty();
+                    });
+
+                if !config.label.is_empty() {
+                    ui.text(&config.label, |t| {
+                        if let Some(font) = config.font_asset {
+                            t.font(font);
+                        }
+                        t.font_size(config.font_size).color(config.label_color)
+                    });
+                }
+            });
+
+        let mut changed = false;
+        if released {
+            // A drag settles on the nearer side; a click or key press flips
+            let new_value = if state.dragged { state.position > 0.5 } else { !*on };
+            changed = new_value != *on;
+            *on = new_value;
+        }
+        if !pressed {
+            state.drag_start = None;
+            state.dragged = false;
+        }
+        *self.ply.component_states.widget_state(id.id, || initial) = state;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::{Dimensions, Vector2};
+    use crate::Ply;
+
+    #[test]
+    fn test_toggle_flips_on_click_and_follows_drag() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 200.0));
+        let mut on = false;
+        let mut frame = |ply: &mut Ply<()>| {
+            ply.context.frame_delta_time = 1.0;
+            let mut ui = ply.begin();
+            ui.toggle("wifi", &mut on, |t| t.label("Wi-Fi"));
+            ui.eval();
+            on
+        };
+
+        // Click: press and release in place
+        frame(&mut ply);
+        ply.context.set_pointer_state(Vector2::new(5.0, 5.0), true);
+        assert!(!frame(&mut ply));
+        ply.context.set_pointer_state(Vector2::new(5.0, 5.0), false);
+        assert!(frame(&mut ply));
+        assert!(frame(&mut ply));
+
+        // Drag the thumb back past the middle to switch off
+        ply.context.set_pointer_state(Vector2::new(30.0, 5.0), true);
+        frame(&mut ply);
+        ply.context.set_pointer_state(Vector2::new(20.0, 5.0), true);
+        frame(&mut ply);
+        ply.context.set_pointer_state(Vector2::new(0.0, 5.0), true);
+        assert!(frame(&mut ply));
+        ply.context.set_pointer_state(Vector2::new(0.0, 5.0), false);
+        assert!(!frame(&mut ply));
+    }
+}
@@ -0,0 +1,261 @@
+//! System appearance preferences: dark mode, reduced motion and text size.
+//!
+//! ```ignore
+//! let background = if system::prefers_dark() { DARK_BG } else { LIGHT_BG };
+//! let duration = if system::prefers_reduced_motion() { 0.0 } else { 0.25 };
+//! let font_size = (16.0 * system::text_scale_factor()).round() as u16;
+//! ```
+//!
+//! On the web these read the `prefers-color-scheme` and
+//! `prefers-reduced-motion` media queries and the root font size. Natively
+//! they read the platform settings: the personalization and accessibility
+//! settings on Windows, the interface style and accessibility display options
+//! on macOS, and the GNOME interface settings on Linux. Elsewhere they return
+//! light, full motion and `1.0`.
+//!
+//! Native values are cached and refreshed in the background about once a
+//! second, so calling these every frame is cheap and changes in the system
+//! settings still show up while running.
+
+/// Returns `true` if the user prefers a dark color scheme.
+pub fn prefers_dark() -> bool {
+    appearance().dark
+}
+
+/// Returns `true` if the user asked for less motion. Skip or shorten
+/// animations and transitions when set.
+pub fn prefers_reduced_motion() -> bool {
+    appearance().reduced_motion
+}
+
+/// Returns the text size the user chose, relative to the default size.
+/// `1.0` is the default, `1.5` means text should be 50% larger.
+pub fn text_scale_factor() -> f32 {
+    appearance().text_scale
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Appearance {
+    dark: bool,
+    reduced_motion: bool,
+    text_scale: f32,
+}
+
+#[cfg(target_arch = "wasm32")]
+fn appearance() -> Appearance {
+    unsafe {
+        Appearance {
+            dark: ply_system_prefers_dark() != 0,
+            reduced_motion: ply_system_prefers_reduced_motion() != 0,
+            text_scale: valid_text_scale(ply_system_text_scale()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn appearance() -> Appearance {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    /// How long a query result is used before asking the system again.
+    const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+    static APPEARANCE: Mutex<Option<(Instant, Appearance)>> = Mutex::new(None);
+    static REFRESHING: AtomicBool = AtomicBool::new(false);
+
+    fn refresh() -> Appearance {
+        let appearance = platform::query();
+        *APPEARANCE.lock().unwrap() = Some((Instant::now(), appearance));
+        appearance
+    }
+
+    let cached = *APPEARANCE.lock().unwrap();
+    match cached {
+        None => refresh(),
+        Some((queried_at, appearance)) => {
+            // Stale values are refreshed off the render loop, since querying
+            // can mean spawning a process
+            if queried_at.elapsed() >= REFRESH_INTERVAL && !REFRESHING.swap(true, Ordering::AcqRel) {
+                std::thread::spawn(|| {
+                    refresh();
+                    REFRESHING.store(false, Ordering::Release);
+                });
+            }
+            appearance
+        }
+    }
+}
+
+/// Falls back to `1.0` for scales the platform reported badly.
+#[cfg(any(target_os = "windows", target_os = "linux", target_arch = "wasm32"))]
+fn valid_text_scale(scale: f32) -> f32 {
+    if scale.is_finite() && scale > 0.0 {
+        scale
+    } else {
+        1.0
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{valid_text_scale, Appearance};
+    use std::ffi::c_void;
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn RegGetValueW(
+            hkey: isize,
+            sub_key: *const u16,
+            value: *const u16,
+            flags: u32,
+            value_type: *mut u32,
+            data: *mut c_void,
+            data_len: *mut u32,
+        ) -> i32;
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn SystemParametersInfoW(action: u32, param: u32, pv_param: *mut c_void, win_ini: u32) -> i32;
+    }
+
+    const HKEY_CURRENT_USER: isize = 0x8000_0001_u32 as i32 as isize;
+    const RRF_RT_REG_DWORD: u32 = 0x0000_0010;
+    const SPI_GETCLIENTAREAANIMATION: u32 = 0x1042;
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    fn read_dword(sub_key: &str, value: &str) -> Option<u32> {
+        let sub_key = wide(sub_key);
+        let value = wide(value);
+        let mut data: u32 = 0;
+        let mut data_len = std::mem::size_of::<u32>() as u32;
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                sub_key.as_ptr(),
+                value.as_ptr(),
+                RRF_RT_REG_DWORD,
+                std::ptr::null_mut(),
+                &mut data as *mut u32 as *mut c_void,
+                &mut data_len,
+            )
+        };
+        (status == 0).then_some(data)
+    }
+
+    pub(super) fn query() -> Appearance {
+        let dark = read_dword(
+            "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize",
+            "AppsUseLightTheme",
+        )
+        .is_some_and(|light| light == 0);
+
+        let mut animations: i32 = 1;
+        let reduced_motion = unsafe {
+            SystemParametersInfoW(
+                SPI_GETCLIENTAREAANIMATION,
+                0,
+                &mut animations as *mut i32 as *mut c_void,
+                0,
+            ) != 0
+        } && animations == 0;
+
+        // "Make text bigger" in the accessibility settings, in percent
+        let text_scale = read_dword("Software\\Microsoft\\Accessibility", "TextScaleFactor")
+            .map_or(1.0, |percent| valid_text_scale(percent as f32 / 100.0));
+
+        Appearance { dark, reduced_motion, text_scale }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Appearance;
+    use macroquad::miniquad::native::apple::apple_util::{nsstring_to_string, str_to_nsstring};
+    use macroquad::miniquad::native::apple::frameworks::{class, msg_send, ObjcId, BOOL, YES};
+
+    pub(super) fn query() -> Appearance {
+        unsafe {
+            // AppleInterfaceStyle is only set, to "Dark", in dark mode
+            let defaults: ObjcId = msg_send![class!(NSUserDefaults), standardUserDefaults];
+            let style: ObjcId =
+                msg_send![defaults, stringForKey: str_to_nsstring("AppleInterfaceStyle")];
+            let dark = !style.is_null() && nsstring_to_string(style).eq_ignore_ascii_case("dark");
+
+            let workspace: ObjcId = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let reduce_motion: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+
+            // macOS has no system-wide text size for regular apps
+            Appearance {
+                dark,
+                reduced_motion: reduce_motion == YES,
+                text_scale: 1.0,
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{valid_text_scale, Appearance};
+
+    /// Reads a key from the GNOME interface settings, without quotes.
+    fn gsetting(key: &str) -> Option<String> {
+        let output = std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", key])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let value = String::from_utf8(output.stdout).ok()?;
+        Some(value.trim().trim_matches('\'').to_owned())
+    }
+
+    pub(super) fn query() -> Appearance {
+        let dark = match gsetting("color-scheme").as_deref() {
+            Some("prefer-dark") => true,
+            Some("prefer-light") => false,
+            // Older desktops only have a theme name, like "Adwaita-dark"
+            _ => gsetting("gtk-theme")
+                .or_else(|| std::env::var("GTK_THEME").ok())
+                .is_some_and(|theme| theme.to_ascii_lowercase().contains("dark")),
+        };
+        let reduced_motion = gsetting("enable-animations").as_deref() == Some("false");
+        let text_scale = gsetting("text-scaling-factor")
+            .and_then(|value| value.parse::<f32>().ok())
+            .map_or(1.0, valid_text_scale);
+
+        Appearance { dark, reduced_motion, text_scale }
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_arch = "wasm32"
+)))]
+mod platform {
+    use super::Appearance;
+
+    pub(super) fn query() -> Appearance {
+        Appearance {
+            dark: false,
+            reduced_motion: false,
+            text_scale: 1.0,
+        }
+    }
+}
+
+// WASM FFI
+#[cfg(target_arch = "wasm32")]
+extern "C" {
+    fn ply_system_prefers_dark() -> i32;
+    fn ply_system_prefers_reduced_motion() -> i32;
+    fn ply_system_text_scale() -> f32;
+}
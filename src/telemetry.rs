@@ -0,0 +1,30 @@
+//! Hooks into the host app's `log` and `tracing` setup.
+//!
+//! With the `tracing` feature, frames show up as `ply::layout`,
+//! `ply::text_wrap`, `ply::render_commands` and `ply::render` spans at debug
+//! level. With the `log` feature, warnings and new diagnostics go to `log`
+//! under the `ply` target instead of macroquad's logger.
+
+/// Logs a warning through `log` when enabled, through macroquad otherwise.
+macro_rules! warning {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "log")]
+        log::warn!(target: "ply", $($arg)*);
+        #[cfg(not(feature = "log"))]
+        macroquad::prelude::warn!($($arg)*);
+    }};
+}
+pub(crate) use warning;
+
+/// Enters a debug span until the end of the enclosing block.
+#[cfg(feature = "tracing")]
+macro_rules! span {
+    ($name:literal) => {
+        let _span = tracing::debug_span!($name).entered();
+    };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+    ($name:literal) => {};
+}
+pub(crate) use span;
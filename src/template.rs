@@ -0,0 +1,790 @@
+//! Templates: element trees written as XML, with slots for dynamic values,
+//! content and callbacks.
+//!
+//! ```xml
+//! <template>
+//!   <!-- Settings screen -->
+//!   <element id="settings" width="grow" height="grow" padding="24" gap="12"
+//!            direction="top_to_bottom" background_color="surface">
+//!     <text font_size="24" color="text">Hello, {name}!</text>
+//!     <slot name="options"/>
+//!     <element padding="8 16 8 16" corner_radius="radius" background_color="#3A7BD5"
+//!              on_press="save">
+//!       <text font_size="16" color="#FFFFFF">Save</text>
+//!     </element>
+//!   </element>
+//! </template>
+//! ```
+//!
+//! ```ignore
+//! ui.template_file("assets/settings.xml", |t| t
+//!     .value("name", &user.name)
+//!     .content("options", |ui| draw_options(ui, &mut options))
+//!     .on_action(|action| if action == "save" { save(&options) })
+//! );
+//! ```
+//!
+//! `<element>` takes `id`, `width`, `height`, `background_color`,
+//! `corner_radius`, `padding`, `gap`, `direction`, `align`, `border`,
+//! `on_press` and, with the `stylesheet` feature, `class`. `<text>` takes
+//! `font_size`, `color`, `line_height` and `letter_spacing`. `<slot>` is
+//! replaced by the content registered under its `name`.
+//!
+//! Values are written like in stylesheets: colors are `#RRGGBB` or
+//! `#RRGGBBAA`, sizes are a number, `grow`, `fit` or `50%`, and any color or
+//! number can be the name of a theme token. Padding is one number or
+//! `top right bottom left`, `align` is `center_x center_y` and `border` is
+//! `color width`. Attributes and text can contain `{name}` placeholders,
+//! filled with [`TemplateBuilder::value`]; write `{{` and `}}` for literal
+//! braces.
+//!
+//! Tags and attribute names are checked when the template is parsed. Values
+//! are parsed when the template is drawn, so they follow theme swaps and
+//! placeholders; a bad value is skipped with a warning the first time.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::id::Id;
+use crate::layout::{LayoutDirection, Padding, Sizing};
+use crate::style::Style;
+use crate::text::TextConfig;
+use crate::theme;
+use crate::watch::WatchedFile;
+use crate::Ui;
+
+const ELEMENT_ATTRIBUTES: &[&str] = &[
+    "id",
+    "class",
+    "width",
+    "height",
+    "background_color",
+    "corner_radius",
+    "padding",
+    "gap",
+    "direction",
+    "align",
+    "border",
+    "on_press",
+];
+
+const TEXT_ATTRIBUTES: &[&str] = &["font_size", "color", "line_height", "letter_spacing"];
+
+#[derive(Debug, Clone)]
+pub(crate) enum Node {
+    Element {
+        attributes: Vec<(String, String)>,
+        children: Vec<Node>,
+    },
+    Text {
+        attributes: Vec<(String, String)>,
+        content: String,
+    },
+    Slot {
+        name: String,
+    },
+}
+
+/// An element tree parsed from XML, drawn with [`Ui::template`].
+#[derive(Debug, Clone, Default)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// Parses a template. The root must be a `<template>` tag.
+    pub fn parse(xml: &str) -> Result<Self, String> {
+        let mut parser = Parser { xml, pos: 0 };
+        parser.skip_misc()?;
+        let line = parser.line();
+        if !parser.eat("<") || parser.name()? != "template" {
+            return Err(error_at(line, "expected a <template> root"));
+        }
+        let (attributes, self_closing) = parser.attributes()?;
+        if let Some((name, _)) = attributes.first() {
+            return Err(error_at(line, format!("<template> has no attribute \"{name}\"")));
+        }
+        let nodes = if self_closing { Vec::new() } else { parser.nodes("template")? };
+        parser.skip_misc()?;
+        if !parser.rest().is_empty() {
+            return Err(parser.error("unexpected content after </template>"));
+        }
+        Ok(Self { nodes })
+    }
+}
+
+fn error_at(line: usize, message: impl std::fmt::Display) -> String {
+    format!("line {line}: {message}")
+}
+
+struct Parser<'a> {
+    xml: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.xml[self.pos..]
+    }
+
+    fn line(&self) -> usize {
+        self.xml[..self.pos].matches('\n').count() + 1
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> String {
+        error_at(self.line(), message)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.xml.len() - self.rest().trim_start().len();
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<(), String> {
+        match self.rest().find(end) {
+            Some(i) => {
+                self.pos += i + end.len();
+                Ok(())
+            }
+            None => Err(self.error(format!("missing \"{end}\""))),
+        }
+    }
+
+    /// Skips whitespace, comments and `<?xml ...?>` declarations.
+    fn skip_misc(&mut self) -> Result<(), String> {
+        loop {
+            self.skip_whitespace();
+            if self.eat("<!--") {
+                self.skip_past("-->")?;
+            } else if self.eat("<?") {
+                self.skip_past("?>")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Reads the attributes of a tag up to its end. Returns whether it was self-closing.
+    fn attributes(&mut self) -> Result<(Vec<(String, String)>, bool), String> {
+        let mut attributes: Vec<(String, String)> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat("/>") {
+                return Ok((attributes, true));
+            }
+            if self.eat(">") {
+                return Ok((attributes, false));
+            }
+            let name = self.name()?;
+            self.skip_whitespace();
+            if !self.eat("=") {
+                return Err(self.error(format!("expected = after {name}")));
+            }
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return Err(self.error(format!("expected a quoted value for {name}"))),
+            };
+            self.pos += 1;
+            let len = self
+                .rest()
+                .find(quote)
+                .ok_or_else(|| self.error(format!("unclosed value for {name}")))?;
+            let value = decode_entities(&self.rest()[..len]).map_err(|e| self.error(e))?;
+            check_placeholders(&value).map_err(|e| self.error(format!("{name}: {e}")))?;
+            self.pos += len + 1;
+            if attributes.iter().any(|(existing, _)| existing == name) {
+                return Err(self.error(format!("duplicate attribute {name}")));
+            }
+            attributes.push((name.to_owned(), value));
+        }
+    }
+
+    fn close_tag(&mut self, name: &str) -> Result<(), String> {
+        self.skip_whitespace();
+        let found = self.eat("</") && self.name().ok() == Some(name) && {
+            self.skip_whitespace();
+            self.eat(">")
+        };
+        if found {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected </{name}>")))
+        }
+    }
+
+    /// Reads child nodes up to the closing tag of `parent`.
+    fn nodes(&mut self, parent: &str) -> Result<Vec<Node>, String> {
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_misc()?;
+            if self.rest().starts_with("</") {
+                self.close_tag(parent)?;
+                return Ok(nodes);
+            }
+            if self.rest().is_empty() {
+                return Err(self.error(format!("<{parent}> is not closed")));
+            }
+            if !self.eat("<") {
+                return Err(self.error("text must be inside a <text> tag"));
+            }
+            nodes.push(self.node()?);
+        }
+    }
+
+    /// Reads a tag and its children, after the opening `<`.
+    fn node(&mut self) -> Result<Node, String> {
+        let line = self.line();
+        let tag = self.name()?;
+        let (attributes, self_closing) = self.attributes()?;
+        check_attributes(tag, &attributes).map_err(|e| error_at(line, e))?;
+        match tag {
+            "element" => {
+                let children = if self_closing { Vec::new() } else { self.nodes(tag)? };
+                Ok(Node::Element { attributes, children })
+            }
+            "text" => {
+                let content = if self_closing {
+                    String::new()
+                } else {
+                    let len = self.rest().find('<').unwrap_or(self.rest().len());
+                    let raw = decode_entities(&self.rest()[..len]).map_err(|e| self.error(e))?;
+                    check_placeholders(&raw).map_err(|e| self.error(e))?;
+                    self.pos += len;
+                    self.close_tag(tag)?;
+                    raw.split_whitespace().collect::<Vec<_>>().join(" ")
+                };
+                Ok(Node::Text { attributes, content })
+            }
+            "slot" => {
+                let name = match attributes.into_iter().next() {
+                    Some((_, name)) => name,
+                    None => return Err(error_at(line, "<slot> needs a name")),
+                };
+                if !self_closing {
+                    self.close_tag(tag)?;
+                }
+                Ok(Node::Slot { name })
+            }
+            _ => unreachable!("checked by check_attributes"),
+        }
+    }
+}
+
+/// Checks that `tag` is known and takes all of `attributes`.
+pub(crate) fn check_attributes(tag: &str, attributes: &[(String, String)]) -> Result<(), String> {
+    let allowed = match tag {
+        "element" => ELEMENT_ATTRIBUTES,
+        "text" => TEXT_ATTRIBUTES,
+        "slot" => &["name"],
+        _ => return Err(format!("unknown tag <{tag}>")),
+    };
+    for (name, _) in attributes {
+        if !allowed.contains(&name.as_str()) {
+            return Err(format!("<{tag}> has no attribute \"{name}\""));
+        }
+        if name == "class" && !cfg!(feature = "stylesheet") {
+            return Err("class needs the stylesheet feature".to_owned());
+        }
+    }
+    Ok(())
+}
+
+fn decode_entities(raw: &str) -> Result<String, String> {
+    let mut decoded = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or_else(|| "unterminated entity".to_owned())?;
+        let entity = &rest[start + 1..start + end];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity
+                    .strip_prefix('#')
+                    .and_then(|decimal| decimal.parse().ok())
+                    .and_then(char::from_u32),
+            },
+        };
+        decoded.push(character.ok_or_else(|| format!("unknown entity &{entity};"))?);
+        rest = &rest[start + end + 1..];
+    }
+    decoded.push_str(rest);
+    Ok(decoded)
+}
+
+/// Checks that every `{` starts a `{name}` placeholder or a `{{` escape.
+fn check_placeholders(value: &str) -> Result<(), String> {
+    fill_placeholders(value, |name| {
+        if name.is_empty() {
+            Err("empty placeholder {}".to_owned())
+        } else {
+            Ok("")
+        }
+    })
+    .map(|_| ())
+}
+
+/// Replaces `{name}` placeholders with what `lookup` writes, and `{{`/`}}` with braces.
+fn fill_placeholders<T: std::fmt::Display>(
+    value: &str,
+    mut lookup: impl FnMut(&str) -> Result<T, String>,
+) -> Result<String, String> {
+    let mut filled = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(i) = rest.find(['{', '}']) {
+        filled.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+        if rest[i..].starts_with('}') {
+            if !after.starts_with('}') {
+                return Err("unmatched }, write }} for a brace".to_owned());
+            }
+            filled.push('}');
+            rest = &after[1..];
+        } else if let Some(after_brace) = after.strip_prefix('{') {
+            filled.push('{');
+            rest = after_brace;
+        } else {
+            let end = after
+                .find('}')
+                .ok_or_else(|| "unclosed {, write {{ for a brace".to_owned())?;
+            filled.push_str(&lookup(&after[..end])?.to_string());
+            rest = &after[end + 1..];
+        }
+    }
+    filled.push_str(rest);
+    Ok(filled)
+}
+
+thread_local! {
+    /// Template problems already warned about, to warn once per message.
+    static WARNED: RefCell<FxHashSet<String>> = RefCell::new(FxHashSet::default());
+}
+
+pub(crate) fn warn_once(message: String) {
+    WARNED.with(|warned| {
+        if !warned.borrow().contains(&message) {
+            crate::telemetry::warning!("{}", message);
+            warned.borrow_mut().insert(message);
+        }
+    });
+}
+
+type ContentFn<'f, CustomElementData> = Box<dyn FnMut(&mut Ui<'_, CustomElementData>) + 'f>;
+type ActionFn<'f> = Box<dyn FnMut(&str) + 'f>;
+
+/// Values, content and callbacks for the slots of a template, set in the
+/// closure passed to [`Ui::template`].
+pub struct TemplateBuilder<'f, CustomElementData: Clone + Default + std::fmt::Debug = ()> {
+    values: FxHashMap<String, String>,
+    contents: FxHashMap<String, ContentFn<'f, CustomElementData>>,
+    on_action_fn: Option<ActionFn<'f>>,
+}
+
+impl<'f, CustomElementData: Clone + Default + std::fmt::Debug> TemplateBuilder<'f, CustomElementData> {
+    pub(crate) fn new() -> Self {
+        Self {
+            values: FxHashMap::default(),
+            contents: FxHashMap::default(),
+            on_action_fn: None,
+        }
+    }
+
+    /// Sets the value of `{name}` placeholders.
+    #[inline]
+    pub fn value(&mut self, name: &str, value: impl ToString) -> &mut Self {
+        self.values.insert(name.to_owned(), value.to_string());
+        self
+    }
+
+    /// Sets what is drawn in place of `<slot name="..."/>`.
+    #[inline]
+    pub fn content(&mut self, name: &str, content: impl FnMut(&mut Ui<'_, CustomElementData>) + 'f) -> &mut Self {
+        self.contents.insert(name.to_owned(), Box::new(content));
+        self
+    }
+
+    /// Sets the callback for `on_press` attributes, called with the
+    /// attribute's value when the element is pressed.
+    #[inline]
+    pub fn on_action(&mut self, on_action: impl FnMut(&str) + 'f) -> &mut Self {
+        self.on_action_fn = Some(Box::new(on_action));
+        self
+    }
+
+    /// Returns the values set with [`value`](Self::value).
+    #[cfg(feature = "scripting")]
+    pub(crate) fn values(&self) -> &FxHashMap<String, String> {
+        &self.values
+    }
+
+    pub(crate) fn fill(&self, value: &str) -> String {
+        let filled = fill_placeholders(value, |name| match self.values.get(name) {
+            Some(value) => Ok(value.as_str()),
+            None => {
+                warn_once(format!("Template placeholder {{{name}}} has no value"));
+                Ok("")
+            }
+        });
+        filled.unwrap_or_default()
+    }
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Draws a template, with values, content and callbacks for its slots.
+    ///
+    /// ```rust,ignore
+    /// ui.template(&settings, |t| t
+    ///     .value("name", &user.name)
+    ///     .on_action(|action| println!("{action}"))
+    /// );
+    /// ```
+    pub fn template<'f>(
+        &mut self,
+        template: &Template,
+        f: impl for<'a> FnOnce(&'a mut TemplateBuilder<'f, CustomElementData>) -> &'a mut TemplateBuilder<'f, CustomElementData>,
+    ) {
+        let mut builder = TemplateBuilder::new();
+        f(&mut builder);
+        draw_nodes(self, &template.nodes, &mut builder);
+    }
+
+    /// Draws the template in an XML file, reloading it whenever it changes.
+    ///
+    /// Draws nothing until the file has been read, which on the web takes a
+    /// frame or two. A file that fails to parse is reported with a warning
+    /// and the previous version is kept.
+    pub fn template_file<'f>(
+        &mut self,
+        path: &str,
+        f: impl for<'a> FnOnce(&'a mut TemplateBuilder<'f, CustomElementData>) -> &'a mut TemplateBuilder<'f, CustomElementData>,
+    ) {
+        let now = self.ply.context.current_time;
+        if let Some(template) = self.ply.template_files.get(path, now) {
+            self.template(&template, f);
+        }
+    }
+}
+
+pub(crate) fn draw_nodes<CustomElementData: Clone + Default + std::fmt::Debug>(
+    ui: &mut Ui<'_, CustomElementData>,
+    nodes: &[Node],
+    builder: &mut TemplateBuilder<'_, CustomElementData>,
+) {
+    for node in nodes {
+        match node {
+            Node::Element { attributes, children } => {
+                let mut element = ui.element();
+                let mut style = Style::new();
+                let mut action = None;
+                for (name, value) in attributes {
+                    let value = builder.fill(value);
+                    match name.as_str() {
+                        "id" => element = element.id(Id::from((value.as_str(), 0))),
+                        #[cfg(feature = "stylesheet")]
+                        "class" => element = element.class(&value),
+                        "on_press" => action = Some(value),
+                        _ => match style_attribute(style.clone(), name, &value) {
+                            Ok(styled) => style = styled,
+                            Err(e) => warn_once(format!("Template attribute {name}=\"{value}\": {e}")),
+                        },
+                    }
+                }
+                // Attributes override the class, whatever their order
+                element.style(&style).children(|ui| {
+                    if let (Some(action), Some(on_action)) = (&action, &mut builder.on_action_fn) {
+                        if ui.just_pressed() {
+                            on_action(action);
+                        }
+                    }
+                    draw_nodes(ui, children, builder);
+                });
+            }
+            Node::Text { attributes, content } => {
+                let attributes: Vec<(&str, String)> = attributes
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), builder.fill(value)))
+                    .collect();
+                ui.text(&builder.fill(content), |config| {
+                    for (name, value) in &attributes {
+                        if let Err(e) = text_attribute(config, name, value) {
+                            warn_once(format!("Template attribute {name}=\"{value}\": {e}"));
+                        }
+                    }
+                    config
+                });
+            }
+            Node::Slot { name } => match builder.contents.get_mut(name) {
+                Some(content) => content(ui),
+                None => warn_once(format!("Template slot \"{name}\" has no content")),
+            },
+        }
+    }
+}
+
+fn parse_color(value: &str) -> Result<Color, String> {
+    let Some(digits) = value.strip_prefix('#') else {
+        return theme::with_active(|theme| theme.get_color(value))
+            .ok_or_else(|| format!("no color token named \"{value}\""));
+    };
+    let channel = |i: usize| {
+        digits
+            .get(i * 2..i * 2 + 2)
+            .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+            .ok_or_else(|| "invalid hex color".to_owned())
+    };
+    match digits.len() {
+        6 => Ok(Color::u_rgb(channel(0)?, channel(1)?, channel(2)?)),
+        8 => Ok(Color::u_rgba(channel(0)?, channel(1)?, channel(2)?, channel(3)?)),
+        _ => Err("invalid hex color".to_owned()),
+    }
+}
+
+fn parse_number(value: &str) -> Result<f32, String> {
+    value
+        .parse::<f32>()
+        .ok()
+        .or_else(|| theme::with_active(|theme| theme.get_number(value)))
+        .ok_or_else(|| format!("expected a number or number token, got \"{value}\""))
+}
+
+fn parse_u16(value: &str) -> Result<u16, String> {
+    Ok(parse_number(value)?.round().clamp(0.0, u16::MAX as f32) as u16)
+}
+
+fn parse_sizing(value: &str) -> Result<Sizing, String> {
+    match value {
+        "grow" => Ok(Sizing::Grow(0.0, f32::MAX, 1.0)),
+        "fit" => Ok(Sizing::Fit(0.0, f32::MAX)),
+        "min_content" => Ok(Sizing::MinContent),
+        "max_content" => Ok(Sizing::MaxContent),
+        _ => match value.strip_suffix('%') {
+            Some(percent) => percent
+                .trim()
+                .parse::<f32>()
+                .map(|percent| Sizing::Percent((percent / 100.0).clamp(0.0, 1.0)))
+                .map_err(|_| "invalid percentage".to_owned()),
+            None => match Sizing::parse_viewport(value) {
+                Some(sizing) => Ok(sizing),
+                None => Ok(Sizing::Fixed(parse_number(value)?)),
+            },
+        },
+    }
+}
+
+fn style_attribute(style: Style, name: &str, value: &str) -> Result<Style, String> {
+    Ok(match name {
+        "width" => style.width(parse_sizing(value)?),
+        "height" => style.height(parse_sizing(value)?),
+        "background_color" => style.background_color(parse_color(value)?),
+        "corner_radius" => style.corner_radius(parse_number(value)?),
+        "padding" => {
+            let sides = value
+                .split_whitespace()
+                .map(parse_u16)
+                .collect::<Result<Vec<_>, _>>()?;
+            match sides[..] {
+                [all] => style.padding(Padding::all(all)),
+                [top, right, bottom, left] => style.padding(Padding::from((top, right, bottom, left))),
+                _ => return Err("expected one number or \"top right bottom left\"".to_owned()),
+            }
+        }
+        "gap" => style.gap(parse_u16(value)?),
+        "direction" => style.direction(match value {
+            "left_to_right" => LayoutDirection::LeftToRight,
+            "top_to_bottom" => LayoutDirection::TopToBottom,
+            _ => return Err("expected \"left_to_right\" or \"top_to_bottom\"".to_owned()),
+        }),
+        "align" => {
+            let mut parts = value.split_whitespace();
+            let (Some(x), Some(y), None) = (parts.next(), parts.next(), parts.next()) else {
+                return Err("expected \"x y\"".to_owned());
+            };
+            let x = match x {
+                "left" => AlignX::Left,
+                "center_x" => AlignX::CenterX,
+                "right" => AlignX::Right,
+                _ => return Err("x must be \"left\", \"center_x\" or \"right\"".to_owned()),
+            };
+            let y = match y {
+                "top" => AlignY::Top,
+                "center_y" => AlignY::CenterY,
+                "bottom" => AlignY::Bottom,
+                "baseline" => AlignY::Baseline,
+                _ => return Err("y must be \"top\", \"center_y\", \"bottom\" or \"baseline\"".to_owned()),
+            };
+            style.align(x, y)
+        }
+        "border" => {
+            let mut parts = value.split_whitespace();
+            let (Some(color), Some(width), None) = (parts.next(), parts.next(), parts.next()) else {
+                return Err("expected \"color width\"".to_owned());
+            };
+            style.border(parse_color(color)?, parse_u16(width)?)
+        }
+        _ => style,
+    })
+}
+
+fn text_attribute(config: &mut TextConfig, name: &str, value: &str) -> Result<(), String> {
+    match name {
+        "font_size" => config.font_size(parse_u16(value)?),
+        "color" => config.color(parse_color(value)?),
+        "line_height" => config.line_height(parse_u16(value)?),
+        "letter_spacing" => config.letter_spacing(parse_u16(value)?),
+        _ => config,
+    };
+    Ok(())
+}
+
+struct WatchedTemplate {
+    file: WatchedFile,
+    template: Option<Rc<Template>>,
+}
+
+impl WatchedTemplate {
+    fn poll(&mut self, now: f64) {
+        let Some(contents) = self.file.poll(now) else {
+            return;
+        };
+        match Template::parse(&String::from_utf8_lossy(contents)) {
+            Ok(template) => self.template = Some(Rc::new(template)),
+            Err(e) => {
+                crate::telemetry::warning!("Failed to parse template {}: {}", self.file.path(), e);
+            }
+        }
+    }
+}
+
+/// Template files drawn with `template_file`, kept by [`Ply`](crate::Ply).
+#[derive(Default)]
+pub(crate) struct TemplateFiles {
+    files: FxHashMap<String, WatchedTemplate>,
+}
+
+impl TemplateFiles {
+    /// Reloads the files that changed. Called from `begin()`.
+    pub(crate) fn update(&mut self, now: f64) {
+        for file in self.files.values_mut() {
+            file.poll(now);
+        }
+    }
+
+    /// Returns the template in a file, starting to watch it on first use.
+    fn get(&mut self, path: &str, now: f64) -> Option<Rc<Template>> {
+        let file = self.files.entry(path.to_owned()).or_insert_with(|| {
+            let mut file = WatchedTemplate {
+                file: WatchedFile::new(path),
+                template: None,
+            };
+            file.poll(now);
+            file
+        });
+        file.template.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{Dimensions, Vector2};
+    use crate::render_commands::RenderCommandConfig;
+    use crate::Ply;
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        let error = |xml: &str| Template::parse(xml).unwrap_err();
+        assert_eq!(error("<element/>"), "line 1: expected a <template> root");
+        assert_eq!(error("<template>\n  <box/>\n</template>"), "line 2: unknown tag <box>");
+        assert_eq!(
+            error("<template>\n\n  <element colour=\"#FFFFFF\"/>\n</template>"),
+            "line 3: <element> has no attribute \"colour\""
+        );
+        assert_eq!(error("<template><element></template>"), "line 1: expected </element>");
+        assert_eq!(error("<template>loose</template>"), "line 1: text must be inside a <text> tag");
+        assert_eq!(error("<template><text>{</text></template>"), "line 1: unclosed {, write {{ for a brace");
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        let mut builder = TemplateBuilder::<()>::new();
+        builder.value("name", "Ada").value("count", 3);
+        assert_eq!(builder.fill("Hello, {name}! {count} new"), "Hello, Ada! 3 new");
+        assert_eq!(builder.fill("{{literal}} {missing}"), "{literal} ");
+        assert_eq!(decode_entities("a &lt;b&gt; &amp; &#x41;&#66;").unwrap(), "a <b> & AB");
+    }
+
+    #[test]
+    fn test_template_draws_values_slots_and_actions() {
+        let template = Template::parse(
+            r##"<?xml version="1.0"?>
+            <template>
+              <element id="card" width="grow" height="100" background_color="{background}" on_press="open">
+                <text font_size="{size}">Hi {name} &amp; co</text>
+                <slot name="extra"/>
+              </element>
+            </template>"##,
+        )
+        .unwrap();
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 200.0));
+        let mut actions = Vec::new();
+
+        for pressed in [false, true] {
+            ply.context.set_pointer_state(Vector2::new(50.0, 50.0), pressed);
+            let mut slot_drawn = false;
+            let mut ui = ply.begin();
+            ui.template(&template, |t| t
+                .value("background", "#FF0000")
+                .value("size", 10)
+                .value("name", "Ada")
+                .content("extra", |_| slot_drawn = true)
+                .on_action(|action| actions.push(action.to_owned()))
+            );
+            let items = ui.eval();
+            assert!(slot_drawn);
+            match &items[0].config {
+                RenderCommandConfig::Rectangle(rect) => assert_eq!(rect.color, Color::u_rgb(255, 0, 0)),
+                other => panic!("Expected a rectangle, got {:?}", other),
+            }
+            match &items[1].config {
+                RenderCommandConfig::Text(text) => {
+                    assert_eq!(text.text, "Hi Ada & co");
+                    assert_eq!(text.font_size, 10);
+                }
+                other => panic!("Expected text, got {:?}", other),
+            }
+        }
+        assert_eq!(actions, ["open"]);
+        assert!(ply.pointer_over(Id::from(("card", 0))));
+    }
+}
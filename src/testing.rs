@@ -0,0 +1,856 @@
+//! Golden-image snapshot tests for layouts.
+//!
+//! ```ignore
+//! #[test]
+//! fn settings_panel() {
+//!     let mut ply = Ply::<()>::new_headless(Dimensions::new(320.0, 240.0));
+//!     let image = testing::render(&mut ply, |ui| settings_panel(ui));
+//!     testing::assert_snapshot("settings_panel", &image, |s| s);
+//! }
+//! ```
+//!
+//! [`render`] lays a tree out and draws its render commands with a small
+//! software rasterizer, so images come out the same on every machine and
+//! need no window. It draws rectangles, borders, images (as their tint, or
+//! gray), custom elements (as their background), canvases and shape
+//! rotation. Text is drawn as a bar across the middle of each line, so
+//! baselines catch layout changes without depending on fonts. Shaders,
+//! visual rotation and `.on_draw()` hooks are skipped.
+//!
+//! [`assert_snapshot`] compares an image against `tests/snapshots/<name>.png`.
+//! A missing baseline is written and the test passes; set
+//! `PLY_UPDATE_SNAPSHOTS=1` to rewrite baselines after intended changes.
+//! On a mismatch, `<name>.actual.png` and `<name>.diff.png` are written next
+//! to the baseline, with differing pixels in red over a dimmed baseline.
+//!
+//! [`TestHarness`] drives a headless UI with simulated input:
+//!
+//! ```ignore
+//! let mut harness = TestHarness::with_state(Dimensions::new(320.0, 240.0), Login::default(), |ui, login| {
+//!     login_form(ui, login);
+//! });
+//! harness.type_text("username", "bob").press_key(KeyCode::Tab).click("submit_btn");
+//! assert!(harness.state().submitted);
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use crate::canvas::PaintCommand;
+use crate::capture::ImageBuffer;
+use crate::color::Color;
+use crate::engine::ShapeRotationConfig;
+use macroquad::prelude::KeyCode;
+
+use crate::engine::{ArrowDirection, TextInputAction};
+use crate::id::Id;
+use crate::math::{BoundingBox, Dimensions, Vector2};
+use crate::render_commands::{CornerRadii, RenderCommand, RenderCommandConfig};
+use crate::text::TextConfig;
+use crate::{Ply, Ui};
+
+/// Color of images, which the rasterizer doesn't decode, without a tint.
+const IMAGE_PLACEHOLDER: Color = Color::rgba(128.0, 128.0, 128.0, 255.0);
+/// Sample offsets within a pixel, for 2×2 anti-aliasing.
+const SAMPLES: [(f32, f32); 4] = [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)];
+
+/// Lays out the tree built in `build` and rasterizes it at the size `ply` was created with.
+pub fn render<CustomElementData: Clone + Default + std::fmt::Debug>(
+    ply: &mut Ply<CustomElementData>,
+    build: impl FnOnce(&mut Ui<'_, CustomElementData>),
+) -> ImageBuffer {
+    let size = ply.context.layout_dimensions;
+    let mut ui = ply.begin();
+    build(&mut ui);
+    let commands = ui.eval();
+    rasterize(&commands, size.width as u16, size.height as u16)
+}
+
+/// Draws render commands into a `width` × `height` image, starting transparent.
+pub fn rasterize<CustomElementData>(
+    commands: &[RenderCommand<CustomElementData>],
+    width: u16,
+    height: u16,
+) -> ImageBuffer {
+    let mut raster = Raster::new(width, height);
+    for command in commands {
+        let bb = command.bounding_box;
+        let rotation = command.shape_rotation.as_ref();
+        match &command.config {
+            RenderCommandConfig::Rectangle(rect) => raster.rounded_rect(&bb, &rect.corner_radii, rotation, rect.color),
+            RenderCommandConfig::Image(image) => {
+                let color = if image.background_color.a > 0.0 { image.background_color } else { IMAGE_PLACEHOLDER };
+                raster.rounded_rect(&bb, &image.corner_radii, rotation, color);
+            }
+            RenderCommandConfig::Custom(custom) => {
+                raster.rounded_rect(&bb, &custom.corner_radii, rotation, custom.background_color)
+            }
+            RenderCommandConfig::Text(text) => {
+                let bar = BoundingBox::new(bb.x, bb.y + bb.height * 0.25, bb.width, bb.height * 0.5);
+                raster.fill(bounds(&bar), text.color, |x, y| contains(&bar, x, y));
+            }
+            RenderCommandConfig::Border(border) => {
+                let s = match border.position {
+                    crate::elements::BorderPosition::Outside => 1.0,
+                    crate::elements::BorderPosition::Middle => 0.5,
+                    crate::elements::BorderPosition::Inside => 0.0,
+                };
+                let w = &border.width;
+                let (left, right, top, bottom) = (w.left as f32, w.right as f32, w.top as f32, w.bottom as f32);
+                let outer = BoundingBox::new(
+                    bb.x - left * s,
+                    bb.y - top * s,
+                    bb.width + (left + right) * s,
+                    bb.height + (top + bottom) * s,
+                );
+                let inner = BoundingBox::new(
+                    bb.x + left * (1.0 - s),
+                    bb.y + top * (1.0 - s),
+                    bb.width - (left + right) * (1.0 - s),
+                    bb.height - (top + bottom) * (1.0 - s),
+                );
+                let cr = &border.corner_radii;
+                let outer_radii = CornerRadii {
+                    top_left: cr.top_left + left.max(top) * s,
+                    top_right: cr.top_right + right.max(top) * s,
+                    bottom_left: cr.bottom_left + left.max(bottom) * s,
+                    bottom_right: cr.bottom_right + right.max(bottom) * s,
+                };
+                let inner_radii = CornerRadii {
+                    top_left: (cr.top_left - left.max(top) * (1.0 - s)).max(0.0),
+                    top_right: (cr.top_right - right.max(top) * (1.0 - s)).max(0.0),
+                    bottom_left: (cr.bottom_left - left.max(bottom) * (1.0 - s)).max(0.0),
+                    bottom_right: (cr.bottom_right - right.max(bottom) * (1.0 - s)).max(0.0),
+                };
+                raster.fill(bounds(&outer), border.color, |x, y| {
+                    in_rounded_rect(&outer, &outer_radii, x, y) && !in_rounded_rect(&inner, &inner_radii, x, y)
+                });
+            }
+            RenderCommandConfig::Canvas(canvas) => {
+                let to_local = inverse_transform(&bb, rotation);
+                let area = transformed_bounds(&bb, rotation);
+                let on_canvas = |local: Vector2| local.x >= 0.0 && local.y >= 0.0 && local.x <= bb.width && local.y <= bb.height;
+                for paint in &canvas.commands {
+                    match paint {
+                        PaintCommand::Line { from, to, thickness, color } => {
+                            raster.fill(area, *color, |x, y| {
+                                let p = to_local(x, y);
+                                on_canvas(p) && segment_distance(p, *from, *to) <= thickness / 2.0
+                            });
+                        }
+                        PaintCommand::Circle { center, radius, fill, stroke } => {
+                            let distance = |x: f32, y: f32| {
+                                let p = to_local(x, y);
+                                (on_canvas(p), (p.x - center.x).hypot(p.y - center.y))
+                            };
+                            if let Some(color) = fill {
+                                raster.fill(area, *color, |x, y| matches!(distance(x, y), (true, d) if d <= *radius));
+                            }
+                            if let Some(stroke) = stroke {
+                                raster.fill(area, stroke.color, |x, y| {
+                                    matches!(distance(x, y), (true, d) if (d - radius).abs() <= stroke.thickness / 2.0)
+                                });
+                            }
+                        }
+                        PaintCommand::Path { points, closed, fill, stroke } => {
+                            if let Some(color) = fill {
+                                raster.fill(area, *color, |x, y| {
+                                    let p = to_local(x, y);
+                                    on_canvas(p) && in_polygon(points, p)
+                                });
+                            }
+                            if let Some(stroke) = stroke {
+                                let closing = if *closed { points.last().zip(points.first()) } else { None };
+                                raster.fill(area, stroke.color, |x, y| {
+                                    let p = to_local(x, y);
+                                    on_canvas(p)
+                                        && points
+                                            .windows(2)
+                                            .map(|pair| (pair[0], pair[1]))
+                                            .chain(closing.map(|(a, b)| (*a, *b)))
+                                            .any(|(a, b)| segment_distance(p, a, b) <= stroke.thickness / 2.0)
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            RenderCommandConfig::ScissorStart() => {
+                let clip = intersect(raster.clip(), bounds(&bb));
+                raster.clips.push(clip);
+            }
+            RenderCommandConfig::ScissorEnd() => {
+                raster.clips.pop();
+            }
+            RenderCommandConfig::Draw(_)
+            | RenderCommandConfig::GroupBegin { .. }
+            | RenderCommandConfig::GroupEnd
+            | RenderCommandConfig::None() => {}
+        }
+    }
+    raster.finish()
+}
+
+/// How two images differ, from [`diff`].
+#[derive(Debug, Clone)]
+pub struct SnapshotDiff {
+    /// Pixels with a channel differing by more than the tolerance.
+    pub differing: usize,
+    pub total: usize,
+    /// Differing pixels in red over the dimmed expected image.
+    pub image: ImageBuffer,
+}
+
+impl SnapshotDiff {
+    /// Share of pixels that differ, from 0 to 1.
+    pub fn ratio(&self) -> f32 {
+        if self.total == 0 { 0.0 } else { self.differing as f32 / self.total as f32 }
+    }
+}
+
+/// Compares two images pixel by pixel. Channels may differ by up to `tolerance`.
+///
+/// Images of different sizes differ everywhere.
+pub fn diff(expected: &ImageBuffer, actual: &ImageBuffer, tolerance: u8) -> SnapshotDiff {
+    if (expected.width, expected.height) != (actual.width, actual.height) {
+        let total = actual.rgba.len() / 4;
+        return SnapshotDiff {
+            differing: total.max(expected.rgba.len() / 4),
+            total: total.max(expected.rgba.len() / 4),
+            image: ImageBuffer {
+                width: actual.width,
+                height: actual.height,
+                rgba: [255, 0, 0, 255].repeat(total),
+            },
+        };
+    }
+    let mut differing = 0;
+    let mut rgba = Vec::with_capacity(expected.rgba.len());
+    for (want, got) in expected.rgba.chunks_exact(4).zip(actual.rgba.chunks_exact(4)) {
+        if want.iter().zip(got).any(|(a, b)| a.abs_diff(*b) > tolerance) {
+            differing += 1;
+            rgba.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let luma = (want[0] as u32 * 3 + want[1] as u32 * 6 + want[2] as u32) / 10;
+            let dimmed = (luma * want[3] as u32 / 255 / 3) as u8;
+            rgba.extend_from_slice(&[dimmed, dimmed, dimmed, 255]);
+        }
+    }
+    SnapshotDiff {
+        differing,
+        total: expected.rgba.len() / 4,
+        image: ImageBuffer { width: expected.width, height: expected.height, rgba },
+    }
+}
+
+/// Builder for configuring a snapshot comparison via closure.
+#[derive(Debug, Clone)]
+pub struct SnapshotBuilder {
+    pub(crate) dir: PathBuf,
+    pub(crate) tolerance: u8,
+    pub(crate) threshold: f32,
+}
+
+impl Default for SnapshotBuilder {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("tests/snapshots"),
+            tolerance: 2,
+            threshold: 0.0,
+        }
+    }
+}
+
+impl SnapshotBuilder {
+    /// Sets the directory baselines live in. Defaults to `tests/snapshots`.
+    #[inline]
+    pub fn dir(&mut self, dir: impl Into<PathBuf>) -> &mut Self {
+        self.dir = dir.into();
+        self
+    }
+
+    /// Sets how much a channel may differ before its pixel counts as changed. Defaults to 2.
+    #[inline]
+    pub fn tolerance(&mut self, tolerance: u8) -> &mut Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the share of pixels, from 0 to 1, allowed to change. Defaults to 0.
+    #[inline]
+    pub fn threshold(&mut self, threshold: f32) -> &mut Self {
+        self.threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// Compares `image` against the baseline `<dir>/<name>.png`, panicking when
+/// more pixels changed than the threshold allows.
+pub fn assert_snapshot(
+    name: &str,
+    image: &ImageBuffer,
+    config_fn: impl for<'a> FnOnce(&'a mut SnapshotBuilder) -> &'a mut SnapshotBuilder,
+) {
+    let mut config = SnapshotBuilder::default();
+    config_fn(&mut config);
+    let baseline = config.dir.join(format!("{name}.png"));
+    let update = std::env::var_os("PLY_UPDATE_SNAPSHOTS").is_some_and(|value| value != "0");
+
+    if update || !baseline.exists() {
+        std::fs::create_dir_all(&config.dir)
+            .and_then(|_| save_png(&baseline, image))
+            .unwrap_or_else(|e| panic!("Failed to write snapshot {}: {e}", baseline.display()));
+        return;
+    }
+
+    let expected = load_png(&baseline)
+        .unwrap_or_else(|e| panic!("Failed to read snapshot {}: {e}", baseline.display()));
+    let result = diff(&expected, image, config.tolerance);
+    if result.differing > 0 && result.ratio() > config.threshold {
+        let actual_path = config.dir.join(format!("{name}.actual.png"));
+        let diff_path = config.dir.join(format!("{name}.diff.png"));
+        let _ = save_png(&actual_path, image);
+        let _ = save_png(&diff_path, &result.image);
+        panic!(
+            "Snapshot '{name}' differs in {} of {} pixels ({:.2}%, allowed {:.2}%); see {}",
+            result.differing,
+            result.total,
+            result.ratio() * 100.0,
+            config.threshold * 100.0,
+            diff_path.display(),
+        );
+    }
+}
+
+/// Reads an 8-bit RGBA PNG, as written by [`save_png`].
+pub fn load_png(path: impl AsRef<Path>) -> io::Result<ImageBuffer> {
+    let decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    let mut reader = decoder.read_info().map_err(io::Error::other)?;
+    let mut rgba = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut rgba).map_err(io::Error::other)?;
+    if info.color_type != png::ColorType::Rgba || info.bit_depth != png::BitDepth::Eight {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected an 8-bit RGBA PNG"));
+    }
+    rgba.truncate(info.buffer_size());
+    let width = u16::try_from(info.width).map_err(io::Error::other)?;
+    let height = u16::try_from(info.height).map_err(io::Error::other)?;
+    Ok(ImageBuffer { width, height, rgba })
+}
+
+/// Writes an image as an 8-bit RGBA PNG.
+pub fn save_png(path: impl AsRef<Path>, image: &ImageBuffer) -> io::Result<()> {
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), image.width as u32, image.height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(&image.rgba).map_err(io::Error::other)
+}
+
+/// Pointer moves in a [`TestHarness::drag`], so drags register as movement over several frames.
+const DRAG_STEPS: u32 = 4;
+
+type BuildFn<State, CustomElementData> = Box<dyn FnMut(&mut Ui<'_, CustomElementData>, &mut State)>;
+
+/// A headless [`Ply`] that rebuilds the UI each frame and simulates pointer
+/// and keyboard input, for testing apps without a window.
+///
+/// Every input runs the frames a real one would, so callbacks and
+/// `is_just_pressed` queries fire as they do on screen. Elements are found
+/// by their bounds from the last frame.
+pub struct TestHarness<State = (), CustomElementData: Clone + Default + std::fmt::Debug = ()> {
+    ply: Ply<CustomElementData>,
+    state: State,
+    build: BuildFn<State, CustomElementData>,
+    commands: Vec<RenderCommand<CustomElementData>>,
+    frame_time: f32,
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> TestHarness<(), CustomElementData> {
+    /// Creates a harness drawing `build` every frame, and runs the first frame.
+    pub fn new(dimensions: impl Into<Dimensions>, mut build: impl FnMut(&mut Ui<'_, CustomElementData>) + 'static) -> Self {
+        Self::with_state(dimensions, (), move |ui, _| build(ui))
+    }
+}
+
+impl<State, CustomElementData: Clone + Default + std::fmt::Debug> TestHarness<State, CustomElementData> {
+    /// Creates a harness drawing `build` with `state` every frame, and runs the first frame.
+    ///
+    /// Text is measured as if monospaced, half as wide as the font size, so
+    /// layouts don't depend on fonts.
+    pub fn with_state(
+        dimensions: impl Into<Dimensions>,
+        state: State,
+        build: impl FnMut(&mut Ui<'_, CustomElementData>, &mut State) + 'static,
+    ) -> Self {
+        let mut ply = Ply::new_headless(dimensions.into());
+        ply.set_measure_text_function(|text: &str, config: &TextConfig| {
+            let advance = config.font_size as f32 / 2.0 + config.letter_spacing as f32;
+            let height = if config.line_height > 0 { config.line_height } else { config.font_size };
+            Dimensions::new(text.chars().count() as f32 * advance, height as f32)
+        });
+        let mut harness = Self {
+            ply,
+            state,
+            build: Box::new(build),
+            commands: Vec::new(),
+            frame_time: 1.0 / 60.0,
+        };
+        harness.step();
+        harness
+    }
+
+    /// The underlying engine, for queries like `is_pressed` or `get_text_value`.
+    pub fn ply(&self) -> &Ply<CustomElementData> {
+        &self.ply
+    }
+
+    pub fn ply_mut(&mut self) -> &mut Ply<CustomElementData> {
+        &mut self.ply
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+
+    /// Render commands of the last frame.
+    pub fn commands(&self) -> &[RenderCommand<CustomElementData>] {
+        &self.commands
+    }
+
+    /// Sets how much time passes each frame. Defaults to 1/60 s.
+    #[inline]
+    pub fn frame_time(&mut self, seconds: f32) -> &mut Self {
+        self.frame_time = seconds;
+        self
+    }
+
+    /// Runs one frame.
+    pub fn step(&mut self) -> &mut Self {
+        self.ply.context.current_time += self.frame_time as f64;
+        self.ply.context.frame_delta_time = self.frame_time;
+        let mut ui = self.ply.begin();
+        (self.build)(&mut ui, &mut self.state);
+        self.commands = ui.eval();
+        self
+    }
+
+    /// Runs frames until `seconds` have passed, e.g. to let animations finish.
+    pub fn advance(&mut self, seconds: f32) -> &mut Self {
+        let frames = (seconds / self.frame_time).ceil().max(1.0) as u32;
+        for _ in 0..frames {
+            self.step();
+        }
+        self
+    }
+
+    /// Bounds of an element in the last frame.
+    ///
+    /// # Panics
+    /// When the element wasn't drawn.
+    pub fn bounds(&self, id: impl Into<Id>) -> BoundingBox {
+        let id = id.into();
+        self.ply
+            .bounding_box(id.clone())
+            .unwrap_or_else(|| panic!("Element {id:?} wasn't drawn in the last frame"))
+    }
+
+    fn center(&self, id: impl Into<Id>) -> Vector2 {
+        let bounds = self.bounds(id);
+        Vector2::new(bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0)
+    }
+
+    fn pointer(&mut self, position: Vector2, is_down: bool) -> &mut Self {
+        self.ply.context.set_pointer_state(position, is_down);
+        self.step()
+    }
+
+    /// Moves the pointer over an element without pressing.
+    pub fn hover(&mut self, id: impl Into<Id>) -> &mut Self {
+        let center = self.center(id);
+        self.pointer(center, false)
+    }
+
+    /// Presses and releases at a position.
+    pub fn click_at(&mut self, position: impl Into<Vector2>) -> &mut Self {
+        let position = position.into();
+        self.pointer(position, false).pointer(position, true).pointer(position, false)
+    }
+
+    /// Presses and releases at the center of an element.
+    pub fn click(&mut self, id: impl Into<Id>) -> &mut Self {
+        let center = self.center(id);
+        self.click_at(center)
+    }
+
+    /// Presses at the center of an element, moves the pointer by `offset` and releases.
+    pub fn drag(&mut self, id: impl Into<Id>, offset: impl Into<Vector2>) -> &mut Self {
+        let from = self.center(id);
+        let offset = offset.into();
+        self.pointer(from, false).pointer(from, true);
+        for step in 1..=DRAG_STEPS {
+            let t = step as f32 / DRAG_STEPS as f32;
+            self.pointer(Vector2::new(from.x + offset.x * t, from.y + offset.y * t), true);
+        }
+        self.pointer(Vector2::new(from.x + offset.x, from.y + offset.y), false)
+    }
+
+    /// Scrolls the scroll container under an element by `delta` pixels.
+    pub fn scroll(&mut self, id: impl Into<Id>, delta: impl Into<Vector2>) -> &mut Self {
+        let center = self.center(id);
+        self.ply.context.set_pointer_state(center, false);
+        self.ply.update_scroll_containers(false, delta.into(), self.frame_time);
+        self.step()
+    }
+
+    /// Focuses a text input and types `text` at its cursor.
+    pub fn type_text(&mut self, id: impl Into<Id>, text: &str) -> &mut Self {
+        let id = id.into();
+        if self.ply.context.focused_element_id != id.id {
+            self.ply.set_focus(id);
+            self.step();
+        }
+        for ch in text.chars() {
+            self.ply.context.process_text_input_char(ch);
+        }
+        self.ply.context.update_text_input_scroll();
+        self.step()
+    }
+
+    /// Sets whether shift is held, for shift+Tab and extending selections with the arrow keys.
+    #[inline]
+    pub fn hold_shift(&mut self, held: bool) -> &mut Self {
+        self.ply.shift_down = held;
+        self
+    }
+
+    /// Presses and releases a key, handled as the focused element would on
+    /// screen: Tab cycles focus, Escape unfocuses or closes, the focused
+    /// element's activation keys (Enter and Space unless set) activate it, the
+    /// arrow keys move focus, and text inputs take editing keys.
+    /// Other keys only run a frame.
+    pub fn press_key(&mut self, key: KeyCode) -> &mut Self {
+        let shift = self.ply.shift_down;
+        let context = &mut self.ply.context;
+        if key == KeyCode::Tab {
+            context.cycle_focus(shift);
+        } else if key == KeyCode::Escape {
+            if context.is_text_input_focused() {
+                if context.should_focused_text_input_clear_on_escape() {
+                    context.process_text_input_action(TextInputAction::Clear);
+                } else {
+                    context.clear_focus();
+                }
+            }
+            self.ply.escape_pressed = true;
+            self.step();
+            self.ply.escape_pressed = false;
+            return self;
+        } else if context.is_text_input_focused() {
+            let multiline = context.is_focused_text_input_multiline();
+            let numeric = context.is_focused_text_input_numeric();
+            let action = match key {
+                KeyCode::Left => Some(TextInputAction::MoveLeft { shift }),
+                KeyCode::Right => Some(TextInputAction::MoveRight { shift }),
+                KeyCode::Home => Some(TextInputAction::MoveHome { shift }),
+                KeyCode::End => Some(TextInputAction::MoveEnd { shift }),
+                KeyCode::Backspace => Some(TextInputAction::Backspace),
+                KeyCode::Delete => Some(TextInputAction::Delete),
+                KeyCode::Enter => Some(TextInputAction::Submit),
+                KeyCode::Up if multiline => Some(TextInputAction::MoveUp { shift }),
+                KeyCode::Down if multiline => Some(TextInputAction::MoveDown { shift }),
+                KeyCode::Up if numeric => Some(TextInputAction::Increment),
+                KeyCode::Down if numeric => Some(TextInputAction::Decrement),
+                KeyCode::Up => {
+                    context.arrow_focus(ArrowDirection::Up);
+                    None
+                }
+                KeyCode::Down => {
+                    context.arrow_focus(ArrowDirection::Down);
+                    None
+                }
+                _ => None,
+            };
+            if let Some(action) = action {
+                context.process_text_input_action(action);
+                context.update_text_input_scroll();
+            }
+        } else {
+            match key {
+                KeyCode::Left => context.arrow_focus(ArrowDirection::Left),
+                KeyCode::Right => context.arrow_focus(ArrowDirection::Right),
+                KeyCode::Up => context.arrow_focus(ArrowDirection::Up),
+                KeyCode::Down => context.arrow_focus(ArrowDirection::Down),
+                _ if context.activation_keys(context.focused_element_id).contains(&key) => {
+                    context.handle_keyboard_activation(true, false);
+                    self.step();
+                    self.ply.context.handle_keyboard_activation(false, true);
+                }
+                _ => {}
+            }
+        }
+        self.step()
+    }
+}
+
+/// Premultiplied RGBA pixels being drawn into, with the scissor stack.
+struct Raster {
+    width: u16,
+    height: u16,
+    pixels: Vec<[f32; 4]>,
+    clips: Vec<(f32, f32, f32, f32)>,
+}
+
+impl Raster {
+    fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.0; 4]; width as usize * height as usize],
+            clips: Vec::new(),
+        }
+    }
+
+    fn clip(&self) -> (f32, f32, f32, f32) {
+        self.clips.last().copied().unwrap_or((0.0, 0.0, self.width as f32, self.height as f32))
+    }
+
+    /// Blends `color` over the pixels within `area` and the clip, weighted by
+    /// how many samples `inside` accepts.
+    fn fill(&mut self, area: (f32, f32, f32, f32), color: Color, inside: impl Fn(f32, f32) -> bool) {
+        if color.a <= 0.0 {
+            return;
+        }
+        let clip = self.clip();
+        let (x0, y0, x1, y1) = intersect(area, intersect(clip, (0.0, 0.0, self.width as f32, self.height as f32)));
+        if x1 <= x0 || y1 <= y0 {
+            return;
+        }
+        let alpha = color.a / 255.0;
+        let source = [color.r / 255.0 * alpha, color.g / 255.0 * alpha, color.b / 255.0 * alpha, alpha];
+        for py in y0.floor() as usize..y1.ceil() as usize {
+            for px in x0.floor() as usize..x1.ceil() as usize {
+                let hits = SAMPLES
+                    .iter()
+                    .map(|(dx, dy)| (px as f32 + dx, py as f32 + dy))
+                    .filter(|&(x, y)| x >= clip.0 && y >= clip.1 && x < clip.2 && y < clip.3 && inside(x, y))
+                    .count();
+                if hits == 0 {
+                    continue;
+                }
+                let coverage = hits as f32 / SAMPLES.len() as f32;
+                let pixel = &mut self.pixels[py * self.width as usize + px];
+                let keep = 1.0 - source[3] * coverage;
+                for channel in 0..4 {
+                    pixel[channel] = source[channel] * coverage + pixel[channel] * keep;
+                }
+            }
+        }
+    }
+
+    fn rounded_rect(&mut self, bb: &BoundingBox, radii: &CornerRadii, rotation: Option<&ShapeRotationConfig>, color: Color) {
+        let to_local = inverse_transform(bb, rotation);
+        let local = BoundingBox::new(0.0, 0.0, bb.width, bb.height);
+        self.fill(transformed_bounds(bb, rotation), color, |x, y| {
+            let p = to_local(x, y);
+            in_rounded_rect(&local, radii, p.x, p.y)
+        });
+    }
+
+    fn finish(self) -> ImageBuffer {
+        let rgba = self
+            .pixels
+            .iter()
+            .flat_map(|&[r, g, b, a]| {
+                let straight = |c: f32| if a > 0.0 { (c / a * 255.0).round().clamp(0.0, 255.0) as u8 } else { 0 };
+                [straight(r), straight(g), straight(b), (a * 255.0).round().clamp(0.0, 255.0) as u8]
+            })
+            .collect();
+        ImageBuffer { width: self.width, height: self.height, rgba }
+    }
+}
+
+/// `(x0, y0, x1, y1)` of a bounding box.
+fn bounds(bb: &BoundingBox) -> (f32, f32, f32, f32) {
+    (bb.x, bb.y, bb.x + bb.width, bb.y + bb.height)
+}
+
+fn intersect(a: (f32, f32, f32, f32), b: (f32, f32, f32, f32)) -> (f32, f32, f32, f32) {
+    (a.0.max(b.0), a.1.max(b.1), a.2.min(b.2), a.3.min(b.3))
+}
+
+fn contains(bb: &BoundingBox, x: f32, y: f32) -> bool {
+    x >= bb.x && y >= bb.y && x < bb.x + bb.width && y < bb.y + bb.height
+}
+
+fn in_rounded_rect(bb: &BoundingBox, radii: &CornerRadii, x: f32, y: f32) -> bool {
+    if !contains(bb, x, y) {
+        return false;
+    }
+    let (x0, y0, x1, y1) = bounds(bb);
+    let max_radius = bb.width.min(bb.height) / 2.0;
+    let corners = [
+        (radii.top_left, x0, y0, 1.0, 1.0),
+        (radii.top_right, x1, y0, -1.0, 1.0),
+        (radii.bottom_left, x0, y1, 1.0, -1.0),
+        (radii.bottom_right, x1, y1, -1.0, -1.0),
+    ];
+    corners.iter().all(|&(radius, cx, cy, sx, sy)| {
+        let radius = radius.clamp(0.0, max_radius);
+        // Only the square in the corner is rounded off
+        let (center_x, center_y) = (cx + sx * radius, cy + sy * radius);
+        let in_corner = (x - center_x) * sx < 0.0 && (y - center_y) * sy < 0.0;
+        !in_corner || (x - center_x).hypot(y - center_y) <= radius
+    })
+}
+
+/// Maps screen points into the element's unrotated local space, undoing
+/// [`crate::canvas::transform`].
+fn inverse_transform(bb: &BoundingBox, rotation: Option<&ShapeRotationConfig>) -> impl Fn(f32, f32) -> Vector2 {
+    let (center_x, center_y) = (bb.x + bb.width / 2.0, bb.y + bb.height / 2.0);
+    let (half_w, half_h) = (bb.width / 2.0, bb.height / 2.0);
+    let (flip_x, flip_y, sin, cos) = match rotation {
+        Some(r) => (r.flip_x, r.flip_y, r.rotation_radians.sin(), r.rotation_radians.cos()),
+        None => (false, false, 0.0, 1.0),
+    };
+    move |x, y| {
+        let (dx, dy) = (x - center_x, y - center_y);
+        let mut lx = dx * cos + dy * sin;
+        let mut ly = -dx * sin + dy * cos;
+        if flip_x {
+            lx = -lx;
+        }
+        if flip_y {
+            ly = -ly;
+        }
+        Vector2::new(lx + half_w, ly + half_h)
+    }
+}
+
+/// Screen-space bounds of the element after its shape rotation.
+fn transformed_bounds(bb: &BoundingBox, rotation: Option<&ShapeRotationConfig>) -> (f32, f32, f32, f32) {
+    let to_screen = crate::canvas::transform(bb, rotation);
+    let corners = [(0.0, 0.0), (bb.width, 0.0), (0.0, bb.height), (bb.width, bb.height)]
+        .map(|(x, y)| to_screen(Vector2::new(x, y)));
+    corners.iter().fold((f32::MAX, f32::MAX, f32::MIN, f32::MIN), |(x0, y0, x1, y1), c| {
+        (x0.min(c.x), y0.min(c.y), x1.max(c.x), y1.max(c.y))
+    })
+}
+
+fn segment_distance(p: Vector2, a: Vector2, b: Vector2) -> f32 {
+    let (abx, aby) = (b.x - a.x, b.y - a.y);
+    let length_squared = abx * abx + aby * aby;
+    let t = if length_squared > 0.0 {
+        (((p.x - a.x) * abx + (p.y - a.y) * aby) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (p.x - (a.x + t * abx)).hypot(p.y - (a.y + t * aby))
+}
+
+/// Even-odd test against the polygon through `points`.
+fn in_polygon(points: &[Vector2], p: Vector2) -> bool {
+    let mut inside = false;
+    let mut previous = match points.last() {
+        Some(last) => *last,
+        None => return false,
+    };
+    for &point in points {
+        if (point.y > p.y) != (previous.y > p.y)
+            && p.x < (previous.x - point.x) * (p.y - point.y) / (previous.y - point.y) + point.x
+        {
+            inside = !inside;
+        }
+        previous = point;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::Sizing;
+    use crate::math::Dimensions;
+
+    fn panel(ui: &mut Ui<'_, ()>, color: u32) {
+        ui.element()
+            .layout(|l| l.padding((4, 4, 4, 4)))
+            .children(|ui| {
+                ui.element()
+                    .width(Sizing::Fixed(20.0))
+                    .height(Sizing::Fixed(10.0))
+                    .background_color(color)
+                    .corner_radius(4.0)
+                    .empty();
+            });
+    }
+
+    #[test]
+    fn test_rasterize_and_compare_snapshots() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(32.0, 20.0));
+        let image = render(&mut ply, |ui| panel(ui, 0xFF0000));
+
+        assert_eq!((image.width, image.height), (32, 20));
+        assert_eq!(image.pixel(14, 9), Some([255, 0, 0, 255]));
+        // Outside the rectangle and in its rounded-off corner
+        assert_eq!(image.pixel(1, 1), Some([0, 0, 0, 0]));
+        assert_eq!(image.pixel(4, 4), Some([0, 0, 0, 0]));
+
+        let dir = std::env::temp_dir().join(format!("ply-snapshots-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        // The first run writes the baseline, the second matches it
+        assert_snapshot("panel", &image, |s| s.dir(&dir));
+        assert!(dir.join("panel.png").exists());
+        assert_snapshot("panel", &image, |s| s.dir(&dir));
+
+        let changed = render(&mut ply, |ui| panel(ui, 0x00FF00));
+        let result = diff(&image, &changed, 2);
+        assert!(result.differing > 100 && result.differing < 200);
+        assert_eq!(result.image.pixel(14, 9), Some([255, 0, 0, 255]));
+        let failed = std::panic::catch_unwind(|| assert_snapshot("panel", &changed, |s| s.dir(&dir)));
+        assert!(failed.is_err());
+        assert!(dir.join("panel.diff.png").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[derive(Default)]
+    struct Form {
+        submits: u32,
+    }
+
+    #[test]
+    fn test_harness_clicks_types_and_tabs() {
+        let mut harness: TestHarness<Form> = TestHarness::with_state((200.0, 100.0), Form::default(), |ui, form| {
+            ui.element()
+                .id("username")
+                .width(Sizing::Fixed(120.0))
+                .height(Sizing::Fixed(24.0))
+                .text_input(|t| t)
+                .empty();
+            ui.element()
+                .id("submit_btn")
+                .width(Sizing::Fixed(60.0))
+                .height(Sizing::Fixed(24.0))
+                .empty();
+            if ui.is_just_pressed("submit_btn") {
+                form.submits += 1;
+            }
+        });
+
+        harness.type_text("username", "bob").press_key(KeyCode::Backspace).type_text("username", "x");
+        assert_eq!(harness.ply().get_text_value("username"), "box");
+
+        // Escape leaves the input, Tab comes back to it
+        harness.press_key(KeyCode::Escape);
+        assert_eq!(harness.ply().focused_element(), None);
+        harness.press_key(KeyCode::Tab);
+        assert_eq!(harness.ply().focused_element().map(|id| id.id), Some(Id::new("username").id));
+
+        harness.click("submit_btn");
+        assert_eq!(harness.state().submits, 1);
+    }
+}
@@ -1,9 +1,12 @@
 use crate::align::AlignX;
 use crate::color::Color;
-use crate::renderer::FontAsset;
+use crate::math::{Dimensions, Vector2};
+use crate::id::Id;
+use crate::renderer::{FontAsset, ImageSource};
 use crate::shaders::{ShaderAsset, ShaderBuilder, ShaderConfig};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum WrapMode {
     /// Wraps on whitespaces not breaking words
@@ -13,16 +16,85 @@ pub enum WrapMode {
     Newline,
     /// Never wraps, can overflow of parent layout
     None,
+    /// Wraps on whitespaces like `Words`, but breaks words that don't fit on
+    /// a line by themselves at grapheme boundaries (URLs, hashes, CJK text)
+    Anywhere,
+}
+
+/// Marks where an inline atom goes in a text string. The n-th marker is replaced
+/// by the n-th atom added with [`TextConfig::inline_image`] or [`TextConfig::inline_element`].
+/// Markers past the last atom stay in the text.
+pub const INLINE_ATOM: char = '\u{FFFC}';
+
+/// Baseline estimate, as a fraction of the line height, when no baseline function is set.
+pub(crate) const INLINE_BASELINE_RATIO: f32 = 0.8;
+
+/// Vertical placement of inline atoms within their line of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InlineAlign {
+    /// The bottom of the atom sits on the text baseline.
+    #[default]
+    Baseline,
+    /// Top edges of atom and text line up.
+    Top,
+    /// The atom is centered on the text.
+    Center,
+    /// Bottom edges of atom and text line up.
+    Bottom,
+}
+
+/// What an inline atom draws.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum InlineAtomContent {
+    Image(ImageSource),
+    /// Reserved space, filled by a floating element attached to this ID.
+    Element(Id),
+}
+
+/// An icon, image or element embedded in the text flow.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct InlineAtom {
+    pub(crate) content: InlineAtomContent,
+    pub(crate) width: f32,
+    pub(crate) height: f32,
+}
+
+/// A drop shadow drawn behind text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextShadow {
+    /// Offset of the shadow from the text, in pixels.
+    pub offset: Vector2,
+    /// Blur radius in pixels. `0.0` gives a hard shadow.
+    pub blur: f32,
+    /// The color of the shadow.
+    pub color: Color,
+}
+
+/// An outline drawn around each glyph.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextOutline {
+    /// Outline thickness in pixels.
+    pub width: f32,
+    /// The color of the outline.
+    pub color: Color,
 }
 
 /// Configuration settings for rendering text elements.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct TextConfig {
     /// Internal engine user data.
     pub(crate) user_data: usize,
     /// The color of the text.
     pub color: Color,
     /// The font asset to use. `None` means use the default font.
+    #[cfg_attr(feature = "serde", serde(with = "crate::render_commands::serde_impls::font_asset"))]
     pub font_asset: Option<&'static FontAsset>,
     /// The font size of the text.
     pub font_size: u16,
@@ -34,10 +106,31 @@ pub struct TextConfig {
     pub wrap_mode: WrapMode,
     /// The alignment of the text.
     pub alignment: AlignX,
+    /// Stretches wrapped lines to the full width. The last line and lines
+    /// ending in a newline keep `alignment`.
+    pub justify: bool,
+    /// Maximum number of wrapped lines. `0` means unlimited.
+    pub max_lines: u16,
+    /// Optional drop shadow.
+    pub shadow: Option<TextShadow>,
+    /// Optional glyph outline.
+    pub outline: Option<TextOutline>,
     /// Per-element shader effects applied to this text.
     pub(crate) effects: Vec<ShaderConfig>,
     /// When true, the text content is exposed to screen readers as static text.
     pub(crate) accessible: bool,
+    /// When true, the text can be selected with the pointer and copied.
+    pub(crate) selectable: bool,
+    /// Highlight behind the selected text.
+    pub(crate) selection_color: Color,
+    /// Atoms replacing the [`INLINE_ATOM`] markers in the text, in order.
+    pub(crate) inline_atoms: Vec<InlineAtom>,
+    /// Byte offsets in the text of the markers `inline_atoms` replace, set
+    /// when the text is declared.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) inline_atom_offsets: Vec<usize>,
+    /// Vertical placement of inline atoms.
+    pub inline_align: InlineAlign,
 }
 
 impl TextConfig {
@@ -95,6 +188,58 @@ impl TextConfig {
         self
     }
 
+    /// Stretches wrapped lines to the full width by widening the gaps between
+    /// words, or between characters on lines without spaces. The last line
+    /// and lines ending in a newline keep their [`alignment`](Self::alignment).
+    #[inline]
+    pub fn justify(&mut self) -> &mut Self {
+        self.justify = true;
+        self
+    }
+
+    /// Limits wrapped text to `lines` lines, ending the last one with an ellipsis.
+    ///
+    /// Use [`Ply::is_text_clamped`](crate::Ply::is_text_clamped) on the parent
+    /// element to check whether any text was cut off.
+    ///
+    /// ```ignore
+    /// ui.text(&article.body, |t| t.font_size(16).max_lines(3));
+    /// ```
+    #[inline]
+    pub fn max_lines(&mut self, lines: u16) -> &mut Self {
+        self.max_lines = lines;
+        self
+    }
+
+    /// Draws a drop shadow behind the text.
+    ///
+    /// ```ignore
+    /// ui.text("Score: 100", |t| t.shadow((2.0, 2.0), 3.0, (0, 0, 0, 180)));
+    /// ```
+    #[inline]
+    pub fn shadow(&mut self, offset: impl Into<Vector2>, blur: f32, color: impl Into<Color>) -> &mut Self {
+        self.shadow = Some(TextShadow {
+            offset: offset.into(),
+            blur: blur.max(0.0),
+            color: color.into(),
+        });
+        self
+    }
+
+    /// Draws an outline around the text, keeping it readable over busy backgrounds.
+    ///
+    /// ```ignore
+    /// ui.text("Game Over", |t| t.font_size(48).color(0xFFFFFF).outline(2.0, 0x000000));
+    /// ```
+    #[inline]
+    pub fn outline(&mut self, width: f32, color: impl Into<Color>) -> &mut Self {
+        self.outline = Some(TextOutline {
+            width: width.max(0.0),
+            color: color.into(),
+        });
+        self
+    }
+
     /// Adds a per-element shader effect to this text.
     #[inline]
     pub fn effect(&mut self, asset: &ShaderAsset, f: impl FnOnce(&mut ShaderBuilder<'_>)) -> &mut Self {
@@ -114,6 +259,78 @@ impl TextConfig {
         self.accessible = true;
         self
     }
+
+    /// Lets users click-drag to select this text and copy it with Ctrl+C.
+    ///
+    /// ```ignore
+    /// ui.text(&log_line, |t| t.font_size(14).selectable());
+    /// ```
+    #[inline]
+    pub fn selectable(&mut self) -> &mut Self {
+        self.selectable = true;
+        self
+    }
+
+    /// Sets the highlight behind selected text. Defaults to the same color as
+    /// a text input's `selection_color`.
+    #[inline]
+    pub fn selection_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.selection_color = color.into();
+        self
+    }
+
+    /// Places an image at the next [`INLINE_ATOM`] marker in the text.
+    /// It wraps with the text like a word of the given width.
+    ///
+    /// ```ignore
+    /// ui.text(&format!("gg {INLINE_ATOM} well played"), |t| t
+    ///     .font_size(16)
+    ///     .inline_image(&EMOTE, (20.0, 20.0))
+    /// );
+    /// ```
+    #[inline]
+    pub fn inline_image(&mut self, image: impl Into<ImageSource>, size: impl Into<Dimensions>) -> &mut Self {
+        let size = size.into();
+        self.inline_atoms.push(InlineAtom {
+            content: InlineAtomContent::Image(image.into()),
+            width: size.width,
+            height: size.height,
+        });
+        self
+    }
+
+    /// Reserves space at the next [`INLINE_ATOM`] marker for an arbitrary element.
+    ///
+    /// The space is registered under `id`; fill it with a floating element
+    /// attached to that ID.
+    ///
+    /// ```ignore
+    /// ui.text(&format!("Press {INLINE_ATOM} to jump"), |t| t
+    ///     .inline_element("jump_key", (24.0, 24.0))
+    /// );
+    /// ui.element()
+    ///     .width(fixed!(24.0)).height(fixed!(24.0))
+    ///     .floating(|f| f.attach_id("jump_key"))
+    ///     .children(|ui| key_cap(ui, "Space"));
+    /// ```
+    #[inline]
+    pub fn inline_element(&mut self, id: impl Into<Id>, size: impl Into<Dimensions>) -> &mut Self {
+        let size = size.into();
+        self.inline_atoms.push(InlineAtom {
+            content: InlineAtomContent::Element(id.into()),
+            width: size.width,
+            height: size.height,
+        });
+        self
+    }
+
+    /// Sets how inline atoms are aligned vertically within their line.
+    /// Atoms taller than the text overflow it; raise `line_height` to make room.
+    #[inline]
+    pub fn inline_align(&mut self, align: InlineAlign) -> &mut Self {
+        self.inline_align = align;
+        self
+    }
 }
 
 impl Default for TextConfig {
@@ -126,9 +343,18 @@ impl Default for TextConfig {
             line_height: 0,
             wrap_mode: WrapMode::Words,
             alignment: AlignX::Left,
+            justify: false,
+            max_lines: 0,
+            shadow: None,
+            outline: None,
             effects: Vec::new(),
             font_asset: None,
             accessible: false,
+            selectable: false,
+            selection_color: crate::text_input::DEFAULT_SELECTION_COLOR,
+            inline_atoms: Vec::new(),
+            inline_atom_offsets: Vec::new(),
+            inline_align: InlineAlign::Baseline,
         }
     }
 }
@@ -258,6 +258,21 @@ impl TextEditState {
         self.reset_blink();
     }
 
+    /// Replaces the whole text, placing the cursor at the end.
+    pub fn replace_all(&mut self, text: &str) {
+        self.text = text.to_string();
+        #[cfg(feature = "text-styling")]
+        {
+            self.cursor_pos = styling::cursor_len(&self.text);
+        }
+        #[cfg(not(feature = "text-styling"))]
+        {
+            self.cursor_pos = self.text.chars().count();
+        }
+        self.selection_anchor = None;
+        self.reset_blink();
+    }
+
     /// Select all text.
     pub fn select_all(&mut self) {
         let len = self.text.chars().count();
@@ -1126,6 +1141,42 @@ impl TextEditState {
         self.reset_blink();
     }
 
+    /// Toggle the style `tag` on the selected text in styled mode, keeping the
+    /// selection. Returns false when nothing is selected.
+    pub fn toggle_style_styled(&mut self, tag: &str) -> bool {
+        let Some((start, end)) = self.selection_range() else {
+            return false;
+        };
+        let start_cp = styling::cursor_to_content(&self.text, start);
+        let end_cp = styling::cursor_to_content(&self.text, end);
+        if start_cp == end_cp {
+            return false;
+        }
+        let forward = self.cursor_pos >= self.selection_anchor.unwrap_or(0);
+        self.text = styling::toggle_style(&self.text, start_cp, end_cp, tag);
+        let start = styling::content_to_cursor(&self.text, start_cp, true);
+        let end = styling::content_to_cursor(&self.text, end_cp, false);
+        if forward {
+            self.selection_anchor = Some(start);
+            self.cursor_pos = end;
+        } else {
+            self.selection_anchor = Some(end);
+            self.cursor_pos = start;
+        }
+        self.snap_to_content_pos();
+        self.preferred_col = None;
+        self.reset_blink();
+        true
+    }
+
+    /// Get the selection as balanced markup, keeping the styles around it.
+    pub fn selected_markup_styled(&self) -> Option<String> {
+        let (start, end) = self.selection_range()?;
+        let start_cp = styling::cursor_to_content(&self.text, start);
+        let end_cp = styling::cursor_to_content(&self.text, end);
+        Some(styling::slice_content(&self.text, start_cp, end_cp))
+    }
+
     /// Convert the visual cursor_pos to a raw position for rendering.
     /// Enters empty style tags at the cursor boundary.
     pub fn cursor_pos_raw(&self) -> usize {
@@ -1152,6 +1203,7 @@ impl TextEditState {
 /// Configuration for a text input element's visual appearance.
 /// Stored per-frame in `PlyContext::text_input_configs`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextInputConfig {
     /// Placeholder text shown when input is empty.
     pub placeholder: String,
@@ -1178,11 +1230,25 @@ pub struct TextInputConfig {
     /// When true, cursor movement skips over `}` and empty content style positions.
     pub no_styles_movement: bool,
     /// The font asset to use. Resolved by the renderer.
+    #[cfg_attr(feature = "serde", serde(with = "crate::render_commands::serde_impls::font_asset"))]
     pub font_asset: Option<&'static crate::renderer::FontAsset>,
     /// Optional scrollbar configuration.
     pub scrollbar: Option<engine::ScrollbarConfig>,
+    /// When true, Escape clears the text instead of unfocusing, unless it is already empty.
+    pub clear_on_escape: bool,
+    /// When true, Ctrl+B and Ctrl+I toggle bold and italic on the selection.
+    pub rich_text: bool,
+    /// When true, dragging a touch selection handle shows a magnified view of the cursor's line.
+    pub magnifier: bool,
+    /// When set, the input only accepts numbers within these constraints.
+    pub numeric: Option<NumericConfig>,
+    /// Border color while the validator rejects the text.
+    pub invalid_border_color: Color,
 }
 
+/// Highlight behind selected text, in text inputs and selectable text.
+pub(crate) const DEFAULT_SELECTION_COLOR: Color = Color::rgba(69.0, 130.0, 181.0, 128.0);
+
 impl Default for TextInputConfig {
     fn default() -> Self {
         Self {
@@ -1195,20 +1261,153 @@ impl Default for TextInputConfig {
             text_color: Color::rgba(255.0, 255.0, 255.0, 255.0),
             placeholder_color: Color::rgba(128.0, 128.0, 128.0, 255.0),
             cursor_color: Color::rgba(255.0, 255.0, 255.0, 255.0),
-            selection_color: Color::rgba(69.0, 130.0, 181.0, 128.0),
+            selection_color: DEFAULT_SELECTION_COLOR,
             line_height: 0,
             no_styles_movement: false,
             font_asset: None,
             scrollbar: None,
+            clear_on_escape: false,
+            rich_text: false,
+            magnifier: false,
+            numeric: None,
+            invalid_border_color: Color::rgba(220.0, 53.0, 69.0, 255.0),
         }
     }
 }
 
+/// Constraints for a numeric text input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumericConfig {
+    /// Smallest allowed value. Also allows typing `-` when below zero or unset.
+    pub min: Option<f64>,
+    /// Largest allowed value.
+    pub max: Option<f64>,
+    /// Amount added or removed by arrow keys and the scroll wheel.
+    pub step: f64,
+    /// Maximum number of decimal places. `None` = unlimited, `Some(0)` = integers only.
+    pub decimals: Option<u8>,
+}
+
+impl Default for NumericConfig {
+    fn default() -> Self {
+        Self {
+            min: None,
+            max: None,
+            step: 1.0,
+            decimals: None,
+        }
+    }
+}
+
+impl NumericConfig {
+    /// Returns true if `text` is a number, or a prefix of one such as `-` or `1.`.
+    pub fn accepts(&self, text: &str) -> bool {
+        let allow_negative = self.min.is_none_or(|min| min < 0.0);
+        let digits = match text.strip_prefix('-') {
+            Some(rest) if allow_negative => rest,
+            Some(_) => return false,
+            None => text,
+        };
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => {
+                if self.decimals == Some(0) {
+                    return false;
+                }
+                (whole, Some(fraction))
+            }
+            None => (digits, None),
+        };
+        if !whole.chars().all(|c| c.is_ascii_digit()) {
+            return false;
+        }
+        match fraction {
+            Some(fraction) => {
+                fraction.chars().all(|c| c.is_ascii_digit())
+                    && self.decimals.is_none_or(|d| fraction.len() <= d as usize)
+            }
+            None => true,
+        }
+    }
+
+    /// Clamps `value` to the range and rounds it to the allowed decimals.
+    pub fn clamp(&self, mut value: f64) -> f64 {
+        if let Some(decimals) = self.decimals {
+            let factor = 10f64.powi(decimals as i32);
+            value = (value * factor).round() / factor;
+        }
+        if let Some(max) = self.max {
+            value = value.min(max);
+        }
+        if let Some(min) = self.min {
+            value = value.max(min);
+        }
+        value
+    }
+
+    /// Formats `value` for display, with exactly `decimals` places when set.
+    pub fn format(&self, value: f64) -> String {
+        match self.decimals {
+            Some(decimals) => format!("{:.*}", decimals as usize, value),
+            None => value.to_string(),
+        }
+    }
+
+    /// Returns the text after stepping `text` by `steps` times the step size.
+    /// Unparseable text counts as zero.
+    pub(crate) fn stepped(&self, text: &str, steps: f64) -> String {
+        let value = text.parse::<f64>().unwrap_or(0.0);
+        self.format(self.clamp(value + steps * self.step))
+    }
+
+    /// Returns the clamped, formatted text on submit. Empty and partial input is kept.
+    pub(crate) fn submitted(&self, text: &str) -> Option<String> {
+        text.parse::<f64>().ok().map(|value| self.format(self.clamp(value)))
+    }
+}
+
+/// Builder for numeric text input constraints via closure.
+pub struct NumericBuilder {
+    pub(crate) config: NumericConfig,
+}
+
+impl NumericBuilder {
+    /// Sets the smallest allowed value.
+    #[inline]
+    pub fn min(&mut self, min: f64) -> &mut Self {
+        self.config.min = Some(min);
+        self
+    }
+
+    /// Sets the largest allowed value.
+    #[inline]
+    pub fn max(&mut self, max: f64) -> &mut Self {
+        self.config.max = Some(max);
+        self
+    }
+
+    /// Sets the amount added or removed by arrow keys and the scroll wheel.
+    #[inline]
+    pub fn step(&mut self, step: f64) -> &mut Self {
+        self.config.step = step;
+        self
+    }
+
+    /// Limits the number of decimal places. `0` allows integers only.
+    #[inline]
+    pub fn decimals(&mut self, decimals: u8) -> &mut Self {
+        self.config.decimals = Some(decimals);
+        self
+    }
+}
+
 /// Builder for configuring a text input element via closure.
 pub struct TextInputBuilder {
     pub(crate) config: TextInputConfig,
-    pub(crate) on_changed_fn: Option<Box<dyn FnMut(&str) + 'static>>,
-    pub(crate) on_submit_fn: Option<Box<dyn FnMut(&str) + 'static>>,
+    pub(crate) on_changed_fn: Option<crate::engine::StrCallback>,
+    pub(crate) on_submit_fn: Option<crate::engine::StrCallback>,
+    pub(crate) on_changed_value_fn: Option<Box<dyn FnMut(f64) + 'static>>,
+    pub(crate) validate_fn: Option<crate::engine::ValidateFn>,
 }
 
 impl TextInputBuilder {
@@ -1217,6 +1416,8 @@ impl TextInputBuilder {
             config: TextInputConfig::default(),
             on_changed_fn: None,
             on_submit_fn: None,
+            on_changed_value_fn: None,
+            validate_fn: None,
         }
     }
 
@@ -1324,6 +1525,21 @@ impl TextInputBuilder {
         self
     }
 
+    /// Makes Escape clear the text. Escape on an empty input still unfocuses it.
+    #[inline]
+    pub fn clear_on_escape(&mut self) -> &mut Self {
+        self.config.clear_on_escape = true;
+        self
+    }
+
+    /// Shows a magnifier loupe above the cursor while a touch selection handle is dragged.
+    /// Selection handles themselves appear whenever the input is used by touch.
+    #[inline]
+    pub fn magnifier(&mut self) -> &mut Self {
+        self.config.magnifier = true;
+        self
+    }
+
     /// Enables no-styles movement mode.
     /// When enabled, cursor navigation skips over `}` exit positions and
     /// empty content markers, so the cursor only stops at visible character
@@ -1335,6 +1551,16 @@ impl TextInputBuilder {
         self
     }
 
+    /// Enables rich text editing. Ctrl+B and Ctrl+I toggle `{bold|...}` and
+    /// `{italic|...}` on the selection, and undo restores the previous spans.
+    /// Read the content with `Ply::get_styled_text`.
+    #[cfg(feature = "text-styling")]
+    #[inline]
+    pub fn rich_text(&mut self) -> &mut Self {
+        self.config.rich_text = true;
+        self
+    }
+
     /// Registers a callback fired whenever the text content changes.
     #[inline]
     pub fn on_changed<F>(&mut self, callback: F) -> &mut Self
@@ -1354,6 +1580,67 @@ impl TextInputBuilder {
         self.on_submit_fn = Some(Box::new(callback));
         self
     }
+
+    /// Restricts the input to numbers.
+    ///
+    /// Other characters are rejected as they are typed, the value is clamped
+    /// and rounded on Enter, and Up/Down or the scroll wheel step it.
+    ///
+    /// ```ignore
+    /// .text_input(|t| t.numeric(|n| n.min(0.0).max(100.0).step(1.0).decimals(2)))
+    /// ```
+    #[inline]
+    pub fn numeric(
+        &mut self,
+        f: impl for<'a> FnOnce(&'a mut NumericBuilder) -> &'a mut NumericBuilder,
+    ) -> &mut Self {
+        let mut builder = NumericBuilder {
+            config: self.config.numeric.unwrap_or_default(),
+        };
+        f(&mut builder);
+        self.config.numeric = Some(builder.config);
+        self
+    }
+
+    /// Sets a validator run against the text every frame.
+    ///
+    /// While it returns an error, the input's border switches to the
+    /// [`invalid_border_color`](Self::invalid_border_color) and
+    /// [`Ply::is_valid`](crate::Ply::is_valid) returns `false`.
+    ///
+    /// ```ignore
+    /// .text_input(|t| t.validate(|text| {
+    ///     if text.contains('@') { Ok(()) } else { Err("Not an email address".into()) }
+    /// }))
+    /// ```
+    #[inline]
+    pub fn validate<F>(&mut self, validator: F) -> &mut Self
+    where
+        F: Fn(&str) -> Result<(), String> + 'static,
+    {
+        self.validate_fn = Some(Box::new(validator));
+        self
+    }
+
+    /// Sets the border color shown while the validator rejects the text.
+    ///
+    /// Inputs without a border get a 2px one while invalid.
+    #[inline]
+    pub fn invalid_border_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.config.invalid_border_color = color.into();
+        self
+    }
+
+    /// Registers a callback fired with the parsed value whenever the text
+    /// changes to a complete number. Pairs with [`numeric`](Self::numeric).
+    #[inline]
+    pub fn on_changed_value<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(f64) + 'static,
+    {
+        self.on_changed_value_fn = Some(Box::new(callback));
+        self
+    }
 }
 
 /// Convert a character index to a byte index in the string.
@@ -2489,6 +2776,122 @@ pub mod styling {
         visual
     }
 
+    /// Split styled markup into its visible characters, each with the stack of
+    /// style tags around it, outermost first.
+    pub(crate) fn styled_chars(raw: &str) -> Vec<(char, Vec<String>)> {
+        let mut chars: Vec<(char, Vec<String>)> = Vec::new();
+        let mut stack: Vec<String> = Vec::new();
+        let mut header = String::new();
+        let mut escaped = false;
+        let mut in_style_def = false;
+        for c in raw.chars() {
+            if in_style_def {
+                if escaped {
+                    header.push(c);
+                    escaped = false;
+                    continue;
+                }
+                match c {
+                    '\\' => {
+                        header.push(c);
+                        escaped = true;
+                    }
+                    '|' => {
+                        stack.push(std::mem::take(&mut header));
+                        in_style_def = false;
+                    }
+                    _ => header.push(c),
+                }
+                continue;
+            }
+            if escaped {
+                chars.push((c, stack.clone()));
+                escaped = false;
+                continue;
+            }
+            match c {
+                '\\' => escaped = true,
+                '{' => in_style_def = true,
+                '}' => { stack.pop(); }
+                _ => chars.push((c, stack.clone())),
+            }
+        }
+        chars
+    }
+
+    /// Write characters with their style stacks, opening and closing tags only
+    /// where neighbouring characters differ, so shared styles stay nested.
+    pub(crate) fn write_styled_chars(
+        chars: &[(char, Vec<String>)],
+        out: &mut String,
+        mut open_tag: impl FnMut(&mut String, &str),
+        mut close_tag: impl FnMut(&mut String, &str),
+        mut write_char: impl FnMut(&mut String, char),
+    ) {
+        let mut open: Vec<&str> = Vec::new();
+        for (ch, tags) in chars {
+            let common = open.iter().zip(tags).take_while(|(a, b)| **a == b.as_str()).count();
+            while open.len() > common {
+                if let Some(tag) = open.pop() {
+                    close_tag(out, tag);
+                }
+            }
+            for tag in &tags[common..] {
+                open_tag(out, tag);
+                open.push(tag);
+            }
+            write_char(out, *ch);
+        }
+        while let Some(tag) = open.pop() {
+            close_tag(out, tag);
+        }
+    }
+
+    fn chars_to_markup(chars: &[(char, Vec<String>)]) -> String {
+        let mut result = String::new();
+        write_styled_chars(
+            chars,
+            &mut result,
+            |out, tag| {
+                out.push('{');
+                out.push_str(tag);
+                out.push('|');
+            },
+            |out, _| out.push('}'),
+            |out, ch| out.push_str(&escape_char(ch)),
+        );
+        result
+    }
+
+    /// Toggle the style `tag` on the content characters in `[content_start, content_end)`.
+    /// If every character in the range already carries the tag it is removed,
+    /// otherwise it is added as the innermost style. The markup is rebuilt from
+    /// per-character style stacks, so empty style tags are dropped.
+    pub fn toggle_style(raw: &str, content_start: usize, content_end: usize, tag: &str) -> String {
+        let mut chars = styled_chars(raw);
+        let end = content_end.min(chars.len());
+        let start = content_start.min(end);
+        let range = &mut chars[start..end];
+        let remove = !range.is_empty() && range.iter().all(|(_, tags)| tags.iter().any(|t| t == tag));
+        for (_, tags) in range.iter_mut() {
+            if remove {
+                tags.retain(|t| t != tag);
+            } else if !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+        chars_to_markup(&chars)
+    }
+
+    /// Get balanced markup for the content characters in `[content_start, content_end)`,
+    /// reopening the style tags that enclose the range.
+    pub fn slice_content(raw: &str, content_start: usize, content_end: usize) -> String {
+        let chars = styled_chars(raw);
+        let end = content_end.min(chars.len());
+        let start = content_start.min(end);
+        chars_to_markup(&chars[start..end])
+    }
+
     /// Delete content characters in `[content_start, content_end)` from the
     /// raw styled string, preserving all structural/tag characters.
     pub fn delete_content_range(raw: &str, content_start: usize, content_end: usize) -> String {
@@ -3208,9 +3611,7 @@ mod tests {
 
     #[test]
     fn test_backspace() {
-        let mut state = TextEditState::default();
-        state.text = "Hello".to_string();
-        state.cursor_pos = 5;
+        let mut state = TextEditState { text: "Hello".to_string(), cursor_pos: 5, ..Default::default() };
         state.backspace();
         assert_eq!(state.text, "Hell");
         assert_eq!(state.cursor_pos, 4);
@@ -3218,9 +3619,7 @@ mod tests {
 
     #[test]
     fn test_delete_forward() {
-        let mut state = TextEditState::default();
-        state.text = "Hello".to_string();
-        state.cursor_pos = 0;
+        let mut state = TextEditState { text: "Hello".to_string(), cursor_pos: 0, ..Default::default() };
         state.delete_forward();
         assert_eq!(state.text, "ello");
         assert_eq!(state.cursor_pos, 0);
@@ -3228,10 +3627,12 @@ mod tests {
 
     #[test]
     fn test_selection_delete() {
-        let mut state = TextEditState::default();
-        state.text = "Hello World".to_string();
-        state.selection_anchor = Some(0);
-        state.cursor_pos = 5;
+        let mut state = TextEditState {
+            text: "Hello World".to_string(),
+            selection_anchor: Some(0),
+            cursor_pos: 5,
+            ..Default::default()
+        };
         state.delete_selection();
         assert_eq!(state.text, " World");
         assert_eq!(state.cursor_pos, 0);
@@ -3240,9 +3641,7 @@ mod tests {
 
     #[test]
     fn test_select_all() {
-        let mut state = TextEditState::default();
-        state.text = "Hello".to_string();
-        state.cursor_pos = 2;
+        let mut state = TextEditState { text: "Hello".to_string(), cursor_pos: 2, ..Default::default() };
         state.select_all();
         assert_eq!(state.selection_anchor, Some(0));
         assert_eq!(state.cursor_pos, 5);
@@ -3250,9 +3649,7 @@ mod tests {
 
     #[test]
     fn test_move_left_right() {
-        let mut state = TextEditState::default();
-        state.text = "AB".to_string();
-        state.cursor_pos = 1;
+        let mut state = TextEditState { text: "AB".to_string(), cursor_pos: 1, ..Default::default() };
 
         state.move_left(false);
         assert_eq!(state.cursor_pos, 0);
@@ -3263,9 +3660,7 @@ mod tests {
 
     #[test]
     fn test_move_with_shift_creates_selection() {
-        let mut state = TextEditState::default();
-        state.text = "Hello".to_string();
-        state.cursor_pos = 2;
+        let mut state = TextEditState { text: "Hello".to_string(), cursor_pos: 2, ..Default::default() };
 
         state.move_right(true);
         assert_eq!(state.cursor_pos, 3);
@@ -3303,8 +3698,7 @@ mod tests {
 
     #[test]
     fn test_ensure_cursor_visible() {
-        let mut state = TextEditState::default();
-        state.scroll_offset = 0.0;
+        let mut state = TextEditState { scroll_offset: 0.0, ..Default::default() };
 
         // Cursor at x=150, visible_width=100 → should scroll right
         state.ensure_cursor_visible(150.0, 100.0);
@@ -3317,9 +3711,11 @@ mod tests {
 
     #[test]
     fn test_backspace_word() {
-        let mut state = TextEditState::default();
-        state.text = "hello world".to_string();
-        state.cursor_pos = 11;
+        let mut state = TextEditState {
+            text: "hello world".to_string(),
+            cursor_pos: 11,
+            ..Default::default()
+        };
         state.backspace_word();
         assert_eq!(state.text, "hello ");
         assert_eq!(state.cursor_pos, 6);
@@ -3327,9 +3723,11 @@ mod tests {
 
     #[test]
     fn test_delete_word_forward() {
-        let mut state = TextEditState::default();
-        state.text = "hello world".to_string();
-        state.cursor_pos = 0;
+        let mut state = TextEditState {
+            text: "hello world".to_string(),
+            cursor_pos: 0,
+            ..Default::default()
+        };
         state.delete_word_forward();
         assert_eq!(state.text, "world");
         assert_eq!(state.cursor_pos, 0);
@@ -3414,8 +3812,7 @@ mod tests {
 
     #[test]
     fn test_move_up_down() {
-        let mut state = TextEditState::default();
-        state.text = "hello\nworld".to_string();
+        let mut state = TextEditState { text: "hello\nworld".to_string(), ..Default::default() };
         state.cursor_pos = 8; // 'r' on line 1, col 2
 
         state.move_up(false);
@@ -3427,8 +3824,7 @@ mod tests {
 
     #[test]
     fn test_move_up_clamps_column() {
-        let mut state = TextEditState::default();
-        state.text = "ab\nhello".to_string();
+        let mut state = TextEditState { text: "ab\nhello".to_string(), ..Default::default() };
         state.cursor_pos = 7; // line 1, col 4 (before 'o')
 
         state.move_up(false);
@@ -3437,9 +3833,11 @@ mod tests {
 
     #[test]
     fn test_move_up_from_first_line() {
-        let mut state = TextEditState::default();
-        state.text = "hello\nworld".to_string();
-        state.cursor_pos = 3;
+        let mut state = TextEditState {
+            text: "hello\nworld".to_string(),
+            cursor_pos: 3,
+            ..Default::default()
+        };
 
         state.move_up(false);
         assert_eq!(state.cursor_pos, 0); // moves to start
@@ -3447,9 +3845,11 @@ mod tests {
 
     #[test]
     fn test_move_down_from_last_line() {
-        let mut state = TextEditState::default();
-        state.text = "hello\nworld".to_string();
-        state.cursor_pos = 8;
+        let mut state = TextEditState {
+            text: "hello\nworld".to_string(),
+            cursor_pos: 8,
+            ..Default::default()
+        };
 
         state.move_down(false);
         assert_eq!(state.cursor_pos, 11); // moves to end
@@ -3457,8 +3857,7 @@ mod tests {
 
     #[test]
     fn test_move_line_home_end() {
-        let mut state = TextEditState::default();
-        state.text = "hello\nworld".to_string();
+        let mut state = TextEditState { text: "hello\nworld".to_string(), ..Default::default() };
         state.cursor_pos = 8; // line 1, col 2
 
         state.move_line_home(false);
@@ -3470,9 +3869,11 @@ mod tests {
 
     #[test]
     fn test_move_up_with_shift_selects() {
-        let mut state = TextEditState::default();
-        state.text = "hello\nworld".to_string();
-        state.cursor_pos = 8;
+        let mut state = TextEditState {
+            text: "hello\nworld".to_string(),
+            cursor_pos: 8,
+            ..Default::default()
+        };
 
         state.move_up(true);
         assert_eq!(state.cursor_pos, 2);
@@ -3481,8 +3882,7 @@ mod tests {
 
     #[test]
     fn test_ensure_cursor_visible_vertical() {
-        let mut state = TextEditState::default();
-        state.scroll_offset_y = 0.0;
+        let mut state = TextEditState { scroll_offset_y: 0.0, ..Default::default() };
 
         // Cursor on line 5, line_height=20, visible_height=60
         // cursor_bottom = 5*20+20 = 120 > 60 → scroll down
@@ -3601,9 +4001,7 @@ mod tests {
 
     #[test]
     fn test_undo_basic() {
-        let mut state = TextEditState::default();
-        state.text = "hello".to_string();
-        state.cursor_pos = 5;
+        let mut state = TextEditState { text: "hello".to_string(), cursor_pos: 5, ..Default::default() };
 
         // Push undo, then modify
         state.push_undo(UndoActionKind::Paste);
@@ -3645,9 +4043,7 @@ mod tests {
 
     #[test]
     fn test_undo_grouping_backspace() {
-        let mut state = TextEditState::default();
-        state.text = "hello".to_string();
-        state.cursor_pos = 5;
+        let mut state = TextEditState { text: "hello".to_string(), cursor_pos: 5, ..Default::default() };
 
         // Backspace 3 times
         state.push_undo(UndoActionKind::Backspace);
@@ -3708,9 +4104,7 @@ mod tests {
 
     #[cfg(feature = "text-styling")]
     fn make_no_styles_state(raw: &str) -> TextEditState {
-        let mut s = TextEditState::default();
-        s.text = raw.to_string();
-        s.no_styles_movement = true;
+        let mut s = TextEditState { text: raw.to_string(), no_styles_movement: true, ..Default::default() };
         // Snap the cursor to a content boundary at position 0
         s.cursor_pos = 0;
         s
@@ -3751,6 +4145,48 @@ mod tests {
         assert_eq!(delete_content_range("abc", 1, 1), "abc");
     }
 
+    #[test]
+    #[cfg(feature = "text-styling")]
+    fn test_toggle_style() {
+        use crate::text_input::styling::toggle_style;
+        assert_eq!(toggle_style("hello world", 0, 5, "bold"), "{bold|hello} world");
+        assert_eq!(toggle_style("{bold|hello} world", 0, 5, "bold"), "hello world");
+        // Partially styled ranges get the style everywhere
+        assert_eq!(toggle_style("{bold|hello} world", 3, 8, "bold"), "{bold|hello wo}rld");
+        // New styles nest inside existing ones
+        assert_eq!(toggle_style("{red|abc}", 1, 2, "italic"), "{red|a{italic|b}c}");
+        assert_eq!(toggle_style("a\\{b", 0, 3, "bold"), "{bold|a\\{b}");
+    }
+
+    #[test]
+    #[cfg(feature = "text-styling")]
+    fn test_slice_content() {
+        use crate::text_input::styling::slice_content;
+        assert_eq!(slice_content("{bold|hello} world", 2, 8), "{bold|llo} wo");
+        assert_eq!(slice_content("{red|a{italic|bc}d}", 1, 2), "{red|{italic|b}}");
+        assert_eq!(slice_content("a\\{b", 1, 3), "\\{b");
+        assert_eq!(slice_content("abc", 2, 10), "c");
+    }
+
+    #[test]
+    #[cfg(feature = "text-styling")]
+    fn test_toggle_style_keeps_selection_and_undoes() {
+        let mut state = TextEditState {
+            text: "hello world".to_string(),
+            selection_anchor: Some(0),
+            cursor_pos: 5,
+            ..Default::default()
+        };
+
+        state.push_undo(UndoActionKind::Other);
+        assert!(state.toggle_style_styled("bold"));
+        assert_eq!(state.text, "{bold|hello} world");
+        assert_eq!(state.selected_text_styled(), "hello");
+
+        assert!(state.undo());
+        assert_eq!(state.text, "hello world");
+    }
+
     #[test]
     #[cfg(feature = "text-styling")]
     fn test_no_styles_move_right() {
@@ -3855,4 +4291,35 @@ mod tests {
         let stripped = styling::strip_styling(&s.text);
         assert!(stripped.is_empty());
     }
+
+    #[test]
+    fn test_numeric_accepts_partial_numbers() {
+        let numeric = NumericConfig { min: Some(-10.0), decimals: Some(2), ..Default::default() };
+        assert!(numeric.accepts(""));
+        assert!(numeric.accepts("-"));
+        assert!(numeric.accepts("1."));
+        assert!(numeric.accepts("-3.25"));
+        assert!(!numeric.accepts("3.255"));
+        assert!(!numeric.accepts("1e3"));
+        assert!(!numeric.accepts("1.2.3"));
+
+        let unsigned_integer = NumericConfig { min: Some(0.0), decimals: Some(0), ..Default::default() };
+        assert!(!unsigned_integer.accepts("-1"));
+        assert!(!unsigned_integer.accepts("1."));
+    }
+
+    #[test]
+    fn test_numeric_clamps_and_steps() {
+        let numeric = NumericConfig {
+            min: Some(0.0),
+            max: Some(100.0),
+            step: 2.5,
+            decimals: Some(1),
+        };
+        assert_eq!(numeric.submitted("150").as_deref(), Some("100.0"));
+        assert_eq!(numeric.submitted("3.14").as_deref(), Some("3.1"));
+        assert_eq!(numeric.submitted("-"), None);
+        assert_eq!(numeric.stepped("1", 1.0), "3.5");
+        assert_eq!(numeric.stepped("", -1.0), "0.0");
+    }
 }
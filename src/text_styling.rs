@@ -1,6 +1,7 @@
 use rustc_hash::FxHashMap;
 use std::f32::consts::PI;
 use crate::color::Color;
+use crate::text_input::styling::escape_str;
 
 #[derive(Debug, Clone)]
 pub struct StyledSegment {
@@ -15,6 +16,10 @@ pub struct Transform {
     pub scale_x: f32, 
     pub scale_y: f32,
     pub rotation: f32,
+    /// Extra horizontal stroke in pixels for faux bold. `0.0` draws normally.
+    pub weight: f32,
+    /// Horizontal shear around the baseline for faux italic. `0.0` draws upright.
+    pub skew: f32,
 }
 
 impl Default for Transform {
@@ -24,11 +29,281 @@ impl Default for Transform {
             y: 0.0, 
             scale_x: 1.0, 
             scale_y: 1.0, 
-            rotation: 0.0 
+            rotation: 0.0,
+            weight: 0.0,
+            skew: 0.0,
         } 
     }
 }
 
+/// Faux bold stroke width as a fraction of the font size.
+const BOLD_WEIGHT_RATIO: f32 = 1.0 / 24.0;
+/// Faux italic shear factor.
+const ITALIC_SKEW: f32 = 0.2;
+
+/// Color of `{link=...|...}` text unless an inner `color` overrides it.
+pub const LINK_COLOR: Color = Color { r: 51.0, g: 153.0, b: 255.0, a: 255.0 };
+
+/// A `{link=URL|text}` span. `start..end` is the byte range of its content in the raw markup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkSpan {
+    pub start: usize,
+    pub end: usize,
+    pub url: String,
+}
+
+/// Finds all link spans in styled markup. Nested links are listed innermost first.
+pub fn link_spans(raw: &str) -> Vec<LinkSpan> {
+    let mut spans = Vec::new();
+    // For each open style: its link URL (if any) and where its content starts
+    let mut stack: Vec<(Option<String>, usize)> = Vec::new();
+    let mut in_style_def = false;
+    let mut escaped = false;
+    let mut style_buffer = String::new();
+
+    for (i, c) in raw.char_indices() {
+        if escaped {
+            if in_style_def {
+                style_buffer.push(c);
+            }
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '{' if !in_style_def => {
+                in_style_def = true;
+                style_buffer.clear();
+            }
+            '|' if in_style_def => {
+                in_style_def = false;
+                // The URL is everything after `link=`, so it may contain `_` and `=`
+                let url = style_buffer.strip_prefix("link=").map(str::to_string);
+                stack.push((url, i + 1));
+            }
+            '}' if !in_style_def => {
+                if let Some((Some(url), start)) = stack.pop() {
+                    spans.push(LinkSpan { start, end: i, url });
+                }
+            }
+            _ => {
+                if in_style_def {
+                    style_buffer.push(c);
+                }
+            }
+        }
+    }
+    spans
+}
+
+/// Text with styled spans, built in code instead of written as markup.
+///
+/// Span text is escaped automatically, so dynamic content can't break the
+/// markup. Derefs to the generated markup, so it can be passed anywhere a
+/// styled `&str` is accepted:
+///
+/// ```ignore
+/// let message = StyledText::new()
+///     .push(&user_name, |s| s.bold().color(0x4FC3F7))
+///     .plain(": ")
+///     .plain(&user_input);
+/// ui.text(&message, |t| t.font_size(16));
+/// ply.set_text_value("editor", &message);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct StyledText {
+    markup: String,
+}
+
+impl StyledText {
+    /// Creates empty styled text.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps existing markup, e.g. the value of a styled text input.
+    pub fn from_markup(markup: impl Into<String>) -> Self {
+        Self { markup: markup.into() }
+    }
+
+    /// Appends unstyled text.
+    pub fn plain(mut self, text: &str) -> Self {
+        self.markup.push_str(&escape_str(text));
+        self
+    }
+
+    /// Appends a span styled by `style`.
+    pub fn push(mut self, text: &str, style: impl FnOnce(&mut SpanStyle) -> &mut SpanStyle) -> Self {
+        let mut span = SpanStyle::default();
+        style(&mut span);
+        for tag in &span.tags {
+            self.markup.push('{');
+            self.markup.push_str(tag);
+            self.markup.push('|');
+        }
+        self.markup.push_str(&escape_str(text));
+        for _ in &span.tags {
+            self.markup.push('}');
+        }
+        self
+    }
+
+    /// Appends other styled text.
+    pub fn append(mut self, other: &StyledText) -> Self {
+        self.markup.push_str(&other.markup);
+        self
+    }
+
+    /// Returns the generated markup.
+    pub fn as_str(&self) -> &str {
+        &self.markup
+    }
+
+    /// Returns the generated markup.
+    pub fn into_markup(self) -> String {
+        self.markup
+    }
+
+    /// Returns the text with all styling removed.
+    pub fn plain_text(&self) -> String {
+        crate::text_input::styling::strip_styling(&self.markup)
+    }
+}
+
+impl std::ops::Deref for StyledText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.markup
+    }
+}
+
+impl std::fmt::Display for StyledText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.markup)
+    }
+}
+
+impl From<StyledText> for String {
+    fn from(text: StyledText) -> Self {
+        text.markup
+    }
+}
+
+/// Styles for one [`StyledText`] span. Styles apply in call order, later ones
+/// overriding earlier ones where they conflict.
+#[derive(Debug, Clone, Default)]
+pub struct SpanStyle {
+    tags: Vec<String>,
+}
+
+impl SpanStyle {
+    /// Sets the text color. Alpha below 255 is applied as opacity.
+    #[inline]
+    pub fn color(&mut self, color: impl Into<Color>) -> &mut Self {
+        let color = color.into();
+        self.tags.push(format!(
+            "color=#{:02X}{:02X}{:02X}",
+            color.r.clamp(0.0, 255.0) as u8,
+            color.g.clamp(0.0, 255.0) as u8,
+            color.b.clamp(0.0, 255.0) as u8,
+        ));
+        if color.a < 255.0 {
+            self.opacity(color.a.max(0.0) / 255.0);
+        }
+        self
+    }
+
+    /// Multiplies the text opacity (0.0 to 1.0).
+    #[inline]
+    pub fn opacity(&mut self, opacity: f32) -> &mut Self {
+        self.tags.push(format!("opacity={opacity}"));
+        self
+    }
+
+    /// Draws the text faux-bold.
+    #[inline]
+    pub fn bold(&mut self) -> &mut Self {
+        self.tags.push("bold".to_string());
+        self
+    }
+
+    /// Draws the text faux-italic.
+    #[inline]
+    pub fn italic(&mut self) -> &mut Self {
+        self.tags.push("italic".to_string());
+        self
+    }
+
+    /// Makes the span a link to `url`. See `on_link_clicked`.
+    #[inline]
+    pub fn link(&mut self, url: &str) -> &mut Self {
+        // Spaces would split the tag when wrapping, so percent-encode them
+        let url = escape_str(url).replace(' ', "%20");
+        self.tags.push(format!("link={url}"));
+        self
+    }
+
+    /// Bobs the characters up and down with default wave settings.
+    #[inline]
+    pub fn wave(&mut self) -> &mut Self {
+        self.tags.push("wave".to_string());
+        self
+    }
+
+    /// Pulses the character size with default settings.
+    #[inline]
+    pub fn pulse(&mut self) -> &mut Self {
+        self.tags.push("pulse".to_string());
+        self
+    }
+
+    /// Rocks the characters back and forth with default settings.
+    #[inline]
+    pub fn swing(&mut self) -> &mut Self {
+        self.tags.push("swing".to_string());
+        self
+    }
+
+    /// Shakes the characters randomly with default settings.
+    #[inline]
+    pub fn jitter(&mut self) -> &mut Self {
+        self.tags.push("jitter".to_string());
+        self
+    }
+
+    /// Cycles the characters through the default rainbow gradient.
+    #[inline]
+    pub fn gradient(&mut self) -> &mut Self {
+        self.tags.push("gradient".to_string());
+        self
+    }
+
+    /// Adds a raw style tag such as `"wave_a=0.5_f=2"`, for parameters not
+    /// covered by the other methods. The tag is not escaped.
+    #[inline]
+    pub fn tag(&mut self, tag: &str) -> &mut Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+}
+
+/// Whether styled text changes over time on its own: waves, pulses, swings,
+/// jitter, gradients and `id`-tracked in/out animations.
+pub(crate) fn is_animated(raw: &str) -> bool {
+    if !raw.contains('{') {
+        return false;
+    }
+    let Ok(lines) = parse_text_lines(vec![raw.to_string()]) else {
+        return false;
+    };
+    lines.iter().flatten().flat_map(|segment| &segment.styles).any(|style| {
+        let cmd = style.split(['_', '=']).next().unwrap_or("");
+        matches!(cmd, "wave" | "pulse" | "swing" | "jitter" | "gradient")
+            || style.split('_').any(|arg| arg.starts_with("id="))
+    })
+}
+
 pub fn parse_text_lines(lines: Vec<String>) -> Result<Vec<Vec<StyledSegment>>, String> {
     let mut result_lines: Vec<Vec<StyledSegment>> = Vec::new();
     let mut style_stack: Vec<String> = Vec::new();
@@ -135,6 +410,60 @@ pub fn parse_text_lines(lines: Vec<String>) -> Result<Vec<Vec<StyledSegment>>, S
     Ok(result_lines)
 }
 
+/// Splits one line of markup into segments. `style_stack` holds the styles
+/// left open by earlier lines and is updated for the next one.
+pub(crate) fn parse_styled_line(line: &str, style_stack: &mut Vec<String>) -> Result<Vec<StyledSegment>, &'static str> {
+    let mut segments = Vec::new();
+    let mut in_style_def = false;
+    let mut escaped = false;
+    let mut text_buffer = String::new();
+    let mut style_buffer = String::new();
+
+    for c in line.chars() {
+        if escaped {
+            if in_style_def {
+                style_buffer.push(c);
+            } else {
+                text_buffer.push(c);
+            }
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '{' if in_style_def => style_buffer.push(c),
+            '{' => {
+                if !text_buffer.is_empty() {
+                    segments.push(StyledSegment { text: std::mem::take(&mut text_buffer), styles: style_stack.clone() });
+                }
+                in_style_def = true;
+            }
+            '|' if in_style_def => {
+                style_stack.push(std::mem::take(&mut style_buffer));
+                in_style_def = false;
+            }
+            '}' if in_style_def => style_buffer.push(c),
+            '}' => {
+                if !text_buffer.is_empty() {
+                    segments.push(StyledSegment { text: std::mem::take(&mut text_buffer), styles: style_stack.clone() });
+                }
+                if style_stack.pop().is_none() {
+                    return Err("Encountered } without opened style! Make sure to escape curly braces with \\.");
+                }
+            }
+            _ if in_style_def => style_buffer.push(c),
+            _ => text_buffer.push(c),
+        }
+    }
+    if in_style_def {
+        return Err("Style definition didn't end!");
+    }
+    if !text_buffer.is_empty() {
+        segments.push(StyledSegment { text: text_buffer, styles: style_stack.clone() });
+    }
+    Ok(segments)
+}
+
 /// Render styled text segments with a custom default color for unstyled text.
 pub fn render_styled_text<F1, F2>(
     segments: &[StyledSegment], 
@@ -192,7 +521,8 @@ pub fn render_styled_text<F1, F2>(
             } else {
                 (first_part, None)
             };
-            if cmd != "color" && cmd != "opacity" && !cmd.is_empty() {
+            let is_property = matches!(cmd, "color" | "opacity" | "link" | "bold" | "italic");
+            if !is_property && !cmd.is_empty() {
                 has_effects = true;
                 break;
             }
@@ -201,6 +531,7 @@ pub fn render_styled_text<F1, F2>(
         if !has_effects {
             let mut color = default_color;
             let mut opacity_mult = 1.0;
+            let mut tr = Transform::default();
             
             for style_str in &segment.styles {
                 let mut parts = style_str.split('_');
@@ -219,13 +550,19 @@ pub fn render_styled_text<F1, F2>(
 
                 if cmd == "color" {
                     color = parse_color(val);
+                } else if cmd == "link" {
+                    color = LINK_COLOR;
+                } else if cmd == "bold" {
+                    tr.weight = (font_size * BOLD_WEIGHT_RATIO).max(1.0);
+                } else if cmd == "italic" {
+                    tr.skew = ITALIC_SKEW;
                 } else if cmd == "opacity" {
                     opacity_mult *= parse_float(val);
                 }
             }
             
             color.a *= opacity_mult;
-            render_fn(&segment.text, Transform::default(), color);
+            render_fn(&segment.text, tr, color);
             *total_char_index += segment.text.chars().count();
             continue;
         }
@@ -448,6 +785,18 @@ pub fn render_styled_text<F1, F2>(
                     }
                 }
 
+                if cmd == "link" {
+                    color = LINK_COLOR;
+                }
+
+                if cmd == "bold" {
+                    tr.weight = (font_size * BOLD_WEIGHT_RATIO).max(1.0);
+                }
+
+                if cmd == "italic" {
+                    tr.skew = ITALIC_SKEW;
+                }
+
                 if cmd == "shadow" {
                     let color_str = args.get("color").unwrap_or(&"black");
                     let sc = parse_color(color_str);
@@ -509,6 +858,60 @@ mod tests {
         assert_eq!(rendered[0].1.scale_y, 1.0, "Default scale_y should be 1.0");
     }
 
+    #[test]
+    fn test_link_spans() {
+        let raw = "a {link=https://x.io/a_b|go {color=red|here}} \\{link=no|x\\}";
+        let spans = link_spans(raw);
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].url, "https://x.io/a_b");
+        assert_eq!(&raw[spans[0].start..spans[0].end], "go {color=red|here}");
+    }
+
+    #[test]
+    fn test_styled_text_builds_escaped_markup() {
+        let text = StyledText::new()
+            .push("a{b}", |s| s.bold().color(Color::rgba(255.0, 0.0, 0.0, 127.5)))
+            .plain(" | ")
+            .push("docs", |s| s.link("https://x.io/a b"));
+        assert_eq!(
+            text.as_str(),
+            "{bold|{color=#FF0000|{opacity=0.5|a\\{b\\}}}} \\| {link=https://x.io/a%20b|docs}"
+        );
+        assert_eq!(text.plain_text(), "a{b} | docs");
+        assert!(parse_text_lines(vec![text.to_string()]).is_ok());
+        assert_eq!(link_spans(&text)[0].url, "https://x.io/a%20b");
+    }
+
+    #[test]
+    fn test_render_bold_italic_transform() {
+        let segments = parse_text_lines(vec!["{bold|{italic|B}}".to_string()]).unwrap();
+        let mut tracker = FxHashMap::default();
+        let mut rendered = Vec::new();
+
+        render_styled_text(&segments[0], 0.0, 48.0, WHITE, &mut tracker, &mut 0,
+            |c, tr, col| rendered.push((c.to_string(), tr, col)),
+            |_, _, _| {});
+
+        assert_eq!(rendered.len(), 1, "bold and italic should stay on the fast path");
+        assert_eq!(rendered[0].1.weight, 2.0);
+        assert!(rendered[0].1.skew > 0.0);
+    }
+
+    #[test]
+    fn test_render_link_uses_link_color() {
+        let lines = vec!["{link=https://x.io|L}".to_string()];
+        let segments = parse_text_lines(lines).unwrap();
+        let mut tracker = FxHashMap::default();
+        let mut rendered = Vec::new();
+
+        render_styled_text(&segments[0], 0.0, 16.0, WHITE, &mut tracker, &mut 0,
+            |c, tr, col| rendered.push((c.to_string(), tr, col)),
+            |_, _, _| {});
+
+        assert_eq!(rendered.len(), 1);
+        assert_eq!(rendered[0].2.b, LINK_COLOR.b);
+    }
+
     #[test]
     fn test_render_color_named() {
         let lines = vec!["{color=red|R}".to_string()];
@@ -0,0 +1,224 @@
+//! Themes: named design tokens for colors, radii, spacing and font sizes.
+//!
+//! ```ignore
+//! let dark = Theme::new()
+//!     .color("surface", 0x202124)
+//!     .color("text", 0xE8EAED)
+//!     .number("radius", 8.0)
+//!     .number("space", 12.0);
+//! ply.set_theme(dark);
+//!
+//! ui.element()
+//!     .background_color(token!("surface"))
+//!     .corner_radius(token!("radius"))
+//!     .layout(|l| l.padding(token!("space")).gap(token!("space").into()))
+//!     .empty();
+//! ```
+//!
+//! Tokens are looked up when the element is declared, so swapping the theme
+//! with [`Ply::set_theme`](crate::Ply::set_theme) restyles every element on
+//! the next frame. A token missing from the theme resolves to transparent or
+//! `0`, with a warning the first time.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::color::Color;
+use crate::layout::{CornerRadius, Padding};
+
+/// A value stored under a token name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TokenValue {
+    Color(Color),
+    /// Radii, spacing, font sizes and other sizes, in logical pixels.
+    Number(f32),
+}
+
+/// A set of named design tokens.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Theme {
+    tokens: FxHashMap<String, TokenValue>,
+}
+
+impl Theme {
+    /// Creates an empty theme.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a color token.
+    pub fn color(mut self, name: &str, color: impl Into<Color>) -> Self {
+        self.set(name, TokenValue::Color(color.into()));
+        self
+    }
+
+    /// Sets a number token, for radii, spacing or font sizes.
+    pub fn number(mut self, name: &str, value: f32) -> Self {
+        self.set(name, TokenValue::Number(value));
+        self
+    }
+
+    /// Sets a token, replacing any value under the same name.
+    pub fn set(&mut self, name: &str, value: TokenValue) {
+        self.tokens.insert(name.to_owned(), value);
+    }
+
+    /// Returns the value of a token.
+    pub fn get(&self, name: &str) -> Option<TokenValue> {
+        self.tokens.get(name).copied()
+    }
+
+    /// Returns a color token. `None` if missing or not a color.
+    pub fn get_color(&self, name: &str) -> Option<Color> {
+        match self.get(name)? {
+            TokenValue::Color(color) => Some(color),
+            TokenValue::Number(_) => None,
+        }
+    }
+
+    /// Returns a number token. `None` if missing or not a number.
+    pub fn get_number(&self, name: &str) -> Option<f32> {
+        match self.get(name)? {
+            TokenValue::Number(value) => Some(value),
+            TokenValue::Color(_) => None,
+        }
+    }
+
+    /// Returns a copy of this theme with the tokens of `other` on top.
+    /// Handy for white-labeling: override a few tokens of a base theme.
+    pub fn merged(mut self, other: &Theme) -> Self {
+        for (name, value) in &other.tokens {
+            self.tokens.insert(name.clone(), *value);
+        }
+        self
+    }
+}
+
+thread_local! {
+    /// Theme of the [`Ply`](crate::Ply) currently declaring elements.
+    static ACTIVE_THEME: RefCell<Rc<Theme>> = RefCell::new(Rc::new(Theme::default()));
+    /// Token names already warned about, to warn once per name.
+    static WARNED_TOKENS: RefCell<FxHashSet<&'static str>> = RefCell::new(FxHashSet::default());
+}
+
+/// Makes `theme` the one tokens resolve against. Called from `begin()` and `set_theme()`.
+pub(crate) fn activate(theme: &Rc<Theme>) {
+    ACTIVE_THEME.with(|active| *active.borrow_mut() = theme.clone());
+}
+
+/// Runs `f` with the theme tokens currently resolve against.
+#[cfg(feature = "templates")]
+pub(crate) fn with_active<R>(f: impl FnOnce(&Theme) -> R) -> R {
+    ACTIVE_THEME.with(|active| f(&active.borrow()))
+}
+
+fn warn_once(name: &'static str, expected: &str) {
+    let first = WARNED_TOKENS.with(|warned| warned.borrow_mut().insert(name));
+    if first {
+        crate::telemetry::warning!("Theme has no {} token named \"{}\"", expected, name);
+    }
+}
+
+/// A reference to a theme token, created with [`token!`](crate::token).
+///
+/// Converts into [`Color`], [`CornerRadius`], [`Padding`], `f32` and `u16`,
+/// so it can be passed to builder methods taking any of those.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Token {
+    pub name: &'static str,
+}
+
+impl Token {
+    pub const fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    /// Resolves the token as a color against the active theme.
+    pub fn color(self) -> Color {
+        let color = ACTIVE_THEME.with(|active| active.borrow().get_color(self.name));
+        color.unwrap_or_else(|| {
+            warn_once(self.name, "color");
+            Color::default()
+        })
+    }
+
+    /// Resolves the token as a number against the active theme.
+    pub fn number(self) -> f32 {
+        let value = ACTIVE_THEME.with(|active| active.borrow().get_number(self.name));
+        value.unwrap_or_else(|| {
+            warn_once(self.name, "number");
+            0.0
+        })
+    }
+}
+
+impl From<Token> for Color {
+    fn from(token: Token) -> Self {
+        token.color()
+    }
+}
+
+impl From<Token> for f32 {
+    fn from(token: Token) -> Self {
+        token.number()
+    }
+}
+
+impl From<Token> for u16 {
+    /// Rounds the number, clamped to `u16`.
+    fn from(token: Token) -> Self {
+        token.number().round().clamp(0.0, u16::MAX as f32) as u16
+    }
+}
+
+impl From<Token> for CornerRadius {
+    fn from(token: Token) -> Self {
+        CornerRadius::from(token.number())
+    }
+}
+
+impl From<Token> for Padding {
+    fn from(token: Token) -> Self {
+        Padding::all(token.into())
+    }
+}
+
+/// References a theme token by name: `token!("surface")`.
+///
+/// Resolved when passed to a builder, against the theme set with
+/// [`Ply::set_theme`](crate::Ply::set_theme). Parameters of type `u16` or
+/// `f32` need `.into()`: `.gap(token!("space").into())`.
+#[macro_export]
+macro_rules! token {
+    ($name:expr) => {
+        $crate::theme::Token::new($name)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_resolve_against_active_theme() {
+        let light = Rc::new(Theme::new().color("surface", (255u8, 255u8, 255u8)).number("space", 11.6));
+        activate(&light);
+        assert_eq!(Color::from(token!("surface")), Color::u_rgb(255, 255, 255));
+        assert_eq!(f32::from(token!("space")), 11.6);
+        assert_eq!(u16::from(token!("space")), 12);
+        assert_eq!(Padding::from(token!("space")).left, 12);
+
+        let dark = Rc::new(light.as_ref().clone().merged(&Theme::new().color("surface", (0u8, 0u8, 0u8))));
+        activate(&dark);
+        assert_eq!(Color::from(token!("surface")), Color::u_rgb(0, 0, 0));
+        assert_eq!(f32::from(token!("space")), 11.6);
+
+        // Missing or mistyped tokens fall back
+        assert_eq!(Color::from(token!("missing")), Color::default());
+        assert_eq!(f32::from(token!("surface")), 0.0);
+    }
+}
@@ -0,0 +1,267 @@
+//! Toasts: short notifications stacked in a corner of the window.
+//!
+//! ```ignore
+//! if saved {
+//!     ply.toast("Saved", |t| t.level(Success));
+//! }
+//! ply.toast("Connection lost", |t| t.level(Error).duration(6.0));
+//! ```
+//!
+//! Ply draws the toasts above everything else when the frame is evaluated,
+//! so they can be queued from anywhere. Each one slides in, stays for its
+//! duration and fades out. Hovering a toast pauses its timer and clicking it
+//! dismisses it. Screen readers announce new toasts; errors interrupt.
+
+use crate::accessibility::AccessibilityBuilder;
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::easing::ease_out_cubic;
+use crate::elements::Layer;
+use crate::id::Id;
+use crate::layout::LayoutDirection;
+use crate::{Ply, Ui};
+
+/// Seconds a toast takes to slide in, and to fade out.
+const TRANSITION: f64 = 0.2;
+/// Toasts shown at once. Later ones wait until a slot frees up.
+const MAX_VISIBLE: usize = 5;
+/// Gap between toasts and between the stack and the window edge.
+const MARGIN: u16 = 16;
+/// How far toasts slide in from the right edge.
+const SLIDE: u16 = 32;
+
+/// How important a toast is. Sets its color and how it is announced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToastLevel {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    /// Announced right away by screen readers, interrupting other speech.
+    Error,
+}
+
+impl ToastLevel {
+    fn color(self) -> Color {
+        match self {
+            ToastLevel::Info => Color::rgba(58.0, 123.0, 213.0, 255.0),
+            ToastLevel::Success => Color::rgba(46.0, 160.0, 67.0, 255.0),
+            ToastLevel::Warning => Color::rgba(224.0, 150.0, 20.0, 255.0),
+            ToastLevel::Error => Color::rgba(210.0, 55.0, 55.0, 255.0),
+        }
+    }
+}
+
+/// Builder for configuring a toast via closure.
+#[derive(Debug, Clone)]
+pub struct ToastBuilder {
+    pub(crate) level: ToastLevel,
+    pub(crate) duration: f64,
+}
+
+impl Default for ToastBuilder {
+    fn default() -> Self {
+        Self {
+            level: ToastLevel::Info,
+            duration: 3.0,
+        }
+    }
+}
+
+impl ToastBuilder {
+    /// Sets the level. Defaults to [`ToastLevel::Info`].
+    #[inline]
+    pub fn level(&mut self, level: ToastLevel) -> &mut Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets how many seconds the toast stays, not counting time hovered. Defaults to 3.
+    #[inline]
+    pub fn duration(&mut self, seconds: f64) -> &mut Self {
+        self.duration = seconds.max(0.0);
+        self
+    }
+}
+
+struct Toast {
+    key: u32,
+    message: String,
+    level: ToastLevel,
+    duration: f64,
+    /// Seconds since it appeared, for the slide-in.
+    age: f64,
+    /// Seconds it has counted towards its duration.
+    shown: f64,
+    /// Seconds since it started fading out.
+    leaving: Option<f64>,
+}
+
+impl Toast {
+    /// How far in it is, from 0 (hidden) to 1 (fully shown).
+    fn presence(&self) -> f32 {
+        let entered = ease_out_cubic((self.age / TRANSITION).min(1.0) as f32);
+        let left = self.leaving.map_or(0.0, |leaving| (leaving / TRANSITION).min(1.0) as f32);
+        entered * (1.0 - left)
+    }
+}
+
+/// Queued toasts, kept by [`Ply`].
+#[derive(Default)]
+pub(crate) struct Toasts {
+    queue: Vec<Toast>,
+    next_key: u32,
+}
+
+fn toast_id(key: u32) -> Id {
+    Id::new_index("ply_toast", key)
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData> {
+    /// Queues a toast notification.
+    ///
+    /// ```ignore
+    /// ply.toast("Settings saved", |t| t.level(Success).duration(2.0));
+    /// ```
+    pub fn toast(
+        &mut self,
+        message: &str,
+        config_fn: impl for<'a> FnOnce(&'a mut ToastBuilder) -> &'a mut ToastBuilder,
+    ) {
+        let mut config = ToastBuilder::default();
+        config_fn(&mut config);
+        let toasts = &mut self.toasts;
+        toasts.queue.push(Toast {
+            key: toasts.next_key,
+            message: message.to_string(),
+            level: config.level,
+            duration: config.duration,
+            age: 0.0,
+            shown: 0.0,
+            leaving: None,
+        });
+        toasts.next_key = toasts.next_key.wrapping_add(1);
+    }
+
+    /// Removes all toasts right away, including queued ones.
+    pub fn clear_toasts(&mut self) {
+        self.toasts.queue.clear();
+    }
+
+    /// Advances the toast timers and declares the visible toasts. Called from `eval()`.
+    pub(crate) fn draw_toasts(&mut self) {
+        let delta = self.context.frame_delta_time as f64;
+        let visible = self.toasts.queue.len().min(MAX_VISIBLE);
+        for toast in &mut self.toasts.queue[..visible] {
+            toast.age += delta;
+            match toast.leaving.as_mut() {
+                Some(leaving) => *leaving += delta,
+                None => {
+                    // Hovering pauses the timer so the toast can be read
+                    if !self.context.pointer_over(toast_id(toast.key)) {
+                        toast.shown += delta;
+                    }
+                    if toast.shown >= toast.duration {
+                        toast.leaving = Some(0.0);
+                    }
+                }
+            }
+        }
+        self.toasts.queue.retain(|toast| toast.leaving.is_none_or(|leaving| leaving < TRANSITION));
+        if self.toasts.queue.is_empty() {
+            return;
+        }
+
+        let mut dismissed = Vec::new();
+        let toasts = std::mem::take(&mut self.toasts.queue);
+        let mut ui = Ui { ply: &mut *self };
+        ui.element()
+            .id("ply_toasts")
+            .floating(|f| f
+                .attach_root()
+                .anchor((AlignX::Right, AlignY::Bottom), (AlignX::Right, AlignY::Bottom))
+                .offset(((SLIDE - MARGIN) as f32, -(MARGIN as f32)))
+                .layer(Layer::Overlay)
+                .passthrough()
+            )
+            .layout(|l| l
+                .direction(LayoutDirection::TopToBottom)
+                .gap(MARGIN / 2)
+                .align(AlignX::Right, AlignY::Bottom)
+            )
+            .children(|ui| {
+                for toast in toasts.iter().take(MAX_VISIBLE) {
+                    let presence = toast.presence();
+                    let mut background = toast.level.color();
+                    background.a *= presence;
+                    let mut text_color = Color::rgba(255.0, 255.0, 255.0, 255.0);
+                    text_color.a *= presence;
+                    // The stack sits `SLIDE` past the edge; this padding pulls the toast in
+                    let slide = (SLIDE as f32 * presence).round() as u16;
+                    ui.element().layout(|l| l.padding((0, slide, 0, 0))).children(|ui| {
+                        ui.element()
+                            .id(toast_id(toast.key))
+                            .background_color(background)
+                            .corner_radius(6.0)
+                            .accessibility(|a| announce(a, toast))
+                            .layout(|l| l.padding((10, 16, 10, 16)))
+                            .children(|ui| {
+                                if ui.just_pressed() {
+                                    dismissed.push(toast.key);
+                                }
+                                ui.text(&toast.message, |t| t.font_size(16).color(text_color));
+                            });
+                    });
+                }
+            });
+
+        self.toasts.queue = toasts;
+        for toast in &mut self.toasts.queue {
+            if dismissed.contains(&toast.key) && toast.leaving.is_none() {
+                toast.leaving = Some(0.0);
+            }
+        }
+    }
+}
+
+fn announce<'a>(a: &'a mut AccessibilityBuilder, toast: &Toast) -> &'a mut AccessibilityBuilder {
+    a.static_text(&toast.message);
+    match toast.level {
+        ToastLevel::Error => a.live_region_assertive(),
+        _ => a.live_region_polite(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{Dimensions, Vector2};
+    use crate::render_commands::RenderCommandConfig;
+
+    fn frame(ply: &mut Ply<()>, delta: f32) -> usize {
+        ply.context.frame_delta_time = delta;
+        let mut ui = ply.begin();
+        ui.eval()
+            .iter()
+            .filter(|command| matches!(&command.config, RenderCommandConfig::Text(text) if text.text == "Saved"))
+            .count()
+    }
+
+    #[test]
+    fn test_toast_pauses_while_hovered_and_leaves_after_duration() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 300.0));
+        ply.toast("Saved", |t| t.level(ToastLevel::Success).duration(1.0));
+        assert_eq!(frame(&mut ply, 0.5), 1);
+
+        // Hovered: the timer stands still
+        let bounds = ply.context.get_element_data(toast_id(0)).unwrap();
+        ply.context.set_pointer_state(Vector2::new(bounds.x + 1.0, bounds.y + 1.0), false);
+        assert_eq!(frame(&mut ply, 2.0), 1);
+        assert_eq!(frame(&mut ply, 2.0), 1);
+
+        // Left alone, it fades out and is gone
+        ply.context.set_pointer_state(Vector2::new(0.0, 0.0), false);
+        assert_eq!(frame(&mut ply, 2.0), 1);
+        assert_eq!(frame(&mut ply, 1.0), 0);
+    }
+}
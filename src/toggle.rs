@@ -0,0 +1,381 @@
+//! Checkboxes and radio groups.
+//!
+//! ```ignore
+//! if ui.checkbox("vsync", &mut settings.vsync, "V-Sync", |c| c) {
+//!     apply_settings(&settings);
+//! }
+//!
+//! ui.radio_group("quality", &mut settings.quality, &["Low", "Medium", "High"], |r| r
+//!     .label("Texture quality")
+//!     .style_states(|s| s.hover(|h| h.background_color(0x2A2A2A)))
+//! );
+//! ```
+//!
+//! Both toggle on click and on Enter or Space while focused. In a radio
+//! group only the selected option is in the Tab order; the arrow keys move
+//! to the neighbouring option and select it, like native radio buttons.
+
+use crate::accessibility::AccessibilityRole;
+use crate::align::{AlignX, AlignY};
+use crate::color::Color;
+use crate::id::Id;
+use crate::layout::{LayoutDirection, Sizing};
+use crate::renderer::FontAsset;
+use crate::style::StyleStates;
+use crate::Ui;
+
+/// Builder for styling a checkbox or radio group via closure.
+#[derive(Debug, Clone)]
+pub struct ToggleBuilder {
+    pub(crate) font_asset: Option<&'static FontAsset>,
+    pub(crate) font_size: u16,
+    pub(crate) gap: u16,
+    pub(crate) label_color: Color,
+    pub(crate) box_color: Color,
+    pub(crate) border_color: Color,
+    pub(crate) checked_color: Color,
+    pub(crate) mark_color: Color,
+    pub(crate) disabled: bool,
+    pub(crate) states: StyleStates,
+    pub(crate) group_label: String,
+    pub(crate) direction: LayoutDirection,
+}
+
+impl Default for ToggleBuilder {
+    fn default() -> Self {
+        Self {
+            font_asset: None,
+            font_size: 16,
+            gap: 8,
+            label_color: Color::rgba(255.0, 255.0, 255.0, 255.0),
+            box_color: Color::rgba(40.0, 40.0, 40.0, 255.0),
+            border_color: Color::rgba(128.0, 128.0, 128.0, 255.0),
+            checked_color: Color::rgba(58.0, 123.0, 213.0, 255.0),
+            mark_color: Color::rgba(255.0, 255.0, 255.0, 255.0),
+            disabled: false,
+            states: StyleStates::default(),
+            group_label: String::new(),
+            direction: LayoutDirection::TopToBottom,
+        }
+    }
+}
+
+impl ToggleBuilder {
+    /// Sets the font asset of the labels.
+    #[inline]
+    pub fn font(&mut self, asset: &'static FontAsset) -> &mut Self {
+        self.font_asset = Some(asset);
+        self
+    }
+
+    /// Sets the font size of the labels. The box scales with it. Defaults to 16.
+    #[inline]
+    pub fn font_size(&mut self, size: u16) -> &mut Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Sets the gap between the box and its label, and between radio options. Defaults to 8.
+    #[inline]
+    pub fn gap(&mut self, gap: u16) -> &mut Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Sets the color of the labels.
+    #[inline]
+    pub fn label_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.label_color = color.into();
+        self
+    }
+
+    /// Sets the background color of the box while unchecked.
+    #[inline]
+    pub fn box_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.box_color = color.into();
+        self
+    }
+
+    /// Sets the border color of the box while unchecked.
+    #[inline]
+    pub fn border_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.border_color = color.into();
+        self
+    }
+
+    /// Sets the background and border color of the box while checked.
+    #[inline]
+    pub fn checked_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.checked_color = color.into();
+        self
+    }
+
+    /// Sets the color of the check mark or radio dot.
+    #[inline]
+    pub fn mark_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.mark_color = color.into();
+        self
+    }
+
+    /// Disables toggling and removes the control from the Tab order.
+    /// Pair with `style_states(|s| s.disabled(true, ...))` to dim it.
+    #[inline]
+    pub fn disabled(&mut self, disabled: bool) -> &mut Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Sets style overrides for each option row while hovered, focused,
+    /// pressed or disabled. See [`ElementBuilder::style_states`](crate::ElementBuilder::style_states).
+    #[inline]
+    pub fn style_states(&mut self, f: impl for<'a> FnOnce(&'a mut StyleStates) -> &'a mut StyleStates) -> &mut Self {
+        f(&mut self.states);
+        self
+    }
+
+    /// Sets the accessible name of a radio group.
+    #[inline]
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        self.group_label = label.to_string();
+        self
+    }
+
+    /// Sets whether radio options are stacked or in a row. Defaults to stacked.
+    #[inline]
+    pub fn direction(&mut self, direction: LayoutDirection) -> &mut Self {
+        self.direction = direction;
+        self
+    }
+}
+
+/// Role-specific parts of an option row.
+struct ToggleKind<'a> {
+    role: AccessibilityRole,
+    /// Corner radius of the box, as a fraction of its size.
+    roundness: f32,
+    /// Size of the mark, as a fraction of the box size.
+    mark_size: f32,
+    position_in_set: Option<(u32, u32)>,
+    /// Previous and next options, for arrow key navigation.
+    neighbors: Option<(Id, Id)>,
+    tab_stop: bool,
+    label: &'a str,
+}
+
+impl<'ply, CustomElementData: Clone + Default + std::fmt::Debug> Ui<'ply, CustomElementData> {
+    /// Adds a checkbox with a label. Toggles `checked` on click, Enter or Space.
+    ///
+    /// Returns `true` on the frame `checked` changed.
+    ///
+    /// ```ignore
+    /// ui.checkbox("agree", &mut agreed, "I agree to the terms", |c| c.font_size(14));
+    /// ```
+    pub fn checkbox(
+        &mut self,
+        id: impl Into<Id>,
+        checked: &mut bool,
+        label: &str,
+        config_fn: impl for<'a> FnOnce(&'a mut ToggleBuilder) -> &'a mut ToggleBuilder,
+    ) -> bool {
+        let mut config = ToggleBuilder::default();
+        config_fn(&mut config);
+        let kind = ToggleKind {
+            role: AccessibilityRole::Checkbox,
+            roundness: 0.2,
+            mark_size: 0.5,
+            position_in_set: None,
+            neighbors: None,
+            tab_stop: true,
+            label,
+        };
+        let (pressed, _) = self.toggle_row(id.into(), *checked, &config, kind);
+        if pressed {
+            *checked = !*checked;
+        }
+        pressed
+    }
+
+    /// Adds a group of radio buttons, one per option. `selected` is the index
+    /// of the selected option.
+    ///
+    /// Returns `true` on the frame `selected` changed.
+    ///
+    /// ```ignore
+    /// ui.radio_group("difficulty", &mut difficulty, &["Easy", "Normal", "Hard"], |r| r
+    ///     .label("Difficulty")
+    ///     .direction(LayoutDirection::LeftToRight)
+    /// );
+    /// ```
+    pub fn radio_group(
+        &mut self,
+        id: impl Into<Id>,
+        selected: &mut usize,
+        options: &[&str],
+        config_fn: impl for<'a> FnOnce(&'a mut ToggleBuilder) -> &'a mut ToggleBuilder,
+    ) -> bool {
+        let mut config = ToggleBuilder::default();
+        config_fn(&mut config);
+        let id = id.into();
+        let count = options.len();
+        let option_id = |index: usize| id.child_index("option", index as u32);
+        let tab_stop = if *selected < count { *selected } else { 0 };
+
+        let mut new_selection = None;
+        self.element()
+            .id(id.clone())
+            .accessibility(|a| a.role(AccessibilityRole::RadioGroup).label(&config.group_label))
+            .layout(|l| l.direction(config.direction).gap(config.gap))
+            .children(|ui| {
+                for (index, option) in options.iter().enumerate() {
+                    let kind = ToggleKind {
+                        role: AccessibilityRole::RadioButton,
+                        roundness: 0.5,
+                        mark_size: 0.4,
+                        position_in_set: Some((index as u32 + 1, count as u32)),
+                        neighbors: Some((
+                            option_id((index + count - 1) % count),
+                            option_id((index + 1) % count),
+                        )),
+                        tab_stop: index == tab_stop,
+                        label: option,
+                    };
+                    let (pressed, focused) = ui.toggle_row(option_id(index), *selected == index, &config, kind);
+                    // Selection follows focus, which only arrow keys move to an unselected option
+                    if (pressed || focused) && *selected != index {
+                        new_selection = Some(index);
+                    }
+                }
+            });
+
+        match new_selection {
+            Some(index) => {
+                *selected = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Draws one box-and-label row. Returns whether it was pressed this
+    /// frame and whether it has focus.
+    fn toggle_row(&mut self, id: Id, checked: bool, config: &ToggleBuilder, kind: ToggleKind<'_>) -> (bool, bool) {
+        let box_size = (config.font_size as f32 * 1.125).round();
+        let (box_color, border_color) = if checked {
+            (config.checked_color, config.checked_color)
+        } else {
+            (config.box_color, config.border_color)
+        };
+        let focusable = kind.tab_stop && !config.disabled;
+
+        let mut row = self
+            .element()
+            .id(id)
+            .accessibility(|a| {
+                a.role(kind.role.clone()).label(kind.label).checked(checked);
+                if focusable {
+                    a.focusable();
+                }
+                if let Some((position, size)) = kind.position_in_set {
+                    a.position_in_set(position, size);
+                }
+                if let Some((previous, next)) = kind.neighbors.clone() {
+                    a.focus_up(previous.clone()).focus_left(previous).focus_down(next.clone()).focus_right(next);
+                }
+                a
+            })
+            .layout(|l| l.gap(config.gap).align(AlignX::Left, AlignY::CenterY));
+        let mut states = config.states.clone();
+        if config.disabled {
+            states.disabled(true, |d| d);
+        }
+        row.style_states = Some(states);
+
+        let mut result = (false, false);
+        row.children(|ui| {
+            result = (ui.just_pressed() && !config.disabled, ui.focused() && !config.disabled);
+
+            ui.element()
+                .width(Sizing::Fixed(box_size))
+                .height(Sizing::Fixed(box_size))
+                .background_color(box_color)
+                .corner_radius(box_size * kind.roundness)
+                .border(|b| b.color(border_color).all(2))
+                .layout(|l| l.align(AlignX::CenterX, AlignY::CenterY))
+                .children(|ui| {
+                    if checked {
+                        let mark_size = (box_size * kind.mark_size).round();
+                        ui.element()
+                            .width(Sizing::Fixed(mark_size))
+                            .height(Sizing::Fixed(mark_size))
+                            .background_color(config.mark_color)
+                            .corner_radius(mark_size * kind.roundness)
+                            .empty();
+                    }
+                });
+
+            ui.text(kind.label, |t| {
+                if let Some(font) = config.font_asset {
+                    t.font(font);
+                }
+                t.font_size(config.font_size).color(config.label_color)
+            });
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{Dimensions, Vector2};
+    use crate::Ply;
+
+    #[test]
+    fn test_checkbox_toggles_on_click() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 200.0));
+        let mut checked = false;
+        let mut frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            let changed = ui.checkbox("agree", &mut checked, "Agree", |c| c);
+            ui.eval();
+            changed
+        };
+
+        assert!(!frame(&mut ply));
+        ply.context.set_pointer_state(Vector2::new(5.0, 5.0), true);
+        assert!(frame(&mut ply));
+        ply.context.set_pointer_state(Vector2::new(5.0, 5.0), false);
+        assert!(!frame(&mut ply));
+        assert!(checked);
+    }
+
+    #[test]
+    fn test_radio_group_selects_on_click_and_follows_focus() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(400.0, 400.0));
+        let mut selected = 0;
+        let options = ["Low", "Medium", "High"];
+        let mut frame = |ply: &mut Ply<()>| {
+            let mut ui = ply.begin();
+            ui.radio_group("quality", &mut selected, &options, |r| r.label("Quality"));
+            ui.eval();
+            selected
+        };
+        let option = |index: u32| Id::new("quality").child_index("option", index);
+
+        frame(&mut ply);
+        // Only the selected option is a Tab stop
+        ply.context.cycle_focus(false);
+        ply.context.cycle_focus(false);
+        assert_eq!(ply.focused_element().map(|id| id.id), Some(option(0).id));
+
+        // Arrow keys move focus to a neighbour, which selects it
+        ply.context.arrow_focus(crate::engine::ArrowDirection::Up);
+        assert_eq!(frame(&mut ply), 2);
+
+        // Clicking selects too
+        let bounds = ply.context.get_element_data(option(1)).unwrap();
+        let center = Vector2::new(bounds.x + 4.0, bounds.y + bounds.height / 2.0);
+        ply.context.set_pointer_state(center, true);
+        assert_eq!(frame(&mut ply), 1);
+    }
+}
@@ -0,0 +1,190 @@
+//! Transitions between two states of a subtree, e.g. screen changes.
+//!
+//! ```ignore
+//! ui.element()
+//!     .id("screen")
+//!     .width(grow!()).height(grow!())
+//!     .transition(current_screen, |t| t.duration(0.4))
+//!     .children(|ui| draw_screen(ui, current_screen));
+//! ```
+//!
+//! When the key changes, the subtree's last frame before the change is kept
+//! and blended into the live subtree by a transition shader over the
+//! duration. The shader sees the new frame as `Texture`, the kept one as
+//! `ply_previous` and the eased progress from `0.0` to `1.0` as `ply_progress`.
+
+use std::hash::{Hash, Hasher};
+
+use rustc_hash::FxHashMap;
+
+use crate::easing;
+use crate::shaders::{ShaderAsset, ShaderBuilder, ShaderConfig, ShaderUniform, ShaderUniformValue};
+
+/// Fades the previous frame out and the new one in. The default pattern.
+pub const CROSSFADE: ShaderAsset = ShaderAsset::Source {
+    file_name: "ply_transition_crossfade",
+    fragment: "#version 100
+precision mediump float;
+varying vec2 uv;
+varying vec4 color;
+uniform sampler2D Texture;
+uniform sampler2D ply_previous;
+uniform float ply_progress;
+
+void main() {
+    vec4 next = texture2D(Texture, uv);
+    vec4 previous = texture2D(ply_previous, uv);
+    gl_FragColor = mix(previous, next, ply_progress) * color;
+}
+",
+};
+
+/// Marks a group shader as a transition, for the renderer to bind the kept
+/// frame as `ply_previous`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransitionCapture {
+    /// The element whose frames are kept.
+    pub id: u32,
+    /// Bumped each time the key changes. The renderer keeps the last frame it
+    /// drew when this changes.
+    pub generation: u32,
+}
+
+/// Builder for a transition, used in the closure passed to `.transition()`.
+pub struct TransitionBuilder {
+    duration: f32,
+    easing: fn(f32) -> f32,
+    shader: Option<ShaderConfig>,
+}
+
+impl TransitionBuilder {
+    pub(crate) fn new() -> Self {
+        Self { duration: 0.3, easing: easing::ease_in_out_quad, shader: None }
+    }
+
+    /// Seconds from the key change to the new state. Defaults to `0.3`.
+    pub fn duration(&mut self, seconds: f32) -> &mut Self {
+        self.duration = seconds;
+        self
+    }
+
+    /// Shapes the progress, e.g. [`easing::ease_out_cubic`]. Defaults to
+    /// [`easing::ease_in_out_quad`].
+    pub fn easing(&mut self, easing: fn(f32) -> f32) -> &mut Self {
+        self.easing = easing;
+        self
+    }
+
+    /// The transition pattern. Defaults to [`CROSSFADE`].
+    ///
+    /// The shader samples `Texture` (new) and `ply_previous` (old) and is
+    /// given `ply_progress`, plus its own uniforms from `f`.
+    pub fn shader(&mut self, asset: &ShaderAsset, f: impl FnOnce(&mut ShaderBuilder<'_>)) -> &mut Self {
+        let mut builder = ShaderBuilder::new(asset);
+        f(&mut builder);
+        self.shader = Some(builder.into_config());
+        self
+    }
+}
+
+struct TransitionState {
+    key: u64,
+    generation: u32,
+    started: f64,
+    drawn: bool,
+}
+
+/// Key and start time of each element's transition.
+#[derive(Default)]
+pub(crate) struct Transitions {
+    states: FxHashMap<u32, TransitionState>,
+}
+
+impl Transitions {
+    /// Drops the transitions of elements not drawn last frame. Called from `begin()`.
+    pub(crate) fn sweep(&mut self) {
+        self.states.retain(|_, state| std::mem::take(&mut state.drawn));
+    }
+
+    /// Builds the group shader for element `id`'s transition at `now`,
+    /// restarting it when `key` changed.
+    pub(crate) fn shader(&mut self, id: u32, key: u64, now: f64, builder: TransitionBuilder) -> ShaderConfig {
+        let state = self.states.entry(id).or_insert(TransitionState {
+            key,
+            generation: 0,
+            // Nothing to transition from on first appearance
+            started: f64::NEG_INFINITY,
+            drawn: true,
+        });
+        state.drawn = true;
+        if state.key != key {
+            state.key = key;
+            state.generation = state.generation.wrapping_add(1);
+            state.started = now;
+        }
+
+        let linear = if builder.duration > 0.0 {
+            ((now - state.started) / builder.duration as f64).clamp(0.0, 1.0) as f32
+        } else {
+            1.0
+        };
+        let mut config = builder.shader.unwrap_or_else(|| ShaderBuilder::new(&CROSSFADE).into_config());
+        config.uniforms.push(ShaderUniform {
+            name: "ply_progress".to_string(),
+            value: ShaderUniformValue::Float((builder.easing)(linear)),
+        });
+        config.transition = Some(TransitionCapture { id, generation: state.generation });
+        config
+    }
+}
+
+/// Hashes a transition key, so any `Hash` value can be one.
+pub(crate) fn hash_key(key: impl Hash) -> u64 {
+    let mut hasher = rustc_hash::FxHasher::default();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::Dimensions;
+    use crate::render_commands::RenderCommandConfig;
+    use crate::{fixed, Ply};
+
+    #[test]
+    fn test_transition_restarts_when_key_changes() {
+        let mut ply = Ply::<()>::new_headless(Dimensions::new(200.0, 200.0));
+        let frame = |ply: &mut Ply<()>, time: f64, screen: u32| {
+            let mut ui = ply.begin();
+            ui.ply.context.current_time = time;
+            ui.element()
+                .id("screen")
+                .width(fixed!(100.0))
+                .height(fixed!(100.0))
+                .transition(screen, |t| t.duration(1.0).easing(|t| t))
+                .children(|ui| {
+                    ui.element().width(fixed!(10.0)).height(fixed!(10.0)).background_color(0x336699).empty();
+                });
+            let commands = ui.eval();
+            let Some(RenderCommandConfig::GroupBegin { shader: Some(shader), .. }) = commands.first().map(|c| &c.config) else {
+                panic!("expected a transition group, got {commands:?}");
+            };
+            let progress = shader.uniforms.iter().find(|u| u.name == "ply_progress").map(|u| u.value.clone());
+            (shader.transition.unwrap(), progress)
+        };
+
+        let (first, progress) = frame(&mut ply, 0.0, 1);
+        assert_eq!(first.generation, 0);
+        assert!(matches!(progress, Some(crate::shaders::ShaderUniformValue::Float(p)) if p == 1.0));
+
+        let (switched, progress) = frame(&mut ply, 10.0, 2);
+        assert_eq!(switched.id, first.id);
+        assert_eq!(switched.generation, 1);
+        assert!(matches!(progress, Some(crate::shaders::ShaderUniformValue::Float(p)) if p == 0.0));
+
+        let (same, progress) = frame(&mut ply, 10.25, 2);
+        assert_eq!(same.generation, 1);
+        assert!(matches!(progress, Some(crate::shaders::ShaderUniformValue::Float(p)) if p == 0.25));
+    }
+}
@@ -0,0 +1,71 @@
+//! Polling of asset files for hot reloading.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Seconds between checks of a watched file for changes.
+pub(crate) const RELOAD_INTERVAL: f64 = 1.0;
+
+type LoadResult = Result<Vec<u8>, String>;
+
+/// A file that is re-read every [`RELOAD_INTERVAL`] seconds, through the
+/// file system natively and by fetching it on the web.
+pub(crate) struct WatchedFile {
+    path: String,
+    /// Time of the last load request, in seconds.
+    last_load: Option<f64>,
+    /// Filled by the load callback, which may run later on the web.
+    pending: Rc<RefCell<Option<LoadResult>>>,
+    loading: bool,
+    /// Contents last returned by `poll`.
+    contents: Option<Vec<u8>>,
+}
+
+impl WatchedFile {
+    pub(crate) fn new(path: &str) -> Self {
+        Self {
+            path: path.to_owned(),
+            last_load: None,
+            pending: Rc::default(),
+            loading: false,
+            contents: None,
+        }
+    }
+
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Requests the file when due. Returns its contents when they differ
+    /// from the last call's. Load errors are logged and return `None`.
+    pub(crate) fn poll(&mut self, now: f64) -> Option<&[u8]> {
+        let due = self
+            .last_load
+            .is_none_or(|last| now - last >= RELOAD_INTERVAL || now < last);
+        if !self.loading && due {
+            self.loading = true;
+            self.last_load = Some(now);
+            let pending = self.pending.clone();
+            macroquad::miniquad::fs::load_file(
+                crate::renderer::resolve_asset_path(&self.path),
+                move |response| {
+                    *pending.borrow_mut() = Some(response.map_err(|e| format!("{e:?}")));
+                },
+            );
+        }
+
+        let response = self.pending.borrow_mut().take()?;
+        self.loading = false;
+        match response {
+            Ok(contents) if self.contents.as_ref() != Some(&contents) => {
+                self.contents = Some(contents);
+                self.contents.as_deref()
+            }
+            Ok(_) => None,
+            Err(e) => {
+                crate::telemetry::warning!("Failed to load {}: {}", self.path, e);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Window management: title, size limits, fullscreen and resize events.
+//!
+//! ```ignore
+//! ply.window()
+//!     .set_title("Editor")
+//!     .set_min_size(640.0, 480.0)
+//!     .on_resize(|size| println!("{} x {}", size.width, size.height));
+//! ```
+//!
+//! The layout follows the window size automatically, so there is no need to
+//! call [`Ply::set_layout_dimensions`] from `on_resize`.
+//!
+//! Titles are applied on Windows, macOS and the web. Always-on-top is applied
+//! on Windows and macOS. Elsewhere these are stored but have no effect.
+//!
+//! These settings apply to the one window macroquad opens. Apps that need
+//! secondary windows, such as tool palettes or inspectors, create them with
+//! winit and drive one `Ply` per window through `winit::WinitWindows`
+//! (feature `winit`).
+
+use crate::math::Dimensions;
+use crate::Ply;
+
+/// Window settings and resize tracking kept by [`Ply`].
+#[derive(Default)]
+pub(crate) struct WindowState {
+    title: String,
+    min_size: Option<Dimensions>,
+    max_size: Option<Dimensions>,
+    fullscreen: bool,
+    always_on_top: bool,
+    /// Size seen on the last frame, to detect resizes.
+    last_size: Option<Dimensions>,
+    on_resize: Option<Box<dyn FnMut(Dimensions)>>,
+}
+
+impl WindowState {
+    /// Clamp a size to the configured min/max size.
+    fn clamp(&self, size: Dimensions) -> Dimensions {
+        let mut size = size;
+        if let Some(max) = self.max_size {
+            size.width = size.width.min(max.width);
+            size.height = size.height.min(max.height);
+        }
+        if let Some(min) = self.min_size {
+            size.width = size.width.max(min.width);
+            size.height = size.height.max(min.height);
+        }
+        size
+    }
+}
+
+/// A handle to the window, returned by [`Ply::window`].
+pub struct Window<'ply, CustomElementData: Clone + Default + std::fmt::Debug = ()> {
+    pub(crate) ply: &'ply mut Ply<CustomElementData>,
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> Window<'_, CustomElementData> {
+    /// Returns the window title set with [`set_title`](Self::set_title).
+    pub fn title(&self) -> &str {
+        &self.ply.window_state.title
+    }
+
+    /// Sets the window title.
+    pub fn set_title(&mut self, title: &str) -> &mut Self {
+        self.ply.window_state.title = title.to_owned();
+        if !self.ply.headless {
+            platform::set_title(title);
+        }
+        self
+    }
+
+    /// Returns the window size, which is also the layout size.
+    pub fn size(&self) -> Dimensions {
+        self.ply.context.layout_dimensions
+    }
+
+    /// Resizes the window, within the min/max size. The layout uses the new
+    /// size right away.
+    pub fn set_size(&mut self, width: f32, height: f32) -> &mut Self {
+        let size = self.ply.window_state.clamp(Dimensions::new(width, height));
+        if !self.ply.headless {
+            macroquad::miniquad::window::set_window_size(size.width as u32, size.height as u32);
+        }
+        self.ply.context.set_layout_dimensions(size);
+        self
+    }
+
+    /// Sets the smallest size the window may be resized to.
+    pub fn set_min_size(&mut self, width: f32, height: f32) -> &mut Self {
+        self.ply.window_state.min_size = Some(Dimensions::new(width, height));
+        self
+    }
+
+    /// Sets the largest size the window may be resized to.
+    pub fn set_max_size(&mut self, width: f32, height: f32) -> &mut Self {
+        self.ply.window_state.max_size = Some(Dimensions::new(width, height));
+        self
+    }
+
+    /// Returns if the window was made fullscreen through this handle.
+    pub fn is_fullscreen(&self) -> bool {
+        self.ply.window_state.fullscreen
+    }
+
+    /// Enters or leaves fullscreen.
+    pub fn set_fullscreen(&mut self, fullscreen: bool) -> &mut Self {
+        self.ply.window_state.fullscreen = fullscreen;
+        if !self.ply.headless {
+            macroquad::miniquad::window::set_fullscreen(fullscreen);
+        }
+        self
+    }
+
+    /// Toggles fullscreen.
+    pub fn toggle_fullscreen(&mut self) -> &mut Self {
+        let fullscreen = !self.ply.window_state.fullscreen;
+        self.set_fullscreen(fullscreen)
+    }
+
+    /// Returns if the window is kept above other windows.
+    pub fn is_always_on_top(&self) -> bool {
+        self.ply.window_state.always_on_top
+    }
+
+    /// Keeps the window above other windows.
+    pub fn set_always_on_top(&mut self, always_on_top: bool) -> &mut Self {
+        self.ply.window_state.always_on_top = always_on_top;
+        if !self.ply.headless {
+            platform::set_always_on_top(always_on_top);
+        }
+        self
+    }
+
+    /// Registers a callback fired from [`Ply::begin`] when the window size
+    /// changes. Receives the new size, which the layout already uses.
+    pub fn on_resize(&mut self, callback: impl FnMut(Dimensions) + 'static) -> &mut Self {
+        self.ply.window_state.on_resize = Some(Box::new(callback));
+        self
+    }
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> Ply<CustomElementData> {
+    /// Returns a handle for managing the window.
+    pub fn window(&mut self) -> Window<'_, CustomElementData> {
+        Window { ply: self }
+    }
+
+    /// Applies the min/max size and fires `on_resize` when the size changed.
+    /// Called from `begin()` after the layout size is updated.
+    pub(crate) fn update_window(&mut self) {
+        let size = self.context.layout_dimensions;
+        let clamped = self.window_state.clamp(size);
+        if clamped != size {
+            if !self.headless {
+                macroquad::miniquad::window::set_window_size(
+                    clamped.width as u32,
+                    clamped.height as u32,
+                );
+            }
+            self.context.set_layout_dimensions(clamped);
+        }
+
+        let state = &mut self.window_state;
+        let resized = state.last_size.is_some_and(|last| last != clamped);
+        state.last_size = Some(clamped);
+        if resized {
+            if let Some(ref mut callback) = state.on_resize {
+                callback(clamped);
+            }
+        }
+    }
+}
+
+mod platform {
+    #[cfg(target_os = "windows")]
+    #[link(name = "user32")]
+    extern "system" {
+        fn SetWindowTextW(hwnd: isize, text: *const u16) -> i32;
+        fn SetWindowPos(
+            hwnd: isize,
+            hwnd_insert_after: isize,
+            x: i32,
+            y: i32,
+            cx: i32,
+            cy: i32,
+            flags: u32,
+        ) -> i32;
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    extern "C" {
+        fn ply_set_window_title(title: *const u8, len: usize);
+    }
+
+    pub(super) fn set_title(title: &str) {
+        #[cfg(target_os = "windows")]
+        {
+            let hwnd = macroquad::miniquad::window::windows_hwnd() as isize;
+            let wide: Vec<u16> = title.encode_utf16().chain(std::iter::once(0)).collect();
+            unsafe {
+                SetWindowTextW(hwnd, wide.as_ptr());
+            }
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            use macroquad::miniquad::native::apple::apple_util::str_to_nsstring;
+            use macroquad::miniquad::native::apple::frameworks::{msg_send, ObjcId};
+
+            let view = macroquad::miniquad::window::apple_view();
+            if view.is_null() {
+                return;
+            }
+            unsafe {
+                let window: ObjcId = msg_send![view, window];
+                if !window.is_null() {
+                    let _: () = msg_send![window, setTitle: str_to_nsstring(title)];
+                }
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        unsafe {
+            ply_set_window_title(title.as_ptr(), title.len());
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos", target_arch = "wasm32")))]
+        let _ = title;
+    }
+
+    pub(super) fn set_always_on_top(always_on_top: bool) {
+        #[cfg(target_os = "windows")]
+        {
+            const HWND_TOPMOST: isize = -1;
+            const HWND_NOTOPMOST: isize = -2;
+            const SWP_NOSIZE: u32 = 0x0001;
+            const SWP_NOMOVE: u32 = 0x0002;
+            const SWP_NOACTIVATE: u32 = 0x0010;
+
+            let hwnd = macroquad::miniquad::window::windows_hwnd() as isize;
+            let insert_after = if always_on_top { HWND_TOPMOST } else { HWND_NOTOPMOST };
+            unsafe {
+                SetWindowPos(hwnd, insert_after, 0, 0, 0, 0, SWP_NOSIZE | SWP_NOMOVE | SWP_NOACTIVATE);
+            }
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            use macroquad::miniquad::native::apple::frameworks::{msg_send, ObjcId};
+
+            // NSNormalWindowLevel and NSFloatingWindowLevel
+            let level: i64 = if always_on_top { 3 } else { 0 };
+            let view = macroquad::miniquad::window::apple_view();
+            if view.is_null() {
+                return;
+            }
+            unsafe {
+                let window: ObjcId = msg_send![view, window];
+                if !window.is_null() {
+                    let _: () = msg_send![window, setLevel: level];
+                }
+            }
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let _ = always_on_top;
+    }
+}
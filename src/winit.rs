@@ -0,0 +1,433 @@
+//! Driving Ply from a winit event loop instead of macroquad's main loop, for
+//! apps that own their window and pick their own renderer.
+//!
+//! ```ignore
+//! let software = Rc::new(RefCell::new(SoftwareRenderer::new()));
+//! let mut adapter = WinitAdapter::<()>::new(window.inner_size(), window.scale_factor());
+//! adapter.ply_mut().measure_text_with(software.clone());
+//!
+//! // In `ApplicationHandler::window_event`:
+//! if adapter.handle_event(&event) {
+//!     window.request_redraw();
+//! }
+//! if let WindowEvent::RedrawRequested = event {
+//!     software.borrow_mut().scale(adapter.scale_factor() as f32);
+//!     adapter.frame(&software, |ui| build_ui(ui));
+//!     // ...present `software.borrow_mut().take_frame()` with softbuffer or a texture...
+//!     window.set_cursor(adapter.cursor_icon());
+//!     window.set_ime_allowed(adapter.ime_allowed());
+//! }
+//! ```
+//!
+//! Pointer, wheel, touch, keyboard, IME and resize events become Ply's input,
+//! and frames are timed with the system clock. Layout is in logical pixels:
+//! the window's physical size over its scale factor. There is no system
+//! clipboard without macroquad, so copied text is handed out by
+//! [`WinitAdapter::take_copied_text`] and pasted with [`WinitAdapter::paste`].
+//!
+//! Apps with tool palettes or inspectors next to their main window keep a
+//! [`WinitWindows`], which holds one adapter per window and routes events by
+//! window id. Each window has its own layout, focus, pointer and widget state,
+//! while all of them measure text through the same renderer and so share its
+//! fonts:
+//!
+//! ```ignore
+//! let mut windows = WinitWindows::<()>::with_backend(software.clone());
+//! let palette = event_loop.create_window(Window::default_attributes())?;
+//! windows.add(palette.id(), palette.inner_size(), palette.scale_factor());
+//!
+//! // In `ApplicationHandler::window_event(event_loop, window_id, event)`:
+//! if windows.handle_event(window_id, &event) {
+//!     // ...request a redraw of that window...
+//! }
+//! if let WindowEvent::RedrawRequested = event {
+//!     windows.frame(window_id, &software, |ui| build_ui_for(window_id, ui));
+//! }
+//! if let WindowEvent::CloseRequested = event {
+//!     windows.remove(window_id);
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+use ::winit::dpi::PhysicalSize;
+use ::winit::event::{ElementState, Ime, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent};
+use ::winit::keyboard::{KeyCode as WinitKeyCode, PhysicalKey};
+use ::winit::window::{CursorIcon, WindowId};
+use macroquad::miniquad::CursorIcon as MiniquadCursorIcon;
+use rustc_hash::FxHashMap;
+
+use crate::event_input::{map_key_code, EventInput, LINE_HEIGHT};
+use crate::math::{Dimensions, Vector2};
+use crate::renderer::{self, Renderer};
+use crate::text::TextConfig;
+use crate::{Ply, Ui};
+
+/// A headless [`Ply`] fed by winit window events.
+pub struct WinitAdapter<CustomElementData: Clone + Default + std::fmt::Debug = ()> {
+    ply: Ply<CustomElementData>,
+    input: EventInput,
+    size: PhysicalSize<u32>,
+    scale_factor: f64,
+    start: Instant,
+    last_frame: Option<Instant>,
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> WinitAdapter<CustomElementData> {
+    /// Wraps a new headless [`Ply`] sized to the window. Set its measure
+    /// function, e.g. with [`Ply::measure_text_with`], before the first frame.
+    pub fn new(size: PhysicalSize<u32>, scale_factor: f64) -> Self {
+        let scale_factor = if scale_factor > 0.0 { scale_factor } else { 1.0 };
+        Self {
+            ply: Ply::new_headless(logical_size(size, scale_factor)),
+            input: EventInput::new(),
+            size,
+            scale_factor,
+            start: Instant::now(),
+            last_frame: None,
+        }
+    }
+
+    pub fn ply(&self) -> &Ply<CustomElementData> {
+        &self.ply
+    }
+
+    pub fn ply_mut(&mut self) -> &mut Ply<CustomElementData> {
+        &mut self.ply
+    }
+
+    /// Physical pixels per logical pixel, for the renderer's scale.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Layout size in logical pixels.
+    pub fn dimensions(&self) -> Dimensions {
+        logical_size(self.size, self.scale_factor)
+    }
+
+    /// Takes in a window event. Returns whether it was input for Ply, so a
+    /// redraw is worth requesting.
+    pub fn handle_event(&mut self, event: &WindowEvent) -> bool {
+        let input = &mut self.input;
+        match event {
+            WindowEvent::Resized(size) => self.size = *size,
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => self.scale_factor = *scale_factor,
+            WindowEvent::ModifiersChanged(modifiers) => {
+                let state = modifiers.state();
+                (input.shift, input.ctrl, input.alt) = (state.shift_key(), state.control_key(), state.alt_key());
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                input.pointer = logical(position.x, position.y, self.scale_factor);
+            }
+            WindowEvent::CursorLeft { .. } => input.pointer = Vector2::new(-1.0, -1.0),
+            WindowEvent::MouseInput { state, button: MouseButton::Left, .. } => {
+                input.set_pointer_down(*state == ElementState::Pressed);
+            }
+            WindowEvent::MouseWheel { delta, .. } => input.scroll_by(match delta {
+                MouseScrollDelta::LineDelta(x, y) => Vector2::new(x * LINE_HEIGHT, y * LINE_HEIGHT),
+                MouseScrollDelta::PixelDelta(position) => logical(position.x, position.y, self.scale_factor),
+            }),
+            WindowEvent::Touch(touch) => {
+                if input.touch.is_some_and(|id| id != touch.id) {
+                    return false;
+                }
+                input.pointer = logical(touch.location.x, touch.location.y, self.scale_factor);
+                match touch.phase {
+                    TouchPhase::Started => {
+                        input.touch = Some(touch.id);
+                        input.set_pointer_down(true);
+                    }
+                    TouchPhase::Moved => {}
+                    TouchPhase::Ended | TouchPhase::Cancelled => {
+                        input.touch = None;
+                        input.set_pointer_down(false);
+                    }
+                }
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                let key = match event.physical_key {
+                    PhysicalKey::Code(code) => map_key_code!(code, WinitKeyCode),
+                    PhysicalKey::Unidentified(_) => None,
+                };
+                if event.state == ElementState::Pressed {
+                    if let Some(key) = key {
+                        input.press_key(&mut self.ply, key, event.repeat);
+                    }
+                    if let Some(text) = &event.text {
+                        input.type_key_text(&mut self.ply, text);
+                    }
+                } else if let Some(key) = key {
+                    input.release_key(&self.ply, key);
+                }
+            }
+            WindowEvent::Ime(ime) => match ime {
+                Ime::Enabled => input.ime_enabled = true,
+                Ime::Disabled => {
+                    input.ime_enabled = false;
+                    input.preedit.clear();
+                }
+                Ime::Preedit(text, _) => input.preedit = text.clone(),
+                Ime::Commit(text) => {
+                    input.preedit.clear();
+                    input.type_text(&mut self.ply, text);
+                }
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /// Runs a frame: applies the input since the last one, declares the UI
+    /// in `build` and draws it with `backend`.
+    pub fn frame<R: Renderer<CustomElementData>>(
+        &mut self,
+        backend: &RefCell<R>,
+        build: impl FnOnce(&mut Ui<'_, CustomElementData>),
+    ) {
+        let now = Instant::now();
+        let dimensions = self.dimensions();
+        let ply = &mut self.ply;
+        ply.context.current_time = (now - self.start).as_secs_f64();
+        ply.context.frame_delta_time = self.last_frame.map_or(0.0, |last| (now - last).as_secs_f32());
+        self.last_frame = Some(now);
+        renderer::set_display_scale(self.scale_factor as f32);
+        ply.set_layout_dimensions(dimensions);
+        self.input.apply(ply);
+
+        let mut ui = ply.begin();
+        build(&mut ui);
+        // Layout measures text through the backend, so it's borrowed to draw after
+        let commands = ui.eval();
+        crate::jobs::block_on_native(self.ply.draw_with(&commands, &mut *backend.borrow_mut()));
+    }
+
+    /// The pointer cursor Ply asks for, to set on the window after a frame.
+    pub fn cursor_icon(&self) -> CursorIcon {
+        match self.ply.cursor {
+            MiniquadCursorIcon::Pointer => CursorIcon::Pointer,
+            MiniquadCursorIcon::Move => CursorIcon::Move,
+            MiniquadCursorIcon::EWResize => CursorIcon::EwResize,
+            MiniquadCursorIcon::NSResize => CursorIcon::NsResize,
+            _ => CursorIcon::Default,
+        }
+    }
+
+    /// Whether a text input has focus, to pass to `Window::set_ime_allowed`.
+    pub fn ime_allowed(&self) -> bool {
+        self.ply.context.is_text_input_focused()
+    }
+
+    /// Text the IME is composing, not yet committed to the focused input.
+    pub fn ime_preedit(&self) -> &str {
+        &self.input.preedit
+    }
+
+    /// Takes the text copied or cut with Ctrl+C / Ctrl+X, to put on the
+    /// system clipboard.
+    pub fn take_copied_text(&mut self) -> Option<String> {
+        self.input.copied.take()
+    }
+
+    /// Pastes text from the system clipboard into the focused text input.
+    pub fn paste(&mut self, text: &str) {
+        EventInput::paste(&mut self.ply, text);
+    }
+}
+
+/// Measures text for every window of a [`WinitWindows`].
+type SharedMeasure = Rc<dyn Fn(&str, &TextConfig) -> Dimensions>;
+
+/// One [`WinitAdapter`] per window of the app, keyed by winit's window id.
+pub struct WinitWindows<CustomElementData: Clone + Default + std::fmt::Debug = ()> {
+    windows: FxHashMap<WindowId, WinitAdapter<CustomElementData>>,
+    measure: SharedMeasure,
+}
+
+impl<CustomElementData: Clone + Default + std::fmt::Debug> WinitWindows<CustomElementData> {
+    /// Starts without windows. Every window added measures text with `measure`.
+    pub fn new(measure: impl Fn(&str, &TextConfig) -> Dimensions + 'static) -> Self {
+        Self { windows: FxHashMap::default(), measure: Rc::new(measure) }
+    }
+
+    /// Starts without windows. Every window added measures text with
+    /// `backend`, so layout matches what it draws in any of them.
+    pub fn with_backend<R>(backend: Rc<RefCell<R>>) -> Self
+    where
+        R: Renderer<CustomElementData> + 'static,
+    {
+        Self::new(move |text, config| backend.borrow().measure_text(text, config))
+    }
+
+    /// Adds a window created on the event loop, with its own headless
+    /// [`Ply`]. A window already added under `id` is replaced.
+    pub fn add(&mut self, id: WindowId, size: PhysicalSize<u32>, scale_factor: f64) -> &mut WinitAdapter<CustomElementData> {
+        let mut adapter = WinitAdapter::new(size, scale_factor);
+        let measure = self.measure.clone();
+        adapter.ply_mut().set_measure_text_function(move |text, config| measure(text, config));
+        self.windows.insert(id, adapter);
+        self.windows.get_mut(&id).unwrap()
+    }
+
+    /// Removes a closed window, returning its adapter.
+    pub fn remove(&mut self, id: WindowId) -> Option<WinitAdapter<CustomElementData>> {
+        self.windows.remove(&id)
+    }
+
+    /// The adapter of window `id`, to read its [`Ply`] after a frame.
+    pub fn get(&self, id: WindowId) -> Option<&WinitAdapter<CustomElementData>> {
+        self.windows.get(&id)
+    }
+
+    /// The adapter of window `id`, e.g. to paste into it or change its [`Ply`]'s settings.
+    pub fn get_mut(&mut self, id: WindowId) -> Option<&mut WinitAdapter<CustomElementData>> {
+        self.windows.get_mut(&id)
+    }
+
+    /// Ids of the windows added, in no particular order.
+    pub fn ids(&self) -> impl Iterator<Item = WindowId> + '_ {
+        self.windows.keys().copied()
+    }
+
+    /// Number of windows added.
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Whether every window was removed, so the event loop can exit.
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// Takes in an event for window `id`, see [`WinitAdapter::handle_event`].
+    /// Events of windows not added are ignored.
+    pub fn handle_event(&mut self, id: WindowId, event: &WindowEvent) -> bool {
+        self.windows.get_mut(&id).is_some_and(|adapter| adapter.handle_event(event))
+    }
+
+    /// Runs a frame of window `id`, see [`WinitAdapter::frame`]. Returns
+    /// `false` without drawing when the window wasn't added.
+    pub fn frame<R: Renderer<CustomElementData>>(
+        &mut self,
+        id: WindowId,
+        backend: &RefCell<R>,
+        build: impl FnOnce(&mut Ui<'_, CustomElementData>),
+    ) -> bool {
+        let Some(adapter) = self.windows.get_mut(&id) else {
+            return false;
+        };
+        adapter.frame(backend, build);
+        true
+    }
+}
+
+fn logical(x: f64, y: f64, scale_factor: f64) -> Vector2 {
+    Vector2::new((x / scale_factor) as f32, (y / scale_factor) as f32)
+}
+
+fn logical_size(size: PhysicalSize<u32>, scale_factor: f64) -> Dimensions {
+    Dimensions::new((size.width as f64 / scale_factor) as f32, (size.height as f64 / scale_factor) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_commands::RenderCommand;
+    use crate::{fixed, grow};
+    use ::winit::dpi::PhysicalPosition;
+    use ::winit::event::DeviceId;
+
+    /// Counts what it is asked to draw.
+    #[derive(Default)]
+    struct Recorder {
+        frames: u32,
+        commands: usize,
+    }
+
+    impl Renderer<()> for Recorder {
+        fn measure_text(&self, text: &str, _config: &crate::text::TextConfig) -> Dimensions {
+            Dimensions::new(text.len() as f32 * 8.0, 16.0)
+        }
+
+        fn begin_frame(&mut self, _dimensions: Dimensions) {
+            self.frames += 1;
+        }
+
+        async fn draw_command(&mut self, _command: &RenderCommand<()>) {
+            self.commands += 1;
+        }
+    }
+
+    #[test]
+    fn test_winit_events_drive_layout_and_pointer() {
+        let device_id = DeviceId::dummy();
+        let recorder = RefCell::new(Recorder::default());
+        let mut adapter = WinitAdapter::<()>::new(PhysicalSize::new(400, 200), 2.0);
+        let build = |ui: &mut Ui<'_, ()>| {
+            ui.element().id("root").width(grow!()).height(grow!()).background_color(0x202020).children(|ui| {
+                ui.element().id("button").width(fixed!(50.0)).height(fixed!(30.0)).background_color(0x336699).empty();
+            });
+        };
+
+        adapter.frame(&recorder, build);
+        // Physical size over the scale factor
+        assert_eq!(adapter.dimensions(), Dimensions::new(200.0, 100.0));
+        assert_eq!(adapter.ply().bounding_box("root").unwrap().width, 200.0);
+        assert_eq!(recorder.borrow().frames, 1);
+        assert!(recorder.borrow().commands >= 2);
+
+        assert!(adapter.handle_event(&WindowEvent::CursorMoved { device_id, position: PhysicalPosition::new(40.0, 40.0) }));
+        adapter.handle_event(&WindowEvent::MouseInput { device_id, state: ElementState::Pressed, button: MouseButton::Left });
+        adapter.frame(&recorder, build);
+        assert!(adapter.ply().pointer_over("button"));
+        assert!(adapter.ply().is_pressed("button"));
+
+        adapter.handle_event(&WindowEvent::MouseInput { device_id, state: ElementState::Released, button: MouseButton::Left });
+        adapter.handle_event(&WindowEvent::Resized(PhysicalSize::new(800, 200)));
+        adapter.frame(&recorder, build);
+        assert!(!adapter.ply().is_pressed("button"));
+        assert_eq!(adapter.ply().bounding_box("root").unwrap().width, 400.0);
+        assert!(!adapter.handle_event(&WindowEvent::Focused(true)));
+    }
+
+    #[test]
+    fn test_winit_windows_keep_separate_state() {
+        let device_id = DeviceId::dummy();
+        let recorder = Rc::new(RefCell::new(Recorder::default()));
+        let mut windows = WinitWindows::<()>::with_backend(recorder.clone());
+        let (main, palette) = (WindowId::from(1), WindowId::from(2));
+        windows.add(main, PhysicalSize::new(400, 200), 1.0);
+        windows.add(palette, PhysicalSize::new(100, 300), 2.0);
+        let build = |ui: &mut Ui<'_, ()>| {
+            ui.element().id("root").width(grow!()).height(grow!()).children(|ui| {
+                ui.element().id("button").width(fixed!(40.0)).height(fixed!(20.0)).background_color(0x336699).empty();
+                ui.element().id("label").children(|ui| ui.text("label", |t| t.font_size(16)));
+            });
+        };
+
+        assert!(windows.frame(main, &*recorder, build));
+        assert!(windows.frame(palette, &*recorder, build));
+        assert!(windows.handle_event(palette, &WindowEvent::CursorMoved { device_id, position: PhysicalPosition::new(20.0, 20.0) }));
+        windows.frame(main, &*recorder, build);
+        windows.frame(palette, &*recorder, build);
+        assert_eq!(recorder.borrow().frames, 4);
+
+        // Each window lays out at its own size, and the pointer stays in the window it moved in
+        let main_ply = windows.get(main).unwrap().ply();
+        let palette_ply = windows.get(palette).unwrap().ply();
+        assert_eq!(main_ply.bounding_box("root").unwrap().height, 200.0);
+        assert_eq!(palette_ply.bounding_box("root").unwrap().height, 150.0);
+        assert!(!main_ply.pointer_over("button"));
+        assert!(palette_ply.pointer_over("button"));
+        // Text is measured by the shared backend in both
+        assert_eq!(main_ply.bounding_box("label").unwrap().width, 40.0);
+        assert_eq!(palette_ply.bounding_box("label").unwrap().width, 40.0);
+
+        assert!(windows.remove(palette).is_some());
+        assert!(!windows.handle_event(palette, &WindowEvent::CursorLeft { device_id }));
+        assert!(!windows.frame(palette, &*recorder, build));
+        assert_eq!(windows.ids().collect::<Vec<_>>(), vec![main]);
+    }
+}